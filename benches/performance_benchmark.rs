@@ -360,6 +360,9 @@ fn bench_task_scheduling_overhead(c: &mut Criterion) {
                 task_timeout: 300,
                 gather_facts: false,
                 extra_vars: HashMap::new(),
+                safe_mode: None,
+                become_defaults: Default::default(),
+                batch_pause_skip: None,
             };
             let executor = Executor::new(black_box(config));
             black_box(executor)
@@ -603,6 +603,7 @@ fn host_to_response(host: &Host) -> HostResponse {
         crate::inventory::ConnectionType::Docker => "docker",
         crate::inventory::ConnectionType::Podman => "podman",
         crate::inventory::ConnectionType::Winrm => "winrm",
+        crate::inventory::ConnectionType::Kubectl => "kubectl",
     };
 
     HostResponse {
@@ -72,8 +72,9 @@ use async_trait::async_trait;
 use parking_lot::RwLock;
 use tracing::{debug, error, trace, warn};
 
+use super::BoxedCallback;
 use crate::facts::Facts;
-use crate::traits::{ExecutionCallback, ExecutionResult};
+use crate::traits::{ExecutionCallback, ExecutionResult, SerialProgress};
 
 // ============================================================================
 // Plugin Priority System
@@ -367,6 +368,21 @@ impl CallbackManager {
         self.register(name, plugin, PluginPriority::NORMAL).await
     }
 
+    /// Registers a boxed callback with default (NORMAL) priority.
+    ///
+    /// Lets callers hand over a [`BoxedCallback`] (for example one built by the
+    /// plugin factory) without wrapping it in an `Arc` first.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// manager.register_boxed("minimal", Box::new(MinimalCallback::new())).await;
+    /// ```
+    pub async fn register_boxed(&self, name: &str, plugin: BoxedCallback) -> bool {
+        self.register(name, Arc::from(plugin), PluginPriority::NORMAL)
+            .await
+    }
+
     /// Deregisters a callback plugin by name.
     ///
     /// # Returns
@@ -902,6 +918,96 @@ impl CallbackManager {
 
         result
     }
+
+    /// Dispatches `on_batch_pause` event to all enabled plugins.
+    pub async fn on_batch_pause(
+        &self,
+        batch: usize,
+        total: usize,
+        duration: std::time::Duration,
+    ) -> DispatchResult {
+        if *self.paused.read() {
+            return DispatchResult::default();
+        }
+
+        let mut result = DispatchResult::default();
+        let plugins = self.get_ordered_plugins();
+
+        for (plugin_name, plugin, enabled) in plugins {
+            if !enabled {
+                result.skipped_count += 1;
+                continue;
+            }
+
+            trace!(plugin = %plugin_name, "Dispatching on_batch_pause");
+
+            let dispatch_result = {
+                let plugin = Arc::clone(&plugin);
+                tokio::spawn(async move {
+                    plugin.on_batch_pause(batch, total, duration).await;
+                })
+                .await
+            };
+
+            match dispatch_result {
+                Ok(()) => result.success_count += 1,
+                Err(e) => {
+                    let err = PluginError {
+                        plugin_name: plugin_name.clone(),
+                        event: "on_batch_pause".to_string(),
+                        message: e.to_string(),
+                    };
+                    error!(%err, "Plugin error");
+                    result.errors.push(err);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Dispatches `on_batch_complete` event to all enabled plugins.
+    pub async fn on_batch_complete(&self, progress: &SerialProgress) -> DispatchResult {
+        if *self.paused.read() {
+            return DispatchResult::default();
+        }
+
+        let mut result = DispatchResult::default();
+        let plugins = self.get_ordered_plugins();
+
+        for (plugin_name, plugin, enabled) in plugins {
+            if !enabled {
+                result.skipped_count += 1;
+                continue;
+            }
+
+            trace!(plugin = %plugin_name, "Dispatching on_batch_complete");
+
+            let dispatch_result = {
+                let plugin = Arc::clone(&plugin);
+                let progress = progress.clone();
+                tokio::spawn(async move {
+                    plugin.on_batch_complete(&progress).await;
+                })
+                .await
+            };
+
+            match dispatch_result {
+                Ok(()) => result.success_count += 1,
+                Err(e) => {
+                    let err = PluginError {
+                        plugin_name: plugin_name.clone(),
+                        event: "on_batch_complete".to_string(),
+                        message: e.to_string(),
+                    };
+                    error!(%err, "Plugin error");
+                    result.errors.push(err);
+                }
+            }
+        }
+
+        result
+    }
 }
 
 // ============================================================================
@@ -945,6 +1051,14 @@ impl ExecutionCallback for CallbackManager {
     async fn on_facts_gathered(&self, host: &str, facts: &Facts) {
         let _ = CallbackManager::on_facts_gathered(self, host, facts).await;
     }
+
+    async fn on_batch_pause(&self, batch: usize, total: usize, duration: std::time::Duration) {
+        let _ = CallbackManager::on_batch_pause(self, batch, total, duration).await;
+    }
+
+    async fn on_batch_complete(&self, progress: &SerialProgress) {
+        let _ = CallbackManager::on_batch_complete(self, progress).await;
+    }
 }
 
 // ============================================================================
@@ -226,6 +226,7 @@ pub mod prelude {
     pub use crate::traits::ExecutionCallback;
     pub use crate::traits::ExecutionResult;
     pub use crate::traits::ModuleResult;
+    pub use crate::traits::SerialProgress;
 
     // ========================================================================
     // Core Output Plugins
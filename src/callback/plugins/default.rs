@@ -659,6 +659,15 @@ impl ExecutionCallback for DefaultCallback {
             }
         }
 
+        // Warnings are shown even when the result itself is hidden
+        for warning in &result.result.warnings {
+            if self.use_color() {
+                eprintln!("{}", format!("[WARNING]: {}", warning).bright_magenta());
+            } else {
+                eprintln!("[WARNING]: {}", warning);
+            }
+        }
+
         // Check if we should display this result
         if result.result.skipped && !self.config.show_skipped {
             return;
@@ -760,6 +769,18 @@ impl ExecutionCallback for DefaultCallback {
             }
         }
     }
+
+    async fn on_batch_pause(&self, batch: usize, total: usize, duration: std::time::Duration) {
+        self.print_header(
+            "PAUSE",
+            &format!(
+                "batch {}/{} done, waiting {}",
+                batch,
+                total,
+                humantime_serde::re::humantime::format_duration(duration)
+            ),
+        );
+    }
 }
 
 // ============================================================================
@@ -26,7 +26,18 @@
 //!  }
 //! changed: [webserver1]
 //! ```
+//!
+//! At the end of the run the summary lists, per host, how many files or
+//! resources changed and which ones:
+//!
+//! ```text
+//! Diff Summary: 2 file(s), 3 insertion(s)(+), 1 deletion(s)(-)
+//! webserver1: 2 changes
+//!   /etc/nginx/nginx.conf
+//!   /etc/nginx/sites-enabled/default
+//! ```
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -37,7 +48,7 @@ use tokio::sync::RwLock;
 
 use crate::executor::task::TaskDiff;
 use crate::facts::Facts;
-use crate::traits::{ExecutionCallback, ExecutionResult, ModuleDiff};
+use crate::traits::{ExecutionCallback, ExecutionResult, ModuleDiff, SerialProgress};
 
 /// Configuration for the diff callback plugin.
 #[derive(Debug, Clone)]
@@ -114,6 +125,8 @@ struct DiffStats {
     lines_added: u32,
     /// Total lines removed
     lines_removed: u32,
+    /// Changed files or resources per host, in the order they changed
+    host_changes: BTreeMap<String, Vec<String>>,
 }
 
 /// Diff callback plugin that displays before/after changes.
@@ -294,8 +307,8 @@ impl DiffCallback {
         (lines_added, lines_removed)
     }
 
-    /// Formats a task diff for display.
-    async fn display_task_diff(&self, diff: &TaskDiff) {
+    /// Formats a task diff for display and records `resource` as changed on `host`.
+    async fn display_task_diff(&self, host: &str, resource: &str, diff: &TaskDiff) {
         if !self.config.enabled {
             return;
         }
@@ -318,6 +331,10 @@ impl DiffCallback {
         stats.files_diffed += 1;
         stats.lines_added += added;
         stats.lines_removed += removed;
+        let changes = stats.host_changes.entry(host.to_string()).or_default();
+        if !changes.iter().any(|c| c == resource) {
+            changes.push(resource.to_string());
+        }
     }
 
     /// Formats a module diff for display.
@@ -359,6 +376,38 @@ impl DiffCallback {
         (stats.files_diffed, stats.lines_added, stats.lines_removed)
     }
 
+    /// Returns the changed files or resources recorded for each host.
+    pub async fn host_changes(&self) -> BTreeMap<String, Vec<String>> {
+        self.stats.read().await.host_changes.clone()
+    }
+
+    /// Renders the per-host change summary, one host per block.
+    ///
+    /// ```text
+    /// web1: 2 changes
+    ///   /etc/app.conf
+    ///   /etc/motd
+    /// ```
+    pub async fn render_host_summary(&self) -> String {
+        let stats = self.stats.read().await;
+        let mut out = String::new();
+        for (host, changes) in &stats.host_changes {
+            let count = match changes.len() {
+                1 => "1 change".to_string(),
+                n => format!("{} changes", n),
+            };
+            if self.config.use_color {
+                out.push_str(&format!("{}: {}\n", host.bright_white().bold(), count));
+            } else {
+                out.push_str(&format!("{}: {}\n", host, count));
+            }
+            for change in changes {
+                out.push_str(&format!("  {}\n", change));
+            }
+        }
+        out
+    }
+
     /// Prints a summary of diff statistics.
     pub async fn print_summary(&self) {
         if !self.config.enabled {
@@ -385,8 +434,31 @@ impl DiffCallback {
                     stats.files_diffed, stats.lines_added, stats.lines_removed,
                 );
             }
+            drop(stats);
+            print!("{}", self.render_host_summary().await);
         }
     }
+
+    /// Name of what a task changed, for the per-host summary
+    ///
+    /// Prefers the module's `path`/`dest`, then the diff header, then the task name.
+    fn changed_resource(result: &ExecutionResult, diff: &TaskDiff) -> String {
+        let data = result.result.data.as_ref();
+        data.and_then(|d| d.get("path").or_else(|| d.get("dest")))
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .or_else(|| {
+                diff.after_header
+                    .as_deref()
+                    .or(diff.before_header.as_deref())
+                    .map(|h| {
+                        h.trim_start_matches("after: ")
+                            .trim_start_matches("before: ")
+                            .to_string()
+                    })
+            })
+            .unwrap_or_else(|| result.task_name.clone())
+    }
 }
 
 impl Default for DiffCallback {
@@ -447,7 +519,9 @@ impl ExecutionCallback for DiffCallback {
             // Try to extract diff from module result data
             if let Some(diff_obj) = data.get("diff") {
                 if let Ok(task_diff) = serde_json::from_value::<TaskDiff>(diff_obj.clone()) {
-                    self.display_task_diff(&task_diff).await;
+                    let resource = Self::changed_resource(result, &task_diff);
+                    self.display_task_diff(&result.host, &resource, &task_diff)
+                        .await;
                 }
             }
 
@@ -468,7 +542,9 @@ impl ExecutionCallback for DiffCallback {
                         .and_then(|v| v.as_str())
                         .map(|p| format!("after: {}", p)),
                 };
-                self.display_task_diff(&task_diff).await;
+                let resource = Self::changed_resource(result, &task_diff);
+                self.display_task_diff(&result.host, &resource, &task_diff)
+                    .await;
             }
         }
     }
@@ -584,6 +660,18 @@ impl ExecutionCallback for CompositeCallback {
             callback.on_facts_gathered(host, facts).await;
         }
     }
+
+    async fn on_batch_pause(&self, batch: usize, total: usize, duration: std::time::Duration) {
+        for callback in &self.callbacks {
+            callback.on_batch_pause(batch, total, duration).await;
+        }
+    }
+
+    async fn on_batch_complete(&self, progress: &SerialProgress) {
+        for callback in &self.callbacks {
+            callback.on_batch_complete(progress).await;
+        }
+    }
 }
 
 /// Helper function to generate a unified diff string.
@@ -794,6 +882,37 @@ mod tests {
         assert_eq!(removed, 0);
     }
 
+    #[tokio::test]
+    async fn test_host_summary_lists_changed_paths() {
+        let callback = DiffCallback::with_config(DiffConfig::enabled().with_color(false));
+
+        for path in ["/etc/app.conf", "/etc/motd"] {
+            let mut result = _create_execution_result("web1", "Write file", true, true, false, "");
+            result.result.data = Some(serde_json::json!({
+                "path": path,
+                "before": "old\n",
+                "after": "new\n",
+            }));
+            callback.on_task_complete(&result).await;
+        }
+
+        let mut result = _create_execution_result("web2", "Write file", true, true, false, "");
+        result.result.data = Some(serde_json::json!({
+            "diff": {
+                "before": "a\n",
+                "after": "b\n",
+                "after_header": "after: /etc/hosts",
+            },
+        }));
+        callback.on_task_complete(&result).await;
+
+        let summary = callback.render_host_summary().await;
+        assert_eq!(
+            summary,
+            "web1: 2 changes\n  /etc/app.conf\n  /etc/motd\nweb2: 1 change\n  /etc/hosts\n"
+        );
+    }
+
     #[test]
     fn test_clone_shares_state() {
         let callback1 = DiffCallback::new();
@@ -219,7 +219,7 @@ pub use logstash::{
 
 // Profile tasks callback
 pub use profile_tasks::{
-    AggregatedTaskTiming, HostTaskTiming, HostTiming, PerformanceRecommendation,
+    AggregatedTaskTiming, HostTaskTiming, HostTiming, PerformanceRecommendation, ProfileReport,
     ProfileTasksCallback, ProfileTasksCallbackBuilder, ProfileTasksConfig, RecommendationSeverity,
     SortOrder, TaskTiming,
 };
@@ -20,6 +20,15 @@
 //! appserver1 | SKIPPED => Skipped: condition was false
 //! ```
 //!
+//! With the task name enabled (see [`OnelineConfig::compact`]), each line
+//! mirrors `ansible -o`:
+//!
+//! ```text
+//! webserver1 | CHANGED | Deploy config | File copied successfully
+//! ```
+//!
+//! Messages are always folded onto a single line.
+//!
 //! # Usage
 //!
 //! ```rust,ignore
@@ -53,10 +62,11 @@
 
 use std::io::{self, Write};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 
 use crate::facts::Facts;
 use crate::traits::{ExecutionCallback, ExecutionResult, ModuleResult};
@@ -110,9 +120,23 @@ impl OnelineConfig {
         Self::default()
     }
 
+    /// Preset for `host | STATUS | task | msg` lines, like `ansible -o`.
+    pub fn compact() -> Self {
+        Self {
+            show_task_name: true,
+            max_message_length: 120,
+            result_separator: " | ".to_string(),
+            ..Self::default()
+        }
+    }
+
     /// Enable task name in output.
+    ///
+    /// The message then follows the task name after the field separator,
+    /// giving `host | STATUS | task | msg` lines.
     pub fn with_task_name(mut self) -> Self {
         self.show_task_name = true;
+        self.result_separator = self.separator.clone();
         self
     }
 
@@ -223,6 +247,8 @@ pub struct OnelineCallback {
     host_stats: RwLock<std::collections::HashMap<String, HostStats>>,
     /// Total task count
     task_count: AtomicU64,
+    /// Destination for output instead of stdout/stderr
+    writer: Option<Arc<Mutex<Box<dyn Write + Send>>>>,
 }
 
 impl OnelineCallback {
@@ -234,6 +260,7 @@ impl OnelineCallback {
             start_time: RwLock::new(None),
             host_stats: RwLock::new(std::collections::HashMap::new()),
             task_count: AtomicU64::new(0),
+            writer: None,
         }
     }
 
@@ -245,14 +272,25 @@ impl OnelineCallback {
             start_time: RwLock::new(None),
             host_stats: RwLock::new(std::collections::HashMap::new()),
             task_count: AtomicU64::new(0),
+            writer: None,
         }
     }
 
+    /// Write output to `writer` instead of stdout/stderr.
+    pub fn with_writer(mut self, writer: impl Write + Send + 'static) -> Self {
+        self.writer = Some(Arc::new(Mutex::new(Box::new(writer))));
+        self
+    }
+
     /// Format and output a line.
     fn output_line(&self, line: &str) {
         self.has_output.store(true, Ordering::SeqCst);
 
-        if self.config.use_stderr {
+        if let Some(writer) = &self.writer {
+            let mut writer = writer.lock();
+            let _ = writeln!(writer, "{}", line);
+            let _ = writer.flush();
+        } else if self.config.use_stderr {
             let _ = writeln!(io::stderr(), "{}", line);
         } else {
             println!("{}", line);
@@ -291,15 +329,23 @@ impl OnelineCallback {
         }
     }
 
-    /// Truncate a message if configured.
-    fn truncate_message(&self, msg: &str) -> String {
-        if self.config.max_message_length > 0 && msg.len() > self.config.max_message_length {
-            format!("{}...", &msg[..self.config.max_message_length - 3])
+    /// Collapse whitespace (including newlines) to single spaces and truncate
+    /// to `max_len` characters with a trailing `...` (0 = no limit).
+    pub fn single_line(msg: &str, max_len: usize) -> String {
+        let folded = msg.split_whitespace().collect::<Vec<_>>().join(" ");
+        if max_len > 0 && folded.chars().count() > max_len {
+            let kept: String = folded.chars().take(max_len.saturating_sub(3)).collect();
+            format!("{}...", kept)
         } else {
-            msg.to_string()
+            folded
         }
     }
 
+    /// Fold a message onto one line and truncate it if configured.
+    fn truncate_message(&self, msg: &str) -> String {
+        Self::single_line(msg, self.config.max_message_length)
+    }
+
     /// Get the result message.
     fn get_result_message(&self, result: &ModuleResult) -> String {
         let msg = &result.message;
@@ -324,16 +370,17 @@ impl OnelineCallback {
         // Hostname
         parts.push(exec_result.host.clone());
 
-        // Optional task name
-        if self.config.show_task_name {
-            parts.push(format!("[{}]", exec_result.task_name));
-        }
-
         // Build the line
         let prefix = parts.join(" ");
-        let status = self.format_status(&exec_result.result);
+        let mut status = self.format_status(&exec_result.result);
         let message = self.get_result_message(&exec_result.result);
 
+        // Optional task name, between the status and the message
+        if self.config.show_task_name {
+            status.push_str(&self.config.separator);
+            status.push_str(&Self::single_line(&exec_result.task_name, 0));
+        }
+
         format!(
             "{}{}{}{}{}",
             prefix, self.config.separator, status, self.config.result_separator, message
@@ -534,7 +581,23 @@ mod tests {
         let callback = OnelineCallback::with_config(config);
         let result = create_test_result("webserver1", true, false, false, "ok");
         let line = callback.format_task_line(&result);
-        assert_eq!(line, "webserver1 [test task] | OK => ok");
+        assert_eq!(line, "webserver1 | OK | test task | ok");
+    }
+
+    #[test]
+    fn test_compact_preset() {
+        let callback = OnelineCallback::with_config(OnelineConfig::compact());
+        let result = create_test_result("web1", true, true, false, "line one\n  line two\n");
+        let line = callback.format_task_line(&result);
+        assert_eq!(line, "web1 | CHANGED | test task | line one line two");
+    }
+
+    #[test]
+    fn test_single_line() {
+        assert_eq!(OnelineCallback::single_line("a\nb\t c ", 0), "a b c");
+        assert_eq!(OnelineCallback::single_line("abcdefghij", 8), "abcde...");
+        // Truncation counts characters, not bytes
+        assert_eq!(OnelineCallback::single_line("ééééé", 4), "é...");
     }
 
     #[test]
@@ -263,6 +263,49 @@ pub enum RecommendationSeverity {
     Critical,
 }
 
+/// Structured timing report for a profiled run.
+#[derive(Debug, Clone)]
+pub struct ProfileReport {
+    /// Tasks ordered by their slowest host, slowest first
+    pub tasks: Vec<AggregatedTaskTiming>,
+    /// Hosts ordered by total task time, slowest first
+    pub hosts: Vec<(String, HostTiming)>,
+    /// Wall-clock time from playbook start to end
+    pub wall_time: Duration,
+}
+
+impl std::fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Slowest tasks:")?;
+        for task in &self.tasks {
+            let task_name = truncate_str(&task.task_name, 50);
+            writeln!(
+                f,
+                "  {} {} {} ({} host{})",
+                task_name,
+                "-".repeat(52usize.saturating_sub(task_name.len()).max(1)),
+                format_duration_short(task.max_duration),
+                task.host_count,
+                if task.host_count == 1 { "" } else { "s" }
+            )?;
+        }
+
+        writeln!(f, "Slowest hosts:")?;
+        for (host, timing) in &self.hosts {
+            writeln!(
+                f,
+                "  {:<30} total: {:>10}  avg: {:>10}  tasks: {}",
+                host,
+                format_duration_short(timing.total_duration),
+                format_duration_short(timing.avg_duration()),
+                timing.task_count
+            )?;
+        }
+
+        write!(f, "Total wall time: {}", format_duration(self.wall_time))
+    }
+}
+
 // ============================================================================
 // Internal State
 // ============================================================================
@@ -280,6 +323,8 @@ struct ProfileState {
     task_starts: HashMap<String, Instant>,
     /// Playbook start time
     playbook_start: Option<Instant>,
+    /// Wall-clock duration of the finished playbook
+    playbook_duration: Option<Duration>,
     /// Playbook name
     playbook_name: Option<String>,
     /// Play start times
@@ -296,6 +341,7 @@ impl ProfileState {
             host_timings: HashMap::new(),
             task_starts: HashMap::new(),
             playbook_start: None,
+            playbook_duration: None,
             playbook_name: None,
             play_starts: HashMap::new(),
             current_play: None,
@@ -523,6 +569,38 @@ impl ProfileTasksCallback {
         self.state.read().generate_recommendations()
     }
 
+    /// Build a structured report of the recorded timings.
+    ///
+    /// Tasks and hosts are always ranked slowest first, independent of the
+    /// configured display sort order.
+    pub fn report(&self) -> ProfileReport {
+        let state = self.state.read();
+
+        let mut tasks = state.aggregate_by_task();
+        tasks.sort_by_key(|t| std::cmp::Reverse(t.max_duration));
+
+        let mut hosts: Vec<_> = state
+            .host_timings
+            .iter()
+            .map(|(host, timing)| (host.clone(), timing.clone()))
+            .collect();
+        hosts.sort_by(|a, b| {
+            b.1.total_duration
+                .cmp(&a.1.total_duration)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+
+        let wall_time = state
+            .playbook_duration
+            .unwrap_or_else(|| state.elapsed_since_playbook_start());
+
+        ProfileReport {
+            tasks,
+            hosts,
+            wall_time,
+        }
+    }
+
     /// Print timing for a single task.
     fn print_task_timing(&self, timing: &TaskTiming) {
         let state = self.state.read();
@@ -749,6 +827,7 @@ impl ProfileTasksCallback {
         state.task_starts.clear();
         state.play_starts.clear();
         state.playbook_start = None;
+        state.playbook_duration = None;
         state.playbook_name = None;
         state.current_play = None;
         self.total_tasks.store(0, Ordering::Relaxed);
@@ -779,6 +858,7 @@ impl ExecutionCallback for ProfileTasksCallback {
     async fn on_playbook_start(&self, name: &str) {
         let mut state = self.state.write();
         state.playbook_start = Some(Instant::now());
+        state.playbook_duration = None;
         state.playbook_name = Some(name.to_string());
         state.task_timings.clear();
         state.host_timings.clear();
@@ -788,6 +868,10 @@ impl ExecutionCallback for ProfileTasksCallback {
     }
 
     async fn on_playbook_end(&self, _name: &str, _success: bool) {
+        {
+            let mut state = self.state.write();
+            state.playbook_duration = Some(state.elapsed_since_playbook_start());
+        }
         self.print_summary();
     }
 
@@ -1090,6 +1174,39 @@ mod tests {
         assert_eq!(truncate_str("ab", 2), "ab");
     }
 
+    #[tokio::test]
+    async fn test_report_ranks_slowest_first() {
+        let profiler = ProfileTasksCallback::summary_only();
+        profiler.on_playbook_start("site").await;
+
+        for (task, host, millis) in [
+            ("fast", "web1", 10),
+            ("slow", "web1", 300),
+            ("slow", "web2", 50),
+            ("medium", "web2", 100),
+        ] {
+            profiler
+                .on_task_complete(&ExecutionResult {
+                    host: host.to_string(),
+                    task_name: task.to_string(),
+                    result: ModuleResult::ok("done"),
+                    duration: Duration::from_millis(millis),
+                    notify: vec![],
+                })
+                .await;
+        }
+
+        let report = profiler.report();
+        let tasks: Vec<_> = report.tasks.iter().map(|t| t.task_name.as_str()).collect();
+        assert_eq!(tasks, vec!["slow", "medium", "fast"]);
+        let hosts: Vec<_> = report.hosts.iter().map(|(h, _)| h.as_str()).collect();
+        assert_eq!(hosts, vec!["web1", "web2"]);
+
+        let text = report.to_string();
+        assert!(text.starts_with("Slowest tasks:\n  slow "));
+        assert!(text.contains("Total wall time: "));
+    }
+
     #[test]
     fn test_host_timing_recording() {
         let mut timing = HostTiming::default();
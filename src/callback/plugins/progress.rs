@@ -41,7 +41,7 @@ use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use parking_lot::RwLock;
 
 use crate::facts::Facts;
-use crate::traits::{ExecutionCallback, ExecutionResult};
+use crate::traits::{ExecutionCallback, ExecutionResult, SerialProgress};
 
 // ============================================================================
 // Configuration
@@ -349,6 +349,22 @@ impl ProgressCallback {
         }
     }
 
+    /// Format a serial batch progress line
+    fn serial_message(progress: &SerialProgress) -> String {
+        let mut message = format!(
+            "Serial: batch {}/{} - {}/{} hosts done, {} failed",
+            progress.batch,
+            progress.total_batches,
+            progress.hosts_completed,
+            progress.total_hosts,
+            progress.hosts_failed
+        );
+        if let Some(eta) = progress.eta {
+            message.push_str(&format!(", ETA {}", Self::format_duration(eta)));
+        }
+        message
+    }
+
     /// Finish all progress bars and show completion message
     fn finish_all(&self, success: bool) {
         // Finish any active task spinners
@@ -737,6 +753,24 @@ impl ExecutionCallback for ProgressCallback {
             self.print_progress(&format!("Facts gathered for {}", host));
         }
     }
+
+    async fn on_batch_complete(&self, progress: &SerialProgress) {
+        let message = Self::serial_message(progress);
+
+        if !self.is_tty() {
+            self.print_progress(&message);
+            return;
+        }
+
+        if let Some(play_state) = self.current_play.read().as_ref() {
+            if let Some(bar) = &play_state.progress_bar {
+                bar.set_position(progress.hosts_completed as u64);
+            }
+        }
+        if let Some(mp) = self.get_multi_progress() {
+            let _ = mp.println(message);
+        }
+    }
 }
 
 // ============================================================================
@@ -890,6 +924,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_serial_message() {
+        let mut progress = SerialProgress {
+            batch: 1,
+            total_batches: 3,
+            hosts_completed: 2,
+            total_hosts: 6,
+            hosts_failed: 1,
+            eta: Some(Duration::from_secs(65)),
+        };
+        assert_eq!(
+            ProgressCallback::serial_message(&progress),
+            "Serial: batch 1/3 - 2/6 hosts done, 1 failed, ETA 1m 5s"
+        );
+
+        progress.batch = 3;
+        progress.hosts_completed = 6;
+        progress.eta = None;
+        assert_eq!(progress.remaining_hosts(), 0);
+        assert_eq!(
+            ProgressCallback::serial_message(&progress),
+            "Serial: batch 3/3 - 6/6 hosts done, 1 failed"
+        );
+    }
+
     #[tokio::test]
     async fn test_callback_lifecycle() {
         let callback = ProgressCallback::new();
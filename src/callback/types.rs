@@ -1080,6 +1080,7 @@ mod tests {
             msg: Some("Package installed".to_string()),
             result: None,
             diff: None,
+            warnings: Vec::new(),
         };
 
         let result_info = ResultInfo::from_task_result(&task_result, Duration::from_millis(1500));
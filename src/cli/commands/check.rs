@@ -54,6 +54,10 @@ pub struct CheckArgs {
     /// Private key file
     #[arg(long)]
     pub private_key: Option<PathBuf>,
+
+    /// Exit 0 without drift, 2 when changes would be made and 1 on failures
+    #[arg(long)]
+    pub detailed_exitcode: bool,
 }
 
 impl CheckArgs {
@@ -80,6 +84,9 @@ impl CheckArgs {
             private_key: self.private_key.clone(),
             ssh_common_args: None,
             plan: false, // check mode doesn't need plan mode
+            profile: false,
+            force: false,
+            detailed_exitcode: self.detailed_exitcode,
         };
 
         ctx.output.banner("CHECK MODE - DRY RUN");
@@ -0,0 +1,209 @@
+//! Facts command - Pre-warm the fact cache
+//!
+//! This module implements the `facts` subcommand, which gathers facts from
+//! inventory hosts ahead of time and persists them into the fact cache so
+//! later playbook runs can skip the expensive gathering step.
+
+use super::inventory::{HostInfo, Inventory};
+use super::{CommandContext, Runnable};
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use rustible::cache::tiered_facts::{TieredCacheConfig, TieredFactCache};
+use rustible::connection::local::LocalConnection;
+use rustible::connection::Connection;
+use rustible::executor::{ConnectionFactGathererFactory, FactPipeline, FactPipelineConfig};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Arguments for the facts command
+#[derive(Parser, Debug, Clone)]
+pub struct FactsArgs {
+    #[command(subcommand)]
+    pub action: FactsAction,
+}
+
+/// Facts subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum FactsAction {
+    /// Gather facts from matching hosts and persist them to the fact cache
+    Gather(GatherArgs),
+}
+
+/// Arguments for the gather action
+#[derive(Parser, Debug, Clone)]
+pub struct GatherArgs {
+    /// Host pattern to match
+    #[arg(default_value = "all")]
+    pub pattern: String,
+
+    /// Directory for the persistent fact cache
+    #[arg(long, env = "RUSTIBLE_FACT_CACHE_DIR")]
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl GatherArgs {
+    /// Resolve the fact cache directory
+    fn cache_dir(&self) -> PathBuf {
+        self.cache_dir
+            .clone()
+            .or_else(crate::cli::env::fact_cache_dir)
+            .unwrap_or_else(|| TieredCacheConfig::default().l2_cache_path)
+    }
+}
+
+impl FactsArgs {
+    /// Execute the facts command
+    pub async fn execute(&self, ctx: &mut CommandContext) -> Result<i32> {
+        match &self.action {
+            FactsAction::Gather(args) => self.gather(args, ctx).await,
+        }
+    }
+
+    /// Gather facts for all matching hosts and persist them
+    async fn gather(&self, args: &GatherArgs, ctx: &mut CommandContext) -> Result<i32> {
+        let sources = ctx.inventory_sources();
+        if sources.is_empty() {
+            ctx.output
+                .error("No inventory specified. Use -i to specify an inventory file.");
+            return Ok(1);
+        }
+
+        let inventory = Inventory::load_sources(&sources)?;
+        let mut hosts: Vec<&HostInfo> = inventory.get_hosts(&args.pattern);
+        if let Some(ref limit) = ctx.limit {
+            hosts.retain(|h| h.name == *limit || h.groups.contains(limit));
+        }
+        hosts.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if hosts.is_empty() {
+            ctx.output
+                .warning(&format!("No hosts matched pattern: {}", args.pattern));
+            return Ok(0);
+        }
+
+        ctx.output.banner("FACTS GATHER");
+
+        // Connect to every host up front; connection failures are reported
+        // alongside gathering failures rather than aborting the run.
+        let mut connections: HashMap<String, Arc<dyn Connection>> = HashMap::new();
+        let mut connect_failures = Vec::new();
+        for host in &hosts {
+            match Self::connect(ctx, host).await {
+                Ok(conn) => {
+                    connections.insert(host.name.clone(), conn);
+                }
+                Err(e) => connect_failures.push((host.name.clone(), e.to_string())),
+            }
+        }
+
+        let cache_dir = args.cache_dir();
+        let cache = TieredFactCache::new(TieredCacheConfig {
+            l2_cache_path: cache_dir.clone(),
+            ..TieredCacheConfig::default()
+        });
+        let pipeline = FactPipeline::new(FactPipelineConfig {
+            max_concurrent: ctx.forks.max(1),
+            gather_timeout: Duration::from_secs(ctx.timeout),
+            enable_cache: false,
+            enable_prefetch: false,
+            ..FactPipelineConfig::default()
+        });
+
+        let reachable: Vec<String> = connections.keys().cloned().collect();
+        let mut report = pipeline
+            .warm_cache(
+                &reachable,
+                ConnectionFactGathererFactory::new(connections),
+                &cache,
+            )
+            .await;
+        report.failed.extend(connect_failures);
+        report.failed.sort();
+
+        ctx.close_connections().await;
+
+        for host in &report.succeeded {
+            ctx.output.info(&format!("ok: [{}] facts cached", host));
+        }
+        for (host, error) in &report.failed {
+            ctx.output.error(&format!("failed: [{}] {}", host, error));
+        }
+
+        ctx.output.section("FACTS SUMMARY");
+        ctx.output.info(&format!(
+            "{} succeeded, {} failed in {:.2}s (cache: {})",
+            report.succeeded.len(),
+            report.failed.len(),
+            report.duration.as_secs_f64(),
+            cache_dir.display()
+        ));
+
+        if report.all_succeeded() {
+            Ok(0)
+        } else {
+            Ok(2)
+        }
+    }
+
+    /// Open a connection to a host based on its inventory variables
+    async fn connect(ctx: &CommandContext, host: &HostInfo) -> Result<Arc<dyn Connection>> {
+        let var_str = |key: &str| host.vars.get(key).and_then(|v| v.as_str());
+
+        if ctx.is_local(&host.name, var_str("ansible_connection")) {
+            return Ok(Arc::new(LocalConnection::new()));
+        }
+
+        let ansible_host = var_str("ansible_host").unwrap_or(&host.name).to_string();
+        let ansible_user = var_str("ansible_user")
+            .map(String::from)
+            .unwrap_or_else(|| std::env::var("USER").unwrap_or_else(|_| "root".to_string()));
+        let ansible_port = host
+            .vars
+            .get("ansible_port")
+            .and_then(|p| p.as_u64())
+            .unwrap_or(22) as u16;
+        let ansible_key = var_str("ansible_ssh_private_key_file");
+        let transfer_method = ctx.transfer_method(&host.vars)?;
+
+        let conn = ctx
+            .get_connection(
+                &host.name,
+                &ansible_host,
+                &ansible_user,
+                ansible_port,
+                ansible_key,
+                transfer_method,
+            )
+            .await?;
+        Ok(conn)
+    }
+}
+
+#[async_trait::async_trait]
+impl Runnable for FactsArgs {
+    async fn run(&self, ctx: &mut CommandContext) -> Result<i32> {
+        self.execute(ctx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gather_args_parsing() {
+        let args = FactsArgs::try_parse_from(["facts", "gather", "webservers"]).unwrap();
+        match args.action {
+            FactsAction::Gather(gather) => assert_eq!(gather.pattern, "webservers"),
+        }
+    }
+
+    #[test]
+    fn test_gather_explicit_cache_dir() {
+        let args = GatherArgs::try_parse_from(["gather", "--cache-dir", "/tmp/facts"]).unwrap();
+        assert_eq!(args.pattern, "all");
+        assert_eq!(args.cache_dir(), PathBuf::from("/tmp/facts"));
+    }
+}
@@ -5,6 +5,7 @@
 use super::{CommandContext, Runnable};
 use anyhow::{Context, Result};
 use clap::Parser;
+use rustible::inventory::InventorySource;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -35,11 +36,11 @@ pub struct ListTasksArgs {
     #[arg(required = true)]
     pub playbook: PathBuf,
 
-    /// Show only tasks with these tags
+    /// Mark tasks with these tags as would run
     #[arg(long, short = 't', action = clap::ArgAction::Append)]
     pub tags: Vec<String>,
 
-    /// Skip tasks with these tags
+    /// Mark tasks with these tags as skipped
     #[arg(long, action = clap::ArgAction::Append)]
     pub skip_tags: Vec<String>,
 
@@ -92,6 +93,67 @@ impl Inventory {
         }
     }
 
+    /// Load and merge inventory sources in order, later sources winning
+    pub fn load_sources(sources: &[String]) -> Result<Self> {
+        let mut inventory = Inventory::default();
+        for source in InventorySource::parse_all(sources) {
+            let loaded = match source {
+                InventorySource::Path(path) => Self::load(&path)?,
+                InventorySource::Hosts(hosts) => Self::from_host_list(&hosts),
+            };
+            inventory.merge(loaded);
+        }
+        Ok(inventory)
+    }
+
+    /// Build an inventory of ungrouped hosts from an ad-hoc host list
+    pub fn from_host_list(hosts: &[String]) -> Self {
+        let mut content = String::new();
+        for host in hosts {
+            content.push_str(host);
+            content.push('\n');
+        }
+        // A bare host list is valid INI with every host ungrouped
+        Self::parse_ini(&content).unwrap_or_default()
+    }
+
+    /// Merge another inventory into this one, unioning hosts and groups
+    pub fn merge(&mut self, other: Inventory) {
+        for (name, host) in other.hosts {
+            let existing = self.hosts.entry(name).or_insert_with(|| HostInfo {
+                name: host.name.clone(),
+                groups: Vec::new(),
+                vars: HashMap::new(),
+            });
+            for group in host.groups {
+                if !existing.groups.contains(&group) {
+                    existing.groups.push(group);
+                }
+            }
+            existing.vars.extend(host.vars);
+        }
+
+        for (name, group) in other.groups {
+            let existing = self.groups.entry(name).or_insert_with(|| GroupInfo {
+                name: group.name.clone(),
+                hosts: Vec::new(),
+                children: Vec::new(),
+                vars: HashMap::new(),
+            });
+            for host in group.hosts {
+                if !existing.hosts.contains(&host) {
+                    existing.hosts.push(host);
+                }
+            }
+            for child in group.children {
+                if !existing.children.contains(&child) {
+                    existing.children.push(child);
+                }
+            }
+            existing.vars.extend(group.vars);
+        }
+    }
+
     /// Parse YAML inventory
     fn parse_yaml(content: &str) -> Result<Self> {
         let yaml: serde_yaml::Value = serde_yaml::from_str(content)?;
@@ -352,18 +414,16 @@ fn glob_match(name: &str, pattern: &str) -> bool {
 impl ListHostsArgs {
     /// Execute the list-hosts command
     pub async fn execute(&self, ctx: &mut CommandContext) -> Result<i32> {
-        // Get inventory path
-        let inventory_path = match ctx.inventory() {
-            Some(path) => path.clone(),
-            None => {
-                ctx.output
-                    .error("No inventory specified. Use -i to specify an inventory file.");
-                return Ok(1);
-            }
-        };
+        // Get inventory sources
+        let sources = ctx.inventory_sources();
+        if sources.is_empty() {
+            ctx.output
+                .error("No inventory specified. Use -i to specify an inventory file.");
+            return Ok(1);
+        }
 
         // Load inventory
-        let inventory = Inventory::load(&inventory_path)?;
+        let inventory = Inventory::load_sources(&sources)?;
 
         // Get matching hosts
         let hosts = inventory.get_hosts(&self.pattern);
@@ -458,7 +518,7 @@ impl ListTasksArgs {
         ctx.output
             .section(&format!("Tasks in playbook: {}", self.playbook.display()));
 
-        let mut task_count = 0;
+        let mut counts = TaskCounts::default();
 
         // Process plays
         if let Some(plays) = playbook.as_sequence() {
@@ -467,99 +527,26 @@ impl ListTasksArgs {
                     .get("name")
                     .and_then(|n| n.as_str())
                     .unwrap_or("Unnamed play");
+                let play_tags = parse_tags(play.get("tags"));
 
                 println!("\nPlay #{}: {}", play_idx + 1, play_name);
                 println!("{}", "-".repeat(40));
 
                 // Get tasks
                 if let Some(tasks) = play.get("tasks").and_then(|t| t.as_sequence()) {
-                    for (task_idx, task) in tasks.iter().enumerate() {
-                        let task_name = task
-                            .get("name")
-                            .and_then(|n| n.as_str())
-                            .unwrap_or("Unnamed task");
-
-                        // Get task tags for filtering
-                        let task_tags: Vec<String> = task
-                            .get("tags")
-                            .and_then(|t| {
-                                if let Some(s) = t.as_str() {
-                                    Some(vec![s.to_string()])
-                                } else if let Some(seq) = t.as_sequence() {
-                                    Some(
-                                        seq.iter()
-                                            .filter_map(|v| v.as_str().map(String::from))
-                                            .collect(),
-                                    )
-                                } else {
-                                    None
-                                }
-                            })
-                            .unwrap_or_default();
-
-                        // Check skip_tags filter first - skip if any skip_tag matches
-                        if !self.skip_tags.is_empty() {
-                            let should_skip = self.skip_tags.iter().any(|t| task_tags.contains(t));
-                            if should_skip {
-                                continue;
-                            }
-                        }
-
-                        // Check tags filter - only include if tags match
-                        if !self.tags.is_empty() {
-                            let matches = self.tags.iter().any(|t| task_tags.contains(t));
-                            if !matches {
-                                continue;
-                            }
-                        }
-
-                        task_count += 1;
-
-                        // Detect module
-                        let module = detect_module(task);
-
-                        if self.detailed {
-                            println!("  {:>3}. {} [{}]", task_idx + 1, task_name, module);
-
-                            // Show tags
-                            if let Some(tags) = task.get("tags") {
-                                println!("       Tags: {}", format_value(tags));
-                            }
-
-                            // Show when condition
-                            if let Some(when) = task.get("when") {
-                                println!("       When: {}", format_value(when));
-                            }
-                        } else {
-                            println!("  {:>3}. {}", task_idx + 1, task_name);
-                        }
-                    }
+                    self.list_task_section(tasks, &play_tags, "  ", &mut counts);
                 }
 
                 // Handle pre_tasks
                 if let Some(tasks) = play.get("pre_tasks").and_then(|t| t.as_sequence()) {
                     println!("\n  Pre-tasks:");
-                    for (task_idx, task) in tasks.iter().enumerate() {
-                        let task_name = task
-                            .get("name")
-                            .and_then(|n| n.as_str())
-                            .unwrap_or("Unnamed task");
-                        println!("    {:>3}. {}", task_idx + 1, task_name);
-                        task_count += 1;
-                    }
+                    self.list_task_section(tasks, &play_tags, "    ", &mut counts);
                 }
 
                 // Handle post_tasks
                 if let Some(tasks) = play.get("post_tasks").and_then(|t| t.as_sequence()) {
                     println!("\n  Post-tasks:");
-                    for (task_idx, task) in tasks.iter().enumerate() {
-                        let task_name = task
-                            .get("name")
-                            .and_then(|n| n.as_str())
-                            .unwrap_or("Unnamed task");
-                        println!("    {:>3}. {}", task_idx + 1, task_name);
-                        task_count += 1;
-                    }
+                    self.list_task_section(tasks, &play_tags, "    ", &mut counts);
                 }
 
                 // Handle handlers
@@ -577,10 +564,125 @@ impl ListTasksArgs {
         }
 
         println!("\n{}", "=".repeat(40));
-        println!("Total tasks: {}", task_count);
+        println!("Total tasks: {}", counts.total);
+        if self.has_tag_filter() {
+            println!("Tasks that would run: {}", counts.selected);
+        }
 
         Ok(0)
     }
+
+    /// Print one list of tasks, annotating each with its resolved tags and,
+    /// when `--tags`/`--skip-tags` is given, whether the filter selects it.
+    fn list_task_section(
+        &self,
+        tasks: &[serde_yaml::Value],
+        play_tags: &[String],
+        indent: &str,
+        counts: &mut TaskCounts,
+    ) {
+        for (task_idx, task) in tasks.iter().enumerate() {
+            let task_name = task
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or("Unnamed task");
+
+            // Tasks inherit the tags of their play
+            let mut tags = play_tags.to_vec();
+            for tag in parse_tags(task.get("tags")) {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+
+            counts.total += 1;
+
+            let status = if self.has_tag_filter() {
+                if self.would_run(&tags) {
+                    counts.selected += 1;
+                    " (would run)"
+                } else {
+                    " (skipped)"
+                }
+            } else {
+                ""
+            };
+
+            let tag_list = if tags.is_empty() {
+                String::new()
+            } else {
+                format!("  TAGS: [{}]", tags.join(", "))
+            };
+
+            if self.detailed {
+                let module = detect_module(task);
+                println!(
+                    "{}{:>3}. {} [{}]{}{}",
+                    indent,
+                    task_idx + 1,
+                    task_name,
+                    module,
+                    tag_list,
+                    status
+                );
+
+                // Show when condition
+                if let Some(when) = task.get("when") {
+                    println!("{}     When: {}", indent, format_value(when));
+                }
+            } else {
+                println!(
+                    "{}{:>3}. {}{}{}",
+                    indent,
+                    task_idx + 1,
+                    task_name,
+                    tag_list,
+                    status
+                );
+            }
+        }
+    }
+
+    /// Whether `--tags` or `--skip-tags` was given
+    fn has_tag_filter(&self) -> bool {
+        !self.tags.is_empty() || !self.skip_tags.is_empty()
+    }
+
+    /// Check whether a task with the given resolved tags would run under the
+    /// requested tag selection. Mirrors the filter applied by `run`.
+    fn would_run(&self, task_tags: &[String]) -> bool {
+        if self.skip_tags.iter().any(|t| task_tags.contains(t)) {
+            return false;
+        }
+
+        if !self.tags.is_empty() {
+            return self
+                .tags
+                .iter()
+                .any(|t| t == "all" || task_tags.contains(t));
+        }
+
+        true
+    }
+}
+
+/// Running totals for the task listing
+#[derive(Debug, Default)]
+struct TaskCounts {
+    total: usize,
+    selected: usize,
+}
+
+/// Parse a `tags` value given either as a single string or a list
+fn parse_tags(value: Option<&serde_yaml::Value>) -> Vec<String> {
+    match value {
+        Some(serde_yaml::Value::String(s)) => vec![s.clone()],
+        Some(serde_yaml::Value::Sequence(seq)) => seq
+            .iter()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    }
 }
 
 /// Detect the module used in a task
@@ -685,6 +787,32 @@ mod tests {
         assert_eq!(args.playbook, PathBuf::from("playbook.yml"));
     }
 
+    #[test]
+    fn test_list_tasks_would_run() {
+        let args =
+            ListTasksArgs::try_parse_from(["list-tasks", "playbook.yml", "--tags", "db"]).unwrap();
+        assert!(args.would_run(&["db".to_string(), "setup".to_string()]));
+        assert!(!args.would_run(&["web".to_string()]));
+        assert!(!args.would_run(&[]));
+
+        let args =
+            ListTasksArgs::try_parse_from(["list-tasks", "playbook.yml", "--skip-tags", "db"])
+                .unwrap();
+        assert!(!args.would_run(&["db".to_string()]));
+        assert!(args.would_run(&[]));
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        let single: serde_yaml::Value = serde_yaml::from_str("db").unwrap();
+        assert_eq!(parse_tags(Some(&single)), vec!["db"]);
+
+        let list: serde_yaml::Value = serde_yaml::from_str("[db, web]").unwrap();
+        assert_eq!(parse_tags(Some(&list)), vec!["db", "web"]);
+
+        assert!(parse_tags(None).is_empty());
+    }
+
     #[test]
     fn test_glob_match() {
         assert!(glob_match("web01.example.com", "web*.example.com"));
@@ -0,0 +1,110 @@
+//! Lint command - Report semantic issues in playbooks
+//!
+//! This module implements the `lint` subcommand. Beyond syntax checking it
+//! reports likely mistakes such as unnamed tasks, `shell` used where
+//! `command` would do, deprecated modules and literal secrets. Findings can
+//! be suppressed per task with a `# noqa: <rule>` comment.
+
+use super::{CommandContext, Runnable};
+use anyhow::Result;
+use clap::Parser;
+use rustible::lint::{LintConfig, LintIssue, Linter, Severity};
+use std::path::PathBuf;
+
+/// Arguments for the lint command
+#[derive(Parser, Debug, Clone)]
+pub struct LintArgs {
+    /// Playbook file to lint
+    pub playbook: PathBuf,
+
+    /// Rule ids to skip (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    pub skip: Vec<String>,
+
+    /// Treat warnings as errors
+    #[arg(long)]
+    pub strict: bool,
+}
+
+impl LintArgs {
+    /// Build the lint configuration from the arguments
+    fn config(&self) -> LintConfig {
+        LintConfig {
+            skip_rules: self.skip.clone(),
+            warnings_as_errors: self.strict,
+            ..LintConfig::default()
+        }
+    }
+
+    /// Execute the lint command
+    pub async fn execute(&self, ctx: &mut CommandContext) -> Result<i32> {
+        ctx.output.banner("PLAYBOOK LINT");
+
+        if !self.playbook.exists() {
+            ctx.output
+                .error(&format!("Playbook not found: {}", self.playbook.display()));
+            return Ok(1);
+        }
+
+        let result = Linter::new(self.config()).check_file(&self.playbook)?;
+
+        for issue in &result.issues {
+            let line = format_issue(issue);
+            match issue.severity {
+                Severity::Critical | Severity::Error => ctx.output.error(&line),
+                Severity::Warning => ctx.output.warning(&line),
+                Severity::Hint => ctx.output.hint(&line),
+            }
+        }
+
+        ctx.output.section("LINT SUMMARY");
+        if result.issues.is_empty() {
+            ctx.output.info("No issues found.");
+        } else {
+            ctx.output.warning(&result.summary());
+        }
+
+        Ok(result.exit_code())
+    }
+}
+
+/// Format a finding as `file:line [RULE] rule-name: message`
+fn format_issue(issue: &LintIssue) -> String {
+    let mut line = format!(
+        "{} [{}] {}: {}",
+        issue.location, issue.rule_id, issue.rule_name, issue.message
+    );
+    if let Some(ref suggestion) = issue.suggestion {
+        line.push_str(&format!(" ({})", suggestion));
+    }
+    line
+}
+
+#[async_trait::async_trait]
+impl Runnable for LintArgs {
+    async fn run(&self, ctx: &mut CommandContext) -> Result<i32> {
+        self.execute(ctx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_args_parsing() {
+        let args =
+            LintArgs::try_parse_from(["lint", "site.yml", "--skip", "B004,B007", "--strict"])
+                .unwrap();
+        assert_eq!(args.playbook, PathBuf::from("site.yml"));
+        assert_eq!(args.skip, vec!["B004", "B007"]);
+
+        let config = args.config();
+        assert!(config.warnings_as_errors);
+        assert!(!config.should_run_rule(
+            "B007",
+            rustible::lint::RuleCategory::BestPractices,
+            Severity::Warning
+        ));
+    }
+}
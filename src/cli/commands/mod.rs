@@ -3,16 +3,24 @@
 //! This module contains all the subcommand implementations.
 
 pub mod check;
+pub mod facts;
 pub mod inventory;
+pub mod lint;
 pub mod run;
 pub mod vault;
 
 use crate::cli::output::OutputFormatter;
+use crate::cli::ConnectionOverride;
 use crate::config::Config;
 use anyhow::Result;
-use rustible::connection::Connection;
+use indexmap::IndexMap;
+use rustible::connection::docker::DockerConnection;
+use rustible::connection::{Connection, ConnectionIdentity, ConnectionType, TransferMethod};
+use rustible::executor::runtime::BecomeDefaults;
+use rustible::inventory::{InventoryPlugin, SourcesInventoryPlugin};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -22,8 +30,8 @@ pub struct CommandContext {
     pub config: Config,
     /// Output formatter
     pub output: OutputFormatter,
-    /// Inventory path
-    pub inventory_path: Option<PathBuf>,
+    /// Inventory sources given with `-i`
+    pub inventory_paths: Vec<PathBuf>,
     /// Extra variables
     pub extra_vars: Vec<String>,
     /// Verbosity level
@@ -32,7 +40,6 @@ pub struct CommandContext {
     /// Check mode (dry-run)
     pub check_mode: bool,
     /// Diff mode
-    #[allow(dead_code)]
     pub diff_mode: bool,
     /// Limit pattern
     pub limit: Option<String>,
@@ -44,17 +51,32 @@ pub struct CommandContext {
     pub timeout: u64,
     /// Connection pool for reusing SSH connections
     pub connections: Arc<RwLock<HashMap<String, Arc<dyn Connection + Send + Sync>>>>,
+    /// Privilege escalation defaults from `--become` and friends
+    pub become_defaults: BecomeDefaults,
+    /// Number of hosts that failed SSH authentication so far
+    pub auth_failures: Arc<AtomicUsize>,
+    /// Connection type forced for every host with `--connection`
+    pub connection: Option<ConnectionOverride>,
+    /// `ansible_*` facts for each host of the play being run
+    pub host_facts: HashMap<String, IndexMap<String, serde_yaml::Value>>,
 }
 
 impl CommandContext {
     /// Create a new command context from CLI arguments
     pub fn new(cli: &crate::cli::Cli, config: Config) -> Self {
-        let output = OutputFormatter::new(!cli.no_color, cli.is_json(), cli.verbosity());
+        let output = OutputFormatter::new(!cli.no_color, cli.is_json(), cli.verbosity())
+            .with_buffer_mode(cli.output_buffer)
+            .with_oneline(cli.oneline)
+            .with_host_prefix(cli.host_prefix)
+            .with_diff_report(
+                cli.diff_mode
+                    && (cli.check_mode || matches!(cli.command, crate::cli::Commands::Check(_))),
+            );
 
         Self {
             config,
             output,
-            inventory_path: cli.inventory.clone(),
+            inventory_paths: cli.inventory.clone(),
             extra_vars: cli.extra_vars.clone(),
             verbosity: cli.verbosity(),
             check_mode: cli.check_mode,
@@ -63,9 +85,52 @@ impl CommandContext {
             forks: cli.forks,
             timeout: cli.timeout,
             connections: Arc::new(RwLock::new(HashMap::new())),
+            become_defaults: BecomeDefaults::default(),
+            auth_failures: Arc::new(AtomicUsize::new(0)),
+            connection: cli.connection,
+            host_facts: HashMap::new(),
+        }
+    }
+
+    /// Whether a host runs on the control node
+    ///
+    /// `--connection` takes priority over the host's `ansible_connection`;
+    /// without either, only `localhost` and `127.0.0.1` run locally.
+    pub fn is_local(&self, host: &str, ansible_connection: Option<&str>) -> bool {
+        match self.connection {
+            Some(connection) => connection == ConnectionOverride::Local,
+            None => {
+                ansible_connection == Some("local") || host == "localhost" || host == "127.0.0.1"
+            }
         }
     }
 
+    /// Whether enough hosts have failed authentication to abort the run,
+    /// as configured by `max_auth_failures`
+    pub fn auth_failure_limit_reached(&self) -> bool {
+        self.config
+            .connection
+            .max_auth_failures
+            .is_some_and(|max| self.auth_failures.load(Ordering::Relaxed) >= max)
+    }
+
+    /// Convert a failed connection attempt into an error, counting
+    /// authentication failures towards `max_auth_failures`
+    #[cfg(any(feature = "russh", feature = "ssh2-backend"))]
+    fn connect_error(
+        &self,
+        host: &str,
+        error: rustible::connection::ConnectionError,
+    ) -> anyhow::Error {
+        if matches!(
+            error,
+            rustible::connection::ConnectionError::AuthenticationFailed(_)
+        ) {
+            self.auth_failures.fetch_add(1, Ordering::Relaxed);
+        }
+        anyhow::anyhow!("Failed to connect to {}: {}", host, error)
+    }
+
     /// Get or create a connection for a host
     /// This pools connections to avoid creating new SSH sessions for every command
     pub async fn get_connection(
@@ -75,11 +140,38 @@ impl CommandContext {
         ansible_user: &str,
         ansible_port: u16,
         ansible_key: Option<&str>,
+        transfer_method: TransferMethod,
     ) -> Result<Arc<dyn Connection + Send + Sync>> {
-        // Check if we already have a connection for this host
+        // Expand ~ to home directory
+        let key_path = ansible_key.map(|key_path| {
+            if let Some(rest) = key_path.strip_prefix("~/") {
+                if let Some(home) = dirs::home_dir() {
+                    return home.join(rest).to_string_lossy().to_string();
+                }
+            }
+            key_path.to_string()
+        });
+
+        if self.connection == Some(ConnectionOverride::Docker) {
+            return Ok(self.docker_connection(ansible_host).await);
+        }
+
+        // Pool by the full auth identity so different users or keys for the
+        // same host never share a session
+        let mut identity = ConnectionIdentity::new()
+            .with_user(ansible_user)
+            .with_port(ansible_port);
+        identity.identity_file = key_path.clone();
+        let pool_key = identity.pool_key(&ConnectionType::Ssh {
+            host: ansible_host.to_string(),
+            port: ansible_port,
+            user: ansible_user.to_string(),
+        });
+
+        // Check if we already have a connection for this identity
         {
             let connections = self.connections.read().await;
-            if let Some(conn) = connections.get(host) {
+            if let Some(conn) = connections.get(&pool_key) {
                 if conn.is_alive().await {
                     self.output
                         .debug(&format!("Reusing connection for {}", host));
@@ -98,19 +190,8 @@ impl CommandContext {
         host_config.hostname = Some(ansible_host.to_string());
         host_config.port = Some(ansible_port);
         host_config.user = Some(ansible_user.to_string());
-        if let Some(key_path) = ansible_key {
-            // Expand ~ to home directory
-            let expanded_path = if key_path.starts_with("~/") {
-                if let Some(home) = dirs::home_dir() {
-                    home.join(&key_path[2..]).to_string_lossy().to_string()
-                } else {
-                    key_path.to_string()
-                }
-            } else {
-                key_path.to_string()
-            };
-            host_config.identity_file = Some(expanded_path);
-        }
+        host_config.identity_file = key_path;
+        host_config.transfer_method = Some(transfer_method);
 
         // Create SSH connection - prefer russh (pure Rust) when available
         let conn_config = rustible::connection::ConnectionConfig::default();
@@ -124,7 +205,7 @@ impl CommandContext {
                 &conn_config,
             )
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to connect to {}: {}", host, e))?;
+            .map_err(|e| self.connect_error(host, e))?;
             Arc::new(conn)
         };
         #[cfg(all(feature = "ssh2-backend", not(feature = "russh")))]
@@ -137,7 +218,7 @@ impl CommandContext {
                 &conn_config,
             )
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to connect to {}: {}", host, e))?;
+            .map_err(|e| self.connect_error(host, e))?;
             Arc::new(conn)
         };
         #[cfg(not(any(feature = "russh", feature = "ssh2-backend")))]
@@ -150,12 +231,57 @@ impl CommandContext {
         // Cache the connection
         {
             let mut connections = self.connections.write().await;
-            connections.insert(host.to_string(), Arc::clone(&conn));
+            connections.insert(pool_key, Arc::clone(&conn));
         }
 
         Ok(conn)
     }
 
+    /// Get or create a connection to the container named `container`
+    async fn docker_connection(&self, container: &str) -> Arc<dyn Connection + Send + Sync> {
+        let pool_key = ConnectionType::Docker {
+            container: container.to_string(),
+        }
+        .pool_key();
+        let mut connections = self.connections.write().await;
+        let conn = connections.entry(pool_key).or_insert_with(|| {
+            self.output
+                .debug(&format!("Creating new Docker connection: {}", container));
+            Arc::new(DockerConnection::new(container.to_string()))
+        });
+        Arc::clone(conn)
+    }
+
+    /// Resolve the file transfer method for a host.
+    ///
+    /// The `ansible_ssh_transfer_method` or `ansible_scp_if_ssh` host
+    /// variable wins over the configured `transfer_method`, which in turn
+    /// wins over `scp_if_ssh`.
+    pub fn transfer_method(
+        &self,
+        host_vars: &HashMap<String, serde_yaml::Value>,
+    ) -> Result<TransferMethod> {
+        if let Some(method) = host_vars
+            .get("ansible_ssh_transfer_method")
+            .and_then(|v| v.as_str())
+        {
+            return Ok(method.parse()?);
+        }
+        if let Some(scp_if_ssh) = host_vars
+            .get("ansible_scp_if_ssh")
+            .and_then(|v| v.as_bool())
+        {
+            return Ok(TransferMethod::from_scp_if_ssh(scp_if_ssh));
+        }
+
+        match &self.config.connection.transfer_method {
+            Some(method) => Ok(method.parse()?),
+            None => Ok(TransferMethod::from_scp_if_ssh(
+                self.config.connection.scp_if_ssh,
+            )),
+        }
+    }
+
     /// Close all cached connections
     pub async fn close_connections(&self) {
         let connections: Vec<_> = {
@@ -168,11 +294,30 @@ impl CommandContext {
         }
     }
 
-    /// Get the effective inventory path
-    pub fn inventory(&self) -> Option<&PathBuf> {
-        self.inventory_path
-            .as_ref()
-            .or(self.config.defaults.inventory.as_ref())
+    /// Get the effective inventory sources, falling back to the configured default
+    pub fn inventory_sources(&self) -> Vec<String> {
+        if self.inventory_paths.is_empty() {
+            self.config
+                .defaults
+                .inventory
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect()
+        } else {
+            self.inventory_paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect()
+        }
+    }
+
+    /// Inventory source reloaded by `meta: refresh_inventory`, if any sources are set
+    pub fn inventory_source(&self) -> Option<Arc<dyn InventoryPlugin>> {
+        let sources = self.inventory_sources();
+        if sources.is_empty() {
+            return None;
+        }
+        Some(Arc::new(SourcesInventoryPlugin::new(sources)))
     }
 
     /// Parse extra variables into a HashMap
@@ -199,6 +344,17 @@ impl CommandContext {
     }
 }
 
+impl Drop for CommandContext {
+    fn drop(&mut self) {
+        // Connections left open by an early return or panic are closed
+        // best-effort, unless another owner still shares the pool
+        if let Some(pool) = Arc::get_mut(&mut self.connections) {
+            let connections = pool.get_mut().drain().map(|(_, v)| v).collect();
+            rustible::connection::close_detached(connections);
+        }
+    }
+}
+
 /// Trait for runnable commands
 #[async_trait::async_trait]
 #[allow(dead_code)]
@@ -2,12 +2,22 @@
 //!
 //! This module implements the `run` subcommand for executing Ansible-like playbooks.
 
+use super::inventory::Inventory;
 use super::{CommandContext, Runnable};
 use crate::cli::output::{RecapStats, TaskStatus};
 use anyhow::{Context, Result};
 use clap::Parser;
 use indexmap::IndexMap;
 use regex::Regex;
+use rustible::cache::tiered_facts::{TieredCacheConfig, TieredFactCache};
+use rustible::callback::plugins::ProfileTasksCallback;
+use rustible::connection::{ExecuteOptions, TransferMethod};
+use rustible::executor::playbook::{find_role, require_rustible_version, role_search_dirs};
+use rustible::executor::runtime::BecomeDefaults;
+use rustible::executor::SafeMode;
+use rustible::traits::{ExecutionCallback, ExecutionResult, ModuleResult};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
@@ -44,15 +54,15 @@ pub struct RunArgs {
     #[arg(long)]
     pub vault_password_file: Option<PathBuf>,
 
-    /// Become (sudo/su)
+    /// Become (sudo/su) for tasks that don't set `become` themselves
     #[arg(short = 'b', long)]
     pub r#become: bool,
 
-    /// Become method (sudo, su, etc.)
+    /// Become method (sudo, su, etc.) for tasks that don't set one
     #[arg(long, default_value = "sudo")]
     pub become_method: String,
 
-    /// Become user
+    /// Become user for tasks that don't set one
     #[arg(long, default_value = "root")]
     pub become_user: String,
 
@@ -75,6 +85,19 @@ pub struct RunArgs {
     /// Plan mode - show what would be executed without running
     #[arg(long)]
     pub plan: bool,
+
+    /// Print a per-task and per-host timing report after the run
+    #[arg(long)]
+    pub profile: bool,
+
+    /// Run destructive modules even when safe mode is enabled
+    #[arg(long)]
+    pub force: bool,
+
+    /// Exit 0 without changes, 2 when changes were made (or would be, in
+    /// check mode) and 1 on failures
+    #[arg(long)]
+    pub detailed_exitcode: bool,
 }
 
 impl RunArgs {
@@ -109,8 +132,7 @@ impl RunArgs {
             .with_context(|| "Failed to parse playbook YAML")?;
 
         // Get inventory
-        let inventory_path = ctx.inventory().cloned();
-        if inventory_path.is_none() {
+        if ctx.inventory_sources().is_empty() {
             ctx.output
                 .warning("No inventory specified, using localhost");
             ctx.output
@@ -125,6 +147,8 @@ impl RunArgs {
             }
         }
 
+        ctx.become_defaults = self.become_defaults(ctx)?;
+
         // Parse extra vars
         let extra_vars = ctx.parse_extra_vars()?;
         ctx.output.debug(&format!("Extra vars: {:?}", extra_vars));
@@ -144,6 +168,13 @@ impl RunArgs {
         // Initialize stats (wrapped in Arc<Mutex<>> for thread-safe parallel execution)
         let stats = Arc::new(Mutex::new(RecapStats::new()));
 
+        let profiler = self.profile.then(ProfileTasksCallback::summary_only);
+        if let Some(ref profiler) = profiler {
+            profiler
+                .on_playbook_start(&self.playbook.to_string_lossy())
+                .await;
+        }
+
         // Process playbook plays
         if let Some(plays) = playbook.as_sequence() {
             if self.plan {
@@ -152,7 +183,14 @@ impl RunArgs {
             } else {
                 // Normal execution
                 for play in plays {
-                    self.execute_play(ctx, play, &stats).await?;
+                    let result = self
+                        .execute_play(ctx, play, &stats, profiler.as_ref())
+                        .await;
+                    if result.is_err() {
+                        // Don't leave pooled connections open on failure
+                        ctx.close_connections().await;
+                    }
+                    result?;
                 }
             }
         } else {
@@ -167,6 +205,13 @@ impl RunArgs {
         let stats_guard = stats.lock().await;
         ctx.output.recap(&stats_guard);
 
+        if let Some(ref profiler) = profiler {
+            ctx.output.section("PROFILE");
+            for line in profiler.report().to_string().lines() {
+                ctx.output.info(line);
+            }
+        }
+
         // Print timing
         let duration = start_time.elapsed();
         ctx.output.info(&format!(
@@ -174,11 +219,21 @@ impl RunArgs {
             duration.as_secs_f64()
         ));
 
-        // Return exit code
-        if stats_guard.has_failures() {
-            Ok(2)
-        } else {
-            Ok(0)
+        let detailed = self.detailed_exitcode || ctx.config.defaults.detailed_exitcode;
+        Ok(Self::exit_code(&stats_guard, detailed))
+    }
+
+    /// Exit code for a finished run
+    ///
+    /// Failures exit 2 by default. In detailed mode failures exit 1 instead,
+    /// so that 2 can mean a successful run that reported changes, e.g. drift
+    /// found by a check run.
+    fn exit_code(stats: &RecapStats, detailed: bool) -> i32 {
+        match (stats.has_failures(), detailed) {
+            (true, false) => 2,
+            (true, true) => 1,
+            (false, true) if stats.has_changes() => 2,
+            (false, _) => 0,
         }
     }
 
@@ -268,7 +323,6 @@ impl RunArgs {
                 .unwrap_or_default();
 
             // Count role tasks
-            let playbook_dir = self.playbook.parent().unwrap_or(std::path::Path::new("."));
             let mut role_task_count = 0;
             for role in &roles {
                 let role_name = if let Some(name) = role.as_str() {
@@ -280,11 +334,10 @@ impl RunArgs {
                 } else {
                     continue;
                 };
-                let role_tasks_path = playbook_dir
-                    .join("roles")
-                    .join(&role_name)
-                    .join("tasks")
-                    .join("main.yml");
+                let Ok(role_dir) = self.role_dir(ctx, &role_name) else {
+                    continue;
+                };
+                let role_tasks_path = role_dir.join("tasks").join("main.yml");
                 if role_tasks_path.exists() {
                     if let Ok(content) = std::fs::read_to_string(&role_tasks_path) {
                         if let Ok(role_tasks) =
@@ -384,11 +437,10 @@ impl RunArgs {
                     continue;
                 };
 
-                let role_tasks_path = playbook_dir
-                    .join("roles")
-                    .join(&role_name)
-                    .join("tasks")
-                    .join("main.yml");
+                let Ok(role_dir) = self.role_dir(ctx, &role_name) else {
+                    continue;
+                };
+                let role_tasks_path = role_dir.join("tasks").join("main.yml");
                 if role_tasks_path.exists() {
                     if let Ok(content) = std::fs::read_to_string(&role_tasks_path) {
                         if let Ok(role_tasks) =
@@ -443,8 +495,6 @@ impl RunArgs {
             }
 
             // Count all tasks: pre_tasks + role tasks + tasks + post_tasks
-            let playbook_dir = self.playbook.parent().unwrap_or(std::path::Path::new("."));
-
             if let Some(pre_tasks) = play.get("pre_tasks").and_then(|t| t.as_sequence()) {
                 for task in pre_tasks {
                     if self.should_run_task(task) {
@@ -464,11 +514,10 @@ impl RunArgs {
                     } else {
                         continue;
                     };
-                    let role_tasks_path = playbook_dir
-                        .join("roles")
-                        .join(&role_name)
-                        .join("tasks")
-                        .join("main.yml");
+                    let Ok(role_dir) = self.role_dir(ctx, &role_name) else {
+                        continue;
+                    };
+                    let role_tasks_path = role_dir.join("tasks").join("main.yml");
                     if role_tasks_path.exists() {
                         if let Ok(content) = std::fs::read_to_string(&role_tasks_path) {
                             if let Ok(role_tasks) =
@@ -697,6 +746,7 @@ impl RunArgs {
         ctx: &mut CommandContext,
         play: &serde_yaml::Value,
         stats: &Arc<Mutex<RecapStats>>,
+        profiler: Option<&ProfileTasksCallback>,
     ) -> Result<()> {
         // Get play name
         let play_name = play
@@ -706,6 +756,14 @@ impl RunArgs {
 
         ctx.output.play_header(play_name);
 
+        if let Some(required) = play.get("min_rustible_version") {
+            let required = match required {
+                serde_yaml::Value::String(s) => s.clone(),
+                other => serde_yaml::to_string(other)?.trim().to_string(),
+            };
+            require_rustible_version(&required).map_err(|e| anyhow::anyhow!(e))?;
+        }
+
         // Get hosts pattern
         let hosts_pattern = play
             .get("hosts")
@@ -747,24 +805,28 @@ impl RunArgs {
         }
 
         // Get pre_tasks, tasks, post_tasks
-        let pre_tasks = play
+        let mut pre_tasks = play
             .get("pre_tasks")
             .and_then(|t| t.as_sequence())
             .cloned()
             .unwrap_or_default();
 
-        let tasks = play
+        let mut tasks = play
             .get("tasks")
             .and_then(|t| t.as_sequence())
             .cloned()
             .unwrap_or_default();
 
-        let post_tasks = play
+        let mut post_tasks = play
             .get("post_tasks")
             .and_then(|t| t.as_sequence())
             .cloned()
             .unwrap_or_default();
 
+        Self::inherit_play_become(play, &mut pre_tasks);
+        Self::inherit_play_become(play, &mut tasks);
+        Self::inherit_play_become(play, &mut post_tasks);
+
         // Get roles
         let roles = play
             .get("roles")
@@ -781,47 +843,69 @@ impl RunArgs {
         // Ansible execution order: gather_facts -> pre_tasks -> roles -> tasks -> post_tasks
 
         // 0. Gather facts if enabled
+        ctx.host_facts.clear();
         if gather_facts {
             ctx.output.task_header("Gathering Facts");
 
-            // Execute the facts module using rustible's native implementation
-            use rustible::modules::{facts::FactsModule, Module, ModuleContext};
-            let facts_module = FactsModule;
-            let params = std::collections::HashMap::new();
-            let module_ctx = ModuleContext::default();
-
-            match facts_module.execute(&params, &module_ctx) {
-                Ok(output) => {
-                    // Extract ansible_facts and add them to vars with ansible_ prefix
-                    if let Some(facts) = output.data.get("ansible_facts") {
-                        if let Some(facts_obj) = facts.as_object() {
+            // Facts cached by `rustible facts gather` stand in for gathering
+            let mut cached = Self::cached_facts(&hosts);
+            let local_facts = if hosts.iter().all(|host| cached.contains_key(host)) {
+                IndexMap::new()
+            } else {
+                // Execute the facts module using rustible's native implementation
+                use rustible::modules::{facts::FactsModule, Module, ModuleContext};
+                let facts_module = FactsModule;
+                let params = std::collections::HashMap::new();
+                let module_ctx = ModuleContext::default();
+
+                match facts_module.execute(&params, &module_ctx) {
+                    Ok(output) => {
+                        // Extract ansible_facts with the ansible_ prefix
+                        let mut facts = IndexMap::new();
+                        if let Some(facts_obj) =
+                            output.data.get("ansible_facts").and_then(|f| f.as_object())
+                        {
                             for (key, value) in facts_obj {
                                 // Convert JSON value to YAML value
                                 if let Ok(yaml_val) = serde_yaml::to_value(value) {
-                                    vars.insert(format!("ansible_{}", key), yaml_val);
+                                    facts.insert(format!("ansible_{}", key), yaml_val);
                                 }
                             }
                         }
+                        facts
                     }
-                    for host in &hosts {
-                        ctx.output.task_result(host, TaskStatus::Ok, None);
-                        stats.lock().await.record(host, TaskStatus::Ok);
+                    Err(e) => {
+                        for host in &hosts {
+                            ctx.output
+                                .task_result(host, TaskStatus::Failed, Some(&e.to_string()));
+                            ctx.output.flush_host(host);
+                            stats.lock().await.record(host, TaskStatus::Failed);
+                        }
+                        return Err(anyhow::anyhow!("Failed to gather facts: {}", e));
                     }
                 }
-                Err(e) => {
-                    for host in &hosts {
+            };
+
+            for host in &hosts {
+                let facts = match cached.remove(host) {
+                    Some(facts) => {
                         ctx.output
-                            .task_result(host, TaskStatus::Failed, Some(&e.to_string()));
-                        stats.lock().await.record(host, TaskStatus::Failed);
+                            .debug(&format!("Using cached facts for {}", host));
+                        facts
                     }
-                    return Err(anyhow::anyhow!("Failed to gather facts: {}", e));
-                }
+                    None => local_facts.clone(),
+                };
+                ctx.host_facts.insert(host.clone(), facts);
+                ctx.output.task_result(host, TaskStatus::Ok, None);
+                ctx.output.flush_host(host);
+                stats.lock().await.record(host, TaskStatus::Ok);
             }
         }
 
         // 1. Execute pre_tasks
         for task in &pre_tasks {
-            self.execute_task(ctx, task, &hosts, stats, &vars).await?;
+            self.execute_task(ctx, task, &hosts, stats, profiler, &vars)
+                .await?;
         }
 
         // 2. Execute role tasks
@@ -836,28 +920,22 @@ impl RunArgs {
                 continue;
             };
 
-            // Load role tasks from roles/<role_name>/tasks/main.yml
-            let playbook_dir = self.playbook.parent().unwrap_or(std::path::Path::new("."));
-            let role_tasks_path = playbook_dir
-                .join("roles")
-                .join(&role_name)
-                .join("tasks")
-                .join("main.yml");
+            // Load role tasks from <role_dir>/tasks/main.yml
+            let role_dir = self.role_dir(ctx, &role_name)?;
+            let role_tasks_path = role_dir.join("tasks").join("main.yml");
 
             if role_tasks_path.exists() {
                 if let Ok(role_content) = std::fs::read_to_string(&role_tasks_path) {
-                    if let Ok(role_tasks) =
+                    if let Ok(mut role_tasks) =
                         serde_yaml::from_str::<Vec<serde_yaml::Value>>(&role_content)
                     {
+                        Self::inherit_play_become(play, &mut role_tasks);
+
                         // Merge role vars if present
                         let mut role_vars = vars.clone();
 
                         // Load role defaults
-                        let defaults_path = playbook_dir
-                            .join("roles")
-                            .join(&role_name)
-                            .join("defaults")
-                            .join("main.yml");
+                        let defaults_path = role_dir.join("defaults").join("main.yml");
                         if defaults_path.exists() {
                             if let Ok(defaults_content) = std::fs::read_to_string(&defaults_path) {
                                 if let Ok(defaults) =
@@ -877,11 +955,7 @@ impl RunArgs {
                         }
 
                         // Load role vars (higher precedence than defaults)
-                        let vars_path = playbook_dir
-                            .join("roles")
-                            .join(&role_name)
-                            .join("vars")
-                            .join("main.yml");
+                        let vars_path = role_dir.join("vars").join("main.yml");
                         if vars_path.exists() {
                             if let Ok(vars_content) = std::fs::read_to_string(&vars_path) {
                                 if let Ok(role_vars_file) =
@@ -900,7 +974,7 @@ impl RunArgs {
 
                         // Execute role tasks
                         for task in &role_tasks {
-                            self.execute_task(ctx, task, &hosts, stats, &role_vars)
+                            self.execute_task(ctx, task, &hosts, stats, profiler, &role_vars)
                                 .await?;
                         }
                     }
@@ -916,17 +990,52 @@ impl RunArgs {
 
         // 3. Execute tasks
         for task in &tasks {
-            self.execute_task(ctx, task, &hosts, stats, &vars).await?;
+            self.execute_task(ctx, task, &hosts, stats, profiler, &vars)
+                .await?;
         }
 
         // 4. Execute post_tasks
         for task in &post_tasks {
-            self.execute_task(ctx, task, &hosts, stats, &vars).await?;
+            self.execute_task(ctx, task, &hosts, stats, profiler, &vars)
+                .await?;
         }
 
         Ok(())
     }
 
+    /// Facts that `rustible facts gather` persisted for `hosts`
+    ///
+    /// Hosts without unexpired cached facts are left out.
+    fn cached_facts(hosts: &[String]) -> HashMap<String, IndexMap<String, serde_yaml::Value>> {
+        let Some(cache_dir) = crate::cli::env::fact_cache_dir().filter(|dir| dir.is_dir()) else {
+            return HashMap::new();
+        };
+        let cache = TieredFactCache::new(TieredCacheConfig {
+            l2_cache_path: cache_dir,
+            ..TieredCacheConfig::default()
+        });
+        cache.warm_from_disk();
+
+        hosts
+            .iter()
+            .filter_map(|host| {
+                let facts = cache
+                    .get(host)?
+                    .into_iter()
+                    .filter_map(|(key, value)| Some((key, serde_yaml::to_value(value).ok()?)))
+                    .collect();
+                Some((host.clone(), facts))
+            })
+            .collect()
+    }
+
+    /// Find a role's directory on the configured roles path, falling back to
+    /// the `roles/` directory next to the playbook
+    fn role_dir(&self, ctx: &CommandContext, role_name: &str) -> Result<PathBuf> {
+        let search_dirs = role_search_dirs(Some(&self.playbook), &ctx.config.roles_path());
+        Ok(find_role(role_name, &search_dirs)?)
+    }
+
     /// Resolve hosts from pattern
     fn resolve_hosts(&self, ctx: &CommandContext, pattern: &str) -> Result<Vec<String>> {
         // Simplified host resolution
@@ -938,25 +1047,12 @@ impl RunArgs {
 
         if pattern == "all" {
             // Load from inventory if available
-            if let Some(inv_path) = ctx.inventory() {
-                if inv_path.exists() {
-                    let content = std::fs::read_to_string(inv_path)?;
-                    let inventory: serde_yaml::Value = serde_yaml::from_str(&content)?;
-
-                    let mut hosts = Vec::new();
-                    if let Some(all) = inventory.get("all") {
-                        if let Some(host_list) = all.get("hosts") {
-                            if let Some(map) = host_list.as_mapping() {
-                                for (key, _) in map {
-                                    if let Some(host) = key.as_str() {
-                                        hosts.push(host.to_string());
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    if !hosts.is_empty() {
-                        return Ok(hosts);
+            let sources = ctx.inventory_sources();
+            if !sources.is_empty() {
+                let inventory = Inventory::load_sources(&sources)?;
+                if let Some(all) = inventory.groups.get("all") {
+                    if !all.hosts.is_empty() {
+                        return Ok(all.hosts.clone());
                     }
                 }
             }
@@ -980,6 +1076,7 @@ impl RunArgs {
         task: &serde_yaml::Value,
         hosts: &[String],
         stats: &Arc<Mutex<RecapStats>>,
+        profiler: Option<&ProfileTasksCallback>,
         vars: &IndexMap<String, serde_yaml::Value>,
     ) -> Result<()> {
         // Get task name
@@ -999,76 +1096,320 @@ impl RunArgs {
 
         ctx.output.task_header(task_name);
 
-        // Check conditions (when)
-        let when_condition = task.get("when");
+        // A refresh reloads the inventory for the whole play, so it runs once
+        let (module, args) = self.detect_module(task);
+        let hosts =
+            if module == "meta" && args.and_then(|a| a.as_str()) == Some("refresh_inventory") {
+                &hosts[..hosts.len().min(1)]
+            } else {
+                hosts
+            };
 
         // Execute on each host
         for host in hosts {
-            // Check when condition (simplified)
-            if let Some(when) = when_condition {
-                let condition = when.as_str().unwrap_or("true");
-                if condition == "false" {
+            let started = Instant::now();
+            let host_vars = match ctx.host_facts.get(host) {
+                Some(facts) => {
+                    let mut merged = vars.clone();
+                    merged.extend(facts.iter().map(|(k, v)| (k.clone(), v.clone())));
+                    Cow::Owned(merged)
+                }
+                None => Cow::Borrowed(vars),
+            };
+            let status = self.execute_task_on_host(ctx, task, host, &host_vars).await;
+            stats.lock().await.record(host, status);
+
+            // The host's task is complete; release its buffered output as one block
+            ctx.output.flush_host(host);
+
+            if ctx.auth_failure_limit_reached() {
+                return Err(anyhow::anyhow!(
+                    "Aborting run: {} host(s) failed SSH authentication (max_auth_failures = {})",
+                    ctx.auth_failures.load(std::sync::atomic::Ordering::Relaxed),
+                    ctx.config.connection.max_auth_failures.unwrap_or_default()
+                ));
+            }
+
+            if let Some(profiler) = profiler {
+                let result = match status {
+                    TaskStatus::Changed => ModuleResult::changed(""),
+                    TaskStatus::Skipped => ModuleResult::skipped(""),
+                    TaskStatus::Failed | TaskStatus::Unreachable => ModuleResult::failed(""),
+                    TaskStatus::Ok | TaskStatus::Rescued | TaskStatus::Ignored => {
+                        ModuleResult::ok("")
+                    }
+                };
+                profiler
+                    .on_task_complete(&ExecutionResult {
+                        host: host.clone(),
+                        task_name: task_name.to_string(),
+                        result,
+                        duration: started.elapsed(),
+                        notify: Vec::new(),
+                    })
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Execute a task on one host, reporting its result and returning the status
+    async fn execute_task_on_host(
+        &self,
+        ctx: &mut CommandContext,
+        task: &serde_yaml::Value,
+        host: &str,
+        vars: &IndexMap<String, serde_yaml::Value>,
+    ) -> TaskStatus {
+        // Check when condition (simplified)
+        if let Some(when) = task.get("when") {
+            let condition = when.as_str().unwrap_or("true");
+            if condition == "false" {
+                ctx.output
+                    .task_result(host, TaskStatus::Skipped, Some("conditional check failed"));
+                return TaskStatus::Skipped;
+            }
+        }
+
+        // Determine the module being used
+        let (module, args) = self.detect_module(task);
+
+        // Meta actions run in check mode too
+        if module == "meta" {
+            let action = args.and_then(|a| a.as_str()).unwrap_or_default();
+            return self.execute_meta(ctx, host, action).await;
+        }
+
+        // In check mode, don't actually execute
+        if ctx.check_mode {
+            if ctx.diff_mode {
+                if let Some((before, after)) = self.check_mode_diff(ctx, host, task, vars).await {
+                    if before == after {
+                        ctx.output.task_result(host, TaskStatus::Ok, None);
+                        return TaskStatus::Ok;
+                    }
+                    let task_name = task.get("name").and_then(|n| n.as_str()).unwrap_or(module);
+                    ctx.output.host_diff(host, task_name, &before, &after);
+                }
+            }
+            ctx.output.task_result(
+                host,
+                TaskStatus::Changed,
+                Some(&format!("[check mode] would run: {}", module)),
+            );
+            return TaskStatus::Changed;
+        }
+
+        // Execute the task (simplified)
+        match self.execute_module(ctx, host, task, vars).await {
+            Ok(changed) => {
+                let status = if changed {
+                    TaskStatus::Changed
+                } else {
+                    TaskStatus::Ok
+                };
+                ctx.output.task_result(host, status, None);
+                status
+            }
+            Err(e) => {
+                // Check for ignore_errors
+                let ignore_errors = task
+                    .get("ignore_errors")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+
+                if ignore_errors {
                     ctx.output.task_result(
                         host,
-                        TaskStatus::Skipped,
-                        Some("conditional check failed"),
+                        TaskStatus::Ignored,
+                        Some(&format!("ignored error: {}", e)),
                     );
-                    stats.lock().await.record(host, TaskStatus::Skipped);
-                    continue;
+                    TaskStatus::Ignored
+                } else {
+                    ctx.output
+                        .task_result(host, TaskStatus::Failed, Some(&e.to_string()));
+                    TaskStatus::Failed
                 }
             }
+        }
+    }
 
-            // Determine the module being used
-            let (module, _args) = self.detect_module(task);
+    /// Before and after content of a file a task would write, for `--diff`
+    ///
+    /// Only `copy` with inline `content` is known ahead of running the task.
+    async fn check_mode_diff(
+        &self,
+        ctx: &CommandContext,
+        host: &str,
+        task: &serde_yaml::Value,
+        vars: &IndexMap<String, serde_yaml::Value>,
+    ) -> Option<(String, String)> {
+        let (module, args) = self.detect_module(task);
+        if module != "copy" {
+            return None;
+        }
+        let args = args?;
+        let content = args.get("content")?.as_str()?;
+        let dest = args.get("dest")?.as_str()?;
+
+        let vars = Self::host_task_vars(ctx, host, vars);
+        let after = Self::template_string(content, &vars);
+        let dest = PathBuf::from(Self::template_string(dest, &vars));
 
-            // In check mode, don't actually execute
-            if ctx.check_mode {
-                ctx.output.task_result(
+        let ansible_connection = vars.get("ansible_connection").and_then(|c| c.as_str());
+        let before = if ctx.is_local(host, ansible_connection) {
+            std::fs::read(&dest).unwrap_or_default()
+        } else {
+            let (ansible_host, ansible_user, ansible_port, ansible_key, transfer_method) =
+                self.get_host_connection_info(ctx, host).ok()?;
+            let conn = ctx
+                .get_connection(
                     host,
-                    TaskStatus::Changed,
-                    Some(&format!("[check mode] would run: {}", module)),
-                );
-                stats.lock().await.record(host, TaskStatus::Changed);
-                continue;
+                    &ansible_host,
+                    &ansible_user,
+                    ansible_port,
+                    ansible_key.as_deref(),
+                    transfer_method,
+                )
+                .await
+                .ok()?;
+            if conn.path_exists(&dest).await.ok()? {
+                conn.download_content(&dest).await.ok()?
+            } else {
+                Vec::new()
             }
+        };
 
-            // Execute the task (simplified)
-            let result = self.execute_module(ctx, host, task, vars).await;
+        Some((String::from_utf8_lossy(&before).into_owned(), after))
+    }
 
-            match result {
-                Ok(changed) => {
-                    let status = if changed {
-                        TaskStatus::Changed
-                    } else {
-                        TaskStatus::Ok
-                    };
-                    ctx.output.task_result(host, status, None);
-                    stats.lock().await.record(host, status);
-                }
-                Err(e) => {
-                    // Check for ignore_errors
-                    let ignore_errors = task
-                        .get("ignore_errors")
-                        .and_then(|v| v.as_bool())
-                        .unwrap_or(false);
-
-                    if ignore_errors {
-                        ctx.output.task_result(
-                            host,
-                            TaskStatus::Ignored,
-                            Some(&format!("ignored error: {}", e)),
-                        );
-                        stats.lock().await.record(host, TaskStatus::Ignored);
-                    } else {
-                        ctx.output
-                            .task_result(host, TaskStatus::Failed, Some(&e.to_string()));
-                        stats.lock().await.record(host, TaskStatus::Failed);
+    /// Play variables layered over the host's inventory and group variables
+    fn host_task_vars(
+        ctx: &CommandContext,
+        host: &str,
+        vars: &IndexMap<String, serde_yaml::Value>,
+    ) -> IndexMap<String, serde_yaml::Value> {
+        let mut merged = IndexMap::new();
+        let sources = ctx.inventory_sources();
+        if let Ok(inventory) = Inventory::load_sources(&sources) {
+            if let Some(all) = inventory.groups.get("all") {
+                merged.extend(all.vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+            if let Some(host) = inventory.hosts.get(host) {
+                for group in host.groups.iter().filter(|g| g.as_str() != "all") {
+                    if let Some(group) = inventory.groups.get(group) {
+                        merged.extend(group.vars.iter().map(|(k, v)| (k.clone(), v.clone())));
                     }
                 }
+                merged.extend(host.vars.iter().map(|(k, v)| (k.clone(), v.clone())));
             }
         }
+        merged.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+        merged
+    }
 
-        Ok(())
+    /// Execute a `meta` action on one host
+    async fn execute_meta(&self, ctx: &mut CommandContext, host: &str, action: &str) -> TaskStatus {
+        if action != "refresh_inventory" {
+            ctx.output.task_result(host, TaskStatus::Ok, None);
+            return TaskStatus::Ok;
+        }
+
+        let Some(source) = ctx.inventory_source() else {
+            ctx.output
+                .warning("refresh_inventory: no inventory source configured, nothing to refresh");
+            ctx.output.task_result(
+                host,
+                TaskStatus::Skipped,
+                Some("no inventory source configured"),
+            );
+            return TaskStatus::Skipped;
+        };
+
+        // Later plays resolve their hosts from the same sources, so they see
+        // whatever this reload finds
+        let refreshed = match source.refresh().await {
+            Ok(()) => source.parse().await,
+            Err(e) => Err(e),
+        };
+        match refreshed {
+            Ok(inventory) => {
+                let msg = format!(
+                    "inventory refreshed ({} host(s))",
+                    inventory.hosts().count()
+                );
+                ctx.output.task_result(host, TaskStatus::Ok, Some(&msg));
+                TaskStatus::Ok
+            }
+            Err(e) => {
+                let msg = format!("failed to refresh inventory: {}", e);
+                ctx.output.task_result(host, TaskStatus::Failed, Some(&msg));
+                TaskStatus::Failed
+            }
+        }
+    }
+
+    /// Privilege escalation defaults from `--become`, `--become-user` and
+    /// `--become-method`, prompting for the password with `--ask-become-pass`
+    fn become_defaults(&self, ctx: &CommandContext) -> Result<BecomeDefaults> {
+        let password = if self.ask_become_pass && !self.plan {
+            ctx.output.flush();
+            Some(
+                dialoguer::Password::new()
+                    .with_prompt("BECOME password")
+                    .interact()?,
+            )
+        } else {
+            None
+        };
+
+        Ok(BecomeDefaults {
+            enabled: self.r#become,
+            method: Some(self.become_method.clone()),
+            user: Some(self.become_user.clone()),
+            password,
+        })
+    }
+
+    /// Execute options for a task's remote commands
+    ///
+    /// A task's own `become`, `become_user` and `become_method` take
+    /// precedence over the command line defaults.
+    fn execute_options(task: &serde_yaml::Value, defaults: &BecomeDefaults) -> ExecuteOptions {
+        let r#become = task
+            .get("become")
+            .and_then(|b| b.as_bool())
+            .unwrap_or(defaults.enabled);
+        if !r#become {
+            return ExecuteOptions::new();
+        }
+
+        let task_str = |key: &str| task.get(key).and_then(|v| v.as_str()).map(String::from);
+        let mut options = ExecuteOptions::new()
+            .with_escalation(task_str("become_user").or_else(|| defaults.user.clone()));
+        options.escalate_method = task_str("become_method").or_else(|| defaults.method.clone());
+        options.escalate_password = defaults.password.clone();
+        options
+    }
+
+    /// Copy the play's `become`, `become_user` and `become_method` into tasks
+    /// that don't set them, so the command line defaults only fill in what
+    /// the playbook left open
+    fn inherit_play_become(play: &serde_yaml::Value, tasks: &mut [serde_yaml::Value]) {
+        for key in ["become", "become_user", "become_method"] {
+            let Some(value) = play.get(key) else {
+                continue;
+            };
+            for task in tasks.iter_mut() {
+                if let Some(mapping) = task.as_mapping_mut() {
+                    let key = serde_yaml::Value::String(key.to_string());
+                    if !mapping.contains_key(&key) {
+                        mapping.insert(key, value.clone());
+                    }
+                }
+            }
+        }
     }
 
     /// Check if a task should run based on tags
@@ -1142,6 +1483,7 @@ impl RunArgs {
             "include_tasks",
             "import_tasks",
             "block",
+            "meta",
         ];
 
         for module in modules {
@@ -1150,9 +1492,42 @@ impl RunArgs {
             }
         }
 
+        // Fully-qualified names of the same built-in modules
+        if let Some(mapping) = task.as_mapping() {
+            for (key, args) in mapping {
+                let Some(name) = key.as_str() else { continue };
+                let short = rustible::modules::builtin_module_name(name);
+                if let Some(module) = modules.into_iter().find(|m| *m == short && short != name) {
+                    return (module, Some(args));
+                }
+            }
+        }
+
         ("unknown", None)
     }
 
+    /// Safe mode from the config, unless overridden with `--force`
+    fn safe_mode(&self, ctx: &CommandContext) -> Option<SafeMode> {
+        let defaults = &ctx.config.defaults;
+        if self.force || !defaults.safe_mode {
+            return None;
+        }
+        Some(SafeMode {
+            allowlist: defaults.safe_mode_allowlist.iter().cloned().collect(),
+        })
+    }
+
+    /// Read the `state` argument from mapping or free-form module args
+    fn module_state(args: &serde_yaml::Value) -> Option<String> {
+        if let Some(state) = args.get("state") {
+            return state.as_str().map(String::from);
+        }
+        args.as_str()?
+            .split_whitespace()
+            .find_map(|arg| arg.strip_prefix("state="))
+            .map(|state| state.trim_matches(|c| c == '"' || c == '\'').to_string())
+    }
+
     /// Execute a module (simplified implementation)
     async fn execute_module(
         &self,
@@ -1163,25 +1538,47 @@ impl RunArgs {
     ) -> Result<bool> {
         let (module, args) = self.detect_module(task);
 
+        if let Some(safe_mode) = self.safe_mode(ctx) {
+            let name = task.get("name").and_then(|n| n.as_str()).unwrap_or(module);
+            let state = args.and_then(Self::module_state);
+            safe_mode
+                .check(name, module, state.as_deref())
+                .map_err(|e| anyhow::anyhow!(e))?;
+        }
+
         ctx.output
             .debug(&format!("Executing module '{}' on host '{}'", module, host));
 
         // Handle debug module locally
         if module == "debug" {
             if let Some(args) = args {
-                if let Some(msg) = args.get("msg").and_then(|m| m.as_str()) {
-                    let templated_msg = Self::template_string(msg, vars);
-                    ctx.output.info(&format!("DEBUG: {}", templated_msg));
+                match args.get("msg") {
+                    Some(serde_yaml::Value::String(msg)) => {
+                        let templated_msg = Self::template_string(msg, vars);
+                        ctx.output
+                            .host_info(host, &Self::debug_message(&templated_msg));
+                    }
+                    Some(msg) if msg.is_mapping() || msg.is_sequence() => {
+                        ctx.output
+                            .host_info(host, &Self::debug_message(&Self::format_debug(msg)));
+                    }
+                    _ => {}
                 }
                 if let Some(var) = args.get("var").and_then(|v| v.as_str()) {
                     // Look up the variable value
                     let var_name = Self::template_string(var, vars);
                     if let Some(value) = vars.get(&var_name) {
-                        ctx.output
-                            .info(&format!("DEBUG: {} = {:?}", var_name, value));
+                        ctx.output.host_info(
+                            host,
+                            &Self::debug_message(&format!(
+                                "{} = {}",
+                                var_name,
+                                Self::format_debug(value)
+                            )),
+                        );
                     } else {
                         ctx.output
-                            .info(&format!("DEBUG: {} = <undefined>", var_name));
+                            .host_info(host, &format!("DEBUG: {} = <undefined>", var_name));
                     }
                 }
             }
@@ -1212,7 +1609,28 @@ impl RunArgs {
                 return Err(anyhow::anyhow!("No command specified"));
             }
 
-            if host == "localhost" || host == "127.0.0.1" {
+            let params: rustible::modules::ModuleParams = args
+                .filter(|a| a.is_mapping())
+                .and_then(|a| serde_json::to_value(a).ok())
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or_default();
+            for warning in rustible::modules::command_warnings(&cmd, &params) {
+                ctx.output.warning(&warning);
+            }
+
+            let options = Self::execute_options(task, &ctx.become_defaults);
+            if ctx.is_local(host, None) && options.escalate {
+                // Local execution through sudo/su
+                ctx.output.debug(&format!("Local execution: {}", cmd));
+                let result = rustible::connection::local::execute_local_with_options(&cmd, options)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to execute command: {}", e))?;
+                if result.success {
+                    return Ok(true);
+                } else {
+                    return Err(anyhow::anyhow!("Command failed: {}", result.stderr));
+                }
+            } else if ctx.is_local(host, None) {
                 // Local execution
                 ctx.output.debug(&format!("Local execution: {}", cmd));
                 let parts: Vec<&str> = cmd.split_whitespace().collect();
@@ -1238,7 +1656,7 @@ impl RunArgs {
                 }
             } else {
                 // Remote execution via SSH
-                return self.execute_remote_command(ctx, host, &cmd).await;
+                return self.execute_remote_command(ctx, host, &cmd, options).await;
             }
         }
 
@@ -1253,9 +1671,10 @@ impl RunArgs {
         ctx: &CommandContext,
         host: &str,
         cmd: &str,
+        options: ExecuteOptions,
     ) -> Result<bool> {
         // Get host connection details from inventory
-        let (ansible_host, ansible_user, ansible_port, ansible_key) =
+        let (ansible_host, ansible_user, ansible_port, ansible_key, transfer_method) =
             self.get_host_connection_info(ctx, host)?;
 
         // Get or create a pooled connection
@@ -1266,12 +1685,13 @@ impl RunArgs {
                 &ansible_user,
                 ansible_port,
                 ansible_key.as_deref(),
+                transfer_method,
             )
             .await?;
 
         // Execute command on the pooled connection
         let result = conn
-            .execute(cmd, None)
+            .execute(cmd, Some(options))
             .await
             .map_err(|e| anyhow::anyhow!("Command execution failed: {}", e))?;
 
@@ -1295,58 +1715,59 @@ impl RunArgs {
         &self,
         ctx: &CommandContext,
         host: &str,
-    ) -> Result<(String, String, u16, Option<String>)> {
+    ) -> Result<(String, String, u16, Option<String>, TransferMethod)> {
         // Try to load from inventory
-        if let Some(inv_path) = ctx.inventory() {
-            if inv_path.exists() {
-                let content = std::fs::read_to_string(inv_path)?;
-                let inventory: serde_yaml::Value = serde_yaml::from_str(&content)?;
-
-                // Look for host-specific vars
-                if let Some(all) = inventory.get("all") {
-                    // Get global vars
-                    let global_user = all
-                        .get("vars")
-                        .and_then(|v| v.get("ansible_user"))
-                        .and_then(|u| u.as_str())
-                        .map(|s| s.to_string());
-                    let global_key = all
-                        .get("vars")
-                        .and_then(|v| v.get("ansible_ssh_private_key_file"))
-                        .and_then(|k| k.as_str())
-                        .map(|s| s.to_string());
-
-                    // Get host-specific vars
-                    if let Some(hosts) = all.get("hosts") {
-                        if let Some(host_config) = hosts.get(host) {
-                            let ansible_host = host_config
-                                .get("ansible_host")
-                                .and_then(|h| h.as_str())
-                                .map(|s| s.to_string())
-                                .unwrap_or_else(|| host.to_string());
-                            let ansible_user = host_config
-                                .get("ansible_user")
-                                .and_then(|u| u.as_str())
-                                .map(|s| s.to_string())
-                                .or(global_user)
-                                .unwrap_or_else(|| {
-                                    std::env::var("USER").unwrap_or_else(|_| "root".to_string())
-                                });
-                            let ansible_port = host_config
-                                .get("ansible_port")
-                                .and_then(|p| p.as_u64())
-                                .unwrap_or(22)
-                                as u16;
-                            let ansible_key = host_config
-                                .get("ansible_ssh_private_key_file")
-                                .and_then(|k| k.as_str())
-                                .map(|s| s.to_string())
-                                .or(global_key);
-
-                            return Ok((ansible_host, ansible_user, ansible_port, ansible_key));
-                        }
-                    }
-                }
+        let sources = ctx.inventory_sources();
+        if !sources.is_empty() {
+            let inventory = Inventory::load_sources(&sources)?;
+
+            if let Some(host_config) = inventory.hosts.get(host) {
+                // Global vars from the all group
+                let global_vars = inventory.groups.get("all").map(|g| &g.vars);
+                let global_var = |name: &str| {
+                    global_vars
+                        .and_then(|vars| vars.get(name))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string())
+                };
+                let global_user = global_var("ansible_user");
+                let global_key = global_var("ansible_ssh_private_key_file");
+
+                let ansible_host = host_config
+                    .vars
+                    .get("ansible_host")
+                    .and_then(|h| h.as_str())
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| host.to_string());
+                let ansible_user = host_config
+                    .vars
+                    .get("ansible_user")
+                    .and_then(|u| u.as_str())
+                    .map(|s| s.to_string())
+                    .or(global_user)
+                    .unwrap_or_else(|| {
+                        std::env::var("USER").unwrap_or_else(|_| "root".to_string())
+                    });
+                let ansible_port = host_config
+                    .vars
+                    .get("ansible_port")
+                    .and_then(|p| p.as_u64())
+                    .unwrap_or(22) as u16;
+                let ansible_key = host_config
+                    .vars
+                    .get("ansible_ssh_private_key_file")
+                    .and_then(|k| k.as_str())
+                    .map(|s| s.to_string())
+                    .or(global_key);
+                let transfer_method = ctx.transfer_method(&host_config.vars)?;
+
+                return Ok((
+                    ansible_host,
+                    ansible_user,
+                    ansible_port,
+                    ansible_key,
+                    transfer_method,
+                ));
             }
         }
 
@@ -1360,7 +1781,9 @@ impl RunArgs {
             .as_ref()
             .map(|p| p.to_string_lossy().to_string());
 
-        Ok((host.to_string(), user, 22, key))
+        let transfer_method = ctx.transfer_method(&HashMap::new())?;
+
+        Ok((host.to_string(), user, 22, key, transfer_method))
     }
 
     /// Validate a limit pattern
@@ -1396,6 +1819,21 @@ impl RunArgs {
         Ok(())
     }
 
+    /// Format a debug value, showing structures as indented JSON
+    fn format_debug(value: &serde_yaml::Value) -> String {
+        match serde_json::to_value(value) {
+            Ok(value) => rustible::modules::debug::format_debug_value(&value),
+            Err(_) => serde_yaml::to_string(value).unwrap_or_default(),
+        }
+    }
+
+    /// Prefix a debug message, aligning continuation lines under its first line
+    fn debug_message(message: &str) -> String {
+        const PREFIX: &str = "DEBUG: ";
+        let indent = format!("\n{}", " ".repeat(PREFIX.len()));
+        format!("{}{}", PREFIX, message.replace('\n', &indent))
+    }
+
     /// Template a string by replacing {{ variable }} patterns with values
     fn template_string(template: &str, vars: &IndexMap<String, serde_yaml::Value>) -> String {
         // Simple Jinja2-like templating for {{ variable }} syntax
@@ -1465,6 +1903,22 @@ mod tests {
         assert_eq!(args.playbook, PathBuf::from("playbook.yml"));
     }
 
+    #[test]
+    fn test_detailed_exit_codes() {
+        let mut stats = RecapStats::new();
+        stats.record("web1", TaskStatus::Ok);
+        assert_eq!(RunArgs::exit_code(&stats, false), 0);
+        assert_eq!(RunArgs::exit_code(&stats, true), 0);
+
+        stats.record("web1", TaskStatus::Changed);
+        assert_eq!(RunArgs::exit_code(&stats, false), 0);
+        assert_eq!(RunArgs::exit_code(&stats, true), 2);
+
+        stats.record("web2", TaskStatus::Failed);
+        assert_eq!(RunArgs::exit_code(&stats, false), 2);
+        assert_eq!(RunArgs::exit_code(&stats, true), 1);
+    }
+
     #[test]
     fn test_run_args_with_tags() {
         let args = RunArgs::try_parse_from([
@@ -1488,12 +1942,75 @@ mod tests {
         assert_eq!(args.become_user, "admin");
     }
 
+    #[test]
+    fn test_execute_options_apply_become_defaults() {
+        let defaults = BecomeDefaults {
+            enabled: true,
+            method: Some("sudo".to_string()),
+            user: Some("root".to_string()),
+            password: None,
+        };
+
+        let task: serde_yaml::Value =
+            serde_yaml::from_str("name: Install nginx\nshell: apt-get install -y nginx").unwrap();
+        let options = RunArgs::execute_options(&task, &defaults);
+        assert_eq!(
+            options.become_command("apt-get install -y nginx"),
+            "sudo -u root -- apt-get install -y nginx"
+        );
+
+        let task: serde_yaml::Value =
+            serde_yaml::from_str("shell: whoami\nbecome_user: deploy").unwrap();
+        let options = RunArgs::execute_options(&task, &defaults);
+        assert_eq!(options.escalate_user.as_deref(), Some("deploy"));
+
+        let task: serde_yaml::Value = serde_yaml::from_str("shell: whoami\nbecome: false").unwrap();
+        assert!(!RunArgs::execute_options(&task, &defaults).escalate);
+
+        let task: serde_yaml::Value = serde_yaml::from_str("shell: whoami\nbecome: true").unwrap();
+        assert!(RunArgs::execute_options(&task, &BecomeDefaults::default()).escalate);
+    }
+
+    #[test]
+    fn test_play_become_overrides_become_defaults() {
+        let defaults = BecomeDefaults {
+            enabled: true,
+            method: Some("sudo".to_string()),
+            user: Some("root".to_string()),
+            password: None,
+        };
+        let play: serde_yaml::Value =
+            serde_yaml::from_str("hosts: all\nbecome: false\nbecome_user: deploy").unwrap();
+        let mut tasks: Vec<serde_yaml::Value> = vec![
+            serde_yaml::from_str("shell: whoami").unwrap(),
+            serde_yaml::from_str("shell: whoami\nbecome: true").unwrap(),
+        ];
+        RunArgs::inherit_play_become(&play, &mut tasks);
+
+        assert!(!RunArgs::execute_options(&tasks[0], &defaults).escalate);
+        let options = RunArgs::execute_options(&tasks[1], &defaults);
+        assert!(options.escalate);
+        assert_eq!(options.escalate_user.as_deref(), Some("deploy"));
+        assert_eq!(options.escalate_method.as_deref(), Some("sudo"));
+    }
+
     #[test]
     fn test_run_args_plan_flag() {
         let args = RunArgs::try_parse_from(["run", "playbook.yml", "--plan"]).unwrap();
         assert!(args.plan);
     }
 
+    #[test]
+    fn test_run_args_profile_flag() {
+        let args = RunArgs::try_parse_from(["run", "playbook.yml", "--profile"]).unwrap();
+        assert!(args.profile);
+        assert!(
+            !RunArgs::try_parse_from(["run", "playbook.yml"])
+                .unwrap()
+                .profile
+        );
+    }
+
     #[test]
     fn test_get_action_description_command() {
         let run_args = RunArgs::try_parse_from(["run", "playbook.yml"]).unwrap();
@@ -1580,6 +2097,23 @@ mod tests {
         assert_eq!(desc, "will display: Hello World");
     }
 
+    #[test]
+    fn test_debug_message_formats_nested_values() {
+        let value: serde_yaml::Value =
+            serde_yaml::from_str("server:\n  name: web\n  ports: [80, 443]").unwrap();
+
+        let formatted = RunArgs::format_debug(&value);
+        assert!(!formatted.contains("Mapping"));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&formatted).unwrap(),
+            serde_json::json!({"server": {"name": "web", "ports": [80, 443]}})
+        );
+
+        let message = RunArgs::debug_message(&format!("config = {}", formatted));
+        assert!(message.starts_with("DEBUG: config = {\n"));
+        assert!(message.ends_with("\n       }"));
+    }
+
     #[test]
     fn test_get_action_description_with_variables() {
         let run_args = RunArgs::try_parse_from(["run", "playbook.yml"]).unwrap();
@@ -28,9 +28,9 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
 
-    /// Path to inventory file or directory
-    #[arg(short = 'i', long, global = true, env = "RUSTIBLE_INVENTORY")]
-    pub inventory: Option<PathBuf>,
+    /// Inventory file, directory or `host1,host2,` list (repeatable, later sources win)
+    #[arg(short = 'i', long, global = true, env = "RUSTIBLE_INVENTORY", action = clap::ArgAction::Append)]
+    pub inventory: Vec<PathBuf>,
 
     /// Extra variables (key=value or @file.yml)
     #[arg(short = 'e', long = "extra-vars", global = true, action = clap::ArgAction::Append)]
@@ -71,6 +71,22 @@ pub struct Cli {
     /// Disable colored output
     #[arg(long, global = true)]
     pub no_color: bool,
+
+    /// How per-host task output is written when hosts run in parallel
+    #[arg(long, global = true, default_value = "stream")]
+    pub output_buffer: OutputBufferMode,
+
+    /// Print one line per host and task: `host | STATUS | task | msg`
+    #[arg(long, visible_alias = "one-line", global = true)]
+    pub oneline: bool,
+
+    /// Prefix every host line with a colored `[host]` label
+    #[arg(long, global = true)]
+    pub host_prefix: bool,
+
+    /// Connection type for all hosts, overriding `ansible_connection`
+    #[arg(long, global = true)]
+    pub connection: Option<ConnectionOverride>,
 }
 
 /// Output format for CLI
@@ -92,6 +108,27 @@ impl Default for OutputFormat {
     }
 }
 
+/// Connection type forced for every host with `--connection`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConnectionOverride {
+    /// Run everything on the control node
+    Local,
+    /// Connect over SSH, even to localhost
+    Ssh,
+    /// Execute in the Docker container named like the host
+    Docker,
+}
+
+/// Buffering mode for per-host task output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputBufferMode {
+    /// Write each result as soon as it is produced
+    #[default]
+    Stream,
+    /// Buffer each host's output and flush it as one block per task
+    Host,
+}
+
 /// Available subcommands
 #[derive(Subcommand, Debug, Clone)]
 pub enum Commands {
@@ -112,11 +149,17 @@ pub enum Commands {
     /// Vault operations (encrypt/decrypt secrets)
     Vault(commands::vault::VaultArgs),
 
+    /// Fact cache operations (pre-warm facts for later runs)
+    Facts(commands::facts::FactsArgs),
+
     /// Initialize a new Rustible project
     Init(InitArgs),
 
     /// Validate playbook syntax
     Validate(ValidateArgs),
+
+    /// Lint a playbook for common mistakes
+    Lint(commands::lint::LintArgs),
 }
 
 /// Arguments for init command
@@ -175,6 +218,15 @@ pub mod env {
             .or_else(|| dirs::home_dir().map(|h| h.join(".rustible")))
     }
 
+    /// Get the persistent fact cache directory written by `facts gather`
+    /// and read by `run`
+    pub fn fact_cache_dir() -> Option<PathBuf> {
+        env::var("RUSTIBLE_FACT_CACHE_DIR")
+            .ok()
+            .map(PathBuf::from)
+            .or_else(|| rustible_home().map(|home| home.join("facts")))
+    }
+
     /// Get the default inventory path
     #[allow(dead_code)]
     pub fn default_inventory() -> Option<PathBuf> {
@@ -218,6 +270,50 @@ mod tests {
         assert!(matches!(cli.command, Commands::Run(_)));
     }
 
+    #[test]
+    fn test_facts_gather_parsing() {
+        let cli = Cli::try_parse_from(["rustible", "-f", "10", "facts", "gather", "web*"]).unwrap();
+        assert!(matches!(cli.command, Commands::Facts(_)));
+        assert_eq!(cli.forks, 10);
+    }
+
+    #[test]
+    fn test_lint_parsing() {
+        let cli = Cli::try_parse_from(["rustible", "lint", "site.yml"]).unwrap();
+        assert!(matches!(cli.command, Commands::Lint(_)));
+    }
+
+    #[test]
+    fn test_output_buffer_parsing() {
+        let cli = Cli::try_parse_from(["rustible", "run", "playbook.yml"]).unwrap();
+        assert_eq!(cli.output_buffer, OutputBufferMode::Stream);
+
+        let cli = Cli::try_parse_from(["rustible", "--output-buffer", "host", "run", "site.yml"])
+            .unwrap();
+        assert_eq!(cli.output_buffer, OutputBufferMode::Host);
+    }
+
+    #[test]
+    fn test_oneline_parsing() {
+        let cli = Cli::try_parse_from(["rustible", "run", "site.yml"]).unwrap();
+        assert!(!cli.oneline);
+
+        let cli = Cli::try_parse_from(["rustible", "run", "site.yml", "--oneline"]).unwrap();
+        assert!(cli.oneline);
+
+        let cli = Cli::try_parse_from(["rustible", "--one-line", "run", "site.yml"]).unwrap();
+        assert!(cli.oneline);
+    }
+
+    #[test]
+    fn test_host_prefix_parsing() {
+        let cli = Cli::try_parse_from(["rustible", "run", "site.yml"]).unwrap();
+        assert!(!cli.host_prefix);
+
+        let cli = Cli::try_parse_from(["rustible", "run", "site.yml", "--host-prefix"]).unwrap();
+        assert!(cli.host_prefix);
+    }
+
     #[test]
     fn test_verbosity() {
         let cli = Cli::try_parse_from(["rustible", "-vvv", "run", "playbook.yml"]).unwrap();
@@ -2,9 +2,15 @@
 //!
 //! Provides colored output, progress indicators, and various output formats.
 
-use colored::Colorize;
+use super::diff::{ColorizedDiff, DiffOptions};
+use super::OutputBufferMode;
+use colored::{Color, Colorize};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use std::collections::HashMap;
+use parking_lot::Mutex;
+use rustible::callback::OnelineCallback;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
 use std::io::{self, Write};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -43,6 +49,19 @@ impl TaskStatus {
         }
     }
 
+    /// Get the upper-case token used in oneline output
+    pub fn token(&self) -> &'static str {
+        match self {
+            TaskStatus::Ok => "OK",
+            TaskStatus::Changed => "CHANGED",
+            TaskStatus::Skipped => "SKIPPED",
+            TaskStatus::Failed => "FAILED",
+            TaskStatus::Unreachable => "UNREACHABLE",
+            TaskStatus::Rescued => "RESCUED",
+            TaskStatus::Ignored => "IGNORED",
+        }
+    }
+
     /// Get the plain string representation
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -70,6 +89,194 @@ pub struct OutputFormatter {
     /// Multi-progress bar container
     #[allow(dead_code)]
     multi_progress: Option<Arc<MultiProgress>>,
+    /// Per-host buffer used when output is flushed per task completion
+    host_buffer: Option<HostOutputBuffer>,
+    /// Current task name when printing one line per host and task
+    oneline_task: Option<Mutex<String>>,
+    /// Prefix each host line with a colored `[host]` label
+    host_prefix: bool,
+    /// Diffs collected for one grouped report instead of printing per host
+    diff_report: Option<DiffReport>,
+}
+
+/// Colors assigned to host labels, chosen to stay readable on dark and light
+/// terminals and to avoid the red/green used for task status
+const HOST_LABEL_COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Blue,
+    Color::BrightCyan,
+    Color::BrightMagenta,
+    Color::BrightBlue,
+];
+
+/// Pick the label color for a host
+///
+/// Uses FNV-1a over the host name so a host keeps the same color across runs
+/// and Rust versions, independent of the order hosts report in.
+pub fn host_color(host: &str) -> Color {
+    let hash = host.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    HOST_LABEL_COLORS[(hash % HOST_LABEL_COLORS.len() as u64) as usize]
+}
+
+/// Buffers task output per host so each host's lines are written as one block
+///
+/// Chunks may arrive interleaved from several hosts; nothing is written until
+/// the host's buffer is flushed, at which point its contents go out in a
+/// single write so they cannot be split by another host's output.
+#[derive(Debug, Default)]
+pub struct HostOutputBuffer {
+    buffers: Mutex<HashMap<String, String>>,
+}
+
+impl HostOutputBuffer {
+    /// Create an empty buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a chunk of output for a host
+    pub fn push(&self, host: &str, chunk: &str) {
+        self.buffers
+            .lock()
+            .entry(host.to_string())
+            .or_default()
+            .push_str(chunk);
+    }
+
+    /// Write a host's buffered output to `writer` and clear it
+    pub fn flush_host<W: Write>(&self, host: &str, writer: &mut W) -> io::Result<()> {
+        let pending = self.buffers.lock().remove(host);
+        match pending {
+            Some(block) if !block.is_empty() => {
+                writer.write_all(block.as_bytes())?;
+                writer.flush()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Write every host's buffered output to `writer`, ordered by host name
+    pub fn flush_all<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut hosts: Vec<String> = self.buffers.lock().keys().cloned().collect();
+        hosts.sort();
+        for host in hosts {
+            self.flush_host(&host, writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// One change a task would make on a host
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize)]
+pub struct HostChange {
+    /// Task that reported the change
+    pub task: String,
+    /// Content before the change
+    pub before: String,
+    /// Content after the change
+    pub after: String,
+}
+
+/// Collects diffs across hosts and coalesces hosts with identical change sets
+///
+/// A host's change set is every diff recorded for it, in task order. Hosts
+/// whose change sets share a fingerprint are reported once as a group, so a
+/// dry run over many identical hosts shows each distinct change only once.
+#[derive(Debug, Default)]
+pub struct DiffReport {
+    changes: Mutex<BTreeMap<String, Vec<HostChange>>>,
+}
+
+impl DiffReport {
+    /// Create an empty report
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a diff for a host
+    pub fn push(&self, host: &str, task: &str, before: &str, after: &str) {
+        self.changes
+            .lock()
+            .entry(host.to_string())
+            .or_default()
+            .push(HostChange {
+                task: task.to_string(),
+                before: before.to_string(),
+                after: after.to_string(),
+            });
+    }
+
+    /// Fingerprint of a host's change set
+    fn fingerprint(changes: &[HostChange]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        changes.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Hosts grouped by identical change sets
+    ///
+    /// Larger groups come first; hosts are sorted within a group and groups
+    /// of the same size are ordered by their first host.
+    pub fn groups(&self) -> Vec<(Vec<String>, Vec<HostChange>)> {
+        let changes = self.changes.lock();
+        let mut groups: Vec<(u64, Vec<String>, Vec<HostChange>)> = Vec::new();
+        for (host, host_changes) in changes.iter() {
+            let fingerprint = Self::fingerprint(host_changes);
+            match groups
+                .iter_mut()
+                .find(|(fp, _, group)| *fp == fingerprint && group == host_changes)
+            {
+                Some((_, hosts, _)) => hosts.push(host.clone()),
+                None => groups.push((fingerprint, vec![host.clone()], host_changes.clone())),
+            }
+        }
+        groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.1.cmp(&b.1)));
+        groups
+            .into_iter()
+            .map(|(_, hosts, changes)| (hosts, changes))
+            .collect()
+    }
+
+    /// Whether no diffs were recorded
+    pub fn is_empty(&self) -> bool {
+        self.changes.lock().is_empty()
+    }
+
+    /// Render the grouped report as text
+    pub fn render(&self, use_color: bool) -> String {
+        let differ = ColorizedDiff::with_options(DiffOptions {
+            use_color,
+            show_line_numbers: false,
+            ..Default::default()
+        });
+
+        let mut out = String::new();
+        for (hosts, changes) in self.groups() {
+            let heading = if hosts.len() > 1 {
+                format!(
+                    "changes identical on {} hosts: {}",
+                    hosts.len(),
+                    hosts.join(", ")
+                )
+            } else {
+                format!("changes on {}", hosts[0])
+            };
+            if use_color {
+                out.push_str(&format!("\n{}\n", heading.yellow().bold()));
+            } else {
+                out.push_str(&format!("\n{}\n", heading));
+            }
+
+            for change in changes {
+                out.push_str(&format!("TASK [{}]\n", change.task));
+                out.push_str(&differ.diff(&change.before, &change.after, "before", "after"));
+            }
+        }
+        out
+    }
 }
 
 impl OutputFormatter {
@@ -84,6 +291,91 @@ impl OutputFormatter {
             verbosity,
             start_time: Instant::now(),
             multi_progress: None,
+            host_buffer: None,
+            oneline_task: None,
+            host_prefix: false,
+            diff_report: None,
+        }
+    }
+
+    /// Select how per-host task output is written
+    pub fn with_buffer_mode(mut self, mode: OutputBufferMode) -> Self {
+        self.host_buffer = match mode {
+            OutputBufferMode::Stream => None,
+            OutputBufferMode::Host => Some(HostOutputBuffer::new()),
+        };
+        self
+    }
+
+    /// Print task results as `host | STATUS | task | msg` lines
+    ///
+    /// Task headers are folded into each result line instead of being printed.
+    pub fn with_oneline(mut self, enabled: bool) -> Self {
+        self.oneline_task = enabled.then(|| Mutex::new(String::new()));
+        self
+    }
+
+    /// Prepend a `[host]` label to every line written for a host
+    ///
+    /// JSON output is left untouched since each record already names its host.
+    pub fn with_host_prefix(mut self, enabled: bool) -> Self {
+        self.host_prefix = enabled;
+        self
+    }
+
+    /// Collect diffs into one report grouped by identical change sets
+    ///
+    /// The report is printed before the recap instead of each diff being
+    /// printed with its host's task result.
+    pub fn with_diff_report(mut self, enabled: bool) -> Self {
+        self.diff_report = enabled.then(DiffReport::new);
+        self
+    }
+
+    /// Format the `[host]` label, colored per host when color is enabled
+    fn host_label(&self, host: &str) -> String {
+        let label = format!("[{}]", host);
+        if self.use_color {
+            label.color(host_color(host)).bold().to_string()
+        } else {
+            label
+        }
+    }
+
+    /// Apply the host label to each line of `text` when prefixing is enabled
+    fn prefix_lines(&self, host: &str, text: &str) -> String {
+        if !self.host_prefix || self.json_mode {
+            return text.to_string();
+        }
+
+        let label = self.host_label(host);
+        text.split_inclusive('\n')
+            .map(|line| format!("{} {}", label, line))
+            .collect()
+    }
+
+    /// Write output belonging to a host, buffering it in host mode
+    fn emit(&self, host: &str, text: &str) {
+        let text = self.prefix_lines(host, text);
+        match &self.host_buffer {
+            Some(buffer) => buffer.push(host, &text),
+            None => print!("{}", text),
+        }
+    }
+
+    /// Flush a host's buffered output once its task has completed
+    ///
+    /// This is a no-op when output is streamed.
+    pub fn flush_host(&self, host: &str) {
+        if let Some(buffer) = &self.host_buffer {
+            let _ = buffer.flush_host(host, &mut io::stdout().lock());
+        }
+    }
+
+    /// Flush buffered output for every host
+    fn flush_buffered(&self) {
+        if let Some(buffer) = &self.host_buffer {
+            let _ = buffer.flush_all(&mut io::stdout().lock());
         }
     }
 
@@ -156,10 +448,16 @@ impl OutputFormatter {
 
     /// Print a task header
     pub fn task_header(&self, task_name: &str) {
+        self.flush_buffered();
         if self.json_mode {
             return;
         }
 
+        if let Some(current) = &self.oneline_task {
+            *current.lock() = task_name.to_string();
+            return;
+        }
+
         let header = format!("TASK [{}]", task_name);
         let stars = "*".repeat(80_usize.saturating_sub(header.len()));
 
@@ -182,7 +480,16 @@ impl OutputFormatter {
                 "status": status.as_str(),
                 "message": message
             });
-            println!("{}", serde_json::to_string(&result).unwrap());
+            self.emit(
+                host,
+                &format!("{}\n", serde_json::to_string(&result).unwrap()),
+            );
+            return;
+        }
+
+        if let Some(current) = &self.oneline_task {
+            let line = self.oneline_result(host, status, &current.lock(), message);
+            self.emit(host, &line);
             return;
         }
 
@@ -198,24 +505,45 @@ impl OutputFormatter {
             host.to_string()
         };
 
-        match status {
-            TaskStatus::Ok
-            | TaskStatus::Changed
-            | TaskStatus::Skipped
-            | TaskStatus::Rescued
-            | TaskStatus::Ignored => {
-                print!("{}: [{}]", status_str, host_str);
-            }
-            TaskStatus::Failed | TaskStatus::Unreachable => {
-                print!("{}: [{}]", status_str, host_str);
-            }
-        }
-
+        let mut line = format!("{}: [{}]", status_str, host_str);
         if let Some(msg) = message {
-            print!(" => {}", msg);
+            line.push_str(&format!(" => {}", msg));
         }
+        line.push('\n');
 
-        println!();
+        self.emit(host, &line);
+    }
+
+    /// Format a task result as a single `host | STATUS | task | msg` line
+    fn oneline_result(
+        &self,
+        host: &str,
+        status: TaskStatus,
+        task_name: &str,
+        message: Option<&str>,
+    ) -> String {
+        let token = status.token();
+        let token = if self.use_color {
+            match status {
+                TaskStatus::Ok => token.green().to_string(),
+                TaskStatus::Changed => token.yellow().to_string(),
+                TaskStatus::Skipped => token.cyan().to_string(),
+                TaskStatus::Failed | TaskStatus::Unreachable => token.red().bold().to_string(),
+                TaskStatus::Rescued => token.magenta().to_string(),
+                TaskStatus::Ignored => token.blue().to_string(),
+            }
+        } else {
+            token.to_string()
+        };
+        let message = message.unwrap_or_else(|| status.as_str());
+
+        format!(
+            "{} | {} | {} | {}\n",
+            host,
+            token,
+            OnelineCallback::single_line(task_name, 0),
+            OnelineCallback::single_line(message, 120)
+        )
     }
 
     /// Print task result with detailed output
@@ -236,7 +564,10 @@ impl OutputFormatter {
                 "status": status.as_str(),
                 "details": details
             });
-            println!("{}", serde_json::to_string_pretty(&result).unwrap());
+            self.emit(
+                host,
+                &format!("{}\n", serde_json::to_string_pretty(&result).unwrap()),
+            );
             return;
         }
 
@@ -244,15 +575,17 @@ impl OutputFormatter {
 
         for (key, value) in details {
             if self.use_color {
-                println!("    {}: {}", key.bright_black(), value);
+                self.emit(host, &format!("    {}: {}\n", key.bright_black(), value));
             } else {
-                println!("    {}: {}", key, value);
+                self.emit(host, &format!("    {}: {}\n", key, value));
             }
         }
     }
 
     /// Print a recap summary
     pub fn recap(&self, stats: &RecapStats) {
+        self.flush_buffered();
+        self.print_diff_report();
         if self.json_mode {
             println!("{}", serde_json::to_string_pretty(stats).unwrap());
             return;
@@ -438,6 +771,35 @@ impl OutputFormatter {
         }
     }
 
+    /// Print an info message produced while running a task on a host
+    ///
+    /// Unlike [`info`](Self::info), the message is kept with the host's other
+    /// task output when buffering per host.
+    pub fn host_info(&self, host: &str, message: &str) {
+        if self.verbosity < 1 {
+            return;
+        }
+
+        if self.json_mode {
+            let info = serde_json::json!({
+                "type": "info",
+                "host": host,
+                "message": message
+            });
+            self.emit(
+                host,
+                &format!("{}\n", serde_json::to_string(&info).unwrap()),
+            );
+            return;
+        }
+
+        if self.use_color {
+            self.emit(host, &format!("{} {}\n", "INFO:".blue(), message));
+        } else {
+            self.emit(host, &format!("INFO: {}\n", message));
+        }
+    }
+
     /// Print plan output (always shows, bypasses verbosity)
     pub fn plan(&self, message: &str) {
         if self.json_mode {
@@ -506,6 +868,70 @@ impl OutputFormatter {
         println!();
     }
 
+    /// Print a diff a task produced on a host
+    ///
+    /// With a diff report enabled the diff is only recorded, and shown grouped
+    /// with identical diffs from other hosts when the run ends.
+    pub fn host_diff(&self, host: &str, task_name: &str, before: &str, after: &str) {
+        if let Some(report) = &self.diff_report {
+            report.push(host, task_name, before, after);
+            return;
+        }
+
+        if self.json_mode {
+            let diff = serde_json::json!({
+                "type": "diff",
+                "host": host,
+                "task": task_name,
+                "before": before,
+                "after": after
+            });
+            self.emit(
+                host,
+                &format!("{}\n", serde_json::to_string(&diff).unwrap()),
+            );
+            return;
+        }
+
+        let differ = ColorizedDiff::with_options(DiffOptions {
+            use_color: self.use_color,
+            show_line_numbers: false,
+            ..Default::default()
+        });
+        self.emit(host, &differ.diff(before, after, "before", "after"));
+    }
+
+    /// Print the grouped diff report, if one was collected
+    fn print_diff_report(&self) {
+        let Some(report) = self.diff_report.as_ref().filter(|r| !r.is_empty()) else {
+            return;
+        };
+
+        if self.json_mode {
+            let groups: Vec<_> = report
+                .groups()
+                .into_iter()
+                .map(|(hosts, changes)| serde_json::json!({ "hosts": hosts, "changes": changes }))
+                .collect();
+            let report = serde_json::json!({ "type": "diff_report", "groups": groups });
+            println!("{}", serde_json::to_string(&report).unwrap());
+            return;
+        }
+
+        let header = "DIFF REPORT";
+        let stars = "*".repeat(80 - header.len());
+        if self.use_color {
+            println!(
+                "\n{} {}",
+                header.bright_white().bold(),
+                stars.bright_black()
+            );
+        } else {
+            println!("\n{} {}", header, stars);
+        }
+        print!("{}", report.render(self.use_color));
+    }
+
     /// Create a progress bar for a task
     #[allow(dead_code)]
     pub fn create_progress_bar(&self, len: u64, message: &str) -> Option<ProgressBar> {
@@ -707,6 +1133,11 @@ impl RecapStats {
         self.hosts.values().any(|h| h.has_failures())
     }
 
+    /// Check if any host reported a change
+    pub fn has_changes(&self) -> bool {
+        self.hosts.values().any(|h| h.changed > 0)
+    }
+
     /// Get total task count
     #[allow(dead_code)]
     pub fn total_tasks(&self) -> u32 {
@@ -786,6 +1217,100 @@ mod tests {
         assert_eq!(recap.total_tasks(), 3);
     }
 
+    #[test]
+    fn test_host_buffer_keeps_blocks_contiguous() {
+        let buffer = HostOutputBuffer::new();
+
+        // Chunks from two hosts arrive interleaved, some mid-line
+        buffer.push("web1", "ok: [web1]");
+        buffer.push("web2", "changed: [web2]");
+        buffer.push("web1", " => started\n");
+        buffer.push("web2", " => restarted\n");
+        buffer.push("web1", "    rc: 0\n");
+        buffer.push("web2", "    rc: 0\n");
+
+        let mut out = Vec::new();
+        buffer.flush_host("web2", &mut out).unwrap();
+        buffer.flush_host("web1", &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "changed: [web2] => restarted\n    rc: 0\nok: [web1] => started\n    rc: 0\n"
+        );
+
+        // Flushed buffers are cleared
+        let mut out = Vec::new();
+        buffer.flush_all(&mut out).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_host_prefix_is_distinct_and_consistent() {
+        let output = OutputFormatter::new(false, false, 0).with_host_prefix(true);
+
+        let web = output.prefix_lines("web1", "ok: [web1]\n    changed: 1\n");
+        let db = output.prefix_lines("db1", "ok: [db1]\n");
+        assert_eq!(web, "[web1] ok: [web1]\n[web1]     changed: 1\n");
+        assert_eq!(db, "[db1] ok: [db1]\n");
+        assert_eq!(
+            output.prefix_lines("web1", "ok: [web1]\n    changed: 1\n"),
+            web
+        );
+
+        assert_ne!(host_color("web1"), host_color("db1"));
+        assert_eq!(host_color("web1"), host_color("web1"));
+
+        let plain = OutputFormatter::new(false, false, 0);
+        assert_eq!(plain.prefix_lines("web1", "ok\n"), "ok\n");
+        let json = OutputFormatter::new(false, true, 0).with_host_prefix(true);
+        assert_eq!(json.prefix_lines("web1", "{}\n"), "{}\n");
+    }
+
+    #[test]
+    fn test_diff_report_groups_identical_change_sets() {
+        let report = DiffReport::new();
+        report.push("web1", "Update config", "port=80\n", "port=8080\n");
+        report.push("db1", "Update config", "port=80\n", "port=5432\n");
+        report.push("web2", "Update config", "port=80\n", "port=8080\n");
+
+        let groups = report.groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, vec!["web1", "web2"]);
+        assert_eq!(groups[0].1[0].after, "port=8080\n");
+        assert_eq!(groups[1].0, vec!["db1"]);
+        assert_eq!(groups[1].1[0].after, "port=5432\n");
+
+        let text = report.render(false);
+        assert_eq!(text.matches("+port=8080").count(), 1, "{}", text);
+        assert_eq!(text.matches("+port=5432").count(), 1, "{}", text);
+        let grouped = text
+            .find("changes identical on 2 hosts: web1, web2")
+            .unwrap();
+        let single = text.find("changes on db1").unwrap();
+        assert!(grouped < text.find("+port=8080").unwrap());
+        assert!(single < text.find("+port=5432").unwrap());
+        assert!(grouped < single);
+    }
+
+    #[test]
+    fn test_oneline_result() {
+        let output = OutputFormatter::new(false, false, 0).with_oneline(true);
+        output.task_header("Install packages");
+        let current = output.oneline_task.as_ref().unwrap().lock().clone();
+        assert_eq!(current, "Install packages");
+
+        let line = output.oneline_result(
+            "web1",
+            TaskStatus::Failed,
+            &current,
+            Some("no\nsuch package"),
+        );
+        assert_eq!(line, "web1 | FAILED | Install packages | no such package\n");
+
+        let line = output.oneline_result("web2", TaskStatus::Ok, &current, None);
+        assert_eq!(line, "web2 | OK | Install packages | ok\n");
+    }
+
     #[test]
     fn test_format_duration() {
         assert_eq!(format_duration(Duration::from_millis(500)), "500ms");
@@ -109,7 +109,8 @@ pub struct Defaults {
     /// Retry files save path
     pub retry_files_save_path: Option<PathBuf>,
 
-    /// Roles path
+    /// Roles path, searched in order (a list or a colon-separated string)
+    #[serde(deserialize_with = "deserialize_path_list")]
     pub roles_path: Vec<PathBuf>,
 
     /// Collections path
@@ -123,6 +124,40 @@ pub struct Defaults {
 
     /// Default strategy
     pub strategy: String,
+
+    /// Refuse destructive modules unless allowlisted or `--force` is given
+    pub safe_mode: bool,
+
+    /// Module or task names allowed to run in safe mode
+    pub safe_mode_allowlist: Vec<String>,
+
+    /// Exit 2 from `run`/`check` when a successful run reported changes
+    pub detailed_exitcode: bool,
+}
+
+/// Split a colon-separated path list.
+fn split_path_list(paths: &str) -> Vec<PathBuf> {
+    std::env::split_paths(paths)
+        .filter(|p| !p.as_os_str().is_empty())
+        .collect()
+}
+
+/// Deserialize a path list given either as a sequence or a colon-separated string
+fn deserialize_path_list<'de, D>(deserializer: D) -> std::result::Result<Vec<PathBuf>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum PathList {
+        Joined(String),
+        List(Vec<String>),
+    }
+
+    Ok(match PathList::deserialize(deserializer)? {
+        PathList::Joined(paths) => split_path_list(&paths),
+        PathList::List(paths) => paths.iter().flat_map(|p| split_path_list(p)).collect(),
+    })
 }
 
 impl Default for Defaults {
@@ -144,6 +179,9 @@ impl Default for Defaults {
             action_plugins: vec![],
             strategy_plugins: vec![],
             strategy: "linear".to_string(),
+            safe_mode: false,
+            safe_mode_allowlist: vec![],
+            detailed_exitcode: false,
         }
     }
 }
@@ -167,11 +205,17 @@ pub struct ConnectionConfig {
     /// SSH executable
     pub ssh_executable: String,
 
-    /// SCP if SSH transfer fails
+    /// Fall back to SCP when the SFTP subsystem is unavailable
     pub scp_if_ssh: bool,
 
+    /// File transfer method (`sftp`, `scp` or `smart`), overriding `scp_if_ssh`
+    pub transfer_method: Option<String>,
+
     /// SFTP batch mode
     pub sftp_batch_mode: bool,
+
+    /// Abort the run once this many hosts have failed authentication
+    pub max_auth_failures: Option<usize>,
 }
 
 impl Default for ConnectionConfig {
@@ -183,7 +227,9 @@ impl Default for ConnectionConfig {
             control_persist: 60,
             ssh_executable: "ssh".to_string(),
             scp_if_ssh: false,
+            transfer_method: None,
             sftp_batch_mode: true,
+            max_auth_failures: None,
         }
     }
 }
@@ -569,6 +615,14 @@ impl Config {
                     other.defaults.strategy_plugins
                 },
                 strategy: other.defaults.strategy,
+                safe_mode: other.defaults.safe_mode || self.defaults.safe_mode,
+                safe_mode_allowlist: if other.defaults.safe_mode_allowlist.is_empty() {
+                    self.defaults.safe_mode_allowlist.clone()
+                } else {
+                    other.defaults.safe_mode_allowlist
+                },
+                detailed_exitcode: other.defaults.detailed_exitcode
+                    || self.defaults.detailed_exitcode,
             },
             connection: other.connection,
             privilege_escalation: other.privilege_escalation,
@@ -674,6 +728,13 @@ impl Config {
         if let Ok(strategy) = std::env::var("RUSTIBLE_STRATEGY") {
             self.defaults.strategy = strategy;
         }
+
+        // RUSTIBLE_ROLES_PATH (or ANSIBLE_ROLES_PATH), colon-separated
+        if let Ok(paths) =
+            std::env::var("RUSTIBLE_ROLES_PATH").or_else(|_| std::env::var("ANSIBLE_ROLES_PATH"))
+        {
+            self.defaults.roles_path = split_path_list(&paths);
+        }
     }
 
     /// Get the effective inventory path
@@ -700,6 +761,20 @@ impl Config {
         self.vault.password_file.as_ref()
     }
 
+    /// Directories searched for roles, in order, with a leading `~` expanded
+    #[allow(dead_code)]
+    pub fn roles_path(&self) -> Vec<PathBuf> {
+        self.defaults
+            .roles_path
+            .iter()
+            .chain(&self.role_paths)
+            .map(|p| match (p.strip_prefix("~"), dirs::home_dir()) {
+                (Ok(rest), Some(home)) => home.join(rest),
+                _ => p.clone(),
+            })
+            .collect()
+    }
+
     /// Load from a specific file (legacy compatibility)
     #[allow(dead_code)]
     pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
@@ -736,6 +811,22 @@ mod tests {
         assert_eq!(merged.defaults.forks, 10);
     }
 
+    #[test]
+    fn test_roles_path_accepts_colon_separated_string() {
+        let defaults: Defaults =
+            serde_yaml::from_str("roles_path: /opt/roles:/srv/shared/roles").unwrap();
+        assert_eq!(
+            defaults.roles_path,
+            vec![
+                PathBuf::from("/opt/roles"),
+                PathBuf::from("/srv/shared/roles")
+            ]
+        );
+
+        let defaults: Defaults = serde_yaml::from_str("roles_path: [/opt/roles]").unwrap();
+        assert_eq!(defaults.roles_path, vec![PathBuf::from("/opt/roles")]);
+    }
+
     #[test]
     fn test_env_override() {
         std::env::set_var("RUSTIBLE_FORKS", "20");
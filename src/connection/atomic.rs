@@ -0,0 +1,348 @@
+//! Atomic file replacement on the target.
+//!
+//! Writing a file in place lets readers see it half-written, and a crash in
+//! the middle leaves it truncated. The helpers here write a temp file next to
+//! the destination instead, flush it to disk and rename it over the
+//! destination, which replaces the file in a single step:
+//!
+//! - The temp file lives in the destination's directory, so the rename never
+//!   crosses filesystems
+//! - Without an explicit mode or owner, those of the replaced file are kept
+//! - Connections that do not report
+//!   [`Connection::supports_atomic_rename`] (WinRM, test doubles) and
+//!   destinations that are symlinks on the target are written in place, with
+//!   none of the guarantees above
+//!
+//! The `*_with_fallback` variants implement `unsafe_writes`: when the atomic
+//! replacement fails (bind mounts, some special filesystems), they rewrite
+//! the destination in place and return a warning instead of failing.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use super::remote_tmp::shell_path;
+use super::{Connection, ConnectionError, ConnectionResult, TransferOptions};
+
+/// Temp file path used while replacing `dest`
+pub fn temp_path(dest: &Path) -> PathBuf {
+    let name = dest
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    dest.with_file_name(format!(".{}.rustible-{}.tmp", name, &id[..12]))
+}
+
+/// Atomically replace a local file with `content`
+///
+/// A symlinked destination is resolved first so the link itself survives.
+pub fn write_file(dest: &Path, content: &[u8], mode: Option<u32>) -> io::Result<()> {
+    let dest = fs::canonicalize(dest).unwrap_or_else(|_| dest.to_path_buf());
+    let tmp = temp_path(&dest);
+
+    let result = write_and_rename(&tmp, &dest, content, mode);
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Rewrite a local file in place, truncating it first
+///
+/// Readers can see the file half-written; only used for `unsafe_writes`.
+pub fn write_file_in_place(dest: &Path, content: &[u8], mode: Option<u32>) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(dest)?;
+    file.write_all(content)?;
+
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+
+    file.sync_all()
+}
+
+/// Atomically replace a local file, rewriting it in place if that fails and
+/// `unsafe_writes` is set
+///
+/// Returns a warning when the in-place fallback was used.
+pub fn write_file_with_fallback(
+    dest: &Path,
+    content: &[u8],
+    mode: Option<u32>,
+    unsafe_writes: bool,
+) -> io::Result<Option<String>> {
+    match write_file(dest, content, mode) {
+        Ok(()) => Ok(None),
+        Err(e) if unsafe_writes => {
+            write_file_in_place(dest, content, mode)?;
+            Ok(Some(in_place_warning(dest, &e)))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Warning reported when `unsafe_writes` rewrote `dest` in place
+pub(crate) fn in_place_warning(dest: &Path, error: &dyn std::fmt::Display) -> String {
+    format!(
+        "Atomic write of '{}' failed ({}); rewrote it in place because unsafe_writes is set",
+        dest.display(),
+        error
+    )
+}
+
+fn write_and_rename(tmp: &Path, dest: &Path, content: &[u8], mode: Option<u32>) -> io::Result<()> {
+    let existing = fs::metadata(dest).ok();
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(tmp)?;
+    file.write_all(content)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        if let Some(mode) = mode.or_else(|| existing.as_ref().map(|m| m.mode() & 0o7777)) {
+            file.set_permissions(fs::Permissions::from_mode(mode))?;
+        }
+        if let Some(meta) = &existing {
+            // Only root may give files away; keep the owner when we can
+            let _ = std::os::unix::fs::fchown(&file, Some(meta.uid()), Some(meta.gid()));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (mode, existing);
+
+    file.sync_all()?;
+    drop(file);
+    fs::rename(tmp, dest)?;
+
+    // Persist the rename itself
+    #[cfg(unix)]
+    if let Some(parent) = dest.parent() {
+        if let Ok(dir) = fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+
+    Ok(())
+}
+
+/// A temp file staged next to a remote destination
+struct Staged {
+    tmp: PathBuf,
+    options: TransferOptions,
+    /// `uid:gid` of the replaced file, restored on the temp file
+    owner: Option<String>,
+}
+
+/// Prepare an atomic replacement, or `None` to write in place
+async fn stage(
+    conn: &dyn Connection,
+    dest: &Path,
+    options: Option<TransferOptions>,
+) -> Option<Staged> {
+    let options = options.unwrap_or_default();
+    if !conn.supports_atomic_rename() || options.backup {
+        return None;
+    }
+
+    let existing = conn.stat(dest).await.ok();
+    if existing.as_ref().is_some_and(|s| s.is_symlink) {
+        debug!("{} is a symlink, replacing it in place", dest.display());
+        return None;
+    }
+
+    let mut staged = Staged {
+        tmp: temp_path(dest),
+        options,
+        owner: None,
+    };
+    if let Some(stat) = existing.filter(|s| s.is_file) {
+        if staged.options.mode.is_none() {
+            staged.options.mode = Some(stat.mode & 0o7777);
+        }
+        if staged.options.owner.is_none() && staged.options.group.is_none() {
+            staged.owner = Some(format!("{}:{}", stat.uid, stat.gid));
+        }
+    }
+    Some(staged)
+}
+
+/// Flush the staged file and rename it over the destination
+async fn commit(conn: &dyn Connection, staged: Staged, dest: &Path) -> ConnectionResult<()> {
+    let tmp = shell_path(&staged.tmp.to_string_lossy());
+    let mut command = String::new();
+    if let Some(owner) = &staged.owner {
+        command.push_str(&format!("chown {} {} 2>/dev/null; ", owner, tmp));
+    }
+    command.push_str(&format!(
+        "sync {tmp} 2>/dev/null; mv -f {tmp} {dest}",
+        tmp = tmp,
+        dest = shell_path(&dest.to_string_lossy())
+    ));
+
+    let error = match conn.execute(&command, None).await {
+        Ok(result) if result.success => return Ok(()),
+        Ok(result) => result.stderr.trim().to_string(),
+        Err(e) => e.to_string(),
+    };
+    discard(conn, &staged.tmp).await;
+    Err(ConnectionError::TransferFailed(format!(
+        "Failed to move {} into place: {}",
+        dest.display(),
+        error
+    )))
+}
+
+/// Remove a staged file after a failed transfer
+async fn discard(conn: &dyn Connection, tmp: &Path) {
+    let command = format!("rm -f {}", shell_path(&tmp.to_string_lossy()));
+    let _ = conn.execute(&command, None).await;
+}
+
+/// Atomically replace a file on the target with `content`
+pub async fn upload_content(
+    conn: &dyn Connection,
+    content: &[u8],
+    dest: &Path,
+    options: Option<TransferOptions>,
+) -> ConnectionResult<()> {
+    let Some(staged) = stage(conn, dest, options.clone()).await else {
+        return conn.upload_content(content, dest, options).await;
+    };
+
+    if let Err(e) = conn
+        .upload_content(content, &staged.tmp, Some(staged.options.clone()))
+        .await
+    {
+        discard(conn, &staged.tmp).await;
+        return Err(e);
+    }
+    commit(conn, staged, dest).await
+}
+
+/// Atomically replace a file on the target with a local file
+pub async fn upload(
+    conn: &dyn Connection,
+    local_path: &Path,
+    dest: &Path,
+    options: Option<TransferOptions>,
+) -> ConnectionResult<()> {
+    let Some(staged) = stage(conn, dest, options.clone()).await else {
+        return conn.upload(local_path, dest, options).await;
+    };
+
+    if let Err(e) = conn
+        .upload(local_path, &staged.tmp, Some(staged.options.clone()))
+        .await
+    {
+        discard(conn, &staged.tmp).await;
+        return Err(e);
+    }
+    commit(conn, staged, dest).await
+}
+
+/// Atomically replace a file on the target with `content`, rewriting it in
+/// place if that fails and `unsafe_writes` is set
+///
+/// Returns a warning when the in-place fallback was used.
+pub async fn upload_content_with_fallback(
+    conn: &dyn Connection,
+    content: &[u8],
+    dest: &Path,
+    options: Option<TransferOptions>,
+    unsafe_writes: bool,
+) -> ConnectionResult<Option<String>> {
+    match upload_content(conn, content, dest, options.clone()).await {
+        Ok(()) => Ok(None),
+        Err(e) if unsafe_writes => {
+            conn.upload_content(content, dest, options).await?;
+            Ok(Some(in_place_warning(dest, &e)))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Atomically replace a file on the target with a local file, rewriting it
+/// in place if that fails and `unsafe_writes` is set
+///
+/// Returns a warning when the in-place fallback was used.
+pub async fn upload_with_fallback(
+    conn: &dyn Connection,
+    local_path: &Path,
+    dest: &Path,
+    options: Option<TransferOptions>,
+    unsafe_writes: bool,
+) -> ConnectionResult<Option<String>> {
+    match upload(conn, local_path, dest, options.clone()).await {
+        Ok(()) => Ok(None),
+        Err(e) if unsafe_writes => {
+            conn.upload(local_path, dest, options).await?;
+            Ok(Some(in_place_warning(dest, &e)))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temp_path_is_hidden_sibling() {
+        let tmp = temp_path(Path::new("/etc/nginx/nginx.conf"));
+        assert_eq!(tmp.parent(), Some(Path::new("/etc/nginx")));
+        let name = tmp.file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with(".nginx.conf.rustible-"));
+        assert!(name.ends_with(".tmp"));
+        assert_ne!(tmp, temp_path(Path::new("/etc/nginx/nginx.conf")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_file_keeps_mode_and_follows_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("app.conf");
+        let link = dir.path().join("current.conf");
+        fs::write(&target, "old").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o640)).unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_file(&link, b"new", None).unwrap();
+
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+        let mode = fs::metadata(&target).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode, 0o640);
+        // No temp files left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_write_file_in_place_truncates() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("app.conf");
+        fs::write(&dest, "a much longer old body").unwrap();
+
+        write_file_in_place(&dest, b"new", None).unwrap();
+
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "new");
+    }
+}
@@ -10,6 +10,8 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+use super::jump_host::proxy_jump_from_ssh_args;
+use super::transfer::TransferMethod;
 use super::ConnectionError;
 
 /// Default connection timeout in seconds
@@ -139,6 +141,9 @@ impl ConnectionConfig {
         if config.identity_file.is_none() && !self.defaults.identity_files.is_empty() {
             config.identity_file = self.defaults.identity_files.first().cloned();
         }
+        if config.transfer_method.is_none() {
+            config.transfer_method = Some(self.defaults.transfer_method);
+        }
 
         config
     }
@@ -162,6 +167,11 @@ impl ConnectionConfig {
     pub fn set_default_timeout(&mut self, timeout: u64) {
         self.defaults.timeout = timeout;
     }
+
+    /// Enable or disable SSH agent authentication
+    pub fn set_use_agent(&mut self, use_agent: bool) {
+        self.defaults.use_agent = use_agent;
+    }
 }
 
 /// Default connection settings
@@ -202,6 +212,10 @@ pub struct ConnectionDefaults {
     /// Known hosts file path
     #[serde(default)]
     pub known_hosts_file: Option<PathBuf>,
+
+    /// File transfer method (sftp, scp or smart)
+    #[serde(default)]
+    pub transfer_method: TransferMethod,
 }
 
 fn default_user() -> String {
@@ -238,6 +252,7 @@ impl Default for ConnectionDefaults {
             use_agent: true,
             verify_host_key: true,
             known_hosts_file: None,
+            transfer_method: TransferMethod::default(),
         }
     }
 }
@@ -270,10 +285,10 @@ pub struct HostConfig {
     /// Retry delay in seconds
     pub retry_delay: Option<u64>,
 
-    /// Connection type (ssh, local, docker)
+    /// Connection type (ssh, local, docker, podman, winrm, kubectl)
     pub connection: Option<String>,
 
-    /// Proxy/jump host
+    /// Proxy/jump hosts, as a comma-separated `[user@]host[:port]` chain
     pub proxy_jump: Option<String>,
 
     /// Forward agent
@@ -296,6 +311,38 @@ pub struct HostConfig {
     /// User known hosts file
     pub user_known_hosts_file: Option<String>,
 
+    /// File transfer method (sftp, scp or smart)
+    #[serde(default)]
+    pub transfer_method: Option<TransferMethod>,
+
+    /// WinRM authentication transport (ntlm, basic, credssp or kerberos)
+    #[serde(default)]
+    pub winrm_transport: Option<String>,
+
+    /// Podman binary to run (default: `podman` from PATH)
+    #[serde(default)]
+    pub podman_executable: Option<String>,
+
+    /// Kubernetes namespace of the pod (default: `default`)
+    #[serde(default)]
+    pub kube_namespace: Option<String>,
+
+    /// Kubernetes pod to exec in (default: the hostname)
+    #[serde(default)]
+    pub kube_pod: Option<String>,
+
+    /// Container within the pod (default: the pod's first container)
+    #[serde(default)]
+    pub kube_container: Option<String>,
+
+    /// Kubeconfig file to load
+    #[serde(default)]
+    pub kube_config: Option<String>,
+
+    /// Kubeconfig context to use
+    #[serde(default)]
+    pub kube_context: Option<String>,
+
     /// Extra SSH options
     #[serde(default)]
     pub options: HashMap<String, String>,
@@ -307,6 +354,53 @@ impl HostConfig {
         Self::default()
     }
 
+    /// Build a host config from an inventory host's connection variables
+    ///
+    /// Covers `ansible_host`, `ansible_port`, `ansible_user`, the password and
+    /// key variables, `ansible_connection`, `ansible_winrm_transport`,
+    /// `ansible_podman_executable` and the `ansible_kube_*` pod variables.
+    pub fn from_inventory_host(host: &crate::inventory::Host) -> Self {
+        use crate::inventory::ConnectionType;
+
+        let var = |key: &str| {
+            host.vars
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        };
+        let ssh = &host.connection.ssh;
+        let connection = &host.connection.connection;
+        // 22 is the inventory's default port, not a WinRM one
+        let port = match connection {
+            ConnectionType::Winrm if ssh.port == 22 => None,
+            ConnectionType::Podman | ConnectionType::Kubectl => None,
+            _ => Some(ssh.port),
+        };
+
+        Self {
+            hostname: host.ansible_host.clone(),
+            port,
+            user: ssh.user.clone(),
+            identity_file: ssh.private_key_file.clone(),
+            password: ssh.password.clone().or_else(|| var("ansible_password")),
+            connection: Some(connection.to_string()),
+            winrm_transport: var("ansible_winrm_transport"),
+            podman_executable: var("ansible_podman_executable"),
+            kube_namespace: var("ansible_kube_namespace"),
+            kube_pod: var("ansible_kube_pod"),
+            kube_container: var("ansible_kube_container"),
+            kube_config: var("ansible_kube_config"),
+            kube_context: var("ansible_kube_context"),
+            proxy_jump: var("ansible_ssh_proxy").or_else(|| {
+                ssh.common_args
+                    .clone()
+                    .or_else(|| var("ansible_ssh_common_args"))
+                    .and_then(|args| proxy_jump_from_ssh_args(&args))
+            }),
+            ..Self::default()
+        }
+    }
+
     /// Set hostname
     pub fn hostname(mut self, hostname: impl Into<String>) -> Self {
         self.hostname = Some(hostname.into());
@@ -349,6 +443,12 @@ impl HostConfig {
         self
     }
 
+    /// Set the file transfer method
+    pub fn transfer_method(mut self, method: TransferMethod) -> Self {
+        self.transfer_method = Some(method);
+        self
+    }
+
     /// Get the connection timeout as Duration
     pub fn timeout_duration(&self) -> Duration {
         Duration::from_secs(self.connect_timeout.unwrap_or(DEFAULT_TIMEOUT))
@@ -402,6 +502,13 @@ impl RetryConfig {
             self.retry_delay
         }
     }
+
+    /// Whether a connection attempt that failed with `error` should be retried.
+    ///
+    /// Fail-fast errors such as authentication failures are never retried.
+    pub fn should_retry(&self, attempt: u32, error: &ConnectionError) -> bool {
+        attempt < self.max_retries && !error.is_fail_fast()
+    }
 }
 
 /// SSH config file parser
@@ -607,6 +714,69 @@ Host *.internal
         assert_eq!(internal.proxy_jump, Some("bastion".to_string()));
     }
 
+    #[test]
+    fn test_host_config_from_inventory_winrm_host() {
+        let host = crate::inventory::Host::parse(
+            "win1 ansible_host=10.0.0.9 ansible_user=Administrator ansible_connection=winrm ansible_winrm_transport=basic",
+        )
+        .unwrap();
+
+        let config = HostConfig::from_inventory_host(&host);
+        assert_eq!(config.hostname.as_deref(), Some("10.0.0.9"));
+        assert_eq!(config.connection.as_deref(), Some("winrm"));
+        assert_eq!(config.winrm_transport.as_deref(), Some("basic"));
+        // The inventory's SSH default port does not apply to WinRM
+        assert_eq!(config.port, None);
+    }
+
+    #[test]
+    fn test_host_config_from_inventory_podman_host() {
+        let host = crate::inventory::Host::parse(
+            "web ansible_host=web-container ansible_connection=podman ansible_podman_executable=/opt/podman/bin/podman",
+        )
+        .unwrap();
+
+        let config = HostConfig::from_inventory_host(&host);
+        assert_eq!(config.hostname.as_deref(), Some("web-container"));
+        assert_eq!(config.connection.as_deref(), Some("podman"));
+        assert_eq!(
+            config.podman_executable.as_deref(),
+            Some("/opt/podman/bin/podman")
+        );
+    }
+
+    #[test]
+    fn test_host_config_from_inventory_kubectl_host() {
+        let host = crate::inventory::Host::parse(
+            "api ansible_connection=kubectl ansible_kube_namespace=staging ansible_kube_pod=api-7d9f ansible_kube_container=app ansible_kube_config=/etc/kube/staging",
+        )
+        .unwrap();
+
+        let config = HostConfig::from_inventory_host(&host);
+        assert_eq!(config.connection.as_deref(), Some("kubectl"));
+        assert_eq!(config.kube_namespace.as_deref(), Some("staging"));
+        assert_eq!(config.kube_pod.as_deref(), Some("api-7d9f"));
+        assert_eq!(config.kube_container.as_deref(), Some("app"));
+        assert_eq!(config.kube_config.as_deref(), Some("/etc/kube/staging"));
+        assert_eq!(config.port, None);
+    }
+
+    #[test]
+    fn test_host_config_from_inventory_proxy_jump() {
+        let mut host = crate::inventory::Host::new("db1");
+        host.set_var(
+            "ansible_ssh_common_args",
+            serde_yaml::Value::from("-o StrictHostKeyChecking=no -J ops@bastion:2222"),
+        );
+        let config = HostConfig::from_inventory_host(&host);
+        assert_eq!(config.proxy_jump.as_deref(), Some("ops@bastion:2222"));
+
+        // A dedicated ansible_ssh_proxy wins over the common args
+        host.set_var("ansible_ssh_proxy", serde_yaml::Value::from("jump1,jump2"));
+        let config = HostConfig::from_inventory_host(&host);
+        assert_eq!(config.proxy_jump.as_deref(), Some("jump1,jump2"));
+    }
+
     #[test]
     fn test_pattern_matching() {
         assert!(matches_pattern("*.example.com", "server.example.com"));
@@ -649,4 +819,52 @@ user = "web"
         assert_eq!(webserver.hostname, Some("192.168.1.100".to_string()));
         assert_eq!(webserver.port, Some(2222));
     }
+
+    #[test]
+    fn test_transfer_method_from_toml() {
+        let toml = r#"
+[defaults]
+transfer_method = "smart"
+
+[hosts.legacy]
+transfer_method = "scp"
+"#;
+
+        let config = ConnectionConfig::from_toml(toml).unwrap();
+        assert_eq!(config.defaults.transfer_method, TransferMethod::Smart);
+        assert_eq!(
+            config.get_host_merged("legacy").transfer_method,
+            Some(TransferMethod::Scp)
+        );
+        assert_eq!(
+            config.get_host_merged("other").transfer_method,
+            Some(TransferMethod::Smart)
+        );
+    }
+
+    #[test]
+    fn test_auth_failures_are_not_retried() {
+        let retry = RetryConfig::default();
+        let network = ConnectionError::ConnectionFailed("connection refused".to_string());
+        let auth = ConnectionError::AuthenticationFailed("publickey rejected".to_string());
+
+        assert!(retry.should_retry(0, &network));
+        assert!(!retry.should_retry(retry.max_retries, &network));
+        assert!(!retry.should_retry(0, &auth));
+
+        let err = auth.with_auth_context("deploy", Some("/home/deploy/.ssh/id_ed25519"));
+        assert!(matches!(err, ConnectionError::AuthenticationFailed(_)));
+        let msg = err.to_string();
+        assert!(msg.contains("publickey rejected"));
+        assert!(msg.contains("user 'deploy'"));
+        assert!(msg.contains("key '/home/deploy/.ssh/id_ed25519'"));
+
+        let err = ConnectionError::AuthenticationFailed("denied".to_string())
+            .with_auth_context("root", None);
+        assert!(err.to_string().contains("default keys and agent"));
+        assert_eq!(
+            network.with_auth_context("deploy", None).to_string(),
+            "Connection failed: connection refused"
+        );
+    }
 }
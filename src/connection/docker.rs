@@ -66,6 +66,10 @@ impl DockerConnection {
     }
 
     /// Build the docker exec command
+    ///
+    /// Privilege escalation maps to `--user` rather than wrapping the command
+    /// in sudo, which containers rarely ship. `become` without a user runs
+    /// as root.
     fn build_exec_command(&self, command: &str, options: &ExecuteOptions) -> Command {
         let mut cmd = Command::new(&self.docker_path);
 
@@ -74,32 +78,34 @@ impl DockerConnection {
 
             // Add compose-specific options
             cmd.arg("-T"); // Disable pseudo-TTY
-
-            if let Some(service) = &self.compose_service {
-                cmd.arg(service);
-            }
         } else {
             cmd.arg("exec");
 
             // Add docker exec options
             cmd.arg("-i"); // Keep STDIN open
+        }
 
-            // Set user if escalation is requested
-            if options.escalate {
-                let user = options.escalate_user.as_deref().unwrap_or("root");
-                cmd.arg("-u").arg(user);
-            }
+        // Set user if escalation is requested
+        if options.escalate {
+            let user = options.escalate_user.as_deref().unwrap_or("root");
+            cmd.arg("--user").arg(user);
+        }
 
-            // Set working directory
-            if let Some(cwd) = &options.cwd {
-                cmd.arg("-w").arg(cwd);
-            }
+        // Set working directory
+        if let Some(cwd) = &options.cwd {
+            cmd.arg("-w").arg(cwd);
+        }
 
-            // Set environment variables
-            for (key, value) in &options.env {
-                cmd.arg("-e").arg(format!("{}={}", key, value));
-            }
+        // Set environment variables
+        for (key, value) in &options.env {
+            cmd.arg("-e").arg(format!("{}={}", key, value));
+        }
 
+        if self.use_compose {
+            if let Some(service) = &self.compose_service {
+                cmd.arg(service);
+            }
+        } else {
             cmd.arg(&self.container);
         }
 
@@ -478,6 +484,10 @@ impl Connection for DockerConnection {
         // The container continues running
         Ok(())
     }
+
+    fn supports_atomic_rename(&self) -> bool {
+        true
+    }
 }
 
 /// Builder for Docker connections
@@ -636,4 +646,67 @@ mod tests {
 
         let _ = conn.build_exec_command("echo hello", &options);
     }
+
+    fn exec_args(cmd: &Command) -> Vec<String> {
+        cmd.as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_build_exec_command_become_user_maps_to_exec_user() {
+        let conn = DockerConnection::new("my-container");
+        let options = ExecuteOptions::new().with_escalation(Some("app".to_string()));
+
+        let args = exec_args(&conn.build_exec_command("id -un", &options));
+        assert_eq!(
+            args,
+            vec![
+                "exec",
+                "-i",
+                "--user",
+                "app",
+                "my-container",
+                "sh",
+                "-c",
+                "id -un"
+            ]
+        );
+        assert!(!args.iter().any(|arg| arg.contains("sudo")));
+    }
+
+    #[test]
+    fn test_build_exec_command_become_without_user_runs_as_root() {
+        let conn = DockerConnection::with_docker_path("my-container", "podman");
+        let options = ExecuteOptions::new().with_escalation(None);
+
+        let cmd = conn.build_exec_command("id -un", &options);
+        assert_eq!(cmd.as_std().get_program(), "podman");
+        assert_eq!(
+            exec_args(&cmd),
+            vec![
+                "exec",
+                "-i",
+                "--user",
+                "root",
+                "my-container",
+                "sh",
+                "-c",
+                "id -un"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_exec_command_compose_honors_become_user() {
+        let conn = DockerConnection::compose("web");
+        let options = ExecuteOptions::new().with_escalation(Some("app".to_string()));
+
+        let args = exec_args(&conn.build_exec_command("id -un", &options));
+        assert_eq!(
+            args,
+            vec!["compose", "exec", "-T", "--user", "app", "web", "sh", "-c", "id -un"]
+        );
+    }
 }
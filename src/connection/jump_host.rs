@@ -411,10 +411,106 @@ impl<'a> JumpHostResolver<'a> {
     }
 }
 
+/// Extract a ProxyJump specification from OpenSSH command line arguments.
+///
+/// Understands `-J spec`, `-o ProxyJump=spec` and the older
+/// `-o ProxyCommand="ssh -W %h:%p [-l user] [-p port] bastion"` form, as
+/// commonly set in `ansible_ssh_common_args`.
+pub fn proxy_jump_from_ssh_args(args: &str) -> Option<String> {
+    let mut words = shell_words::split(args).ok()?.into_iter();
+    while let Some(word) = words.next() {
+        if let Some(spec) = word.strip_prefix("-J") {
+            return if spec.is_empty() {
+                words.next()
+            } else {
+                Some(spec.to_string())
+            };
+        }
+
+        let Some(option) = word.strip_prefix("-o") else {
+            continue;
+        };
+        let option = if option.is_empty() {
+            match words.next() {
+                Some(option) => option,
+                None => break,
+            }
+        } else {
+            option.to_string()
+        };
+        let Some((key, value)) = option.split_once(|c: char| c == '=' || c.is_whitespace()) else {
+            continue;
+        };
+        let value = value.trim();
+        if key.eq_ignore_ascii_case("ProxyJump") {
+            return Some(value.to_string());
+        }
+        if key.eq_ignore_ascii_case("ProxyCommand") {
+            if let Some(spec) = proxy_command_jump(value) {
+                return Some(spec);
+            }
+        }
+    }
+    None
+}
+
+/// Jump host of an `ssh -W %h:%p bastion` ProxyCommand
+fn proxy_command_jump(command: &str) -> Option<String> {
+    let words = shell_words::split(command).ok()?;
+    let (program, args) = words.split_first()?;
+    if !program.ends_with("ssh") || !args.iter().any(|arg| arg == "-W") {
+        return None;
+    }
+
+    let (mut user, mut port, mut destination) = (None, None, None);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-l" => user = args.next(),
+            "-p" => port = args.next(),
+            // Options taking a value that does not matter here
+            "-W" | "-i" | "-F" | "-o" | "-J" => {
+                args.next();
+            }
+            flag if flag.starts_with('-') => {}
+            host => destination = Some(host),
+        }
+    }
+
+    let mut spec = destination?.to_string();
+    if let Some(user) = user.filter(|_| !spec.contains('@')) {
+        spec = format!("{}@{}", user, spec);
+    }
+    if let Some(port) = port {
+        spec = format!("{}:{}", spec, port);
+    }
+    Some(spec)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_proxy_jump_from_ssh_args() {
+        assert_eq!(
+            proxy_jump_from_ssh_args("-J admin@bastion:2222,jump2"),
+            Some("admin@bastion:2222,jump2".to_string())
+        );
+        assert_eq!(
+            proxy_jump_from_ssh_args("-o StrictHostKeyChecking=no -o ProxyJump=bastion"),
+            Some("bastion".to_string())
+        );
+        assert_eq!(
+            proxy_jump_from_ssh_args(r#"-o ProxyCommand="ssh -W %h:%p -q -p 2222 ops@bastion""#),
+            Some("ops@bastion:2222".to_string())
+        );
+        assert_eq!(
+            proxy_jump_from_ssh_args("-o StrictHostKeyChecking=no"),
+            None
+        );
+    }
+
     #[test]
     fn test_jump_host_config_new() {
         let jump = JumpHostConfig::new("bastion.example.com");
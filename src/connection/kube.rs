@@ -1,8 +1,8 @@
 //! Kubernetes connection module
 //!
-//! This module provides connectivity to Kubernetes pods using the kube-rs crate.
-//! It supports executing commands inside pods via the Kubernetes API, with full
-//! support for kubeconfig, service accounts, and namespace isolation.
+//! This module runs commands inside pods through the Kubernetes exec API
+//! (the equivalent of `kubectl exec`) using the kube-rs crate, so no
+//! `kubectl` binary is needed on the control node.
 //!
 //! # Features
 //!
@@ -10,15 +10,23 @@
 //! - Kubeconfig file loading with context selection
 //! - Service account token authentication
 //! - Namespace-aware operations
-//! - File transfer via tar streaming
+//! - File transfer by streaming tar archives through exec
+//!
+//! # Inventory
+//!
+//! Hosts with `ansible_connection=kubectl` are routed here by the
+//! [`ConnectionFactory`](super::ConnectionFactory). The pod is taken from
+//! `ansible_kube_pod` (default: `ansible_host`, then the inventory name),
+//! along with `ansible_kube_namespace`, `ansible_kube_container`,
+//! `ansible_kube_config` and `ansible_kube_context`.
 //!
 //! # Example
 //!
 //! ```rust,ignore
-//! use rustible::connection::kubernetes::{KubernetesConnection, KubernetesConnectionBuilder};
+//! use rustible::connection::kube::{KubeConnection, KubeConnectionBuilder};
 //!
 //! // Connect to a pod in the default namespace
-//! let conn = KubernetesConnectionBuilder::new()
+//! let conn = KubeConnectionBuilder::new()
 //!     .namespace("default")
 //!     .pod("my-pod")
 //!     .container("app")
@@ -31,25 +39,44 @@
 //! ```
 
 use async_trait::async_trait;
-use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
 use k8s_openapi::api::core::v1::Pod;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Status;
 use kube::{
     api::{Api, AttachParams, AttachedProcess, ListParams},
     config::{KubeConfigOptions, Kubeconfig},
     Client, Config,
 };
+use std::fmt;
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tracing::{debug, trace};
 
+use super::config::expand_path;
 use super::{
     CommandResult, Connection, ConnectionError, ConnectionResult, ExecuteOptions, FileStat,
     TransferOptions,
 };
 
+/// Environment variable naming a kubeconfig, as read by Ansible's
+/// kubernetes.core collection
+const KUBECONFIG_ENV: &str = "K8S_AUTH_KUBECONFIG";
+
+/// Resolve the kubeconfig file for a host
+///
+/// `ansible_kube_config` wins, then `K8S_AUTH_KUBECONFIG`. `None` leaves the
+/// choice to kube-rs, which reads `$KUBECONFIG` or `~/.kube/config`.
+pub fn resolve_kubeconfig(configured: Option<&str>) -> Option<PathBuf> {
+    configured
+        .map(String::from)
+        .or_else(|| std::env::var(KUBECONFIG_ENV).ok())
+        .filter(|path| !path.is_empty())
+        .map(|path| expand_path(&path))
+}
+
 /// Authentication method for Kubernetes API
 #[derive(Debug, Clone)]
-pub enum KubernetesAuth {
+pub enum KubeAuth {
     /// Use kubeconfig file (default: ~/.kube/config)
     Kubeconfig {
         /// Path to kubeconfig file (None = default location)
@@ -63,9 +90,9 @@ pub enum KubernetesAuth {
     BearerToken(String),
 }
 
-impl Default for KubernetesAuth {
+impl Default for KubeAuth {
     fn default() -> Self {
-        KubernetesAuth::Kubeconfig {
+        KubeAuth::Kubeconfig {
             path: None,
             context: None,
         }
@@ -73,8 +100,7 @@ impl Default for KubernetesAuth {
 }
 
 /// Kubernetes connection for executing commands inside pods
-#[derive(Debug)]
-pub struct KubernetesConnection {
+pub struct KubeConnection {
     /// Kubernetes API client
     client: Client,
     /// Target namespace
@@ -89,7 +115,17 @@ pub struct KubernetesConnection {
     identifier: String,
 }
 
-impl Clone for KubernetesConnection {
+impl fmt::Debug for KubeConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KubeConnection")
+            .field("namespace", &self.namespace)
+            .field("pod", &self.pod)
+            .field("container", &self.container)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Clone for KubeConnection {
     fn clone(&self) -> Self {
         Self {
             client: self.client.clone(),
@@ -102,13 +138,13 @@ impl Clone for KubernetesConnection {
     }
 }
 
-impl KubernetesConnection {
+impl KubeConnection {
     /// Create a new Kubernetes connection
     pub async fn new(
         namespace: impl Into<String>,
         pod: impl Into<String>,
         container: Option<String>,
-        auth: KubernetesAuth,
+        auth: KubeAuth,
     ) -> ConnectionResult<Self> {
         let namespace = namespace.into();
         let pod = pod.into();
@@ -128,9 +164,9 @@ impl KubernetesConnection {
     }
 
     /// Create a Kubernetes client from authentication config
-    async fn create_client(auth: KubernetesAuth) -> ConnectionResult<Client> {
+    async fn create_client(auth: KubeAuth) -> ConnectionResult<Client> {
         let config = match auth {
-            KubernetesAuth::Kubeconfig { path, context } => {
+            KubeAuth::Kubeconfig { path, context } => {
                 let kubeconfig = if let Some(path) = path {
                     Kubeconfig::read_from(&path).map_err(|e| {
                         ConnectionError::InvalidConfig(format!(
@@ -163,16 +199,16 @@ impl KubernetesConnection {
                         ))
                     })?
             }
-            KubernetesAuth::InCluster => Config::incluster().map_err(|e| {
+            KubeAuth::InCluster => Config::incluster().map_err(|e| {
                 ConnectionError::InvalidConfig(format!("Failed to load in-cluster config: {}", e))
             })?,
-            KubernetesAuth::BearerToken(token) => {
+            KubeAuth::BearerToken(token) => {
                 // Start with default kubeconfig to get server URL
                 let mut config = Config::infer().await.map_err(|e| {
                     ConnectionError::InvalidConfig(format!("Failed to infer config: {}", e))
                 })?;
                 // Override with bearer token auth header
-                config.auth_info.token = Some(secrecy::SecretString::new(token));
+                config.auth_info.token = Some(token.into());
                 config
             }
         };
@@ -227,18 +263,12 @@ impl KubernetesConnection {
     }
 
     /// Build attach parameters for exec
-    fn build_attach_params(&self, options: &ExecuteOptions) -> AttachParams {
-        let mut params = AttachParams::default();
-
+    fn build_attach_params(&self, stdin: bool) -> AttachParams {
         // Always capture stdout and stderr
-        params = params.stdout(true).stderr(true);
-
-        // Enable stdin if we might need to send input (e.g., for password)
-        if options.escalate && options.escalate_password.is_some() {
-            params = params.stdin(true);
-        } else {
-            params = params.stdin(false);
-        }
+        let mut params = AttachParams::default()
+            .stdout(true)
+            .stderr(true)
+            .stdin(stdin);
 
         // Set container if specified
         if let Some(container) = &self.container {
@@ -248,13 +278,17 @@ impl KubernetesConnection {
         params
     }
 
-    /// Execute command and collect output
-    async fn execute_in_pod(
+    /// Run a command in the pod, feeding `input` to its stdin
+    ///
+    /// Returns the exit code with the raw output, which carries a tar archive
+    /// during downloads. Closing stdin tears down the whole exec stream, so
+    /// commands given input must stop reading on their own.
+    async fn exec(
         &self,
         command: Vec<String>,
-        options: &ExecuteOptions,
-    ) -> ConnectionResult<CommandResult> {
-        let attach_params = self.build_attach_params(options);
+        input: Option<&[u8]>,
+    ) -> ConnectionResult<(i32, Vec<u8>, Vec<u8>)> {
+        let attach_params = self.build_attach_params(input.is_some());
 
         debug!(
             pod = %self.pod,
@@ -265,68 +299,32 @@ impl KubernetesConnection {
 
         let mut attached: AttachedProcess = self
             .pods_api
-            .exec(
-                &self.pod,
-                command.iter().map(|s| s.as_str()),
-                &attach_params,
-            )
+            .exec(&self.pod, command, &attach_params)
             .await
             .map_err(|e| {
                 ConnectionError::ExecutionFailed(format!("Failed to exec in pod: {}", e))
             })?;
 
-        // Handle stdin for password escalation
-        if options.escalate && options.escalate_password.is_some() {
-            if let Some(mut stdin) = attached.stdin() {
-                let password = options.escalate_password.as_ref().unwrap();
-                stdin
-                    .write_all(format!("{}\n", password).as_bytes())
-                    .await
-                    .map_err(|e| {
-                        ConnectionError::ExecutionFailed(format!("Failed to write password: {}", e))
-                    })?;
-            }
-        }
-
-        // Collect stdout
-        let mut stdout_data = Vec::new();
-        if let Some(mut stdout) = attached.stdout() {
-            stdout.read_to_end(&mut stdout_data).await.map_err(|e| {
-                ConnectionError::ExecutionFailed(format!("Failed to read stdout: {}", e))
+        // Held until the command exits, see above
+        let mut stdin = attached.stdin();
+        if let (Some(input), Some(stdin)) = (input, stdin.as_mut()) {
+            stdin.write_all(input).await.map_err(|e| {
+                ConnectionError::ExecutionFailed(format!("Failed to write stdin: {}", e))
             })?;
         }
 
-        // Collect stderr
-        let mut stderr_data = Vec::new();
-        if let Some(mut stderr) = attached.stderr() {
-            stderr.read_to_end(&mut stderr_data).await.map_err(|e| {
-                ConnectionError::ExecutionFailed(format!("Failed to read stderr: {}", e))
-            })?;
-        }
+        // Read both streams at once so a full stderr pipe cannot stall stdout
+        let (stdout, stderr) = tokio::try_join!(
+            read_stream(attached.stdout()),
+            read_stream(attached.stderr())
+        )
+        .map_err(|e| ConnectionError::ExecutionFailed(format!("Failed to read output: {}", e)))?;
 
-        // Wait for process to complete and get exit status
-        let status = attached.take_status();
-        let exit_code = if let Some(status) = status {
-            match status.await {
-                Some(status) => {
-                    if status.status == Some("Success".to_string()) {
-                        0
-                    } else {
-                        status
-                            .reason
-                            .as_ref()
-                            .and_then(|r| r.parse::<i32>().ok())
-                            .unwrap_or(1)
-                    }
-                }
-                None => 0,
-            }
-        } else {
-            0
+        let exit_code = match attached.take_status() {
+            Some(status) => status.await.as_ref().map_or(0, exit_code),
+            None => 0,
         };
-
-        let stdout = String::from_utf8_lossy(&stdout_data).to_string();
-        let stderr = String::from_utf8_lossy(&stderr_data).to_string();
+        drop(stdin);
 
         trace!(
             exit_code = %exit_code,
@@ -335,6 +333,27 @@ impl KubernetesConnection {
             "Kubernetes exec completed"
         );
 
+        Ok((exit_code, stdout, stderr))
+    }
+
+    /// Execute command and collect output
+    async fn execute_in_pod(
+        &self,
+        command: Vec<String>,
+        options: &ExecuteOptions,
+    ) -> ConnectionResult<CommandResult> {
+        // Feed the password to `sudo -S`
+        let password = match (&options.escalate_password, options.escalate) {
+            (Some(password), true) => Some(format!("{}\n", password)),
+            _ => None,
+        };
+        let (exit_code, stdout, stderr) = self
+            .exec(command, password.as_deref().map(str::as_bytes))
+            .await?;
+
+        let stdout = String::from_utf8_lossy(&stdout).to_string();
+        let stderr = String::from_utf8_lossy(&stderr).to_string();
+
         if exit_code == 0 {
             Ok(CommandResult::success(stdout, stderr))
         } else {
@@ -342,6 +361,20 @@ impl KubernetesConnection {
         }
     }
 
+    /// Run a transfer helper through `sh -c`, failing on a non-zero exit
+    async fn transfer(&self, script: String, input: Option<&[u8]>) -> ConnectionResult<Vec<u8>> {
+        let command = vec!["sh".to_string(), "-c".to_string(), script];
+        let (exit_code, stdout, stderr) = self.exec(command, input).await?;
+        if exit_code != 0 {
+            return Err(ConnectionError::TransferFailed(format!(
+                "tar exited with {}: {}",
+                exit_code,
+                String::from_utf8_lossy(&stderr).trim()
+            )));
+        }
+        Ok(stdout)
+    }
+
     /// Build the full command with options
     fn build_command(&self, command: &str, options: &ExecuteOptions) -> Vec<String> {
         let mut parts = vec!["sh".to_string(), "-c".to_string()];
@@ -407,7 +440,7 @@ impl KubernetesConnection {
 }
 
 #[async_trait]
-impl Connection for KubernetesConnection {
+impl Connection for KubeConnection {
     fn identifier(&self) -> &str {
         &self.identifier
     }
@@ -485,32 +518,18 @@ impl Connection for KubernetesConnection {
             "Uploading content to Kubernetes pod"
         );
 
-        // Create parent directories if needed
-        if options.create_dirs {
-            if let Some(parent) = remote_path.parent() {
-                let mkdir_cmd = format!("mkdir -p '{}'", parent.display());
-                self.execute(&mkdir_cmd, None).await?;
-            }
-        }
-
-        // Base64 encode the content and decode it on the pod
-        // This avoids issues with binary data and shell escaping
-        let encoded = BASE64_STANDARD.encode(content);
+        let (dir, name) = split_remote_path(remote_path)?;
+        let archive = tar_archive(content, &name, options.mode.unwrap_or(0o644))?;
 
-        // Write using base64 decode
-        let write_cmd = format!(
-            "echo '{}' | base64 -d > '{}'",
-            encoded,
-            remote_path.display()
+        // The target directory is always created, and `head -c` ends the
+        // input at the archive size since stdin cannot be closed
+        let dir = shell_words::quote(&dir).into_owned();
+        let script = format!(
+            "mkdir -p {dir} && head -c {len} | tar -xf - -C {dir}",
+            dir = dir,
+            len = archive.len()
         );
-        let result = self.execute(&write_cmd, None).await?;
-
-        if !result.success {
-            return Err(ConnectionError::TransferFailed(format!(
-                "Failed to write file: {}",
-                result.stderr
-            )));
-        }
+        self.transfer(script, Some(&archive)).await?;
 
         // Set permissions if specified
         if let Some(mode) = options.mode {
@@ -565,23 +584,14 @@ impl Connection for KubernetesConnection {
             "Downloading content from Kubernetes pod"
         );
 
-        // Use base64 to safely transfer binary content
-        let command = format!("base64 '{}'", remote_path.display());
-        let result = self.execute(&command, None).await?;
-
-        if !result.success {
-            return Err(ConnectionError::TransferFailed(format!(
-                "Failed to read file: {}",
-                result.stderr
-            )));
-        }
-
-        // Decode base64 content
-        let decoded = BASE64_STANDARD.decode(result.stdout.trim()).map_err(|e| {
-            ConnectionError::TransferFailed(format!("Failed to decode content: {}", e))
-        })?;
-
-        Ok(decoded)
+        let (dir, name) = split_remote_path(remote_path)?;
+        let script = format!(
+            "tar -cf - -C {} {}",
+            shell_words::quote(&dir),
+            shell_words::quote(&name)
+        );
+        let archive = self.transfer(script, None).await?;
+        untar_file(&archive)
     }
 
     async fn path_exists(&self, path: &Path) -> ConnectionResult<bool> {
@@ -635,6 +645,94 @@ impl Connection for KubernetesConnection {
         // The client can be reused
         Ok(())
     }
+
+    fn supports_atomic_rename(&self) -> bool {
+        true
+    }
+}
+
+/// Read an exec output stream to the end, if it was attached
+async fn read_stream(stream: Option<impl AsyncRead + Unpin>) -> std::io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    if let Some(mut stream) = stream {
+        stream.read_to_end(&mut data).await?;
+    }
+    Ok(data)
+}
+
+/// Exit code reported by the exec status
+///
+/// A failing command reports `NonZeroExitCode` with the code in an
+/// `ExitCode` cause.
+fn exit_code(status: &Status) -> i32 {
+    if status.status.as_deref() == Some("Success") {
+        return 0;
+    }
+    status
+        .details
+        .as_ref()
+        .and_then(|details| details.causes.as_ref())
+        .and_then(|causes| {
+            causes
+                .iter()
+                .find(|cause| cause.reason.as_deref() == Some("ExitCode"))
+        })
+        .and_then(|cause| cause.message.as_ref())
+        .and_then(|code| code.parse().ok())
+        .unwrap_or(1)
+}
+
+/// Split a remote file path into its directory and file name
+fn split_remote_path(path: &Path) -> ConnectionResult<(String, String)> {
+    let name = path.file_name().ok_or_else(|| {
+        ConnectionError::TransferFailed(format!("Not a file path: {}", path.display()))
+    })?;
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    Ok((
+        dir.to_string_lossy().into_owned(),
+        name.to_string_lossy().into_owned(),
+    ))
+}
+
+/// Build a tar archive holding a single file
+fn tar_archive(content: &[u8], name: &str, mode: u32) -> ConnectionResult<Vec<u8>> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(mode);
+    header.set_mtime(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    );
+
+    let mut builder = tar::Builder::new(Vec::new());
+    builder
+        .append_data(&mut header, name, content)
+        .and_then(|_| builder.into_inner())
+        .map_err(|e| ConnectionError::TransferFailed(format!("Failed to build archive: {}", e)))
+}
+
+/// Extract the first regular file from a tar archive
+fn untar_file(archive: &[u8]) -> ConnectionResult<Vec<u8>> {
+    let invalid =
+        |e: std::io::Error| ConnectionError::TransferFailed(format!("Invalid archive: {}", e));
+
+    let mut archive = tar::Archive::new(archive);
+    for entry in archive.entries().map_err(invalid)? {
+        let mut entry = entry.map_err(invalid)?;
+        if entry.header().entry_type().is_file() {
+            let mut content = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut content).map_err(invalid)?;
+            return Ok(content);
+        }
+    }
+    Err(ConnectionError::TransferFailed(
+        "Archive holds no regular file".to_string(),
+    ))
 }
 
 /// Pod information
@@ -654,16 +752,16 @@ pub struct PodInfo {
 
 /// Builder for Kubernetes connections
 #[derive(Debug, Clone, Default)]
-pub struct KubernetesConnectionBuilder {
+pub struct KubeConnectionBuilder {
     namespace: Option<String>,
     pod: Option<String>,
     container: Option<String>,
-    auth: Option<KubernetesAuth>,
+    auth: Option<KubeAuth>,
     kubeconfig_path: Option<PathBuf>,
     context: Option<String>,
 }
 
-impl KubernetesConnectionBuilder {
+impl KubeConnectionBuilder {
     /// Create a new builder
     pub fn new() -> Self {
         Self::default()
@@ -701,35 +799,35 @@ impl KubernetesConnectionBuilder {
 
     /// Use in-cluster service account authentication
     pub fn in_cluster(mut self) -> Self {
-        self.auth = Some(KubernetesAuth::InCluster);
+        self.auth = Some(KubeAuth::InCluster);
         self
     }
 
     /// Use bearer token authentication
     pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
-        self.auth = Some(KubernetesAuth::BearerToken(token.into()));
+        self.auth = Some(KubeAuth::BearerToken(token.into()));
         self
     }
 
     /// Build the connection
-    pub async fn build(self) -> ConnectionResult<KubernetesConnection> {
+    pub async fn build(self) -> ConnectionResult<KubeConnection> {
         let namespace = self.namespace.unwrap_or_else(|| "default".to_string());
         let pod = self
             .pod
             .ok_or_else(|| ConnectionError::InvalidConfig("Pod name is required".to_string()))?;
 
-        let auth = self.auth.unwrap_or_else(|| KubernetesAuth::Kubeconfig {
+        let auth = self.auth.unwrap_or_else(|| KubeAuth::Kubeconfig {
             path: self.kubeconfig_path,
             context: self.context,
         });
 
-        KubernetesConnection::new(namespace, pod, self.container, auth).await
+        KubeConnection::new(namespace, pod, self.container, auth).await
     }
 }
 
 /// List pods in a namespace
-pub async fn list_pods(namespace: &str, auth: KubernetesAuth) -> ConnectionResult<Vec<PodInfo>> {
-    let client = KubernetesConnection::create_client(auth).await?;
+pub async fn list_pods(namespace: &str, auth: KubeAuth) -> ConnectionResult<Vec<PodInfo>> {
+    let client = KubeConnection::create_client(auth).await?;
     let pods_api: Api<Pod> = Api::namespaced(client, namespace);
 
     let pods = pods_api
@@ -774,10 +872,10 @@ pub async fn list_pods(namespace: &str, auth: KubernetesAuth) -> ConnectionResul
 }
 
 /// List namespaces
-pub async fn list_namespaces(auth: KubernetesAuth) -> ConnectionResult<Vec<String>> {
+pub async fn list_namespaces(auth: KubeAuth) -> ConnectionResult<Vec<String>> {
     use k8s_openapi::api::core::v1::Namespace;
 
-    let client = KubernetesConnection::create_client(auth).await?;
+    let client = KubeConnection::create_client(auth).await?;
     let ns_api: Api<Namespace> = Api::all(client);
 
     let namespaces = ns_api.list(&ListParams::default()).await.map_err(|e| {
@@ -797,9 +895,9 @@ mod tests {
 
     #[test]
     fn test_kubernetes_auth_default() {
-        let auth = KubernetesAuth::default();
+        let auth = KubeAuth::default();
         match auth {
-            KubernetesAuth::Kubeconfig { path, context } => {
+            KubeAuth::Kubeconfig { path, context } => {
                 assert!(path.is_none());
                 assert!(context.is_none());
             }
@@ -811,7 +909,7 @@ mod tests {
     fn test_builder_missing_pod() {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
-            let result = KubernetesConnectionBuilder::new()
+            let result = KubeConnectionBuilder::new()
                 .namespace("default")
                 .build()
                 .await;
@@ -828,7 +926,7 @@ mod tests {
 
     #[test]
     fn test_builder_chain() {
-        let builder = KubernetesConnectionBuilder::new()
+        let builder = KubeConnectionBuilder::new()
             .namespace("my-namespace")
             .pod("my-pod")
             .container("app")
@@ -847,24 +945,22 @@ mod tests {
 
     #[test]
     fn test_builder_in_cluster() {
-        let builder = KubernetesConnectionBuilder::new()
-            .pod("my-pod")
-            .in_cluster();
+        let builder = KubeConnectionBuilder::new().pod("my-pod").in_cluster();
 
         match builder.auth {
-            Some(KubernetesAuth::InCluster) => {}
+            Some(KubeAuth::InCluster) => {}
             _ => panic!("Expected InCluster auth"),
         }
     }
 
     #[test]
     fn test_builder_bearer_token() {
-        let builder = KubernetesConnectionBuilder::new()
+        let builder = KubeConnectionBuilder::new()
             .pod("my-pod")
             .bearer_token("my-token");
 
         match builder.auth {
-            Some(KubernetesAuth::BearerToken(token)) => {
+            Some(KubeAuth::BearerToken(token)) => {
                 assert_eq!(token, "my-token");
             }
             _ => panic!("Expected BearerToken auth"),
@@ -872,9 +968,74 @@ mod tests {
     }
 
     #[test]
-    fn test_build_command_basic() {
-        // We need a mock connection for this test
-        // For now, we just verify the builder compiles
+    fn test_exit_code_from_status() {
+        use k8s_openapi::apimachinery::pkg::apis::meta::v1::{StatusCause, StatusDetails};
+
+        let success = Status {
+            status: Some("Success".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(exit_code(&success), 0);
+
+        let failure = Status {
+            status: Some("Failure".to_string()),
+            reason: Some("NonZeroExitCode".to_string()),
+            details: Some(StatusDetails {
+                causes: Some(vec![StatusCause {
+                    reason: Some("ExitCode".to_string()),
+                    message: Some("3".to_string()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(exit_code(&failure), 3);
+
+        // Errors without an exit code still fail
+        let error = Status {
+            status: Some("Failure".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(exit_code(&error), 1);
+    }
+
+    #[test]
+    fn test_tar_round_trip() {
+        let content = b"\x00binary\ncontent\xff";
+        let archive = tar_archive(content, "app.conf", 0o600).unwrap();
+        assert_eq!(untar_file(&archive).unwrap(), content);
+
+        assert!(untar_file(&tar_archive(b"", "empty", 0o644).unwrap())
+            .unwrap()
+            .is_empty());
+        assert!(untar_file(b"not an archive").is_err());
+    }
+
+    #[test]
+    fn test_split_remote_path() {
+        assert_eq!(
+            split_remote_path(Path::new("/etc/app/app.conf")).unwrap(),
+            ("/etc/app".to_string(), "app.conf".to_string())
+        );
+        assert_eq!(
+            split_remote_path(Path::new("app.conf")).unwrap(),
+            (".".to_string(), "app.conf".to_string())
+        );
+        assert!(split_remote_path(Path::new("/")).is_err());
+    }
+
+    #[test]
+    fn test_resolve_kubeconfig_prefers_host_setting() {
+        assert_eq!(
+            resolve_kubeconfig(Some("/etc/kube/staging")),
+            Some(PathBuf::from("/etc/kube/staging"))
+        );
+        let home = std::env::var("HOME").unwrap_or_default();
+        assert_eq!(
+            resolve_kubeconfig(Some("~/.kube/staging")),
+            Some(PathBuf::from(home).join(".kube/staging"))
+        );
     }
 
     #[test]
@@ -85,6 +85,12 @@ impl LocalConnection {
             c
         };
 
+        Self::configure_command(&mut cmd, options);
+        cmd
+    }
+
+    /// Apply the working directory, environment and stdio setup
+    fn configure_command(cmd: &mut Command, options: &ExecuteOptions) {
         // Set working directory
         if let Some(cwd) = &options.cwd {
             cmd.current_dir(cwd);
@@ -99,38 +105,14 @@ impl LocalConnection {
         cmd.stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
-
-        cmd
-    }
-}
-
-impl Default for LocalConnection {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-#[async_trait]
-impl Connection for LocalConnection {
-    fn identifier(&self) -> &str {
-        &self.identifier
     }
 
-    async fn is_alive(&self) -> bool {
-        // Local connection is always alive
-        true
-    }
-
-    async fn execute(
+    /// Spawn a prepared command and collect its result
+    async fn run(
         &self,
-        command: &str,
-        options: Option<ExecuteOptions>,
+        mut cmd: Command,
+        options: &ExecuteOptions,
     ) -> ConnectionResult<CommandResult> {
-        let options = options.unwrap_or_default();
-        debug!(command = %command, "Executing local command");
-
-        let mut cmd = self.build_command(command, &options);
-
         // Spawn the process
         let mut child = cmd.spawn().map_err(|e| {
             ConnectionError::ExecutionFailed(format!("Failed to spawn process: {}", e))
@@ -180,6 +162,65 @@ impl Connection for LocalConnection {
             Ok(CommandResult::failure(exit_code, stdout, stderr))
         }
     }
+}
+
+impl Default for LocalConnection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Connection for LocalConnection {
+    fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    async fn is_alive(&self) -> bool {
+        // Local connection is always alive
+        true
+    }
+
+    async fn execute(
+        &self,
+        command: &str,
+        options: Option<ExecuteOptions>,
+    ) -> ConnectionResult<CommandResult> {
+        let options = options.unwrap_or_default();
+        debug!(command = %command, "Executing local command");
+
+        let cmd = self.build_command(command, &options);
+        self.run(cmd, &options).await
+    }
+
+    async fn execute_argv(
+        &self,
+        argv: &[String],
+        options: Option<ExecuteOptions>,
+    ) -> ConnectionResult<CommandResult> {
+        let options = options.unwrap_or_default();
+        let Some((program, args)) = argv.split_first() else {
+            return Err(ConnectionError::ExecutionFailed(
+                "argv cannot be empty".to_string(),
+            ));
+        };
+
+        // Escalation goes through a shell, so quote the arguments for it
+        if options.escalate {
+            let command = argv
+                .iter()
+                .map(|arg| shell_words::quote(arg))
+                .collect::<Vec<_>>()
+                .join(" ");
+            return self.execute(&command, Some(options)).await;
+        }
+
+        debug!(argv = ?argv, "Executing local program");
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        Self::configure_command(&mut cmd, &options);
+        self.run(cmd, &options).await
+    }
 
     async fn upload(
         &self,
@@ -364,6 +405,10 @@ impl Connection for LocalConnection {
         // Nothing to close for local connection
         Ok(())
     }
+
+    fn supports_atomic_rename(&self) -> bool {
+        true
+    }
 }
 
 impl LocalConnection {
@@ -463,6 +508,19 @@ mod tests {
         assert!(result.stdout.contains("/tmp"));
     }
 
+    #[tokio::test]
+    async fn test_local_execute_argv_skips_shell() {
+        let conn = LocalConnection::new();
+        let argv: Vec<String> = ["printf", "%s|", "a b", "$HOME"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let result = conn.execute_argv(&argv, None).await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.stdout, "a b|$HOME|");
+    }
+
     #[tokio::test]
     async fn test_local_execute_failure() {
         let conn = LocalConnection::new();
@@ -16,6 +16,7 @@
 //!   - libssh2 bindings (`ssh2-backend` feature)
 //! - **Local**: Direct execution on the control node
 //! - **Docker**: Container-based execution via `docker exec`
+//! - **WinRM**: PowerShell over WS-Management for Windows hosts (`winrm` feature)
 //!
 //! # Connection Management
 //!
@@ -53,6 +54,9 @@ pub mod config;
 /// Docker container connection implementation.
 pub mod docker;
 
+/// Podman container connection implementation.
+pub mod podman;
+
 /// Local execution connection implementation.
 pub mod local;
 
@@ -88,6 +92,15 @@ pub mod jump_host;
 /// Robust retry logic with exponential backoff.
 pub mod retry;
 
+/// Atomic file replacement on the target.
+pub mod atomic;
+
+/// Per-run remote temp directory for module staging.
+pub mod remote_tmp;
+
+/// SFTP/SCP transfer method selection.
+pub mod transfer;
+
 /// SSH Agent forwarding support.
 #[cfg(feature = "russh")]
 pub mod ssh_agent;
@@ -101,7 +114,7 @@ pub mod winrm;
 
 /// Kubernetes pod connection support.
 #[cfg(feature = "kubernetes")]
-pub mod kubernetes;
+pub mod kube;
 
 use async_trait::async_trait;
 use parking_lot::RwLock;
@@ -155,6 +168,12 @@ pub use jump_host::{JumpHostChain, JumpHostConfig, JumpHostResolver, MAX_JUMP_DE
 // Re-export retry types
 pub use retry::{BackoffStrategy, RetryPolicy, RetryResult, RetryStats};
 
+// Re-export remote temp directory types
+pub use remote_tmp::{RemoteIdCache, RemoteTmp, RemoteTmpConnection};
+
+// Re-export transfer method types
+pub use transfer::{TransferMethod, TransferStats};
+
 // Re-export SSH agent types (feature-gated)
 #[cfg(feature = "russh")]
 pub use ssh_agent::{
@@ -227,6 +246,10 @@ pub enum ConnectionError {
     #[error("File transfer failed: {0}")]
     TransferFailed(String),
 
+    /// The server refused the SFTP subsystem.
+    #[error("SFTP subsystem request failed: {0}")]
+    SftpUnavailable(String),
+
     /// Connection or operation timed out.
     #[error("Connection timeout after {0} seconds")]
     Timeout(u64),
@@ -273,6 +296,55 @@ pub enum ConnectionError {
 /// A type alias for `Result<T, ConnectionError>`.
 pub type ConnectionResult<T> = Result<T, ConnectionError>;
 
+impl ConnectionError {
+    /// Whether this error means the host itself could not be reached.
+    ///
+    /// Tasks failing with such an error are reported as unreachable rather
+    /// than failed. Authentication failures count as unreachable too.
+    pub fn is_unreachable(&self) -> bool {
+        matches!(
+            self,
+            ConnectionError::ConnectionFailed(_)
+                | ConnectionError::AuthenticationFailed(_)
+                | ConnectionError::Timeout(_)
+                | ConnectionError::HostNotFound(_)
+                | ConnectionError::ConnectionClosed
+        )
+    }
+
+    /// Whether retrying the operation cannot change the outcome.
+    ///
+    /// A rejected key or password is rejected again on every attempt, so
+    /// these errors are surfaced immediately instead of going through the
+    /// connection retry loop. Network errors are not fail-fast.
+    pub fn is_fail_fast(&self) -> bool {
+        matches!(
+            self,
+            ConnectionError::AuthenticationFailed(_) | ConnectionError::InvalidConfig(_)
+        )
+    }
+
+    /// Add the user and key that were rejected to an authentication error.
+    ///
+    /// Other errors are returned unchanged.
+    pub fn with_auth_context(self, user: &str, identity_file: Option<&str>) -> Self {
+        match self {
+            ConnectionError::AuthenticationFailed(msg) => {
+                let key = match identity_file {
+                    Some(key) => format!("key '{}'", key),
+                    None => "the default keys and agent".to_string(),
+                };
+                ConnectionError::AuthenticationFailed(format!(
+                    "{} (user '{}', {}); check that the key is authorized for this user \
+                     on the host, or set ansible_user / ansible_ssh_private_key_file",
+                    msg, user, key
+                ))
+            }
+            other => other,
+        }
+    }
+}
+
 /// The result of executing a command on a connection.
 ///
 /// Contains the exit code, stdout, stderr, and a convenience boolean
@@ -384,6 +456,26 @@ impl ExecuteOptions {
         self.escalate_user = user;
         self
     }
+
+    /// Wrap a command for privilege escalation
+    ///
+    /// Returns the command unchanged unless `escalate` is set. Shell-based
+    /// connections run the result instead of the bare command.
+    pub fn become_command(&self, command: &str) -> String {
+        if !self.escalate {
+            return command.to_string();
+        }
+
+        let user = self.escalate_user.as_deref().unwrap_or("root");
+        match self.escalate_method.as_deref().unwrap_or("sudo") {
+            "su" => format!("su - {} -c {}", user, command),
+            "doas" => format!("doas -u {} {}", user, command),
+            _ if self.escalate_password.is_some() => {
+                format!("sudo -S -u {} -- {}", user, command)
+            }
+            _ => format!("sudo -u {} -- {}", user, command),
+        }
+    }
 }
 
 /// Options for file transfer
@@ -399,6 +491,9 @@ pub struct TransferOptions {
     pub create_dirs: bool,
     /// Backup existing file before overwriting
     pub backup: bool,
+    /// Keep mode and ownership as the transport's archive mode does
+    /// (`podman cp --archive` for Podman)
+    pub preserve_mode: bool,
 }
 
 impl TransferOptions {
@@ -430,6 +525,12 @@ impl TransferOptions {
         self.create_dirs = true;
         self
     }
+
+    /// Enable the transport's archive mode
+    pub fn with_preserve_mode(mut self) -> Self {
+        self.preserve_mode = true;
+        self
+    }
 }
 
 /// The main connection trait that all transport implementations must implement
@@ -448,6 +549,30 @@ pub trait Connection: Send + Sync {
         options: Option<ExecuteOptions>,
     ) -> ConnectionResult<CommandResult>;
 
+    /// Execute a program with an argument vector, without shell parsing
+    ///
+    /// Arguments are passed verbatim, so one containing spaces stays a single
+    /// argument. The default quotes each argument and runs the result with
+    /// [`Connection::execute`]; transports that can spawn processes directly
+    /// override it.
+    async fn execute_argv(
+        &self,
+        argv: &[String],
+        options: Option<ExecuteOptions>,
+    ) -> ConnectionResult<CommandResult> {
+        if argv.is_empty() {
+            return Err(ConnectionError::ExecutionFailed(
+                "argv cannot be empty".to_string(),
+            ));
+        }
+        let command = argv
+            .iter()
+            .map(|arg| shell_words::quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.execute(&command, options).await
+    }
+
     /// Upload a file to the remote host
     async fn upload(
         &self,
@@ -499,6 +624,23 @@ pub trait Connection: Send + Sync {
         }
         results
     }
+
+    /// Managed temp directory for staging module files, if this connection has one
+    fn remote_tmp(&self) -> Option<&RemoteTmp> {
+        None
+    }
+
+    /// Cache of user and group ids resolved on the host, if this connection keeps one
+    fn remote_ids(&self) -> Option<&RemoteIdCache> {
+        None
+    }
+
+    /// Whether files can be replaced with a POSIX `sync` and `mv` on the target
+    ///
+    /// See [`atomic`] for how this is used.
+    fn supports_atomic_rename(&self) -> bool {
+        false
+    }
 }
 
 /// File statistics
@@ -537,11 +679,27 @@ pub enum ConnectionType {
     },
     /// Docker container connection
     Docker { container: String },
+    /// Podman container connection
+    Podman {
+        container: String,
+        /// Podman binary (None = `podman` from PATH)
+        executable: Option<String>,
+    },
     /// Kubernetes pod connection
     Kubernetes {
         namespace: String,
         pod: String,
         container: Option<String>,
+        /// Kubeconfig file (None = `K8S_AUTH_KUBECONFIG` or the default lookup)
+        kubeconfig: Option<String>,
+        /// Kubeconfig context (None = current context)
+        context: Option<String>,
+    },
+    /// WinRM connection to a Windows host
+    WinRm {
+        host: String,
+        port: u16,
+        user: String,
     },
 }
 
@@ -552,10 +710,12 @@ impl ConnectionType {
             ConnectionType::Local => "local".to_string(),
             ConnectionType::Ssh { host, port, user } => format!("ssh://{}@{}:{}", user, host, port),
             ConnectionType::Docker { container } => format!("docker://{}", container),
+            ConnectionType::Podman { container, .. } => format!("podman://{}", container),
             ConnectionType::Kubernetes {
                 namespace,
                 pod,
                 container,
+                ..
             } => {
                 if let Some(c) = container {
                     format!("k8s://{}/{}:{}", namespace, pod, c)
@@ -563,10 +723,87 @@ impl ConnectionType {
                     format!("k8s://{}/{}", namespace, pod)
                 }
             }
+            ConnectionType::WinRm { host, port, user } => {
+                format!("winrm://{}@{}:{}", user, host, port)
+            }
         }
     }
 }
 
+/// Authentication identity used when requesting a pooled connection.
+///
+/// Unset fields fall back to the host's configuration. Every field is part of
+/// the pool key, so requests made with different credentials never share a
+/// pooled connection.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ConnectionIdentity {
+    /// Remote user to authenticate as
+    pub user: Option<String>,
+    /// Port to connect to
+    pub port: Option<u16>,
+    /// Private key file used for authentication
+    pub identity_file: Option<String>,
+    /// User that commands on this connection escalate to
+    pub become_user: Option<String>,
+}
+
+impl ConnectionIdentity {
+    /// Create an identity that uses the host's configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the remote user
+    pub fn with_user(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Set the port
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Set the private key file
+    pub fn with_identity_file(mut self, identity_file: impl Into<String>) -> Self {
+        self.identity_file = Some(identity_file.into());
+        self
+    }
+
+    /// Set the escalation user
+    pub fn with_become_user(mut self, become_user: impl Into<String>) -> Self {
+        self.become_user = Some(become_user.into());
+        self
+    }
+
+    /// Get the pool key for a connection to `target` made with this identity
+    pub fn pool_key(&self, target: &ConnectionType) -> String {
+        let mut key = target.pool_key();
+
+        // SSH and WinRM targets already carry the resolved user and port
+        if !matches!(
+            target,
+            ConnectionType::Ssh { .. } | ConnectionType::WinRm { .. }
+        ) {
+            if let Some(ref user) = self.user {
+                key.push_str(&format!(";user={}", user));
+            }
+            if let Some(port) = self.port {
+                key.push_str(&format!(";port={}", port));
+            }
+        }
+        if let Some(ref identity_file) = self.identity_file {
+            key.push_str(&format!(";key={}", identity_file));
+        }
+        if let Some(ref become_user) = self.become_user {
+            key.push_str(&format!(";become={}", become_user));
+        }
+
+        key
+    }
+}
+
 /// Factory for creating connections
 #[derive(Clone)]
 pub struct ConnectionFactory {
@@ -574,6 +811,10 @@ pub struct ConnectionFactory {
     config: Arc<ConnectionConfig>,
     /// Connection pool
     pool: Arc<RwLock<ConnectionPool>>,
+    /// Run identifier naming each connection's remote temp directory
+    run_id: Arc<str>,
+    /// Connection type forced for every host (`local`, `ssh` or `docker`)
+    connection_override: Option<String>,
 }
 
 impl ConnectionFactory {
@@ -582,6 +823,8 @@ impl ConnectionFactory {
         Self {
             config: Arc::new(config),
             pool: Arc::new(RwLock::new(ConnectionPool::new(10))), // Default pool size of 10
+            run_id: new_run_id(),
+            connection_override: None,
         }
     }
 
@@ -590,26 +833,59 @@ impl ConnectionFactory {
         Self {
             config: Arc::new(config),
             pool: Arc::new(RwLock::new(ConnectionPool::new(pool_size))),
+            run_id: new_run_id(),
+            connection_override: None,
         }
     }
 
+    /// Force a connection type for every host, regardless of host configuration
+    ///
+    /// Accepts `local`, `ssh` or `docker`; takes priority over both the host's
+    /// `connection` setting and the `localhost`/`docker://` name detection.
+    pub fn with_connection_override(mut self, connection: impl Into<String>) -> Self {
+        self.connection_override = Some(connection.into());
+        self
+    }
+
+    /// Run identifier used for remote temp directories
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
     /// Get a connection for a host
     pub async fn get_connection(
         &self,
         host: &str,
     ) -> ConnectionResult<Arc<dyn Connection + Send + Sync>> {
-        let conn_type = self.resolve_connection_type(host)?;
-        let pool_key = conn_type.pool_key();
+        self.get_connection_as(host, &ConnectionIdentity::default())
+            .await
+    }
+
+    /// Get a connection for a host using a specific authentication identity
+    ///
+    /// Connections are pooled per host and identity, so a connection opened
+    /// for one user or key is never handed out for another.
+    pub async fn get_connection_as(
+        &self,
+        host: &str,
+        identity: &ConnectionIdentity,
+    ) -> ConnectionResult<Arc<dyn Connection + Send + Sync>> {
+        let conn_type = self.resolve_connection_type(host, identity)?;
+        let pool_key = identity.pool_key(&conn_type);
 
         // Try to get from pool first
-        if let Some(conn) = self.pool.write().get(&pool_key) {
+        let pooled = self.pool.write().get(&pool_key);
+        if let Some(conn) = pooled {
             if conn.is_alive().await {
                 return Ok(conn);
             }
         }
 
-        // Create new connection
-        let conn = self.create_connection(&conn_type).await?;
+        // Create new connection, removing its temp directory when it closes
+        let conn: Arc<dyn Connection + Send + Sync> = Arc::new(RemoteTmpConnection::new(
+            self.create_connection(host, &conn_type, identity).await?,
+            RemoteTmp::new(self.run_id.as_ref()),
+        ));
 
         // Add to pool
         self.pool.write().put(pool_key, conn.clone());
@@ -618,7 +894,64 @@ impl ConnectionFactory {
     }
 
     /// Resolve a host name to a connection type
-    fn resolve_connection_type(&self, host: &str) -> ConnectionResult<ConnectionType> {
+    fn resolve_connection_type(
+        &self,
+        host: &str,
+        identity: &ConnectionIdentity,
+    ) -> ConnectionResult<ConnectionType> {
+        match self.connection_override.as_deref() {
+            Some("local") => return Ok(ConnectionType::Local),
+            Some("docker") => {
+                let container = host.strip_prefix("docker://").unwrap_or(host).to_string();
+                return Ok(ConnectionType::Docker { container });
+            }
+            Some("ssh") => return Ok(self.resolve_ssh(host, identity)),
+            Some(other) => {
+                return Err(ConnectionError::InvalidConfig(format!(
+                    "Unsupported connection override '{}'",
+                    other
+                )))
+            }
+            None => {}
+        }
+
+        if let Some(hc) = self.config.get_host(host) {
+            if hc.connection.as_deref() == Some("winrm") {
+                let user = hc
+                    .user
+                    .clone()
+                    .unwrap_or_else(|| self.config.defaults.user.clone());
+                return Ok(ConnectionType::WinRm {
+                    host: hc.hostname.clone().unwrap_or_else(|| host.to_string()),
+                    // Like Ansible, default to the HTTPS listener
+                    port: identity.port.or(hc.port).unwrap_or(5986),
+                    user: identity.user.clone().unwrap_or(user),
+                });
+            }
+            if hc.connection.as_deref() == Some("podman") {
+                return Ok(ConnectionType::Podman {
+                    container: hc.hostname.clone().unwrap_or_else(|| host.to_string()),
+                    executable: hc.podman_executable.clone(),
+                });
+            }
+            if hc.connection.as_deref() == Some("kubectl") {
+                return Ok(ConnectionType::Kubernetes {
+                    namespace: hc
+                        .kube_namespace
+                        .clone()
+                        .unwrap_or_else(|| "default".to_string()),
+                    pod: hc
+                        .kube_pod
+                        .clone()
+                        .or_else(|| hc.hostname.clone())
+                        .unwrap_or_else(|| host.to_string()),
+                    container: hc.kube_container.clone(),
+                    kubeconfig: hc.kube_config.clone(),
+                    context: hc.kube_context.clone(),
+                });
+            }
+        }
+
         // Check for special connection types
         if host == "localhost" || host == "127.0.0.1" || host == "local" {
             // Check if we should use local connection
@@ -638,6 +971,11 @@ impl ConnectionFactory {
         }
 
         // Default to SSH
+        Ok(self.resolve_ssh(host, identity))
+    }
+
+    /// Resolve the SSH target for a host from its configuration
+    fn resolve_ssh(&self, host: &str, identity: &ConnectionIdentity) -> ConnectionType {
         let host_config = self.config.get_host(host);
         let (actual_host, port, user) = if let Some(hc) = host_config {
             (
@@ -651,17 +989,22 @@ impl ConnectionFactory {
             (host.to_string(), 22, self.config.defaults.user.clone())
         };
 
-        Ok(ConnectionType::Ssh {
+        ConnectionType::Ssh {
             host: actual_host,
-            port,
-            user,
-        })
+            port: identity.port.unwrap_or(port),
+            user: identity.user.clone().unwrap_or(user),
+        }
     }
 
     /// Create a new connection based on type
+    ///
+    /// SSH hosts with a `proxy_jump` have their whole jump chain resolved
+    /// from `name`, the inventory host name, before the target is dialled.
     async fn create_connection(
         &self,
+        name: &str,
         conn_type: &ConnectionType,
+        identity: &ConnectionIdentity,
     ) -> ConnectionResult<Arc<dyn Connection + Send + Sync>> {
         match conn_type {
             ConnectionType::Local => {
@@ -669,22 +1012,35 @@ impl ConnectionFactory {
                 Ok(Arc::new(conn))
             }
             ConnectionType::Ssh { host, port, user } => {
-                let host_config = self.config.get_host(host).cloned();
+                let mut host_config = self.config.get_host(host).cloned();
+                if let Some(ref identity_file) = identity.identity_file {
+                    host_config
+                        .get_or_insert_with(HostConfig::default)
+                        .identity_file = Some(identity_file.clone());
+                }
+                let jumps = jump_host::JumpHostResolver::new(&self.config).resolve(name)?;
                 // Prefer russh (pure Rust) when available, fall back to ssh2
                 #[cfg(feature = "russh")]
                 {
-                    let conn = russh::RusshConnection::connect(
+                    let conn = russh::RusshConnection::connect_through(
                         host,
                         *port,
                         user,
                         host_config,
                         &self.config,
+                        &jumps,
                     )
                     .await?;
                     Ok(Arc::new(conn))
                 }
                 #[cfg(all(feature = "ssh2-backend", not(feature = "russh")))]
                 {
+                    if !jumps.is_empty() {
+                        return Err(ConnectionError::InvalidConfig(format!(
+                            "ProxyJump through {} requires the russh backend",
+                            jumps
+                        )));
+                    }
                     let conn =
                         ssh::SshConnection::connect(host, *port, user, host_config, &self.config)
                             .await?;
@@ -702,31 +1058,64 @@ impl ConnectionFactory {
                 let conn = docker::DockerConnection::new(container.clone());
                 Ok(Arc::new(conn))
             }
+            ConnectionType::Podman {
+                container,
+                executable,
+            } => {
+                let conn = match executable {
+                    Some(path) => podman::PodmanConnection::with_podman_path(container, path),
+                    None => podman::PodmanConnection::new(container),
+                };
+                Ok(Arc::new(conn))
+            }
             ConnectionType::Kubernetes {
                 namespace,
                 pod,
                 container,
+                kubeconfig,
+                context,
             } => {
                 // Kubernetes connection requires the kubernetes feature
                 #[cfg(feature = "kubernetes")]
                 {
-                    let conn = kubernetes::KubernetesConnection::new(
+                    let auth = kube::KubeAuth::Kubeconfig {
+                        path: kube::resolve_kubeconfig(kubeconfig.as_deref()),
+                        context: context.clone(),
+                    };
+                    let conn = kube::KubeConnection::new(
                         namespace.clone(),
                         pod.clone(),
                         container.clone(),
+                        auth,
                     )
                     .await?;
                     Ok(Arc::new(conn))
                 }
                 #[cfg(not(feature = "kubernetes"))]
                 {
-                    let _ = (namespace, pod, container);
+                    let _ = (namespace, pod, container, kubeconfig, context);
                     Err(ConnectionError::InvalidConfig(
                         "Kubernetes support not available. Enable 'kubernetes' feature."
                             .to_string(),
                     ))
                 }
             }
+            ConnectionType::WinRm { host, port, user } => {
+                #[cfg(feature = "winrm")]
+                {
+                    let host_config = self.config.get_host_merged(host);
+                    let config = winrm::WinRmConfig::for_host(host, *port, user, &host_config)?;
+                    let conn = winrm::WinRmConnection::connect(config).await?;
+                    Ok(Arc::new(conn))
+                }
+                #[cfg(not(feature = "winrm"))]
+                {
+                    let _ = (host, port, user);
+                    Err(ConnectionError::InvalidConfig(
+                        "WinRM support not available. Enable 'winrm' feature.".to_string(),
+                    ))
+                }
+            }
         }
     }
 
@@ -750,6 +1139,12 @@ impl ConnectionFactory {
     }
 }
 
+/// Generate a short identifier for one run's remote temp directories
+fn new_run_id() -> Arc<str> {
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    Arc::from(&id[..12])
+}
+
 /// Connection pool for reusing connections
 pub struct ConnectionPool {
     /// Maximum number of connections per host
@@ -803,6 +1198,31 @@ impl ConnectionPool {
     }
 }
 
+impl Drop for ConnectionPool {
+    fn drop(&mut self) {
+        close_detached(self.drain());
+    }
+}
+
+/// Close connections without waiting for them, for use where awaiting is not
+/// possible (e.g. in `Drop`).
+///
+/// Inside a tokio runtime the connections are closed on a background task;
+/// otherwise they are simply dropped. Closing is best-effort and errors are
+/// ignored.
+pub fn close_detached(connections: Vec<Arc<dyn Connection + Send + Sync>>) {
+    if connections.is_empty() {
+        return;
+    }
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        handle.spawn(async move {
+            for conn in connections {
+                let _ = conn.close().await;
+            }
+        });
+    }
+}
+
 /// Pool statistics
 #[derive(Debug, Clone)]
 pub struct PoolStats {
@@ -882,6 +1302,22 @@ impl ConnectionBuilder {
                 "docker" => ConnectionType::Docker {
                     container: self.host.clone(),
                 },
+                "winrm" => ConnectionType::WinRm {
+                    host: self.host.clone(),
+                    port: self.port.unwrap_or(5986),
+                    user: self.user.clone().unwrap_or_else(whoami),
+                },
+                "podman" => ConnectionType::Podman {
+                    container: self.host.clone(),
+                    executable: None,
+                },
+                "kubectl" => ConnectionType::Kubernetes {
+                    namespace: "default".to_string(),
+                    pod: self.host.clone(),
+                    container: None,
+                    kubeconfig: None,
+                    context: None,
+                },
                 "ssh" | _ => ConnectionType::Ssh {
                     host: self.host.clone(),
                     port: self.port.unwrap_or(22),
@@ -950,27 +1386,58 @@ impl ConnectionBuilder {
             ConnectionType::Docker { container } => {
                 Ok(Arc::new(docker::DockerConnection::new(container)))
             }
+            ConnectionType::Podman {
+                container,
+                executable,
+            } => Ok(Arc::new(match executable {
+                Some(path) => podman::PodmanConnection::with_podman_path(container, path),
+                None => podman::PodmanConnection::new(container),
+            })),
             ConnectionType::Kubernetes {
                 namespace,
                 pod,
                 container,
+                kubeconfig,
+                context,
             } => {
                 // Kubernetes connection requires the kubernetes feature
                 #[cfg(feature = "kubernetes")]
                 {
-                    let conn =
-                        kubernetes::KubernetesConnection::new(namespace, pod, container).await?;
+                    let auth = kube::KubeAuth::Kubeconfig {
+                        path: kube::resolve_kubeconfig(kubeconfig.as_deref()),
+                        context,
+                    };
+                    let conn = kube::KubeConnection::new(namespace, pod, container, auth).await?;
                     Ok(Arc::new(conn))
                 }
                 #[cfg(not(feature = "kubernetes"))]
                 {
-                    let _ = (namespace, pod, container);
+                    let _ = (namespace, pod, container, kubeconfig, context);
                     Err(ConnectionError::InvalidConfig(
                         "Kubernetes support not available. Enable 'kubernetes' feature."
                             .to_string(),
                     ))
                 }
             }
+            ConnectionType::WinRm { host, port, user } => {
+                #[cfg(feature = "winrm")]
+                {
+                    let host_config = HostConfig {
+                        password: self.password,
+                        connect_timeout: self.timeout,
+                        ..Default::default()
+                    };
+                    let config = winrm::WinRmConfig::for_host(&host, port, &user, &host_config)?;
+                    Ok(Arc::new(winrm::WinRmConnection::connect(config).await?))
+                }
+                #[cfg(not(feature = "winrm"))]
+                {
+                    let _ = (host, port, user);
+                    Err(ConnectionError::InvalidConfig(
+                        "WinRM support not available. Enable 'winrm' feature.".to_string(),
+                    ))
+                }
+            }
         }
     }
 }
@@ -985,6 +1452,7 @@ fn whoami() -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[test]
     fn test_command_result_success() {
@@ -1023,6 +1491,324 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_connection_identity_pool_key() {
+        let target = ConnectionType::Ssh {
+            host: "example.com".to_string(),
+            port: 22,
+            user: "deploy".to_string(),
+        };
+
+        assert_eq!(
+            ConnectionIdentity::new().pool_key(&target),
+            "ssh://deploy@example.com:22"
+        );
+        assert_eq!(
+            ConnectionIdentity::new()
+                .with_identity_file("~/.ssh/deploy")
+                .with_become_user("postgres")
+                .pool_key(&target),
+            "ssh://deploy@example.com:22;key=~/.ssh/deploy;become=postgres"
+        );
+        assert_eq!(
+            ConnectionIdentity::new()
+                .with_user("alice")
+                .pool_key(&ConnectionType::Local),
+            "local;user=alice"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_factory_pools_connections_per_identity() {
+        let factory = ConnectionFactory::new(ConnectionConfig::default());
+        let alice = ConnectionIdentity::new().with_user("alice");
+        let bob = ConnectionIdentity::new().with_user("bob");
+
+        let alice_conn = factory
+            .get_connection_as("localhost", &alice)
+            .await
+            .unwrap();
+        let bob_conn = factory.get_connection_as("localhost", &bob).await.unwrap();
+
+        assert!(!Arc::ptr_eq(&alice_conn, &bob_conn));
+        assert_eq!(factory.pool_stats().active_connections, 2);
+
+        // The same identity reuses its pooled connection
+        let again = factory
+            .get_connection_as("localhost", &alice)
+            .await
+            .unwrap();
+        assert!(Arc::ptr_eq(&alice_conn, &again));
+        assert_eq!(factory.pool_stats().active_connections, 2);
+    }
+
+    #[cfg(feature = "russh")]
+    #[tokio::test]
+    async fn test_factory_reports_failed_jump_host() {
+        // Nothing listens on port 1, so the first hop fails straight away
+        let mut config = ConnectionConfig::default();
+        config.add_host(
+            "db1",
+            HostConfig {
+                proxy_jump: Some("bastion@127.0.0.1:1".to_string()),
+                retries: Some(0),
+                ..HostConfig::new().hostname("10.0.0.7").timeout(5)
+            },
+        );
+
+        let factory = ConnectionFactory::new(config);
+        let err = match factory.get_connection("db1").await {
+            Ok(_) => panic!("connection through an unreachable jump host succeeded"),
+            Err(err) => err,
+        };
+        match err {
+            ConnectionError::ConnectionFailed(msg) => {
+                assert!(msg.contains("Jump host bastion@127.0.0.1:1"), "{}", msg);
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_connection_override_takes_priority() {
+        let mut config = ConnectionConfig::default();
+        config.add_host(
+            "web1",
+            HostConfig::new().hostname("10.0.0.5").user("deploy"),
+        );
+        let identity = ConnectionIdentity::new();
+
+        let factory = ConnectionFactory::new(config.clone()).with_connection_override("local");
+        assert_eq!(
+            factory.resolve_connection_type("web1", &identity).unwrap(),
+            ConnectionType::Local
+        );
+
+        let factory = ConnectionFactory::new(config.clone()).with_connection_override("ssh");
+        assert_eq!(
+            factory
+                .resolve_connection_type("localhost", &identity)
+                .unwrap(),
+            ConnectionType::Ssh {
+                host: "localhost".to_string(),
+                port: 22,
+                user: config.defaults.user.clone(),
+            }
+        );
+
+        let factory = ConnectionFactory::new(config).with_connection_override("docker");
+        assert_eq!(
+            factory.resolve_connection_type("app", &identity).unwrap(),
+            ConnectionType::Docker {
+                container: "app".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_winrm_hosts_resolve_to_winrm() {
+        let mut config = ConnectionConfig::default();
+        config.add_host(
+            "win1",
+            HostConfig::new()
+                .hostname("10.0.0.9")
+                .user("Administrator")
+                .connection_type("winrm"),
+        );
+        config.add_host(
+            "win2",
+            HostConfig::new().port(5985).connection_type("winrm"),
+        );
+        let factory = ConnectionFactory::new(config);
+        let identity = ConnectionIdentity::new();
+
+        assert_eq!(
+            factory.resolve_connection_type("win1", &identity).unwrap(),
+            ConnectionType::WinRm {
+                host: "10.0.0.9".to_string(),
+                port: 5986,
+                user: "Administrator".to_string(),
+            }
+        );
+        assert!(matches!(
+            factory.resolve_connection_type("win2", &identity).unwrap(),
+            ConnectionType::WinRm { port: 5985, .. }
+        ));
+    }
+
+    #[test]
+    fn test_podman_hosts_resolve_to_podman() {
+        let mut config = ConnectionConfig::default();
+        let mut web = HostConfig::new()
+            .hostname("web-container")
+            .connection_type("podman");
+        web.podman_executable = Some("/opt/podman/bin/podman".to_string());
+        config.add_host("web", web);
+        let factory = ConnectionFactory::new(config);
+
+        let resolved = factory
+            .resolve_connection_type("web", &ConnectionIdentity::new())
+            .unwrap();
+        assert_eq!(resolved.pool_key(), "podman://web-container");
+        assert_eq!(
+            resolved,
+            ConnectionType::Podman {
+                container: "web-container".to_string(),
+                executable: Some("/opt/podman/bin/podman".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_kubectl_hosts_resolve_to_pods() {
+        let mut config = ConnectionConfig::default();
+        let mut pod = HostConfig::new().connection_type("kubectl");
+        pod.kube_namespace = Some("staging".to_string());
+        pod.kube_pod = Some("api-7d9f".to_string());
+        pod.kube_container = Some("app".to_string());
+        pod.kube_config = Some("~/.kube/staging".to_string());
+        config.add_host("api", pod);
+        config.add_host("worker", HostConfig::new().connection_type("kubectl"));
+        let factory = ConnectionFactory::new(config);
+        let identity = ConnectionIdentity::new();
+
+        assert_eq!(
+            factory.resolve_connection_type("api", &identity).unwrap(),
+            ConnectionType::Kubernetes {
+                namespace: "staging".to_string(),
+                pod: "api-7d9f".to_string(),
+                container: Some("app".to_string()),
+                kubeconfig: Some("~/.kube/staging".to_string()),
+                context: None,
+            }
+        );
+        // Without pod variables the host name is the pod in `default`
+        assert_eq!(
+            factory
+                .resolve_connection_type("worker", &identity)
+                .unwrap()
+                .pool_key(),
+            "k8s://default/worker"
+        );
+    }
+
+    /// Connection that counts how often it was closed
+    struct CountingConnection {
+        id: String,
+        closed: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Connection for CountingConnection {
+        fn identifier(&self) -> &str {
+            &self.id
+        }
+
+        async fn is_alive(&self) -> bool {
+            true
+        }
+
+        async fn execute(
+            &self,
+            _command: &str,
+            _options: Option<ExecuteOptions>,
+        ) -> ConnectionResult<CommandResult> {
+            Ok(CommandResult::success(String::new(), String::new()))
+        }
+
+        async fn upload(
+            &self,
+            _local_path: &Path,
+            _remote_path: &Path,
+            _options: Option<TransferOptions>,
+        ) -> ConnectionResult<()> {
+            Ok(())
+        }
+
+        async fn upload_content(
+            &self,
+            _content: &[u8],
+            _remote_path: &Path,
+            _options: Option<TransferOptions>,
+        ) -> ConnectionResult<()> {
+            Ok(())
+        }
+
+        async fn download(&self, _remote_path: &Path, _local_path: &Path) -> ConnectionResult<()> {
+            Ok(())
+        }
+
+        async fn download_content(&self, _remote_path: &Path) -> ConnectionResult<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn path_exists(&self, _path: &Path) -> ConnectionResult<bool> {
+            Ok(false)
+        }
+
+        async fn is_directory(&self, _path: &Path) -> ConnectionResult<bool> {
+            Ok(false)
+        }
+
+        async fn stat(&self, path: &Path) -> ConnectionResult<FileStat> {
+            Err(ConnectionError::TransferFailed(format!(
+                "{} not found",
+                path.display()
+            )))
+        }
+
+        async fn close(&self) -> ConnectionResult<()> {
+            self.closed.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn factory_with_counting_connections(count: usize) -> (ConnectionFactory, Arc<AtomicUsize>) {
+        let factory = ConnectionFactory::new(ConnectionConfig::default());
+        let closed = Arc::new(AtomicUsize::new(0));
+        for i in 0..count {
+            let conn = CountingConnection {
+                id: format!("mock-{}", i),
+                closed: Arc::clone(&closed),
+            };
+            factory
+                .pool
+                .write()
+                .put(format!("mock://{}", i), Arc::new(conn));
+        }
+        (factory, closed)
+    }
+
+    #[tokio::test]
+    async fn test_factory_close_all_closes_pooled_connections() {
+        let (factory, closed) = factory_with_counting_connections(3);
+
+        factory.close_all().await.unwrap();
+
+        assert_eq!(closed.load(Ordering::SeqCst), 3);
+        assert_eq!(factory.pool_stats().active_connections, 0);
+    }
+
+    #[tokio::test]
+    async fn test_factory_drop_closes_pooled_connections() {
+        let (factory, closed) = factory_with_counting_connections(2);
+
+        // Clones share the pool, so only the last one tears it down
+        let clone = factory.clone();
+        drop(factory);
+        tokio::task::yield_now().await;
+        assert_eq!(closed.load(Ordering::SeqCst), 0);
+
+        drop(clone);
+        for _ in 0..10 {
+            if closed.load(Ordering::SeqCst) == 2 {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(closed.load(Ordering::SeqCst), 2);
+    }
+
     #[test]
     fn test_execute_options_builder() {
         let options = ExecuteOptions::new()
@@ -1037,4 +1823,16 @@ mod tests {
         assert!(options.escalate);
         assert_eq!(options.escalate_user, Some("root".to_string()));
     }
+
+    #[test]
+    fn test_become_command() {
+        let mut options = ExecuteOptions::new();
+        assert_eq!(options.become_command("id -un"), "id -un");
+
+        options = options.with_escalation(Some("deploy".to_string()));
+        assert_eq!(options.become_command("id -un"), "sudo -u deploy -- id -un");
+
+        options.escalate_method = Some("doas".to_string());
+        assert_eq!(options.become_command("id -un"), "doas -u deploy id -un");
+    }
 }
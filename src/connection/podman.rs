@@ -0,0 +1,579 @@
+//! Podman connection module
+//!
+//! This module provides connectivity to Podman containers using the
+//! podman CLI. It mirrors the Docker connection: commands run through
+//! `podman exec` and files move with `podman cp`.
+//!
+//! Podman is often run rootless, where every user has their own container
+//! storage. A rootless connection only sees containers of the user running
+//! rustible, and needs `XDG_RUNTIME_DIR` to find that user's runtime state,
+//! which non-login sessions (cron, systemd services) usually lack.
+
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::{debug, trace};
+
+use super::{
+    CommandResult, Connection, ConnectionError, ConnectionResult, ExecuteOptions, FileStat,
+    TransferOptions,
+};
+
+pub use super::docker::ContainerInfo;
+
+/// Podman connection for executing commands inside containers
+#[derive(Debug, Clone)]
+pub struct PodmanConnection {
+    /// Container ID or name
+    container: String,
+    /// Podman executable path (default: "podman")
+    podman_path: String,
+    /// Whether podman runs without root privileges
+    rootless: bool,
+}
+
+impl PodmanConnection {
+    /// Create a new Podman connection
+    ///
+    /// Rootless mode is assumed when rustible itself runs as a regular user.
+    pub fn new(container: impl Into<String>) -> Self {
+        Self {
+            container: container.into(),
+            podman_path: "podman".to_string(),
+            rootless: !nix::unistd::geteuid().is_root(),
+        }
+    }
+
+    /// Create a new Podman connection with a custom podman path
+    pub fn with_podman_path(container: impl Into<String>, podman_path: impl Into<String>) -> Self {
+        Self {
+            podman_path: podman_path.into(),
+            ..Self::new(container)
+        }
+    }
+
+    /// Set whether podman runs rootless
+    pub fn rootless(mut self, rootless: bool) -> Self {
+        self.rootless = rootless;
+        self
+    }
+
+    /// Whether podman runs rootless
+    pub fn is_rootless(&self) -> bool {
+        self.rootless
+    }
+
+    /// Start a podman command
+    ///
+    /// Rootless podman is given the user's runtime directory when the
+    /// environment does not name one.
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.podman_path);
+        if self.rootless && std::env::var_os("XDG_RUNTIME_DIR").is_none() {
+            if let Some(dir) = user_runtime_dir() {
+                cmd.env("XDG_RUNTIME_DIR", dir);
+            }
+        }
+        cmd
+    }
+
+    /// Build the podman exec command
+    ///
+    /// Privilege escalation maps to `--user` rather than wrapping the command
+    /// in sudo, which containers rarely ship. `become` without a user runs
+    /// as root, which is the invoking user on the host when rootless.
+    fn build_exec_command(&self, command: &str, options: &ExecuteOptions) -> Command {
+        let mut cmd = self.command();
+
+        cmd.arg("exec");
+
+        // Keep STDIN open
+        cmd.arg("-i");
+
+        // Set user if escalation is requested
+        if options.escalate {
+            let user = options.escalate_user.as_deref().unwrap_or("root");
+            cmd.arg("--user").arg(user);
+        }
+
+        // Set working directory
+        if let Some(cwd) = &options.cwd {
+            cmd.arg("-w").arg(cwd);
+        }
+
+        // Set environment variables
+        for (key, value) in &options.env {
+            cmd.arg("-e").arg(format!("{}={}", key, value));
+        }
+
+        cmd.arg(&self.container);
+
+        // Add the actual command
+        cmd.arg("sh").arg("-c").arg(command);
+
+        // Configure stdio
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        cmd
+    }
+
+    /// Build podman cp command for uploading
+    ///
+    /// `preserve_mode` maps to `--archive`, which hands the copied file to
+    /// the container's primary user. Without it the file keeps the uid and
+    /// gid of the local copy.
+    fn build_cp_to_container_command(
+        &self,
+        local_path: &Path,
+        remote_path: &Path,
+        options: &TransferOptions,
+    ) -> Command {
+        let mut cmd = self.command();
+
+        cmd.arg("cp")
+            .arg(format!("--archive={}", options.preserve_mode))
+            .arg(local_path)
+            .arg(format!("{}:{}", self.container, remote_path.display()));
+
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        cmd
+    }
+
+    /// Build podman cp command for downloading
+    fn build_cp_from_container_command(&self, remote_path: &Path, local_path: &Path) -> Command {
+        let mut cmd = self.command();
+
+        cmd.arg("cp")
+            .arg(format!("{}:{}", self.container, remote_path.display()))
+            .arg(local_path);
+
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        cmd
+    }
+
+    /// Error for a container podman cannot find
+    fn not_found(&self) -> ConnectionError {
+        let hint = if self.rootless {
+            " (rootless podman only sees the current user's containers)"
+        } else {
+            ""
+        };
+        ConnectionError::ConnectionFailed(format!("Container {} not found{}", self.container, hint))
+    }
+
+    /// Check if container is running
+    ///
+    /// A container podman does not know is an error rather than `false`, so
+    /// the rootless hint reaches the user.
+    async fn is_container_running(&self) -> ConnectionResult<bool> {
+        let mut cmd = self.command();
+
+        cmd.arg("inspect")
+            .arg("-f")
+            .arg("{{.State.Running}}")
+            .arg(&self.container)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let output = cmd.output().await.map_err(|e| {
+            ConnectionError::ConnectionFailed(format!("Failed to inspect container: {}", e))
+        })?;
+
+        if !output.status.success() {
+            return Err(self.not_found());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.trim() == "true")
+    }
+
+    /// Get container info
+    pub async fn container_info(&self) -> ConnectionResult<ContainerInfo> {
+        let mut cmd = self.command();
+
+        cmd.arg("inspect")
+            .arg("-f")
+            .arg("{{.Id}}|{{.Name}}|{{.State.Running}}|{{.Config.Image}}")
+            .arg(&self.container)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let output = cmd.output().await.map_err(|e| {
+            ConnectionError::ConnectionFailed(format!("Failed to inspect container: {}", e))
+        })?;
+
+        if !output.status.success() {
+            return Err(self.not_found());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let parts: Vec<&str> = stdout.trim().split('|').collect();
+
+        if parts.len() != 4 {
+            return Err(ConnectionError::ConnectionFailed(
+                "Invalid container info format".to_string(),
+            ));
+        }
+
+        Ok(ContainerInfo {
+            id: parts[0].to_string(),
+            name: parts[1].trim_start_matches('/').to_string(),
+            running: parts[2] == "true",
+            image: parts[3].to_string(),
+        })
+    }
+}
+
+/// Runtime directory of the current user, if it exists
+fn user_runtime_dir() -> Option<PathBuf> {
+    let dir = PathBuf::from(format!("/run/user/{}", nix::unistd::getuid()));
+    dir.is_dir().then_some(dir)
+}
+
+#[async_trait]
+impl Connection for PodmanConnection {
+    fn identifier(&self) -> &str {
+        &self.container
+    }
+
+    async fn is_alive(&self) -> bool {
+        self.is_container_running().await.unwrap_or(false)
+    }
+
+    async fn execute(
+        &self,
+        command: &str,
+        options: Option<ExecuteOptions>,
+    ) -> ConnectionResult<CommandResult> {
+        let options = options.unwrap_or_default();
+
+        // Verify container is running
+        if !self.is_container_running().await? {
+            return Err(ConnectionError::ConnectionFailed(format!(
+                "Container {} is not running",
+                self.container
+            )));
+        }
+
+        debug!(
+            container = %self.container,
+            command = %command,
+            "Executing command in Podman container"
+        );
+
+        let mut cmd = self.build_exec_command(command, &options);
+
+        // Spawn the process
+        let child = cmd.spawn().map_err(|e| {
+            ConnectionError::ExecutionFailed(format!("Failed to execute podman exec: {}", e))
+        })?;
+
+        // Wait for the process with optional timeout
+        let output = if let Some(timeout_secs) = options.timeout {
+            let timeout = tokio::time::Duration::from_secs(timeout_secs);
+            let wait_future = child.wait_with_output();
+            match tokio::time::timeout(timeout, wait_future).await {
+                Ok(result) => result.map_err(|e| {
+                    ConnectionError::ExecutionFailed(format!("Failed to wait for process: {}", e))
+                })?,
+                Err(_) => {
+                    // Timeout occurred
+                    return Err(ConnectionError::Timeout(timeout_secs));
+                }
+            }
+        } else {
+            child.wait_with_output().await.map_err(|e| {
+                ConnectionError::ExecutionFailed(format!("Failed to wait for process: {}", e))
+            })?
+        };
+
+        let exit_code = output.status.code().unwrap_or(-1);
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+        trace!(
+            exit_code = %exit_code,
+            stdout_len = %stdout.len(),
+            stderr_len = %stderr.len(),
+            "Podman exec completed"
+        );
+
+        if output.status.success() {
+            Ok(CommandResult::success(stdout, stderr))
+        } else {
+            Ok(CommandResult::failure(exit_code, stdout, stderr))
+        }
+    }
+
+    async fn upload(
+        &self,
+        local_path: &Path,
+        remote_path: &Path,
+        options: Option<TransferOptions>,
+    ) -> ConnectionResult<()> {
+        let options = options.unwrap_or_default();
+
+        debug!(
+            local = %local_path.display(),
+            remote = %remote_path.display(),
+            container = %self.container,
+            "Uploading file to Podman container"
+        );
+
+        // Create parent directories if needed
+        if options.create_dirs {
+            if let Some(parent) = remote_path.parent() {
+                let mkdir_cmd = format!("mkdir -p {}", parent.display());
+                self.execute(&mkdir_cmd, None).await?;
+            }
+        }
+
+        // Copy file to container
+        let mut cmd = self.build_cp_to_container_command(local_path, remote_path, &options);
+        let output = cmd.output().await.map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to execute podman cp: {}", e))
+        })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(ConnectionError::TransferFailed(format!(
+                "podman cp failed: {}",
+                stderr
+            )));
+        }
+
+        // Set permissions if specified
+        if let Some(mode) = options.mode {
+            let chmod_cmd = format!("chmod {:o} {}", mode, remote_path.display());
+            self.execute(&chmod_cmd, None).await?;
+        }
+
+        // Set owner/group if specified
+        if options.owner.is_some() || options.group.is_some() {
+            let ownership = match (&options.owner, &options.group) {
+                (Some(o), Some(g)) => format!("{}:{}", o, g),
+                (Some(o), None) => o.to_string(),
+                (None, Some(g)) => format!(":{}", g),
+                (None, None) => return Ok(()),
+            };
+
+            let chown_cmd = format!("chown {} {}", ownership, remote_path.display());
+            self.execute(&chown_cmd, None).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn upload_content(
+        &self,
+        content: &[u8],
+        remote_path: &Path,
+        options: Option<TransferOptions>,
+    ) -> ConnectionResult<()> {
+        let options = options.unwrap_or_default();
+
+        debug!(
+            remote = %remote_path.display(),
+            container = %self.container,
+            size = %content.len(),
+            "Uploading content to Podman container"
+        );
+
+        // Create a temporary file
+        let temp_file = tempfile::NamedTempFile::new().map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to create temp file: {}", e))
+        })?;
+
+        // Write content to temp file
+        std::fs::write(temp_file.path(), content).map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to write temp file: {}", e))
+        })?;
+
+        // Upload temp file
+        self.upload(temp_file.path(), remote_path, Some(options))
+            .await
+    }
+
+    async fn download(&self, remote_path: &Path, local_path: &Path) -> ConnectionResult<()> {
+        debug!(
+            remote = %remote_path.display(),
+            local = %local_path.display(),
+            container = %self.container,
+            "Downloading file from Podman container"
+        );
+
+        // Create parent directories for local file
+        if let Some(parent) = local_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                ConnectionError::TransferFailed(format!("Failed to create local directory: {}", e))
+            })?;
+        }
+
+        // Copy file from container
+        let mut cmd = self.build_cp_from_container_command(remote_path, local_path);
+        let output = cmd.output().await.map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to execute podman cp: {}", e))
+        })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(ConnectionError::TransferFailed(format!(
+                "podman cp failed: {}",
+                stderr
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn download_content(&self, remote_path: &Path) -> ConnectionResult<Vec<u8>> {
+        debug!(
+            remote = %remote_path.display(),
+            container = %self.container,
+            "Downloading content from Podman container"
+        );
+
+        // Copy through a temp file so binary content survives
+        let temp_dir = tempfile::tempdir().map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to create temp dir: {}", e))
+        })?;
+        let local_path = temp_dir.path().join("content");
+        self.download(remote_path, &local_path).await?;
+
+        std::fs::read(&local_path).map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to read downloaded file: {}", e))
+        })
+    }
+
+    async fn path_exists(&self, path: &Path) -> ConnectionResult<bool> {
+        let command = format!("test -e {} && echo yes || echo no", path.display());
+        let result = self.execute(&command, None).await?;
+        Ok(result.stdout.trim() == "yes")
+    }
+
+    async fn is_directory(&self, path: &Path) -> ConnectionResult<bool> {
+        let command = format!("test -d {} && echo yes || echo no", path.display());
+        let result = self.execute(&command, None).await?;
+        Ok(result.stdout.trim() == "yes")
+    }
+
+    async fn stat(&self, path: &Path) -> ConnectionResult<FileStat> {
+        // Use stat command to get file info
+        let command = format!("stat -c '%s|%a|%u|%g|%X|%Y|%F' {}", path.display());
+        let result = self.execute(&command, None).await?;
+
+        if !result.success {
+            return Err(ConnectionError::TransferFailed(format!(
+                "Failed to stat file: {}",
+                result.stderr
+            )));
+        }
+
+        let parts: Vec<&str> = result.stdout.trim().split('|').collect();
+        if parts.len() != 7 {
+            return Err(ConnectionError::TransferFailed(
+                "Invalid stat output".to_string(),
+            ));
+        }
+
+        let file_type = parts[6];
+
+        Ok(FileStat {
+            size: parts[0].parse().unwrap_or(0),
+            mode: u32::from_str_radix(parts[1], 8).unwrap_or(0),
+            uid: parts[2].parse().unwrap_or(0),
+            gid: parts[3].parse().unwrap_or(0),
+            atime: parts[4].parse().unwrap_or(0),
+            mtime: parts[5].parse().unwrap_or(0),
+            is_dir: file_type.contains("directory"),
+            is_file: file_type.contains("regular"),
+            is_symlink: file_type.contains("symbolic link"),
+        })
+    }
+
+    async fn close(&self) -> ConnectionResult<()> {
+        // Nothing to close for podman connection
+        // The container continues running
+        Ok(())
+    }
+
+    fn supports_atomic_rename(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(cmd: &Command) -> Vec<String> {
+        cmd.as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_podman_connection_new() {
+        let conn = PodmanConnection::new("my-container");
+        assert_eq!(conn.container, "my-container");
+        assert_eq!(conn.podman_path, "podman");
+        assert_eq!(conn.is_rootless(), !nix::unistd::geteuid().is_root());
+        assert!(!conn.rootless(false).is_rootless());
+    }
+
+    #[test]
+    fn test_build_exec_command_uses_custom_executable() {
+        let conn = PodmanConnection::with_podman_path("web", "/opt/podman/bin/podman");
+        let options = ExecuteOptions::new()
+            .with_cwd("/app")
+            .with_escalation(Some("app".to_string()));
+
+        let cmd = conn.build_exec_command("id -un", &options);
+        assert_eq!(cmd.as_std().get_program(), "/opt/podman/bin/podman");
+        assert_eq!(
+            args(&cmd),
+            vec!["exec", "-i", "--user", "app", "-w", "/app", "web", "sh", "-c", "id -un"]
+        );
+    }
+
+    #[test]
+    fn test_build_cp_command_maps_preserve_mode_to_archive() {
+        let conn = PodmanConnection::new("web");
+        let local = Path::new("/tmp/app.conf");
+        let remote = Path::new("/etc/app.conf");
+
+        let cmd = conn.build_cp_to_container_command(local, remote, &TransferOptions::new());
+        assert_eq!(
+            args(&cmd),
+            vec![
+                "cp",
+                "--archive=false",
+                "/tmp/app.conf",
+                "web:/etc/app.conf"
+            ]
+        );
+
+        let options = TransferOptions::new().with_preserve_mode();
+        let cmd = conn.build_cp_to_container_command(local, remote, &options);
+        assert_eq!(args(&cmd)[1], "--archive=true");
+    }
+
+    #[test]
+    fn test_rootless_commands_get_a_runtime_dir() {
+        let has_env = |conn: &PodmanConnection| {
+            conn.command()
+                .as_std()
+                .get_envs()
+                .any(|(key, _)| key == "XDG_RUNTIME_DIR")
+        };
+
+        assert!(!has_env(&PodmanConnection::new("web").rootless(false)));
+        if std::env::var_os("XDG_RUNTIME_DIR").is_none() && user_runtime_dir().is_some() {
+            assert!(has_env(&PodmanConnection::new("web").rootless(true)));
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! Managed remote temp directory for module staging.
+//!
+//! Modules that need to place scripts or files on the target (the Python
+//! fallback, validation commands, file sync) stage them in a single
+//! per-run directory instead of inventing their own temp paths:
+//!
+//! - The directory is `~/.rustible/tmp/<run-id>` by default
+//! - It is created with mode 0700 the first time a module asks for it
+//! - It is removed when the connection closes
+//!
+//! Connections handed out by [`ConnectionFactory`](super::ConnectionFactory)
+//! are wrapped in a [`RemoteTmpConnection`], so modules reach the directory
+//! through [`Connection::remote_tmp`]. The wrapper also carries a
+//! [`RemoteIdCache`] of user and group ids looked up on the host, which
+//! lives exactly as long as the connection.
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tracing::{debug, warn};
+
+use super::{
+    CommandResult, Connection, ConnectionError, ConnectionResult, ExecuteOptions, FileStat,
+    TransferOptions,
+};
+
+/// Default base directory for remote temp directories
+pub const DEFAULT_REMOTE_TMP_BASE: &str = "~/.rustible/tmp";
+
+/// A per-run temp directory on a remote host, created on first use
+#[derive(Debug)]
+pub struct RemoteTmp {
+    /// Base directory, may start with `~/`
+    base: String,
+    /// Run identifier used as the directory name
+    run_id: String,
+    /// Absolute path of the directory once created
+    path: OnceCell<PathBuf>,
+}
+
+impl RemoteTmp {
+    /// Create a temp directory manager for a run
+    pub fn new(run_id: impl Into<String>) -> Self {
+        Self {
+            base: DEFAULT_REMOTE_TMP_BASE.to_string(),
+            run_id: run_id.into(),
+            path: OnceCell::new(),
+        }
+    }
+
+    /// Use a different base directory
+    pub fn with_base(mut self, base: impl Into<String>) -> Self {
+        self.base = base.into();
+        self
+    }
+
+    /// Run identifier used as the directory name
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// Path of the directory if it has been created
+    pub fn created_path(&self) -> Option<&Path> {
+        self.path.get().map(PathBuf::as_path)
+    }
+
+    /// Get the directory, creating it on the remote host on first use
+    pub async fn path(&self, conn: &dyn Connection) -> ConnectionResult<&Path> {
+        let path = self
+            .path
+            .get_or_try_init(|| async {
+                let dir = shell_path(&format!(
+                    "{}/{}",
+                    self.base.trim_end_matches('/'),
+                    self.run_id
+                ));
+                let command = format!(
+                    "umask 077 && mkdir -p {dir} && cd {dir} && pwd -P",
+                    dir = dir
+                );
+                let result = conn.execute(&command, None).await?;
+                if !result.success {
+                    return Err(ConnectionError::ExecutionFailed(format!(
+                        "Failed to create remote temp directory: {}",
+                        result.stderr.trim()
+                    )));
+                }
+                let path = PathBuf::from(result.stdout.trim());
+                debug!("Created remote temp directory {}", path.display());
+                Ok(path)
+            })
+            .await?;
+        Ok(path.as_path())
+    }
+
+    /// Upload content into the temp directory and return its remote path
+    pub async fn stage(
+        &self,
+        conn: &dyn Connection,
+        name: &str,
+        content: &[u8],
+    ) -> ConnectionResult<PathBuf> {
+        let remote_path = self.path(conn).await?.join(name);
+        conn.upload_content(
+            content,
+            &remote_path,
+            Some(TransferOptions::new().with_mode(0o600)),
+        )
+        .await?;
+        Ok(remote_path)
+    }
+
+    /// Remove the directory if it was created
+    pub async fn cleanup(&self, conn: &dyn Connection) -> ConnectionResult<()> {
+        let Some(path) = self.created_path() else {
+            return Ok(());
+        };
+        let command = format!("rm -rf {}", shell_path(&path.to_string_lossy()));
+        let result = conn.execute(&command, None).await?;
+        if !result.success {
+            return Err(ConnectionError::ExecutionFailed(format!(
+                "Failed to remove remote temp directory {}: {}",
+                path.display(),
+                result.stderr.trim()
+            )));
+        }
+        debug!("Removed remote temp directory {}", path.display());
+        Ok(())
+    }
+}
+
+/// Quote a path for the remote shell, expanding a leading `~/` to `$HOME`
+pub(super) fn shell_path(path: &str) -> String {
+    let (prefix, rest) = match path.strip_prefix("~/") {
+        Some(rest) => ("$HOME/", rest),
+        None => ("", path),
+    };
+    let escaped: String = rest
+        .chars()
+        .flat_map(|c| match c {
+            '"' | '$' | '`' | '\\' => vec!['\\', c],
+            _ => vec![c],
+        })
+        .collect();
+    format!("\"{}{}\"", prefix, escaped)
+}
+
+/// User and group ids resolved on one host, keyed by `getent` database and name
+#[derive(Debug, Default)]
+pub struct RemoteIdCache {
+    ids: Mutex<HashMap<(&'static str, String), u32>>,
+}
+
+impl RemoteIdCache {
+    /// A previously resolved id
+    pub fn get(&self, database: &'static str, name: &str) -> Option<u32> {
+        self.ids.lock().get(&(database, name.to_string())).copied()
+    }
+
+    /// Remember a resolved id
+    pub fn insert(&self, database: &'static str, name: &str, id: u32) {
+        self.ids.lock().insert((database, name.to_string()), id);
+    }
+}
+
+/// A connection that owns a [`RemoteTmp`] and removes it on close
+pub struct RemoteTmpConnection {
+    inner: Arc<dyn Connection + Send + Sync>,
+    tmp: RemoteTmp,
+    ids: RemoteIdCache,
+}
+
+impl RemoteTmpConnection {
+    /// Wrap a connection with a managed temp directory
+    pub fn new(inner: Arc<dyn Connection + Send + Sync>, tmp: RemoteTmp) -> Self {
+        Self {
+            inner,
+            tmp,
+            ids: RemoteIdCache::default(),
+        }
+    }
+
+    /// The wrapped connection
+    pub fn inner(&self) -> &Arc<dyn Connection + Send + Sync> {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl Connection for RemoteTmpConnection {
+    fn identifier(&self) -> &str {
+        self.inner.identifier()
+    }
+
+    async fn is_alive(&self) -> bool {
+        self.inner.is_alive().await
+    }
+
+    async fn execute(
+        &self,
+        command: &str,
+        options: Option<ExecuteOptions>,
+    ) -> ConnectionResult<CommandResult> {
+        self.inner.execute(command, options).await
+    }
+
+    async fn execute_argv(
+        &self,
+        argv: &[String],
+        options: Option<ExecuteOptions>,
+    ) -> ConnectionResult<CommandResult> {
+        self.inner.execute_argv(argv, options).await
+    }
+
+    async fn upload(
+        &self,
+        local_path: &Path,
+        remote_path: &Path,
+        options: Option<TransferOptions>,
+    ) -> ConnectionResult<()> {
+        self.inner.upload(local_path, remote_path, options).await
+    }
+
+    async fn upload_content(
+        &self,
+        content: &[u8],
+        remote_path: &Path,
+        options: Option<TransferOptions>,
+    ) -> ConnectionResult<()> {
+        self.inner
+            .upload_content(content, remote_path, options)
+            .await
+    }
+
+    async fn download(&self, remote_path: &Path, local_path: &Path) -> ConnectionResult<()> {
+        self.inner.download(remote_path, local_path).await
+    }
+
+    async fn download_content(&self, remote_path: &Path) -> ConnectionResult<Vec<u8>> {
+        self.inner.download_content(remote_path).await
+    }
+
+    async fn path_exists(&self, path: &Path) -> ConnectionResult<bool> {
+        self.inner.path_exists(path).await
+    }
+
+    async fn is_directory(&self, path: &Path) -> ConnectionResult<bool> {
+        self.inner.is_directory(path).await
+    }
+
+    async fn stat(&self, path: &Path) -> ConnectionResult<FileStat> {
+        self.inner.stat(path).await
+    }
+
+    async fn close(&self) -> ConnectionResult<()> {
+        // A leftover directory must not keep the connection open
+        if let Err(e) = self.tmp.cleanup(self.inner.as_ref()).await {
+            warn!("{}", e);
+        }
+        self.inner.close().await
+    }
+
+    async fn execute_batch(
+        &self,
+        commands: &[&str],
+        options: Option<ExecuteOptions>,
+    ) -> Vec<ConnectionResult<CommandResult>> {
+        self.inner.execute_batch(commands, options).await
+    }
+
+    fn remote_tmp(&self) -> Option<&RemoteTmp> {
+        Some(&self.tmp)
+    }
+
+    fn remote_ids(&self) -> Option<&RemoteIdCache> {
+        Some(&self.ids)
+    }
+
+    fn supports_atomic_rename(&self) -> bool {
+        self.inner.supports_atomic_rename()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::local::LocalConnection;
+
+    #[test]
+    fn test_shell_path() {
+        assert_eq!(
+            shell_path("~/.rustible/tmp/abc"),
+            "\"$HOME/.rustible/tmp/abc\""
+        );
+        assert_eq!(shell_path("/var/tmp/a b"), "\"/var/tmp/a b\"");
+        assert_eq!(shell_path("/tmp/$x"), "\"/tmp/\\$x\"");
+    }
+
+    #[tokio::test]
+    async fn test_remote_tmp_created_on_first_use_and_removed_on_close() {
+        let base = tempfile::tempdir().unwrap();
+        let tmp = RemoteTmp::new("run-1").with_base(base.path().to_string_lossy());
+        let conn = RemoteTmpConnection::new(Arc::new(LocalConnection::new()), tmp);
+
+        let tmp = conn.remote_tmp().unwrap();
+        assert!(tmp.created_path().is_none());
+        assert!(!base.path().join("run-1").exists());
+
+        let staged = tmp.stage(&conn, "module.py", b"print('hi')").await.unwrap();
+        let dir = tmp.created_path().unwrap().to_path_buf();
+        assert!(dir.ends_with("run-1"));
+        assert_eq!(staged, dir.join("module.py"));
+        assert_eq!(std::fs::read(&staged).unwrap(), b"print('hi')");
+
+        // Later calls reuse the same directory
+        assert_eq!(tmp.path(&conn).await.unwrap(), dir.as_path());
+
+        conn.close().await.unwrap();
+        assert!(!dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_execute_argv_reaches_inner_connection() {
+        let tmp = RemoteTmp::new("argv");
+        let conn = RemoteTmpConnection::new(Arc::new(LocalConnection::new()), tmp);
+
+        let argv: Vec<String> = ["printf", "%s|", "a b", "$HOME"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let result = conn.execute_argv(&argv, None).await.unwrap();
+        assert_eq!(result.stdout, "a b|$HOME|");
+
+        // Spawned directly, a missing program fails to start instead of
+        // reaching a shell that reports exit code 127
+        let missing = vec!["rustible-no-such-program".to_string()];
+        assert!(matches!(
+            conn.execute_argv(&missing, None).await,
+            Err(ConnectionError::ExecutionFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_close_without_use_leaves_nothing() {
+        let base = tempfile::tempdir().unwrap();
+        let tmp = RemoteTmp::new("unused").with_base(base.path().to_string_lossy());
+        let conn = RemoteTmpConnection::new(Arc::new(LocalConnection::new()), tmp);
+
+        conn.close().await.unwrap();
+        assert!(std::fs::read_dir(base.path()).unwrap().next().is_none());
+    }
+}
@@ -276,6 +276,7 @@ impl RetryPolicy {
             // Potentially retryable
             ConnectionError::ExecutionFailed(_) => false,
             ConnectionError::TransferFailed(_) => false,
+            ConnectionError::SftpUnavailable(_) => false,
             ConnectionError::DockerError(_) => false,
             ConnectionError::KubernetesError(_) => false,
         }
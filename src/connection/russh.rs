@@ -45,6 +45,8 @@ const MIN_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(5);
 use super::config::{
     default_identity_files, expand_path, ConnectionConfig, HostConfig, RetryConfig,
 };
+use super::jump_host::{JumpHostChain, JumpHostConfig, JumpHostResolver};
+use super::transfer::{self, TransferMethod, TransferStats};
 use super::{
     CommandResult, Connection, ConnectionError, ConnectionResult, ExecuteOptions, FileStat,
     RusshError, TransferOptions,
@@ -578,6 +580,8 @@ pub struct RusshConnection {
     /// Read lock: channel operations (execute, upload, download, etc.)
     /// Write lock: close operation only
     handle: Arc<RwLock<Option<Handle<ClientHandler>>>>,
+    /// Jump host sessions carrying the connection, closest to us first
+    jump_sessions: Vec<Handle<ClientHandler>>,
     /// Host configuration (kept for future connection pooling improvements)
     #[allow(dead_code)]
     host_config: HostConfig,
@@ -616,32 +620,7 @@ impl RusshConnection {
             parts.push(format!("cd {} && ", cwd));
         }
 
-        // Handle privilege escalation
-        if options.escalate {
-            let escalate_method = options.escalate_method.as_deref().unwrap_or("sudo");
-            let escalate_user = options.escalate_user.as_deref().unwrap_or("root");
-
-            match escalate_method {
-                "sudo" => {
-                    if options.escalate_password.is_some() {
-                        parts.push(format!("sudo -S -u {} -- ", escalate_user));
-                    } else {
-                        parts.push(format!("sudo -u {} -- ", escalate_user));
-                    }
-                }
-                "su" => {
-                    parts.push(format!("su - {} -c ", escalate_user));
-                }
-                "doas" => {
-                    parts.push(format!("doas -u {} ", escalate_user));
-                }
-                _ => {
-                    parts.push(format!("sudo -u {} -- ", escalate_user));
-                }
-            }
-        }
-
-        parts.push(command.to_string());
+        parts.push(options.become_command(command));
         parts.concat()
     }
 
@@ -673,13 +652,208 @@ impl RusshConnection {
             ConnectionError::TransferFailed(format!("Failed to open channel: {}", e))
         })?;
 
-        channel.request_subsystem(true, "sftp").await.map_err(|e| {
-            ConnectionError::TransferFailed(format!("Failed to request SFTP subsystem: {}", e))
+        channel
+            .request_subsystem(true, "sftp")
+            .await
+            .map_err(|e| ConnectionError::SftpUnavailable(e.to_string()))?;
+
+        // Servers without an SFTP subsystem reject the request, which only
+        // surfaces once the session handshake fails
+        SftpSession::new(channel.into_stream())
+            .await
+            .map_err(|e| ConnectionError::SftpUnavailable(e.to_string()))
+    }
+
+    /// Transfer method configured for this host
+    fn transfer_method(&self) -> TransferMethod {
+        self.host_config.transfer_method.unwrap_or_default()
+    }
+
+    /// Open a channel running `command`, for protocols spoken over stdio
+    async fn open_exec_channel(
+        &self,
+        command: &str,
+    ) -> ConnectionResult<russh::Channel<russh::client::Msg>> {
+        let handle_guard = self.handle.read().await;
+        let handle = handle_guard
+            .as_ref()
+            .ok_or_else(|| ConnectionError::ConnectionClosed)?;
+
+        let channel = handle.channel_open_session().await.map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to open channel: {}", e))
         })?;
+        drop(handle_guard);
 
-        SftpSession::new(channel.into_stream()).await.map_err(|e| {
-            ConnectionError::TransferFailed(format!("Failed to create SFTP session: {}", e))
-        })
+        channel.exec(true, command).await.map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to execute {}: {}", command, e))
+        })?;
+
+        Ok(channel)
+    }
+
+    /// Write content to a remote file over SFTP
+    async fn upload_content_sftp(
+        &self,
+        content: &[u8],
+        remote_path: &Path,
+        options: &TransferOptions,
+    ) -> ConnectionResult<()> {
+        // Get handle using read lock - allows concurrent uploads
+        let handle_guard = self.handle.read().await;
+        let handle = handle_guard
+            .as_ref()
+            .ok_or_else(|| ConnectionError::ConnectionClosed)?;
+
+        // Open SFTP session (while holding read lock)
+        let sftp = Self::open_sftp(handle).await?;
+
+        // Release the read lock immediately after opening SFTP session
+        drop(handle_guard);
+
+        // Create parent directories if needed
+        if options.create_dirs {
+            if let Some(parent) = remote_path.parent() {
+                Self::create_remote_dirs_sftp(&sftp, parent).await?;
+            }
+        }
+
+        // Create/open remote file for writing
+        let remote_path_str = remote_path.to_string_lossy().to_string();
+        let mut remote_file = sftp.create(&remote_path_str).await.map_err(|e| {
+            ConnectionError::TransferFailed(format!(
+                "Failed to create remote file {}: {}",
+                remote_path.display(),
+                e
+            ))
+        })?;
+
+        // Write content to remote file
+        remote_file.write_all(content).await.map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to write to remote file: {}", e))
+        })?;
+
+        // Close the file
+        drop(remote_file);
+
+        // Set permissions using setstat
+        if let Some(mode) = options.mode {
+            let mut attrs = russh_sftp::protocol::FileAttributes::default();
+            attrs.permissions = Some(mode);
+            sftp.set_metadata(&remote_path_str, attrs)
+                .await
+                .map_err(|e| {
+                    ConnectionError::TransferFailed(format!(
+                        "Failed to set file permissions: {}",
+                        e
+                    ))
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Write content to a remote file with `scp -t`
+    async fn upload_content_scp(
+        &self,
+        content: &[u8],
+        remote_path: &Path,
+        options: &TransferOptions,
+    ) -> ConnectionResult<()> {
+        let escaped_path = escape_shell_arg(&remote_path.to_string_lossy());
+
+        // Create parent directories if needed
+        if options.create_dirs {
+            if let Some(parent) = remote_path.parent() {
+                let mkdir_cmd = format!("mkdir -p {}", escape_shell_arg(&parent.to_string_lossy()));
+                let result = self.execute(&mkdir_cmd, None).await?;
+                if !result.success {
+                    return Err(ConnectionError::TransferFailed(format!(
+                        "Failed to create remote directory {}: {}",
+                        parent.display(),
+                        result.stderr
+                    )));
+                }
+            }
+        }
+
+        let channel = self
+            .open_exec_channel(&transfer::scp_sink_command(remote_path))
+            .await?;
+        let mut stream = channel.into_stream();
+        transfer::scp_send(
+            &mut stream,
+            remote_path,
+            options.mode.unwrap_or(0o644),
+            content,
+        )
+        .await?;
+
+        // scp only applies the mode to files it creates
+        if let Some(mode) = options.mode {
+            let chmod_cmd = format!("chmod {:o} {}", mode, escaped_path);
+            let result = self.execute(&chmod_cmd, None).await?;
+            if !result.success {
+                return Err(ConnectionError::TransferFailed(format!(
+                    "Failed to set file permissions: {}",
+                    result.stderr
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read a remote file over SFTP
+    async fn download_content_sftp(&self, remote_path: &Path) -> ConnectionResult<Vec<u8>> {
+        // Get handle using read lock - allows concurrent downloads
+        let handle_guard = self.handle.read().await;
+        let handle = handle_guard
+            .as_ref()
+            .ok_or_else(|| ConnectionError::ConnectionClosed)?;
+
+        // Open SFTP session (while holding read lock)
+        let sftp = Self::open_sftp(handle).await?;
+
+        // Release the read lock immediately after opening SFTP session
+        drop(handle_guard);
+
+        // Open remote file for reading
+        let remote_path_str = remote_path.to_string_lossy().to_string();
+        let mut remote_file = sftp.open(&remote_path_str).await.map_err(|e| {
+            ConnectionError::TransferFailed(format!(
+                "Failed to open remote file {}: {}",
+                remote_path.display(),
+                e
+            ))
+        })?;
+
+        // Read content from remote file
+        let mut content = Vec::new();
+        remote_file.read_to_end(&mut content).await.map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to read remote file: {}", e))
+        })?;
+
+        Ok(content)
+    }
+
+    /// Read a remote file with `scp -f`
+    async fn download_content_scp(&self, remote_path: &Path) -> ConnectionResult<Vec<u8>> {
+        let channel = self
+            .open_exec_channel(&transfer::scp_source_command(remote_path))
+            .await?;
+        let mut stream = channel.into_stream();
+        let mut content = Vec::new();
+        transfer::scp_receive(&mut stream, &mut content)
+            .await
+            .map_err(|e| {
+                ConnectionError::TransferFailed(format!(
+                    "Failed to download remote file {}: {}",
+                    remote_path.display(),
+                    e
+                ))
+            })?;
+
+        Ok(content)
     }
 
     /// Create remote directories recursively via SFTP
@@ -796,12 +970,31 @@ impl RusshConnection {
 
 impl RusshConnection {
     /// Connect to a remote host via SSH using russh
+    ///
+    /// Hosts with a `proxy_jump` are reached through their jump host chain.
     pub async fn connect(
         host: &str,
         port: u16,
         user: &str,
         host_config: Option<HostConfig>,
         global_config: &ConnectionConfig,
+    ) -> ConnectionResult<Self> {
+        let host_config = host_config.unwrap_or_else(|| global_config.get_host_merged(host));
+        let jumps = JumpHostResolver::new(global_config).resolve_from_config(&host_config)?;
+        Self::connect_through(host, port, user, Some(host_config), global_config, &jumps).await
+    }
+
+    /// Connect to a remote host through a chain of jump hosts
+    ///
+    /// Each hop opens a `direct-tcpip` channel on the previous one and runs
+    /// the next SSH handshake over it; an empty chain connects directly.
+    pub async fn connect_through(
+        host: &str,
+        port: u16,
+        user: &str,
+        host_config: Option<HostConfig>,
+        global_config: &ConnectionConfig,
+        jumps: &JumpHostChain,
     ) -> ConnectionResult<Self> {
         let host_config = host_config.unwrap_or_else(|| global_config.get_host_merged(host));
         let retry_config = host_config.retry_config();
@@ -815,18 +1008,20 @@ impl RusshConnection {
             host = %actual_host,
             port = %actual_port,
             user = %actual_user,
+            jumps = %jumps,
             "Connecting via SSH (russh)"
         );
 
         let identifier = format!("{}@{}:{}", actual_user, actual_host, actual_port);
 
         // Connect with retry logic
-        let handle = Self::connect_with_retry(
+        let (handle, jump_sessions) = Self::connect_with_retry(
             actual_host,
             actual_port,
             actual_user,
             &host_config,
             global_config,
+            jumps,
             timeout,
             &retry_config,
         )
@@ -841,6 +1036,7 @@ impl RusshConnection {
         let conn = Self {
             identifier,
             handle: Arc::new(RwLock::new(Some(handle))),
+            jump_sessions,
             host_config,
             connected: Arc::new(AtomicBool::new(true)),
             last_keepalive: AtomicU64::new(0),
@@ -859,15 +1055,17 @@ impl RusshConnection {
     }
 
     /// Connect with retry logic
+    #[allow(clippy::too_many_arguments)]
     async fn connect_with_retry(
         host: &str,
         port: u16,
         user: &str,
         host_config: &HostConfig,
         global_config: &ConnectionConfig,
+        jumps: &JumpHostChain,
         timeout: Duration,
         retry_config: &RetryConfig,
-    ) -> ConnectionResult<Handle<ClientHandler>> {
+    ) -> ConnectionResult<(Handle<ClientHandler>, Vec<Handle<ClientHandler>>)> {
         let mut last_error = None;
 
         for attempt in 0..=retry_config.max_retries {
@@ -877,10 +1075,15 @@ impl RusshConnection {
                 tokio::time::sleep(delay).await;
             }
 
-            match Self::do_connect(host, port, user, host_config, global_config, timeout).await {
-                Ok(handle) => return Ok(handle),
+            match Self::do_connect(host, port, user, host_config, global_config, jumps, timeout)
+                .await
+            {
+                Ok(sessions) => return Ok(sessions),
                 Err(e) => {
                     warn!(attempt = %attempt, error = %e, "SSH connection attempt failed");
+                    if !retry_config.should_retry(attempt, &e) {
+                        return Err(e.with_auth_context(user, host_config.identity_file.as_deref()));
+                    }
                     last_error = Some(e);
                 }
             }
@@ -891,16 +1094,8 @@ impl RusshConnection {
         }))
     }
 
-    /// Perform the actual connection
-    async fn do_connect(
-        host: &str,
-        port: u16,
-        user: &str,
-        host_config: &HostConfig,
-        global_config: &ConnectionConfig,
-        timeout: Duration,
-    ) -> ConnectionResult<Handle<ClientHandler>> {
-        // Create optimized russh client configuration
+    /// Optimized russh client configuration
+    fn client_config(timeout: Duration) -> Arc<russh::client::Config> {
         let mut config = russh::client::Config::default();
         config.inactivity_timeout = Some(timeout);
         // Optimize preferred algorithms for faster negotiation
@@ -927,9 +1122,99 @@ impl RusshConnection {
             // No compression for speed
             compression: std::borrow::Cow::Borrowed(&[russh::compression::NONE]),
         };
-        let config = Arc::new(config);
+        Arc::new(config)
+    }
+
+    /// Perform the actual connection, hopping through `jumps` first
+    ///
+    /// Returns the target session and the jump host sessions carrying it,
+    /// which must stay open for as long as the target session is used.
+    async fn do_connect(
+        host: &str,
+        port: u16,
+        user: &str,
+        host_config: &HostConfig,
+        global_config: &ConnectionConfig,
+        jumps: &JumpHostChain,
+        timeout: Duration,
+    ) -> ConnectionResult<(Handle<ClientHandler>, Vec<Handle<ClientHandler>>)> {
+        let config = Self::client_config(timeout);
+
+        let mut jump_sessions: Vec<Handle<ClientHandler>> = Vec::with_capacity(jumps.len());
+        for (hop, jump) in jumps.iter().enumerate() {
+            let jump_config = Self::jump_host_config(jump, host_config, global_config);
+            let jump_host = jump_config.hostname.as_deref().unwrap_or(&jump.host);
+            let jump_user = jump
+                .user
+                .as_deref()
+                .or(jump_config.user.as_deref())
+                .unwrap_or(user);
+
+            let session = match jump_sessions.last() {
+                None => match Self::open_socket(jump_host, jump.port, timeout).await {
+                    Ok(socket) => {
+                        Self::handshake(
+                            config.clone(),
+                            socket,
+                            jump_host,
+                            jump.port,
+                            jump_user,
+                            &jump_config,
+                            global_config,
+                        )
+                        .await
+                    }
+                    Err(e) => Err(e),
+                },
+                Some(previous) => {
+                    let via = &jumps.as_slice()[hop - 1];
+                    match Self::open_tunnel(previous, via, jump_host, jump.port, timeout).await {
+                        Ok(stream) => {
+                            Self::handshake(
+                                config.clone(),
+                                stream,
+                                jump_host,
+                                jump.port,
+                                jump_user,
+                                &jump_config,
+                                global_config,
+                            )
+                            .await
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+            };
+            let session = session.map_err(|e| {
+                ConnectionError::ConnectionFailed(format!("Jump host {} failed: {}", jump, e))
+            })?;
+            debug!(jump = %jump, "Connected to jump host");
+            jump_sessions.push(session);
+        }
+
+        let session = match (jump_sessions.last(), jumps.as_slice().last()) {
+            (Some(previous), Some(via)) => {
+                let stream = Self::open_tunnel(previous, via, host, port, timeout).await?;
+                Self::handshake(config, stream, host, port, user, host_config, global_config)
+                    .await?
+            }
+            _ => {
+                let socket = Self::open_socket(host, port, timeout).await?;
+                Self::handshake(config, socket, host, port, user, host_config, global_config)
+                    .await?
+            }
+        };
 
-        // Connect to the SSH server
+        debug!("SSH connection established successfully");
+        Ok((session, jump_sessions))
+    }
+
+    /// Open a TCP connection to the SSH server
+    async fn open_socket(
+        host: &str,
+        port: u16,
+        timeout: Duration,
+    ) -> ConnectionResult<tokio::net::TcpStream> {
         let addr = format!("{}:{}", host, port);
         let socket = tokio::time::timeout(timeout, tokio::net::TcpStream::connect(&addr))
             .await
@@ -942,12 +1227,51 @@ impl RusshConnection {
         socket.set_nodelay(true).map_err(|e| {
             ConnectionError::ConnectionFailed(format!("Failed to set TCP_NODELAY: {}", e))
         })?;
+        Ok(socket)
+    }
+
+    /// Open a `direct-tcpip` channel to `host:port` on the jump host session `via`
+    async fn open_tunnel(
+        session: &Handle<ClientHandler>,
+        via: &JumpHostConfig,
+        host: &str,
+        port: u16,
+        timeout: Duration,
+    ) -> ConnectionResult<russh::ChannelStream<russh::client::Msg>> {
+        let channel = tokio::time::timeout(
+            timeout,
+            session.channel_open_direct_tcpip(host, u32::from(port), "127.0.0.1", 0),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(timeout.as_secs()))?
+        .map_err(|e| {
+            ConnectionError::ConnectionFailed(format!(
+                "Jump host {} could not open a tunnel to {}:{}: {}",
+                via, host, port, e
+            ))
+        })?;
+        Ok(channel.into_stream())
+    }
 
+    /// Run the SSH handshake over `stream` and authenticate
+    #[allow(clippy::too_many_arguments)]
+    async fn handshake<S>(
+        config: Arc<russh::client::Config>,
+        stream: S,
+        host: &str,
+        port: u16,
+        user: &str,
+        host_config: &HostConfig,
+        global_config: &ConnectionConfig,
+    ) -> ConnectionResult<Handle<ClientHandler>>
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+    {
         // Create client handler with host key verification
         // Accept unknown hosts by default (like StrictHostKeyChecking=accept-new)
         let handler = ClientHandler::new(host, port, true);
 
-        let mut session = russh::client::connect_stream(config, socket, handler)
+        let mut session = russh::client::connect_stream(config, stream, handler)
             .await
             .map_err(|e| {
                 ConnectionError::ConnectionFailed(format!("SSH handshake failed: {}", e))
@@ -955,26 +1279,40 @@ impl RusshConnection {
 
         // Authenticate
         Self::authenticate(&mut session, user, host_config, global_config).await?;
-
-        debug!("SSH connection established successfully");
         Ok(session)
     }
 
+    /// Settings used to authenticate to a jump host
+    ///
+    /// Starts from the jump host's own SSH config entry; without an identity
+    /// file of its own, the jump host is tried with the target's key.
+    fn jump_host_config(
+        jump: &JumpHostConfig,
+        target: &HostConfig,
+        global_config: &ConnectionConfig,
+    ) -> HostConfig {
+        let mut config = global_config
+            .get_host(&jump.host)
+            .cloned()
+            .unwrap_or_default();
+        config.identity_file = jump
+            .identity_file
+            .clone()
+            .or(config.identity_file)
+            .or_else(|| target.identity_file.clone());
+        config
+    }
+
     /// Perform SSH authentication
+    ///
+    /// A configured identity file is tried first; without one, the SSH agent
+    /// is the first choice. Default keys and the password come last.
     async fn authenticate(
         session: &mut Handle<ClientHandler>,
         user: &str,
         host_config: &HostConfig,
         global_config: &ConnectionConfig,
     ) -> ConnectionResult<()> {
-        // Try SSH agent first if enabled
-        if global_config.defaults.use_agent {
-            if Self::try_agent_auth(session, user).await.is_ok() {
-                debug!("Authenticated using SSH agent");
-                return Ok(());
-            }
-        }
-
         // Try key-based authentication
         // 1. Try specific identity file if configured
         if let Some(identity_file) = &host_config.identity_file {
@@ -988,6 +1326,18 @@ impl RusshConnection {
             }
         }
 
+        // Then the SSH agent, if enabled
+        if global_config.defaults.use_agent {
+            match Self::try_agent_auth(session, user).await {
+                Ok(true) => {
+                    debug!("Authenticated using SSH agent");
+                    return Ok(());
+                }
+                Ok(false) => debug!("No SSH agent identity was accepted"),
+                Err(e) => warn!(error = %e, "SSH agent unavailable, falling back to key files"),
+            }
+        }
+
         // 2. Try default identity files from global config
         for identity_file in &global_config.defaults.identity_files {
             let key_path = expand_path(identity_file);
@@ -1038,10 +1388,12 @@ impl RusshConnection {
     ///
     /// Connects to the SSH agent via SSH_AUTH_SOCK environment variable,
     /// retrieves available identities, and attempts authentication with each.
+    /// Returns `Ok(false)` when the agent has no identity the server accepts,
+    /// and an error only when the agent itself cannot be used.
     async fn try_agent_auth(
         session: &mut Handle<ClientHandler>,
         user: &str,
-    ) -> ConnectionResult<()> {
+    ) -> ConnectionResult<bool> {
         // Connect to SSH agent using SSH_AUTH_SOCK environment variable
         let mut agent = AgentClient::connect_env().await.map_err(|e| {
             ConnectionError::AuthenticationFailed(format!("Failed to connect to SSH agent: {}", e))
@@ -1052,12 +1404,6 @@ impl RusshConnection {
             ConnectionError::AuthenticationFailed(format!("Failed to get agent identities: {}", e))
         })?;
 
-        if identities.is_empty() {
-            return Err(ConnectionError::AuthenticationFailed(
-                "SSH agent has no identities".to_string(),
-            ));
-        }
-
         debug!(identity_count = %identities.len(), "Found SSH agent identities");
 
         // Try each identity until one works
@@ -1074,7 +1420,7 @@ impl RusshConnection {
             match result {
                 Ok(true) => {
                     debug!("SSH agent authentication successful");
-                    return Ok(());
+                    return Ok(true);
                 }
                 Ok(false) => {
                     // Key was rejected, try the next one
@@ -1087,9 +1433,7 @@ impl RusshConnection {
             }
         }
 
-        Err(ConnectionError::AuthenticationFailed(
-            "All SSH agent identities rejected".to_string(),
-        ))
+        Ok(false)
     }
 
     /// Try key-based authentication
@@ -1296,33 +1640,12 @@ impl Connection for RusshConnection {
         remote_path: &Path,
         options: Option<TransferOptions>,
     ) -> ConnectionResult<()> {
-        let options = options.unwrap_or_default();
-
         debug!(
             local = %local_path.display(),
             remote = %remote_path.display(),
-            "Uploading file via SFTP"
+            "Uploading file"
         );
 
-        // Get handle using read lock - allows concurrent uploads
-        let handle_guard = self.handle.read().await;
-        let handle = handle_guard
-            .as_ref()
-            .ok_or_else(|| ConnectionError::ConnectionClosed)?;
-
-        // Open SFTP session (while holding read lock)
-        let sftp = Self::open_sftp(handle).await?;
-
-        // Release the read lock immediately after opening SFTP session
-        drop(handle_guard);
-
-        // Create parent directories if needed
-        if options.create_dirs {
-            if let Some(parent) = remote_path.parent() {
-                Self::create_remote_dirs_sftp(&sftp, parent).await?;
-            }
-        }
-
         // Read local file
         let content = tokio::fs::read(local_path).await.map_err(|e| {
             ConnectionError::TransferFailed(format!(
@@ -1332,62 +1655,7 @@ impl Connection for RusshConnection {
             ))
         })?;
 
-        // Create/open remote file for writing
-        let remote_path_str = remote_path.to_string_lossy().to_string();
-        let mut remote_file = sftp.create(&remote_path_str).await.map_err(|e| {
-            ConnectionError::TransferFailed(format!(
-                "Failed to create remote file {}: {}",
-                remote_path.display(),
-                e
-            ))
-        })?;
-
-        // Write content to remote file
-        remote_file.write_all(&content).await.map_err(|e| {
-            ConnectionError::TransferFailed(format!("Failed to write to remote file: {}", e))
-        })?;
-
-        // Close the file
-        drop(remote_file);
-
-        // Set permissions using setstat
-        if let Some(mode) = options.mode {
-            let mut attrs = russh_sftp::protocol::FileAttributes::default();
-            attrs.permissions = Some(mode);
-            sftp.set_metadata(&remote_path_str, attrs)
-                .await
-                .map_err(|e| {
-                    ConnectionError::TransferFailed(format!(
-                        "Failed to set file permissions: {}",
-                        e
-                    ))
-                })?;
-        }
-
-        // Drop the SFTP session before using execute()
-        drop(sftp);
-
-        // Set owner/group if specified using chown command
-        if options.owner.is_some() || options.group.is_some() {
-            let escaped_path = escape_shell_arg(&remote_path.to_string_lossy());
-            let owner_group = match (&options.owner, &options.group) {
-                (Some(owner), Some(group)) => format!("{}:{}", owner, group),
-                (Some(owner), None) => owner.clone(),
-                (None, Some(group)) => format!(":{}", group),
-                (None, None) => unreachable!(),
-            };
-            let chown_cmd = format!("chown {} {}", owner_group, escaped_path);
-            let result = self.execute(&chown_cmd, None).await?;
-            if !result.success {
-                warn!(
-                    "Failed to set owner/group on {}: {}",
-                    remote_path.display(),
-                    result.stderr
-                );
-            }
-        }
-
-        Ok(())
+        self.upload_content(&content, remote_path, options).await
     }
 
     async fn upload_content(
@@ -1397,66 +1665,25 @@ impl Connection for RusshConnection {
         options: Option<TransferOptions>,
     ) -> ConnectionResult<()> {
         let options = options.unwrap_or_default();
+        let method = self.transfer_method();
 
         debug!(
             remote = %remote_path.display(),
             size = %content.len(),
-            "Uploading content via SFTP"
+            method = %method,
+            "Uploading content"
         );
 
-        // Get handle using read lock - allows concurrent uploads
-        let handle_guard = self.handle.read().await;
-        let handle = handle_guard
-            .as_ref()
-            .ok_or_else(|| ConnectionError::ConnectionClosed)?;
-
-        // Open SFTP session (while holding read lock)
-        let sftp = Self::open_sftp(handle).await?;
-
-        // Release the read lock immediately after opening SFTP session
-        drop(handle_guard);
-
-        // Create parent directories if needed
-        if options.create_dirs {
-            if let Some(parent) = remote_path.parent() {
-                Self::create_remote_dirs_sftp(&sftp, parent).await?;
-            }
-        }
-
-        // Create/open remote file for writing
-        let remote_path_str = remote_path.to_string_lossy().to_string();
-        let mut remote_file = sftp.create(&remote_path_str).await.map_err(|e| {
-            ConnectionError::TransferFailed(format!(
-                "Failed to create remote file {}: {}",
-                remote_path.display(),
-                e
-            ))
-        })?;
-
-        // Write content to remote file
-        remote_file.write_all(content).await.map_err(|e| {
-            ConnectionError::TransferFailed(format!("Failed to write to remote file: {}", e))
-        })?;
-
-        // Close the file
-        drop(remote_file);
-
-        // Set permissions using setstat
-        if let Some(mode) = options.mode {
-            let mut attrs = russh_sftp::protocol::FileAttributes::default();
-            attrs.permissions = Some(mode);
-            sftp.set_metadata(&remote_path_str, attrs)
-                .await
-                .map_err(|e| {
-                    ConnectionError::TransferFailed(format!(
-                        "Failed to set file permissions: {}",
-                        e
-                    ))
-                })?;
-        }
-
-        // Drop the SFTP session before using execute()
-        drop(sftp);
+        let opts = &options;
+        let start = Instant::now();
+        transfer::with_scp_fallback(
+            method,
+            &self.identifier,
+            move || self.upload_content_sftp(content, remote_path, opts),
+            move || self.upload_content_scp(content, remote_path, opts),
+        )
+        .await?;
+        TransferStats::since(content.len() as u64, start).log("Upload", remote_path);
 
         // Set owner/group if specified using chown command
         if options.owner.is_some() || options.group.is_some() {
@@ -1485,36 +1712,10 @@ impl Connection for RusshConnection {
         debug!(
             remote = %remote_path.display(),
             local = %local_path.display(),
-            "Downloading file via russh SFTP"
+            "Downloading file"
         );
 
-        // Get handle using read lock - allows concurrent downloads
-        let handle_guard = self.handle.read().await;
-        let handle = handle_guard
-            .as_ref()
-            .ok_or_else(|| ConnectionError::ConnectionClosed)?;
-
-        // Open SFTP session (while holding read lock)
-        let sftp = Self::open_sftp(handle).await?;
-
-        // Release the read lock immediately after opening SFTP session
-        drop(handle_guard);
-
-        // Open remote file for reading
-        let remote_path_str = remote_path.to_string_lossy().to_string();
-        let mut remote_file = sftp.open(&remote_path_str).await.map_err(|e| {
-            ConnectionError::TransferFailed(format!(
-                "Failed to open remote file {}: {}",
-                remote_path.display(),
-                e
-            ))
-        })?;
-
-        // Read content from remote file
-        let mut content = Vec::new();
-        remote_file.read_to_end(&mut content).await.map_err(|e| {
-            ConnectionError::TransferFailed(format!("Failed to read remote file: {}", e))
-        })?;
+        let content = self.download_content(remote_path).await?;
 
         // Create parent directories for local file
         if let Some(parent) = local_path.parent() {
@@ -1541,37 +1742,19 @@ impl Connection for RusshConnection {
     }
 
     async fn download_content(&self, remote_path: &Path) -> ConnectionResult<Vec<u8>> {
-        debug!(remote = %remote_path.display(), "Downloading content via russh SFTP");
-
-        // Get handle using read lock - allows concurrent downloads
-        let handle_guard = self.handle.read().await;
-        let handle = handle_guard
-            .as_ref()
-            .ok_or_else(|| ConnectionError::ConnectionClosed)?;
-
-        // Open SFTP session (while holding read lock)
-        let sftp = Self::open_sftp(handle).await?;
-
-        // Release the read lock immediately after opening SFTP session
-        drop(handle_guard);
-
-        // Open remote file for reading
-        let remote_path_str = remote_path.to_string_lossy().to_string();
-        let mut remote_file = sftp.open(&remote_path_str).await.map_err(|e| {
-            ConnectionError::TransferFailed(format!(
-                "Failed to open remote file {}: {}",
-                remote_path.display(),
-                e
-            ))
-        })?;
+        let method = self.transfer_method();
+        debug!(remote = %remote_path.display(), method = %method, "Downloading content");
 
-        // Read content from remote file
-        let mut content = Vec::new();
-        remote_file.read_to_end(&mut content).await.map_err(|e| {
-            ConnectionError::TransferFailed(format!("Failed to read remote file: {}", e))
-        })?;
+        let start = Instant::now();
+        let content = transfer::with_scp_fallback(
+            method,
+            &self.identifier,
+            move || self.download_content_sftp(remote_path),
+            move || self.download_content_scp(remote_path),
+        )
+        .await?;
 
-        debug!(size = %content.len(), "Content download completed successfully");
+        TransferStats::since(content.len() as u64, start).log("Download", remote_path);
         Ok(content)
     }
 
@@ -1712,9 +1895,24 @@ impl Connection for RusshConnection {
                 .await;
         }
 
+        // Then tear down the tunnel, innermost hop first
+        for session in self.jump_sessions.iter().rev() {
+            let _ = session
+                .disconnect(
+                    russh::Disconnect::ByApplication,
+                    "Connection closed by client",
+                    "en",
+                )
+                .await;
+        }
+
         Ok(())
     }
 
+    fn supports_atomic_rename(&self) -> bool {
+        true
+    }
+
     /// Execute multiple commands in batch with channel multiplexing
     ///
     /// Overrides the default sequential implementation to use SSH channel
@@ -2010,6 +2208,10 @@ pub struct RusshConnectionBuilder {
     pub timeout: Option<u64>,
     /// Enable compression
     pub compression: bool,
+    /// Jump hosts to tunnel through, as `[user@]host[:port],...` (optional)
+    pub proxy_jump: Option<String>,
+    /// Try SSH agent identities (default: true)
+    pub use_agent: bool,
 }
 
 impl RusshConnectionBuilder {
@@ -2023,6 +2225,8 @@ impl RusshConnectionBuilder {
             private_key: None,
             timeout: Some(30),
             compression: false,
+            proxy_jump: None,
+            use_agent: true,
         }
     }
 
@@ -2062,6 +2266,18 @@ impl RusshConnectionBuilder {
         self
     }
 
+    /// Connect through jump hosts
+    pub fn proxy_jump(mut self, spec: impl Into<String>) -> Self {
+        self.proxy_jump = Some(spec.into());
+        self
+    }
+
+    /// Enable or disable SSH agent authentication
+    pub fn use_agent(mut self, enabled: bool) -> Self {
+        self.use_agent = enabled;
+        self
+    }
+
     /// Build and connect
     pub async fn connect(self) -> ConnectionResult<RusshConnection> {
         let host_config = HostConfig {
@@ -2072,10 +2288,12 @@ impl RusshConnectionBuilder {
             identity_file: self.private_key,
             connect_timeout: self.timeout,
             compression: self.compression,
+            proxy_jump: self.proxy_jump,
             ..Default::default()
         };
 
-        let config = ConnectionConfig::default();
+        let mut config = ConnectionConfig::default();
+        config.set_use_agent(self.use_agent);
         RusshConnection::connect(
             &self.host,
             self.port,
@@ -3618,6 +3836,10 @@ mod tests {
         assert_eq!(builder.port, 2222);
         assert_eq!(builder.user, "admin");
         assert!(builder.compression);
+        assert!(builder.use_agent);
+
+        let builder = RusshConnectionBuilder::new("example.com").use_agent(false);
+        assert!(!builder.use_agent);
     }
 
     #[test]
@@ -11,13 +11,14 @@ use std::io::{Read, Write};
 use std::net::TcpStream;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::task;
 use tracing::{debug, trace, warn};
 
 use super::config::{
     default_identity_files, expand_path, ConnectionConfig, HostConfig, RetryConfig,
 };
+use super::transfer::{TransferMethod, TransferStats};
 use super::{
     CommandResult, Connection, ConnectionError, ConnectionResult, ExecuteOptions, FileStat,
     TransferOptions,
@@ -116,6 +117,9 @@ impl SshConnection {
                 Ok(session) => return Ok(session),
                 Err(e) => {
                     warn!(attempt = %attempt, error = %e, "SSH connection attempt failed");
+                    if !retry_config.should_retry(attempt, &e) {
+                        return Err(e.with_auth_context(user, host_config.identity_file.as_deref()));
+                    }
                     last_error = Some(e);
                 }
             }
@@ -391,41 +395,164 @@ impl SshConnection {
             parts.push(format!("cd {} && ", cwd));
         }
 
-        // Handle privilege escalation
-        if options.escalate {
-            let escalate_method = options.escalate_method.as_deref().unwrap_or("sudo");
-            let escalate_user = options.escalate_user.as_deref().unwrap_or("root");
-
-            match escalate_method {
-                "sudo" => {
-                    if options.escalate_password.is_some() {
-                        parts.push(format!("sudo -S -u {} -- ", escalate_user));
-                    } else {
-                        parts.push(format!("sudo -u {} -- ", escalate_user));
-                    }
-                }
-                "su" => {
-                    parts.push(format!("su - {} -c ", escalate_user));
-                }
-                "doas" => {
-                    parts.push(format!("doas -u {} ", escalate_user));
-                }
-                _ => {
-                    parts.push(format!("sudo -u {} -- ", escalate_user));
-                }
-            }
-        }
-
-        parts.push(command.to_string());
+        parts.push(options.become_command(command));
         parts.concat()
     }
 
     /// Get SFTP session
     fn get_sftp(session: &Session) -> ConnectionResult<Sftp> {
-        session.sftp().map_err(|e| {
-            ConnectionError::TransferFailed(format!("Failed to open SFTP channel: {}", e))
+        session
+            .sftp()
+            .map_err(|e| ConnectionError::SftpUnavailable(e.to_string()))
+    }
+
+    /// Transfer method configured for this host
+    fn transfer_method(&self) -> TransferMethod {
+        self.host_config.transfer_method.unwrap_or_default()
+    }
+
+    /// Blocking counterpart of [`super::transfer::with_scp_fallback`]
+    fn with_scp_fallback<T>(
+        method: TransferMethod,
+        target: &str,
+        sftp: impl FnOnce() -> ConnectionResult<T>,
+        scp: impl FnOnce() -> ConnectionResult<T>,
+    ) -> ConnectionResult<T> {
+        if method == TransferMethod::Scp {
+            return scp();
+        }
+
+        match sftp() {
+            Err(e) if method.falls_back_on(&e) => {
+                warn!(target = %target, error = %e, "SFTP unavailable, retrying transfer over SCP");
+                scp()
+            }
+            result => result,
+        }
+    }
+
+    /// Write content to a remote file over SFTP
+    fn write_file_sftp(
+        session: &Session,
+        content: &[u8],
+        remote_path: &Path,
+        create_dirs: bool,
+    ) -> ConnectionResult<()> {
+        let sftp = Self::get_sftp(session)?;
+
+        // Create parent directories if needed
+        if create_dirs {
+            if let Some(parent) = remote_path.parent() {
+                Self::create_remote_dirs(&sftp, parent)?;
+            }
+        }
+
+        let mut remote_file = sftp.create(remote_path).map_err(|e| {
+            ConnectionError::TransferFailed(format!(
+                "Failed to create remote file {}: {}",
+                remote_path.display(),
+                e
+            ))
+        })?;
+
+        remote_file.write_all(content).map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to write to remote file: {}", e))
         })
     }
+
+    /// Write content to a remote file over SCP
+    fn write_file_scp(
+        session: &Session,
+        content: &[u8],
+        remote_path: &Path,
+        mode: u32,
+        create_dirs: bool,
+    ) -> ConnectionResult<()> {
+        // Create parent directories if needed
+        if create_dirs {
+            if let Some(parent) = remote_path.parent() {
+                let result = Self::exec_sync(
+                    session,
+                    &format!("mkdir -p {}", shell_words::quote(&parent.to_string_lossy())),
+                    &ExecuteOptions::default(),
+                )?;
+                if !result.success {
+                    return Err(ConnectionError::TransferFailed(format!(
+                        "Failed to create remote directory {}: {}",
+                        parent.display(),
+                        result.stderr
+                    )));
+                }
+            }
+        }
+
+        let mut channel = session
+            .scp_send(
+                remote_path,
+                (mode & 0o7777) as i32,
+                content.len() as u64,
+                None,
+            )
+            .map_err(|e| {
+                ConnectionError::TransferFailed(format!(
+                    "Failed to start SCP upload to {}: {}",
+                    remote_path.display(),
+                    e
+                ))
+            })?;
+
+        channel.write_all(content).map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to write to remote file: {}", e))
+        })?;
+
+        channel.send_eof().ok();
+        channel.wait_eof().ok();
+        channel.close().ok();
+        channel.wait_close().ok();
+        Ok(())
+    }
+
+    /// Read a remote file over SFTP
+    fn read_file_sftp(session: &Session, remote_path: &Path) -> ConnectionResult<Vec<u8>> {
+        let sftp = Self::get_sftp(session)?;
+
+        let mut remote_file = sftp.open(remote_path).map_err(|e| {
+            ConnectionError::TransferFailed(format!(
+                "Failed to open remote file {}: {}",
+                remote_path.display(),
+                e
+            ))
+        })?;
+
+        let mut content = Vec::new();
+        remote_file.read_to_end(&mut content).map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to read remote file: {}", e))
+        })?;
+
+        Ok(content)
+    }
+
+    /// Read a remote file over SCP
+    fn read_file_scp(session: &Session, remote_path: &Path) -> ConnectionResult<Vec<u8>> {
+        let (mut channel, stat) = session.scp_recv(remote_path).map_err(|e| {
+            ConnectionError::TransferFailed(format!(
+                "Failed to open remote file {}: {}",
+                remote_path.display(),
+                e
+            ))
+        })?;
+
+        let mut content = Vec::with_capacity(stat.size() as usize);
+        channel.read_to_end(&mut content).map_err(|e| {
+            ConnectionError::TransferFailed(format!("Failed to read remote file: {}", e))
+        })?;
+
+        channel.send_eof().ok();
+        channel.wait_eof().ok();
+        channel.close().ok();
+        channel.wait_close().ok();
+        Ok(content)
+    }
 }
 
 #[async_trait]
@@ -490,85 +617,22 @@ impl Connection for SshConnection {
         remote_path: &Path,
         options: Option<TransferOptions>,
     ) -> ConnectionResult<()> {
-        let options = options.unwrap_or_default();
-        let session = self.session.clone();
-        let local_path = local_path.to_path_buf();
-        let remote_path = remote_path.to_path_buf();
-
         debug!(
             local = %local_path.display(),
             remote = %remote_path.display(),
-            "Uploading file via SFTP"
+            "Uploading file"
         );
 
-        task::spawn_blocking(move || {
-            let session = session.lock();
-            let sftp = Self::get_sftp(&session)?;
-
-            // Create parent directories if needed
-            if options.create_dirs {
-                if let Some(parent) = remote_path.parent() {
-                    Self::create_remote_dirs(&sftp, parent)?;
-                }
-            }
-
-            // Read local file
-            let content = std::fs::read(&local_path).map_err(|e| {
-                ConnectionError::TransferFailed(format!(
-                    "Failed to read local file {}: {}",
-                    local_path.display(),
-                    e
-                ))
-            })?;
-
-            // Write to remote file
-            let mode = options.mode.unwrap_or(0o644);
-            let mut remote_file = sftp.create(&remote_path).map_err(|e| {
-                ConnectionError::TransferFailed(format!(
-                    "Failed to create remote file {}: {}",
-                    remote_path.display(),
-                    e
-                ))
-            })?;
-
-            remote_file.write_all(&content).map_err(|e| {
-                ConnectionError::TransferFailed(format!("Failed to write to remote file: {}", e))
-            })?;
-
-            // Set permissions using chmod via SFTP
-            // Note: sftp.stat and chmod aren't directly available, so we'll use exec
-            drop(remote_file);
-            drop(sftp);
-
-            // Set mode via command
-            let chmod_cmd = format!("chmod {:o} {}", mode, remote_path.display());
-            let mut channel = session.channel_session().map_err(|e| {
-                ConnectionError::TransferFailed(format!("Failed to open channel: {}", e))
-            })?;
-            channel.exec(&chmod_cmd).ok();
-            channel.wait_close().ok();
-
-            // Set owner/group if specified
-            if options.owner.is_some() || options.group.is_some() {
-                let ownership = match (&options.owner, &options.group) {
-                    (Some(o), Some(g)) => format!("{}:{}", o, g),
-                    (Some(o), None) => o.to_string(),
-                    (None, Some(g)) => format!(":{}", g),
-                    (None, None) => return Ok(()),
-                };
-
-                let chown_cmd = format!("chown {} {}", ownership, remote_path.display());
-                let mut channel = session.channel_session().map_err(|e| {
-                    ConnectionError::TransferFailed(format!("Failed to open channel: {}", e))
-                })?;
-                channel.exec(&chown_cmd).ok();
-                channel.wait_close().ok();
-            }
+        // Read local file
+        let content = tokio::fs::read(local_path).await.map_err(|e| {
+            ConnectionError::TransferFailed(format!(
+                "Failed to read local file {}: {}",
+                local_path.display(),
+                e
+            ))
+        })?;
 
-            Ok(())
-        })
-        .await
-        .map_err(|e| ConnectionError::TransferFailed(format!("Task join error: {}", e)))?
+        self.upload_content(&content, remote_path, options).await
     }
 
     async fn upload_content(
@@ -581,40 +645,36 @@ impl Connection for SshConnection {
         let session = self.session.clone();
         let content = content.to_vec();
         let remote_path = remote_path.to_path_buf();
+        let method = self.transfer_method();
+        let identifier = self.identifier.clone();
 
         debug!(
             remote = %remote_path.display(),
             size = %content.len(),
-            "Uploading content via SFTP"
+            method = %method,
+            "Uploading content"
         );
 
         task::spawn_blocking(move || {
             let session = session.lock();
-            let sftp = Self::get_sftp(&session)?;
-
-            // Create parent directories if needed
-            if options.create_dirs {
-                if let Some(parent) = remote_path.parent() {
-                    Self::create_remote_dirs(&sftp, parent)?;
-                }
-            }
-
-            // Write to remote file
             let mode = options.mode.unwrap_or(0o644);
-            let mut remote_file = sftp.create(&remote_path).map_err(|e| {
-                ConnectionError::TransferFailed(format!(
-                    "Failed to create remote file {}: {}",
-                    remote_path.display(),
-                    e
-                ))
-            })?;
-
-            remote_file.write_all(&content).map_err(|e| {
-                ConnectionError::TransferFailed(format!("Failed to write to remote file: {}", e))
-            })?;
 
-            drop(remote_file);
-            drop(sftp);
+            let start = Instant::now();
+            Self::with_scp_fallback(
+                method,
+                &identifier,
+                || Self::write_file_sftp(&session, &content, &remote_path, options.create_dirs),
+                || {
+                    Self::write_file_scp(
+                        &session,
+                        &content,
+                        &remote_path,
+                        mode,
+                        options.create_dirs,
+                    )
+                },
+            )?;
+            TransferStats::since(content.len() as u64, start).log("Upload", &remote_path);
 
             // Set mode via command
             let chmod_cmd = format!("chmod {:o} {}", mode, remote_path.display());
@@ -648,86 +708,53 @@ impl Connection for SshConnection {
     }
 
     async fn download(&self, remote_path: &Path, local_path: &Path) -> ConnectionResult<()> {
-        let session = self.session.clone();
-        let remote_path = remote_path.to_path_buf();
-        let local_path = local_path.to_path_buf();
-
         debug!(
             remote = %remote_path.display(),
             local = %local_path.display(),
-            "Downloading file via SFTP"
+            "Downloading file"
         );
 
-        task::spawn_blocking(move || {
-            let session = session.lock();
-            let sftp = Self::get_sftp(&session)?;
+        let content = self.download_content(remote_path).await?;
 
-            // Open remote file
-            let mut remote_file = sftp.open(&remote_path).map_err(|e| {
+        // Create parent directories for local file
+        if let Some(parent) = local_path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
                 ConnectionError::TransferFailed(format!(
-                    "Failed to open remote file {}: {}",
-                    remote_path.display(),
+                    "Failed to create local directory {}: {}",
+                    parent.display(),
                     e
                 ))
             })?;
+        }
 
-            // Read content
-            let mut content = Vec::new();
-            remote_file.read_to_end(&mut content).map_err(|e| {
-                ConnectionError::TransferFailed(format!("Failed to read remote file: {}", e))
-            })?;
-
-            // Create parent directories for local file
-            if let Some(parent) = local_path.parent() {
-                std::fs::create_dir_all(parent).map_err(|e| {
-                    ConnectionError::TransferFailed(format!(
-                        "Failed to create local directory {}: {}",
-                        parent.display(),
-                        e
-                    ))
-                })?;
-            }
-
-            // Write local file
-            std::fs::write(&local_path, &content).map_err(|e| {
-                ConnectionError::TransferFailed(format!(
-                    "Failed to write local file {}: {}",
-                    local_path.display(),
-                    e
-                ))
-            })?;
-
-            Ok(())
+        // Write local file
+        tokio::fs::write(local_path, &content).await.map_err(|e| {
+            ConnectionError::TransferFailed(format!(
+                "Failed to write local file {}: {}",
+                local_path.display(),
+                e
+            ))
         })
-        .await
-        .map_err(|e| ConnectionError::TransferFailed(format!("Task join error: {}", e)))?
     }
 
     async fn download_content(&self, remote_path: &Path) -> ConnectionResult<Vec<u8>> {
         let session = self.session.clone();
         let remote_path = remote_path.to_path_buf();
+        let method = self.transfer_method();
+        let identifier = self.identifier.clone();
 
-        debug!(remote = %remote_path.display(), "Downloading content via SFTP");
+        debug!(remote = %remote_path.display(), method = %method, "Downloading content");
 
         task::spawn_blocking(move || {
             let session = session.lock();
-            let sftp = Self::get_sftp(&session)?;
-
-            // Open remote file
-            let mut remote_file = sftp.open(&remote_path).map_err(|e| {
-                ConnectionError::TransferFailed(format!(
-                    "Failed to open remote file {}: {}",
-                    remote_path.display(),
-                    e
-                ))
-            })?;
-
-            // Read content
-            let mut content = Vec::new();
-            remote_file.read_to_end(&mut content).map_err(|e| {
-                ConnectionError::TransferFailed(format!("Failed to read remote file: {}", e))
-            })?;
-
+            let start = Instant::now();
+            let content = Self::with_scp_fallback(
+                method,
+                &identifier,
+                || Self::read_file_sftp(&session, &remote_path),
+                || Self::read_file_scp(&session, &remote_path),
+            )?;
+            TransferStats::since(content.len() as u64, start).log("Download", &remote_path);
             Ok(content)
         })
         .await
@@ -808,6 +835,10 @@ impl Connection for SshConnection {
         .await
         .map_err(|e| ConnectionError::ConnectionFailed(format!("Task join error: {}", e)))?
     }
+
+    fn supports_atomic_rename(&self) -> bool {
+        true
+    }
 }
 
 impl SshConnection {
@@ -0,0 +1,536 @@
+//! File transfer method selection for SSH connections.
+//!
+//! SSH connections move files over SFTP by default. Some hosts disable the
+//! SFTP subsystem and only allow `scp`, others the reverse, so the transfer
+//! method can be chosen globally or per host:
+//!
+//! - `sftp`: only use SFTP (default)
+//! - `scp`: only use the SCP protocol over an exec channel
+//! - `smart`: try SFTP first and retry over SCP when the server refuses the
+//!   SFTP subsystem
+//!
+//! The SCP helpers in this module speak the classic `scp -t` / `scp -f`
+//! protocol over any bidirectional stream, so backends without native SCP
+//! support can run it on top of a plain exec channel.
+
+use std::fmt;
+use std::future::Future;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tracing::{debug, warn};
+
+use super::{ConnectionError, ConnectionResult};
+
+/// How files are transferred over an SSH connection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferMethod {
+    /// Transfer files over the SFTP subsystem
+    #[default]
+    Sftp,
+    /// Transfer files with the SCP protocol
+    Scp,
+    /// Try SFTP first, falling back to SCP if the subsystem is unavailable
+    Smart,
+}
+
+impl TransferMethod {
+    /// Map the legacy `scp_if_ssh` setting to a transfer method.
+    ///
+    /// Enabling it transfers files over SCP only, as Ansible does.
+    pub fn from_scp_if_ssh(scp_if_ssh: bool) -> Self {
+        if scp_if_ssh {
+            TransferMethod::Scp
+        } else {
+            TransferMethod::Sftp
+        }
+    }
+
+    /// Whether a failed SFTP attempt should be retried over SCP
+    pub fn falls_back_on(&self, error: &ConnectionError) -> bool {
+        *self == TransferMethod::Smart && is_sftp_unavailable(error)
+    }
+}
+
+impl fmt::Display for TransferMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferMethod::Sftp => write!(f, "sftp"),
+            TransferMethod::Scp => write!(f, "scp"),
+            TransferMethod::Smart => write!(f, "smart"),
+        }
+    }
+}
+
+impl FromStr for TransferMethod {
+    type Err = ConnectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "sftp" => Ok(TransferMethod::Sftp),
+            "scp" => Ok(TransferMethod::Scp),
+            "smart" => Ok(TransferMethod::Smart),
+            other => Err(ConnectionError::InvalidConfig(format!(
+                "Unknown transfer method '{}' (expected sftp, scp or smart)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Size and duration of a single file transfer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransferStats {
+    /// Bytes moved
+    pub bytes: u64,
+    /// Time the transfer took
+    pub duration: Duration,
+}
+
+impl TransferStats {
+    /// Stats for `bytes` moved since `start`
+    pub fn since(bytes: u64, start: Instant) -> Self {
+        Self {
+            bytes,
+            duration: start.elapsed(),
+        }
+    }
+
+    /// Average throughput in bytes per second
+    pub fn bytes_per_sec(&self) -> f64 {
+        // Tiny transfers can finish within the clock's resolution
+        let secs = self.duration.as_secs_f64().max(1e-6);
+        self.bytes as f64 / secs
+    }
+
+    /// Log the transfer at debug level
+    pub fn log(&self, direction: &str, path: &Path) {
+        debug!(
+            path = %path.display(),
+            bytes = self.bytes,
+            duration_ms = self.duration.as_secs_f64() * 1000.0,
+            bytes_per_sec = self.bytes_per_sec().round(),
+            "{} finished",
+            direction
+        );
+    }
+
+    /// Stats as reported in module results (`data.transfer_stats`)
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "bytes": self.bytes,
+            "duration_ms": self.duration.as_secs_f64() * 1000.0,
+            "bytes_per_sec": self.bytes_per_sec().round() as u64,
+        })
+    }
+}
+
+/// Check whether an error means the server refused the SFTP subsystem
+pub fn is_sftp_unavailable(error: &ConnectionError) -> bool {
+    match error {
+        ConnectionError::SftpUnavailable(_) => true,
+        other => other
+            .to_string()
+            .to_lowercase()
+            .contains("subsystem request failed"),
+    }
+}
+
+/// Run a transfer with the given method.
+///
+/// `sftp` and `scp` perform the same transfer over the respective protocol.
+/// With [`TransferMethod::Smart`], an SFTP attempt the server refuses is
+/// retried once over SCP; any other error is returned as is.
+pub async fn with_scp_fallback<T, S, SFut, C, CFut>(
+    method: TransferMethod,
+    target: &str,
+    sftp: S,
+    scp: C,
+) -> ConnectionResult<T>
+where
+    S: FnOnce() -> SFut,
+    SFut: Future<Output = ConnectionResult<T>>,
+    C: FnOnce() -> CFut,
+    CFut: Future<Output = ConnectionResult<T>>,
+{
+    if method == TransferMethod::Scp {
+        return scp().await;
+    }
+
+    match sftp().await {
+        Err(e) if method.falls_back_on(&e) => {
+            warn!(target = %target, error = %e, "SFTP unavailable, retrying transfer over SCP");
+            scp().await
+        }
+        result => result,
+    }
+}
+
+/// Command that receives a file over SCP on the remote side
+pub fn scp_sink_command(remote_path: &Path) -> String {
+    format!(
+        "scp -t {}",
+        shell_words::quote(&remote_path.to_string_lossy())
+    )
+}
+
+/// Command that sends a file over SCP from the remote side
+pub fn scp_source_command(remote_path: &Path) -> String {
+    format!(
+        "scp -f {}",
+        shell_words::quote(&remote_path.to_string_lossy())
+    )
+}
+
+/// Send `content` to a remote `scp -t` process.
+///
+/// The file is created with `mode`; an existing file keeps its mode, as
+/// with the `scp` command line tool.
+pub async fn scp_send<S>(
+    stream: &mut S,
+    remote_path: &Path,
+    mode: u32,
+    content: &[u8],
+) -> ConnectionResult<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let name = remote_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            ConnectionError::TransferFailed(format!(
+                "Invalid SCP destination: {}",
+                remote_path.display()
+            ))
+        })?;
+
+    read_ack(stream).await?;
+
+    let header = format!("C{:04o} {} {}\n", mode & 0o7777, content.len(), name);
+    write_all(stream, header.as_bytes()).await?;
+    read_ack(stream).await?;
+
+    write_all(stream, content).await?;
+    write_all(stream, &[0]).await?;
+    read_ack(stream).await?;
+
+    stream
+        .shutdown()
+        .await
+        .map_err(|e| ConnectionError::TransferFailed(format!("SCP shutdown failed: {}", e)))
+}
+
+/// Receive a file from a remote `scp -f` process into `writer`, returning
+/// its mode and size
+///
+/// The content is copied as it arrives, so the size announced by the remote
+/// never decides how much is allocated up front.
+pub async fn scp_receive<S, W>(stream: &mut S, writer: &mut W) -> ConnectionResult<(u32, u64)>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    write_all(stream, &[0]).await?;
+
+    let header = read_line(stream).await?;
+    let (mode, size) = parse_scp_header(&header)?;
+    write_all(stream, &[0]).await?;
+
+    let copied = tokio::io::copy(&mut (&mut *stream).take(size), writer)
+        .await
+        .map_err(|e| ConnectionError::TransferFailed(format!("SCP read failed: {}", e)))?;
+    if copied != size {
+        return Err(ConnectionError::TransferFailed(format!(
+            "SCP read failed: expected {} bytes, got {}",
+            size, copied
+        )));
+    }
+    writer
+        .flush()
+        .await
+        .map_err(|e| ConnectionError::TransferFailed(format!("SCP write failed: {}", e)))?;
+    read_ack(stream).await?;
+    write_all(stream, &[0]).await?;
+
+    Ok((mode, size))
+}
+
+/// Parse an SCP `C<mode> <size> <name>` header
+fn parse_scp_header(header: &str) -> ConnectionResult<(u32, u64)> {
+    let invalid = || ConnectionError::TransferFailed(format!("Invalid SCP header: {}", header));
+
+    let rest = header.strip_prefix('C').ok_or_else(invalid)?;
+    let mut parts = rest.splitn(3, ' ');
+    let mode = parts
+        .next()
+        .and_then(|m| u32::from_str_radix(m, 8).ok())
+        .ok_or_else(invalid)?;
+    let size = parts
+        .next()
+        .and_then(|s| s.parse::<u64>().ok())
+        .ok_or_else(invalid)?;
+
+    Ok((mode, size))
+}
+
+/// Read a single SCP status byte, turning warnings and errors into failures
+async fn read_ack<S>(stream: &mut S) -> ConnectionResult<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut status = [0u8; 1];
+    stream
+        .read_exact(&mut status)
+        .await
+        .map_err(|e| ConnectionError::TransferFailed(format!("SCP read failed: {}", e)))?;
+
+    match status[0] {
+        0 => Ok(()),
+        _ => {
+            let message = read_line(stream).await.unwrap_or_default();
+            Err(ConnectionError::TransferFailed(format!(
+                "scp: {}",
+                message.trim()
+            )))
+        }
+    }
+}
+
+/// Read one newline-terminated protocol line. A leading error status byte
+/// is reported as a failure.
+async fn read_line<S>(stream: &mut S) -> ConnectionResult<String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut line = Vec::new();
+    let mut byte = [0u8; 1];
+    loop {
+        stream
+            .read_exact(&mut byte)
+            .await
+            .map_err(|e| ConnectionError::TransferFailed(format!("SCP read failed: {}", e)))?;
+        if byte[0] == b'\n' {
+            break;
+        }
+        line.push(byte[0]);
+    }
+
+    match line.first() {
+        Some(1) | Some(2) => Err(ConnectionError::TransferFailed(format!(
+            "scp: {}",
+            String::from_utf8_lossy(&line[1..]).trim()
+        ))),
+        _ => Ok(String::from_utf8_lossy(&line).to_string()),
+    }
+}
+
+async fn write_all<S>(stream: &mut S, data: &[u8]) -> ConnectionResult<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream
+        .write_all(data)
+        .await
+        .map_err(|e| ConnectionError::TransferFailed(format!("SCP write failed: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{duplex, DuplexStream};
+
+    #[test]
+    fn test_transfer_stats_json() {
+        let stats = TransferStats {
+            bytes: 4096,
+            duration: Duration::from_millis(500),
+        };
+        let json = stats.to_json();
+        assert_eq!(json["bytes"], 4096);
+        assert_eq!(json["duration_ms"], 500.0);
+        assert_eq!(json["bytes_per_sec"], 8192);
+    }
+
+    /// Minimal `scp -t` peer: accepts one file and returns its header and data
+    async fn scp_sink(mut peer: DuplexStream) -> (String, Vec<u8>) {
+        peer.write_all(&[0]).await.unwrap();
+        let header = read_line(&mut peer).await.unwrap();
+        let (_, size) = parse_scp_header(&header).unwrap();
+        peer.write_all(&[0]).await.unwrap();
+
+        let mut data = vec![0u8; size as usize];
+        peer.read_exact(&mut data).await.unwrap();
+        let mut end = [0u8; 1];
+        peer.read_exact(&mut end).await.unwrap();
+        peer.write_all(&[0]).await.unwrap();
+
+        (header, data)
+    }
+
+    #[test]
+    fn test_transfer_method_parsing() {
+        assert_eq!(
+            "sftp".parse::<TransferMethod>().unwrap(),
+            TransferMethod::Sftp
+        );
+        assert_eq!(
+            "SCP".parse::<TransferMethod>().unwrap(),
+            TransferMethod::Scp
+        );
+        assert_eq!(
+            "smart".parse::<TransferMethod>().unwrap(),
+            TransferMethod::Smart
+        );
+        assert!("rsync".parse::<TransferMethod>().is_err());
+
+        assert_eq!(TransferMethod::from_scp_if_ssh(false), TransferMethod::Sftp);
+        assert_eq!(TransferMethod::from_scp_if_ssh(true), TransferMethod::Scp);
+    }
+
+    #[test]
+    fn test_sftp_unavailable_detection() {
+        assert!(is_sftp_unavailable(&ConnectionError::SftpUnavailable(
+            "channel 0".into()
+        )));
+        assert!(is_sftp_unavailable(&ConnectionError::TransferFailed(
+            "subsystem request failed on channel 0".into()
+        )));
+        assert!(!is_sftp_unavailable(&ConnectionError::TransferFailed(
+            "Permission denied".into()
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_smart_falls_back_to_scp_when_sftp_is_refused() {
+        let sftp_attempts = AtomicUsize::new(0);
+        let (mut local, remote) = duplex(1024);
+        let sink = tokio::spawn(scp_sink(remote));
+
+        let result = with_scp_fallback(
+            TransferMethod::Smart,
+            "web1",
+            || async {
+                sftp_attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(ConnectionError::SftpUnavailable(
+                    "subsystem request failed on channel 0".into(),
+                ))
+            },
+            || {
+                scp_send(
+                    &mut local,
+                    Path::new("/tmp/app.conf"),
+                    0o640,
+                    b"key=value\n",
+                )
+            },
+        )
+        .await;
+
+        assert!(result.is_ok(), "SCP fallback failed: {:?}", result);
+        assert_eq!(sftp_attempts.load(Ordering::SeqCst), 1);
+
+        let (header, data) = sink.await.unwrap();
+        assert_eq!(header, "C0640 10 app.conf");
+        assert_eq!(data, b"key=value\n");
+    }
+
+    #[tokio::test]
+    async fn test_sftp_only_does_not_fall_back() {
+        let scp_attempts = AtomicUsize::new(0);
+
+        let result = with_scp_fallback(
+            TransferMethod::Sftp,
+            "web1",
+            || async {
+                Err::<(), _>(ConnectionError::SftpUnavailable(
+                    "subsystem request failed".into(),
+                ))
+            },
+            || async {
+                scp_attempts.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+        )
+        .await;
+
+        assert!(matches!(result, Err(ConnectionError::SftpUnavailable(_))));
+        assert_eq!(scp_attempts.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_smart_keeps_other_sftp_errors() {
+        let result = with_scp_fallback(
+            TransferMethod::Smart,
+            "web1",
+            || async { Err::<(), _>(ConnectionError::TransferFailed("Permission denied".into())) },
+            || async { panic!("SCP must not be attempted") },
+        )
+        .await;
+
+        assert!(matches!(result, Err(ConnectionError::TransferFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_scp_receive_reads_file() {
+        let (mut local, mut remote) = duplex(1024);
+        let source = tokio::spawn(async move {
+            let mut ready = [0u8; 1];
+            remote.read_exact(&mut ready).await.unwrap();
+            remote.write_all(b"C0600 5 secret\n").await.unwrap();
+            remote.read_exact(&mut ready).await.unwrap();
+            remote.write_all(b"hello\0").await.unwrap();
+            remote.read_exact(&mut ready).await.unwrap();
+        });
+
+        let mut content = Vec::new();
+        let (mode, size) = scp_receive(&mut local, &mut content).await.unwrap();
+        source.await.unwrap();
+
+        assert_eq!(mode, 0o600);
+        assert_eq!(size, 5);
+        assert_eq!(content, b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_scp_receive_rejects_short_file() {
+        let (mut local, mut remote) = duplex(1024);
+        tokio::spawn(async move {
+            let mut ready = [0u8; 1];
+            remote.read_exact(&mut ready).await.unwrap();
+            // Announces far more data than it sends, then hangs up
+            remote
+                .write_all(b"C0600 18446744073709551615 huge\n")
+                .await
+                .unwrap();
+            remote.read_exact(&mut ready).await.unwrap();
+            remote.write_all(b"tiny").await.unwrap();
+        });
+
+        let mut content = Vec::new();
+        let err = scp_receive(&mut local, &mut content).await.unwrap_err();
+        assert!(err.to_string().contains("got 4"), "{}", err);
+        assert_eq!(content, b"tiny");
+    }
+
+    #[tokio::test]
+    async fn test_scp_send_reports_remote_error() {
+        let (mut local, mut remote) = duplex(1024);
+        tokio::spawn(async move {
+            remote
+                .write_all(b"\x01scp: /root/x: Permission denied\n")
+                .await
+                .unwrap();
+        });
+
+        let err = scp_send(&mut local, Path::new("/root/x"), 0o644, b"data")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Permission denied"));
+    }
+}
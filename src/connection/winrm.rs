@@ -41,17 +41,15 @@ use async_trait::async_trait;
 use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine};
 use reqwest::{Client, Response};
 use secrecy::{ExposeSecret, SecretString};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::path::Path;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{debug, trace, warn};
 use uuid::Uuid;
-use zeroize::Zeroizing;
 
+use super::config::HostConfig;
 use super::{
     CommandResult, Connection, ConnectionError, ConnectionResult, ExecuteOptions, FileStat,
     TransferOptions,
@@ -84,8 +82,6 @@ const PWSH_NS: &str = "http://schemas.microsoft.com/powershell";
 
 /// WinRM resource URIs
 const SHELL_RESOURCE_URI: &str = "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/cmd";
-const POWERSHELL_RESOURCE_URI: &str =
-    "http://schemas.microsoft.com/powershell/Microsoft.PowerShell";
 
 /// WinRM action URIs
 const ACTION_CREATE: &str = "http://schemas.xmlsoap.org/ws/2004/09/transfer/Create";
@@ -601,6 +597,62 @@ impl WinRmConfig {
         }
     }
 
+    /// Build the configuration for a host from its connection settings
+    ///
+    /// The transport comes from `winrm_transport` (`ntlm` when unset, `basic`,
+    /// `credssp` or `kerberos`). Port 5985 is the HTTP listener, any other
+    /// port is spoken to over HTTPS.
+    pub fn for_host(
+        host: &str,
+        port: u16,
+        user: &str,
+        host_config: &HostConfig,
+    ) -> ConnectionResult<Self> {
+        let password = host_config.password.clone().unwrap_or_default();
+        let auth = match host_config
+            .winrm_transport
+            .as_deref()
+            .unwrap_or("ntlm")
+            .to_lowercase()
+            .as_str()
+        {
+            "ntlm" => WinRmAuth::ntlm(user, password),
+            "basic" => WinRmAuth::basic(user, password),
+            // Same DOMAIN\\user handling as NTLM
+            "credssp" => match WinRmAuth::ntlm(user, password) {
+                WinRmAuth::Ntlm {
+                    username,
+                    password,
+                    domain,
+                } => WinRmAuth::CredSSP {
+                    username,
+                    password,
+                    domain,
+                },
+                auth => auth,
+            },
+            "kerberos" => match user.split_once('@') {
+                Some((name, realm)) => WinRmAuth::kerberos(name, realm),
+                None => WinRmAuth::kerberos(user, ""),
+            },
+            other => {
+                return Err(ConnectionError::InvalidConfig(format!(
+                    "Unsupported WinRM transport '{}' for {}",
+                    other, host
+                )))
+            }
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            use_ssl: port != DEFAULT_WINRM_PORT,
+            auth,
+            timeout: host_config.connect_timeout.unwrap_or(DEFAULT_TIMEOUT),
+            ..Default::default()
+        })
+    }
+
     /// Get the WinRM endpoint URL
     pub fn endpoint_url(&self) -> String {
         let scheme = if self.use_ssl { "https" } else { "http" };
@@ -609,6 +661,9 @@ impl WinRmConfig {
 }
 
 /// Shell type for command execution
+///
+/// Both run in a WinRS `cmd` shell; PowerShell commands are passed to
+/// `powershell.exe -EncodedCommand` so quoting never has to survive cmd.exe.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ShellType {
     /// Command Prompt (cmd.exe)
@@ -864,10 +919,6 @@ impl WinRmConnection {
     /// Create a shell for command execution
     async fn create_shell(&self) -> ConnectionResult<String> {
         let message_id = Uuid::new_v4().to_string();
-        let resource_uri = match self.config.shell {
-            ShellType::Cmd => SHELL_RESOURCE_URI,
-            ShellType::PowerShell => POWERSHELL_RESOURCE_URI,
-        };
 
         let envelope = format!(
             r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -895,7 +946,7 @@ impl WinRmConnection {
   </s:Body>
 </s:Envelope>"#,
             self.config.endpoint_url(),
-            resource_uri,
+            SHELL_RESOURCE_URI,
             message_id,
             self.config.max_envelope_size,
             self.config.operation_timeout,
@@ -927,7 +978,7 @@ impl WinRmConnection {
             let mut session = self.session.write().await;
             *session = Some(WinRmSession {
                 shell_id: shell_id.clone(),
-                resource_uri: resource_uri.to_string(),
+                resource_uri: SHELL_RESOURCE_URI.to_string(),
                 active: true,
             });
         }
@@ -966,10 +1017,6 @@ impl WinRmConnection {
         args: &[&str],
     ) -> ConnectionResult<String> {
         let message_id = Uuid::new_v4().to_string();
-        let resource_uri = match self.config.shell {
-            ShellType::Cmd => SHELL_RESOURCE_URI,
-            ShellType::PowerShell => POWERSHELL_RESOURCE_URI,
-        };
 
         // Build arguments XML
         let args_xml: String = args
@@ -1002,7 +1049,7 @@ impl WinRmConnection {
   </s:Body>
 </s:Envelope>"#,
             self.config.endpoint_url(),
-            resource_uri,
+            SHELL_RESOURCE_URI,
             message_id,
             self.config.max_envelope_size,
             self.config.operation_timeout,
@@ -1055,11 +1102,6 @@ impl WinRmConnection {
         let mut exit_code = 0i32;
         let mut done = false;
 
-        let resource_uri = match self.config.shell {
-            ShellType::Cmd => SHELL_RESOURCE_URI,
-            ShellType::PowerShell => POWERSHELL_RESOURCE_URI,
-        };
-
         while !done {
             let message_id = Uuid::new_v4().to_string();
 
@@ -1087,7 +1129,7 @@ impl WinRmConnection {
   </s:Body>
 </s:Envelope>"#,
                 self.config.endpoint_url(),
-                resource_uri,
+                SHELL_RESOURCE_URI,
                 message_id,
                 self.config.max_envelope_size,
                 self.config.operation_timeout,
@@ -1186,10 +1228,6 @@ impl WinRmConnection {
     /// Signal command termination
     async fn signal_terminate(&self, shell_id: &str, command_id: &str) -> ConnectionResult<()> {
         let message_id = Uuid::new_v4().to_string();
-        let resource_uri = match self.config.shell {
-            ShellType::Cmd => SHELL_RESOURCE_URI,
-            ShellType::PowerShell => POWERSHELL_RESOURCE_URI,
-        };
 
         let envelope = format!(
             r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -1215,7 +1253,7 @@ impl WinRmConnection {
   </s:Body>
 </s:Envelope>"#,
             self.config.endpoint_url(),
-            resource_uri,
+            SHELL_RESOURCE_URI,
             message_id,
             self.config.max_envelope_size,
             self.config.operation_timeout,
@@ -1239,10 +1277,6 @@ impl WinRmConnection {
     /// Delete shell
     async fn delete_shell(&self, shell_id: &str) -> ConnectionResult<()> {
         let message_id = Uuid::new_v4().to_string();
-        let resource_uri = match self.config.shell {
-            ShellType::Cmd => SHELL_RESOURCE_URI,
-            ShellType::PowerShell => POWERSHELL_RESOURCE_URI,
-        };
 
         let envelope = format!(
             r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -1264,7 +1298,7 @@ impl WinRmConnection {
   <s:Body/>
 </s:Envelope>"#,
             self.config.endpoint_url(),
-            resource_uri,
+            SHELL_RESOURCE_URI,
             message_id,
             self.config.max_envelope_size,
             self.config.operation_timeout,
@@ -1289,21 +1323,124 @@ impl WinRmConnection {
 
     /// Execute a PowerShell script
     pub async fn execute_powershell(&self, script: &str) -> ConnectionResult<CommandResult> {
-        // Encode script for PowerShell -EncodedCommand
-        let script_unicode: Vec<u8> = script
-            .encode_utf16()
-            .flat_map(|c| c.to_le_bytes())
-            .collect();
-        let encoded = BASE64_STANDARD.encode(&script_unicode);
+        self.run_command_line(&powershell_command_line(script))
+            .await
+    }
+
+    /// Run a command line in a fresh shell and collect its result
+    async fn run_command_line(&self, command_line: &str) -> ConnectionResult<CommandResult> {
+        let shell_id = self.create_shell().await?;
+        let result = self.run_in_shell(&shell_id, command_line).await;
+        let _ = self.delete_shell(&shell_id).await;
+        let (stdout, stderr, exit_code) = result?;
 
-        // Execute with PowerShell -EncodedCommand
-        let command = format!(
-            "powershell.exe -NoProfile -NonInteractive -EncodedCommand {}",
-            encoded
+        trace!(
+            exit_code = %exit_code,
+            stdout_len = %stdout.len(),
+            stderr_len = %stderr.len(),
+            "WinRM command completed"
         );
 
-        self.execute(&command, None).await
+        if exit_code == 0 {
+            Ok(CommandResult::success(stdout, stderr))
+        } else {
+            Ok(CommandResult::failure(exit_code, stdout, stderr))
+        }
+    }
+
+    /// Run a command in an open shell, returning stdout, stderr and exit code
+    async fn run_in_shell(
+        &self,
+        shell_id: &str,
+        command_line: &str,
+    ) -> ConnectionResult<(String, String, i32)> {
+        let command_id = self.run_command(shell_id, command_line, &[]).await?;
+        let output = self.receive_output(shell_id, &command_id).await;
+        let _ = self.signal_terminate(shell_id, &command_id).await;
+        output
+    }
+}
+
+/// Wrap a command in a PowerShell script applying the execute options
+///
+/// The script exits with the command's exit code: `$LASTEXITCODE` for native
+/// programs, 1 when a cmdlet fails without one.
+fn powershell_script(command: &str, options: &ExecuteOptions) -> String {
+    let mut script = String::new();
+    for (key, value) in &options.env {
+        script.push_str(&format!("$env:{} = {}\n", key, powershell_quote(value)));
+    }
+    if let Some(cwd) = &options.cwd {
+        script.push_str(&format!(
+            "Set-Location -LiteralPath {}\n",
+            powershell_quote(cwd)
+        ));
+    }
+    script.push_str(command);
+    script.push_str(
+        "\nif (-not $?) { if ($LASTEXITCODE) { exit $LASTEXITCODE } else { exit 1 } }\nexit $LASTEXITCODE\n",
+    );
+    script
+}
+
+/// Quote a string as a PowerShell single-quoted literal
+///
+/// PowerShell also closes single-quoted strings on the typographic quotes
+/// U+2018 to U+201B, so those are doubled like `'`.
+fn powershell_quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('\'');
+    for c in s.chars() {
+        if matches!(c, '\'' | '\u{2018}'..='\u{201B}') {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// Quote an argument for a `cmd.exe` command line
+///
+/// Follows the `CommandLineToArgvW` rules, quoting arguments with whitespace,
+/// quotes or `cmd.exe` metacharacters. `%` is still expanded by `cmd.exe`.
+fn cmd_quote(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"', '&', '|', '<', '>', '^', '(', ')']) {
+        return arg.to_string();
+    }
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
     }
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Command line running a script with `powershell.exe -EncodedCommand`
+fn powershell_command_line(script: &str) -> String {
+    // -EncodedCommand takes base64 of the UTF-16LE script
+    let script_unicode: Vec<u8> = script
+        .encode_utf16()
+        .flat_map(|c| c.to_le_bytes())
+        .collect();
+    format!(
+        "powershell.exe -NoProfile -NonInteractive -ExecutionPolicy Bypass -EncodedCommand {}",
+        BASE64_STANDARD.encode(&script_unicode)
+    )
 }
 
 impl std::fmt::Debug for WinRmConnection {
@@ -1340,56 +1477,49 @@ impl Connection for WinRmConnection {
             "Executing WinRM command"
         );
 
-        // Create shell
-        let shell_id = self.create_shell().await?;
-
-        // Build command with options
-        let full_command = if let Some(cwd) = &options.cwd {
-            format!("cd '{}' ; {}", cwd.replace('\'', "''"), command)
-        } else {
-            command.to_string()
-        };
-
-        // Wrap in PowerShell if using PowerShell shell
-        let final_command = match self.config.shell {
-            ShellType::PowerShell => full_command,
+        match self.config.shell {
+            ShellType::PowerShell => {
+                self.execute_powershell(&powershell_script(command, &options))
+                    .await
+            }
             ShellType::Cmd => {
-                if options.env.is_empty() {
-                    full_command
-                } else {
-                    // Set environment variables for cmd
-                    let env_cmds: String = options
-                        .env
-                        .iter()
-                        .map(|(k, v)| format!("set {}={} &&", k, v))
-                        .collect();
-                    format!("{} {}", env_cmds, full_command)
+                let mut command_line = String::new();
+                for (key, value) in &options.env {
+                    command_line.push_str(&format!("set {}={} && ", key, value));
                 }
+                if let Some(cwd) = &options.cwd {
+                    command_line.push_str(&format!("cd /d \"{}\" && ", cwd));
+                }
+                command_line.push_str(command);
+                self.run_command_line(&command_line).await
             }
-        };
-
-        // Run command
-        let command_id = self.run_command(&shell_id, &final_command, &[]).await?;
-
-        // Receive output
-        let (stdout, stderr, exit_code) = self.receive_output(&shell_id, &command_id).await?;
-
-        // Signal termination and cleanup
-        let _ = self.signal_terminate(&shell_id, &command_id).await;
-        let _ = self.delete_shell(&shell_id).await;
-
-        trace!(
-            exit_code = %exit_code,
-            stdout_len = %stdout.len(),
-            stderr_len = %stderr.len(),
-            "WinRM command completed"
-        );
+        }
+    }
 
-        if exit_code == 0 {
-            Ok(CommandResult::success(stdout, stderr))
-        } else {
-            Ok(CommandResult::failure(exit_code, stdout, stderr))
+    async fn execute_argv(
+        &self,
+        argv: &[String],
+        options: Option<ExecuteOptions>,
+    ) -> ConnectionResult<CommandResult> {
+        if argv.is_empty() {
+            return Err(ConnectionError::ExecutionFailed(
+                "argv cannot be empty".to_string(),
+            ));
         }
+        // PowerShell runs the program through the call operator, so the first
+        // argument is never parsed as a cmdlet or expression
+        let command = match self.config.shell {
+            ShellType::PowerShell => std::iter::once("&".to_string())
+                .chain(argv.iter().map(|arg| powershell_quote(arg)))
+                .collect::<Vec<_>>()
+                .join(" "),
+            ShellType::Cmd => argv
+                .iter()
+                .map(|arg| cmd_quote(arg))
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        self.execute(&command, options).await
     }
 
     async fn upload(
@@ -1798,6 +1928,69 @@ fn xml_escape(s: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_config_for_host_maps_transport_and_port() {
+        let host_config = HostConfig {
+            password: Some("secret".to_string()),
+            winrm_transport: Some("basic".to_string()),
+            ..Default::default()
+        };
+        let config = WinRmConfig::for_host("win1", 5985, "admin", &host_config).unwrap();
+        assert!(!config.use_ssl);
+        assert_eq!(config.endpoint_url(), "http://win1:5985/wsman");
+        assert!(
+            matches!(config.auth, WinRmAuth::Basic { ref username, .. } if username == "admin")
+        );
+
+        // NTLM over HTTPS by default
+        let config =
+            WinRmConfig::for_host("win1", 5986, "CORP\\admin", &HostConfig::default()).unwrap();
+        assert!(config.use_ssl);
+        assert!(matches!(
+            config.auth,
+            WinRmAuth::Ntlm { ref domain, .. } if domain.as_deref() == Some("CORP")
+        ));
+
+        let host_config = HostConfig {
+            winrm_transport: Some("telnet".to_string()),
+            ..Default::default()
+        };
+        assert!(WinRmConfig::for_host("win1", 5986, "admin", &host_config).is_err());
+    }
+
+    #[test]
+    fn test_argv_quoting() {
+        assert_eq!(powershell_quote("C:\\It's here"), "'C:\\It''s here'");
+        assert_eq!(powershell_quote("\u{2019}$x"), "'\u{2019}\u{2019}$x'");
+
+        assert_eq!(cmd_quote("plain"), "plain");
+        assert_eq!(cmd_quote(""), "\"\"");
+        assert_eq!(cmd_quote("a b&c"), "\"a b&c\"");
+        assert_eq!(cmd_quote("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(cmd_quote("C:\\dir \\"), "\"C:\\dir \\\\\"");
+    }
+
+    #[test]
+    fn test_powershell_script_propagates_exit_code() {
+        let options = ExecuteOptions::new()
+            .with_cwd("C:\\It's here")
+            .with_env("APP_ENV", "prod");
+        let script = powershell_script("git status", &options);
+        assert!(script.starts_with(
+            "$env:APP_ENV = 'prod'\nSet-Location -LiteralPath 'C:\\It''s here'\ngit status\n"
+        ));
+        assert!(script.contains("exit $LASTEXITCODE"));
+
+        let command_line = powershell_command_line("exit 3");
+        let encoded = command_line.rsplit(' ').next().unwrap();
+        let bytes = BASE64_STANDARD.decode(encoded).unwrap();
+        let units: Vec<u16> = bytes
+            .chunks(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        assert_eq!(String::from_utf16(&units).unwrap(), "exit 3");
+    }
+
     #[test]
     fn test_winrm_auth_ntlm_parse() {
         let auth = WinRmAuth::ntlm("DOMAIN\\user", "password");
@@ -651,6 +651,7 @@ impl AsyncTaskManager {
             msg: Some(format!("Async job started: {}", jid)),
             result: Some(result_data),
             diff: None,
+            warnings: Vec::new(),
         }
     }
 
@@ -687,6 +688,7 @@ impl AsyncTaskManager {
                 msg: info.msg,
                 result: Some(result_data),
                 diff: None,
+                warnings: Vec::new(),
             }
         } else {
             TaskResult::failed(format!("Job not found: {}", jid))
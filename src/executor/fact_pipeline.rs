@@ -19,7 +19,10 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, Semaphore};
-use tracing::{debug, trace, warn};
+use tracing::{debug, info, trace, warn};
+
+use crate::cache::tiered_facts::{CacheTier, TieredFactCache};
+use crate::connection::Connection;
 
 /// Configuration for the fact gathering pipeline
 #[derive(Debug, Clone)]
@@ -118,6 +121,29 @@ impl FactPipelineStats {
     }
 }
 
+/// Summary of a cache warm-up run
+#[derive(Debug, Clone, Default)]
+pub struct FactWarmupReport {
+    /// Hosts whose facts were gathered and persisted
+    pub succeeded: Vec<String>,
+    /// Hosts that failed, with the error message
+    pub failed: Vec<(String, String)>,
+    /// Total wall-clock time of the warm-up
+    pub duration: Duration,
+}
+
+impl FactWarmupReport {
+    /// Total number of hosts processed
+    pub fn total(&self) -> usize {
+        self.succeeded.len() + self.failed.len()
+    }
+
+    /// Whether every host was warmed successfully
+    pub fn all_succeeded(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
 /// Message types for the pipeline
 enum PipelineMessage {
     GatherFacts {
@@ -338,6 +364,58 @@ impl FactPipeline {
         });
     }
 
+    /// Gather facts for all hosts and persist them into a tiered fact cache
+    ///
+    /// Every host goes through [`gather_facts`](Self::gather_facts), so concurrency
+    /// is bounded by the pipeline's fact semaphore. Successful results are
+    /// written to the disk-backed L2 tier so later runs can pick them up with
+    /// [`TieredFactCache::warm_from_disk`]. A failing host never aborts the others;
+    /// it is recorded in the returned report instead.
+    pub async fn warm_cache<F>(
+        &self,
+        hosts: &[String],
+        gather_fn: F,
+        cache: &TieredFactCache,
+    ) -> FactWarmupReport
+    where
+        F: AsyncFactGathererFactory,
+    {
+        let start = Instant::now();
+        let mut report = FactWarmupReport::default();
+
+        let gathers = hosts
+            .iter()
+            .map(|host| self.gather_facts(host, gather_fn.create()));
+
+        for result in futures::future::join_all(gathers).await {
+            match result.error {
+                Some(error) => report.failed.push((result.host, error)),
+                None => {
+                    cache.insert_to_tier(
+                        &result.host,
+                        result.facts,
+                        self.config.gather_subset.clone(),
+                        CacheTier::L2Warm,
+                    );
+                    report.succeeded.push(result.host);
+                }
+            }
+        }
+
+        // Results arrive in completion order; keep the report stable
+        report.succeeded.sort();
+        report.failed.sort();
+        report.duration = start.elapsed();
+
+        info!(
+            "Fact cache warm-up: {} succeeded, {} failed",
+            report.succeeded.len(),
+            report.failed.len()
+        );
+
+        report
+    }
+
     /// Get cached facts for a host
     fn get_cached_facts(&self, host: &str) -> Option<IndexMap<String, JsonValue>> {
         let cache = self.cache.read();
@@ -436,6 +514,83 @@ impl AsyncFactGathererFactory for SimpleFactGathererFactory {
     }
 }
 
+/// Fact gatherer that collects facts over established host connections
+pub struct ConnectionFactGatherer {
+    connections: Arc<HashMap<String, Arc<dyn Connection>>>,
+}
+
+impl ConnectionFactGatherer {
+    /// Build `ansible_*` facts from the raw output of
+    /// [`crate::facts::OS_FACT_COMMANDS`] followed by
+    /// [`crate::facts::USER_FACT_COMMANDS`]
+    fn parse_facts(outputs: &[String]) -> IndexMap<String, JsonValue> {
+        let (os, user) = outputs.split_at(outputs.len().min(crate::facts::OS_FACT_COMMANDS.len()));
+        crate::facts::os_facts(os)
+            .into_iter()
+            .chain(crate::facts::user_facts(user))
+            .map(|(name, value)| (format!("ansible_{}", name), value))
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncFactGatherer for ConnectionFactGatherer {
+    async fn gather_facts(
+        self,
+        host: &str,
+        _subset: &[String],
+    ) -> Result<IndexMap<String, JsonValue>, String> {
+        let connection = self
+            .connections
+            .get(host)
+            .ok_or_else(|| format!("No connection available for host '{}'", host))?;
+
+        let commands: Vec<&str> = crate::facts::OS_FACT_COMMANDS
+            .into_iter()
+            .chain(crate::facts::USER_FACT_COMMANDS)
+            .collect();
+        let mut outputs = Vec::with_capacity(commands.len());
+        for result in connection.execute_batch(&commands, None).await {
+            let result = result.map_err(|e| e.to_string())?;
+            if !result.success {
+                return Err(format!(
+                    "Fact command failed with exit code {}: {}",
+                    result.exit_code,
+                    result.stderr.trim()
+                ));
+            }
+            outputs.push(result.stdout.trim().to_string());
+        }
+
+        Ok(Self::parse_facts(&outputs))
+    }
+}
+
+/// Factory handing out connection-backed gatherers keyed by host name
+#[derive(Clone, Default)]
+pub struct ConnectionFactGathererFactory {
+    connections: Arc<HashMap<String, Arc<dyn Connection>>>,
+}
+
+impl ConnectionFactGathererFactory {
+    /// Create a factory from a map of host name to connection
+    pub fn new(connections: HashMap<String, Arc<dyn Connection>>) -> Self {
+        Self {
+            connections: Arc::new(connections),
+        }
+    }
+}
+
+impl AsyncFactGathererFactory for ConnectionFactGathererFactory {
+    type Gatherer = ConnectionFactGatherer;
+
+    fn create(&self) -> Self::Gatherer {
+        ConnectionFactGatherer {
+            connections: self.connections.clone(),
+        }
+    }
+}
+
 /// Streaming fact results for processing as they arrive
 pub struct FactStream {
     rx: mpsc::Receiver<FactResult>,
@@ -548,6 +703,228 @@ mod tests {
         assert_eq!(pipeline.cache_size(), 0);
     }
 
+    /// Connection that answers fact commands, or fails every command
+    struct MockFactConnection {
+        name: String,
+        fail: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl Connection for MockFactConnection {
+        fn identifier(&self) -> &str {
+            &self.name
+        }
+
+        async fn is_alive(&self) -> bool {
+            !self.fail
+        }
+
+        async fn execute(
+            &self,
+            command: &str,
+            _options: Option<crate::connection::ExecuteOptions>,
+        ) -> crate::connection::ConnectionResult<crate::connection::CommandResult> {
+            use crate::connection::{CommandResult, ConnectionError};
+
+            if self.fail {
+                return Err(ConnectionError::ConnectionFailed(format!(
+                    "{} unreachable",
+                    self.name
+                )));
+            }
+            let stdout = match command {
+                "hostname -f 2>/dev/null || hostname" => format!("{}.example.com", self.name),
+                "id -un" => "deploy".to_string(),
+                "id -u" => "1001".to_string(),
+                "id -g" => "1001".to_string(),
+                "uname -s" => "Linux".to_string(),
+                "uname -r" => "6.1.0".to_string(),
+                "uname -m" => "x86_64".to_string(),
+                _ => "ID=debian\nVERSION_ID=\"12\"\nID_LIKE=debian\n".to_string(),
+            };
+            Ok(CommandResult::success(stdout, String::new()))
+        }
+
+        async fn upload(
+            &self,
+            _local_path: &std::path::Path,
+            _remote_path: &std::path::Path,
+            _options: Option<crate::connection::TransferOptions>,
+        ) -> crate::connection::ConnectionResult<()> {
+            Ok(())
+        }
+
+        async fn upload_content(
+            &self,
+            _content: &[u8],
+            _remote_path: &std::path::Path,
+            _options: Option<crate::connection::TransferOptions>,
+        ) -> crate::connection::ConnectionResult<()> {
+            Ok(())
+        }
+
+        async fn download(
+            &self,
+            _remote_path: &std::path::Path,
+            _local_path: &std::path::Path,
+        ) -> crate::connection::ConnectionResult<()> {
+            Ok(())
+        }
+
+        async fn download_content(
+            &self,
+            _remote_path: &std::path::Path,
+        ) -> crate::connection::ConnectionResult<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn path_exists(
+            &self,
+            _path: &std::path::Path,
+        ) -> crate::connection::ConnectionResult<bool> {
+            Ok(false)
+        }
+
+        async fn is_directory(
+            &self,
+            _path: &std::path::Path,
+        ) -> crate::connection::ConnectionResult<bool> {
+            Ok(false)
+        }
+
+        async fn stat(
+            &self,
+            path: &std::path::Path,
+        ) -> crate::connection::ConnectionResult<crate::connection::FileStat> {
+            Err(crate::connection::ConnectionError::TransferFailed(format!(
+                "no such file: {}",
+                path.display()
+            )))
+        }
+
+        async fn close(&self) -> crate::connection::ConnectionResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warm_cache_persists_facts_and_reports_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = TieredFactCache::new(crate::cache::tiered_facts::TieredCacheConfig {
+            l2_cache_path: dir.path().to_path_buf(),
+            ..Default::default()
+        });
+
+        let mut connections: HashMap<String, Arc<dyn Connection>> = HashMap::new();
+        for (name, fail) in [("web1", false), ("web2", false), ("db1", true)] {
+            connections.insert(
+                name.to_string(),
+                Arc::new(MockFactConnection {
+                    name: name.to_string(),
+                    fail,
+                }),
+            );
+        }
+
+        let pipeline = FactPipeline::new(FactPipelineConfig {
+            max_concurrent: 2,
+            enable_cache: false,
+            ..Default::default()
+        });
+        let hosts: Vec<String> = ["web1", "web2", "db1", "missing"]
+            .iter()
+            .map(|h| h.to_string())
+            .collect();
+
+        let report = pipeline
+            .warm_cache(
+                &hosts,
+                ConnectionFactGathererFactory::new(connections),
+                &cache,
+            )
+            .await;
+
+        assert_eq!(report.total(), 4);
+        assert_eq!(report.succeeded, vec!["web1", "web2"]);
+        assert_eq!(report.failed.len(), 2);
+        assert!(report
+            .failed
+            .iter()
+            .any(|(h, e)| h == "db1" && e.contains("unreachable")));
+        assert!(report.failed.iter().any(|(h, _)| h == "missing"));
+
+        // Facts land in the persistent tier and survive a fresh cache instance
+        let facts = cache.get("web1").unwrap();
+        assert_eq!(facts["ansible_hostname"], "web1");
+        assert_eq!(facts["ansible_distribution"], "Debian");
+        assert_eq!(facts["ansible_os_family"], "Debian");
+        assert!(cache.get("db1").is_none());
+
+        let reloaded = TieredFactCache::new(crate::cache::tiered_facts::TieredCacheConfig {
+            l2_cache_path: dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        assert_eq!(reloaded.warm_from_disk(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_connection_gatherer_collects_user_and_hostname_facts() {
+        let mut connections: HashMap<String, Arc<dyn Connection>> = HashMap::new();
+        connections.insert(
+            "web1".to_string(),
+            Arc::new(MockFactConnection {
+                name: "web1".to_string(),
+                fail: false,
+            }),
+        );
+        let factory = ConnectionFactGathererFactory::new(connections);
+
+        let facts = factory.create().gather_facts("web1", &[]).await.unwrap();
+        assert_eq!(facts["ansible_hostname"], "web1");
+        assert_eq!(facts["ansible_fqdn"], "web1.example.com");
+        assert_eq!(facts["ansible_user_id"], "deploy");
+        assert_eq!(facts["ansible_user_uid"], 1001);
+        assert_eq!(facts["ansible_kernel"], "6.1.0");
+        assert_eq!(facts["ansible_machine"], "x86_64");
+
+        let vars: HashMap<String, JsonValue> = facts.into_iter().collect();
+        let rendered = crate::template::TemplateEngine::new()
+            .render(
+                "{{ ansible_user_id }}@{{ ansible_hostname }} ({{ ansible_fqdn }}){% if ansible_user_uid > 0 %} unprivileged{% endif %}",
+                &vars,
+            )
+            .unwrap();
+        assert_eq!(rendered, "deploy@web1 (web1.example.com) unprivileged");
+    }
+
+    #[test]
+    fn test_parse_facts_release_kernel_and_machine() {
+        let outputs: Vec<String> = [
+            "focal-1.example.com",
+            "Linux",
+            "5.4.0-150-generic",
+            "arm64",
+            "NAME=\"Ubuntu\"\nVERSION_ID=\"20.04\"\nID=ubuntu\nUBUNTU_CODENAME=focal\n",
+            "ubuntu",
+            "1000",
+            "1000",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let facts = ConnectionFactGatherer::parse_facts(&outputs);
+        for (fact, expected) in [
+            ("ansible_distribution_release", "focal"),
+            ("ansible_kernel", "5.4.0-150-generic"),
+            ("ansible_architecture", "arm64"),
+            ("ansible_machine", "aarch64"),
+        ] {
+            assert!(facts[fact].is_string(), "{} should be a string", fact);
+            assert_eq!(facts[fact], expected, "{}", fact);
+        }
+    }
+
     #[test]
     fn test_stats_cache_hit_ratio() {
         let stats = FactPipelineStats {
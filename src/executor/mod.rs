@@ -93,6 +93,9 @@ pub mod throttle;
 /// Work-stealing scheduler for optimal load balancing.
 pub mod work_stealing;
 
+/// Safe mode guarding destructive modules behind an allowlist.
+pub mod safe_mode;
+
 // Re-exports for commonly used types from enhancement modules
 pub use async_runtime::{RuntimeConfig, RuntimeMetrics, SpawnOptions, TaskSpawner};
 pub use async_task::{AsyncConfig, AsyncJobInfo, AsyncJobStatus, AsyncTaskManager};
@@ -101,26 +104,32 @@ pub use condition::{Condition, ConditionContext, ConditionEvaluator};
 pub use dependency::{
     DependencyError, DependencyGraph as AdvancedDependencyGraph, DependencyKind, DependencyNode,
 };
-pub use fact_pipeline::{FactPipeline, FactPipelineConfig, FactResult};
+pub use fact_pipeline::{
+    ConnectionFactGathererFactory, FactPipeline, FactPipelineConfig, FactResult, FactWarmupReport,
+};
 pub use host_pinned::{HostPinnedConfig, HostPinnedExecutor, HostPinnedPool};
 pub use pipeline::{ExecutionPipeline, PipelineConfig, TaskOptimizationHints};
 pub use register::{FailedTaskInfo, LoopResults, RegisteredResultExt};
+pub use safe_mode::SafeMode;
 pub use throttle::{ThrottleConfig, ThrottleManager, ThrottleStats};
 pub use work_stealing::{WorkItem, WorkStealingConfig, WorkStealingScheduler, WorkStealingStats};
 
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use futures::future::join_all;
 use indexmap::IndexMap;
 use thiserror::Error;
-use tokio::sync::{Mutex, RwLock, Semaphore};
+use tokio::sync::{Mutex, OwnedSemaphorePermit, RwLock, Semaphore};
 use tracing::{debug, error, info, instrument, warn};
 
+use crate::callback::manager::CallbackManager;
+use crate::connection::ConnectionFactory;
 use crate::executor::parallelization::ParallelizationManager;
 use crate::executor::playbook::{Play, Playbook};
-use crate::executor::runtime::{ExecutionContext, RuntimeContext};
-use crate::executor::task::{Handler, Task, TaskResult, TaskStatus};
+use crate::executor::runtime::{BecomeDefaults, ExecutionContext, RuntimeContext};
+use crate::executor::task::{BlockRole, Handler, Task, TaskResult, TaskStatus};
 
 /// Errors that can occur during playbook and task execution.
 ///
@@ -156,6 +165,10 @@ pub enum ExecutorError {
     #[error("Module not found: {0}")]
     ModuleNotFound(String),
 
+    /// A role was not found in any of the roles directories.
+    #[error("Role not found: {0}")]
+    RoleNotFound(String),
+
     /// Failed to parse playbook YAML or related content.
     #[error("Playbook parse error: {0}")]
     ParseError(String),
@@ -247,6 +260,27 @@ pub struct ExecutorConfig {
     /// These have the highest precedence and override all other variables.
     /// Similar to Ansible's `--extra-vars` or `-e` option.
     pub extra_vars: HashMap<String, serde_json::Value>,
+
+    /// Safe mode for destructive modules (default: disabled).
+    ///
+    /// When set, tasks using destructive modules such as `shell` or
+    /// `file state=absent` fail unless their module or task name is on
+    /// the allowlist.
+    pub safe_mode: Option<Arc<SafeMode>>,
+
+    /// Privilege escalation defaults (default: disabled).
+    ///
+    /// Applied to tasks that don't configure `become` themselves.
+    /// Similar to Ansible's `--become`, `--become-user` and
+    /// `--become-method` options.
+    pub become_defaults: BecomeDefaults,
+
+    /// Cuts the `serial_pause` in progress short when notified (default: none).
+    ///
+    /// When set, `run_playbook` also notifies it on Ctrl-C while a playbook
+    /// with batch pauses runs. Leave it unset to keep the process's own
+    /// Ctrl-C handling untouched.
+    pub batch_pause_skip: Option<Arc<tokio::sync::Notify>>,
 }
 
 impl Default for ExecutorConfig {
@@ -260,6 +294,9 @@ impl Default for ExecutorConfig {
             task_timeout: 300,
             gather_facts: true,
             extra_vars: HashMap::new(),
+            safe_mode: None,
+            become_defaults: BecomeDefaults::default(),
+            batch_pause_skip: None,
         }
     }
 }
@@ -376,48 +413,316 @@ pub struct Executor {
     config: ExecutorConfig,
     runtime: Arc<RwLock<RuntimeContext>>,
     handlers: Arc<RwLock<HashMap<String, Handler>>>,
+    /// Handler names in definition order
+    handler_order: Arc<RwLock<Vec<String>>>,
     notified_handlers: Arc<Mutex<HashSet<String>>>,
     semaphore: Arc<Semaphore>,
+    host_gauge: Arc<HostConcurrencyGauge>,
     parallelization_manager: Arc<ParallelizationManager>,
+    callbacks: Option<Arc<CallbackManager>>,
+    connections: Option<ConnectionFactory>,
+}
+
+/// Tracks how many hosts currently hold a fork slot, and the peak seen.
+#[derive(Debug, Default)]
+struct HostConcurrencyGauge {
+    active: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+/// A fork slot held by one host; releases the permit and gauge on drop.
+struct HostSlot {
+    _permit: OwnedSemaphorePermit,
+    gauge: Arc<HostConcurrencyGauge>,
+}
+
+impl HostSlot {
+    /// Wait for a fork slot.
+    ///
+    /// Every strategy and every serial batch draws from the executor-wide
+    /// semaphore, so the number of hosts running at once never exceeds `forks`.
+    async fn acquire(semaphore: &Arc<Semaphore>, gauge: &Arc<HostConcurrencyGauge>) -> Self {
+        let permit = Arc::clone(semaphore)
+            .acquire_owned()
+            .await
+            .expect("executor semaphore is never closed");
+        let active = gauge.active.fetch_add(1, Ordering::SeqCst) + 1;
+        gauge.peak.fetch_max(active, Ordering::SeqCst);
+        Self {
+            _permit: permit,
+            gauge: Arc::clone(gauge),
+        }
+    }
+}
+
+impl Drop for HostSlot {
+    fn drop(&mut self) {
+        self.gauge.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Notifies a batch pause skip on every Ctrl-C until dropped
+struct CtrlCListener(tokio::task::JoinHandle<()>);
+
+impl CtrlCListener {
+    fn spawn(skip: Arc<tokio::sync::Notify>) -> Self {
+        Self(tokio::spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                warn!("Ctrl-C received, skipping the batch pause in progress");
+                skip.notify_waiters();
+            }
+        }))
+    }
+}
+
+impl Drop for CtrlCListener {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Block, rescue and always bookkeeping for one host
+///
+/// Shared by the linear and free strategies so both decide the same way
+/// which block tasks run and expose the same failure variables.
+#[derive(Debug, Default)]
+struct HostBlocks {
+    /// Blocks with a failed task
+    failed: HashSet<String>,
+    /// Blocks whose rescue section has run
+    rescued: HashSet<String>,
+    /// Block whose failure is exposed via `ansible_failed_*`
+    exposed: Option<String>,
+}
+
+impl HostBlocks {
+    /// Whether the host runs `task`; `host_done` means it already failed
+    /// or became unreachable, leaving only `always` sections to run
+    fn should_run(&self, task: &Task, host_done: bool) -> bool {
+        if host_done {
+            return task.block_role == BlockRole::Always;
+        }
+        let Some(ref block_id) = task.block_id else {
+            return true;
+        };
+        let block_failed = self.failed.contains(block_id);
+        match task.block_role {
+            // Skip normal tasks once the block has failed
+            BlockRole::Normal => !block_failed,
+            // Run rescue tasks only if the block failed and isn't rescued yet
+            BlockRole::Rescue => block_failed && !self.rescued.contains(block_id),
+            BlockRole::Always => true,
+        }
+    }
+
+    /// Drop the failure details once the host moves past the block that
+    /// exposed them
+    async fn leave_block(
+        &mut self,
+        runtime: &Arc<RwLock<RuntimeContext>>,
+        host: &str,
+        task: &Task,
+    ) {
+        if self.exposed.is_some() && self.exposed != task.block_id {
+            self.exposed = None;
+            clear_block_failure_vars(runtime, host).await;
+        }
+    }
+
+    /// Drop any failure details still exposed at the end of the play
+    async fn finish(&mut self, runtime: &Arc<RwLock<RuntimeContext>>, host: &str) {
+        if self.exposed.take().is_some() {
+            clear_block_failure_vars(runtime, host).await;
+        }
+    }
+
+    /// Record a task's result for the host
+    ///
+    /// A failed task inside a block marks the block failed for its rescue
+    /// section instead of failing the host; the host only fails when a
+    /// rescue task fails too.
+    async fn record(
+        &mut self,
+        runtime: &Arc<RwLock<RuntimeContext>>,
+        host_result: &mut HostResult,
+        task: &Task,
+        task_result: &TaskResult,
+    ) {
+        let task_failed = task_result.status == TaskStatus::Failed;
+
+        // A failure tolerated via ignore_errors never triggers the rescue section
+        if task_failed && !task.ignore_errors && task.block_role == BlockRole::Normal {
+            if let Some(ref block_id) = task.block_id {
+                self.failed.insert(block_id.clone());
+                set_block_failure_vars(runtime, &host_result.host, task, task_result).await;
+                self.exposed = Some(block_id.clone());
+            }
+        }
+
+        if task.block_role == BlockRole::Rescue {
+            if let Some(ref block_id) = task.block_id {
+                self.rescued.insert(block_id.clone());
+            }
+        }
+
+        let should_mark_failed = if task.block_id.is_some() {
+            task.block_role == BlockRole::Rescue && task_failed
+        } else {
+            task_failed
+        };
+
+        // Don't count a normal block failure as a host failure
+        let mut counted = task_result.clone();
+        if task.block_id.is_some() && task.block_role == BlockRole::Normal && task_failed {
+            counted.status = TaskStatus::Ok;
+        }
+        update_stats(&mut host_result.stats, &counted);
+        match counted.status {
+            TaskStatus::Failed => host_result.failed = true,
+            TaskStatus::Unreachable => host_result.unreachable = true,
+            _ => {}
+        }
+
+        if should_mark_failed && !task.ignore_errors {
+            host_result.failed = true;
+        }
+    }
+}
+
+/// Expose a failed block task to the rescue and always sections
+///
+/// Sets `ansible_failed_task` and `ansible_failed_result` for the host,
+/// matching the variables Ansible provides inside `rescue`.
+async fn set_block_failure_vars(
+    runtime: &Arc<RwLock<RuntimeContext>>,
+    host: &str,
+    task: &Task,
+    result: &TaskResult,
+) {
+    let failed_task = serde_json::json!({
+        "name": task.name,
+        "action": task.module,
+        "args": task.args,
+    });
+    let failed_result = result.to_registered(None, None).to_json();
+
+    let mut runtime = runtime.write().await;
+    runtime.set_host_var(host, "ansible_failed_task".to_string(), failed_task);
+    runtime.set_host_var(host, "ansible_failed_result".to_string(), failed_result);
+}
+
+/// Remove the block failure variables once a host leaves the block
+async fn clear_block_failure_vars(runtime: &Arc<RwLock<RuntimeContext>>, host: &str) {
+    let mut runtime = runtime.write().await;
+    runtime.remove_host_var(host, "ansible_failed_task");
+    runtime.remove_host_var(host, "ansible_failed_result");
 }
 
 impl Executor {
     /// Create a new executor with the given configuration
     pub fn new(config: ExecutorConfig) -> Self {
-        let forks = config.forks;
+        // A zero-sized semaphore would never hand out a permit
+        let forks = config.forks.max(1);
         Self {
             config,
             runtime: Arc::new(RwLock::new(RuntimeContext::new())),
             handlers: Arc::new(RwLock::new(HashMap::new())),
+            handler_order: Arc::new(RwLock::new(Vec::new())),
             notified_handlers: Arc::new(Mutex::new(HashSet::new())),
             semaphore: Arc::new(Semaphore::new(forks)),
+            host_gauge: Arc::new(HostConcurrencyGauge::default()),
             parallelization_manager: Arc::new(ParallelizationManager::new()),
+            callbacks: None,
+            connections: None,
         }
     }
 
     /// Create executor with a pre-existing runtime context
     pub fn with_runtime(config: ExecutorConfig, runtime: RuntimeContext) -> Self {
-        let forks = config.forks;
+        let forks = config.forks.max(1);
         Self {
             config,
             runtime: Arc::new(RwLock::new(runtime)),
             handlers: Arc::new(RwLock::new(HashMap::new())),
+            handler_order: Arc::new(RwLock::new(Vec::new())),
             notified_handlers: Arc::new(Mutex::new(HashSet::new())),
             semaphore: Arc::new(Semaphore::new(forks)),
+            host_gauge: Arc::new(HostConcurrencyGauge::default()),
             parallelization_manager: Arc::new(ParallelizationManager::new()),
+            callbacks: None,
+            connections: None,
         }
     }
 
+    /// Dispatch playbook, play and task events to the given callbacks
+    ///
+    /// Every task execution on every host is timed and reported through
+    /// `on_task_start` / `on_task_complete`.
+    pub fn with_callbacks(mut self, callbacks: Arc<CallbackManager>) -> Self {
+        self.callbacks = Some(callbacks);
+        self
+    }
+
+    /// Run tasks over connections from the given factory
+    ///
+    /// Each host's tasks get a pooled connection, and `run_playbook` closes
+    /// the pool before it returns.
+    pub fn with_connection_factory(mut self, connections: ConnectionFactory) -> Self {
+        self.connections = Some(connections);
+        self
+    }
+
+    /// Highest number of hosts that held a fork slot at the same time.
+    ///
+    /// Useful for verifying that `forks` is honoured across strategies and
+    /// serial batches.
+    pub fn peak_host_concurrency(&self) -> usize {
+        self.host_gauge.peak.load(Ordering::SeqCst)
+    }
+
     /// Run a complete playbook
     #[instrument(skip(self, playbook), fields(playbook_name = %playbook.name))]
     pub async fn run_playbook(
         &self,
         playbook: &Playbook,
+    ) -> ExecutorResult<HashMap<String, HostResult>> {
+        let result = self.run_playbook_plays(playbook).await;
+
+        // Close pooled connections whether or not the run succeeded
+        if let Some(ref connections) = self.connections {
+            if let Err(e) = connections.close_all().await {
+                warn!("Failed to close connections: {}", e);
+            }
+        }
+
+        result
+    }
+
+    /// Run every play of a playbook, then the remaining notified handlers
+    async fn run_playbook_plays(
+        &self,
+        playbook: &Playbook,
     ) -> ExecutorResult<HashMap<String, HostResult>> {
         info!("Starting playbook: {}", playbook.name);
+        if let Some(ref callbacks) = self.callbacks {
+            callbacks.on_playbook_start(&playbook.name).await;
+        }
 
         let mut all_results: HashMap<String, HostResult> = HashMap::new();
 
+        // Ctrl-C skips batch pauses for as long as the playbook runs
+        let _ctrl_c = self
+            .config
+            .batch_pause_skip
+            .clone()
+            .filter(|_| {
+                playbook
+                    .plays
+                    .iter()
+                    .any(|play| play.serial_pause.is_some())
+            })
+            .map(CtrlCListener::spawn);
+
         // Set playbook-level variables
         {
             let mut runtime = self.runtime.write().await;
@@ -448,9 +753,14 @@ impl Executor {
         }
 
         // Run any remaining notified handlers
-        self.flush_handlers().await?;
+        let hosts = self.runtime.read().await.get_all_hosts();
+        self.flush_handlers(&hosts).await?;
 
         info!("Playbook completed: {}", playbook.name);
+        if let Some(ref callbacks) = self.callbacks {
+            let success = !all_results.values().any(|r| r.failed || r.unreachable);
+            callbacks.on_playbook_end(&playbook.name, success).await;
+        }
         Ok(all_results)
     }
 
@@ -459,17 +769,22 @@ impl Executor {
     pub async fn run_play(&self, play: &Play) -> ExecutorResult<HashMap<String, HostResult>> {
         info!("Starting play: {}", play.name);
 
-        // Register handlers for this play
+        if let Some(ref required) = play.min_rustible_version {
+            playbook::require_rustible_version(required)?;
+        }
+
+        // Register handlers for this play: role handlers (dependencies first),
+        // then the play's own. They share one map, so any task in the play can
+        // notify any of them by name or `listen` topic.
         {
             let mut handlers = self.handlers.write().await;
-            for handler in &play.handlers {
-                handlers.insert(handler.name.clone(), handler.clone());
-            }
-            // Register handlers from roles
-            for role in &play.roles {
-                for handler in role.get_all_handlers() {
-                    handlers.insert(handler.name.clone(), handler.clone());
+            let mut order = self.handler_order.write().await;
+            let role_handlers = play.roles.iter().flat_map(|r| r.get_all_handlers());
+            for handler in role_handlers.chain(play.handlers.iter().cloned()) {
+                if !order.contains(&handler.name) {
+                    order.push(handler.name.clone());
                 }
+                handlers.insert(handler.name.clone(), handler);
             }
         }
 
@@ -497,17 +812,15 @@ impl Executor {
         }
 
         debug!("Executing on {} hosts", hosts.len());
+        self.runtime.write().await.set_play_hosts(&hosts);
+        if let Some(ref callbacks) = self.callbacks {
+            callbacks.on_play_start(&play.name, &hosts).await;
+        }
 
-        // Combine all tasks: gather_facts (if enabled) + pre_tasks + role tasks + tasks + post_tasks
-        // Pre-allocate with known capacity to avoid reallocations
+        // Split the play into sections: gather_facts (if enabled) + pre_tasks,
+        // role tasks + tasks, and post_tasks
         let gather_facts_count = if play.gather_facts { 1 } else { 0 };
-        let role_tasks_count: usize = play.roles.iter().map(|r| r.get_all_tasks().len()).sum();
-        let total_tasks = gather_facts_count
-            + play.pre_tasks.len()
-            + role_tasks_count
-            + play.tasks.len()
-            + play.post_tasks.len();
-        let mut all_tasks = Vec::with_capacity(total_tasks);
+        let mut pre_tasks = Vec::with_capacity(gather_facts_count + play.pre_tasks.len());
 
         // If gather_facts is enabled, inject a facts-gathering task at the start
         if play.gather_facts {
@@ -516,10 +829,12 @@ impl Executor {
                 name: "Gathering Facts".to_string(),
                 module: "gather_facts".to_string(),
                 args: IndexMap::new(),
+                vars: IndexMap::new(),
                 when: None,
                 notify: Vec::new(),
                 register: None,
                 loop_items: None,
+                loop_template: None,
                 loop_var: "item".to_string(),
                 loop_control: None,
                 ignore_errors: false,
@@ -529,37 +844,46 @@ impl Executor {
                 delegate_facts: None,
                 run_once: false,
                 tags: Vec::new(),
-                r#become: false,
+                r#become: None,
                 become_user: None,
+                become_method: None,
                 block_id: None,
                 block_role: crate::executor::task::BlockRole::Normal,
                 retries: None,
                 delay: None,
                 until: None,
             };
-            all_tasks.push(gather_facts_task);
+            pre_tasks.push(gather_facts_task);
         }
 
         // Ansible execution order: pre_tasks -> role tasks -> tasks -> post_tasks
-        all_tasks.extend(play.pre_tasks.iter().cloned());
-        // Add role tasks (from play.roles) after pre_tasks and before regular tasks
-        for role in &play.roles {
-            all_tasks.extend(role.get_all_tasks());
+        pre_tasks.extend(play.pre_tasks.iter().cloned());
+        // Role tasks run after pre_tasks and before regular tasks
+        let mut main_tasks: Vec<Task> = play.roles.iter().flat_map(|r| r.get_all_tasks()).collect();
+        main_tasks.extend(play.tasks.iter().cloned());
+        let mut sections = [pre_tasks, main_tasks, play.post_tasks.clone()];
+        for task in sections.iter_mut().flatten() {
+            task.inherit_become(
+                play.r#become,
+                play.become_user.as_deref(),
+                play.become_method.as_deref(),
+            );
         }
-        all_tasks.extend(play.tasks.iter().cloned());
-        all_tasks.extend(play.post_tasks.iter().cloned());
 
         // Execute based on serial specification and strategy
         let execution_result = if let Some(ref serial_spec) = play.serial {
-            self.run_serial(serial_spec, &hosts, &all_tasks, play.max_fail_percentage)
-                .await
+            self.run_serial(
+                serial_spec,
+                &hosts,
+                &sections,
+                play.max_fail_percentage,
+                play.max_fail_scope,
+                play.serial_pause,
+            )
+            .await
         } else {
             // Execute based on strategy without serial batching
-            match self.config.strategy {
-                ExecutionStrategy::Linear => self.run_linear(&hosts, &all_tasks).await,
-                ExecutionStrategy::Free => self.run_free(&hosts, &all_tasks).await,
-                ExecutionStrategy::HostPinned => self.run_host_pinned(&hosts, &all_tasks).await,
-            }
+            self.run_sections(&hosts, &sections).await
         };
 
         // Check if play failed
@@ -574,7 +898,7 @@ impl Executor {
             if play.force_handlers && play_failed {
                 info!("Running handlers despite play failure (force_handlers=true)");
             }
-            self.flush_handlers().await?;
+            self.flush_handlers(&hosts).await?;
         } else {
             // Clear notified handlers without running them
             let notified_count = {
@@ -592,17 +916,76 @@ impl Executor {
         }
 
         info!("Play completed: {}", play.name);
+        if let Some(ref callbacks) = self.callbacks {
+            callbacks.on_play_end(&play.name, !play_failed).await;
+        }
         execution_result
     }
 
+    /// Run the play's task sections in order on `hosts`
+    ///
+    /// As in Ansible, handlers notified in a section run before the next
+    /// section starts, so handlers notified by pre_tasks have run before the
+    /// first role task. Hosts that fail in a section are left out of the
+    /// following ones. Handlers notified in the last section are left for
+    /// the caller to flush.
+    async fn run_sections(
+        &self,
+        hosts: &[String],
+        sections: &[Vec<Task>],
+    ) -> ExecutorResult<HashMap<String, HostResult>> {
+        let mut results: HashMap<String, HostResult> = hosts
+            .iter()
+            .map(|h| {
+                (
+                    h.clone(),
+                    HostResult {
+                        host: h.clone(),
+                        stats: ExecutionStats::default(),
+                        failed: false,
+                        unreachable: false,
+                    },
+                )
+            })
+            .collect();
+        let mut active = hosts.to_vec();
+
+        for (index, tasks) in sections.iter().enumerate() {
+            if index > 0 {
+                self.flush_handlers(&active).await?;
+            }
+            if tasks.is_empty() || active.is_empty() {
+                continue;
+            }
+
+            let section_results = match self.config.strategy {
+                ExecutionStrategy::Linear => self.run_linear(&active, tasks).await?,
+                ExecutionStrategy::Free => self.run_free(&active, tasks).await?,
+                ExecutionStrategy::HostPinned => self.run_host_pinned(&active, tasks).await?,
+            };
+            for (host, section) in section_results {
+                let result = results.entry(host.clone()).or_insert_with(|| HostResult {
+                    host,
+                    stats: ExecutionStats::default(),
+                    failed: false,
+                    unreachable: false,
+                });
+                result.stats.merge(&section.stats);
+                result.failed |= section.failed;
+                result.unreachable |= section.unreachable;
+            }
+            active.retain(|h| results.get(h).is_some_and(|r| !r.failed && !r.unreachable));
+        }
+
+        Ok(results)
+    }
+
     /// Run tasks in linear strategy (all hosts per task before next task)
     async fn run_linear(
         &self,
         hosts: &[String],
         tasks: &[Task],
     ) -> ExecutorResult<HashMap<String, HostResult>> {
-        use crate::executor::task::BlockRole;
-
         // Pre-allocate HashMaps with known capacity
         let host_count = hosts.len();
         let mut results: HashMap<String, HostResult> = HashMap::with_capacity(host_count);
@@ -618,66 +1001,29 @@ impl Executor {
             );
         }
 
-        // Track which blocks have failed (per host) - pre-allocate with capacity
-        let mut failed_blocks: HashMap<String, HashSet<String>> =
-            HashMap::with_capacity(host_count);
-        for h in hosts {
-            failed_blocks.insert(h.clone(), HashSet::new());
-        }
-        // Track which blocks have had their rescue tasks run
-        let mut rescued_blocks: HashMap<String, HashSet<String>> =
-            HashMap::with_capacity(host_count);
-        for h in hosts {
-            rescued_blocks.insert(h.clone(), HashSet::new());
-        }
+        // Block/rescue/always bookkeeping per host
+        let mut blocks: HashMap<String, HostBlocks> = hosts
+            .iter()
+            .map(|h| (h.clone(), HostBlocks::default()))
+            .collect();
 
         for task in tasks {
+            // Leaving a block drops the failure details it exposed
+            for (host, host_blocks) in blocks.iter_mut() {
+                host_blocks.leave_block(&self.runtime, host, task).await;
+            }
+
             // Determine which hosts should run this task based on block state
             let active_hosts: Vec<_> = hosts
                 .iter()
                 .filter(|h| {
-                    let host_result = results.get(*h);
-                    let host_failed_blocks = failed_blocks.get(*h);
-                    let host_rescued_blocks = rescued_blocks.get(*h);
-
-                    // Skip if host has failed (and not in a block)
-                    if host_result
+                    let host_done = results
+                        .get(*h)
                         .map(|r| r.failed || r.unreachable)
-                        .unwrap_or(false)
-                    {
-                        // But still run always tasks
-                        if task.block_role == BlockRole::Always {
-                            return true;
-                        }
-                        return false;
-                    }
-
-                    // Handle block-specific logic
-                    if let Some(ref block_id) = task.block_id {
-                        let block_failed = host_failed_blocks
-                            .map(|blocks| blocks.contains(block_id))
-                            .unwrap_or(false);
-                        let block_rescued = host_rescued_blocks
-                            .map(|blocks| blocks.contains(block_id))
-                            .unwrap_or(false);
-
-                        match task.block_role {
-                            BlockRole::Normal => {
-                                // Skip normal tasks if block has failed
-                                !block_failed
-                            }
-                            BlockRole::Rescue => {
-                                // Run rescue tasks only if block failed and hasn't been rescued yet
-                                block_failed && !block_rescued
-                            }
-                            BlockRole::Always => {
-                                // Always run always tasks
-                                true
-                            }
-                        }
-                    } else {
-                        true
-                    }
+                        .unwrap_or(false);
+                    blocks
+                        .get(*h)
+                        .is_some_and(|host_blocks| host_blocks.should_run(task, host_done))
                 })
                 .cloned()
                 .collect();
@@ -691,8 +1037,27 @@ impl Executor {
                 continue;
             }
 
-            // Run task on all active hosts in parallel (limited by semaphore)
-            let task_results = self.run_task_on_hosts(&active_hosts, task).await?;
+            // Run task on all active hosts in parallel (limited by semaphore).
+            // A run_once task runs on the first active host and shares its result.
+            let run_hosts = if task.runs_once() {
+                &active_hosts[..1]
+            } else {
+                &active_hosts[..]
+            };
+            let mut task_results = self.run_task_on_hosts(run_hosts, task).await?;
+            if task.runs_once() {
+                share_run_once_result(&self.runtime, task, &run_hosts[0], &active_hosts).await;
+                // A failed run_once task fails every host it ran for
+                if let Some(failed) = task_results
+                    .get(&run_hosts[0])
+                    .filter(|result| result.status == TaskStatus::Failed)
+                    .cloned()
+                {
+                    for host in &active_hosts[1..] {
+                        task_results.insert(host.clone(), failed.clone());
+                    }
+                }
+            }
 
             debug!(
                 "Task '{}' completed on {} hosts",
@@ -707,78 +1072,18 @@ impl Executor {
                     host, task_result.status, task_result.changed, task_result.msg
                 );
 
-                if let Some(host_result) = results.get_mut(&host) {
-                    // Check if this task failed
-                    let task_failed =
-                        task_result.status == crate::executor::task::TaskStatus::Failed;
-
-                    // If it's a normal task in a block and it failed, mark the block as failed
-                    if task_failed {
-                        if let Some(ref block_id) = task.block_id {
-                            if task.block_role == BlockRole::Normal {
-                                if let Some(blocks) = failed_blocks.get_mut(&host) {
-                                    blocks.insert(block_id.clone());
-                                }
-                                // Mark that rescue is needed - don't mark host as failed yet
-                            }
-                        }
-                    }
-
-                    // If this is a rescue task, mark the block as rescued
-                    if task.block_role == BlockRole::Rescue {
-                        if let Some(ref block_id) = task.block_id {
-                            if let Some(blocks) = rescued_blocks.get_mut(&host) {
-                                blocks.insert(block_id.clone());
-                            }
-                        }
-                    }
-
-                    // Update stats, but only mark host as failed if:
-                    // - Task is not in a block, OR
-                    // - Task is in a block but there's no rescue section (block failed without rescue)
-                    let should_mark_failed = if task.block_id.is_some() {
-                        // For block tasks, we handle failure differently
-                        // The host only fails if rescue also fails
-                        task.block_role == BlockRole::Rescue && task_failed
-                    } else {
-                        task_failed
-                    };
-
-                    // Temporarily modify result for stats update
-                    let mut modified_result = task_result.clone();
-                    if task.block_id.is_some()
-                        && task.block_role == BlockRole::Normal
-                        && task_failed
-                    {
-                        // Don't count normal block failure as host failure
-                        modified_result.status = crate::executor::task::TaskStatus::Ok;
-                    }
-
-                    self.update_host_stats(host_result, &modified_result);
-
-                    // Now set the actual failure state
-                    if should_mark_failed && !task.ignore_errors {
-                        host_result.failed = true;
-                    }
+                if let (Some(host_result), Some(host_blocks)) =
+                    (results.get_mut(&host), blocks.get_mut(&host))
+                {
+                    host_blocks
+                        .record(&self.runtime, host_result, task, &task_result)
+                        .await;
                 }
             }
         }
 
-        // After all tasks, check if any blocks failed without being rescued
-        for (host, host_failed_blocks) in &failed_blocks {
-            if let Some(_host_result) = results.get_mut(host) {
-                let host_rescued = rescued_blocks.get(host);
-                for block_id in host_failed_blocks {
-                    let was_rescued = host_rescued.map(|r| r.contains(block_id)).unwrap_or(false);
-                    if !was_rescued {
-                        // Block failed without rescue - this is a failure
-                        // But we need to check if there was a rescue section defined
-                        // For now, assume if rescue tasks were found, it was rescued
-                        // If no rescue tasks exist, it's a real failure
-                        // This is a simplification - proper implementation would track this differently
-                    }
-                }
-            }
+        for (host, host_blocks) in blocks.iter_mut() {
+            host_blocks.finish(&self.runtime, host).await;
         }
 
         Ok(results)
@@ -795,7 +1100,7 @@ impl Executor {
         // OPTIMIZATION: Fast path for single host
         if hosts.len() == 1 {
             let host = &hosts[0];
-            let _permit = self.semaphore.acquire().await.unwrap();
+            let _slot = HostSlot::acquire(&self.semaphore, &self.host_gauge).await;
 
             let mut host_result = HostResult {
                 host: host.clone(),
@@ -803,39 +1108,41 @@ impl Executor {
                 failed: false,
                 unreachable: false,
             };
+            let mut blocks = HostBlocks::default();
 
             for task in tasks {
-                if host_result.failed || host_result.unreachable {
-                    break;
+                blocks.leave_block(&self.runtime, host, task).await;
+                if !blocks.should_run(task, host_result.failed || host_result.unreachable) {
+                    continue;
                 }
 
                 let ctx = ExecutionContext::new(host.clone())
                     .with_check_mode(self.config.check_mode)
-                    .with_diff_mode(self.config.diff_mode);
-
-                let task_result = task
-                    .execute(
-                        &ctx,
-                        &self.runtime,
-                        &self.handlers,
-                        &self.notified_handlers,
-                        &self.parallelization_manager,
-                    )
-                    .await;
-
-                match task_result {
-                    Ok(result) => {
-                        update_stats(&mut host_result.stats, &result);
-                        if result.status == TaskStatus::Failed {
-                            host_result.failed = true;
-                        }
+                    .with_diff_mode(self.config.diff_mode)
+                    .with_safe_mode(self.config.safe_mode.clone())
+                    .with_become_defaults(self.config.become_defaults.clone());
+
+                let task_result = match connect_context(ctx, self.connections.as_ref()).await {
+                    Ok(ctx) => {
+                        execute_task_timed(
+                            task,
+                            &ctx,
+                            &self.runtime,
+                            &self.handlers,
+                            &self.notified_handlers,
+                            &self.parallelization_manager,
+                            self.callbacks.as_deref(),
+                        )
+                        .await
                     }
-                    Err(_) => {
-                        host_result.failed = true;
-                        host_result.stats.failed += 1;
-                    }
-                }
+                    Err(e) => Err(e),
+                };
+                let task_result = task_result.unwrap_or_else(|e| TaskResult::from_error(&e));
+                blocks
+                    .record(&self.runtime, &mut host_result, task, &task_result)
+                    .await;
             }
+            blocks.finish(&self.runtime, host).await;
 
             let mut results = HashMap::with_capacity(1);
             results.insert(host.clone(), host_result);
@@ -849,6 +1156,12 @@ impl Executor {
         // Avoid cloning entire task list - use Arc slice instead
         let tasks: Arc<[Task]> = tasks.iter().cloned().collect::<Vec<_>>().into();
         let results = Arc::new(Mutex::new(HashMap::with_capacity(hosts.len())));
+        let all_hosts: Arc<[String]> = hosts.to_vec().into();
+        // One cell per task: the first host to reach a run_once task runs it
+        // inside the cell, every other host waits there until it has finished
+        // and picks up the result if it failed
+        let run_once_done: Arc<[tokio::sync::OnceCell<Option<TaskResult>>]> =
+            tasks.iter().map(|_| tokio::sync::OnceCell::new()).collect();
 
         let handles: Vec<_> = hosts
             .iter()
@@ -857,13 +1170,20 @@ impl Executor {
                 let tasks = Arc::clone(&tasks);
                 let results = Arc::clone(&results);
                 let semaphore = Arc::clone(&self.semaphore);
+                let gauge = Arc::clone(&self.host_gauge);
                 let runtime = Arc::clone(&self.runtime);
                 let handlers = Arc::clone(&self.handlers);
                 let notified = Arc::clone(&self.notified_handlers);
                 let parallelization_local = Arc::clone(&self.parallelization_manager);
+                let callbacks = self.callbacks.clone();
+                let safe_mode = self.config.safe_mode.clone();
+                let become_defaults = self.config.become_defaults.clone();
+                let connections = self.connections.clone();
+                let all_hosts = Arc::clone(&all_hosts);
+                let run_once_done = Arc::clone(&run_once_done);
 
                 tokio::spawn(async move {
-                    let _permit = semaphore.acquire().await.unwrap();
+                    let _slot = HostSlot::acquire(&semaphore, &gauge).await;
 
                     let mut host_result = HostResult {
                         host: host.clone(),
@@ -871,33 +1191,76 @@ impl Executor {
                         failed: false,
                         unreachable: false,
                     };
+                    let mut blocks = HostBlocks::default();
 
-                    for task in tasks.iter() {
-                        if host_result.failed || host_result.unreachable {
-                            break;
+                    for (index, task) in tasks.iter().enumerate() {
+                        blocks.leave_block(&runtime, &host, task).await;
+                        if !blocks.should_run(task, host_result.failed || host_result.unreachable) {
+                            continue;
                         }
 
                         let ctx = ExecutionContext::new(host.clone())
                             .with_check_mode(check_mode)
-                            .with_diff_mode(diff_mode);
-
-                        let task_result = task
-                            .execute(&ctx, &runtime, &handlers, &notified, &parallelization_local)
-                            .await;
-
-                        match task_result {
-                            Ok(result) => {
-                                update_stats(&mut host_result.stats, &result);
-                                if result.status == TaskStatus::Failed {
-                                    host_result.failed = true;
-                                }
+                            .with_diff_mode(diff_mode)
+                            .with_safe_mode(safe_mode.clone())
+                            .with_become_defaults(become_defaults.clone());
+                        let ctx = match connect_context(ctx, connections.as_ref()).await {
+                            Ok(ctx) => ctx,
+                            Err(e) => {
+                                let unreachable = TaskResult::from_error(&e);
+                                blocks
+                                    .record(&runtime, &mut host_result, task, &unreachable)
+                                    .await;
+                                continue;
                             }
-                            Err(_) => {
-                                host_result.failed = true;
-                                host_result.stats.failed += 1;
+                        };
+
+                        let run = || {
+                            execute_task_timed(
+                                task,
+                                &ctx,
+                                &runtime,
+                                &handlers,
+                                &notified,
+                                &parallelization_local,
+                                callbacks.as_deref(),
+                            )
+                        };
+
+                        let task_result = if task.runs_once() {
+                            // Hosts that lose the race wait here until the
+                            // registered result has been shared with them
+                            let mut claimed = None;
+                            let failed = run_once_done[index]
+                                .get_or_init(|| async {
+                                    let result = run().await;
+                                    share_run_once_result(&runtime, task, &host, &all_hosts).await;
+                                    let shared = match &result {
+                                        Ok(result) => result.clone(),
+                                        Err(e) => TaskResult::from_error(e),
+                                    };
+                                    claimed = Some(result);
+                                    Some(shared).filter(|r| r.status == TaskStatus::Failed)
+                                })
+                                .await;
+                            // Like the linear strategy, a failed run_once
+                            // task fails the waiting hosts too
+                            match (claimed, failed) {
+                                (Some(result), _) => result,
+                                (None, Some(failed)) => Ok(failed.clone()),
+                                (None, None) => continue,
                             }
-                        }
+                        } else {
+                            run().await
+                        };
+
+                        let task_result =
+                            task_result.unwrap_or_else(|e| TaskResult::from_error(&e));
+                        blocks
+                            .record(&runtime, &mut host_result, task, &task_result)
+                            .await;
                     }
+                    blocks.finish(&runtime, &host).await;
 
                     results.lock().await.insert(host, host_result);
                 })
@@ -906,10 +1269,24 @@ impl Executor {
 
         join_all(handles).await;
 
-        let results = Arc::try_unwrap(results)
+        let mut results = Arc::try_unwrap(results)
             .map_err(|_| ExecutorError::RuntimeError("Failed to unwrap results".into()))?
             .into_inner();
 
+        // A host whose worker panicked still gets a (failed) result, so it is
+        // counted in the recap and in serial batch failure accounting
+        for host in hosts {
+            results.entry(host.clone()).or_insert_with(|| HostResult {
+                host: host.clone(),
+                stats: ExecutionStats {
+                    failed: 1,
+                    ..Default::default()
+                },
+                failed: true,
+                unreachable: false,
+            });
+        }
+
         Ok(results)
     }
 
@@ -929,12 +1306,14 @@ impl Executor {
         &self,
         serial_spec: &crate::playbook::SerialSpec,
         hosts: &[String],
-        tasks: &[Task],
+        sections: &[Vec<Task>],
         max_fail_percentage: Option<u8>,
+        max_fail_scope: crate::playbook::MaxFailScope,
+        serial_pause: Option<std::time::Duration>,
     ) -> ExecutorResult<HashMap<String, HostResult>> {
         info!(
-            "Running with serial batching: {:?}, max_fail_percentage: {:?}",
-            serial_spec, max_fail_percentage
+            "Running with serial batching: {:?}, max_fail_percentage: {:?} ({:?}), serial_pause: {:?}",
+            serial_spec, max_fail_percentage, max_fail_scope, serial_pause
         );
 
         // Split hosts into batches
@@ -948,7 +1327,13 @@ impl Executor {
 
         let mut all_results: HashMap<String, HostResult> = HashMap::new();
         let mut total_failed = 0;
+        let mut hosts_completed = 0;
         let total_hosts = hosts.len();
+        let serial_start = std::time::Instant::now();
+
+        // A first serial entry smaller than the rest (e.g. `serial: [1, "100%"]`)
+        // acts as a canary for the rest of the rollout.
+        let has_canary = batches.len() > 1 && serial_spec.has_canary(hosts.len());
 
         // Execute each batch sequentially
         for (batch_idx, batch_hosts) in batches.iter().enumerate() {
@@ -962,15 +1347,13 @@ impl Executor {
             // Convert batch hosts to owned Strings
             let batch_hosts_owned: Vec<String> =
                 batch_hosts.iter().map(|s| s.to_string()).collect();
+            self.runtime
+                .write()
+                .await
+                .set_play_batch(&batch_hosts_owned);
 
             // Execute this batch based on the configured strategy
-            let batch_results = match self.config.strategy {
-                ExecutionStrategy::Linear => self.run_linear(&batch_hosts_owned, tasks).await?,
-                ExecutionStrategy::Free => self.run_free(&batch_hosts_owned, tasks).await?,
-                ExecutionStrategy::HostPinned => {
-                    self.run_host_pinned(&batch_hosts_owned, tasks).await?
-                }
-            };
+            let batch_results = self.run_sections(&batch_hosts_owned, sections).await?;
 
             // Count failures in this batch
             let batch_failed = batch_results
@@ -980,40 +1363,124 @@ impl Executor {
 
             total_failed += batch_failed;
 
+            // Handlers notified in this batch run before the next batch starts,
+            // on the batch hosts that are still healthy
+            let healthy_hosts: Vec<String> = batch_hosts_owned
+                .iter()
+                .filter(|h| {
+                    batch_results
+                        .get(*h)
+                        .is_some_and(|r| !r.failed && !r.unreachable)
+                })
+                .cloned()
+                .collect();
+            if healthy_hosts.is_empty() {
+                // No host left to run them on; don't let them carry over
+                // into the next batch
+                self.notified_handlers.lock().await.clear();
+            } else {
+                self.flush_handlers(&healthy_hosts).await?;
+            }
+
             // Merge batch results into overall results
             for (host, result) in batch_results {
                 all_results.insert(host, result);
             }
 
-            // Check max_fail_percentage if specified
-            if let Some(max_fail_pct) = max_fail_percentage {
-                let current_fail_pct = (total_failed as f64 / total_hosts as f64 * 100.0) as u8;
-
-                if current_fail_pct > max_fail_pct {
-                    error!(
-                        "Failure percentage ({:.1}%) exceeded max_fail_percentage ({}%), aborting remaining batches",
-                        current_fail_pct, max_fail_pct
-                    );
-
-                    // Mark remaining hosts as skipped
-                    for remaining_batch in batches.iter().skip(batch_idx + 1) {
-                        for host in remaining_batch.iter() {
-                            all_results.insert(
-                                host.to_string(),
-                                HostResult {
-                                    host: host.to_string(),
-                                    stats: ExecutionStats {
-                                        skipped: tasks.len(),
-                                        ..Default::default()
-                                    },
-                                    failed: false,
-                                    unreachable: false,
+            hosts_completed += batch_hosts.len();
+            if let Some(ref callbacks) = self.callbacks {
+                // Pauses between batches are part of the average, since the
+                // remaining batches will pause too
+                let batches_done = batch_idx + 1;
+                let remaining_batches = batches.len() - batches_done;
+                let eta = (remaining_batches > 0).then(|| {
+                    serial_start.elapsed() / batches_done as u32 * remaining_batches as u32
+                });
+                let progress = crate::traits::SerialProgress {
+                    batch: batches_done,
+                    total_batches: batches.len(),
+                    hosts_completed,
+                    total_hosts,
+                    hosts_failed: total_failed,
+                    eta,
+                };
+                callbacks.on_batch_complete(&progress).await;
+            }
+
+            let abort_reason = if batch_idx == 0 && has_canary && batch_failed > 0 {
+                // The canary batch is judged on its own so that a single
+                // failing canary host stops the rollout before the larger batches.
+                let canary_fail_pct = batch_failed as f64 / batch_hosts.len() as f64 * 100.0;
+                match max_fail_percentage {
+                    Some(max_fail_pct) if canary_fail_pct > max_fail_pct as f64 => Some(format!(
+                        "Canary batch failure percentage ({:.1}%) exceeded max_fail_percentage ({}%)",
+                        canary_fail_pct, max_fail_pct
+                    )),
+                    None if batch_failed == batch_hosts.len() => {
+                        Some("All hosts in the canary batch failed".to_string())
+                    }
+                    _ => None,
+                }
+            } else if let Some(max_fail_pct) = max_fail_percentage {
+                let (failed, scope_hosts, label) = match max_fail_scope {
+                    crate::playbook::MaxFailScope::Play => {
+                        (total_failed, total_hosts, "Failure percentage")
+                    }
+                    crate::playbook::MaxFailScope::Batch => {
+                        (batch_failed, batch_hosts.len(), "Batch failure percentage")
+                    }
+                };
+                let current_fail_pct = (failed as f64 / scope_hosts as f64 * 100.0) as u8;
+                (current_fail_pct > max_fail_pct).then(|| {
+                    format!(
+                        "{} ({}%) exceeded max_fail_percentage ({}%)",
+                        label, current_fail_pct, max_fail_pct
+                    )
+                })
+            } else {
+                None
+            };
+
+            if let Some(reason) = abort_reason {
+                error!("{}, aborting remaining batches", reason);
+
+                // Mark remaining hosts as skipped
+                for remaining_batch in batches.iter().skip(batch_idx + 1) {
+                    for host in remaining_batch.iter() {
+                        all_results.insert(
+                            host.to_string(),
+                            HostResult {
+                                host: host.to_string(),
+                                stats: ExecutionStats {
+                                    skipped: sections.iter().map(Vec::len).sum(),
+                                    ..Default::default()
                                 },
-                            );
-                        }
+                                failed: false,
+                                unreachable: false,
+                            },
+                        );
                     }
+                }
 
-                    break;
+                break;
+            }
+
+            // Soak time before the next batch; nothing waits after the last one
+            let pause = serial_pause.filter(|p| !p.is_zero() && batch_idx + 1 < batches.len());
+            if let Some(pause) = pause {
+                info!(
+                    "Batch {}/{} complete, pausing {:?} before the next batch",
+                    batch_idx + 1,
+                    batches.len(),
+                    pause
+                );
+                if let Some(ref callbacks) = self.callbacks {
+                    callbacks
+                        .on_batch_pause(batch_idx + 1, batches.len(), pause)
+                        .await;
+                }
+                if !self.batch_pause(pause).await {
+                    warn!("Batch pause interrupted, continuing with the next batch");
                 }
             }
         }
@@ -1026,6 +1493,20 @@ impl Executor {
         Ok(all_results)
     }
 
+    /// Sleep between serial batches, returning early with `false` when
+    /// `batch_pause_skip` is notified
+    async fn batch_pause(&self, duration: std::time::Duration) -> bool {
+        let Some(ref skip) = self.config.batch_pause_skip else {
+            tokio::time::sleep(duration).await;
+            return true;
+        };
+
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => true,
+            _ = skip.notified() => false,
+        }
+    }
+
     /// Run a single task on multiple hosts in parallel
     ///
     /// OPTIMIZATION: Fast path for single host and small host counts (< 10)
@@ -1040,21 +1521,29 @@ impl Executor {
         // OPTIMIZATION: Fast path for single host - avoid Arc overhead and tokio::spawn
         if hosts.len() == 1 {
             let host = &hosts[0];
-            let _permit = self.semaphore.acquire().await.unwrap();
+            let _slot = HostSlot::acquire(&self.semaphore, &self.host_gauge).await;
 
             let ctx = ExecutionContext::new(host.clone())
                 .with_check_mode(self.config.check_mode)
-                .with_diff_mode(self.config.diff_mode);
-
-            let result = task
-                .execute(
-                    &ctx,
-                    &self.runtime,
-                    &self.handlers,
-                    &self.notified_handlers,
-                    &self.parallelization_manager,
-                )
-                .await;
+                .with_diff_mode(self.config.diff_mode)
+                .with_safe_mode(self.config.safe_mode.clone())
+                .with_become_defaults(self.config.become_defaults.clone());
+
+            let result = match connect_context(ctx, self.connections.as_ref()).await {
+                Ok(ctx) => {
+                    execute_task_timed(
+                        task,
+                        &ctx,
+                        &self.runtime,
+                        &self.handlers,
+                        &self.notified_handlers,
+                        &self.parallelization_manager,
+                        self.callbacks.as_deref(),
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            };
 
             let mut results = HashMap::with_capacity(1);
             match result {
@@ -1063,16 +1552,7 @@ impl Executor {
                 }
                 Err(e) => {
                     error!("Task failed on host {}: {}", host, e);
-                    results.insert(
-                        host.clone(),
-                        TaskResult {
-                            status: TaskStatus::Failed,
-                            changed: false,
-                            msg: Some(e.to_string()),
-                            result: None,
-                            diff: None,
-                        },
-                    );
+                    results.insert(host.clone(), TaskResult::from_error(&e));
                 }
             }
             return Ok(results);
@@ -1093,21 +1573,40 @@ impl Executor {
                 let task = Arc::clone(&task_arc);
                 let results = Arc::clone(&results);
                 let semaphore = Arc::clone(&self.semaphore);
+                let gauge = Arc::clone(&self.host_gauge);
                 let runtime = Arc::clone(&self.runtime);
                 let handlers = Arc::clone(&self.handlers);
                 let notified = Arc::clone(&self.notified_handlers);
                 let parallelization = Arc::clone(&self.parallelization_manager);
+                let callbacks = self.callbacks.clone();
+                let safe_mode = self.config.safe_mode.clone();
+                let become_defaults = self.config.become_defaults.clone();
+                let connections = self.connections.clone();
 
                 tokio::spawn(async move {
-                    let _permit = semaphore.acquire().await.unwrap();
+                    let _slot = HostSlot::acquire(&semaphore, &gauge).await;
 
                     let ctx = ExecutionContext::new(host.clone())
                         .with_check_mode(check_mode)
-                        .with_diff_mode(diff_mode);
-
-                    let result = task
-                        .execute(&ctx, &runtime, &handlers, &notified, &parallelization)
-                        .await;
+                        .with_diff_mode(diff_mode)
+                        .with_safe_mode(safe_mode)
+                        .with_become_defaults(become_defaults);
+
+                    let result = match connect_context(ctx, connections.as_ref()).await {
+                        Ok(ctx) => {
+                            execute_task_timed(
+                                &task,
+                                &ctx,
+                                &runtime,
+                                &handlers,
+                                &notified,
+                                &parallelization,
+                                callbacks.as_deref(),
+                            )
+                            .await
+                        }
+                        Err(e) => Err(e),
+                    };
 
                     match result {
                         Ok(task_result) => {
@@ -1115,16 +1614,10 @@ impl Executor {
                         }
                         Err(e) => {
                             error!("Task failed on host {}: {}", host, e);
-                            results.lock().await.insert(
-                                host,
-                                TaskResult {
-                                    status: TaskStatus::Failed,
-                                    changed: false,
-                                    msg: Some(e.to_string()),
-                                    result: None,
-                                    diff: None,
-                                },
-                            );
+                            results
+                                .lock()
+                                .await
+                                .insert(host, TaskResult::from_error(&e));
                         }
                     }
                 })
@@ -1140,20 +1633,22 @@ impl Executor {
         Ok(results)
     }
 
-    /// Update host statistics based on task result
-    fn update_host_stats(&self, host_result: &mut HostResult, task_result: &TaskResult) {
-        update_stats(&mut host_result.stats, task_result);
-        if task_result.status == TaskStatus::Failed {
-            host_result.failed = true;
-        } else if task_result.status == TaskStatus::Unreachable {
-            host_result.unreachable = true;
-        }
-    }
-
     /// Resolve host pattern to list of hosts
+    ///
+    /// Results are cached per pattern until the runtime inventory changes.
     async fn resolve_hosts(&self, pattern: &str) -> ExecutorResult<Vec<String>> {
         let runtime = self.runtime.read().await;
+        if let Some(hosts) = runtime.host_pattern_cache().get(pattern) {
+            return Ok(hosts);
+        }
+
+        let hosts = Self::match_hosts(&runtime, pattern)?;
+        runtime.host_pattern_cache().insert(pattern, hosts.clone());
+        Ok(hosts)
+    }
 
+    /// Match a host pattern against the runtime inventory
+    fn match_hosts(runtime: &RuntimeContext, pattern: &str) -> ExecutorResult<Vec<String>> {
         // Handle special patterns
         if pattern == "all" {
             return Ok(runtime.get_all_hosts());
@@ -1191,7 +1686,9 @@ impl Executor {
     /// 2. Ensures handlers run in definition order
     /// 3. Supports handler chaining (handlers can notify other handlers)
     /// 4. Deduplicates handlers so each runs only once per flush
-    async fn flush_handlers(&self) -> ExecutorResult<()> {
+    ///
+    /// Handlers run on `hosts`: the play hosts, or the current serial batch.
+    async fn flush_handlers(&self, hosts: &[String]) -> ExecutorResult<()> {
         let notified: Vec<String> = {
             let mut notified = self.notified_handlers.lock().await;
             let handlers: Vec<_> = notified.drain().collect();
@@ -1247,29 +1744,24 @@ impl Executor {
             return Ok(());
         }
 
-        // Sort handlers by their definition order (order in the handlers map)
-        // We use the order from the handlers HashMap which preserves insertion order
+        // Run handlers in definition order, not notification order
+        let order = self.handler_order.read().await;
         let mut ordered_handlers: Vec<&Handler> = handlers
             .values()
             .filter(|h| handlers_to_run.contains(&h.name))
             .collect();
-
-        // Stable sort is not needed since HashMap doesn't preserve order
-        // We'll use the order handlers appear in the play's handlers vector
-        // For now, alphabetical order ensures consistent behavior
-        ordered_handlers.sort_by(|a, b| a.name.cmp(&b.name));
+        ordered_handlers.sort_by_key(|h| {
+            order
+                .iter()
+                .position(|name| name == &h.name)
+                .unwrap_or(usize::MAX)
+        });
 
         info!("Running {} unique handlers", ordered_handlers.len());
 
         // Track handlers that have already run in this flush cycle
         let mut executed_handlers: HashSet<String> = HashSet::new();
 
-        // Get all active hosts from runtime
-        let hosts = {
-            let runtime = self.runtime.read().await;
-            runtime.get_all_hosts()
-        };
-
         // Execute handlers, supporting handler chaining
         // We loop until no new handlers are notified
         let mut current_handlers = ordered_handlers;
@@ -1291,10 +1783,12 @@ impl Executor {
                     name: handler.name.clone(),
                     module: handler.module.clone(),
                     args: handler.args.clone(),
+                    vars: IndexMap::new(),
                     when: handler.when.clone(),
                     notify: Vec::new(), // Handlers don't chain via task.notify in our model
                     register: None,
                     loop_items: None,
+                    loop_template: None,
                     loop_var: "item".to_string(),
                     loop_control: None,
                     ignore_errors: false,
@@ -1304,8 +1798,9 @@ impl Executor {
                     delegate_facts: None,
                     run_once: false,
                     tags: Vec::new(),
-                    r#become: false,
+                    r#become: None,
                     become_user: None,
+                    become_method: None,
                     block_id: None,
                     block_role: crate::executor::task::BlockRole::Normal,
                     retries: None,
@@ -1314,7 +1809,7 @@ impl Executor {
                 };
 
                 // Run handler on all hosts
-                let results = self.run_task_on_hosts(&hosts, &task).await?;
+                let results = self.run_task_on_hosts(hosts, &task).await?;
 
                 // Check if handler execution triggered any changes
                 // If so, check if any handlers listen to this handler's name (handler chaining)
@@ -1374,6 +1869,14 @@ impl Executor {
         Arc::clone(&self.runtime)
     }
 
+    /// Get the manager enforcing module parallelization hints
+    ///
+    /// Custom modules register their hints here so rate limits and global
+    /// exclusivity apply across every host the executor runs on.
+    pub fn parallelization_manager(&self) -> Arc<ParallelizationManager> {
+        Arc::clone(&self.parallelization_manager)
+    }
+
     /// Get execution statistics summary
     pub fn summarize_results(results: &HashMap<String, HostResult>) -> ExecutionStats {
         let mut summary = ExecutionStats::default();
@@ -1401,6 +1904,104 @@ fn update_stats(stats: &mut ExecutionStats, result: &TaskResult) {
     }
 }
 
+/// Give every host the registered result of a `run_once` task
+///
+/// The task ran on `source` only; like Ansible, the other hosts see the same
+/// registered variable afterwards.
+async fn share_run_once_result(
+    runtime: &Arc<RwLock<RuntimeContext>>,
+    task: &Task,
+    source: &str,
+    hosts: &[String],
+) {
+    let Some(ref name) = task.register else {
+        return;
+    };
+    let mut rt = runtime.write().await;
+    let Some(result) = rt.get_registered(source, name).cloned() else {
+        return;
+    };
+    for host in hosts.iter().filter(|h| h.as_str() != source) {
+        rt.register_result(host, name.clone(), result.clone());
+    }
+}
+
+/// Give a host's context a pooled connection when the executor runs over a
+/// connection factory
+async fn connect_context(
+    ctx: ExecutionContext,
+    connections: Option<&ConnectionFactory>,
+) -> ExecutorResult<ExecutionContext> {
+    let Some(connections) = connections else {
+        return Ok(ctx);
+    };
+    let connection = connections
+        .get_connection(&ctx.host)
+        .await
+        .map_err(|e| ExecutorError::HostUnreachable(format!("{}: {}", ctx.host, e)))?;
+    Ok(ctx.with_connection(connection))
+}
+
+/// Execute a task on one host, reporting its start and timed completion
+/// to the callbacks when any are registered
+async fn execute_task_timed(
+    task: &Task,
+    ctx: &ExecutionContext,
+    runtime: &Arc<RwLock<RuntimeContext>>,
+    handlers: &Arc<RwLock<HashMap<String, Handler>>>,
+    notified: &Arc<Mutex<HashSet<String>>>,
+    parallelization_manager: &Arc<ParallelizationManager>,
+    callbacks: Option<&CallbackManager>,
+) -> ExecutorResult<TaskResult> {
+    let Some(callbacks) = callbacks else {
+        return task
+            .execute(ctx, runtime, handlers, notified, parallelization_manager)
+            .await;
+    };
+
+    callbacks.on_task_start(&task.name, &ctx.host).await;
+    let started = std::time::Instant::now();
+    let result = task
+        .execute(ctx, runtime, handlers, notified, parallelization_manager)
+        .await;
+    let duration = started.elapsed();
+
+    let module_result = match &result {
+        Ok(task_result) => task_result_summary(task_result),
+        Err(e) => crate::traits::ModuleResult::failed(e.to_string()),
+    };
+    callbacks
+        .on_task_complete(&crate::traits::ExecutionResult {
+            host: ctx.host.clone(),
+            task_name: task.name.clone(),
+            result: module_result,
+            duration,
+            notify: task.notify.clone(),
+        })
+        .await;
+
+    result
+}
+
+/// Summarize a task result in the shape callbacks consume
+fn task_result_summary(result: &TaskResult) -> crate::traits::ModuleResult {
+    let message = result.msg.clone().unwrap_or_default();
+    let mut summary = match result.status {
+        TaskStatus::Ok | TaskStatus::Changed if result.changed => {
+            crate::traits::ModuleResult::changed(message)
+        }
+        TaskStatus::Ok => crate::traits::ModuleResult::ok(message),
+        TaskStatus::Changed => crate::traits::ModuleResult::changed(message),
+        TaskStatus::Skipped => crate::traits::ModuleResult::skipped(message),
+        TaskStatus::Failed | TaskStatus::Unreachable => {
+            crate::traits::ModuleResult::failed(message)
+        }
+    };
+    summary.data = result.result.clone();
+    summary.warnings = result.warnings.clone();
+    summary
+}
+
 /// Dependency graph for task ordering using topological sort.
 ///
 /// Used internally to resolve task dependencies and detect circular
@@ -1519,6 +2120,32 @@ pub type TaskExecutor = Executor;
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_resolve_hosts_is_cached_until_inventory_changes() {
+        let executor = Executor::new(ExecutorConfig::default());
+        {
+            let mut runtime = executor.runtime.write().await;
+            runtime.add_host("web1".to_string(), Some("webservers"));
+            runtime.add_host("web2".to_string(), Some("webservers"));
+        }
+
+        let first = executor.resolve_hosts("webservers").await.unwrap();
+        let second = executor.resolve_hosts("webservers").await.unwrap();
+        assert_eq!(first, vec!["web1", "web2"]);
+        assert_eq!(second, first);
+        assert_eq!(executor.runtime.read().await.host_pattern_cache().hits(), 1);
+
+        // group_by adds hosts to groups at runtime, which must invalidate
+        executor
+            .runtime
+            .write()
+            .await
+            .add_host("web3".to_string(), Some("webservers"));
+        let third = executor.resolve_hosts("webservers").await.unwrap();
+        assert_eq!(third, vec!["web1", "web2", "web3"]);
+        assert_eq!(executor.runtime.read().await.host_pattern_cache().hits(), 1);
+    }
+
     #[test]
     fn test_dependency_graph_no_cycle() {
         let mut graph = DependencyGraph::new();
@@ -1572,4 +2199,22 @@ mod tests {
         assert_eq!(stats1.skipped, 1);
         assert_eq!(stats1.unreachable, 1);
     }
+
+    #[test]
+    fn test_unreachable_error_counts_in_unreachable_stat() {
+        let mut stats = ExecutionStats::default();
+
+        let unreachable = TaskResult::from_error(&ExecutorError::HostUnreachable(
+            "Connection failed: web1".into(),
+        ));
+        assert_eq!(unreachable.status, TaskStatus::Unreachable);
+        update_stats(&mut stats, &unreachable);
+
+        let failed = TaskResult::from_error(&ExecutorError::TaskFailed("boom".into()));
+        assert_eq!(failed.status, TaskStatus::Failed);
+        update_stats(&mut stats, &failed);
+
+        assert_eq!(stats.unreachable, 1);
+        assert_eq!(stats.failed, 1);
+    }
 }
@@ -3,7 +3,8 @@
 //! This module provides synchronization primitives to enforce the parallelization
 //! hints declared by modules, preventing race conditions and resource contention.
 
-use crate::modules::ParallelizationHint;
+use crate::modules::{Module, ModuleRegistry, ParallelizationHint};
+use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -11,6 +12,9 @@ use std::time::{Duration, Instant};
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tracing::debug;
 
+/// Hints declared by the built-in modules, built once on first lookup
+static BUILTIN_REGISTRY: Lazy<ModuleRegistry> = Lazy::new(ModuleRegistry::with_builtins);
+
 /// Token bucket for rate limiting
 struct TokenBucket {
     /// Maximum tokens (capacity)
@@ -25,6 +29,8 @@ struct TokenBucket {
 
 impl TokenBucket {
     fn new(requests_per_second: u32) -> Self {
+        // A zero rate would never refill; treat it as one request per second
+        let requests_per_second = requests_per_second.max(1);
         Self {
             capacity: requests_per_second,
             tokens: requests_per_second as f64,
@@ -74,6 +80,8 @@ pub struct ParallelizationManager {
     global_mutex: Arc<Semaphore>,
     /// Token buckets for rate-limited modules, keyed by module name
     rate_limiters: Arc<Mutex<HashMap<String, TokenBucket>>>,
+    /// Hints for modules outside the built-in registry, keyed by module name
+    hints: Arc<Mutex<HashMap<String, ParallelizationHint>>>,
 }
 
 impl ParallelizationManager {
@@ -83,7 +91,32 @@ impl ParallelizationManager {
             host_semaphores: Arc::new(Mutex::new(HashMap::new())),
             global_mutex: Arc::new(Semaphore::new(1)),
             rate_limiters: Arc::new(Mutex::new(HashMap::new())),
+            hints: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Set the hint used for a module, overriding what the module declares
+    pub fn set_hint(&self, module_name: impl Into<String>, hint: ParallelizationHint) {
+        self.hints.lock().insert(module_name.into(), hint);
+    }
+
+    /// Record the hint a custom module declares
+    pub fn register_module(&self, module: &dyn Module) {
+        self.set_hint(module.name(), module.parallelization_hint());
+    }
+
+    /// Resolve the hint for a module
+    ///
+    /// Registered hints take precedence over the built-in modules. Unknown
+    /// modules (e.g. the Python fallback) run fully parallel.
+    pub fn hint_for(&self, module_name: &str) -> ParallelizationHint {
+        if let Some(hint) = self.hints.lock().get(module_name) {
+            return *hint;
         }
+        BUILTIN_REGISTRY
+            .get(module_name)
+            .map(|module| module.parallelization_hint())
+            .unwrap_or(ParallelizationHint::FullyParallel)
     }
 
     /// Acquire necessary permits/locks for a module execution
@@ -526,4 +559,21 @@ mod tests {
         assert_eq!(stats.host_locks.get("host1"), Some(&0)); // Locked
         assert_eq!(stats.global_available, 0); // Locked
     }
+
+    #[test]
+    fn test_hint_for_builtin_unknown_and_registered() {
+        let manager = ParallelizationManager::new();
+
+        assert_eq!(manager.hint_for("apt"), ParallelizationHint::HostExclusive);
+        assert_eq!(
+            manager.hint_for("community.custom"),
+            ParallelizationHint::FullyParallel
+        );
+
+        let hint = ParallelizationHint::RateLimited {
+            requests_per_second: 2,
+        };
+        manager.set_hint("community.custom", hint);
+        assert_eq!(manager.hint_for("community.custom"), hint);
+    }
 }
@@ -41,8 +41,27 @@ where
     }
 }
 
+/// Helper function to deserialize an optional version, which YAML may have
+/// parsed as a number (`min_rustible_version: 0.2`)
+fn deserialize_option_version<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+    match Option::<JsonValue>::deserialize(deserializer)? {
+        None | Some(JsonValue::Null) => Ok(None),
+        Some(JsonValue::String(s)) => Ok(Some(s)),
+        Some(JsonValue::Number(n)) => Ok(Some(n.to_string())),
+        Some(other) => Err(D::Error::custom(format!(
+            "invalid version value: {:?}",
+            other
+        ))),
+    }
+}
+
 /// Helper function to deserialize optional flexible booleans
-#[allow(dead_code)]
 fn deserialize_option_flexible_bool<'de, D>(
     deserializer: D,
 ) -> std::result::Result<Option<bool>, D::Error>
@@ -103,14 +122,33 @@ impl Playbook {
 
     /// Load a playbook from a YAML file
     pub fn load<P: AsRef<Path>>(path: P) -> ExecutorResult<Self> {
+        Self::load_with_roles_path(path, &[])
+    }
+
+    /// Load a playbook from a YAML file, searching `roles_path` for roles
+    /// before the `roles/` directory next to the playbook.
+    pub fn load_with_roles_path<P: AsRef<Path>>(
+        path: P,
+        roles_path: &[PathBuf],
+    ) -> ExecutorResult<Self> {
         let path = path.as_ref();
         let content = std::fs::read_to_string(path).map_err(|e| ExecutorError::IoError(e))?;
 
-        Self::parse(&content, Some(path.to_path_buf()))
+        Self::parse_with_roles_path(&content, Some(path.to_path_buf()), roles_path)
     }
 
     /// Parse a playbook from YAML content
     pub fn parse(content: &str, path: Option<PathBuf>) -> ExecutorResult<Self> {
+        Self::parse_with_roles_path(content, path, &[])
+    }
+
+    /// Parse a playbook from YAML content, searching `roles_path` for roles
+    /// before the `roles/` directory next to the playbook.
+    pub fn parse_with_roles_path(
+        content: &str,
+        path: Option<PathBuf>,
+        roles_path: &[PathBuf],
+    ) -> ExecutorResult<Self> {
         // Ansible playbooks are arrays of plays at the top level
         let plays: Vec<PlayDefinition> = serde_yaml::from_str(content)
             .map_err(|e| ExecutorError::ParseError(format!("YAML parse error: {}", e)))?;
@@ -127,7 +165,7 @@ impl Playbook {
         }
 
         for play_def in plays {
-            let play = Play::from_definition(play_def, path.as_ref())?;
+            let play = Play::from_definition(play_def, path.as_ref(), roles_path)?;
             playbook.plays.push(play);
         }
 
@@ -167,9 +205,9 @@ pub struct PlayDefinition {
         deserialize_with = "deserialize_flexible_bool"
     )]
     pub gather_facts: bool,
-    /// Become root
-    #[serde(default, deserialize_with = "deserialize_flexible_bool")]
-    pub r#become: bool,
+    /// Become root (`None` when the play doesn't set it)
+    #[serde(default, deserialize_with = "deserialize_option_flexible_bool")]
+    pub r#become: Option<bool>,
     /// User to become
     #[serde(default)]
     pub become_user: Option<String>,
@@ -215,6 +253,12 @@ pub struct PlayDefinition {
     /// Maximum failure percentage
     #[serde(default)]
     pub max_fail_percentage: Option<u8>,
+    /// Whether max_fail_percentage applies to the play or each batch
+    #[serde(default)]
+    pub max_fail_scope: crate::playbook::MaxFailScope,
+    /// Time to wait between serial batches (seconds or a duration string)
+    #[serde(default, with = "crate::playbook::pause_duration")]
+    pub serial_pause: Option<std::time::Duration>,
     /// Strategy override
     #[serde(default)]
     pub strategy: Option<String>,
@@ -224,6 +268,9 @@ pub struct PlayDefinition {
     /// Force handlers to run even if play fails
     #[serde(default, deserialize_with = "deserialize_flexible_bool")]
     pub force_handlers: bool,
+    /// Oldest rustible release able to run this play
+    #[serde(default, deserialize_with = "deserialize_option_version")]
+    pub min_rustible_version: Option<String>,
     /// Fact gathering subset
     #[serde(default)]
     pub gather_subset: Vec<String>,
@@ -352,12 +399,15 @@ pub struct TaskDefinition {
     /// Tags
     #[serde(default)]
     pub tags: Vec<String>,
-    /// Become
-    #[serde(default, deserialize_with = "deserialize_flexible_bool")]
-    pub r#become: bool,
+    /// Become (`None` when the task doesn't set it)
+    #[serde(default, deserialize_with = "deserialize_option_flexible_bool")]
+    pub r#become: Option<bool>,
     /// Become user
     #[serde(default)]
     pub become_user: Option<String>,
+    /// Become method
+    #[serde(default)]
+    pub become_method: Option<String>,
     /// Block of tasks
     #[serde(default)]
     pub block: Option<Vec<TaskDefinition>>,
@@ -385,6 +435,9 @@ pub struct TaskDefinition {
     /// Environment variables
     #[serde(default)]
     pub environment: IndexMap<String, JsonValue>,
+    /// Task-level variables
+    #[serde(default)]
+    pub vars: IndexMap<String, JsonValue>,
     /// Retries
     #[serde(default)]
     pub retries: Option<usize>,
@@ -498,9 +551,9 @@ fn default_loop_var() -> String {
 pub struct HandlerDefinition {
     /// Handler name
     pub name: String,
-    /// Listen for additional notification names
+    /// Listen for additional notification names (a topic or list of topics)
     #[serde(default)]
-    pub listen: Vec<String>,
+    pub listen: NotifyValue,
     /// When condition
     #[serde(default)]
     pub when: Option<WhenCondition>,
@@ -518,10 +571,12 @@ pub struct Play {
     pub hosts: String,
     /// Whether to gather facts
     pub gather_facts: bool,
-    /// Become root
-    pub r#become: bool,
+    /// Become root (`None` when the play doesn't set it)
+    pub r#become: Option<bool>,
     /// User to become
     pub become_user: Option<String>,
+    /// Become method
+    pub become_method: Option<String>,
     /// Connection type
     pub connection: Option<String>,
     /// Remote user
@@ -548,12 +603,18 @@ pub struct Play {
     pub serial: Option<crate::playbook::SerialSpec>,
     /// Max failure percentage
     pub max_fail_percentage: Option<u8>,
+    /// Whether max_fail_percentage applies to the play or each batch
+    pub max_fail_scope: crate::playbook::MaxFailScope,
+    /// Pause between serial batches
+    pub serial_pause: Option<std::time::Duration>,
     /// Strategy
     pub strategy: Option<String>,
     /// Ignore unreachable hosts
     pub ignore_unreachable: bool,
     /// Force handlers to run even if play fails
     pub force_handlers: bool,
+    /// Oldest rustible release able to run this play
+    pub min_rustible_version: Option<String>,
 }
 
 impl Play {
@@ -563,8 +624,9 @@ impl Play {
             name: name.into(),
             hosts: hosts.into(),
             gather_facts: true,
-            r#become: false,
+            r#become: None,
             become_user: None,
+            become_method: None,
             connection: None,
             remote_user: None,
             vars: IndexMap::new(),
@@ -578,9 +640,12 @@ impl Play {
             tags: Vec::new(),
             serial: None,
             max_fail_percentage: None,
+            max_fail_scope: crate::playbook::MaxFailScope::default(),
+            serial_pause: None,
             strategy: None,
             ignore_unreachable: false,
             force_handlers: false,
+            min_rustible_version: None,
         }
     }
 
@@ -588,12 +653,14 @@ impl Play {
     pub fn from_definition(
         def: PlayDefinition,
         playbook_path: Option<&PathBuf>,
+        roles_path: &[PathBuf],
     ) -> ExecutorResult<Self> {
         let mut play = Play::new(&def.name, &def.hosts);
 
         play.gather_facts = def.gather_facts;
         play.r#become = def.r#become;
-        play.r#become_user = def.r#become_user;
+        play.become_user = def.become_user;
+        play.become_method = def.become_method;
         play.connection = def.connection;
         play.remote_user = def.remote_user;
         play.vars = def.vars;
@@ -604,6 +671,9 @@ impl Play {
         play.ignore_unreachable = def.ignore_unreachable;
         play.force_handlers = def.force_handlers;
         play.max_fail_percentage = def.max_fail_percentage;
+        play.max_fail_scope = def.max_fail_scope;
+        play.serial_pause = def.serial_pause;
+        play.min_rustible_version = def.min_rustible_version;
 
         // Parse serial value into SerialSpec
         if let Some(serial) = def.serial {
@@ -612,7 +682,7 @@ impl Play {
 
         // Parse roles
         for role_def in def.roles {
-            let role = Role::from_definition(role_def, playbook_path)?;
+            let role = Role::from_definition(role_def, playbook_path, roles_path)?;
             play.roles.push(role);
         }
 
@@ -634,6 +704,20 @@ impl Play {
             play.post_tasks.extend(tasks);
         }
 
+        // Play-level become settings fill in whatever tasks and blocks left unset
+        for task in play
+            .pre_tasks
+            .iter_mut()
+            .chain(play.tasks.iter_mut())
+            .chain(play.post_tasks.iter_mut())
+        {
+            task.inherit_become(
+                play.r#become,
+                play.become_user.as_deref(),
+                play.become_method.as_deref(),
+            );
+        }
+
         // Parse handlers
         for handler_def in def.handlers {
             let handler = parse_handler_definition(handler_def)?;
@@ -720,6 +804,7 @@ impl Role {
     pub fn from_definition(
         def: RoleDefinition,
         playbook_path: Option<&PathBuf>,
+        roles_path: &[PathBuf],
     ) -> ExecutorResult<Self> {
         let mut role = Role::new(def.name());
         role.vars = def.vars();
@@ -744,105 +829,98 @@ impl Role {
             role.handlers_from = handlers_from.clone();
         }
 
-        // Load the role from disk
-        // Looking in: ./roles/<name>, ~/.ansible/roles/<name>, /etc/ansible/roles/<name>
-
-        if let Some(playbook_path) = playbook_path {
-            let playbook_dir = playbook_path.parent().unwrap_or(Path::new("."));
-            let role_path = playbook_dir.join("roles").join(role.name.clone());
-
-            if role_path.exists() {
-                role.path = Some(role_path.clone());
-
-                // Load defaults/main.yml (or defaults_from if specified)
-                let defaults_file = if let Some(ref defaults_from) = role.defaults_from {
-                    role_path
-                        .join("defaults")
-                        .join(format!("{}.yml", defaults_from))
-                } else {
-                    role_path.join("defaults").join("main.yml")
-                };
-                if defaults_file.exists() {
-                    if let Ok(content) = std::fs::read_to_string(&defaults_file) {
-                        if let Ok(defaults) =
-                            serde_yaml::from_str::<IndexMap<String, JsonValue>>(&content)
-                        {
-                            role.defaults = defaults;
-                        }
+        // Load the role from disk, searching roles_path in order and then
+        // the roles/ directory next to the playbook
+        let search_dirs = role_search_dirs(playbook_path.map(PathBuf::as_path), roles_path);
+        if !search_dirs.is_empty() {
+            let role_path = find_role(&role.name, &search_dirs)?;
+            role.path = Some(role_path.clone());
+
+            // Load defaults/main.yml (or defaults_from if specified)
+            let defaults_file = if let Some(ref defaults_from) = role.defaults_from {
+                role_path
+                    .join("defaults")
+                    .join(format!("{}.yml", defaults_from))
+            } else {
+                role_path.join("defaults").join("main.yml")
+            };
+            if defaults_file.exists() {
+                if let Ok(content) = std::fs::read_to_string(&defaults_file) {
+                    if let Ok(defaults) =
+                        serde_yaml::from_str::<IndexMap<String, JsonValue>>(&content)
+                    {
+                        role.defaults = defaults;
                     }
                 }
+            }
 
-                // Load vars/main.yml (or vars_from if specified) - higher precedence than defaults
-                let vars_file = if let Some(ref vars_from) = role.vars_from {
-                    role_path.join("vars").join(format!("{}.yml", vars_from))
-                } else {
-                    role_path.join("vars").join("main.yml")
-                };
-                if vars_file.exists() {
-                    if let Ok(content) = std::fs::read_to_string(&vars_file) {
-                        if let Ok(role_vars) =
-                            serde_yaml::from_str::<IndexMap<String, JsonValue>>(&content)
-                        {
-                            role.role_vars = role_vars;
-                        }
+            // Load vars/main.yml (or vars_from if specified) - higher precedence than defaults
+            let vars_file = if let Some(ref vars_from) = role.vars_from {
+                role_path.join("vars").join(format!("{}.yml", vars_from))
+            } else {
+                role_path.join("vars").join("main.yml")
+            };
+            if vars_file.exists() {
+                if let Ok(content) = std::fs::read_to_string(&vars_file) {
+                    if let Ok(role_vars) =
+                        serde_yaml::from_str::<IndexMap<String, JsonValue>>(&content)
+                    {
+                        role.role_vars = role_vars;
                     }
                 }
+            }
 
-                // Load tasks/main.yml (or tasks_from if specified)
-                let tasks_file = if let Some(ref tasks_from) = role.tasks_from {
-                    role_path.join("tasks").join(format!("{}.yml", tasks_from))
-                } else {
-                    role_path.join("tasks").join("main.yml")
-                };
+            // Load tasks/main.yml (or tasks_from if specified)
+            let tasks_file = if let Some(ref tasks_from) = role.tasks_from {
+                role_path.join("tasks").join(format!("{}.yml", tasks_from))
+            } else {
+                role_path.join("tasks").join("main.yml")
+            };
 
-                if tasks_file.exists() {
-                    if let Ok(content) = std::fs::read_to_string(&tasks_file) {
-                        if let Ok(task_defs) = serde_yaml::from_str::<Vec<TaskDefinition>>(&content)
-                        {
-                            for task_def in task_defs {
-                                if let Ok(tasks) =
-                                    parse_task_definition(task_def, Some(&tasks_file))
-                                {
-                                    role.tasks.extend(tasks);
-                                }
+            if tasks_file.exists() {
+                if let Ok(content) = std::fs::read_to_string(&tasks_file) {
+                    if let Ok(task_defs) = serde_yaml::from_str::<Vec<TaskDefinition>>(&content) {
+                        for task_def in task_defs {
+                            if let Ok(tasks) = parse_task_definition(task_def, Some(&tasks_file)) {
+                                role.tasks.extend(tasks);
                             }
                         }
                     }
                 }
+            }
 
-                // Load handlers/main.yml (or handlers_from if specified)
-                let handlers_file = if let Some(ref handlers_from) = role.handlers_from {
-                    role_path
-                        .join("handlers")
-                        .join(format!("{}.yml", handlers_from))
-                } else {
-                    role_path.join("handlers").join("main.yml")
-                };
-                if handlers_file.exists() {
-                    if let Ok(content) = std::fs::read_to_string(&handlers_file) {
-                        if let Ok(handler_defs) =
-                            serde_yaml::from_str::<Vec<HandlerDefinition>>(&content)
-                        {
-                            for handler_def in handler_defs {
-                                if let Ok(handler) = parse_handler_definition(handler_def) {
-                                    role.handlers.push(handler);
-                                }
+            // Load handlers/main.yml (or handlers_from if specified)
+            let handlers_file = if let Some(ref handlers_from) = role.handlers_from {
+                role_path
+                    .join("handlers")
+                    .join(format!("{}.yml", handlers_from))
+            } else {
+                role_path.join("handlers").join("main.yml")
+            };
+            if handlers_file.exists() {
+                if let Ok(content) = std::fs::read_to_string(&handlers_file) {
+                    if let Ok(handler_defs) =
+                        serde_yaml::from_str::<Vec<HandlerDefinition>>(&content)
+                    {
+                        for handler_def in handler_defs {
+                            if let Ok(handler) = parse_handler_definition(handler_def) {
+                                role.handlers.push(handler);
                             }
                         }
                     }
                 }
+            }
 
-                // Load meta/main.yml for dependencies
-                let meta_file = role_path.join("meta").join("main.yml");
-                if meta_file.exists() {
-                    if let Ok(content) = std::fs::read_to_string(&meta_file) {
-                        if let Ok(meta) = serde_yaml::from_str::<RoleMeta>(&content) {
-                            for dep in meta.dependencies {
-                                if let Ok(dep_role) =
-                                    Role::from_definition(dep, Some(playbook_path))
-                                {
-                                    role.dependencies.push(dep_role);
-                                }
+            // Load meta/main.yml for dependencies
+            let meta_file = role_path.join("meta").join("main.yml");
+            if meta_file.exists() {
+                if let Ok(content) = std::fs::read_to_string(&meta_file) {
+                    if let Ok(meta) = serde_yaml::from_str::<RoleMeta>(&content) {
+                        for dep in meta.dependencies {
+                            if let Ok(dep_role) =
+                                Role::from_definition(dep, playbook_path, roles_path)
+                            {
+                                role.dependencies.push(dep_role);
                             }
                         }
                     }
@@ -975,6 +1053,57 @@ struct Platform {
     versions: Option<Vec<String>>,
 }
 
+/// Fail unless the running rustible is at least `required`.
+///
+/// Missing minor and patch components count as zero, so `0.2` means `0.2.0`.
+pub fn require_rustible_version(required: &str) -> ExecutorResult<()> {
+    let parse = |version: &str| {
+        let mut parts: Vec<&str> = version.trim().trim_start_matches('v').split('.').collect();
+        parts.resize(parts.len().max(3), "0");
+        semver::Version::parse(&parts.join(".")).ok()
+    };
+
+    let running = env!("CARGO_PKG_VERSION");
+    let wanted = parse(required).ok_or_else(|| {
+        ExecutorError::ParseError(format!("Invalid min_rustible_version '{}'", required))
+    })?;
+    match parse(running) {
+        Some(current) if current < wanted => Err(ExecutorError::RuntimeError(format!(
+            "This play requires rustible {} or newer, but {} is running",
+            required, running
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Directories searched for roles: each `roles_path` entry in order, then
+/// the `roles/` directory next to the playbook.
+pub fn role_search_dirs(playbook_path: Option<&Path>, roles_path: &[PathBuf]) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    let adjacent = playbook_path.map(|p| p.parent().unwrap_or(Path::new(".")).join("roles"));
+    for dir in roles_path.iter().cloned().chain(adjacent) {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Find the directory of role `name` in the first search directory that has it.
+pub fn find_role(name: &str, search_dirs: &[PathBuf]) -> ExecutorResult<PathBuf> {
+    search_dirs
+        .iter()
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_dir())
+        .ok_or_else(|| {
+            let searched: Vec<String> = search_dirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect();
+            ExecutorError::RoleNotFound(format!("'{}' (searched: {})", name, searched.join(", ")))
+        })
+}
+
 /// Parse a task definition into Task(s)
 fn parse_task_definition(
     def: TaskDefinition,
@@ -999,9 +1128,11 @@ fn parse_task_definition(
         for mut task in block_parsed {
             task.block_id = Some(block_id.clone());
             task.block_role = BlockRole::Normal;
-            if def.r#become {
-                task.r#become = true;
-            }
+            task.inherit_become(
+                def.r#become,
+                def.become_user.as_deref(),
+                def.become_method.as_deref(),
+            );
             if let Some(ref when) = def.when {
                 if task.when.is_none() {
                     task.when = Some(when.to_condition());
@@ -1018,6 +1149,11 @@ fn parse_task_definition(
                 for task in &mut rescue_parsed {
                     task.block_id = Some(block_id.clone());
                     task.block_role = BlockRole::Rescue;
+                    task.inherit_become(
+                        def.r#become,
+                        def.become_user.as_deref(),
+                        def.become_method.as_deref(),
+                    );
                 }
                 tasks.extend(rescue_parsed);
             }
@@ -1031,6 +1167,11 @@ fn parse_task_definition(
                 for task in &mut always_parsed {
                     task.block_id = Some(block_id.clone());
                     task.block_role = BlockRole::Always;
+                    task.inherit_become(
+                        def.r#become,
+                        def.become_user.as_deref(),
+                        def.become_method.as_deref(),
+                    );
                 }
                 tasks.extend(always_parsed);
             }
@@ -1133,15 +1274,19 @@ fn parse_task_definition(
     // Build the task
     let task = Task {
         name: def.name,
-        module: module_name,
+        module: crate::modules::builtin_module_name(&module_name).to_string(),
         args: module_args,
+        vars: def.vars,
         when: def.when.as_ref().map(|w| w.to_condition()),
         notify: def.notify.to_vec(),
         register: def.register,
         loop_items: match def.loop_items {
-            Some(LoopValue::Items(items)) => Some(items),
-            Some(LoopValue::Variable(_)) => None, // Would need runtime resolution
-            None => None,
+            Some(LoopValue::Items(ref items)) => Some(items.clone()),
+            _ => None,
+        },
+        loop_template: match def.loop_items {
+            Some(LoopValue::Variable(template)) => Some(template),
+            _ => None,
         },
         loop_var: def
             .loop_control
@@ -1166,6 +1311,7 @@ fn parse_task_definition(
         tags: def.tags,
         r#become: def.r#become,
         become_user: def.become_user,
+        become_method: def.become_method,
         block_id: None,
         block_role: crate::executor::task::BlockRole::Normal,
         retries: None,
@@ -1200,6 +1346,7 @@ fn find_module_in_definition(
         "tags",
         "become",
         "become_user",
+        "become_method",
         "block",
         "rescue",
         "always",
@@ -1319,10 +1466,10 @@ fn parse_handler_definition(def: HandlerDefinition) -> ExecutorResult<Handler> {
 
     Ok(Handler {
         name: def.name,
-        module: module_name,
+        module: crate::modules::builtin_module_name(&module_name).to_string(),
         args: module_args,
         when: def.when.map(|w| w.to_condition()),
-        listen: def.listen,
+        listen: def.listen.to_vec(),
     })
 }
 
@@ -1378,6 +1525,61 @@ mod tests {
         assert_eq!(task.module, "debug");
     }
 
+    #[test]
+    fn test_parse_max_fail_scope() {
+        let yaml = r#"
+- name: Rolling update
+  hosts: all
+  serial: 5
+  max_fail_percentage: 20
+  max_fail_scope: batch
+  tasks: []
+- name: Default scope
+  hosts: all
+  tasks: []
+"#;
+
+        let playbook = Playbook::parse(yaml, None).unwrap();
+        assert_eq!(
+            playbook.plays[0].max_fail_scope,
+            crate::playbook::MaxFailScope::Batch
+        );
+        assert_eq!(
+            playbook.plays[1].max_fail_scope,
+            crate::playbook::MaxFailScope::Play
+        );
+    }
+
+    #[test]
+    fn test_parse_serial_pause() {
+        let yaml = r#"
+- name: Seconds
+  hosts: all
+  serial: 1
+  serial_pause: 30
+  tasks: []
+- name: Duration string
+  hosts: all
+  serial: 1
+  serial_pause: 1m 30s
+  tasks: []
+- name: No pause
+  hosts: all
+  tasks: []
+"#;
+
+        let playbook = Playbook::parse(yaml, None).unwrap();
+        assert_eq!(
+            playbook.plays[0].serial_pause,
+            Some(std::time::Duration::from_secs(30))
+        );
+        assert_eq!(
+            playbook.plays[1].serial_pause,
+            Some(std::time::Duration::from_secs(90))
+        );
+        assert_eq!(playbook.plays[2].serial_pause, None);
+    }
+
     #[test]
     fn test_parse_when_condition() {
         let single = WhenCondition::Single("ansible_os_family == 'Debian'".to_string());
@@ -14,6 +14,11 @@ use tokio::sync::RwLock;
 use tracing::{debug, trace};
 
 use crate::connection::Connection;
+use crate::executor::safe_mode::SafeMode;
+use crate::inventory::{InventoryPlugin, PatternCache, SourcesInventoryPlugin};
+
+/// Ansible release (major, minor, revision) reported as `ansible_version`
+pub const ANSIBLE_COMPAT_VERSION: (u64, u64, u64) = (2, 16, 0);
 
 /// Scope levels for variable resolution
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -47,6 +52,8 @@ pub struct HostVars {
     vars: IndexMap<String, JsonValue>,
     /// Facts gathered from this host
     facts: IndexMap<String, JsonValue>,
+    /// Facts set with `set_fact`, also exposed as variables by name
+    fact_vars: IndexMap<String, JsonValue>,
     /// Registered task results
     registered: IndexMap<String, RegisteredResult>,
 }
@@ -66,11 +73,22 @@ impl HostVars {
         self.vars.get(name)
     }
 
+    /// Remove a variable from this host
+    pub fn remove_var(&mut self, name: &str) -> Option<JsonValue> {
+        self.vars.shift_remove(name)
+    }
+
     /// Set a fact for this host
     pub fn set_fact(&mut self, name: String, value: JsonValue) {
         self.facts.insert(name, value);
     }
 
+    /// Set a fact that is also exposed as a variable by name
+    pub fn set_fact_var(&mut self, name: String, value: JsonValue) {
+        self.facts.insert(name.clone(), value.clone());
+        self.fact_vars.insert(name, value);
+    }
+
     /// Get a fact for this host
     pub fn get_fact(&self, name: &str) -> Option<&JsonValue> {
         self.facts.get(name)
@@ -99,6 +117,9 @@ impl HostVars {
         for (k, v) in &other.facts {
             self.facts.insert(k.clone(), v.clone());
         }
+        for (k, v) in &other.fact_vars {
+            self.fact_vars.insert(k.clone(), v.clone());
+        }
         for (k, v) in &other.registered {
             self.registered.insert(k.clone(), v.clone());
         }
@@ -183,12 +204,62 @@ impl RegisteredResult {
         }
     }
 
+    /// Merge module result data into this result.
+    ///
+    /// Command output keys fill the typed fields, and every other key,
+    /// including the entries of a nested `data` object, is exposed at the
+    /// top level so templates can use `result.dest` or `result.ansible_facts`
+    /// directly. Status flags are left alone; they come from the task status.
+    pub fn merge_data(&mut self, result: &JsonValue) {
+        let Some(fields) = result.as_object() else {
+            return;
+        };
+
+        for (key, value) in fields {
+            match key.as_str() {
+                "changed" | "failed" | "skipped" | "results" => {}
+                "rc" => self.rc = value.as_i64().map(|rc| rc as i32),
+                "stdout" => self.stdout = value.as_str().map(String::from),
+                "stderr" => self.stderr = value.as_str().map(String::from),
+                "stdout_lines" => self.stdout_lines = string_lines(value),
+                "stderr_lines" => self.stderr_lines = string_lines(value),
+                "msg" if self.msg.is_none() => self.msg = value.as_str().map(String::from),
+                "data" if value.is_object() => self.merge_data(value),
+                _ => {
+                    self.data.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        if self.stdout_lines.is_none() {
+            self.stdout_lines = self
+                .stdout
+                .as_ref()
+                .map(|s| s.lines().map(String::from).collect());
+        }
+        if self.stderr_lines.is_none() {
+            self.stderr_lines = self
+                .stderr
+                .as_ref()
+                .map(|s| s.lines().map(String::from).collect());
+        }
+    }
+
     /// Convert to JSON value
     pub fn to_json(&self) -> JsonValue {
         serde_json::to_value(self).unwrap_or(JsonValue::Null)
     }
 }
 
+fn string_lines(value: &JsonValue) -> Option<Vec<String>> {
+    value.as_array().map(|lines| {
+        lines
+            .iter()
+            .filter_map(|line| line.as_str().map(String::from))
+            .collect()
+    })
+}
+
 /// Group definition in inventory
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct InventoryGroup {
@@ -200,6 +271,34 @@ pub struct InventoryGroup {
     pub children: Vec<String>,
 }
 
+/// Privilege escalation defaults from the command line
+///
+/// Applied to every task on top of its own settings: `enabled` turns
+/// `become` on for tasks that don't set it, while a task's `become_user`
+/// and `become_method` take precedence over `user` and `method`.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct BecomeDefaults {
+    /// Escalate tasks that don't enable `become` themselves (`--become`)
+    pub enabled: bool,
+    /// Escalation method (`--become-method`)
+    pub method: Option<String>,
+    /// User to become (`--become-user`)
+    pub user: Option<String>,
+    /// Password for the escalation method (`--ask-become-pass`)
+    pub password: Option<String>,
+}
+
+impl std::fmt::Debug for BecomeDefaults {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BecomeDefaults")
+            .field("enabled", &self.enabled)
+            .field("method", &self.method)
+            .field("user", &self.user)
+            .field("password", &self.password.as_ref().map(|_| "[REDACTED]"))
+            .finish()
+    }
+}
+
 /// Execution context passed to tasks
 #[derive(Clone)]
 pub struct ExecutionContext {
@@ -213,6 +312,12 @@ pub struct ExecutionContext {
     pub connection: Option<Arc<dyn Connection>>,
     /// Python interpreter path on remote host
     pub python_interpreter: String,
+    /// Safe mode for destructive modules, if enabled
+    pub safe_mode: Option<Arc<SafeMode>>,
+    /// Privilege escalation defaults for tasks on this host
+    pub become_defaults: BecomeDefaults,
+    /// The running task's own `vars:`, rendered for this host
+    pub task_vars: IndexMap<String, JsonValue>,
 }
 
 impl std::fmt::Debug for ExecutionContext {
@@ -226,6 +331,9 @@ impl std::fmt::Debug for ExecutionContext {
                 &self.connection.as_ref().map(|c| c.identifier()),
             )
             .field("python_interpreter", &self.python_interpreter)
+            .field("safe_mode", &self.safe_mode)
+            .field("become_defaults", &self.become_defaults)
+            .field("task_vars", &self.task_vars)
             .finish()
     }
 }
@@ -238,6 +346,9 @@ impl ExecutionContext {
             diff_mode: false,
             connection: None,
             python_interpreter: "/usr/bin/python3".to_string(),
+            safe_mode: None,
+            become_defaults: BecomeDefaults::default(),
+            task_vars: IndexMap::new(),
         }
     }
 
@@ -262,6 +373,24 @@ impl ExecutionContext {
         self.python_interpreter = path.into();
         self
     }
+
+    /// Set the safe mode guarding destructive modules
+    pub fn with_safe_mode(mut self, safe_mode: Option<Arc<SafeMode>>) -> Self {
+        self.safe_mode = safe_mode;
+        self
+    }
+
+    /// Set the privilege escalation defaults applied to tasks
+    pub fn with_become_defaults(mut self, defaults: BecomeDefaults) -> Self {
+        self.become_defaults = defaults;
+        self
+    }
+
+    /// Set the task `vars:` in scope for this host
+    pub fn with_task_vars(mut self, vars: IndexMap<String, JsonValue>) -> Self {
+        self.task_vars = vars;
+        self
+    }
 }
 
 /// The main runtime context holding all state during execution
@@ -305,6 +434,12 @@ pub struct RuntimeContext {
 
     /// Include params
     include_params: IndexMap<String, JsonValue>,
+
+    /// Dynamic inventory source, re-parsed by `meta: refresh_inventory`
+    inventory_source: Option<Arc<dyn InventoryPlugin>>,
+
+    /// Resolved play host patterns, cleared when hosts or groups change
+    host_patterns: PatternCache,
 }
 
 impl RuntimeContext {
@@ -315,33 +450,35 @@ impl RuntimeContext {
         ctx
     }
 
-    /// Create a runtime context from an inventory
+    /// Create a runtime context with the hosts and groups of an inventory
+    ///
+    /// An inventory loaded from sources is reloaded from them by
+    /// `meta: refresh_inventory`.
     pub fn from_inventory(inventory: &crate::inventory::Inventory) -> Self {
         let mut ctx = Self::new();
-
-        // Add hosts from inventory
-        for host in inventory.hosts() {
-            // Convert host variables from serde_yaml to serde_json
-            for (key, value) in &host.vars {
-                if let Ok(json_value) = serde_json::to_value(value) {
-                    ctx.set_host_var(host.name(), key.clone(), json_value);
-                }
-            }
+        if !inventory.sources().is_empty() {
+            ctx.set_inventory_source(Arc::new(SourcesInventoryPlugin::new(
+                inventory.sources().to_vec(),
+            )));
         }
 
+        ctx.merge_inventory(inventory);
         ctx
     }
 
     /// Initialize magic variables
     fn init_magic_vars(&mut self) {
+        // Playbooks migrated from Ansible compare against `ansible_version`,
+        // so it reports the Ansible release whose behavior rustible follows
+        let (major, minor, revision) = ANSIBLE_COMPAT_VERSION;
         self.magic_vars.insert(
             "ansible_version".to_string(),
             serde_json::json!({
-                "full": env!("CARGO_PKG_VERSION"),
-                "major": 2,
-                "minor": 16,
-                "revision": 0,
-                "string": format!("rustible {}", env!("CARGO_PKG_VERSION"))
+                "full": format!("{}.{}.{}", major, minor, revision),
+                "major": major,
+                "minor": minor,
+                "revision": revision,
+                "string": format!("{}.{}.{} (rustible {})", major, minor, revision, env!("CARGO_PKG_VERSION"))
             }),
         );
 
@@ -370,6 +507,11 @@ impl RuntimeContext {
         self.play_vars.insert(name, value);
     }
 
+    /// Play-level variables as they were set, before any templating
+    pub fn play_vars(&self) -> &IndexMap<String, JsonValue> {
+        &self.play_vars
+    }
+
     /// Set a task-level variable
     pub fn set_task_var(&mut self, name: String, value: JsonValue) {
         trace!("Setting task var: {} = {:?}", name, value);
@@ -464,6 +606,19 @@ impl RuntimeContext {
     /// - Inline hint for better optimization
     #[inline]
     pub fn get_merged_vars(&self, host: &str) -> IndexMap<String, JsonValue> {
+        self.get_merged_vars_with_task_vars(host, &IndexMap::new())
+    }
+
+    /// Get all variables merged for a host with a task's own `vars:` layered
+    /// in at task precedence
+    ///
+    /// Task vars belong to the task running on one host, so callers pass them
+    /// in from their [`ExecutionContext`] rather than storing them here.
+    pub fn get_merged_vars_with_task_vars(
+        &self,
+        host: &str,
+        task_vars: &IndexMap<String, JsonValue>,
+    ) -> IndexMap<String, JsonValue> {
         // OPTIMIZATION: Pre-allocate with estimated capacity to reduce reallocations
         let host_facts_count = self
             .host_data
@@ -474,6 +629,7 @@ impl RuntimeContext {
             + self.global_vars.len()
             + self.play_vars.len()
             + self.task_vars.len()
+            + task_vars.len()
             + self.extra_vars.len()
             + host_facts_count // For top-level ansible_* fact variables
             + 10; // Buffer for special vars
@@ -513,7 +669,10 @@ impl RuntimeContext {
             merged.insert(k.clone(), v.clone());
         }
 
-        // Task vars
+        // Task vars: the task's own `vars:` first, then loop vars on top
+        for (k, v) in task_vars {
+            merged.insert(k.clone(), v.clone());
+        }
         for (k, v) in &self.task_vars {
             merged.insert(k.clone(), v.clone());
         }
@@ -539,6 +698,11 @@ impl RuntimeContext {
                 }
             }
 
+            // Facts from set_fact are plain variables too
+            for (k, v) in &host_data.fact_vars {
+                merged.insert(k.clone(), v.clone());
+            }
+
             // Registered vars
             for (k, v) in &host_data.registered {
                 merged.insert(k.clone(), v.to_json());
@@ -597,12 +761,86 @@ impl RuntimeContext {
                 group.hosts.push(host);
             }
         }
+        self.host_patterns.clear();
     }
 
     /// Add a group to the inventory
     pub fn add_group(&mut self, name: String, group: InventoryGroup) {
         debug!("Adding group: {}", name);
         self.groups.insert(name, group);
+        self.host_patterns.clear();
+    }
+
+    /// Cache of resolved play host patterns
+    pub fn host_pattern_cache(&self) -> &PatternCache {
+        &self.host_patterns
+    }
+
+    /// Set the dynamic inventory source used by `meta: refresh_inventory`
+    pub fn set_inventory_source(&mut self, source: Arc<dyn InventoryPlugin>) {
+        self.inventory_source = Some(source);
+    }
+
+    /// Get the dynamic inventory source, if one is configured
+    pub fn inventory_source(&self) -> Option<Arc<dyn InventoryPlugin>> {
+        self.inventory_source.clone()
+    }
+
+    /// Merge hosts, groups and inventory variables into the runtime
+    ///
+    /// Existing hosts keep their facts and registered results; only
+    /// inventory variables are updated. Returns the number of new hosts.
+    pub fn merge_inventory(&mut self, inventory: &crate::inventory::Inventory) -> usize {
+        let mut added = 0;
+
+        let mut hosts: Vec<_> = inventory.hosts().collect();
+        hosts.sort_by(|a, b| a.name().cmp(b.name()));
+        for host in hosts {
+            if !self.has_host(host.name()) {
+                added += 1;
+            }
+            self.add_host(host.name().to_string(), None);
+
+            for (key, value) in &host.vars {
+                if let Ok(json_value) = serde_json::to_value(value) {
+                    self.set_host_var(host.name(), key.clone(), json_value);
+                }
+            }
+        }
+
+        for group in inventory.groups() {
+            if group.name == "all" {
+                continue;
+            }
+
+            let entry = self.groups.entry(group.name.clone()).or_default();
+
+            let mut hosts: Vec<&String> = group.hosts.iter().collect();
+            hosts.sort();
+            for host in hosts {
+                if !entry.hosts.contains(host) {
+                    entry.hosts.push(host.clone());
+                }
+            }
+
+            let mut children: Vec<&String> = group.children.iter().collect();
+            children.sort();
+            for child in children {
+                if !entry.children.contains(child) {
+                    entry.children.push(child.clone());
+                }
+            }
+
+            for (key, value) in &group.vars {
+                if let Ok(json_value) = serde_json::to_value(value) {
+                    entry.vars.insert(key.clone(), json_value);
+                }
+            }
+        }
+
+        self.host_patterns.clear();
+        debug!("Merged inventory: {} new host(s)", added);
+        added
     }
 
     /// Get all hosts (returns a reference to avoid cloning)
@@ -647,6 +885,15 @@ impl RuntimeContext {
         host_data.set_fact(name, value);
     }
 
+    /// Set a fact from `set_fact`, visible as a fact and as a variable
+    pub fn set_host_fact_var(&mut self, host: &str, name: String, value: JsonValue) {
+        let host_data = self
+            .host_data
+            .entry(host.to_string())
+            .or_insert_with(HostVars::new);
+        host_data.set_fact_var(name, value);
+    }
+
     /// Get a fact for a host
     pub fn get_host_fact(&self, host: &str, name: &str) -> Option<JsonValue> {
         self.host_data
@@ -698,6 +945,13 @@ impl RuntimeContext {
             .and_then(|hd| hd.get_var(name).cloned())
     }
 
+    /// Remove a host variable
+    pub fn remove_host_var(&mut self, host: &str, name: &str) -> Option<JsonValue> {
+        self.host_data
+            .get_mut(host)
+            .and_then(|hd| hd.remove_var(name))
+    }
+
     /// Set a magic variable
     pub fn set_magic_var(&mut self, name: String, value: JsonValue) {
         self.magic_vars.insert(name, value);
@@ -839,15 +1093,43 @@ impl RuntimeContext {
             .to_string()
     }
 
+    /// Set the hosts targeted by the current play
+    ///
+    /// Populates `ansible_play_hosts`, `ansible_play_hosts_all` and resets
+    /// `ansible_play_batch` to the whole play until a serial batch starts.
+    pub fn set_play_hosts(&mut self, hosts: &[String]) {
+        let hosts = serde_json::json!(hosts);
+        self.magic_vars
+            .insert("ansible_play_hosts".to_string(), hosts.clone());
+        self.magic_vars
+            .insert("ansible_play_hosts_all".to_string(), hosts.clone());
+        self.magic_vars
+            .insert("ansible_play_batch".to_string(), hosts);
+    }
+
+    /// Set the hosts of the serial batch currently executing
+    pub fn set_play_batch(&mut self, hosts: &[String]) {
+        self.magic_vars
+            .insert("ansible_play_batch".to_string(), serde_json::json!(hosts));
+    }
+
+    /// Read a host list stored as a magic variable
+    fn magic_host_list(&self, name: &str) -> Option<Vec<String>> {
+        self.magic_vars
+            .get(name)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+    }
+
     /// Get ansible_play_hosts (all hosts in current play)
     pub fn get_play_hosts(&self) -> Vec<String> {
-        self.all_hosts.clone()
+        self.magic_host_list("ansible_play_hosts_all")
+            .unwrap_or_else(|| self.all_hosts.clone())
     }
 
     /// Get ansible_play_batch (current batch when using serial)
     pub fn get_play_batch(&self) -> Vec<String> {
-        // For now, return all hosts. This would be updated during serial execution
-        self.all_hosts.clone()
+        self.magic_host_list("ansible_play_batch")
+            .unwrap_or_else(|| self.get_play_hosts())
     }
 
     /// Set a group variable
@@ -857,6 +1139,7 @@ impl RuntimeContext {
             .entry(group.to_string())
             .or_insert_with(InventoryGroup::default);
         group_data.vars.insert(name, value);
+        self.host_patterns.clear();
     }
 
     /// Get a group variable
@@ -1015,13 +1298,18 @@ impl RuntimeContext {
         );
 
         // Add play_hosts
+        let play_hosts = self.get_play_hosts();
         context.insert(
             "ansible_play_hosts".to_string(),
-            serde_json::to_value(&self.all_hosts).unwrap_or(JsonValue::Array(vec![])),
+            serde_json::to_value(&play_hosts).unwrap_or(JsonValue::Array(vec![])),
         );
         context.insert(
             "ansible_play_hosts_all".to_string(),
-            serde_json::to_value(&self.all_hosts).unwrap_or(JsonValue::Array(vec![])),
+            serde_json::to_value(&play_hosts).unwrap_or(JsonValue::Array(vec![])),
+        );
+        context.insert(
+            "ansible_play_batch".to_string(),
+            serde_json::to_value(self.get_play_batch()).unwrap_or(JsonValue::Array(vec![])),
         );
 
         // Add ansible_host (connection address)
@@ -1143,6 +1431,30 @@ mod tests {
         assert_eq!(registered.stdout, Some("hello world".to_string()));
     }
 
+    #[test]
+    fn test_registered_result_merge_data() {
+        let mut result = RegisteredResult::ok(true);
+        result.merge_data(&serde_json::json!({
+            "rc": 2,
+            "stdout": "one\ntwo",
+            "changed": false,
+            "data": { "dest": "/tmp/app.conf" },
+            "ansible_facts": { "role": "web" },
+        }));
+
+        assert!(result.changed);
+        assert_eq!(result.rc, Some(2));
+        assert_eq!(
+            result.stdout_lines,
+            Some(vec!["one".to_string(), "two".to_string()])
+        );
+
+        let json = result.to_json();
+        assert_eq!(json["dest"], "/tmp/app.conf");
+        assert_eq!(json["ansible_facts"]["role"], "web");
+        assert!(json.get("data").is_none());
+    }
+
     #[test]
     fn test_group_hosts() {
         let mut ctx = RuntimeContext::new();
@@ -1447,6 +1759,12 @@ mod tests {
         );
         assert!(merged.get("ansible_ansible_python_interpreter").is_none());
 
+        // Gathered facts are not plain variables, set_fact values are
+        assert!(merged.get("hostname").is_none());
+        ctx.set_host_fact_var("server1", "app_port".to_string(), serde_json::json!(8080));
+        let merged = ctx.get_merged_vars("server1");
+        assert_eq!(merged.get("app_port"), Some(&serde_json::json!(8080)));
+
         // ansible_facts should still contain the nested structure for backwards compatibility
         let ansible_facts = merged.get("ansible_facts").unwrap();
         assert_eq!(
@@ -0,0 +1,112 @@
+//! Safe mode for destructive modules
+//!
+//! When safe mode is enabled the executor refuses to run tasks that can
+//! destroy data or run arbitrary code on a host:
+//! - `command`, `shell`, `raw` and `script`
+//! - `file` with `state: absent`
+//! - package, user and group modules removing something
+//!
+//! A task still runs when its module or task name is on the allowlist.
+//! Safe mode is meant to guard production inventories against accidental
+//! changes; `--force` turns it off for a single run.
+
+use std::collections::HashSet;
+
+/// Modules that run arbitrary commands
+const COMMAND_MODULES: &[&str] = &["command", "shell", "raw", "script"];
+
+/// Modules that remove software when given a removal state
+const PACKAGE_MODULES: &[&str] = &[
+    "package", "apt", "yum", "dnf", "pip", "apk", "pacman", "zypper",
+];
+
+/// States that make a package module remove software
+const REMOVAL_STATES: &[&str] = &["absent", "removed", "purged"];
+
+/// Safe mode configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SafeMode {
+    /// Module or task names allowed to run despite being destructive
+    pub allowlist: HashSet<String>,
+}
+
+impl SafeMode {
+    /// Create a safe mode that blocks every destructive task
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow a module or task name to run
+    pub fn allow(mut self, name: impl Into<String>) -> Self {
+        self.allowlist.insert(name.into());
+        self
+    }
+
+    /// Check whether a task may run.
+    ///
+    /// `state` is the task's templated `state` argument, if any. Returns an
+    /// error message naming the task and how to allow it when it is blocked.
+    pub fn check(&self, task_name: &str, module: &str, state: Option<&str>) -> Result<(), String> {
+        let Some(reason) = destructive_reason(module, state) else {
+            return Ok(());
+        };
+        if self.allowlist.contains(module) || self.allowlist.contains(task_name) {
+            return Ok(());
+        }
+
+        Err(format!(
+            "Safe mode blocked destructive task '{}' ({}). Add '{}' to the safe mode \
+             allowlist or rerun with --force",
+            task_name, reason, module
+        ))
+    }
+}
+
+/// Describe why a module invocation is destructive, if it is
+pub fn destructive_reason(module: &str, state: Option<&str>) -> Option<String> {
+    let removes = state.is_some_and(|s| REMOVAL_STATES.contains(&s));
+
+    if COMMAND_MODULES.contains(&module) {
+        Some(format!("{} runs arbitrary commands", module))
+    } else if module == "file" && state == Some("absent") {
+        Some("file state=absent deletes paths".to_string())
+    } else if PACKAGE_MODULES.contains(&module) && removes {
+        Some(format!("{} state={} removes packages", module, state?))
+    } else if (module == "user" || module == "group") && state == Some("absent") {
+        Some(format!("{} state=absent deletes accounts", module))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_destructive_reason() {
+        assert!(destructive_reason("shell", None).is_some());
+        assert!(destructive_reason("file", Some("absent")).is_some());
+        assert!(destructive_reason("file", Some("directory")).is_none());
+        assert!(destructive_reason("apt", Some("purged")).is_some());
+        assert!(destructive_reason("apt", Some("present")).is_none());
+        assert!(destructive_reason("user", Some("absent")).is_some());
+        assert!(destructive_reason("debug", None).is_none());
+    }
+
+    #[test]
+    fn test_allowlist_by_module_or_task_name() {
+        let safe_mode = SafeMode::new().allow("shell").allow("Remove old release");
+
+        assert!(safe_mode.check("Run script", "shell", None).is_ok());
+        assert!(safe_mode
+            .check("Remove old release", "file", Some("absent"))
+            .is_ok());
+
+        let err = safe_mode
+            .check("Remove cache", "file", Some("absent"))
+            .unwrap_err();
+        assert!(err.contains("Remove cache"));
+        assert!(err.contains("--force"));
+    }
+}
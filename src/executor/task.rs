@@ -32,6 +32,10 @@ use tracing::{debug, info, instrument, warn};
 static TEMPLATE_VAR_REGEX: Lazy<regex::Regex> =
     Lazy::new(|| regex::Regex::new(r"\{\{\s*([^}]+?)\s*\}\}").expect("Invalid template regex"));
 
+/// Shared engine for Jinja2 block syntax (`{% ... %}`) and filter expressions
+static BLOCK_TEMPLATE_ENGINE: Lazy<crate::template::TemplateEngine> =
+    Lazy::new(crate::template::TemplateEngine::new);
+
 /// Cached regex for checking if string contains template syntax
 #[allow(dead_code)]
 static TEMPLATE_CHECK_REGEX: Lazy<regex::Regex> =
@@ -40,6 +44,7 @@ static TEMPLATE_CHECK_REGEX: Lazy<regex::Regex> =
 use crate::executor::parallelization::ParallelizationManager;
 use crate::executor::runtime::{ExecutionContext, RegisteredResult, RuntimeContext};
 use crate::executor::{ExecutorError, ExecutorResult};
+use crate::modules::{ModuleError, ModuleOutput};
 
 /// Status of a task execution
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -76,6 +81,9 @@ pub struct TaskResult {
     pub result: Option<JsonValue>,
     /// Diff showing what changed (if diff_mode enabled)
     pub diff: Option<TaskDiff>,
+    /// Non-fatal warnings raised by the module
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 impl TaskResult {
@@ -127,6 +135,51 @@ impl TaskResult {
         }
     }
 
+    /// Create a result from an execution error
+    ///
+    /// Connection-level errors map to `Unreachable`; everything else is `Failed`.
+    pub fn from_error(error: &ExecutorError) -> Self {
+        match error {
+            ExecutorError::HostUnreachable(_) => Self::unreachable(error.to_string()),
+            _ => Self::failed(error.to_string()),
+        }
+    }
+
+    /// Create a result from a module error
+    ///
+    /// Errors caused by an unreachable host map to `Unreachable` so they are
+    /// not swallowed by `ignore_errors`; everything else is `Failed`.
+    pub fn from_module_error(module: &str, error: &ModuleError) -> Self {
+        if error.is_unreachable() {
+            Self::unreachable(format!("{} module could not reach host: {}", module, error))
+        } else {
+            Self::failed(format!("{} module failed: {}", module, error))
+        }
+    }
+
+    /// Create a result from a module's output
+    ///
+    /// A failed output keeps its `changed` flag, so a module that changed
+    /// something before failing still reports the change.
+    pub fn from_module_output(output: ModuleOutput) -> Self {
+        let status = if output.failed {
+            TaskStatus::Failed
+        } else if output.changed {
+            TaskStatus::Changed
+        } else {
+            TaskStatus::Ok
+        };
+        Self {
+            status,
+            changed: output.changed,
+            msg: Some(output.msg),
+            result: (!output.data.is_empty())
+                .then(|| serde_json::to_value(&output.data).unwrap_or_default()),
+            warnings: output.warnings,
+            ..Default::default()
+        }
+    }
+
     /// Set the result data
     pub fn with_result(mut self, result: JsonValue) -> Self {
         self.result = Some(result);
@@ -151,7 +204,7 @@ impl TaskResult {
         stdout: Option<String>,
         stderr: Option<String>,
     ) -> RegisteredResult {
-        RegisteredResult {
+        let mut registered = RegisteredResult {
             changed: self.changed,
             failed: self.status == TaskStatus::Failed,
             skipped: self.status == TaskStatus::Skipped,
@@ -163,7 +216,16 @@ impl TaskResult {
             msg: self.msg.clone(),
             results: None,
             data: IndexMap::new(),
+        };
+        if let Some(ref result) = self.result {
+            registered.merge_data(result);
         }
+        if !self.warnings.is_empty() {
+            registered
+                .data
+                .insert("warnings".to_string(), serde_json::json!(self.warnings));
+        }
+        registered
     }
 }
 
@@ -223,6 +285,9 @@ pub struct Task {
     /// Module arguments
     #[serde(default)]
     pub args: IndexMap<String, JsonValue>,
+    /// Task-level variables (`vars:`), scoped to this task
+    #[serde(default)]
+    pub vars: IndexMap<String, JsonValue>,
     /// Conditional expression (Jinja2-like)
     #[serde(default)]
     pub when: Option<String>,
@@ -235,6 +300,9 @@ pub struct Task {
     /// Items to loop over
     #[serde(default)]
     pub loop_items: Option<Vec<JsonValue>>,
+    /// Templated loop source (e.g. `"{{ pkgs }}"`), rendered to the items at run time
+    #[serde(default)]
+    pub loop_template: Option<String>,
     /// Loop variable name (default: "item")
     #[serde(default = "default_loop_var")]
     pub loop_var: String,
@@ -262,12 +330,15 @@ pub struct Task {
     /// Tags for task filtering
     #[serde(default)]
     pub tags: Vec<String>,
-    /// Whether to become another user
+    /// Whether to become another user (`None` when the task doesn't set it)
     #[serde(default)]
-    pub r#become: bool,
+    pub r#become: Option<bool>,
     /// User to become
     #[serde(default)]
     pub become_user: Option<String>,
+    /// Privilege escalation method (sudo, su, doas)
+    #[serde(default)]
+    pub become_method: Option<String>,
     /// Block ID this task belongs to (if part of block/rescue/always)
     #[serde(default)]
     pub block_id: Option<String>,
@@ -308,10 +379,12 @@ impl Default for Task {
             name: String::new(),
             module: String::new(),
             args: IndexMap::new(),
+            vars: IndexMap::new(),
             when: None,
             notify: Vec::new(),
             register: None,
             loop_items: None,
+            loop_template: None,
             loop_var: default_loop_var(),
             loop_control: None,
             ignore_errors: false,
@@ -321,8 +394,9 @@ impl Default for Task {
             delegate_facts: None,
             run_once: false,
             tags: Vec::new(),
-            r#become: false,
+            r#become: None,
             become_user: None,
+            become_method: None,
             block_id: None,
             block_role: BlockRole::Normal,
             retries: None,
@@ -350,12 +424,17 @@ impl From<crate::playbook::Task> for Task {
 
         // Convert loop items from various sources
         // Priority: loop > with_items > with_dict > with_fileglob
+        let mut loop_template = None;
         let loop_items = if let Some(v) = pt.loop_.or(pt.with_items) {
-            // Standard loop or with_items - expect array
-            if let Some(arr) = v.as_array() {
-                Some(arr.clone())
-            } else {
-                None
+            // Standard loop or with_items - expect array, or a template
+            // rendered to one at run time
+            match v {
+                JsonValue::Array(arr) => Some(arr),
+                JsonValue::String(template) => {
+                    loop_template = Some(template);
+                    None
+                }
+                _ => None,
             }
         } else if let Some(v) = pt.with_dict {
             // with_dict - convert dict to list of {key, value} objects
@@ -400,12 +479,14 @@ impl From<crate::playbook::Task> for Task {
 
         Self {
             name: pt.name,
-            module: pt.module.name,
+            module: crate::modules::builtin_module_name(&pt.module.name).to_string(),
             args,
+            vars: pt.vars.as_map().clone(),
             when,
             notify: pt.notify,
             register: pt.register,
             loop_items,
+            loop_template,
             loop_var,
             loop_control,
             ignore_errors: pt.ignore_errors,
@@ -415,8 +496,9 @@ impl From<crate::playbook::Task> for Task {
             delegate_facts: pt.delegate_facts,
             run_once: pt.run_once,
             tags: pt.tags,
-            r#become: pt.r#become.unwrap_or(false),
+            r#become: pt.r#become,
             become_user: pt.become_user,
+            become_method: pt.become_method,
             block_id: None,
             block_role: BlockRole::Normal,
             retries: pt.retries,
@@ -428,10 +510,13 @@ impl From<crate::playbook::Task> for Task {
 
 impl Task {
     /// Create a new task with the given name and module
+    ///
+    /// `ansible.builtin.*` and `ansible.legacy.*` module names are stored
+    /// under their short name.
     pub fn new(name: impl Into<String>, module: impl Into<String>) -> Self {
         Self {
             name: name.into(),
-            module: module.into(),
+            module: crate::modules::builtin_module_name(&module.into()).to_string(),
             ..Default::default()
         }
     }
@@ -442,6 +527,12 @@ impl Task {
         self
     }
 
+    /// Add a task-level variable
+    pub fn var(mut self, key: impl Into<String>, value: impl Into<JsonValue>) -> Self {
+        self.vars.insert(key.into(), value.into());
+        self
+    }
+
     /// Set the when condition
     pub fn when(mut self, condition: impl Into<String>) -> Self {
         self.when = Some(condition.into());
@@ -466,6 +557,48 @@ impl Task {
         self
     }
 
+    /// Loop over the list a template renders to, e.g. `"{{ pkgs }}"`
+    pub fn loop_template(mut self, template: impl Into<String>) -> Self {
+        self.loop_template = Some(template.into());
+        self
+    }
+
+    /// Whether the task runs once per loop item
+    fn is_loop(&self) -> bool {
+        self.loop_items.is_some() || self.loop_template.is_some()
+    }
+
+    /// Items to loop over, rendering `loop_template` against the host's vars
+    ///
+    /// A template must render to a list; anything else is an error rather
+    /// than a single item.
+    async fn resolve_loop_items(
+        &self,
+        ctx: &ExecutionContext,
+        runtime: &Arc<RwLock<RuntimeContext>>,
+    ) -> ExecutorResult<Vec<JsonValue>> {
+        let Some(ref template) = self.loop_template else {
+            return Ok(self.loop_items.clone().unwrap_or_default());
+        };
+
+        let vars = {
+            let rt = runtime.read().await;
+            host_vars(&rt, &ctx.host, &ctx.task_vars)
+        };
+        match native_value(template_value(&JsonValue::String(template.clone()), &vars)?) {
+            JsonValue::Array(items) => Ok(items),
+            other => Err(ExecutorError::RuntimeError(format!(
+                "Invalid data passed to 'loop': '{}' must render to a list, got {}",
+                template,
+                match other {
+                    JsonValue::Null => "nothing (is the variable defined?)".to_string(),
+                    JsonValue::Object(_) => "a mapping".to_string(),
+                    other => format!("'{}'", json_to_string(&other)),
+                }
+            ))),
+        }
+    }
+
     /// Set the loop variable name
     pub fn loop_var(mut self, name: impl Into<String>) -> Self {
         self.loop_var = name.into();
@@ -478,6 +611,14 @@ impl Task {
         self
     }
 
+    /// Whether the task runs on a single host on behalf of the whole play
+    ///
+    /// Besides `run_once` tasks, this covers `meta: refresh_inventory`, which
+    /// reloads the inventory for every host at once.
+    pub fn runs_once(&self) -> bool {
+        self.run_once || (self.module == "meta" && meta_action(&self.args) == "refresh_inventory")
+    }
+
     /// Execute the task
     #[instrument(skip(self, ctx, runtime, handlers, notified, parallelization_manager), fields(task_name = %self.name, host = %ctx.host))]
     pub async fn execute(
@@ -490,8 +631,45 @@ impl Task {
     ) -> ExecutorResult<TaskResult> {
         info!("Executing task: {}", self.name);
 
-        // Evaluate when condition
-        if let Some(ref condition) = self.when {
+        if self.vars.is_empty() {
+            return self
+                .execute_scoped(ctx, runtime, handlers, notified, parallelization_manager)
+                .await;
+        }
+
+        // Render the task's own vars before `when` and module args so both can
+        // reference them. Each var may refer to the ones declared before it.
+        // They live on this host's context only, so hosts running the same
+        // task concurrently never see each other's values and nothing has to
+        // be cleaned up afterwards.
+        let rendered = {
+            let rt = runtime.read().await;
+            let mut vars = host_vars(&rt, &ctx.host, &ctx.task_vars);
+            let mut rendered = ctx.task_vars.clone();
+            for (key, value) in &self.vars {
+                let value = template_value(value, &vars)?;
+                vars.insert(key.clone(), value.clone());
+                rendered.insert(key.clone(), value);
+            }
+            rendered
+        };
+        let ctx = ctx.clone().with_task_vars(rendered);
+
+        self.execute_scoped(&ctx, runtime, handlers, notified, parallelization_manager)
+            .await
+    }
+
+    /// Execute the task once its `vars:` are in scope
+    async fn execute_scoped(
+        &self,
+        ctx: &ExecutionContext,
+        runtime: &Arc<RwLock<RuntimeContext>>,
+        handlers: &Arc<RwLock<HashMap<String, Handler>>>,
+        notified: &Arc<Mutex<std::collections::HashSet<String>>>,
+        parallelization_manager: &Arc<ParallelizationManager>,
+    ) -> ExecutorResult<TaskResult> {
+        // Evaluate when condition; looped tasks evaluate it for each item instead
+        if let Some(condition) = self.when.as_ref().filter(|_| !self.is_loop()) {
             let should_run = self.evaluate_condition(condition, ctx, runtime).await?;
             if !should_run {
                 debug!("Task skipped due to when condition: {}", condition);
@@ -529,16 +707,18 @@ impl Task {
         };
 
         // Handle loops - for set_fact, use fact_storage_ctx; for others, use execution_ctx
-        if let Some(ref items) = self.loop_items {
+        if self.is_loop() {
             let loop_ctx = if self.module == "set_fact" {
                 &fact_storage_ctx
             } else {
                 &execution_ctx
             };
+            let items = self.resolve_loop_items(ctx, runtime).await?;
             return self
                 .execute_loop(
-                    items,
+                    &items,
                     loop_ctx,
+                    ctx,
                     runtime,
                     handlers,
                     notified,
@@ -555,15 +735,16 @@ impl Task {
         };
 
         // Handle until/retries/delay retry logic
-        let result = if self.until.is_some() {
+        let outcome = if self.until.is_some() {
             self.execute_with_retry(
                 module_ctx,
+                ctx,
                 runtime,
                 handlers,
                 notified,
                 parallelization_manager,
             )
-            .await?
+            .await
         } else {
             self.execute_module(
                 module_ctx,
@@ -572,7 +753,18 @@ impl Task {
                 notified,
                 parallelization_manager,
             )
-            .await?
+            .await
+        };
+
+        // A module error is an ordinary task failure as far as ignore_errors is
+        // concerned, so it must not escape and fail an enclosing block.
+        // Unreachable hosts are never ignored.
+        let result = match outcome {
+            Ok(result) => result,
+            Err(e) if self.ignore_errors && !matches!(e, ExecutorError::HostUnreachable(_)) => {
+                TaskResult::from_error(&e)
+            }
+            Err(e) => return Err(e),
         };
 
         // Extract and store ansible_facts from module results
@@ -627,6 +819,7 @@ impl Task {
                 msg: Some(format!("Ignored error: {}", result.msg.unwrap_or_default())),
                 result: result.result,
                 diff: result.diff,
+                warnings: result.warnings,
             });
         }
 
@@ -634,10 +827,16 @@ impl Task {
     }
 
     /// Execute task in a loop
+    ///
+    /// The `when` condition is evaluated for each item against `host_ctx`
+    /// (the inventory host), so it can reference `item`. Skipped items are
+    /// recorded in `results` and do not count toward the task status. The
+    /// combined result is registered on that host too, even when delegated.
     async fn execute_loop(
         &self,
         items: &[JsonValue],
         ctx: &ExecutionContext,
+        host_ctx: &ExecutionContext,
         runtime: &Arc<RwLock<RuntimeContext>>,
         handlers: &Arc<RwLock<HashMap<String, Handler>>>,
         notified: &Arc<Mutex<std::collections::HashSet<String>>>,
@@ -650,6 +849,8 @@ impl Task {
         let mut loop_results = Vec::with_capacity(total_items);
         let mut any_changed = false;
         let mut any_failed = false;
+        let mut skipped_items = 0;
+        let mut warnings = Vec::new();
 
         // Extract loop_control options - avoid repeated Option access in loop
         let loop_control = self.loop_control.as_ref();
@@ -659,6 +860,8 @@ impl Task {
 
         // Pre-allocate static string keys to avoid repeated allocations in loop
         static ANSIBLE_LOOP_KEY: &str = "ansible_loop";
+        // Templates may also use the Jinja-style name
+        static LOOP_KEY: &str = "loop";
 
         for (index, item) in items.iter().enumerate() {
             // Pause between iterations (but not before the first)
@@ -689,15 +892,13 @@ impl Task {
                     "first": index == 0,
                     "last": index == total_items - 1,
                     "length": total_items,
+                    "revindex": total_items - index,      // 1-based reverse index
+                    "revindex0": total_items - index - 1, // 0-based reverse index
                 });
 
                 // Add extended loop info if enabled
                 if extended {
-                    let revindex = total_items - index; // 1-based reverse index
-                    let revindex0 = total_items - index - 1; // 0-based reverse index
                     let loop_obj = ansible_loop.as_object_mut().unwrap();
-                    loop_obj.insert("revindex".to_string(), serde_json::json!(revindex));
-                    loop_obj.insert("revindex0".to_string(), serde_json::json!(revindex0));
                     loop_obj.insert("allitems".to_string(), serde_json::json!(items));
                     loop_obj.insert(
                         "previtem".to_string(),
@@ -717,13 +918,43 @@ impl Task {
                     );
                 }
 
+                rt.set_task_var(LOOP_KEY.to_string(), ansible_loop.clone());
                 rt.set_task_var(ANSIBLE_LOOP_KEY.to_string(), ansible_loop);
             }
 
-            // Execute for this item with parallelization enforcement
-            let result = self
-                .execute_module(ctx, runtime, handlers, notified, parallelization_manager)
-                .await?;
+            if let Some(ref condition) = self.when {
+                if !self
+                    .evaluate_condition(condition, host_ctx, runtime)
+                    .await?
+                {
+                    debug!("Loop item {} skipped due to when condition", index);
+                    let mut skipped = RegisteredResult::skipped(format!(
+                        "Skipped: condition '{}' was false",
+                        condition
+                    ));
+                    skipped.data.insert("item".to_string(), item.clone());
+                    loop_results.push(skipped);
+                    skipped_items += 1;
+                    continue;
+                }
+            }
+
+            // Execute for this item with parallelization enforcement; with
+            // `until`, only this item is retried until its condition passes
+            let result = if self.until.is_some() {
+                self.execute_with_retry(
+                    ctx,
+                    host_ctx,
+                    runtime,
+                    handlers,
+                    notified,
+                    parallelization_manager,
+                )
+                .await?
+            } else {
+                self.execute_module(ctx, runtime, handlers, notified, parallelization_manager)
+                    .await?
+            };
 
             // Extract and store ansible_facts from module results in loops
             if let Some(ref result_data) = result.result {
@@ -744,53 +975,75 @@ impl Task {
             if result.changed {
                 any_changed = true;
             }
+            warnings.extend(result.warnings.iter().cloned());
+            let mut registered = result.to_registered(None, None);
+            registered
+                .data
+                .entry("item".to_string())
+                .or_insert_with(|| item.clone());
+
             if result.status == TaskStatus::Failed {
                 any_failed = true;
                 if !self.ignore_errors {
                     // Stop on first failure unless ignore_errors
-                    loop_results.push(result.to_registered(None, None));
+                    loop_results.push(registered);
                     break;
                 }
             }
 
-            loop_results.push(result.to_registered(None, None));
+            loop_results.push(registered);
         }
 
         // Clear only the loop-specific variables, preserving other task vars
         // This allows for future nested loop support
         {
             let mut rt = runtime.write().await;
-            let mut vars_to_clear = vec![self.loop_var.as_str(), "ansible_loop"];
+            let mut vars_to_clear = vec![self.loop_var.as_str(), ANSIBLE_LOOP_KEY, LOOP_KEY];
             if let Some(ref idx_var) = index_var {
                 vars_to_clear.push(idx_var.as_str());
             }
             rt.remove_task_vars(&vars_to_clear);
         }
 
-        // Create combined result
+        // Create combined result from the iterations that ran
+        let executed = loop_results.len() - skipped_items;
+        let all_skipped = executed == 0 && skipped_items > 0;
         let status = if any_failed && !self.ignore_errors {
             TaskStatus::Failed
         } else if any_changed {
             TaskStatus::Changed
+        } else if all_skipped {
+            TaskStatus::Skipped
         } else {
             TaskStatus::Ok
         };
 
+        let msg = if skipped_items > 0 {
+            format!(
+                "Completed {} loop iterations, skipped {}",
+                executed, skipped_items
+            )
+        } else {
+            format!("Completed {} loop iterations", executed)
+        };
+
         let result = TaskResult {
             status,
             changed: any_changed,
-            msg: Some(format!("Completed {} loop iterations", loop_results.len())),
+            msg: Some(msg),
             result: Some(serde_json::to_value(&loop_results).unwrap_or(JsonValue::Null)),
             diff: None,
+            warnings,
         };
 
         // Register combined result if needed
         if let Some(ref register_name) = self.register {
             let mut registered = RegisteredResult::ok(any_changed);
+            registered.skipped = all_skipped;
             registered.results = Some(loop_results);
 
             let mut rt = runtime.write().await;
-            rt.register_result(&ctx.host, register_name.clone(), registered);
+            rt.register_result(&host_ctx.host, register_name.clone(), registered);
         }
 
         // Notify handlers if anything changed
@@ -805,9 +1058,15 @@ impl Task {
     }
 
     /// Execute task with until/retries/delay retry logic
+    ///
+    /// Each attempt is registered on `host_ctx` (the inventory host, not a
+    /// delegate) and `until` is evaluated there. The attempt count is recorded
+    /// as `attempts` in the result. In a loop this runs once per item, so only
+    /// the item whose condition fails is retried.
     async fn execute_with_retry(
         &self,
         ctx: &ExecutionContext,
+        host_ctx: &ExecutionContext,
         runtime: &Arc<RwLock<RuntimeContext>>,
         handlers: &Arc<RwLock<HashMap<String, Handler>>>,
         notified: &Arc<Mutex<std::collections::HashSet<String>>>,
@@ -830,9 +1089,16 @@ impl Task {
             debug!("Retry attempt {} of {}", attempt, max_retries + 1);
 
             // Execute the module
-            let result = self
+            let mut result = self
                 .execute_module(ctx, runtime, handlers, notified, parallelization_manager)
                 .await?;
+            match result.result {
+                Some(JsonValue::Object(ref mut data)) => {
+                    data.insert("attempts".to_string(), serde_json::json!(attempt));
+                }
+                None => result.result = Some(serde_json::json!({ "attempts": attempt })),
+                Some(_) => {}
+            }
 
             // Extract and store ansible_facts from module results during retries
             if let Some(ref result_data) = result.result {
@@ -852,13 +1118,13 @@ impl Task {
 
             // Register the result for condition evaluation
             if let Some(ref register_name) = self.register {
-                self.register_result(register_name, &result, ctx, runtime)
+                self.register_result(register_name, &result, host_ctx, runtime)
                     .await?;
             }
 
             // Evaluate the until condition
             let condition_met = self
-                .evaluate_condition(until_condition, ctx, runtime)
+                .evaluate_condition(until_condition, host_ctx, runtime)
                 .await?;
 
             if condition_met {
@@ -895,6 +1161,7 @@ impl Task {
             )),
             result: last_result.as_ref().and_then(|r| r.result.clone()),
             diff: None,
+            warnings: last_result.map(|r| r.warnings).unwrap_or_default(),
         })
     }
 
@@ -912,27 +1179,38 @@ impl Task {
 
         debug!("Module: {}, Args: {:?}", self.module, args);
 
-        // Enforce parallelization constraints based on module hint
-        // Get the module's parallelization hint from the registry
-        let hint = {
-            let registry = crate::modules::ModuleRegistry::with_builtins();
-            if let Some(module) = registry.get(&self.module) {
-                module.parallelization_hint()
-            } else {
-                // For unknown modules (Python fallback), use FullyParallel as default
-                crate::modules::ParallelizationHint::FullyParallel
+        // Refuse destructive modules that are not allowlisted in safe mode
+        if let Some(ref safe_mode) = ctx.safe_mode {
+            let state = args.get("state").and_then(|v| v.as_str());
+            if let Err(msg) = safe_mode.check(&self.name, &self.module, state) {
+                return Ok(TaskResult::failed(msg));
             }
+        }
+
+        // Enforce parallelization constraints based on module hint
+        let hint = parallelization_manager.hint_for(&self.module);
+
+        // HostExclusive locks the machine rather than the inventory name, so
+        // aliases sharing an ansible_host serialize with each other
+        let lock_host = match hint {
+            crate::modules::ParallelizationHint::HostExclusive => runtime
+                .read()
+                .await
+                .get_host_var(&ctx.host, "ansible_host")
+                .and_then(|v| v.as_str().map(String::from))
+                .unwrap_or_else(|| ctx.host.clone()),
+            _ => ctx.host.clone(),
         };
 
         // Acquire parallelization guard - this will block if necessary based on the hint
         // The guard is automatically released when it goes out of scope (when this function returns)
         let _parallelization_guard = parallelization_manager
-            .acquire(hint, &ctx.host, &self.module)
+            .acquire(hint, &lock_host, &self.module)
             .await;
 
         // Execute based on module type
         let result = match self.module.as_str() {
-            "debug" => self.execute_debug(&args, ctx).await,
+            "debug" => self.execute_debug(&args, ctx, runtime).await,
             "set_fact" => self.execute_set_fact(&args, ctx, runtime).await,
             "command" | "shell" => self.execute_command(&args, ctx, runtime).await,
             "copy" => self.execute_copy(&args, ctx, runtime).await,
@@ -961,7 +1239,7 @@ impl Task {
                 )
                 .await
             }
-            "meta" => self.execute_meta(&args).await,
+            "meta" => self.execute_meta(&args, runtime).await,
             "gather_facts" | "setup" => self.execute_gather_facts(&args, ctx).await,
             _ => {
                 // Python fallback for unknown modules
@@ -998,20 +1276,9 @@ impl Task {
                             )
                             .await
                         {
-                            Ok(output) => {
-                                let msg = output.msg.clone();
-                                let mut result = if output.changed {
-                                    TaskResult::changed()
-                                } else {
-                                    TaskResult::ok()
-                                };
-                                result.msg = Some(msg);
-                                if !output.data.is_empty() {
-                                    result.result = Some(
-                                        serde_json::to_value(&output.data).unwrap_or_default(),
-                                    );
-                                }
-                                Ok(result)
+                            Ok(output) => Ok(TaskResult::from_module_output(output)),
+                            Err(e) if e.is_unreachable() => {
+                                Ok(TaskResult::from_module_error(&self.module, &e))
                             }
                             Err(e) => Err(ExecutorError::RuntimeError(format!(
                                 "Python module {} failed: {}",
@@ -1057,7 +1324,7 @@ impl Task {
         runtime: &Arc<RwLock<RuntimeContext>>,
     ) -> ExecutorResult<IndexMap<String, JsonValue>> {
         let rt = runtime.read().await;
-        let vars = rt.get_merged_vars(&ctx.host);
+        let vars = host_vars(&rt, &ctx.host, &ctx.task_vars);
         let mut result = IndexMap::new();
 
         for (key, value) in &self.args {
@@ -1076,7 +1343,7 @@ impl Task {
         runtime: &Arc<RwLock<RuntimeContext>>,
     ) -> ExecutorResult<bool> {
         let rt = runtime.read().await;
-        let vars = rt.get_merged_vars(&ctx.host);
+        let vars = host_vars(&rt, &ctx.host, &ctx.task_vars);
 
         evaluate_expression(condition, &vars)
     }
@@ -1091,11 +1358,14 @@ impl Task {
         if let Some(ref condition) = self.changed_when {
             let should_be_changed = self.evaluate_condition(condition, ctx, runtime).await?;
             result.changed = should_be_changed;
-            result.status = if should_be_changed {
-                TaskStatus::Changed
-            } else {
-                TaskStatus::Ok
-            };
+            // Changing the changed flag never clears a failure
+            if result.status != TaskStatus::Failed {
+                result.status = if should_be_changed {
+                    TaskStatus::Changed
+                } else {
+                    TaskStatus::Ok
+                };
+            }
         }
         Ok(result)
     }
@@ -1115,6 +1385,13 @@ impl Task {
                     "Failed due to failed_when condition: {}",
                     condition
                 ));
+            } else if result.status == TaskStatus::Failed {
+                // failed_when decides failure on its own; changed is left alone
+                result.status = if result.changed {
+                    TaskStatus::Changed
+                } else {
+                    TaskStatus::Ok
+                };
             }
         }
         Ok(result)
@@ -1136,19 +1413,81 @@ impl Task {
         Ok(())
     }
 
+    /// Fill `become`, `become_user` and `become_method` from an enclosing
+    /// block or play where the task leaves them unset
+    pub fn inherit_become(
+        &mut self,
+        r#become: Option<bool>,
+        become_user: Option<&str>,
+        become_method: Option<&str>,
+    ) {
+        if self.r#become.is_none() {
+            self.r#become = r#become;
+        }
+        if self.become_user.is_none() {
+            self.become_user = become_user.map(String::from);
+        }
+        if self.become_method.is_none() {
+            self.become_method = become_method.map(String::from);
+        }
+    }
+
+    /// Module context for running this task on `ctx`'s host
+    ///
+    /// `--become` defaults from the executor config enable escalation for
+    /// tasks that don't set `become`; the task's own user and method win.
+    fn module_context(&self, ctx: &ExecutionContext) -> crate::modules::ModuleContext {
+        let defaults = &ctx.become_defaults;
+        let r#become = self.r#become.unwrap_or(defaults.enabled);
+
+        crate::modules::ModuleContext {
+            check_mode: ctx.check_mode,
+            diff_mode: ctx.diff_mode,
+            r#become,
+            become_method: self
+                .become_method
+                .clone()
+                .or_else(|| defaults.method.clone().filter(|_| r#become)),
+            become_user: self
+                .become_user
+                .clone()
+                .or_else(|| defaults.user.clone().filter(|_| r#become)),
+            become_password: defaults.password.clone().filter(|_| r#become),
+            ..Default::default()
+        }
+    }
+
     // Module implementations
 
     async fn execute_debug(
         &self,
         args: &IndexMap<String, JsonValue>,
-        _ctx: &ExecutionContext,
+        ctx: &ExecutionContext,
+        runtime: &Arc<RwLock<RuntimeContext>>,
     ) -> ExecutorResult<TaskResult> {
+        use crate::modules::debug::format_debug_value;
+
         if let Some(msg) = args.get("msg") {
+            let msg = match msg {
+                JsonValue::Array(_) | JsonValue::Object(_) => format_debug_value(msg),
+                _ => json_to_string(msg),
+            };
+            info!("DEBUG: {}", msg);
+            Ok(TaskResult::ok().with_msg(msg))
+        } else if let Some(var) = args.get("var").and_then(|v| v.as_str()) {
+            let value = {
+                let rt = runtime.read().await;
+                evaluate_variable_expression(var, &host_vars(&rt, &ctx.host, &ctx.task_vars))?
+            };
+            let msg = if value.is_null() {
+                format!("{}: VARIABLE IS NOT DEFINED!", var)
+            } else {
+                format!("{}: {}", var, format_debug_value(&value))
+            };
             info!("DEBUG: {}", msg);
-            Ok(TaskResult::ok().with_msg(format!("{}", msg)))
-        } else if let Some(var) = args.get("var") {
-            info!("DEBUG: {} = {:?}", var, var);
-            Ok(TaskResult::ok().with_result(var.clone()))
+            Ok(TaskResult::ok()
+                .with_msg(msg)
+                .with_result(serde_json::json!({ var: value })))
         } else {
             Ok(TaskResult::ok())
         }
@@ -1177,6 +1516,9 @@ impl Task {
         if let Some(subset) = gather_subset {
             params.insert("gather_subset".to_string(), serde_json::json!(subset));
         }
+        if let Some(filter) = args.get("filter") {
+            params.insert("filter".to_string(), filter.clone());
+        }
 
         // Create module context
         let module_ctx = ModuleContext::default();
@@ -1187,6 +1529,7 @@ impl Task {
             Ok(output) => {
                 let mut result = TaskResult::ok();
                 result.msg = Some(output.msg.clone());
+                result.warnings = output.warnings;
 
                 // Include ansible_facts in the result so they can be stored
                 if !output.data.is_empty() {
@@ -1218,11 +1561,23 @@ impl Task {
         // appropriate host context
         let fact_target = &ctx.host;
 
-        for (key, value) in args {
+        // Free-form `key=value` arguments are split before templating so each
+        // value keeps the type of its expression
+        let mut facts = args.clone();
+        if let Some(JsonValue::String(raw)) = self.args.get("_raw_params") {
+            facts.shift_remove("_raw_params");
+            let vars = host_vars(&rt, &ctx.host, &ctx.task_vars);
+            for (key, value) in parse_kv_params(raw) {
+                facts.insert(key, template_value(&value, &vars)?);
+            }
+        }
+
+        for (key, value) in facts {
             if key != "cacheable" {
-                // Use set_host_fact instead of set_host_var for proper precedence
-                // Facts set by set_fact should have SetFact precedence level
-                rt.set_host_fact(fact_target, key.clone(), value.clone());
+                let value = native_value(value);
+                // Stored as a fact and exposed by name at SetFact precedence,
+                // above play and task vars
+                rt.set_host_fact_var(fact_target, key.clone(), value.clone());
                 debug!(
                     "Set fact '{}' = {:?} for host '{}'",
                     key, value, fact_target
@@ -1246,18 +1601,32 @@ impl Task {
         ctx: &ExecutionContext,
         _runtime: &Arc<RwLock<RuntimeContext>>,
     ) -> ExecutorResult<TaskResult> {
-        let cmd = args
-            .get("cmd")
-            .or_else(|| args.get("_raw_params"))
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                ExecutorError::RuntimeError("command module requires 'cmd' argument".into())
-            })?;
+        let cmd = match args.get("argv") {
+            Some(JsonValue::Array(argv)) => argv
+                .iter()
+                .map(|arg| arg.as_str().map_or_else(|| arg.to_string(), str::to_string))
+                .collect::<Vec<_>>()
+                .join(" "),
+            _ => args
+                .get("cmd")
+                .or_else(|| args.get("_raw_params"))
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| {
+                    ExecutorError::RuntimeError(
+                        "command module requires 'cmd' or 'argv' argument".into(),
+                    )
+                })?
+                .to_string(),
+        };
 
         if ctx.check_mode {
             return Ok(TaskResult::skipped("Check mode - command not executed"));
         }
 
+        if let Some(connection) = ctx.connection.clone() {
+            return self.execute_command_on(args, ctx, connection).await;
+        }
+
         debug!("Would execute command: {}", cmd);
 
         // In a real implementation, this would actually run the command
@@ -1275,6 +1644,55 @@ impl Task {
             .with_result(result.to_json()))
     }
 
+    /// Run command/shell through its module over the host's connection
+    ///
+    /// The module wraps the command for privilege escalation when the task
+    /// or the `--become` defaults enable `become`.
+    async fn execute_command_on(
+        &self,
+        args: &IndexMap<String, JsonValue>,
+        ctx: &ExecutionContext,
+        connection: Arc<dyn crate::connection::Connection>,
+    ) -> ExecutorResult<TaskResult> {
+        let params: std::collections::HashMap<String, serde_json::Value> =
+            args.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+
+        let module_ctx = crate::modules::ModuleContext {
+            connection: Some(connection),
+            ..self.module_context(ctx)
+        };
+
+        // Modules drive the connection from their own runtime
+        let module_name = self.module.clone();
+        let output = tokio::task::spawn_blocking(move || {
+            let registry = crate::modules::ModuleRegistry::with_builtins();
+            let module = registry.get(&module_name).ok_or_else(|| {
+                ModuleError::ModuleNotFound(format!("{} module not found in registry", module_name))
+            })?;
+            module.execute(&params, &module_ctx)
+        })
+        .await
+        .map_err(|e| {
+            ExecutorError::RuntimeError(format!("{} task panicked: {}", self.module, e))
+        })?;
+
+        match output {
+            Ok(output) => {
+                let result = RegisteredResult {
+                    changed: output.changed,
+                    failed: output.failed,
+                    rc: output.rc,
+                    stdout: output.stdout.clone(),
+                    stderr: output.stderr.clone(),
+                    msg: Some(output.msg.clone()),
+                    ..Default::default()
+                };
+                Ok(TaskResult::from_module_output(output).with_result(result.to_json()))
+            }
+            Err(e) => Ok(TaskResult::from_module_error(&self.module, &e)),
+        }
+    }
+
     async fn execute_copy(
         &self,
         args: &IndexMap<String, JsonValue>,
@@ -1288,7 +1706,7 @@ impl Task {
         // Get all variables from runtime for potential content template substitution
         let vars = {
             let rt = runtime.read().await;
-            rt.get_merged_vars(&ctx.host)
+            host_vars(&rt, &ctx.host, &ctx.task_vars)
         };
 
         // If content contains template variables, use the template module's rendering
@@ -1297,15 +1715,9 @@ impl Task {
                 // Use template module for content with variables
                 let template_params = params.clone();
                 let module_ctx = crate::modules::ModuleContext {
-                    check_mode: ctx.check_mode,
-                    diff_mode: ctx.diff_mode,
                     vars: vars.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
-                    facts: std::collections::HashMap::new(),
-                    work_dir: None,
-                    r#become: false,
-                    become_method: None,
-                    become_user: None,
                     connection: None,
+                    ..self.module_context(ctx)
                 };
 
                 let registry = crate::modules::ModuleRegistry::with_builtins();
@@ -1314,38 +1726,19 @@ impl Task {
                 })?;
 
                 return match module.execute(&template_params, &module_ctx) {
-                    Ok(output) => {
-                        let mut result = if output.changed {
-                            TaskResult::changed()
-                        } else {
-                            TaskResult::ok()
-                        };
-                        result.msg = Some(output.msg);
-                        if !output.data.is_empty() {
-                            result.result =
-                                Some(serde_json::to_value(&output.data).unwrap_or_default());
-                        }
-                        Ok(result)
-                    }
-                    Err(e) => Ok(TaskResult::failed(format!(
-                        "template (for copy with content) failed: {}",
-                        e
-                    ))),
+                    Ok(output) => Ok(TaskResult::from_module_output(output)),
+                    Err(e) => Ok(TaskResult::from_module_error(
+                        "template (for copy with content)",
+                        &e,
+                    )),
                 };
             }
         }
 
         // Create module context from execution context
         let module_ctx = crate::modules::ModuleContext {
-            check_mode: ctx.check_mode,
-            diff_mode: ctx.diff_mode,
-            vars: std::collections::HashMap::new(),
-            facts: std::collections::HashMap::new(),
-            work_dir: None,
-            r#become: false,
-            become_method: None,
-            become_user: None,
             connection: None, // Local execution for integration tests
+            ..self.module_context(ctx)
         };
 
         // Get the copy module from registry and execute
@@ -1355,19 +1748,8 @@ impl Task {
         })?;
 
         match module.execute(&params, &module_ctx) {
-            Ok(output) => {
-                let mut result = if output.changed {
-                    TaskResult::changed()
-                } else {
-                    TaskResult::ok()
-                };
-                result.msg = Some(output.msg);
-                if !output.data.is_empty() {
-                    result.result = Some(serde_json::to_value(&output.data).unwrap_or_default());
-                }
-                Ok(result)
-            }
-            Err(e) => Ok(TaskResult::failed(format!("copy module failed: {}", e))),
+            Ok(output) => Ok(TaskResult::from_module_output(output)),
+            Err(e) => Ok(TaskResult::from_module_error("copy", &e)),
         }
     }
 
@@ -1382,15 +1764,8 @@ impl Task {
 
         // Create module context from execution context
         let module_ctx = crate::modules::ModuleContext {
-            check_mode: ctx.check_mode,
-            diff_mode: ctx.diff_mode,
-            vars: std::collections::HashMap::new(),
-            facts: std::collections::HashMap::new(),
-            work_dir: None,
-            r#become: false,
-            become_method: None,
-            become_user: None,
             connection: None, // Local execution for integration tests
+            ..self.module_context(ctx)
         };
 
         // Get the file module from registry and execute
@@ -1400,19 +1775,8 @@ impl Task {
         })?;
 
         match module.execute(&params, &module_ctx) {
-            Ok(output) => {
-                let mut result = if output.changed {
-                    TaskResult::changed()
-                } else {
-                    TaskResult::ok()
-                };
-                result.msg = Some(output.msg);
-                if !output.data.is_empty() {
-                    result.result = Some(serde_json::to_value(&output.data).unwrap_or_default());
-                }
-                Ok(result)
-            }
-            Err(e) => Ok(TaskResult::failed(format!("file module failed: {}", e))),
+            Ok(output) => Ok(TaskResult::from_module_output(output)),
+            Err(e) => Ok(TaskResult::from_module_error("file", &e)),
         }
     }
 
@@ -1429,20 +1793,14 @@ impl Task {
         // Get all variables from runtime for template substitution
         let vars = {
             let rt = runtime.read().await;
-            rt.get_merged_vars(&ctx.host)
+            host_vars(&rt, &ctx.host, &ctx.task_vars)
         };
 
         // Create module context from execution context with variables
         let module_ctx = crate::modules::ModuleContext {
-            check_mode: ctx.check_mode,
-            diff_mode: ctx.diff_mode,
             vars: vars.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
-            facts: std::collections::HashMap::new(),
-            work_dir: None,
-            r#become: false,
-            become_method: None,
-            become_user: None,
             connection: None, // Local execution for integration tests
+            ..self.module_context(ctx)
         };
 
         // Get the template module from registry and execute
@@ -1452,19 +1810,8 @@ impl Task {
         })?;
 
         match module.execute(&params, &module_ctx) {
-            Ok(output) => {
-                let mut result = if output.changed {
-                    TaskResult::changed()
-                } else {
-                    TaskResult::ok()
-                };
-                result.msg = Some(output.msg);
-                if !output.data.is_empty() {
-                    result.result = Some(serde_json::to_value(&output.data).unwrap_or_default());
-                }
-                Ok(result)
-            }
-            Err(e) => Ok(TaskResult::failed(format!("template module failed: {}", e))),
+            Ok(output) => Ok(TaskResult::from_module_output(output)),
+            Err(e) => Ok(TaskResult::from_module_error("template", &e)),
         }
     }
 
@@ -1651,11 +1998,13 @@ impl Task {
         for condition in conditions {
             let result = self.evaluate_condition(condition, ctx, runtime).await?;
             if !result {
+                // A structured fail_msg is shown as indented JSON
                 let fail_msg = args
                     .get("fail_msg")
                     .or_else(|| args.get("msg"))
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Assertion failed");
+                    .filter(|v| !v.is_null())
+                    .map(crate::modules::debug::format_debug_value)
+                    .unwrap_or_else(|| "Assertion failed".to_string());
 
                 return Ok(TaskResult::failed(format!("{}: {}", fail_msg, condition)));
             }
@@ -2086,21 +2435,40 @@ impl Task {
         }
     }
 
-    async fn execute_meta(&self, args: &IndexMap<String, JsonValue>) -> ExecutorResult<TaskResult> {
-        let action = args
-            .get("_raw_params")
-            .or_else(|| args.get("action"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("noop");
-
-        match action {
+    async fn execute_meta(
+        &self,
+        args: &IndexMap<String, JsonValue>,
+        runtime: &Arc<RwLock<RuntimeContext>>,
+    ) -> ExecutorResult<TaskResult> {
+        match meta_action(args) {
             "flush_handlers" => {
                 debug!("Would flush handlers");
                 Ok(TaskResult::ok().with_msg("Handlers flushed"))
             }
             "refresh_inventory" => {
-                debug!("Would refresh inventory");
-                Ok(TaskResult::ok().with_msg("Inventory refreshed"))
+                let source = runtime.read().await.inventory_source();
+                let Some(source) = source else {
+                    warn!("refresh_inventory: no inventory source configured, nothing to refresh");
+                    return Ok(TaskResult::skipped(
+                        "No inventory source configured, nothing to refresh",
+                    ));
+                };
+
+                source.refresh().await.map_err(|e| {
+                    ExecutorError::Other(format!("Failed to refresh inventory: {}", e))
+                })?;
+                let inventory = source.parse().await.map_err(|e| {
+                    ExecutorError::Other(format!("Failed to refresh inventory: {}", e))
+                })?;
+
+                let added = runtime.write().await.merge_inventory(&inventory);
+                info!(
+                    "Refreshed inventory from '{}': {} new host(s)",
+                    source.name(),
+                    added
+                );
+                Ok(TaskResult::ok()
+                    .with_msg(format!("Inventory refreshed ({} new host(s))", added)))
             }
             "noop" => Ok(TaskResult::ok()),
             "end_play" => Ok(TaskResult::ok().with_msg("Play ended")),
@@ -2110,7 +2478,7 @@ impl Task {
                 Ok(TaskResult::ok().with_msg("Facts cleared"))
             }
             "clear_host_errors" => Ok(TaskResult::ok().with_msg("Host errors cleared")),
-            _ => {
+            action => {
                 warn!("Unknown meta action: {}", action);
                 Ok(TaskResult::ok())
             }
@@ -2118,6 +2486,66 @@ impl Task {
     }
 }
 
+/// Action named by a `meta` task's arguments
+fn meta_action(args: &IndexMap<String, JsonValue>) -> &str {
+    args.get("_raw_params")
+        .or_else(|| args.get("action"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("noop")
+}
+
+/// Maximum number of passes when play vars reference other play vars
+const MAX_PLAY_VAR_DEPTH: usize = 8;
+
+/// Merged variables for a host with templated play vars rendered
+///
+/// Play vars are stored unrendered and evaluated against the host's current
+/// variables each time they are needed, so a play var such as
+/// `"{{ ansible_default_ipv4.address }}"` resolves once facts have been
+/// gathered for that host. Play vars that refer to other play vars are
+/// rendered in repeated passes until they settle.
+fn host_vars(
+    rt: &RuntimeContext,
+    host: &str,
+    task_vars: &IndexMap<String, JsonValue>,
+) -> IndexMap<String, JsonValue> {
+    let mut vars = rt.get_merged_vars_with_task_vars(host, task_vars);
+    let deferred: Vec<(&String, &JsonValue)> = rt
+        .play_vars()
+        .iter()
+        .filter(|(key, value)| is_templated(value) && vars.get(key.as_str()) == Some(*value))
+        .collect();
+
+    for _ in 0..MAX_PLAY_VAR_DEPTH {
+        let mut settled = true;
+        for (key, raw) in &deferred {
+            // A var that cannot be rendered yet keeps its raw value
+            let Ok(rendered) = template_value(raw, &vars) else {
+                continue;
+            };
+            if vars.get(key.as_str()) != Some(&rendered) {
+                vars.insert((*key).clone(), rendered);
+                settled = false;
+            }
+        }
+        if settled {
+            break;
+        }
+    }
+
+    vars
+}
+
+/// Whether a value contains template syntax anywhere
+fn is_templated(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::String(s) => s.contains("{{") || s.contains("{%"),
+        JsonValue::Array(arr) => arr.iter().any(is_templated),
+        JsonValue::Object(obj) => obj.values().any(is_templated),
+        _ => false,
+    }
+}
+
 /// Template a value using variables
 ///
 /// # Performance
@@ -2133,10 +2561,26 @@ fn template_value(
         // OPTIMIZATION: Non-templatable primitives - fast path with clone
         JsonValue::Null | JsonValue::Bool(_) | JsonValue::Number(_) => Ok(value.clone()),
         JsonValue::String(s) => {
+            // Control structures need the full Jinja2 engine
+            if s.contains("{%") {
+                let vars: std::collections::HashMap<String, JsonValue> =
+                    vars.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                let rendered = BLOCK_TEMPLATE_ENGINE.render(s, &vars).map_err(|e| {
+                    ExecutorError::RuntimeError(format!("Failed to render '{}': {}", s, e))
+                })?;
+                return Ok(JsonValue::String(rendered));
+            }
             // OPTIMIZATION: Fast path if no template syntax
             if !s.contains("{{") {
                 return Ok(value.clone());
             }
+            // A lone expression keeps the native type of its result
+            if let Some(expr) = whole_expression(s) {
+                let evaluated = evaluate_variable_expression(expr, vars)?;
+                if !evaluated.is_null() {
+                    return Ok(evaluated);
+                }
+            }
             let templated = template_string(s, vars)?;
             // Try to parse as JSON if it looks like a value
             if let Ok(parsed) = serde_json::from_str::<JsonValue>(&templated) {
@@ -2163,6 +2607,94 @@ fn template_value(
     }
 }
 
+/// Get the expression of a string that consists of a single `{{ expr }}`
+fn whole_expression(template: &str) -> Option<&str> {
+    let template = template.trim();
+    let cap = TEMPLATE_VAR_REGEX.captures(template)?;
+    if cap.get(0)?.len() == template.len() {
+        Some(cap.get(1)?.as_str().trim())
+    } else {
+        None
+    }
+}
+
+/// Coerce a rendered string into its native type
+///
+/// Numbers, booleans and structured values (JSON or YAML flow syntax such as
+/// `['a', 'b']`) are parsed; anything else stays a string.
+fn native_value(value: JsonValue) -> JsonValue {
+    let JsonValue::String(ref s) = value else {
+        return value;
+    };
+    let trimmed = s.trim();
+
+    match trimmed {
+        "True" => return JsonValue::Bool(true),
+        "False" => return JsonValue::Bool(false),
+        _ => {}
+    }
+
+    if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        if let Ok(parsed) = serde_json::from_str::<JsonValue>(trimmed) {
+            return parsed;
+        }
+        if let Ok(parsed) = serde_yaml::from_str::<JsonValue>(trimmed) {
+            if parsed.is_array() || parsed.is_object() {
+                return parsed;
+            }
+        }
+    } else if let Ok(parsed) = serde_json::from_str::<JsonValue>(trimmed) {
+        if !parsed.is_string() {
+            return parsed;
+        }
+    }
+
+    value
+}
+
+/// Split free-form `key=value` arguments on whitespace, keeping quoted values
+/// and `{{ ... }}` expressions intact
+fn parse_kv_params(raw: &str) -> IndexMap<String, JsonValue> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut depth = 0usize;
+    let mut chars = raw.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' if depth == 0 && quote.is_none() => quote = Some(c),
+            c if Some(c) == quote => quote = None,
+            '{' if quote.is_none() && chars.peek() == Some(&'{') => depth += 1,
+            '}' if quote.is_none() && depth > 0 && chars.peek() == Some(&'}') => depth -= 1,
+            c if c.is_whitespace() && quote.is_none() && depth == 0 => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+        .iter()
+        .filter_map(|token| token.split_once('='))
+        .map(|(key, value)| {
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            (key.to_string(), JsonValue::String(value.to_string()))
+        })
+        .collect()
+}
+
 /// Template a string using variables
 ///
 /// # Performance
@@ -2202,6 +2734,20 @@ fn evaluate_variable_expression(
     expr: &str,
     vars: &IndexMap<String, JsonValue>,
 ) -> ExecutorResult<JsonValue> {
+    if let Some(literal) = parse_literal(expr.trim()) {
+        return Ok(literal);
+    }
+
+    // Filter chains (e.g., "items | length | int") go to the Jinja2 engine,
+    // which keeps the native type of the result and rejects unknown filters
+    if find_operator_outside_parens(expr, "|").is_some() {
+        let context: std::collections::HashMap<String, JsonValue> =
+            vars.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        return BLOCK_TEMPLATE_ENGINE.evaluate(expr, &context).map_err(|e| {
+            ExecutorError::RuntimeError(format!("Failed to evaluate '{}': {}", expr, e))
+        });
+    }
+
     // Handle simple variable lookup
     let parts: Vec<&str> = expr.split('.').collect();
 
@@ -2758,33 +3304,38 @@ fn evaluate_expression(expr: &str, vars: &IndexMap<String, JsonValue>) -> Execut
 /// Hot path for expression evaluation - inline for better optimization.
 #[inline]
 fn parse_value(s: &str, vars: &IndexMap<String, JsonValue>) -> ExecutorResult<JsonValue> {
-    let s = s.trim();
+    // Literals are handled before variable lookup
+    evaluate_variable_expression(s.trim(), vars)
+}
 
+/// Parse a string, boolean or number literal
+fn parse_literal(s: &str) -> Option<JsonValue> {
     // String literal
-    if (s.starts_with('\'') && s.ends_with('\'')) || (s.starts_with('"') && s.ends_with('"')) {
-        return Ok(JsonValue::String(s[1..s.len() - 1].to_string()));
+    if s.len() >= 2
+        && ((s.starts_with('\'') && s.ends_with('\'')) || (s.starts_with('"') && s.ends_with('"')))
+    {
+        return Some(JsonValue::String(s[1..s.len() - 1].to_string()));
     }
 
     // Boolean
     if s == "true" || s == "True" {
-        return Ok(JsonValue::Bool(true));
+        return Some(JsonValue::Bool(true));
     }
     if s == "false" || s == "False" {
-        return Ok(JsonValue::Bool(false));
+        return Some(JsonValue::Bool(false));
     }
 
     // Number
     if let Ok(n) = s.parse::<i64>() {
-        return Ok(JsonValue::Number(n.into()));
+        return Some(JsonValue::Number(n.into()));
     }
     if let Ok(n) = s.parse::<f64>() {
         if let Some(num) = serde_json::Number::from_f64(n) {
-            return Ok(JsonValue::Number(num));
+            return Some(JsonValue::Number(num));
         }
     }
 
-    // Variable reference
-    evaluate_variable_expression(s, vars)
+    None
 }
 
 /// Check if a JSON value is "truthy"
@@ -2921,6 +3472,18 @@ mod tests {
         assert_eq!(result.msg, Some("error message".to_string()));
     }
 
+    #[test]
+    fn test_task_result_from_failed_module_output_keeps_changed() {
+        let output = ModuleOutput::changed("partially applied").with_failed(true);
+        let result = TaskResult::from_module_output(output);
+        assert_eq!(result.status, TaskStatus::Failed);
+        assert!(result.changed);
+
+        let registered = result.to_registered(None, None);
+        assert!(registered.failed);
+        assert!(registered.changed);
+    }
+
     #[test]
     fn test_is_truthy() {
         assert!(!is_truthy(&JsonValue::Null));
@@ -2931,4 +3494,99 @@ mod tests {
         assert!(!is_truthy(&JsonValue::Array(vec![])));
         assert!(is_truthy(&JsonValue::Array(vec![JsonValue::Null])));
     }
+
+    #[test]
+    fn test_template_value_keeps_native_types() {
+        let mut vars = IndexMap::new();
+        vars.insert("items".to_string(), serde_json::json!(["a", "b"]));
+        vars.insert("count".to_string(), serde_json::json!("7"));
+        vars.insert("name".to_string(), serde_json::json!("web"));
+
+        let render = |s: &str| template_value(&JsonValue::String(s.to_string()), &vars).unwrap();
+        assert_eq!(render("{{ items | length }}"), serde_json::json!(2));
+        assert_eq!(render("{{ count | int }}"), serde_json::json!(7));
+        assert_eq!(render("{{ items | length | int }}"), serde_json::json!(2));
+        assert_eq!(render("{{ count | int | string }}"), serde_json::json!("7"));
+        assert_eq!(
+            render("{{ missing.port | default(80) | int }}"),
+            serde_json::json!(80)
+        );
+        assert_eq!(
+            render("{{ missing | default('x') }}"),
+            serde_json::json!("x")
+        );
+        assert_eq!(render("{{ true }}"), serde_json::json!(true));
+        assert_eq!(render("n={{ items | length }}"), serde_json::json!("n=2"));
+
+        assert_eq!(render("{{ name | upper }}"), serde_json::json!("WEB"));
+        assert_eq!(render("{{ items | join(',') }}"), serde_json::json!("a,b"));
+        assert_eq!(
+            render("{{ name | regex_search('w.b') }}"),
+            serde_json::json!("web")
+        );
+        assert!(evaluate_expression("name | regex_search('^w')", &vars).unwrap());
+        assert!(!evaluate_expression("name | regex_search('^x')", &vars).unwrap());
+        let err = template_value(&serde_json::json!("{{ name | no_such_filter }}"), &vars);
+        assert!(err
+            .unwrap_err()
+            .to_string()
+            .contains("Failed to evaluate 'name | no_such_filter'"));
+    }
+
+    #[test]
+    fn test_parse_kv_params() {
+        let params = parse_kv_params("a=1 b={{ x | length }} c='two words'");
+        assert_eq!(params.len(), 3);
+        assert_eq!(params["b"], serde_json::json!("{{ x | length }}"));
+        assert_eq!(params["c"], serde_json::json!("two words"));
+    }
+
+    #[test]
+    fn test_native_value() {
+        let native = |s: &str| native_value(JsonValue::String(s.to_string()));
+        assert_eq!(native("42"), serde_json::json!(42));
+        assert_eq!(native("True"), serde_json::json!(true));
+        assert_eq!(native("['a', 'b']"), serde_json::json!(["a", "b"]));
+        assert_eq!(native("{'k': 1}"), serde_json::json!({"k": 1}));
+        assert_eq!(native("hello world"), serde_json::json!("hello world"));
+        assert_eq!(native("\"quoted\""), serde_json::json!("\"quoted\""));
+    }
+
+    #[test]
+    fn test_module_context_applies_become_defaults() {
+        let ctx = ExecutionContext::new("web1").with_become_defaults(
+            crate::executor::runtime::BecomeDefaults {
+                enabled: true,
+                method: Some("sudo".to_string()),
+                user: Some("root".to_string()),
+                password: Some("secret".to_string()),
+            },
+        );
+
+        let plain = Task::new("Install nginx", "package").module_context(&ctx);
+        assert!(plain.r#become);
+        assert_eq!(plain.become_user.as_deref(), Some("root"));
+        assert_eq!(plain.become_password.as_deref(), Some("secret"));
+
+        // The task's own settings win over the command line defaults
+        let mut task = Task::new("Deploy", "shell");
+        task.become_user = Some("deploy".to_string());
+        task.become_method = Some("su".to_string());
+        let own = task.module_context(&ctx);
+        assert_eq!(own.become_user.as_deref(), Some("deploy"));
+        assert_eq!(own.become_method.as_deref(), Some("su"));
+
+        // An explicit `become: false` opts out of `--become`
+        let mut opted_out = Task::new("Check status", "command");
+        opted_out.r#become = Some(false);
+        let opted_out = opted_out.module_context(&ctx);
+        assert!(!opted_out.r#become);
+        assert!(opted_out.become_user.is_none());
+        assert!(opted_out.become_password.is_none());
+
+        let unescalated =
+            Task::new("Install nginx", "package").module_context(&ExecutionContext::new("web1"));
+        assert!(!unescalated.r#become);
+        assert!(unescalated.become_password.is_none());
+    }
 }
@@ -2,6 +2,7 @@
 
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 
 /// Facts gathered from a host
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -51,3 +52,236 @@ impl Facts {
         facts
     }
 }
+
+/// Normalize `uname -m` output into the `machine` fact, e.g. `arm64`
+/// becomes `aarch64`. Unknown architectures are passed through.
+pub fn machine_name(arch: &str) -> &str {
+    match arch {
+        "x86_64" | "amd64" => "x86_64",
+        "aarch64" | "arm64" => "aarch64",
+        "armv7l" => "armv7l",
+        "i686" | "i386" => "i386",
+        _ => arch,
+    }
+}
+
+/// Ansible distribution names for `/etc/os-release` `ID` values
+const DISTRIBUTION_NAMES: &[(&str, &str)] = &[
+    ("ubuntu", "Ubuntu"),
+    ("debian", "Debian"),
+    ("linuxmint", "Linux Mint"),
+    ("pop", "Pop!_OS"),
+    ("kali", "Kali"),
+    ("rhel", "RedHat"),
+    ("centos", "CentOS"),
+    ("rocky", "Rocky"),
+    ("almalinux", "AlmaLinux"),
+    ("ol", "OracleLinux"),
+    ("fedora", "Fedora"),
+    ("amzn", "Amazon"),
+    ("arch", "Archlinux"),
+    ("manjaro", "ManjaroLinux"),
+    ("opensuse-leap", "openSUSE Leap"),
+    ("opensuse-tumbleweed", "openSUSE Tumbleweed"),
+    ("sles", "SLES"),
+    ("alpine", "Alpine"),
+    ("gentoo", "Gentoo"),
+];
+
+/// OS families for Ansible distribution names
+const OS_FAMILIES: &[(&str, &[&str])] = &[
+    (
+        "Debian",
+        &["Ubuntu", "Debian", "Linux Mint", "Pop!_OS", "Kali"],
+    ),
+    (
+        "RedHat",
+        &[
+            "RedHat",
+            "CentOS",
+            "Rocky",
+            "AlmaLinux",
+            "OracleLinux",
+            "Fedora",
+            "Amazon",
+        ],
+    ),
+    ("Archlinux", &["Archlinux", "ManjaroLinux"]),
+    ("Suse", &["openSUSE Leap", "openSUSE Tumbleweed", "SLES"]),
+    ("Alpine", &["Alpine"]),
+    ("Gentoo", &["Gentoo"]),
+];
+
+/// OS families for `/etc/os-release` `ID_LIKE` entries of unknown distributions
+const ID_LIKE_FAMILIES: &[(&str, &str)] = &[
+    ("debian", "Debian"),
+    ("ubuntu", "Debian"),
+    ("rhel", "RedHat"),
+    ("fedora", "RedHat"),
+    ("centos", "RedHat"),
+    ("suse", "Suse"),
+    ("arch", "Archlinux"),
+];
+
+/// Parse `/etc/os-release` content into normalized distribution facts.
+///
+/// Produces `ansible_distribution`, `ansible_distribution_version`,
+/// `ansible_distribution_major_version`, `ansible_distribution_release` and
+/// `ansible_os_family` using the names and family mappings Ansible uses,
+/// e.g. `ID=rocky` becomes distribution `Rocky` in the `RedHat` family.
+/// Facts whose source fields are missing are left out.
+pub fn distribution_facts(os_release: &str) -> IndexMap<String, JsonValue> {
+    let fields: IndexMap<&str, &str> = os_release
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim().trim_matches(['"', '\''])))
+        .collect();
+
+    let mut facts = IndexMap::new();
+    let Some(id) = fields.get("ID").map(|id| id.to_lowercase()) else {
+        return facts;
+    };
+
+    let distribution = DISTRIBUTION_NAMES
+        .iter()
+        .find(|(known, _)| *known == id)
+        .map(|(_, name)| (*name).to_string())
+        .or_else(|| fields.get("NAME").map(|name| (*name).to_string()))
+        .unwrap_or_else(|| id.clone());
+
+    let os_family = OS_FAMILIES
+        .iter()
+        .find(|(_, members)| members.contains(&distribution.as_str()))
+        .map(|(family, _)| *family)
+        .or_else(|| {
+            fields.get("ID_LIKE").and_then(|like| {
+                like.split_whitespace().find_map(|parent| {
+                    ID_LIKE_FAMILIES
+                        .iter()
+                        .find(|(known, _)| *known == parent)
+                        .map(|(_, family)| *family)
+                })
+            })
+        })
+        .map(String::from)
+        .unwrap_or_else(|| distribution.clone());
+
+    facts.insert(
+        "ansible_distribution".to_string(),
+        JsonValue::from(distribution),
+    );
+    if let Some(version) = fields.get("VERSION_ID") {
+        let major = version.split('.').next().unwrap_or(version);
+        facts.insert(
+            "ansible_distribution_version".to_string(),
+            JsonValue::from(*version),
+        );
+        facts.insert(
+            "ansible_distribution_major_version".to_string(),
+            JsonValue::from(major),
+        );
+    }
+    // Older Ubuntu releases only carry the codename as UBUNTU_CODENAME
+    if let Some(codename) = fields
+        .get("VERSION_CODENAME")
+        .or_else(|| fields.get("UBUNTU_CODENAME"))
+    {
+        facts.insert(
+            "ansible_distribution_release".to_string(),
+            JsonValue::from(*codename),
+        );
+    }
+    facts.insert("ansible_os_family".to_string(), JsonValue::from(os_family));
+
+    facts
+}
+
+/// Shell commands reporting a host's OS facts, in the order [`os_facts`]
+/// reads their output
+pub const OS_FACT_COMMANDS: [&str; 5] = [
+    "hostname -f 2>/dev/null || hostname",
+    "uname -s",
+    "uname -r",
+    "uname -m",
+    "cat /etc/os-release 2>/dev/null || true",
+];
+
+/// Commands reporting the connecting user, in the order [`user_facts`]
+/// reads their output
+pub const USER_FACT_COMMANDS: [&str; 3] = ["id -un", "id -u", "id -g"];
+
+/// Build OS facts from the trimmed output of [`OS_FACT_COMMANDS`].
+///
+/// Fact names carry no `ansible_` prefix. A command whose output is empty
+/// (e.g. because it failed) leaves its facts out.
+pub fn os_facts(outputs: &[String]) -> IndexMap<String, JsonValue> {
+    let output = |index: usize| {
+        outputs
+            .get(index)
+            .map(String::as_str)
+            .filter(|out| !out.is_empty())
+    };
+    let mut facts = IndexMap::new();
+
+    if let Some(fqdn) = output(0) {
+        let short = fqdn.split('.').next().unwrap_or(fqdn);
+        facts.insert("hostname".to_string(), JsonValue::from(short));
+        facts.insert("hostname_short".to_string(), JsonValue::from(short));
+        facts.insert("fqdn".to_string(), JsonValue::from(fqdn));
+    }
+    if let Some(system) = output(1) {
+        facts.insert("system".to_string(), JsonValue::from(system));
+    }
+    if let Some(kernel) = output(2) {
+        facts.insert("kernel".to_string(), JsonValue::from(kernel));
+    }
+    if let Some(arch) = output(3) {
+        facts.insert("architecture".to_string(), JsonValue::from(arch));
+        facts.insert("machine".to_string(), JsonValue::from(machine_name(arch)));
+    }
+
+    if let Some(os_release) = output(4) {
+        for (key, value) in distribution_facts(os_release) {
+            let key = key.strip_prefix("ansible_").unwrap_or(&key).to_string();
+            facts.insert(key, value);
+        }
+        for line in os_release.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let fact = match key {
+                "PRETTY_NAME" => "distribution_pretty_name",
+                "VERSION_CODENAME" => "distribution_codename",
+                _ => continue,
+            };
+            facts.insert(fact.to_string(), JsonValue::from(value.trim_matches('"')));
+        }
+    }
+
+    facts
+}
+
+/// Build user facts from the trimmed output of [`USER_FACT_COMMANDS`].
+///
+/// Like [`os_facts`], names carry no `ansible_` prefix and empty output
+/// leaves a fact out.
+pub fn user_facts(outputs: &[String]) -> IndexMap<String, JsonValue> {
+    let output = |index: usize| {
+        outputs
+            .get(index)
+            .map(String::as_str)
+            .filter(|out| !out.is_empty())
+    };
+    let mut facts = IndexMap::new();
+
+    if let Some(user) = output(0) {
+        facts.insert("user_id".to_string(), JsonValue::from(user));
+    }
+    for (index, fact) in [(1, "user_uid"), (2, "user_gid")] {
+        if let Some(id) = output(index).and_then(|id| id.parse::<u32>().ok()) {
+            facts.insert(fact.to_string(), JsonValue::from(id));
+        }
+    }
+
+    facts
+}
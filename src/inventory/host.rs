@@ -22,6 +22,8 @@ pub enum ConnectionType {
     Podman,
     /// WinRM connection for Windows hosts
     Winrm,
+    /// Kubernetes pod exec connection
+    Kubectl,
 }
 
 impl std::fmt::Display for ConnectionType {
@@ -32,6 +34,7 @@ impl std::fmt::Display for ConnectionType {
             ConnectionType::Docker => write!(f, "docker"),
             ConnectionType::Podman => write!(f, "podman"),
             ConnectionType::Winrm => write!(f, "winrm"),
+            ConnectionType::Kubectl => write!(f, "kubectl"),
         }
     }
 }
@@ -332,6 +335,7 @@ impl Host {
                             "docker" => ConnectionType::Docker,
                             "podman" => ConnectionType::Podman,
                             "winrm" => ConnectionType::Winrm,
+                            "kubectl" | "kubernetes.core.kubectl" => ConnectionType::Kubectl,
                             _ => {
                                 return Err(HostParseError::InvalidConnectionType(
                                     value.to_string(),
@@ -348,11 +352,9 @@ impl Host {
                         host.connection.python_interpreter = Some(value.to_string())
                     }
                     _ => {
-                        // Store as generic variable
-                        host.vars.insert(
-                            key.to_string(),
-                            serde_yaml::Value::String(value.to_string()),
-                        );
+                        // Store as generic variable, typed like other INI values
+                        host.vars
+                            .insert(key.to_string(), super::parse_ini_value(value));
                     }
                 }
             }
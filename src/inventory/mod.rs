@@ -100,18 +100,22 @@ pub mod cache;
 pub mod constructed;
 pub mod group;
 pub mod host;
+pub mod pattern_cache;
 pub mod plugin;
 pub mod plugins;
+pub mod validation;
 
 pub use group::{Group, GroupBuilder, GroupHierarchy};
 pub use host::{ConnectionParams, ConnectionType, Host, HostParseError, SshParams};
+pub use pattern_cache::PatternCache;
 pub use plugin::{
     inventory_to_json, parse_json_inventory, parse_json_inventory_from_value,
     AwsEc2InventoryPlugin, CacheStats, CachedInventoryPlugin, FileInventoryPlugin, InventoryCache,
     InventoryPlugin, InventoryPluginConfig, InventoryPluginFactory, InventoryPluginRegistry,
     KeyedGroup, PluginError, PluginErrorKind, PluginInfo, PluginOptionInfo, PluginResult,
-    PluginType, ScriptInventoryPlugin,
+    PluginType, ScriptInventoryPlugin, SourcesInventoryPlugin,
 };
+pub use validation::InventoryIssue;
 
 // Re-export enhanced cache types
 pub use cache::{
@@ -136,7 +140,7 @@ pub use plugins::{
 use indexmap::IndexMap;
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use thiserror::Error;
 
@@ -183,6 +187,54 @@ pub enum InventoryError {
 /// Result type for inventory operations
 pub type InventoryResult<T> = Result<T, InventoryError>;
 
+/// One inventory source as given on the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InventorySource {
+    /// An inventory file or directory
+    Path(PathBuf),
+    /// An ad-hoc host list such as `host1,host2,`
+    Hosts(Vec<String>),
+}
+
+impl InventorySource {
+    /// Parse a single `-i` value
+    ///
+    /// A value with a trailing comma is an ad-hoc host list. Otherwise an
+    /// existing path is used as-is and any other comma-separated value is
+    /// split into one path per entry.
+    pub fn parse(value: &str) -> Vec<Self> {
+        let value = value.trim();
+        if let Some(list) = value.strip_suffix(',') {
+            return vec![InventorySource::Hosts(
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|h| !h.is_empty())
+                    .map(String::from)
+                    .collect(),
+            )];
+        }
+
+        if value.contains(',') && !Path::new(value).exists() {
+            return value
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(|p| InventorySource::Path(PathBuf::from(p)))
+                .collect();
+        }
+
+        vec![InventorySource::Path(PathBuf::from(value))]
+    }
+
+    /// Parse several `-i` values, keeping their order
+    pub fn parse_all<S: AsRef<str>>(values: &[S]) -> Vec<Self> {
+        values
+            .iter()
+            .flat_map(|v| Self::parse(v.as_ref()))
+            .collect()
+    }
+}
+
 /// The main inventory structure holding all hosts and groups
 #[derive(Debug, Clone)]
 pub struct Inventory {
@@ -192,8 +244,14 @@ pub struct Inventory {
     /// All groups indexed by name
     groups: HashMap<String, Group>,
 
-    /// Source file/directory path
-    source: Option<String>,
+    /// Sources this inventory was loaded from, in `-i` form
+    sources: Vec<String>,
+
+    /// Resolved host patterns, cleared whenever hosts or groups change
+    pattern_cache: PatternCache,
+
+    /// Host variables redefined with a different value while parsing
+    conflicts: Vec<InventoryIssue>,
 }
 
 impl Default for Inventory {
@@ -208,7 +266,9 @@ impl Inventory {
         let mut inventory = Self {
             hosts: HashMap::new(),
             groups: HashMap::new(),
-            source: None,
+            sources: Vec::new(),
+            pattern_cache: PatternCache::new(),
+            conflicts: Vec::new(),
         };
 
         // Create default groups
@@ -224,7 +284,7 @@ impl Inventory {
     pub fn load<P: AsRef<Path>>(path: P) -> InventoryResult<Self> {
         let path = path.as_ref();
         let mut inventory = Self::new();
-        inventory.source = Some(path.display().to_string());
+        inventory.sources = vec![path.display().to_string()];
 
         if path.is_file() {
             inventory.load_file(path)?;
@@ -243,6 +303,97 @@ impl Inventory {
         Ok(inventory)
     }
 
+    /// Load and merge several inventory sources
+    ///
+    /// Sources are merged in order, so later sources override host and group
+    /// variables from earlier ones. See [`InventorySource::parse`] for the
+    /// accepted forms.
+    pub fn load_sources<S: AsRef<str>>(sources: &[S]) -> InventoryResult<Self> {
+        let mut inventory = Self::new();
+        for source in InventorySource::parse_all(sources) {
+            let loaded = match source {
+                InventorySource::Path(path) => Self::load(path)?,
+                InventorySource::Hosts(hosts) => Self::from_host_list(&hosts)?,
+            };
+            inventory.merge(loaded);
+        }
+        Ok(inventory)
+    }
+
+    /// Build an inventory of ungrouped hosts from an ad-hoc host list
+    pub fn from_host_list<S: AsRef<str>>(hosts: &[S]) -> InventoryResult<Self> {
+        let mut inventory = Self::new();
+        inventory.sources = vec![hosts
+            .iter()
+            .map(|h| format!("{},", h.as_ref()))
+            .collect::<String>()];
+        for host in hosts {
+            inventory.add_host(Host::parse(host.as_ref())?)?;
+        }
+        Ok(inventory)
+    }
+
+    /// Merge another inventory into this one
+    ///
+    /// Hosts and groups are unioned. Variables from `other` override those
+    /// already present, as do its connection settings when they are set.
+    pub fn merge(&mut self, other: Inventory) {
+        let default_connection = ConnectionParams::default();
+
+        for (name, host) in other.hosts {
+            match self.hosts.get_mut(&name) {
+                Some(existing) => {
+                    existing.merge_vars(&host.vars);
+                    if host.ansible_host.is_some() {
+                        existing.ansible_host = host.ansible_host;
+                    }
+                    if host.connection != default_connection {
+                        existing.connection = host.connection;
+                    }
+                    existing.groups.extend(host.groups);
+                    existing.enabled = host.enabled;
+                }
+                None => {
+                    self.hosts.insert(name, host);
+                }
+            }
+        }
+
+        for (name, group) in other.groups {
+            match self.groups.get_mut(&name) {
+                Some(existing) => {
+                    existing.hosts.extend(group.hosts);
+                    existing.children.extend(group.children);
+                    existing.merge_vars(&group.vars);
+                    if group.priority != 0 {
+                        existing.priority = group.priority;
+                    }
+                }
+                None => {
+                    self.groups.insert(name, group);
+                }
+            }
+        }
+
+        // A host ungrouped in one source may belong to a group in another
+        let grouped: HashSet<String> = self
+            .groups
+            .values()
+            .filter(|g| g.name != "all" && g.name != "ungrouped")
+            .flat_map(|g| g.hosts.iter().cloned())
+            .collect();
+        if let Some(ungrouped) = self.groups.get_mut("ungrouped") {
+            ungrouped.hosts.retain(|h| !grouped.contains(h));
+        }
+
+        self.conflicts.extend(other.conflicts);
+
+        self.sources.extend(other.sources);
+
+        self.compute_group_parents();
+        self.pattern_cache.clear();
+    }
+
     /// Load a single inventory file
     fn load_file(&mut self, path: &Path) -> InventoryResult<()> {
         // Check if it's an executable (dynamic inventory)
@@ -345,8 +496,7 @@ impl Inventory {
                     .unwrap_or("")
                     .to_string();
 
-                let content = std::fs::read_to_string(&file_path)?;
-                let vars: IndexMap<String, serde_yaml::Value> = serde_yaml::from_str(&content)?;
+                let vars = Self::read_vars_file(&file_path)?;
 
                 if let Some(group) = self.groups.get_mut(&group_name) {
                     group.merge_vars(&vars);
@@ -387,8 +537,7 @@ impl Inventory {
                     .unwrap_or("")
                     .to_string();
 
-                let content = std::fs::read_to_string(&file_path)?;
-                let vars: IndexMap<String, serde_yaml::Value> = serde_yaml::from_str(&content)?;
+                let vars = Self::read_vars_file(&file_path)?;
 
                 if let Some(host) = self.hosts.get_mut(&host_name) {
                     host.merge_vars(&vars);
@@ -424,10 +573,8 @@ impl Inventory {
             let file_path = entry.path();
             if file_path.is_file() {
                 let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
-                if ext == "yml" || ext == "yaml" {
-                    let content = std::fs::read_to_string(&file_path)?;
-                    let vars: IndexMap<String, serde_yaml::Value> = serde_yaml::from_str(&content)?;
-                    merged_vars.extend(vars);
+                if ext == "yml" || ext == "yaml" || ext == "json" {
+                    merged_vars.extend(Self::read_vars_file(&file_path)?);
                 }
             }
         }
@@ -435,6 +582,17 @@ impl Inventory {
         Ok(merged_vars)
     }
 
+    /// Read a single vars file, parsing `.json` files as JSON and anything
+    /// else as YAML
+    fn read_vars_file(path: &Path) -> InventoryResult<IndexMap<String, serde_yaml::Value>> {
+        let content = std::fs::read_to_string(path)?;
+        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            Ok(serde_json::from_str(&content)?)
+        } else {
+            Ok(serde_yaml::from_str(&content)?)
+        }
+    }
+
     /// Load dynamic inventory from an executable script
     fn load_dynamic(&mut self, path: &Path) -> InventoryResult<()> {
         let output = Command::new(path)
@@ -487,11 +645,32 @@ impl Inventory {
             if let Some(hosts) = map.get(&serde_yaml::Value::String("hosts".to_string())) {
                 if let serde_yaml::Value::Mapping(hosts_map) = hosts {
                     for (host_key, host_value) in hosts_map {
-                        if let serde_yaml::Value::String(host_name) = host_key {
+                        let serde_yaml::Value::String(host_pattern) = host_key else {
+                            continue;
+                        };
+                        for host_name in &expand_host_range(host_pattern)? {
                             // Check if host already exists
                             let host_exists = self.hosts.contains_key(host_name);
 
                             if host_exists {
+                                if let serde_yaml::Value::Mapping(host_vars) = host_value {
+                                    let vars: Vec<(String, serde_yaml::Value)> = host_vars
+                                        .iter()
+                                        .filter_map(|(k, v)| {
+                                            k.as_str().map(|k| (k.to_string(), v.clone()))
+                                        })
+                                        .collect();
+                                    let ansible_host = vars
+                                        .iter()
+                                        .find(|(k, _)| k == "ansible_host")
+                                        .and_then(|(_, v)| v.as_str());
+                                    self.record_conflicts(
+                                        host_name,
+                                        ansible_host,
+                                        vars.iter().map(|(k, v)| (k, v)),
+                                    );
+                                }
+
                                 // Host exists - just add it to this group and merge vars
                                 if let Some(existing_host) = self.hosts.get_mut(host_name) {
                                     existing_host.add_to_group(name.to_string());
@@ -618,6 +797,7 @@ impl Inventory {
                         "docker" => ConnectionType::Docker,
                         "podman" => ConnectionType::Podman,
                         "winrm" => ConnectionType::Winrm,
+                        "kubectl" | "kubernetes.core.kubectl" => ConnectionType::Kubectl,
                         _ => ConnectionType::Ssh,
                     };
                 }
@@ -802,6 +982,7 @@ impl Inventory {
                         "docker" => ConnectionType::Docker,
                         "podman" => ConnectionType::Podman,
                         "winrm" => ConnectionType::Winrm,
+                        "kubectl" | "kubernetes.core.kubectl" => ConnectionType::Kubectl,
                         _ => ConnectionType::Ssh,
                     };
                 }
@@ -891,31 +1072,35 @@ impl Inventory {
                     .entry(line.to_string())
                     .or_insert_with(|| Group::new(line));
             } else {
-                // Parse host definition
-                let host = Host::parse(line)?;
-                let host_name = host.name.clone();
+                // Parse host definition, expanding ranges like web[01:10]
+                let parsed = Host::parse(line)?;
+                for host_name in expand_host_range(&parsed.name)? {
+                    let mut host = parsed.clone();
+                    host.name = host_name.clone();
 
-                // Add to current group
-                if let Some(group) = self.groups.get_mut(&current_group) {
-                    group.add_host(host_name.clone());
-                }
+                    // Add to current group
+                    if let Some(group) = self.groups.get_mut(&current_group) {
+                        group.add_host(host_name.clone());
+                    }
 
-                // Add to all group
-                if current_group != "all" {
-                    if let Some(all_group) = self.groups.get_mut("all") {
-                        all_group.add_host(host_name.clone());
+                    // Add to all group
+                    if current_group != "all" {
+                        if let Some(all_group) = self.groups.get_mut("all") {
+                            all_group.add_host(host_name.clone());
+                        }
                     }
-                }
 
-                // Update or insert host
-                if let Some(existing) = self.hosts.get_mut(&host_name) {
-                    existing.add_to_group(current_group.clone());
-                    existing.merge_vars(&host.vars);
-                } else {
-                    let mut new_host = host;
-                    new_host.add_to_group(current_group.clone());
-                    new_host.add_to_group("all".to_string());
-                    self.hosts.insert(host_name, new_host);
+                    self.record_conflicts(&host_name, host.ansible_host.as_deref(), &host.vars);
+
+                    // Update or insert host
+                    if let Some(existing) = self.hosts.get_mut(&host_name) {
+                        existing.add_to_group(current_group.clone());
+                        existing.merge_vars(&host.vars);
+                    } else {
+                        host.add_to_group(current_group.clone());
+                        host.add_to_group("all".to_string());
+                        self.hosts.insert(host_name, host);
+                    }
                 }
             }
         }
@@ -957,6 +1142,7 @@ impl Inventory {
         }
 
         self.hosts.insert(name, host);
+        self.pattern_cache.clear();
         Ok(())
     }
 
@@ -965,6 +1151,7 @@ impl Inventory {
         let name = group.name.clone();
         self.groups.insert(name, group);
         self.compute_group_parents();
+        self.pattern_cache.clear();
         Ok(())
     }
 
@@ -975,6 +1162,7 @@ impl Inventory {
 
     /// Get a mutable reference to a host by name
     pub fn get_host_mut(&mut self, name: &str) -> Option<&mut Host> {
+        self.pattern_cache.clear();
         self.hosts.get_mut(name)
     }
 
@@ -985,9 +1173,22 @@ impl Inventory {
 
     /// Get a mutable reference to a group by name
     pub fn get_group_mut(&mut self, name: &str) -> Option<&mut Group> {
+        self.pattern_cache.clear();
         self.groups.get_mut(name)
     }
 
+    /// Cache of resolved host patterns
+    pub fn pattern_cache(&self) -> &PatternCache {
+        &self.pattern_cache
+    }
+
+    /// Sources the inventory was loaded from, empty when built in code
+    ///
+    /// Passing them to [`Inventory::load_sources`] reloads the inventory.
+    pub fn sources(&self) -> &[String] {
+        &self.sources
+    }
+
     /// Get all hosts
     pub fn hosts(&self) -> impl Iterator<Item = &Host> {
         self.hosts.values()
@@ -1024,9 +1225,22 @@ impl Inventory {
     /// - `group1:!group2` - exclusion
     /// - `~regex` - regex match on hostname
     /// - `*` - wildcard match
+    ///
+    /// Resolutions are cached per pattern until hosts or groups change.
     pub fn get_hosts_for_pattern(&self, pattern: &str) -> InventoryResult<Vec<&Host>> {
         let pattern = pattern.trim();
+        if let Some(names) = self.pattern_cache.get(pattern) {
+            return Ok(names.iter().filter_map(|n| self.hosts.get(n)).collect());
+        }
+
+        let hosts = self.resolve_pattern(pattern)?;
+        self.pattern_cache
+            .insert(pattern, hosts.iter().map(|h| h.name.clone()).collect());
+        Ok(hosts)
+    }
 
+    /// Resolve a host pattern without consulting the cache
+    fn resolve_pattern(&self, pattern: &str) -> InventoryResult<Vec<&Host>> {
         if pattern.is_empty() {
             return Ok(Vec::new());
         }
@@ -1269,6 +1483,83 @@ impl Inventory {
     }
 }
 
+/// Expand host range patterns such as `web[01:10].example.com` or `db[a:f:2]`.
+///
+/// Each `[start:end]` or `[start:end:step]` segment is replaced by every value
+/// in the (inclusive) range. Numeric ranges whose start has a leading zero keep
+/// that width, so `web[01:03]` yields `web01`, `web02`, `web03`; alphabetic
+/// ranges take single letters. Names without brackets are returned unchanged.
+pub fn expand_host_range(name: &str) -> InventoryResult<Vec<String>> {
+    let Some(open) = name.find('[') else {
+        return Ok(vec![name.to_string()]);
+    };
+    let close = name[open..]
+        .find(']')
+        .map(|i| open + i)
+        .ok_or_else(|| InventoryError::InvalidPattern(format!("unclosed host range: {}", name)))?;
+    let (head, range, tail) = (&name[..open], &name[open + 1..close], &name[close + 1..]);
+
+    let invalid = |reason: &str| InventoryError::InvalidPattern(format!("{}: {}", reason, name));
+    let parts: Vec<&str> = range.split(':').collect();
+    let (begin, end, step) = match parts[..] {
+        [begin, end] => (begin, end, 1),
+        [begin, end, step] => (
+            begin,
+            end,
+            step.parse::<usize>()
+                .ok()
+                .filter(|s| *s > 0)
+                .ok_or_else(|| invalid("invalid host range step"))?,
+        ),
+        _ => return Err(invalid("invalid host range")),
+    };
+    let begin = if begin.is_empty() { "0" } else { begin };
+    if end.is_empty() {
+        return Err(invalid("host range must specify an end"));
+    }
+
+    let values: Vec<String> =
+        if let (Ok(first), Ok(last)) = (begin.parse::<u64>(), end.parse::<u64>()) {
+            let width = if begin.len() > 1 && begin.starts_with('0') {
+                if begin.len() != end.len() {
+                    return Err(invalid(
+                        "host range must specify equal-length begin and end formats",
+                    ));
+                }
+                begin.len()
+            } else {
+                0
+            };
+            (first..=last)
+                .step_by(step)
+                .map(|i| format!("{:0width$}", i, width = width))
+                .collect()
+        } else {
+            let mut letters = begin.chars().zip(end.chars());
+            match (letters.next(), begin.len(), end.len()) {
+                (Some((first, last)), 1, 1)
+                    if first.is_ascii_alphabetic() && last.is_ascii_alphabetic() =>
+                {
+                    (first..=last)
+                        .step_by(step)
+                        .filter(char::is_ascii_alphabetic)
+                        .map(String::from)
+                        .collect()
+                }
+                _ => return Err(invalid("invalid host range")),
+            }
+        };
+
+    // Later segments (e.g. `rack[1:2]-node[a:b]`) expand recursively
+    let mut hosts = Vec::new();
+    for value in values {
+        for rest in expand_host_range(tail)? {
+            hosts.push(format!("{}{}{}", head, value, rest));
+        }
+    }
+    Ok(hosts)
+}
+
 /// Split pattern by : but not inside brackets
 fn split_pattern(pattern: &str) -> Vec<&str> {
     let mut parts = Vec::new();
@@ -1316,8 +1607,9 @@ fn parse_ini_value(value: &str) -> serde_yaml::Value {
     let value = value.trim();
 
     // Handle quoted strings
-    if (value.starts_with('"') && value.ends_with('"'))
-        || (value.starts_with('\'') && value.ends_with('\''))
+    if value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')))
     {
         return serde_yaml::Value::String(value[1..value.len() - 1].to_string());
     }
@@ -1329,17 +1621,26 @@ fn parse_ini_value(value: &str) -> serde_yaml::Value {
         _ => {}
     }
 
-    // Handle numbers
-    if let Ok(n) = value.parse::<i64>() {
-        return serde_yaml::Value::Number(n.into());
-    }
-    if let Ok(n) = value.parse::<f64>() {
-        // Use From<i64> for the integer part, or convert to string for precision
-        if n.fract() == 0.0 && n >= i64::MIN as f64 && n <= i64::MAX as f64 {
-            return serde_yaml::Value::Number((n as i64).into());
+    // Handle numbers; only plain decimal notation counts, so "inf", "nan"
+    // and zero-padded identifiers such as "007" stay strings
+    let digits = value.trim_start_matches(['-', '+']);
+    let zero_padded = digits.len() > 1
+        && digits.starts_with('0')
+        && !digits.starts_with("0.")
+        && !digits.starts_with("0e")
+        && !digits.starts_with("0E");
+    let numeric = !digits.is_empty()
+        && digits.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && digits
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '-' | '+'));
+    if numeric && !zero_padded {
+        if let Ok(n) = value.parse::<i64>() {
+            return serde_yaml::Value::Number(n.into());
+        }
+        if let Ok(n) = value.parse::<f64>() {
+            return serde_yaml::Value::Number(n.into());
         }
-        // For floats, we need to use a different approach as serde_yaml may not support from_f64
-        return serde_yaml::Value::Number(serde_yaml::Number::from(n as i64));
     }
 
     // Default to string
@@ -1355,12 +1656,7 @@ fn json_to_yaml(value: &serde_json::Value) -> serde_yaml::Value {
             if let Some(i) = n.as_i64() {
                 serde_yaml::Value::Number(i.into())
             } else if let Some(f) = n.as_f64() {
-                // Convert float to integer if it has no fractional part
-                if f.fract() == 0.0 && f >= i64::MIN as f64 && f <= i64::MAX as f64 {
-                    serde_yaml::Value::Number((f as i64).into())
-                } else {
-                    serde_yaml::Value::Number((f as i64).into())
-                }
+                serde_yaml::Value::Number(f.into())
             } else {
                 serde_yaml::Value::Number(0.into())
             }
@@ -1459,6 +1755,55 @@ databases
         assert!(webservers.has_var("http_port"));
     }
 
+    #[test]
+    fn test_expand_host_range() {
+        assert_eq!(
+            expand_host_range("web[01:03]").unwrap(),
+            vec!["web01", "web02", "web03"]
+        );
+        assert_eq!(
+            expand_host_range("db[a:c]").unwrap(),
+            vec!["dba", "dbb", "dbc"]
+        );
+        assert_eq!(
+            expand_host_range("node[0:10:5].lan").unwrap(),
+            vec!["node0.lan", "node5.lan", "node10.lan"]
+        );
+        assert_eq!(
+            expand_host_range("r[1:2]-n[a:b]").unwrap(),
+            vec!["r1-na", "r1-nb", "r2-na", "r2-nb"]
+        );
+        assert_eq!(expand_host_range("plain").unwrap(), vec!["plain"]);
+
+        assert!(expand_host_range("web[01:100]").is_err());
+        assert!(expand_host_range("web[1:]").is_err());
+        assert!(expand_host_range("web[1:3:0]").is_err());
+        assert!(expand_host_range("web[1:3").is_err());
+        assert!(expand_host_range("web[aa:zz]").is_err());
+    }
+
+    #[test]
+    fn test_parse_ini_value_numbers() {
+        let mut inv = Inventory::new();
+        inv.parse_ini("web1 weight=1.5 count=3 id=007 ansible_host=10.0.0.1\n")
+            .unwrap();
+
+        let host = inv.get_host("web1").unwrap();
+        assert_eq!(host.get_var("weight"), Some(&serde_yaml::Value::from(1.5)));
+        assert_eq!(host.get_var("count"), Some(&serde_yaml::Value::from(3)));
+        assert_eq!(host.get_var("id"), Some(&serde_yaml::Value::from("007")));
+
+        assert_eq!(parse_ini_value("-0.25"), serde_yaml::Value::from(-0.25));
+        assert_eq!(parse_ini_value("2.0"), serde_yaml::Value::from(2.0));
+        assert_eq!(parse_ini_value("0"), serde_yaml::Value::from(0));
+        assert_eq!(parse_ini_value("0.5"), serde_yaml::Value::from(0.5));
+        assert_eq!(parse_ini_value("0755"), serde_yaml::Value::from("0755"));
+        assert_eq!(parse_ini_value("nan"), serde_yaml::Value::from("nan"));
+        assert_eq!(parse_ini_value("1.2.3"), serde_yaml::Value::from("1.2.3"));
+        assert_eq!(parse_ini_value("'"), serde_yaml::Value::from("'"));
+        assert_eq!(parse_ini_value("'a'"), serde_yaml::Value::from("a"));
+    }
+
     #[test]
     fn test_pattern_matching() {
         let mut inv = Inventory::new();
@@ -1505,4 +1850,76 @@ db1
         let webs = inv.get_hosts_for_pattern("~web\\d+").unwrap();
         assert_eq!(webs.len(), 2);
     }
+
+    #[test]
+    fn test_pattern_cache_invalidated_by_add_group() {
+        let mut inv = Inventory::new();
+        inv.add_host(Host::new("web1")).unwrap();
+        inv.add_host(Host::new("db1")).unwrap();
+
+        assert_eq!(inv.get_hosts_for_pattern("~web").unwrap().len(), 1);
+        assert_eq!(inv.get_hosts_for_pattern("~web").unwrap().len(), 1);
+        assert_eq!(inv.pattern_cache().hits(), 1);
+
+        inv.add_host(Host::new("web2")).unwrap();
+        assert!(inv.pattern_cache().is_empty());
+        assert_eq!(inv.get_hosts_for_pattern("~web").unwrap().len(), 2);
+
+        inv.add_group(GroupBuilder::new("web").host("db1").build())
+            .unwrap();
+        let names: Vec<_> = inv
+            .get_hosts_for_pattern("web")
+            .unwrap()
+            .iter()
+            .map(|h| h.name.clone())
+            .collect();
+        assert_eq!(names, vec!["db1"]);
+    }
+
+    #[test]
+    fn test_inventory_source_parse() {
+        assert_eq!(
+            InventorySource::parse("web1, web2,"),
+            vec![InventorySource::Hosts(vec![
+                "web1".to_string(),
+                "web2".to_string()
+            ])]
+        );
+        assert_eq!(
+            InventorySource::parse("localhost,"),
+            vec![InventorySource::Hosts(vec!["localhost".to_string()])]
+        );
+        assert_eq!(
+            InventorySource::parse("/nonexistent/a.yml,/nonexistent/b.yml"),
+            vec![
+                InventorySource::Path(PathBuf::from("/nonexistent/a.yml")),
+                InventorySource::Path(PathBuf::from("/nonexistent/b.yml")),
+            ]
+        );
+        assert_eq!(
+            InventorySource::parse("hosts.ini"),
+            vec![InventorySource::Path(PathBuf::from("hosts.ini"))]
+        );
+    }
+
+    #[test]
+    fn test_merge_moves_host_out_of_ungrouped() {
+        let mut inv = Inventory::from_host_list(&["web1", "db1"]).unwrap();
+
+        let mut other = Inventory::new();
+        let mut host = Host::new("web1");
+        host.add_to_group("webservers");
+        other.add_host(host).unwrap();
+        other
+            .add_group(GroupBuilder::new("webservers").host("web1").build())
+            .unwrap();
+
+        inv.merge(other);
+
+        assert_eq!(inv.host_count(), 2);
+        assert!(inv.get_host("web1").unwrap().in_group("webservers"));
+        let ungrouped = inv.get_group("ungrouped").unwrap();
+        assert!(!ungrouped.has_host("web1"));
+        assert!(ungrouped.has_host("db1"));
+    }
 }
@@ -0,0 +1,74 @@
+//! Memoized host pattern resolution.
+//!
+//! Resolving a pattern like `webservers:&prod:!web3` walks group membership
+//! and may compile regexes. Playbooks tend to target the same few patterns
+//! in every play, so the resolved host names are kept per pattern string.
+//! The owner of the cache clears it whenever hosts or groups change.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Host names resolved per pattern, cleared when the inventory changes
+#[derive(Default)]
+pub struct PatternCache {
+    entries: Mutex<HashMap<String, Vec<String>>>,
+    hits: AtomicUsize,
+}
+
+impl PatternCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look up a previously resolved pattern
+    pub fn get(&self, pattern: &str) -> Option<Vec<String>> {
+        let hosts = self.entries.lock().get(pattern).cloned();
+        if hosts.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+        hosts
+    }
+
+    /// Remember the hosts a pattern resolved to
+    pub fn insert(&self, pattern: impl Into<String>, hosts: Vec<String>) {
+        self.entries.lock().insert(pattern.into(), hosts);
+    }
+
+    /// Forget every resolution, e.g. after hosts or groups changed
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+
+    /// Number of lookups answered from the cache
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of cached patterns
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    /// Whether nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().is_empty()
+    }
+}
+
+impl Clone for PatternCache {
+    /// A clone starts empty; its owner may diverge from the original
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for PatternCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PatternCache")
+            .field("patterns", &self.len())
+            .field("hits", &self.hits())
+            .finish()
+    }
+}
@@ -0,0 +1,197 @@
+//! Inventory consistency checks.
+//!
+//! Loading is lenient: dangling group references are kept as-is and a host
+//! defined twice simply ends up with the last value of each variable. The
+//! validation pass reports those problems, along with `children` cycles,
+//! which would otherwise only surface when a pattern walks the group tree.
+
+use super::{Inventory, InventoryError, InventoryResult};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::path::Path;
+
+/// A consistency problem found by [`Inventory::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InventoryIssue {
+    /// A host is a member of a group that is not defined
+    UndefinedGroup { host: String, group: String },
+    /// A group lists a child group that is not defined
+    UndefinedChild { group: String, child: String },
+    /// A group lists a host that is not defined
+    UndefinedHost { group: String, host: String },
+    /// Groups whose `children` lead back to the first one, in order
+    CircularChildren(Vec<String>),
+    /// A host is defined more than once with different values for a variable
+    ConflictingHostVar { host: String, var: String },
+}
+
+impl fmt::Display for InventoryIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UndefinedGroup { host, group } => {
+                write!(f, "host '{}' is in undefined group '{}'", host, group)
+            }
+            Self::UndefinedChild { group, child } => {
+                write!(f, "group '{}' has undefined child group '{}'", group, child)
+            }
+            Self::UndefinedHost { group, host } => {
+                write!(f, "group '{}' lists undefined host '{}'", group, host)
+            }
+            Self::CircularChildren(cycle) => {
+                write!(f, "circular group children: {}", cycle.join(" -> "))
+            }
+            Self::ConflictingHostVar { host, var } => write!(
+                f,
+                "host '{}' is defined more than once with different values for '{}'",
+                host, var
+            ),
+        }
+    }
+}
+
+impl Inventory {
+    /// Load an inventory and run the validation pass over it
+    ///
+    /// Circular `children` relationships fail the load with
+    /// [`InventoryError::CircularDependency`], since resolving patterns
+    /// against them never terminates. Every other issue is returned for the
+    /// caller to report.
+    pub fn load_validated<P: AsRef<Path>>(path: P) -> InventoryResult<(Self, Vec<InventoryIssue>)> {
+        let inventory = Self::load(path)?;
+        let issues = inventory.validate();
+        if let Some(InventoryIssue::CircularChildren(cycle)) = issues
+            .iter()
+            .find(|issue| matches!(issue, InventoryIssue::CircularChildren(_)))
+        {
+            return Err(InventoryError::CircularDependency(cycle.join(" -> ")));
+        }
+        Ok((inventory, issues))
+    }
+
+    /// Check the inventory for dangling references, `children` cycles and
+    /// hosts defined more than once with conflicting variables
+    ///
+    /// Issues are sorted so the report is stable between runs.
+    pub fn validate(&self) -> Vec<InventoryIssue> {
+        let mut issues = Vec::new();
+
+        for host in self.hosts.values() {
+            for group in &host.groups {
+                if !self.groups.contains_key(group) {
+                    issues.push(InventoryIssue::UndefinedGroup {
+                        host: host.name.clone(),
+                        group: group.clone(),
+                    });
+                }
+            }
+        }
+
+        for group in self.groups.values() {
+            for child in &group.children {
+                if !self.groups.contains_key(child) {
+                    issues.push(InventoryIssue::UndefinedChild {
+                        group: group.name.clone(),
+                        child: child.clone(),
+                    });
+                }
+            }
+            for host in &group.hosts {
+                if !self.hosts.contains_key(host) {
+                    issues.push(InventoryIssue::UndefinedHost {
+                        group: group.name.clone(),
+                        host: host.clone(),
+                    });
+                }
+            }
+        }
+
+        issues.sort_by_key(|issue| issue.to_string());
+        issues.extend(self.children_cycles());
+        issues.extend(self.conflicts.iter().cloned());
+        issues
+    }
+
+    /// Find every cycle in the group `children` graph, each reported once
+    fn children_cycles(&self) -> Vec<InventoryIssue> {
+        let mut names: Vec<&String> = self.groups.keys().collect();
+        names.sort();
+
+        let mut done = HashSet::new();
+        let mut seen_cycles = HashSet::new();
+        let mut cycles = Vec::new();
+        for name in names {
+            let mut path = Vec::new();
+            self.walk_children(name, &mut path, &mut done, &mut |cycle| {
+                let members: BTreeSet<String> = cycle.iter().cloned().collect();
+                if seen_cycles.insert(members) {
+                    cycles.push(InventoryIssue::CircularChildren(cycle));
+                }
+            });
+        }
+        cycles
+    }
+
+    /// Depth-first walk reporting a cycle whenever a group on `path` recurs
+    fn walk_children(
+        &self,
+        name: &str,
+        path: &mut Vec<String>,
+        done: &mut HashSet<String>,
+        report: &mut dyn FnMut(Vec<String>),
+    ) {
+        if let Some(start) = path.iter().position(|group| group == name) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(name.to_string());
+            report(cycle);
+            return;
+        }
+        if done.contains(name) {
+            return;
+        }
+
+        path.push(name.to_string());
+        if let Some(group) = self.groups.get(name) {
+            let mut children: Vec<&String> = group.children.iter().collect();
+            children.sort();
+            for child in children {
+                self.walk_children(child, path, done, report);
+            }
+        }
+        path.pop();
+        done.insert(name.to_string());
+    }
+
+    /// Remember variables a repeated host definition sets to a new value
+    pub(super) fn record_conflicts<'a>(
+        &mut self,
+        host: &str,
+        ansible_host: Option<&str>,
+        vars: impl IntoIterator<Item = (&'a String, &'a serde_yaml::Value)>,
+    ) {
+        let Some(existing) = self.hosts.get(host) else {
+            return;
+        };
+
+        let mut conflicting = Vec::new();
+        if let (Some(old), Some(new)) = (existing.ansible_host.as_deref(), ansible_host) {
+            if old != new {
+                conflicting.push("ansible_host".to_string());
+            }
+        }
+        conflicting.extend(
+            vars.into_iter()
+                .filter(|(key, value)| existing.vars.get(*key).is_some_and(|old| old != *value))
+                .map(|(key, _)| key.clone()),
+        );
+
+        for var in conflicting {
+            let issue = InventoryIssue::ConflictingHostVar {
+                host: host.to_string(),
+                var,
+            };
+            if !self.conflicts.contains(&issue) {
+                self.conflicts.push(issue);
+            }
+        }
+    }
+}
@@ -132,8 +132,8 @@ pub mod prelude {
     pub use crate::connection::ssh::{SshConnection, SshConnectionBuilder};
     pub use crate::connection::{
         CommandResult, Connection, ConnectionBuilder, ConnectionConfig, ConnectionError,
-        ConnectionFactory, ConnectionResult, ConnectionType, ExecuteOptions, FileStat, HostConfig,
-        TransferOptions,
+        ConnectionFactory, ConnectionIdentity, ConnectionResult, ConnectionType, ExecuteOptions,
+        FileStat, HostConfig, TransferOptions,
     };
 
     // Error handling
@@ -390,6 +390,10 @@ pub mod modules;
 /// tests, and custom extensions.
 pub mod template;
 
+/// Lookup plugins (`file`, `env`, `password`, `pipe`, `url`) for reading
+/// external data from templates via `lookup()`.
+pub mod lookup;
+
 // ============================================================================
 // Vault (Encrypted secrets management)
 // ============================================================================
@@ -449,6 +453,16 @@ pub mod callback;
 /// Step-by-step Execution, Breakpoint Support, and State Dump.
 pub mod diagnostics;
 
+// ============================================================================
+// Linting
+// ============================================================================
+
+/// Playbook linting and validation.
+///
+/// Reports syntax problems, module parameter mistakes, and semantic issues
+/// such as unnamed tasks or literal secrets, each with a rule id and location.
+pub mod lint;
+
 // ============================================================================
 // Metrics and Observability
 // ============================================================================
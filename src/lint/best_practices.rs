@@ -6,17 +6,37 @@
 //! - Variable usage
 //! - Idempotency concerns
 
+use super::source::SourceIndex;
 use super::types::{LintConfig, LintIssue, LintResult, Location, RuleCategory, Severity};
 use regex::Regex;
-use std::collections::HashSet;
 use std::path::Path;
 
+/// Modules that have been renamed or removed, with their replacements.
+const DEPRECATED_MODULES: &[(&str, &str)] = &[
+    ("include", "include_tasks or import_tasks"),
+    ("docker", "docker_container"),
+    ("easy_install", "pip"),
+    ("ec2", "ec2_instance"),
+    ("s3", "aws_s3"),
+    ("win_msi", "win_package"),
+];
+
+/// Modules that must run as a service account when escalating privileges.
+const BECOME_USER_MODULES: &[&str] = &[
+    "postgresql_db",
+    "postgresql_user",
+    "postgresql_query",
+    "postgresql_privs",
+];
+
 /// Best practices checker.
 pub struct BestPracticesChecker {
     /// Regex for valid task names.
     valid_name_pattern: Regex,
     /// Regex for detecting Jinja2 templates.
     jinja_pattern: Regex,
+    /// Regex for variable and parameter names that hold secrets.
+    secret_key_pattern: Regex,
 }
 
 impl Default for BestPracticesChecker {
@@ -31,6 +51,10 @@ impl BestPracticesChecker {
         Self {
             valid_name_pattern: Regex::new(r"^[A-Z][a-zA-Z0-9\s\-_:]+$").unwrap(),
             jinja_pattern: Regex::new(r"\{\{.*?\}\}|\{%.*?%\}").unwrap(),
+            secret_key_pattern: Regex::new(
+                r"(?i)(^|_)(password|passwd|secret|token|api_?key|private_?key)$",
+            )
+            .unwrap(),
         }
     }
 
@@ -40,13 +64,25 @@ impl BestPracticesChecker {
         value: &serde_yaml::Value,
         path: &Path,
         config: &LintConfig,
+    ) -> LintResult {
+        self.check_playbook_source(value, &SourceIndex::default(), path, config)
+    }
+
+    /// Check best practices in a parsed playbook, attaching line numbers
+    /// from the source index to each finding.
+    pub fn check_playbook_source(
+        &self,
+        value: &serde_yaml::Value,
+        source: &SourceIndex,
+        path: &Path,
+        config: &LintConfig,
     ) -> LintResult {
         let mut result = LintResult::new();
         result.files_analyzed.push(path.to_path_buf());
 
         if let Some(plays) = value.as_sequence() {
             for (play_idx, play) in plays.iter().enumerate() {
-                self.check_play(play, play_idx, path, config, &mut result);
+                self.check_play(play, play_idx, source, path, config, &mut result);
             }
         }
 
@@ -58,6 +94,7 @@ impl BestPracticesChecker {
         &self,
         play: &serde_yaml::Value,
         play_idx: usize,
+        source: &SourceIndex,
         path: &Path,
         config: &LintConfig,
         result: &mut LintResult,
@@ -72,6 +109,7 @@ impl BestPracticesChecker {
             .and_then(|v| v.as_str());
 
         result.plays_analyzed += 1;
+        let first_issue = result.issues.len();
 
         // Check play name
         self.check_play_name(play_name, play_idx, path, config, result);
@@ -79,12 +117,29 @@ impl BestPracticesChecker {
         // Check for gather_facts with no tasks
         self.check_gather_facts_usage(play_map, play_idx, play_name, path, config, result);
 
+        // Check for secrets in play variables
+        if let Some(vars) = play_map.get(&serde_yaml::Value::String("vars".to_string())) {
+            self.check_literal_secrets(
+                vars,
+                Location::file(path).with_play(play_idx, play_name.map(String::from)),
+                config,
+                result,
+            );
+        }
+
+        if let Some(line) = source.play_line(play_idx) {
+            stamp_line(&mut result.issues[first_issue..], line);
+        }
+
         // Check tasks
         for task_key in &["tasks", "pre_tasks", "post_tasks", "handlers"] {
             if let Some(tasks) = play_map.get(&serde_yaml::Value::String(task_key.to_string())) {
                 if let Some(task_list) = tasks.as_sequence() {
                     for (task_idx, task) in task_list.iter().enumerate() {
-                        self.check_task(task, task_idx, play_idx, play_name, path, config, result);
+                        let key = format!("{}/{}/{}", play_idx, task_key, task_idx);
+                        self.check_task(
+                            task, task_idx, play_idx, play_map, &key, source, path, config, result,
+                        );
                     }
                 }
             }
@@ -103,26 +158,34 @@ impl BestPracticesChecker {
         match name {
             None => {
                 if config.should_run_rule("B001", RuleCategory::BestPractices, Severity::Warning) {
-                    result.add_issue(LintIssue::new(
-                        "B001",
-                        "unnamed-play",
-                        Severity::Warning,
-                        RuleCategory::BestPractices,
-                        "Play does not have a name",
-                        Location::file(path).with_play(play_idx, None),
-                    ).with_suggestion("Add a descriptive 'name' to the play for better readability"));
+                    result.add_issue(
+                        LintIssue::new(
+                            "B001",
+                            "unnamed-play",
+                            Severity::Warning,
+                            RuleCategory::BestPractices,
+                            "Play does not have a name",
+                            Location::file(path).with_play(play_idx, None),
+                        )
+                        .with_suggestion(
+                            "Add a descriptive 'name' to the play for better readability",
+                        ),
+                    );
                 }
             }
             Some(name) if name.trim().is_empty() => {
                 if config.should_run_rule("B002", RuleCategory::BestPractices, Severity::Warning) {
-                    result.add_issue(LintIssue::new(
-                        "B002",
-                        "empty-play-name",
-                        Severity::Warning,
-                        RuleCategory::BestPractices,
-                        "Play has an empty name",
-                        Location::file(path).with_play(play_idx, None),
-                    ).with_suggestion("Provide a meaningful name for the play"));
+                    result.add_issue(
+                        LintIssue::new(
+                            "B002",
+                            "empty-play-name",
+                            Severity::Warning,
+                            RuleCategory::BestPractices,
+                            "Play has an empty name",
+                            Location::file(path).with_play(play_idx, None),
+                        )
+                        .with_suggestion("Provide a meaningful name for the play"),
+                    );
                 }
             }
             _ => {}
@@ -162,25 +225,33 @@ impl BestPracticesChecker {
 
         if gather_facts && task_count == 0 {
             if config.should_run_rule("B003", RuleCategory::BestPractices, Severity::Hint) {
-                result.add_issue(LintIssue::new(
-                    "B003",
-                    "gather-facts-no-tasks",
-                    Severity::Hint,
-                    RuleCategory::BestPractices,
-                    "Play gathers facts but has no tasks",
-                    Location::file(path).with_play(play_idx, play_name.map(String::from)),
-                ).with_suggestion("Consider setting 'gather_facts: false' or adding tasks"));
+                result.add_issue(
+                    LintIssue::new(
+                        "B003",
+                        "gather-facts-no-tasks",
+                        Severity::Hint,
+                        RuleCategory::BestPractices,
+                        "Play gathers facts but has no tasks",
+                        Location::file(path).with_play(play_idx, play_name.map(String::from)),
+                    )
+                    .with_suggestion("Consider setting 'gather_facts: false' or adding tasks"),
+                );
             }
         }
     }
 
     /// Check a single task.
+    ///
+    /// `key` identifies the task's position in the playbook (see [`SourceIndex`]).
+    #[allow(clippy::too_many_arguments)]
     fn check_task(
         &self,
         task: &serde_yaml::Value,
         task_idx: usize,
         play_idx: usize,
-        play_name: Option<&str>,
+        play: &serde_yaml::Mapping,
+        key: &str,
+        source: &SourceIndex,
         path: &Path,
         config: &LintConfig,
         result: &mut LintResult,
@@ -190,42 +261,94 @@ impl BestPracticesChecker {
             None => return,
         };
 
+        let play_name = play
+            .get(serde_yaml::Value::String("name".to_string()))
+            .and_then(|v| v.as_str());
         let task_name = task_map
-            .get(&serde_yaml::Value::String("name".to_string()))
+            .get(serde_yaml::Value::String("name".to_string()))
             .and_then(|v| v.as_str());
 
         result.tasks_analyzed += 1;
+        let first_issue = result.issues.len();
 
         // Check task name
-        self.check_task_name(task_name, task_idx, play_idx, play_name, path, config, result);
+        self.check_task_name(
+            task_name, task_idx, play_idx, play_name, path, config, result,
+        );
 
         // Check for command/shell usage
-        self.check_command_usage(task_map, task_idx, play_idx, play_name, task_name, path, config, result);
+        self.check_command_usage(
+            task_map, task_idx, play_idx, play_name, task_name, path, config, result,
+        );
 
         // Check for deprecated features
-        self.check_deprecated_features(task_map, task_idx, play_idx, play_name, task_name, path, config, result);
+        self.check_deprecated_features(
+            task_map, task_idx, play_idx, play_name, task_name, path, config, result,
+        );
 
         // Check for git with version
-        self.check_git_pinning(task_map, task_idx, play_idx, play_name, task_name, path, config, result);
+        self.check_git_pinning(
+            task_map, task_idx, play_idx, play_name, task_name, path, config, result,
+        );
 
         // Check for proper use of become
-        self.check_become_usage(task_map, task_idx, play_idx, play_name, task_name, path, config, result);
+        self.check_become_usage(
+            task_map, task_idx, play_idx, play_name, task_name, path, config, result,
+        );
 
         // Check for relative paths in certain modules
-        self.check_path_usage(task_map, task_idx, play_idx, play_name, task_name, path, config, result);
+        self.check_path_usage(
+            task_map, task_idx, play_idx, play_name, task_name, path, config, result,
+        );
 
         // Check for handlers without notify
-        self.check_handler_names(task_map, task_idx, play_idx, play_name, task_name, path, config, result);
+        self.check_handler_names(
+            task_map, task_idx, play_idx, play_name, task_name, path, config, result,
+        );
 
         // Check for retries without until
-        self.check_retry_usage(task_map, task_idx, play_idx, play_name, task_name, path, config, result);
+        self.check_retry_usage(
+            task_map, task_idx, play_idx, play_name, task_name, path, config, result,
+        );
+
+        // Check for escalation to root where a service account is needed
+        self.check_become_user_required(
+            task_map, play, task_idx, play_idx, play_name, task_name, path, config, result,
+        );
+
+        // Check for secrets written in plain text
+        for (task_key, value) in task_map {
+            let is_nested_block = task_key
+                .as_str()
+                .is_some_and(|k| matches!(k, "block" | "rescue" | "always"));
+            if !is_nested_block {
+                self.check_literal_secrets(
+                    value,
+                    Location::file(path)
+                        .with_play(play_idx, play_name.map(String::from))
+                        .with_task(task_idx, task_name.map(String::from)),
+                    config,
+                    result,
+                );
+            }
+        }
+
+        if let Some(line) = source.task_line(key) {
+            stamp_line(&mut result.issues[first_issue..], line);
+        }
 
         // Recursively check block tasks
         for block_key in &["block", "rescue", "always"] {
-            if let Some(block_tasks) = task_map.get(&serde_yaml::Value::String(block_key.to_string())) {
+            if let Some(block_tasks) =
+                task_map.get(&serde_yaml::Value::String(block_key.to_string()))
+            {
                 if let Some(block_list) = block_tasks.as_sequence() {
                     for (block_idx, block_task) in block_list.iter().enumerate() {
-                        self.check_task(block_task, block_idx, play_idx, play_name, path, config, result);
+                        let block_key = format!("{}/{}/{}", key, block_key, block_idx);
+                        self.check_task(
+                            block_task, block_idx, play_idx, play, &block_key, source, path,
+                            config, result,
+                        );
                     }
                 }
             }
@@ -246,44 +369,53 @@ impl BestPracticesChecker {
         match name {
             None => {
                 if config.should_run_rule("B004", RuleCategory::BestPractices, Severity::Warning) {
-                    result.add_issue(LintIssue::new(
-                        "B004",
-                        "unnamed-task",
-                        Severity::Warning,
-                        RuleCategory::BestPractices,
-                        "Task does not have a name",
-                        Location::file(path)
-                            .with_play(play_idx, play_name.map(String::from))
-                            .with_task(task_idx, None),
-                    ).with_suggestion("Add a descriptive 'name' to the task"));
+                    result.add_issue(
+                        LintIssue::new(
+                            "B004",
+                            "unnamed-task",
+                            Severity::Warning,
+                            RuleCategory::BestPractices,
+                            "Task does not have a name",
+                            Location::file(path)
+                                .with_play(play_idx, play_name.map(String::from))
+                                .with_task(task_idx, None),
+                        )
+                        .with_suggestion("Add a descriptive 'name' to the task"),
+                    );
                 }
             }
             Some(name) if name.trim().is_empty() => {
                 if config.should_run_rule("B005", RuleCategory::BestPractices, Severity::Warning) {
-                    result.add_issue(LintIssue::new(
-                        "B005",
-                        "empty-task-name",
-                        Severity::Warning,
-                        RuleCategory::BestPractices,
-                        "Task has an empty name",
-                        Location::file(path)
-                            .with_play(play_idx, play_name.map(String::from))
-                            .with_task(task_idx, None),
-                    ).with_suggestion("Provide a meaningful name for the task"));
+                    result.add_issue(
+                        LintIssue::new(
+                            "B005",
+                            "empty-task-name",
+                            Severity::Warning,
+                            RuleCategory::BestPractices,
+                            "Task has an empty name",
+                            Location::file(path)
+                                .with_play(play_idx, play_name.map(String::from))
+                                .with_task(task_idx, None),
+                        )
+                        .with_suggestion("Provide a meaningful name for the task"),
+                    );
                 }
             }
             Some(name) if !name.starts_with(|c: char| c.is_uppercase()) => {
                 if config.should_run_rule("B006", RuleCategory::BestPractices, Severity::Hint) {
-                    result.add_issue(LintIssue::new(
-                        "B006",
-                        "lowercase-task-name",
-                        Severity::Hint,
-                        RuleCategory::BestPractices,
-                        "Task name should start with an uppercase letter",
-                        Location::file(path)
-                            .with_play(play_idx, play_name.map(String::from))
-                            .with_task(task_idx, Some(name.to_string())),
-                    ).with_suggestion("Capitalize the first letter of the task name"));
+                    result.add_issue(
+                        LintIssue::new(
+                            "B006",
+                            "lowercase-task-name",
+                            Severity::Hint,
+                            RuleCategory::BestPractices,
+                            "Task name should start with an uppercase letter",
+                            Location::file(path)
+                                .with_play(play_idx, play_name.map(String::from))
+                                .with_task(task_idx, Some(name.to_string())),
+                        )
+                        .with_suggestion("Capitalize the first letter of the task name"),
+                    );
                 }
             }
             _ => {}
@@ -306,20 +438,25 @@ impl BestPracticesChecker {
         if let Some(shell_args) = task.get(&serde_yaml::Value::String("shell".to_string())) {
             let cmd = match shell_args {
                 serde_yaml::Value::String(s) => Some(s.as_str()),
-                serde_yaml::Value::Mapping(m) => {
-                    m.get(&serde_yaml::Value::String("cmd".to_string()))
-                        .and_then(|v| v.as_str())
-                }
+                serde_yaml::Value::Mapping(m) => m
+                    .get(&serde_yaml::Value::String("cmd".to_string()))
+                    .and_then(|v| v.as_str()),
                 _ => None,
             };
 
             if let Some(cmd) = cmd {
                 // Check for shell features
-                let shell_features = ['|', '>', '<', '&', ';', '$', '`', '(', ')', '{', '}', '*', '?', '[', ']'];
+                let shell_features = [
+                    '|', '>', '<', '&', ';', '$', '`', '(', ')', '{', '}', '*', '?', '[', ']',
+                ];
                 let uses_shell_features = cmd.chars().any(|c| shell_features.contains(&c));
 
                 if !uses_shell_features {
-                    if config.should_run_rule("B007", RuleCategory::BestPractices, Severity::Warning) {
+                    if config.should_run_rule(
+                        "B007",
+                        RuleCategory::BestPractices,
+                        Severity::Warning,
+                    ) {
                         result.add_issue(LintIssue::new(
                             "B007",
                             "use-command-instead",
@@ -345,20 +482,26 @@ impl BestPracticesChecker {
                     false
                 };
 
-                let has_changed_when = task.contains_key(&serde_yaml::Value::String("changed_when".to_string()));
+                let has_changed_when =
+                    task.contains_key(&serde_yaml::Value::String("changed_when".to_string()));
 
                 if !has_creates && !has_changed_when {
                     if config.should_run_rule("B008", RuleCategory::BestPractices, Severity::Hint) {
-                        result.add_issue(LintIssue::new(
-                            "B008",
-                            "command-not-idempotent",
-                            Severity::Hint,
-                            RuleCategory::BestPractices,
-                            format!("'{}' module used without idempotency guard", cmd_module),
-                            Location::file(path)
-                                .with_play(play_idx, play_name.map(String::from))
-                                .with_task(task_idx, task_name.map(String::from)),
-                        ).with_suggestion("Add 'creates', 'removes', or 'changed_when' for idempotency"));
+                        result.add_issue(
+                            LintIssue::new(
+                                "B008",
+                                "command-not-idempotent",
+                                Severity::Hint,
+                                RuleCategory::BestPractices,
+                                format!("'{}' module used without idempotency guard", cmd_module),
+                                Location::file(path)
+                                    .with_play(play_idx, play_name.map(String::from))
+                                    .with_task(task_idx, task_name.map(String::from)),
+                            )
+                            .with_suggestion(
+                                "Add 'creates', 'removes', or 'changed_when' for idempotency",
+                            ),
+                        );
                     }
                 }
             }
@@ -380,39 +523,69 @@ impl BestPracticesChecker {
         // Check for deprecated with_* loops
         let deprecated_loops = [
             ("with_items", "Use 'loop' instead of 'with_items'"),
-            ("with_nested", "Use 'loop' with 'product' filter instead of 'with_nested'"),
+            (
+                "with_nested",
+                "Use 'loop' with 'product' filter instead of 'with_nested'",
+            ),
         ];
 
         for (loop_key, suggestion) in deprecated_loops {
             if task.contains_key(&serde_yaml::Value::String(loop_key.to_string())) {
                 if config.should_run_rule("B009", RuleCategory::Deprecation, Severity::Hint) {
-                    result.add_issue(LintIssue::new(
-                        "B009",
-                        "deprecated-loop",
-                        Severity::Hint,
+                    result.add_issue(
+                        LintIssue::new(
+                            "B009",
+                            "deprecated-loop",
+                            Severity::Hint,
+                            RuleCategory::Deprecation,
+                            format!("'{}' is deprecated", loop_key),
+                            Location::file(path)
+                                .with_play(play_idx, play_name.map(String::from))
+                                .with_task(task_idx, task_name.map(String::from)),
+                        )
+                        .with_suggestion(suggestion),
+                    );
+                }
+            }
+        }
+
+        // Check for renamed or removed modules
+        for (module, replacement) in DEPRECATED_MODULES {
+            if task.contains_key(serde_yaml::Value::String(module.to_string()))
+                && config.should_run_rule("B017", RuleCategory::Deprecation, Severity::Warning)
+            {
+                result.add_issue(
+                    LintIssue::new(
+                        "B017",
+                        "deprecated-module",
+                        Severity::Warning,
                         RuleCategory::Deprecation,
-                        format!("'{}' is deprecated", loop_key),
+                        format!("Module '{}' is deprecated", module),
                         Location::file(path)
                             .with_play(play_idx, play_name.map(String::from))
                             .with_task(task_idx, task_name.map(String::from)),
-                    ).with_suggestion(suggestion));
-                }
+                    )
+                    .with_suggestion(format!("Use '{}' instead", replacement)),
+                );
             }
         }
 
         // Check for sudo instead of become
         if task.contains_key(&serde_yaml::Value::String("sudo".to_string())) {
             if config.should_run_rule("B010", RuleCategory::Deprecation, Severity::Warning) {
-                result.add_issue(LintIssue::new(
-                    "B010",
-                    "deprecated-sudo",
-                    Severity::Warning,
-                    RuleCategory::Deprecation,
-                    "'sudo' is deprecated, use 'become' instead",
-                    Location::file(path)
-                        .with_play(play_idx, play_name.map(String::from))
-                        .with_task(task_idx, task_name.map(String::from)),
-                ).with_suggestion("Replace 'sudo: yes' with 'become: yes'"));
+                result.add_issue(
+                    LintIssue::new(
+                        "B010",
+                        "deprecated-sudo",
+                        Severity::Warning,
+                        RuleCategory::Deprecation,
+                        "'sudo' is deprecated, use 'become' instead",
+                        Location::file(path)
+                            .with_play(play_idx, play_name.map(String::from))
+                            .with_task(task_idx, task_name.map(String::from)),
+                    )
+                    .with_suggestion("Replace 'sudo: yes' with 'become: yes'"),
+                );
             }
         }
     }
@@ -431,20 +604,28 @@ impl BestPracticesChecker {
     ) {
         if let Some(git_args) = task.get(&serde_yaml::Value::String("git".to_string())) {
             if let Some(args_map) = git_args.as_mapping() {
-                let has_version = args_map.contains_key(&serde_yaml::Value::String("version".to_string()));
+                let has_version =
+                    args_map.contains_key(&serde_yaml::Value::String("version".to_string()));
 
                 if !has_version {
-                    if config.should_run_rule("B011", RuleCategory::BestPractices, Severity::Warning) {
-                        result.add_issue(LintIssue::new(
-                            "B011",
-                            "git-no-version",
-                            Severity::Warning,
-                            RuleCategory::BestPractices,
-                            "git module used without specifying a version/tag/branch",
-                            Location::file(path)
-                                .with_play(play_idx, play_name.map(String::from))
-                                .with_task(task_idx, task_name.map(String::from)),
-                        ).with_suggestion("Specify 'version' to ensure reproducible builds"));
+                    if config.should_run_rule(
+                        "B011",
+                        RuleCategory::BestPractices,
+                        Severity::Warning,
+                    ) {
+                        result.add_issue(
+                            LintIssue::new(
+                                "B011",
+                                "git-no-version",
+                                Severity::Warning,
+                                RuleCategory::BestPractices,
+                                "git module used without specifying a version/tag/branch",
+                                Location::file(path)
+                                    .with_play(play_idx, play_name.map(String::from))
+                                    .with_task(task_idx, task_name.map(String::from)),
+                            )
+                            .with_suggestion("Specify 'version' to ensure reproducible builds"),
+                        );
                     }
                 }
             }
@@ -465,24 +646,139 @@ impl BestPracticesChecker {
     ) {
         // Check for become_user without become
         let has_become = task.contains_key(&serde_yaml::Value::String("become".to_string()));
-        let has_become_user = task.contains_key(&serde_yaml::Value::String("become_user".to_string()));
+        let has_become_user =
+            task.contains_key(&serde_yaml::Value::String("become_user".to_string()));
 
         if has_become_user && !has_become {
             if config.should_run_rule("B012", RuleCategory::BestPractices, Severity::Warning) {
-                result.add_issue(LintIssue::new(
-                    "B012",
-                    "become-user-without-become",
-                    Severity::Warning,
-                    RuleCategory::BestPractices,
-                    "'become_user' specified without 'become: yes'",
-                    Location::file(path)
-                        .with_play(play_idx, play_name.map(String::from))
-                        .with_task(task_idx, task_name.map(String::from)),
-                ).with_suggestion("Add 'become: yes' or remove 'become_user'"));
+                result.add_issue(
+                    LintIssue::new(
+                        "B012",
+                        "become-user-without-become",
+                        Severity::Warning,
+                        RuleCategory::BestPractices,
+                        "'become_user' specified without 'become: yes'",
+                        Location::file(path)
+                            .with_play(play_idx, play_name.map(String::from))
+                            .with_task(task_idx, task_name.map(String::from)),
+                    )
+                    .with_suggestion("Add 'become: yes' or remove 'become_user'"),
+                );
+            }
+        }
+    }
+
+    /// Check for `become` without `become_user` on modules that need a service account.
+    #[allow(clippy::too_many_arguments)]
+    fn check_become_user_required(
+        &self,
+        task: &serde_yaml::Mapping,
+        play: &serde_yaml::Mapping,
+        task_idx: usize,
+        play_idx: usize,
+        play_name: Option<&str>,
+        task_name: Option<&str>,
+        path: &Path,
+        config: &LintConfig,
+        result: &mut LintResult,
+    ) {
+        let become_key = serde_yaml::Value::String("become".to_string());
+        let become_user_key = serde_yaml::Value::String("become_user".to_string());
+
+        // Task-level settings override the play
+        let becomes = task
+            .get(&become_key)
+            .or_else(|| play.get(&become_key))
+            .is_some_and(is_truthy);
+        let has_become_user =
+            task.contains_key(&become_user_key) || play.contains_key(&become_user_key);
+        if !becomes || has_become_user {
+            return;
+        }
+
+        for module in BECOME_USER_MODULES {
+            if task.contains_key(serde_yaml::Value::String(module.to_string()))
+                && config.should_run_rule("B016", RuleCategory::BestPractices, Severity::Warning)
+            {
+                result.add_issue(
+                    LintIssue::new(
+                        "B016",
+                        "become-user-required",
+                        Severity::Warning,
+                        RuleCategory::BestPractices,
+                        format!(
+                            "'{}' with 'become' but no 'become_user' runs as root",
+                            module
+                        ),
+                        Location::file(path)
+                            .with_play(play_idx, play_name.map(String::from))
+                            .with_task(task_idx, task_name.map(String::from)),
+                    )
+                    .with_suggestion("Set 'become_user' to the service account (e.g. postgres)"),
+                );
             }
         }
     }
 
+    /// Check for secrets written as literal values instead of vault or variables.
+    fn check_literal_secrets(
+        &self,
+        value: &serde_yaml::Value,
+        location: Location,
+        config: &LintConfig,
+        result: &mut LintResult,
+    ) {
+        if !config.should_run_rule("S001", RuleCategory::Security, Severity::Error) {
+            return;
+        }
+
+        let mut keys = Vec::new();
+        self.find_literal_secrets(value, &mut keys);
+        for key in keys {
+            result.add_issue(
+                LintIssue::new(
+                    "S001",
+                    "literal-secret",
+                    Severity::Error,
+                    RuleCategory::Security,
+                    format!("'{}' is set to a literal secret", key),
+                    location.clone(),
+                )
+                .with_suggestion("Encrypt the value with 'rustible vault' or reference a variable"),
+            );
+        }
+    }
+
+    /// Collect keys whose values are plain-text secrets.
+    ///
+    /// Values tagged `!vault` or containing a template expression are allowed.
+    fn find_literal_secrets(&self, value: &serde_yaml::Value, keys: &mut Vec<String>) {
+        match value {
+            serde_yaml::Value::Mapping(map) => {
+                for (key, value) in map {
+                    let Some(key) = key.as_str() else { continue };
+                    match value {
+                        serde_yaml::Value::String(s)
+                            if self.secret_key_pattern.is_match(key)
+                                && !s.is_empty()
+                                && !self.jinja_pattern.is_match(s)
+                                && !s.starts_with("$ANSIBLE_VAULT") =>
+                        {
+                            keys.push(key.to_string());
+                        }
+                        _ => self.find_literal_secrets(value, keys),
+                    }
+                }
+            }
+            serde_yaml::Value::Sequence(items) => {
+                for item in items {
+                    self.find_literal_secrets(item, keys);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Check path usage in certain modules.
     fn check_path_usage(
         &self,
@@ -503,21 +799,44 @@ impl BestPracticesChecker {
                 if let Some(args_map) = args.as_mapping() {
                     // Check dest parameter
                     for dest_key in &["dest", "path"] {
-                        if let Some(dest) = args_map.get(&serde_yaml::Value::String(dest_key.to_string())) {
+                        if let Some(dest) =
+                            args_map.get(&serde_yaml::Value::String(dest_key.to_string()))
+                        {
                             if let Some(dest_str) = dest.as_str() {
                                 // Skip if it's a template variable
-                                if !self.jinja_pattern.is_match(dest_str) && !dest_str.starts_with('/') && !dest_str.starts_with('~') {
-                                    if config.should_run_rule("B013", RuleCategory::BestPractices, Severity::Hint) {
-                                        result.add_issue(LintIssue::new(
-                                            "B013",
-                                            "relative-path",
-                                            Severity::Hint,
-                                            RuleCategory::BestPractices,
-                                            format!("Relative path '{}' used for '{}'", dest_str, dest_key),
-                                            Location::file(path)
-                                                .with_play(play_idx, play_name.map(String::from))
-                                                .with_task(task_idx, task_name.map(String::from)),
-                                        ).with_suggestion("Consider using an absolute path for clarity"));
+                                if !self.jinja_pattern.is_match(dest_str)
+                                    && !dest_str.starts_with('/')
+                                    && !dest_str.starts_with('~')
+                                {
+                                    if config.should_run_rule(
+                                        "B013",
+                                        RuleCategory::BestPractices,
+                                        Severity::Hint,
+                                    ) {
+                                        result.add_issue(
+                                            LintIssue::new(
+                                                "B013",
+                                                "relative-path",
+                                                Severity::Hint,
+                                                RuleCategory::BestPractices,
+                                                format!(
+                                                    "Relative path '{}' used for '{}'",
+                                                    dest_str, dest_key
+                                                ),
+                                                Location::file(path)
+                                                    .with_play(
+                                                        play_idx,
+                                                        play_name.map(String::from),
+                                                    )
+                                                    .with_task(
+                                                        task_idx,
+                                                        task_name.map(String::from),
+                                                    ),
+                                            )
+                                            .with_suggestion(
+                                                "Consider using an absolute path for clarity",
+                                            ),
+                                        );
                                     }
                                 }
                             }
@@ -543,9 +862,7 @@ impl BestPracticesChecker {
         if let Some(notify) = task.get(&serde_yaml::Value::String("notify".to_string())) {
             let handlers: Vec<&str> = match notify {
                 serde_yaml::Value::String(s) => vec![s.as_str()],
-                serde_yaml::Value::Sequence(seq) => {
-                    seq.iter().filter_map(|v| v.as_str()).collect()
-                }
+                serde_yaml::Value::Sequence(seq) => seq.iter().filter_map(|v| v.as_str()).collect(),
                 _ => vec![],
             };
 
@@ -553,16 +870,22 @@ impl BestPracticesChecker {
                 // Check for handler names with spaces in wrong format
                 if handler.contains("  ") {
                     if config.should_run_rule("B014", RuleCategory::BestPractices, Severity::Hint) {
-                        result.add_issue(LintIssue::new(
-                            "B014",
-                            "handler-multiple-spaces",
-                            Severity::Hint,
-                            RuleCategory::BestPractices,
-                            format!("Handler name '{}' contains multiple consecutive spaces", handler),
-                            Location::file(path)
-                                .with_play(play_idx, play_name.map(String::from))
-                                .with_task(task_idx, task_name.map(String::from)),
-                        ).with_suggestion("Use single spaces in handler names"));
+                        result.add_issue(
+                            LintIssue::new(
+                                "B014",
+                                "handler-multiple-spaces",
+                                Severity::Hint,
+                                RuleCategory::BestPractices,
+                                format!(
+                                    "Handler name '{}' contains multiple consecutive spaces",
+                                    handler
+                                ),
+                                Location::file(path)
+                                    .with_play(play_idx, play_name.map(String::from))
+                                    .with_task(task_idx, task_name.map(String::from)),
+                            )
+                            .with_suggestion("Use single spaces in handler names"),
+                        );
                     }
                 }
             }
@@ -586,21 +909,40 @@ impl BestPracticesChecker {
 
         if has_retries && !has_until {
             if config.should_run_rule("B015", RuleCategory::BestPractices, Severity::Warning) {
-                result.add_issue(LintIssue::new(
-                    "B015",
-                    "retries-without-until",
-                    Severity::Warning,
-                    RuleCategory::BestPractices,
-                    "'retries' specified without 'until' condition",
-                    Location::file(path)
-                        .with_play(play_idx, play_name.map(String::from))
-                        .with_task(task_idx, task_name.map(String::from)),
-                ).with_suggestion("Add 'until' condition to define when retries should stop"));
+                result.add_issue(
+                    LintIssue::new(
+                        "B015",
+                        "retries-without-until",
+                        Severity::Warning,
+                        RuleCategory::BestPractices,
+                        "'retries' specified without 'until' condition",
+                        Location::file(path)
+                            .with_play(play_idx, play_name.map(String::from))
+                            .with_task(task_idx, task_name.map(String::from)),
+                    )
+                    .with_suggestion("Add 'until' condition to define when retries should stop"),
+                );
             }
         }
     }
 }
 
+/// Interpret a YAML boolean, accepting `yes`/`true` strings.
+fn is_truthy(value: &serde_yaml::Value) -> bool {
+    match value {
+        serde_yaml::Value::Bool(b) => *b,
+        serde_yaml::Value::String(s) => matches!(s.to_lowercase().as_str(), "true" | "yes"),
+        _ => false,
+    }
+}
+
+/// Attach a source line to findings that do not have one yet.
+fn stamp_line(issues: &mut [LintIssue], line: usize) {
+    for issue in issues {
+        issue.location.line.get_or_insert(line);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
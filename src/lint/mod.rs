@@ -17,20 +17,97 @@
 //! let linter = Linter::new(config);
 //! let result = linter.check_file("playbook.yml")?;
 //!
-//! for issue in result.issues() {
+//! for issue in &result.issues {
 //!     println!("{}: {}", issue.severity, issue.message);
 //! }
 //! ```
+//!
+//! # Suppressing findings
+//!
+//! A `# noqa: B007` comment on any line of a task suppresses that rule for
+//! the task; several rule ids may be separated by commas, and a bare
+//! `# noqa` suppresses every rule.
 
 mod best_practices;
 mod params;
+mod source;
 mod types;
 mod yaml;
 
 pub use best_practices::BestPracticesChecker;
-pub use params::{ModuleDef, ParamDef, ParamType, ParamChecker};
+pub use params::{ModuleDef, ParamDef, ParamType, ParamValidator};
+pub use source::SourceIndex;
 pub use types::{
-    LintConfig, LintError, LintIssue, LintOpResult, LintResult, Location,
-    RuleCategory, Severity,
+    LintConfig, LintError, LintIssue, LintOpResult, LintResult, Location, RuleCategory, Severity,
 };
 pub use yaml::YamlChecker;
+
+use std::path::Path;
+
+/// Runs every lint checker over a playbook and collects the findings.
+pub struct Linter {
+    config: LintConfig,
+    yaml: YamlChecker,
+    best_practices: BestPracticesChecker,
+}
+
+impl Linter {
+    /// Create a linter with the given configuration.
+    pub fn new(config: LintConfig) -> Self {
+        Self {
+            config,
+            yaml: YamlChecker::new(),
+            best_practices: BestPracticesChecker::new(),
+        }
+    }
+
+    /// Lint a playbook file.
+    pub fn check_file(&self, path: impl AsRef<Path>) -> LintOpResult<LintResult> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| LintError::FileRead {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+
+        self.check_content(&content, path)
+    }
+
+    /// Lint playbook content, reporting findings against `path`.
+    pub fn check_content(&self, content: &str, path: &Path) -> LintOpResult<LintResult> {
+        let mut result = self.yaml.check_content(content, path, &self.config)?;
+        let source = SourceIndex::new(content);
+
+        if let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(content) {
+            let mut practices =
+                self.best_practices
+                    .check_playbook_source(&value, &source, path, &self.config);
+            // Already counted by the YAML checker
+            practices.files_analyzed.clear();
+            practices.plays_analyzed = 0;
+            practices.tasks_analyzed = 0;
+            result.merge(practices);
+        }
+
+        for issue in &mut result.issues {
+            if issue.location.line.is_none() {
+                issue.location.line = issue
+                    .location
+                    .play_index
+                    .and_then(|idx| source.play_line(idx));
+            }
+            if self.config.warnings_as_errors && issue.severity == Severity::Warning {
+                issue.severity = Severity::Error;
+            }
+        }
+
+        result.issues.retain(|issue| {
+            !issue
+                .location
+                .line
+                .is_some_and(|line| source.is_suppressed(line, &issue.rule_id))
+        });
+        result.issues.sort_by_key(|issue| issue.location.line);
+
+        Ok(result)
+    }
+}
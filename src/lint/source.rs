@@ -0,0 +1,290 @@
+//! Source line indexing for playbooks.
+//!
+//! `serde_yaml` values carry no position information, so this module scans
+//! the raw playbook text to find where each play and task starts and ends.
+//! The index is used to attach line numbers to findings and to honour
+//! `# noqa` suppression comments.
+
+use std::collections::{HashMap, HashSet};
+
+/// Task list keys within a play.
+const SECTION_KEYS: &[&str] = &["tasks", "pre_tasks", "post_tasks", "handlers"];
+
+/// Nested task list keys within a block task.
+const BLOCK_KEYS: &[&str] = &["block", "rescue", "always"];
+
+/// Line span of a play or task (1-indexed, end exclusive).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// First line of the entry.
+    pub start: usize,
+    /// Line after the last line of the entry.
+    pub end: usize,
+}
+
+impl Span {
+    fn contains(&self, line: usize) -> bool {
+        line >= self.start && line < self.end
+    }
+
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+/// Index of play and task positions in a playbook source file.
+///
+/// Tasks are keyed by their path through the playbook, e.g. `0/tasks/2` for
+/// the third task of the first play or `0/tasks/2/block/0` for the first task
+/// inside that task's block.
+#[derive(Debug, Clone, Default)]
+pub struct SourceIndex {
+    plays: Vec<Span>,
+    tasks: HashMap<String, Span>,
+    noqa: HashMap<usize, HashSet<String>>,
+}
+
+impl SourceIndex {
+    /// Build an index from playbook source text.
+    pub fn new(content: &str) -> Self {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut index = Self::default();
+
+        for (i, line) in lines.iter().enumerate() {
+            if let Some(rules) = parse_noqa(line) {
+                index.noqa.insert(i + 1, rules);
+            }
+        }
+
+        for (play_idx, play) in list_items(&lines, 0, lines.len()).into_iter().enumerate() {
+            index.plays.push(to_span(play));
+            for section in SECTION_KEYS {
+                if let Some(range) = key_range(&lines, play, section) {
+                    let prefix = format!("{}/{}", play_idx, section);
+                    index.index_tasks(&lines, range, &prefix);
+                }
+            }
+        }
+
+        index
+    }
+
+    fn index_tasks(&mut self, lines: &[&str], range: (usize, usize), prefix: &str) {
+        for (task_idx, task) in list_items(lines, range.0, range.1).into_iter().enumerate() {
+            let key = format!("{}/{}", prefix, task_idx);
+            for block_key in BLOCK_KEYS {
+                if let Some(block_range) = key_range(lines, task, block_key) {
+                    self.index_tasks(lines, block_range, &format!("{}/{}", key, block_key));
+                }
+            }
+            self.tasks.insert(key, to_span(task));
+        }
+    }
+
+    /// First line of a play.
+    pub fn play_line(&self, play_idx: usize) -> Option<usize> {
+        self.plays.get(play_idx).map(|span| span.start)
+    }
+
+    /// First line of a task identified by its path key.
+    pub fn task_line(&self, key: &str) -> Option<usize> {
+        self.tasks.get(key).map(|span| span.start)
+    }
+
+    /// Check whether a finding on `line` is suppressed for `rule_id`.
+    ///
+    /// A `# noqa: RULE` comment anywhere within a task applies to findings
+    /// reported at that task's first line, unless the comment belongs to a
+    /// nested task. A bare `# noqa` suppresses every rule.
+    pub fn is_suppressed(&self, line: usize, rule_id: &str) -> bool {
+        let Some(task) = self.innermost_task_starting_at(line) else {
+            return self.noqa_matches(line, rule_id);
+        };
+
+        self.noqa
+            .iter()
+            .filter(|(comment_line, _)| {
+                self.innermost_task_containing(**comment_line) == Some(task)
+            })
+            .any(|(_, rules)| rules.is_empty() || rules.contains(rule_id))
+    }
+
+    fn noqa_matches(&self, line: usize, rule_id: &str) -> bool {
+        self.noqa
+            .get(&line)
+            .is_some_and(|rules| rules.is_empty() || rules.contains(rule_id))
+    }
+
+    fn innermost_task_starting_at(&self, line: usize) -> Option<Span> {
+        self.tasks
+            .values()
+            .filter(|span| span.start == line)
+            .min_by_key(|span| span.len())
+            .copied()
+    }
+
+    fn innermost_task_containing(&self, line: usize) -> Option<Span> {
+        self.tasks
+            .values()
+            .filter(|span| span.contains(line))
+            .min_by_key(|span| span.len())
+            .copied()
+    }
+}
+
+/// Parse the rule ids from a `# noqa` comment, if present.
+fn parse_noqa(line: &str) -> Option<HashSet<String>> {
+    let comment = &line[line.find('#')?..];
+    let rest = comment.trim_start_matches('#').trim_start();
+    let rules = rest.strip_prefix("noqa")?;
+    Some(
+        rules
+            .trim_start_matches(':')
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|rule| !rule.is_empty())
+            .map(String::from)
+            .collect(),
+    )
+}
+
+fn to_span((start, end): (usize, usize)) -> Span {
+    Span {
+        start: start + 1,
+        end: end + 1,
+    }
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Whether a line carries YAML content (not blank, a comment or a document marker).
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#') && trimmed != "---" && trimmed != "..."
+}
+
+fn is_item(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed == "-" || trimmed.starts_with("- ")
+}
+
+/// Find sequence items in `start..end` (0-indexed lines) at the indentation
+/// of the first item. Returns the line range of each item.
+fn list_items(lines: &[&str], start: usize, end: usize) -> Vec<(usize, usize)> {
+    let Some(first) = (start..end).find(|&i| is_content(lines[i])) else {
+        return Vec::new();
+    };
+    if !is_item(lines[first]) {
+        return Vec::new();
+    }
+    let item_indent = indent_of(lines[first]);
+
+    let starts: Vec<usize> = (first..end)
+        .filter(|&i| {
+            is_content(lines[i]) && indent_of(lines[i]) == item_indent && is_item(lines[i])
+        })
+        .collect();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(n, &item_start)| {
+            let limit = starts.get(n + 1).copied().unwrap_or(end);
+            let item_end = (item_start + 1..limit)
+                .find(|&i| is_content(lines[i]) && indent_of(lines[i]) < item_indent + 1)
+                .unwrap_or(limit);
+            (item_start, item_end)
+        })
+        .collect()
+}
+
+/// Find the line range of the value of `key` within a sequence item.
+fn key_range(lines: &[&str], (start, end): (usize, usize), key: &str) -> Option<(usize, usize)> {
+    let dash_line = lines[start];
+    let key_indent = indent_of(dash_line) + 2;
+    let pattern = format!("{}:", key);
+
+    let key_line = (start..end).find(|&i| {
+        let line = lines[i];
+        let body = if i == start {
+            line.trim_start().trim_start_matches('-').trim_start()
+        } else if is_content(line) && indent_of(line) == key_indent {
+            line.trim_start()
+        } else {
+            return false;
+        };
+        body.strip_prefix(&pattern)
+            .is_some_and(|rest| rest.trim().is_empty() || rest.trim_start().starts_with('#'))
+    })?;
+
+    let line_indent = if key_line == start {
+        key_indent
+    } else {
+        indent_of(lines[key_line])
+    };
+    let value_end = (key_line + 1..end)
+        .find(|&i| {
+            is_content(lines[i])
+                && (indent_of(lines[i]) < line_indent
+                    || (indent_of(lines[i]) == line_indent && !is_item(lines[i])))
+        })
+        .unwrap_or(end);
+
+    Some((key_line + 1, value_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYBOOK: &str = r#"---
+- name: Web
+  hosts: all
+  tasks:
+    - name: First
+      debug:
+        msg: one
+    - block:
+        - name: Inner
+          shell: echo hi  # noqa: B007
+      rescue:
+        - debug:
+            msg: rescued
+  handlers:
+  - name: Restart
+    service:
+      name: nginx
+"#;
+
+    #[test]
+    fn test_indexes_plays_and_tasks() {
+        let index = SourceIndex::new(PLAYBOOK);
+
+        assert_eq!(index.play_line(0), Some(2));
+        assert_eq!(index.task_line("0/tasks/0"), Some(5));
+        assert_eq!(index.task_line("0/tasks/1"), Some(8));
+        assert_eq!(index.task_line("0/tasks/1/block/0"), Some(9));
+        assert_eq!(index.task_line("0/tasks/1/rescue/0"), Some(12));
+        assert_eq!(index.task_line("0/handlers/0"), Some(15));
+        assert_eq!(index.task_line("0/tasks/2"), None);
+    }
+
+    #[test]
+    fn test_noqa_applies_to_innermost_task() {
+        let index = SourceIndex::new(PLAYBOOK);
+
+        assert!(index.is_suppressed(9, "B007"));
+        assert!(!index.is_suppressed(9, "B004"));
+        // The comment belongs to the nested task, not the enclosing block
+        assert!(!index.is_suppressed(8, "B007"));
+    }
+
+    #[test]
+    fn test_parse_noqa() {
+        assert_eq!(parse_noqa("  shell: ls"), None);
+        assert!(parse_noqa("  shell: ls # noqa").unwrap().is_empty());
+        let rules = parse_noqa("- name: x  # noqa: B004, S001").unwrap();
+        assert!(rules.contains("B004") && rules.contains("S001"));
+    }
+}
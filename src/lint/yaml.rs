@@ -6,36 +6,108 @@
 //! - Key validation (detecting unknown or misspelled keys)
 //! - Indentation consistency checking
 
-use super::types::{LintConfig, LintIssue, LintOpResult, LintResult, Location, RuleCategory, Severity};
+use super::types::{
+    LintConfig, LintIssue, LintOpResult, LintResult, Location, RuleCategory, Severity,
+};
 use std::collections::HashSet;
 use std::path::Path;
 
 /// Known valid keys at the play level.
 const PLAY_KEYS: &[&str] = &[
-    "name", "hosts", "gather_facts", "gather_subset", "gather_timeout",
-    "remote_user", "become", "become_method", "become_user", "become_password",
-    "connection", "environment", "vars", "vars_files", "vars_prompt",
-    "pre_tasks", "roles", "tasks", "post_tasks", "handlers",
-    "serial", "max_fail_percentage", "ignore_errors", "ignore_unreachable",
-    "module_defaults", "tags", "strategy", "throttle", "order",
-    "force_handlers", "run_once", "when", "any_errors_fatal",
-    "port", "timeout", "collections", "fact_path",
+    "name",
+    "hosts",
+    "gather_facts",
+    "gather_subset",
+    "gather_timeout",
+    "remote_user",
+    "become",
+    "become_method",
+    "become_user",
+    "become_password",
+    "connection",
+    "environment",
+    "vars",
+    "vars_files",
+    "vars_prompt",
+    "pre_tasks",
+    "roles",
+    "tasks",
+    "post_tasks",
+    "handlers",
+    "serial",
+    "max_fail_percentage",
+    "ignore_errors",
+    "ignore_unreachable",
+    "module_defaults",
+    "tags",
+    "strategy",
+    "throttle",
+    "order",
+    "force_handlers",
+    "run_once",
+    "when",
+    "any_errors_fatal",
+    "port",
+    "timeout",
+    "collections",
+    "fact_path",
 ];
 
 /// Known valid keys at the task level.
 const TASK_KEYS: &[&str] = &[
-    "name", "action", "when", "loop", "with_items", "with_dict",
-    "with_file", "with_fileglob", "with_first_found", "with_together",
-    "with_nested", "with_random_choice", "with_sequence", "with_subelements",
-    "with_template", "with_inventory_hostnames", "with_indexed_items",
-    "loop_control", "register", "notify", "listen",
-    "ignore_errors", "ignore_unreachable", "changed_when", "failed_when",
-    "tags", "become", "become_method", "become_user",
-    "delegate_to", "delegate_facts", "local_action", "run_once",
-    "retries", "delay", "until", "async", "poll",
-    "environment", "vars", "args", "block", "rescue", "always",
-    "connection", "throttle", "timeout", "no_log", "diff", "check_mode",
-    "module_defaults", "any_errors_fatal", "debugger",
+    "name",
+    "action",
+    "when",
+    "loop",
+    "with_items",
+    "with_dict",
+    "with_file",
+    "with_fileglob",
+    "with_first_found",
+    "with_together",
+    "with_nested",
+    "with_random_choice",
+    "with_sequence",
+    "with_subelements",
+    "with_template",
+    "with_inventory_hostnames",
+    "with_indexed_items",
+    "loop_control",
+    "register",
+    "notify",
+    "listen",
+    "ignore_errors",
+    "ignore_unreachable",
+    "changed_when",
+    "failed_when",
+    "tags",
+    "become",
+    "become_method",
+    "become_user",
+    "delegate_to",
+    "delegate_facts",
+    "local_action",
+    "run_once",
+    "retries",
+    "delay",
+    "until",
+    "async",
+    "poll",
+    "environment",
+    "vars",
+    "args",
+    "block",
+    "rescue",
+    "always",
+    "connection",
+    "throttle",
+    "timeout",
+    "no_log",
+    "diff",
+    "check_mode",
+    "module_defaults",
+    "any_errors_fatal",
+    "debugger",
 ];
 
 /// YAML syntax checker.
@@ -70,16 +142,22 @@ impl YamlChecker {
 
     /// Check YAML syntax in a file.
     pub fn check_file(&self, path: &Path, config: &LintConfig) -> LintOpResult<LintResult> {
-        let content = std::fs::read_to_string(path).map_err(|e| super::types::LintError::FileRead {
-            path: path.to_path_buf(),
-            message: e.to_string(),
-        })?;
+        let content =
+            std::fs::read_to_string(path).map_err(|e| super::types::LintError::FileRead {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            })?;
 
         self.check_content(&content, path, config)
     }
 
     /// Check YAML syntax in content.
-    pub fn check_content(&self, content: &str, path: &Path, config: &LintConfig) -> LintOpResult<LintResult> {
+    pub fn check_content(
+        &self,
+        content: &str,
+        path: &Path,
+        config: &LintConfig,
+    ) -> LintOpResult<LintResult> {
         let mut result = LintResult::new();
         result.files_analyzed.push(path.to_path_buf());
 
@@ -134,14 +212,17 @@ impl YamlChecker {
             serde_yaml::Value::Mapping(_) => {
                 // Could be a single play or a task file
                 if config.should_run_rule("E002", RuleCategory::Syntax, Severity::Warning) {
-                    result.add_issue(LintIssue::new(
-                        "E002",
-                        "playbook-not-list",
-                        Severity::Warning,
-                        RuleCategory::Syntax,
-                        "Playbook should be a list of plays, not a single mapping",
-                        Location::file(path),
-                    ).with_suggestion("Wrap the play in a YAML list using '- ' prefix"));
+                    result.add_issue(
+                        LintIssue::new(
+                            "E002",
+                            "playbook-not-list",
+                            Severity::Warning,
+                            RuleCategory::Syntax,
+                            "Playbook should be a list of plays, not a single mapping",
+                            Location::file(path),
+                        )
+                        .with_suggestion("Wrap the play in a YAML list using '- ' prefix"),
+                    );
                 }
                 result.plays_analyzed = 1;
             }
@@ -196,14 +277,17 @@ impl YamlChecker {
         // Check for required 'hosts' key
         if !play_map.contains_key(&serde_yaml::Value::String("hosts".to_string())) {
             if config.should_run_rule("E005", RuleCategory::Syntax, Severity::Error) {
-                result.add_issue(LintIssue::new(
-                    "E005",
-                    "missing-hosts",
-                    Severity::Error,
-                    RuleCategory::Syntax,
-                    "Play is missing required 'hosts' key",
-                    Location::file(path).with_play(play_idx, play_name.clone()),
-                ).with_suggestion("Add 'hosts: all' or specify target hosts"));
+                result.add_issue(
+                    LintIssue::new(
+                        "E005",
+                        "missing-hosts",
+                        Severity::Error,
+                        RuleCategory::Syntax,
+                        "Play is missing required 'hosts' key",
+                        Location::file(path).with_play(play_idx, play_name.clone()),
+                    )
+                    .with_suggestion("Add 'hosts: all' or specify target hosts"),
+                );
             }
         }
 
@@ -223,7 +307,8 @@ impl YamlChecker {
                             Location::file(path).with_play(play_idx, play_name.clone()),
                         );
                         if let Some(suggestion) = similar {
-                            issue = issue.with_suggestion(format!("Did you mean '{}'?", suggestion));
+                            issue =
+                                issue.with_suggestion(format!("Did you mean '{}'?", suggestion));
                         }
                         result.add_issue(issue);
                     }
@@ -236,7 +321,9 @@ impl YamlChecker {
             if let Some(tasks) = play_map.get(&serde_yaml::Value::String(task_key.to_string())) {
                 if let Some(task_list) = tasks.as_sequence() {
                     for (task_idx, task) in task_list.iter().enumerate() {
-                        self.check_task(task, task_idx, play_idx, &play_name, path, config, result)?;
+                        self.check_task(
+                            task, task_idx, play_idx, &play_name, path, config, result,
+                        )?;
                         result.tasks_analyzed += 1;
                     }
                 }
@@ -316,7 +403,10 @@ impl YamlChecker {
                         "unknown-task-key",
                         Severity::Warning,
                         RuleCategory::Syntax,
-                        format!("Unknown key '{}' in task - may be misspelled module or attribute", key_str),
+                        format!(
+                            "Unknown key '{}' in task - may be misspelled module or attribute",
+                            key_str
+                        ),
                         Location::file(path)
                             .with_play(play_idx, play_name.clone())
                             .with_task(task_idx, task_name.clone()),
@@ -335,28 +425,37 @@ impl YamlChecker {
         let is_block = task_map.contains_key(&serde_yaml::Value::String("block".to_string()));
         if !found_module && !is_block {
             // Task might be using 'action' key
-            let has_action = task_map.contains_key(&serde_yaml::Value::String("action".to_string()));
-            let has_local_action = task_map.contains_key(&serde_yaml::Value::String("local_action".to_string()));
+            let has_action =
+                task_map.contains_key(&serde_yaml::Value::String("action".to_string()));
+            let has_local_action =
+                task_map.contains_key(&serde_yaml::Value::String("local_action".to_string()));
 
             if !has_action && !has_local_action {
                 if config.should_run_rule("E007", RuleCategory::Syntax, Severity::Error) {
-                    result.add_issue(LintIssue::new(
-                        "E007",
-                        "task-missing-module",
-                        Severity::Error,
-                        RuleCategory::Syntax,
-                        "Task does not specify a module to execute",
-                        Location::file(path)
-                            .with_play(play_idx, play_name.clone())
-                            .with_task(task_idx, task_name.clone()),
-                    ).with_suggestion("Add a module name like 'debug:', 'command:', or 'shell:'"));
+                    result.add_issue(
+                        LintIssue::new(
+                            "E007",
+                            "task-missing-module",
+                            Severity::Error,
+                            RuleCategory::Syntax,
+                            "Task does not specify a module to execute",
+                            Location::file(path)
+                                .with_play(play_idx, play_name.clone())
+                                .with_task(task_idx, task_name.clone()),
+                        )
+                        .with_suggestion(
+                            "Add a module name like 'debug:', 'command:', or 'shell:'",
+                        ),
+                    );
                 }
             }
         }
 
         // Recursively check block/rescue/always
         for block_key in &["block", "rescue", "always"] {
-            if let Some(block_tasks) = task_map.get(&serde_yaml::Value::String(block_key.to_string())) {
+            if let Some(block_tasks) =
+                task_map.get(&serde_yaml::Value::String(block_key.to_string()))
+            {
                 if let Some(block_list) = block_tasks.as_sequence() {
                     for (block_task_idx, block_task) in block_list.iter().enumerate() {
                         self.check_task(
@@ -391,42 +490,56 @@ impl YamlChecker {
             // Check for tabs (YAML should use spaces)
             if line.contains('\t') {
                 if config.should_run_rule("E008", RuleCategory::Syntax, Severity::Error) {
-                    result.add_issue(LintIssue::new(
-                        "E008",
-                        "yaml-tab-character",
-                        Severity::Error,
-                        RuleCategory::Syntax,
-                        "YAML files should use spaces for indentation, not tabs",
-                        Location::file(path).with_line(line_num),
-                    ).with_suggestion("Replace tabs with spaces (2 spaces per level is standard)"));
+                    result.add_issue(
+                        LintIssue::new(
+                            "E008",
+                            "yaml-tab-character",
+                            Severity::Error,
+                            RuleCategory::Syntax,
+                            "YAML files should use spaces for indentation, not tabs",
+                            Location::file(path).with_line(line_num),
+                        )
+                        .with_suggestion(
+                            "Replace tabs with spaces (2 spaces per level is standard)",
+                        ),
+                    );
                 }
             }
 
             // Check for trailing whitespace
             if line.ends_with(' ') || line.ends_with('\t') {
                 if config.should_run_rule("W003", RuleCategory::Syntax, Severity::Hint) {
-                    result.add_issue(LintIssue::new(
-                        "W003",
-                        "trailing-whitespace",
-                        Severity::Hint,
-                        RuleCategory::Syntax,
-                        "Line has trailing whitespace",
-                        Location::file(path).with_line(line_num),
-                    ).with_suggestion("Remove trailing whitespace"));
+                    result.add_issue(
+                        LintIssue::new(
+                            "W003",
+                            "trailing-whitespace",
+                            Severity::Hint,
+                            RuleCategory::Syntax,
+                            "Line has trailing whitespace",
+                            Location::file(path).with_line(line_num),
+                        )
+                        .with_suggestion("Remove trailing whitespace"),
+                    );
                 }
             }
 
             // Check for very long lines
             if line.len() > 160 {
                 if config.should_run_rule("W004", RuleCategory::Syntax, Severity::Hint) {
-                    result.add_issue(LintIssue::new(
-                        "W004",
-                        "line-too-long",
-                        Severity::Hint,
-                        RuleCategory::Syntax,
-                        format!("Line is {} characters long (max recommended: 160)", line.len()),
-                        Location::file(path).with_line(line_num),
-                    ).with_suggestion("Consider breaking long lines for readability"));
+                    result.add_issue(
+                        LintIssue::new(
+                            "W004",
+                            "line-too-long",
+                            Severity::Hint,
+                            RuleCategory::Syntax,
+                            format!(
+                                "Line is {} characters long (max recommended: 160)",
+                                line.len()
+                            ),
+                            Location::file(path).with_line(line_num),
+                        )
+                        .with_suggestion("Consider breaking long lines for readability"),
+                    );
                 }
             }
 
@@ -434,14 +547,17 @@ impl YamlChecker {
             let indent = line.len() - line.trim_start().len();
             if indent > 0 && indent % 2 != 0 && !line.trim().is_empty() {
                 if config.should_run_rule("W005", RuleCategory::Syntax, Severity::Warning) {
-                    result.add_issue(LintIssue::new(
-                        "W005",
-                        "odd-indentation",
-                        Severity::Warning,
-                        RuleCategory::Syntax,
-                        format!("Indentation is {} spaces (should be multiple of 2)", indent),
-                        Location::file(path).with_line(line_num),
-                    ).with_suggestion("Use consistent 2-space indentation"));
+                    result.add_issue(
+                        LintIssue::new(
+                            "W005",
+                            "odd-indentation",
+                            Severity::Warning,
+                            RuleCategory::Syntax,
+                            format!("Indentation is {} spaces (should be multiple of 2)", indent),
+                            Location::file(path).with_line(line_num),
+                        )
+                        .with_suggestion("Use consistent 2-space indentation"),
+                    );
                 }
             }
         }
@@ -449,14 +565,17 @@ impl YamlChecker {
         // Check for CRLF line endings
         if content.contains("\r\n") {
             if config.should_run_rule("W006", RuleCategory::Syntax, Severity::Hint) {
-                result.add_issue(LintIssue::new(
-                    "W006",
-                    "crlf-line-endings",
-                    Severity::Hint,
-                    RuleCategory::Syntax,
-                    "File uses Windows-style (CRLF) line endings",
-                    Location::file(path),
-                ).with_suggestion("Convert to Unix-style (LF) line endings"));
+                result.add_issue(
+                    LintIssue::new(
+                        "W006",
+                        "crlf-line-endings",
+                        Severity::Hint,
+                        RuleCategory::Syntax,
+                        "File uses Windows-style (CRLF) line endings",
+                        Location::file(path),
+                    )
+                    .with_suggestion("Convert to Unix-style (LF) line endings"),
+                );
             }
         }
     }
@@ -509,7 +628,11 @@ fn levenshtein_distance(a: &str, b: &str) -> usize {
 
     for i in 1..=a_len {
         for j in 1..=b_len {
-            let cost = if a_chars[i - 1] == b_chars[j - 1] { 0 } else { 1 };
+            let cost = if a_chars[i - 1] == b_chars[j - 1] {
+                0
+            } else {
+                1
+            };
             matrix[i][j] = (matrix[i - 1][j] + 1)
                 .min(matrix[i][j - 1] + 1)
                 .min(matrix[i - 1][j - 1] + cost);
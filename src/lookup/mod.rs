@@ -42,6 +42,7 @@ pub use password::PasswordLookup;
 pub use pipe::PipeLookup;
 pub use url::UrlLookup;
 
+use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -103,7 +104,7 @@ pub enum LookupError {
 pub type LookupResult<T> = Result<T, LookupError>;
 
 /// Context for lookup plugin execution
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct LookupContext {
     /// Base directory for relative file paths
     pub base_dir: Option<PathBuf>,
@@ -174,6 +175,12 @@ impl LookupContext {
     }
 }
 
+impl Default for LookupContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Trait that all lookup plugins must implement
 pub trait Lookup: Send + Sync {
     /// Returns the name of the lookup plugin
@@ -279,10 +286,7 @@ impl LookupRegistry {
             Ok(result) => Ok(result),
             Err(e) if !context.fail_on_error => {
                 // Return default value if fail_on_error is false
-                Ok(vec![context
-                    .default_value
-                    .clone()
-                    .unwrap_or_default()])
+                Ok(vec![context.default_value.clone().unwrap_or_default()])
             }
             Err(e) => Err(e),
         }
@@ -311,6 +315,42 @@ impl Default for LookupRegistry {
     }
 }
 
+/// Lookup results memoized for the duration of a single render
+///
+/// A template may look up the same term more than once (e.g. in a loop label
+/// and the loop body); with a cache per render, each plugin runs once per
+/// distinct set of arguments, so side effects such as reading a file or
+/// running a command are not repeated. Failed lookups are not cached.
+pub struct LookupCache {
+    registry: Arc<LookupRegistry>,
+    context: LookupContext,
+    results: Mutex<HashMap<(String, Vec<String>), Vec<String>>>,
+}
+
+impl LookupCache {
+    /// Create an empty cache over `registry`
+    pub fn new(registry: Arc<LookupRegistry>, context: LookupContext) -> Self {
+        Self {
+            registry,
+            context,
+            results: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Execute a lookup, reusing the result of an identical earlier one
+    pub fn lookup(&self, name: &str, args: &[String]) -> LookupResult<Vec<String>> {
+        let key = (name.to_string(), args.to_vec());
+        if let Some(cached) = self.results.lock().get(&key) {
+            return Ok(cached.clone());
+        }
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let result = self.registry.lookup(name, &args, &self.context)?;
+        self.results.lock().insert(key, result.clone());
+        Ok(result)
+    }
+}
+
 // ============================================================================
 // Prelude Module
 // ============================================================================
@@ -320,6 +360,7 @@ pub mod prelude {
     pub use super::EnvLookup;
     pub use super::FileLookup;
     pub use super::Lookup;
+    pub use super::LookupCache;
     pub use super::LookupContext;
     pub use super::LookupError;
     pub use super::LookupRegistry;
@@ -379,7 +420,11 @@ mod tests {
             fn description(&self) -> &'static str {
                 "Test lookup"
             }
-            fn lookup(&self, _args: &[&str], _context: &LookupContext) -> LookupResult<Vec<String>> {
+            fn lookup(
+                &self,
+                _args: &[&str],
+                _context: &LookupContext,
+            ) -> LookupResult<Vec<String>> {
                 Ok(vec![])
             }
         }
@@ -96,9 +96,9 @@ impl PipeLookup {
         }
 
         // Execute the command
-        let output = command.output().map_err(|e| {
-            LookupError::CommandFailed(format!("Failed to execute command: {}", e))
-        })?;
+        let output = command
+            .output()
+            .map_err(|e| LookupError::CommandFailed(format!("Failed to execute command: {}", e)))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -291,7 +291,9 @@ mod tests {
         // Valid commands
         assert!(lookup.validate_command("echo hello").is_ok());
         assert!(lookup.validate_command("ls -la").is_ok());
-        assert!(lookup.validate_command("cat /etc/passwd | grep root").is_ok());
+        assert!(lookup
+            .validate_command("cat /etc/passwd | grep root")
+            .is_ok());
 
         // Invalid commands
         assert!(lookup.validate_command("").is_err());
@@ -46,17 +46,22 @@ async fn main() -> Result<()> {
     let mut ctx = CommandContext::new(&cli, config);
 
     // Execute the appropriate command
-    let exit_code = match &cli.command {
-        Commands::Run(args) => args.execute(&mut ctx).await?,
-        Commands::Check(args) => args.execute(&mut ctx).await?,
-        Commands::ListHosts(args) => args.execute(&mut ctx).await?,
-        Commands::ListTasks(args) => args.execute(&mut ctx).await?,
-        Commands::Vault(args) => args.execute(&mut ctx).await?,
-        Commands::Init(args) => init_project(&args.path, &args.template, &mut ctx).await?,
-        Commands::Validate(args) => validate_playbook(&args.playbook, &mut ctx).await?,
+    let result = match &cli.command {
+        Commands::Run(args) => args.execute(&mut ctx).await,
+        Commands::Check(args) => args.execute(&mut ctx).await,
+        Commands::ListHosts(args) => args.execute(&mut ctx).await,
+        Commands::ListTasks(args) => args.execute(&mut ctx).await,
+        Commands::Vault(args) => args.execute(&mut ctx).await,
+        Commands::Facts(args) => args.execute(&mut ctx).await,
+        Commands::Init(args) => init_project(&args.path, &args.template, &mut ctx).await,
+        Commands::Validate(args) => validate_playbook(&args.playbook, &mut ctx).await,
+        Commands::Lint(args) => args.execute(&mut ctx).await,
     };
 
-    std::process::exit(exit_code);
+    // process::exit skips destructors, so close pooled connections first
+    ctx.close_connections().await;
+
+    std::process::exit(result?);
 }
 
 /// Initialize logging based on verbosity level
@@ -11,6 +11,7 @@
 //! - upgrade (dist, full, yes, safe)
 //! - allow_downgrade / only_upgrade
 
+use super::package::{with_package_delta, with_version_transitions};
 use super::{
     Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
     ModuleResult, ParallelizationHint, ParamExt,
@@ -248,16 +249,7 @@ pub struct AptModule;
 impl AptModule {
     /// Build execution options with become/sudo if needed
     fn build_exec_options(context: &ModuleContext) -> ExecuteOptions {
-        let mut options = ExecuteOptions::new();
-
-        if context.r#become {
-            options.escalate = true;
-            options.escalate_user = context
-                .become_user
-                .clone()
-                .or_else(|| Some("root".to_string()));
-            options.escalate_method = context.become_method.clone();
-        }
+        let mut options = context.execute_options();
 
         if let Some(ref work_dir) = context.work_dir {
             options = options.with_cwd(work_dir);
@@ -373,9 +365,10 @@ impl AptModule {
         options: Option<ExecuteOptions>,
     ) -> ModuleResult<bool> {
         let cmd = "apt-get update -qq";
-        let result = conn.execute(cmd, options).await.map_err(|e| {
-            ModuleError::ExecutionFailed(format!("Failed to update apt cache: {}", e))
-        })?;
+        let result = conn
+            .execute(cmd, options)
+            .await
+            .map_err(|e| ModuleError::from_connection("Failed to update apt cache", e))?;
 
         if !result.success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -410,9 +403,10 @@ impl AptModule {
             pkg_list.join(" ")
         );
 
-        let result = conn.execute(&cmd, options).await.map_err(|e| {
-            ModuleError::ExecutionFailed(format!("Failed to install packages: {}", e))
-        })?;
+        let result = conn
+            .execute(&cmd, options)
+            .await
+            .map_err(|e| ModuleError::from_connection("Failed to install packages", e))?;
 
         if !result.success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -443,9 +437,10 @@ impl AptModule {
             pkg_list.join(" ")
         );
 
-        let result = conn.execute(&cmd, options).await.map_err(|e| {
-            ModuleError::ExecutionFailed(format!("Failed to remove packages: {}", e))
-        })?;
+        let result = conn
+            .execute(&cmd, options)
+            .await
+            .map_err(|e| ModuleError::from_connection("Failed to remove packages", e))?;
 
         if !result.success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -473,9 +468,10 @@ impl AptModule {
             pkg_list.join(" ")
         );
 
-        let result = conn.execute(&cmd, options).await.map_err(|e| {
-            ModuleError::ExecutionFailed(format!("Failed to upgrade packages: {}", e))
-        })?;
+        let result = conn
+            .execute(&cmd, options)
+            .await
+            .map_err(|e| ModuleError::from_connection("Failed to upgrade packages", e))?;
 
         if !result.success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -503,9 +499,10 @@ impl AptModule {
             pkg_list.join(" ")
         );
 
-        let result = conn.execute(&cmd, options).await.map_err(|e| {
-            ModuleError::ExecutionFailed(format!("Failed to install build deps: {}", e))
-        })?;
+        let result = conn
+            .execute(&cmd, options)
+            .await
+            .map_err(|e| ModuleError::from_connection("Failed to install build deps", e))?;
 
         if !result.success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -530,9 +527,10 @@ impl AptModule {
             apt_opts
         );
 
-        let result = conn.execute(&cmd, options).await.map_err(|e| {
-            ModuleError::ExecutionFailed(format!("Failed to fix dependencies: {}", e))
-        })?;
+        let result = conn
+            .execute(&cmd, options)
+            .await
+            .map_err(|e| ModuleError::from_connection("Failed to fix dependencies", e))?;
 
         if !result.success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -567,7 +565,7 @@ impl AptModule {
         let result = conn
             .execute(&cmd, options)
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("Failed to install deb: {}", e)))?;
+            .map_err(|e| ModuleError::from_connection("Failed to install deb", e))?;
 
         if !result.success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -599,9 +597,10 @@ impl AptModule {
             upgrade_cmd, apt_opts
         );
 
-        let result = conn.execute(&cmd, options).await.map_err(|e| {
-            ModuleError::ExecutionFailed(format!("Failed to upgrade system: {}", e))
-        })?;
+        let result = conn
+            .execute(&cmd, options)
+            .await
+            .map_err(|e| ModuleError::from_connection("Failed to upgrade system", e))?;
 
         if !result.success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -633,7 +632,7 @@ impl AptModule {
         let result = conn
             .execute(&cmd, options)
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("Failed to autoremove: {}", e)))?;
+            .map_err(|e| ModuleError::from_connection("Failed to autoremove", e))?;
 
         if !result.success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -658,7 +657,7 @@ impl AptModule {
         let result = conn
             .execute(cmd, options)
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("Failed to autoclean: {}", e)))?;
+            .map_err(|e| ModuleError::from_connection("Failed to autoclean", e))?;
 
         if !result.success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -747,6 +746,7 @@ impl Module for AptModule {
                 let mut all_stdout = String::new();
                 let mut all_stderr = String::new();
                 let mut results: HashMap<String, serde_json::Value> = HashMap::new();
+                let mut upgrade_transitions: Vec<(String, String, String)> = Vec::new();
 
                 // Step 1: Update cache if requested (with cache_valid_time support)
                 if apt_params.update_cache {
@@ -827,6 +827,9 @@ impl Module for AptModule {
                     let mut to_upgrade: Vec<String> = Vec::new();
                     let mut to_build_dep: Vec<String> = Vec::new();
                     let mut already_ok: Vec<String> = Vec::new();
+                    let mut installed: Vec<String> = Vec::new();
+                    // (package, installed, candidate) for packages to upgrade
+                    let mut transitions: Vec<(String, String, String)> = Vec::new();
 
                     // Check current state of each package
                     for package in &apt_params.packages {
@@ -836,6 +839,9 @@ impl Module for AptModule {
                             Some(exec_options.clone()),
                         )
                         .await?;
+                        if is_installed {
+                            installed.push(package.clone());
+                        }
 
                         match apt_params.state {
                             AptState::Present => {
@@ -868,10 +874,20 @@ impl Module for AptModule {
                                     )
                                     .await?;
 
-                                    if installed_ver != available_ver {
-                                        to_upgrade.push(package.clone());
-                                    } else {
-                                        already_ok.push(package.clone());
+                                    // Without a newer candidate there is nothing to upgrade to
+                                    match available_ver {
+                                        Some(candidate)
+                                            if installed_ver.as_deref()
+                                                != Some(candidate.as_str()) =>
+                                        {
+                                            to_upgrade.push(package.clone());
+                                            transitions.push((
+                                                package.clone(),
+                                                installed_ver.unwrap_or_default(),
+                                                candidate,
+                                            ));
+                                        }
+                                        _ => already_ok.push(package.clone()),
                                     }
                                 } else {
                                     to_install.push(package.clone());
@@ -913,7 +929,17 @@ impl Module for AptModule {
                             )));
                         }
                         if !messages.is_empty() {
-                            return Ok(ModuleOutput::changed(messages.join(". ")));
+                            let mut output = ModuleOutput::changed(messages.join(". "));
+                            if !transitions.is_empty() {
+                                output = with_version_transitions(output, &transitions);
+                            }
+                            return Ok(with_package_delta(
+                                output,
+                                &installed,
+                                &to_install,
+                                &to_remove,
+                                &transitions,
+                            ));
                         }
                     }
 
@@ -945,11 +971,17 @@ impl Module for AptModule {
                             Some(exec_options.clone()),
                         )
                         .await?;
-                        changed = true;
-                        for pkg in &to_upgrade {
-                            pkg_results.insert(pkg.clone(), "upgraded".to_string());
+                        // A held or pinned package leaves apt with nothing to do
+                        if stdout.contains("0 upgraded, 0 newly installed") {
+                            already_ok.append(&mut to_upgrade);
+                            transitions.clear();
+                        } else {
+                            changed = true;
+                            for pkg in &to_upgrade {
+                                pkg_results.insert(pkg.clone(), "upgraded".to_string());
+                            }
+                            messages.push(format!("Upgraded: {}", to_upgrade.join(", ")));
                         }
-                        messages.push(format!("Upgraded: {}", to_upgrade.join(", ")));
                         all_stdout.push_str(&stdout);
                         all_stderr.push_str(&stderr);
                     }
@@ -1010,6 +1042,7 @@ impl Module for AptModule {
                     }
 
                     results.insert("packages".to_string(), serde_json::json!(pkg_results));
+                    upgrade_transitions = transitions;
                 }
 
                 // Step 5: Handle autoremove
@@ -1060,6 +1093,9 @@ impl Module for AptModule {
                 for (key, value) in results {
                     output = output.with_data(key, value);
                 }
+                if !upgrade_transitions.is_empty() {
+                    output = with_version_transitions(output, &upgrade_transitions);
+                }
 
                 // Add stdout/stderr if present
                 if !all_stdout.is_empty() || !all_stderr.is_empty() {
@@ -0,0 +1,655 @@
+//! Apt repository module - Manage APT sources
+//!
+//! This module adds and removes APT repository entries in
+//! `/etc/apt/sources.list.d`, optionally installs the repository signing key,
+//! and refreshes the package cache after a source is added.
+//!
+//! ## Parameters
+//!
+//! - `repo`: Source line, e.g. `deb [signed-by=/etc/apt/keyrings/docker.asc] https://download.docker.com/linux/ubuntu jammy stable` (required)
+//! - `state`: Desired state (present, absent)
+//! - `filename`: Name of the `.list` file without extension (default: derived from the repository URI)
+//! - `key_url`: URL of a signing key to install before the source is added
+//! - `key_path`: Where to store the signing key (default: `/etc/apt/keyrings/<filename>.asc`)
+//! - `update_cache`: Run `apt-get update` after the source is added (default: true)
+//! - `mode`: File permissions for the `.list` file (default: 0644)
+//!
+//! Every value ends up in a file read by APT or in a remote command, so the
+//! repository line, file name and key URL are validated against strict
+//! patterns before anything is executed.
+
+use super::{
+    Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
+    ModuleResult, ParallelizationHint, ParamExt,
+};
+use crate::connection::{Connection, ExecuteOptions, TransferOptions};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Directory holding APT source lists
+const SOURCES_DIR: &str = "/etc/apt/sources.list.d";
+
+/// Directory holding repository signing keys
+const KEYRINGS_DIR: &str = "/etc/apt/keyrings";
+
+/// Regex for `.list` file names
+static FILENAME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z0-9_.-]+$").expect("Invalid filename regex"));
+
+/// Regex for the `[key=value ...]` options block of a source line
+static OPTIONS_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z0-9_=,./:+ -]*$").expect("Invalid options regex"));
+
+/// Regex for repository URIs
+static URI_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(https?|ftp|file|cdrom|mirror(\+[a-z]+)?):[A-Za-z0-9_.~/:@%+=,-]+$")
+        .expect("Invalid URI regex")
+});
+
+/// Regex for suites and components
+static WORD_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z0-9_./+-]+$").expect("Invalid suite regex"));
+
+/// Regex for signing key URLs
+static KEY_URL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^https?://[A-Za-z0-9_.~/:@%+=,?&-]+$").expect("Invalid key URL regex")
+});
+
+/// Desired state for a repository entry
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepositoryState {
+    Present,
+    Absent,
+}
+
+impl RepositoryState {
+    pub fn from_str(s: &str) -> ModuleResult<Self> {
+        match s.to_lowercase().as_str() {
+            "present" => Ok(RepositoryState::Present),
+            "absent" => Ok(RepositoryState::Absent),
+            _ => Err(ModuleError::InvalidParameter(format!(
+                "Invalid state '{}'. Valid states: present, absent",
+                s
+            ))),
+        }
+    }
+}
+
+/// A parsed one-line-style APT source entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct AptSource {
+    /// `deb` or `deb-src`
+    pub kind: String,
+    /// Contents of the `[...]` options block, if any
+    pub options: Option<String>,
+    /// Repository URI
+    pub uri: String,
+    /// Distribution or suite
+    pub suite: String,
+    /// Components (main, contrib, ...)
+    pub components: Vec<String>,
+}
+
+impl AptSource {
+    /// Parse and validate a source line
+    pub fn parse(line: &str) -> ModuleResult<Self> {
+        let invalid = |reason: &str| {
+            ModuleError::InvalidParameter(format!("Invalid repo '{}': {}", line, reason))
+        };
+
+        if line.chars().any(|c| c.is_control()) {
+            return Err(invalid("must be a single line"));
+        }
+
+        let line = line.trim();
+        let (kind, rest) = line
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| invalid("expected '<type> <uri> <suite> [components]'"))?;
+        if kind != "deb" && kind != "deb-src" {
+            return Err(invalid("type must be 'deb' or 'deb-src'"));
+        }
+
+        let rest = rest.trim_start();
+        let (options, rest) = if let Some(stripped) = rest.strip_prefix('[') {
+            let (options, rest) = stripped
+                .split_once(']')
+                .ok_or_else(|| invalid("unterminated options block"))?;
+            let options = options.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !OPTIONS_REGEX.is_match(&options) {
+                return Err(invalid("options contain unsupported characters"));
+            }
+            (Some(options), rest)
+        } else {
+            (None, rest)
+        };
+
+        let mut fields = rest.split_whitespace();
+        let uri = fields.next().ok_or_else(|| invalid("missing URI"))?;
+        if !URI_REGEX.is_match(uri) {
+            return Err(invalid(
+                "URI is not a supported http(s), ftp, file or mirror URI",
+            ));
+        }
+        let suite = fields.next().ok_or_else(|| invalid("missing suite"))?;
+        let components: Vec<String> = fields.map(str::to_string).collect();
+        if !WORD_REGEX.is_match(suite) || !components.iter().all(|c| WORD_REGEX.is_match(c)) {
+            return Err(invalid(
+                "suite and components contain unsupported characters",
+            ));
+        }
+        // A suite ending in '/' is an exact path and takes no components
+        if components.is_empty() && !suite.ends_with('/') {
+            return Err(invalid("at least one component is required"));
+        }
+
+        Ok(Self {
+            kind: kind.to_string(),
+            options: options.filter(|o| !o.is_empty()),
+            uri: uri.to_string(),
+            suite: suite.to_string(),
+            components,
+        })
+    }
+
+    /// Render the entry in canonical form
+    pub fn to_line(&self) -> String {
+        let mut line = self.kind.clone();
+        if let Some(ref options) = self.options {
+            line.push_str(&format!(" [{}]", options));
+        }
+        line.push_str(&format!(" {} {}", self.uri, self.suite));
+        for component in &self.components {
+            line.push(' ');
+            line.push_str(component);
+        }
+        line
+    }
+
+    /// Derive a `.list` file name from the repository URI
+    ///
+    /// `https://download.docker.com/linux/ubuntu` becomes
+    /// `download_docker_com_linux_ubuntu`.
+    pub fn default_filename(&self) -> String {
+        let without_scheme = self
+            .uri
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(&self.uri);
+        let name: String = without_scheme
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        name.trim_matches('_').to_string()
+    }
+}
+
+/// Add a source to a `.list` file, returning the new content if it changed
+pub fn add_source(content: &str, source: &AptSource) -> Option<String> {
+    if find_source(content, source) {
+        return None;
+    }
+
+    let mut new_content = content.to_string();
+    if !new_content.is_empty() && !new_content.ends_with('\n') {
+        new_content.push('\n');
+    }
+    new_content.push_str(&source.to_line());
+    new_content.push('\n');
+    Some(new_content)
+}
+
+/// Remove a source from a `.list` file, returning the new content if it changed
+pub fn remove_source(content: &str, source: &AptSource) -> Option<String> {
+    if !find_source(content, source) {
+        return None;
+    }
+
+    let kept: Vec<&str> = content
+        .lines()
+        .filter(|line| !line_matches(line, source))
+        .collect();
+    let mut new_content = kept.join("\n");
+    if !new_content.is_empty() {
+        new_content.push('\n');
+    }
+    Some(new_content)
+}
+
+/// Check whether a `.list` file already contains an entry
+fn find_source(content: &str, source: &AptSource) -> bool {
+    content.lines().any(|line| line_matches(line, source))
+}
+
+/// Compare a `.list` line with an entry, ignoring comments and spacing
+fn line_matches(line: &str, source: &AptSource) -> bool {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return false;
+    }
+    AptSource::parse(line)
+        .map(|parsed| &parsed == source)
+        .unwrap_or(false)
+}
+
+/// Validated module parameters
+#[derive(Debug, Clone)]
+struct AptRepositoryConfig {
+    source: AptSource,
+    state: RepositoryState,
+    filename: String,
+    key_url: Option<String>,
+    key_path: String,
+    update_cache: bool,
+    mode: u32,
+}
+
+impl AptRepositoryConfig {
+    fn from_params(params: &ModuleParams) -> ModuleResult<Self> {
+        let source = AptSource::parse(&params.get_string_required("repo")?)?;
+
+        let state = RepositoryState::from_str(
+            &params
+                .get_string("state")?
+                .unwrap_or_else(|| "present".to_string()),
+        )?;
+
+        let filename = match params.get_string("filename")? {
+            Some(name) => name.trim_end_matches(".list").to_string(),
+            None => source.default_filename(),
+        };
+        if !FILENAME_REGEX.is_match(&filename) {
+            return Err(ModuleError::InvalidParameter(format!(
+                "Invalid filename '{}': must contain only alphanumeric characters, underscores, dots, and dashes",
+                filename
+            )));
+        }
+
+        let key_url = params.get_string("key_url")?;
+        if let Some(ref url) = key_url {
+            if !KEY_URL_REGEX.is_match(url) {
+                return Err(ModuleError::InvalidParameter(format!(
+                    "Invalid key_url '{}': must be an http(s) URL",
+                    url
+                )));
+            }
+        }
+
+        let key_path = params
+            .get_string("key_path")?
+            .unwrap_or_else(|| format!("{}/{}.asc", KEYRINGS_DIR, filename));
+        if !key_path.starts_with('/') || !WORD_REGEX.is_match(&key_path) {
+            return Err(ModuleError::InvalidParameter(format!(
+                "Invalid key_path '{}': must be an absolute path",
+                key_path
+            )));
+        }
+
+        Ok(Self {
+            source,
+            state,
+            filename,
+            key_url,
+            key_path,
+            update_cache: params.get_bool_or("update_cache", true),
+            mode: params.get_u32("mode")?.unwrap_or(0o644),
+        })
+    }
+
+    fn list_path(&self) -> String {
+        format!("{}/{}.list", SOURCES_DIR, self.filename)
+    }
+
+    /// Compute the desired file content, or `None` if nothing changes
+    fn desired_content(&self, current: &str) -> Option<String> {
+        match self.state {
+            RepositoryState::Present => add_source(current, &self.source),
+            RepositoryState::Absent => remove_source(current, &self.source),
+        }
+    }
+}
+
+/// Module for APT repository management
+pub struct AptRepositoryModule;
+
+impl AptRepositoryModule {
+    /// Build execute options with privilege escalation if needed
+    fn build_execute_options(context: &ModuleContext) -> Option<ExecuteOptions> {
+        context.r#become.then(|| context.execute_options())
+    }
+
+    /// Run a command on the target, failing on a non-zero exit status
+    async fn run(
+        connection: &(dyn Connection + Send + Sync),
+        command: &str,
+        context: &ModuleContext,
+    ) -> ModuleResult<()> {
+        let result = connection
+            .execute(command, Self::build_execute_options(context))
+            .await?;
+        if result.success {
+            Ok(())
+        } else {
+            Err(ModuleError::ExecutionFailed(format!(
+                "'{}' failed: {}",
+                command,
+                result.stderr.trim()
+            )))
+        }
+    }
+
+    /// Read a file from the target, treating a missing file as empty
+    async fn read_file(connection: &(dyn Connection + Send + Sync), path: &Path) -> String {
+        if connection.path_exists(path).await.unwrap_or(false) {
+            connection
+                .download_content(path)
+                .await
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .unwrap_or_default()
+        } else {
+            String::new()
+        }
+    }
+
+    async fn execute_async(
+        &self,
+        config: &AptRepositoryConfig,
+        context: &ModuleContext,
+        connection: Arc<dyn Connection + Send + Sync>,
+    ) -> ModuleResult<ModuleOutput> {
+        let list_path = config.list_path();
+        let current = Self::read_file(connection.as_ref(), Path::new(&list_path)).await;
+        let desired = config.desired_content(&current);
+
+        let mut changed = false;
+        let mut messages = Vec::new();
+
+        // Install the signing key before the source that references it
+        if config.state == RepositoryState::Present {
+            if let Some(ref key_url) = config.key_url {
+                let key_path = Path::new(&config.key_path);
+                if !connection.path_exists(key_path).await.unwrap_or(false) {
+                    if !context.check_mode {
+                        let key_dir = key_path
+                            .parent()
+                            .and_then(|p| p.to_str())
+                            .unwrap_or(KEYRINGS_DIR);
+                        Self::run(
+                            connection.as_ref(),
+                            &format!("install -d -m 0755 '{}'", key_dir),
+                            context,
+                        )
+                        .await?;
+                        Self::run(
+                            connection.as_ref(),
+                            &format!("curl -fsSL '{}' -o '{}'", key_url, config.key_path),
+                            context,
+                        )
+                        .await?;
+                        Self::run(
+                            connection.as_ref(),
+                            &format!("chmod 0644 '{}'", config.key_path),
+                            context,
+                        )
+                        .await?;
+                    }
+                    messages.push(format!("Installed signing key {}", config.key_path));
+                    changed = true;
+                }
+            }
+        }
+
+        if let Some(ref new_content) = desired {
+            if !context.check_mode {
+                if new_content.is_empty() {
+                    Self::run(
+                        connection.as_ref(),
+                        &format!("rm -f '{}'", list_path),
+                        context,
+                    )
+                    .await?;
+                } else {
+                    let transfer_opts = TransferOptions {
+                        mode: Some(config.mode),
+                        create_dirs: true,
+                        ..Default::default()
+                    };
+                    connection
+                        .upload_content(
+                            new_content.as_bytes(),
+                            Path::new(&list_path),
+                            Some(transfer_opts),
+                        )
+                        .await
+                        .map_err(|e| {
+                            ModuleError::ExecutionFailed(format!(
+                                "Failed to write {}: {}",
+                                list_path, e
+                            ))
+                        })?;
+                }
+            }
+
+            let action = match config.state {
+                RepositoryState::Present => "Added",
+                RepositoryState::Absent => "Removed",
+            };
+            messages.push(format!(
+                "{} '{}' in {}",
+                action,
+                config.source.to_line(),
+                list_path
+            ));
+            changed = true;
+
+            if config.state == RepositoryState::Present && config.update_cache {
+                if !context.check_mode {
+                    Self::run(connection.as_ref(), "apt-get update -qq", context).await?;
+                }
+                messages.push("Updated apt cache".to_string());
+            }
+        }
+
+        let output = if changed {
+            let msg = messages.join(". ");
+            if context.check_mode {
+                ModuleOutput::changed(format!("Would have: {}", msg))
+            } else {
+                ModuleOutput::changed(msg)
+            }
+        } else {
+            ModuleOutput::ok(format!(
+                "Repository '{}' already {}",
+                config.source.to_line(),
+                match config.state {
+                    RepositoryState::Present => "present",
+                    RepositoryState::Absent => "absent",
+                }
+            ))
+        };
+
+        let output = output
+            .with_data("repo", serde_json::json!(config.source.to_line()))
+            .with_data("sources_list", serde_json::json!(list_path))
+            .with_data(
+                "state",
+                serde_json::json!(match config.state {
+                    RepositoryState::Present => "present",
+                    RepositoryState::Absent => "absent",
+                }),
+            );
+
+        match desired {
+            Some(after) if context.diff_mode => Ok(output.with_diff(Diff::new(current, after))),
+            _ => Ok(output),
+        }
+    }
+}
+
+impl Module for AptRepositoryModule {
+    fn name(&self) -> &'static str {
+        "apt_repository"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add and remove APT repositories"
+    }
+
+    fn classification(&self) -> ModuleClassification {
+        ModuleClassification::RemoteCommand
+    }
+
+    fn parallelization_hint(&self) -> ParallelizationHint {
+        // Refreshing the cache takes the APT lock
+        ParallelizationHint::HostExclusive
+    }
+
+    fn required_params(&self) -> &[&'static str] {
+        &["repo"]
+    }
+
+    fn validate_params(&self, params: &ModuleParams) -> ModuleResult<()> {
+        AptRepositoryConfig::from_params(params).map(|_| ())
+    }
+
+    fn execute(
+        &self,
+        params: &ModuleParams,
+        context: &ModuleContext,
+    ) -> ModuleResult<ModuleOutput> {
+        let config = AptRepositoryConfig::from_params(params)?;
+
+        let connection = context.connection.clone().ok_or_else(|| {
+            ModuleError::ExecutionFailed(
+                "No connection available for apt_repository module execution".to_string(),
+            )
+        })?;
+
+        let handle = tokio::runtime::Handle::try_current()
+            .map_err(|_| ModuleError::ExecutionFailed("No tokio runtime available".to_string()))?;
+
+        let context = context.clone();
+        let module = self;
+
+        std::thread::scope(|s| {
+            s.spawn(|| handle.block_on(module.execute_async(&config, &context, connection)))
+                .join()
+                .unwrap()
+        })
+    }
+
+    fn check(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<ModuleOutput> {
+        let check_context = ModuleContext {
+            check_mode: true,
+            ..context.clone()
+        };
+        self.execute(params, &check_context)
+    }
+
+    fn diff(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<Option<Diff>> {
+        let config = AptRepositoryConfig::from_params(params)?;
+
+        let connection = match context.connection.clone() {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+
+        let handle = match tokio::runtime::Handle::try_current() {
+            Ok(h) => h,
+            Err(_) => return Ok(None),
+        };
+
+        let list_path = config.list_path();
+        let current = std::thread::scope(|s| {
+            s.spawn(|| handle.block_on(Self::read_file(connection.as_ref(), Path::new(&list_path))))
+                .join()
+                .unwrap()
+        });
+
+        Ok(config
+            .desired_content(&current)
+            .map(|after| Diff::new(current, after)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCKER: &str = "deb [arch=amd64 signed-by=/etc/apt/keyrings/docker.asc] https://download.docker.com/linux/ubuntu jammy stable";
+
+    #[test]
+    fn test_parse_source_line() {
+        let source = AptSource::parse(DOCKER).unwrap();
+        assert_eq!(source.kind, "deb");
+        assert_eq!(
+            source.options.as_deref(),
+            Some("arch=amd64 signed-by=/etc/apt/keyrings/docker.asc")
+        );
+        assert_eq!(source.uri, "https://download.docker.com/linux/ubuntu");
+        assert_eq!(source.suite, "jammy");
+        assert_eq!(source.components, vec!["stable"]);
+        assert_eq!(source.to_line(), DOCKER);
+        assert_eq!(
+            source.default_filename(),
+            "download_docker_com_linux_ubuntu"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_injection() {
+        assert!(AptSource::parse("deb http://example.com/ubuntu jammy main\nrm -rf /").is_err());
+        assert!(AptSource::parse("deb http://example.com/$(reboot) jammy main").is_err());
+        assert!(AptSource::parse("deb http://example.com jammy main; reboot").is_err());
+        assert!(AptSource::parse("deb [trusted=yes`id`] http://example.com jammy main").is_err());
+        assert!(AptSource::parse("rpm http://example.com jammy main").is_err());
+        assert!(AptSource::parse("deb http://example.com jammy").is_err());
+        assert!(AptSource::parse("deb http://example.com/repo ./").is_ok());
+    }
+
+    #[test]
+    fn test_add_and_remove_source() {
+        let source = AptSource::parse("deb http://example.com/ubuntu jammy main").unwrap();
+
+        let added = add_source("# managed\n", &source).unwrap();
+        assert_eq!(
+            added,
+            "# managed\ndeb http://example.com/ubuntu jammy main\n"
+        );
+
+        // Extra whitespace in an existing entry still counts as present
+        assert!(add_source("deb  http://example.com/ubuntu   jammy main\n", &source).is_none());
+
+        let removed = remove_source(&added, &source).unwrap();
+        assert_eq!(removed, "# managed\n");
+        assert!(remove_source(&removed, &source).is_none());
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let mut params = ModuleParams::new();
+        params.insert("repo".to_string(), serde_json::json!(DOCKER));
+        params.insert(
+            "filename".to_string(),
+            serde_json::json!("../../etc/passwd"),
+        );
+        assert!(AptRepositoryConfig::from_params(&params).is_err());
+
+        params.insert("filename".to_string(), serde_json::json!("docker"));
+        params.insert(
+            "key_url".to_string(),
+            serde_json::json!("https://example.com/key.gpg' && reboot '"),
+        );
+        assert!(AptRepositoryConfig::from_params(&params).is_err());
+
+        params.insert(
+            "key_url".to_string(),
+            serde_json::json!("https://download.docker.com/linux/ubuntu/gpg"),
+        );
+        let config = AptRepositoryConfig::from_params(&params).unwrap();
+        assert_eq!(config.list_path(), "/etc/apt/sources.list.d/docker.list");
+        assert_eq!(config.key_path, "/etc/apt/keyrings/docker.asc");
+    }
+}
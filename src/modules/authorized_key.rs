@@ -4,6 +4,7 @@
 //! - Adding and removing SSH public keys
 //! - Key options (command, from, environment, etc.)
 //! - Exclusive mode for complete key management
+//! - Unified diffs of added and removed keys in diff mode, also in check mode
 //! - Key validation and format checking
 //! - Both local and remote execution
 
@@ -286,14 +287,7 @@ pub struct AuthorizedKeyModule;
 impl AuthorizedKeyModule {
     /// Get execution options with become support if needed
     fn get_exec_options(context: &ModuleContext) -> ExecuteOptions {
-        let mut options = ExecuteOptions::new();
-        if context.r#become {
-            options = options.with_escalation(context.become_user.clone());
-            if let Some(ref method) = context.become_method {
-                options.escalate_method = Some(method.clone());
-            }
-        }
-        options
+        context.execute_options()
     }
 
     /// Execute a command via connection
@@ -314,7 +308,7 @@ impl AuthorizedKeyModule {
                 .join()
                 .unwrap()
         })
-        .map_err(|e| ModuleError::ExecutionFailed(format!("Connection error: {}", e)))?;
+        .map_err(ModuleError::from)?;
 
         Ok((result.success, result.stdout, result.stderr))
     }
@@ -454,9 +448,7 @@ impl AuthorizedKeyModule {
             .join()
             .unwrap()
         })
-        .map_err(|e| {
-            ModuleError::ExecutionFailed(format!("Failed to write authorized_keys: {}", e))
-        })?;
+        .map_err(|e| ModuleError::from_connection("Failed to write authorized_keys", e))?;
 
         // Set ownership and permissions
         let dir_path = Path::new(path).parent().unwrap_or(Path::new("/"));
@@ -519,6 +511,60 @@ impl AuthorizedKeyModule {
         existing_keys.len() != original_len
     }
 
+    /// Render the updated file, keeping comments and blank lines in place.
+    ///
+    /// Existing key lines are replaced by their updated entry or dropped if
+    /// the key was removed; keys not yet in the file are appended.
+    fn render_lines(existing_lines: &[String], keys: &[AuthorizedKey]) -> Vec<String> {
+        let mut emitted = vec![false; keys.len()];
+        let mut lines = Vec::with_capacity(existing_lines.len() + 1);
+
+        for line in existing_lines {
+            let trimmed = line.trim();
+            let parsed = if trimmed.is_empty() || trimmed.starts_with('#') {
+                None
+            } else {
+                AuthorizedKey::parse(line).ok()
+            };
+            match parsed {
+                Some(existing) => {
+                    if let Some(i) = keys.iter().position(|k| k.same_key(&existing)) {
+                        if !emitted[i] {
+                            emitted[i] = true;
+                            lines.push(keys[i].to_line());
+                        }
+                    }
+                }
+                None => lines.push(line.clone()),
+            }
+        }
+
+        lines.extend(
+            keys.iter()
+                .zip(emitted)
+                .filter(|(_, done)| !done)
+                .map(|(key, _)| key.to_line()),
+        );
+        lines
+    }
+
+    /// Unified diff of the authorized_keys file, one line per key
+    fn key_diff(path: &str, existing_lines: &[String], new_lines: &[String]) -> Diff {
+        let join = |lines: &[String]| {
+            if lines.is_empty() {
+                String::new()
+            } else {
+                format!("{}\n", lines.join("\n"))
+            }
+        };
+        let before = join(existing_lines);
+        Diff::for_file(
+            Path::new(path),
+            (!existing_lines.is_empty()).then_some(before.as_str()),
+            &join(new_lines),
+        )
+    }
+
     /// Execute locally using filesystem operations
     fn execute_local(
         context: &ModuleContext,
@@ -594,8 +640,13 @@ impl AuthorizedKeyModule {
             )));
         }
 
+        let new_content = Self::render_lines(&existing_lines, &existing_keys);
+        let diff = context
+            .diff_mode
+            .then(|| Self::key_diff(&authorized_keys_path, &existing_lines, &new_content));
+
         if context.check_mode {
-            return Ok(ModuleOutput::changed(format!(
+            let mut output = ModuleOutput::changed(format!(
                 "Would {} key in '{}'",
                 if state == KeyState::Present {
                     "add"
@@ -603,7 +654,11 @@ impl AuthorizedKeyModule {
                     "remove"
                 },
                 authorized_keys_path
-            )));
+            ));
+            if let Some(diff) = diff {
+                output = output.with_diff(diff);
+            }
+            return Ok(output);
         }
 
         // Create .ssh directory if needed
@@ -617,7 +672,6 @@ impl AuthorizedKeyModule {
         }
 
         // Write the file
-        let new_content: Vec<String> = existing_keys.iter().map(|k| k.to_line()).collect();
         let content = if new_content.is_empty() {
             String::new()
         } else {
@@ -639,8 +693,8 @@ impl AuthorizedKeyModule {
 
         let mut output = ModuleOutput::changed(format!("{} in '{}'", action, authorized_keys_path));
 
-        if context.diff_mode {
-            output = output.with_diff(Diff::new(existing_lines.join("\n"), new_content.join("\n")));
+        if let Some(diff) = diff {
+            output = output.with_diff(diff);
         }
 
         Ok(output)
@@ -744,8 +798,13 @@ impl AuthorizedKeyModule {
             )));
         }
 
+        let new_content = Self::render_lines(&existing_lines, &existing_keys);
+        let diff = context
+            .diff_mode
+            .then(|| Self::key_diff(&authorized_keys_path, &existing_lines, &new_content));
+
         if context.check_mode {
-            return Ok(ModuleOutput::changed(format!(
+            let mut output = ModuleOutput::changed(format!(
                 "Would {} key in '{}'",
                 if state == KeyState::Present {
                     "add"
@@ -753,11 +812,14 @@ impl AuthorizedKeyModule {
                     "remove"
                 },
                 authorized_keys_path
-            )));
+            ));
+            if let Some(diff) = diff {
+                output = output.with_diff(diff);
+            }
+            return Ok(output);
         }
 
         // Write the updated keys
-        let new_content: Vec<String> = existing_keys.iter().map(|k| k.to_line()).collect();
         Self::write_authorized_keys(
             connection,
             &authorized_keys_path,
@@ -779,8 +841,8 @@ impl AuthorizedKeyModule {
 
         let mut output = ModuleOutput::changed(format!("{} in '{}'", action, authorized_keys_path));
 
-        if context.diff_mode {
-            output = output.with_diff(Diff::new(existing_lines.join("\n"), new_content.join("\n")));
+        if let Some(diff) = diff {
+            output = output.with_diff(diff);
         }
 
         Ok(output)
@@ -1162,6 +1224,29 @@ mod tests {
         assert_eq!(shell_escape("with'quote"), "'with'\\''quote'");
     }
 
+    #[test]
+    fn test_render_lines_keeps_comments() {
+        let existing = vec![
+            "# Managed by ops".to_string(),
+            TEST_RSA_KEY.to_string(),
+            TEST_ED25519_KEY.to_string(),
+        ];
+        let new_key =
+            AuthorizedKey::parse("ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAB new@host").unwrap();
+        let keys = vec![AuthorizedKey::parse(TEST_RSA_KEY).unwrap(), new_key];
+
+        let lines = AuthorizedKeyModule::render_lines(&existing, &keys);
+
+        assert_eq!(
+            lines,
+            vec![
+                "# Managed by ops".to_string(),
+                TEST_RSA_KEY.to_string(),
+                "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAB new@host".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_keys() {
         let lines = vec![
@@ -2,10 +2,14 @@
 //!
 //! This module inserts, updates, or removes blocks of multi-line text
 //! surrounded by customizable marker comments.
+//!
+//! With `validate`, the command (`%s` stands for the file) runs against the
+//! edited content in a temp file first; the file is only written if it
+//! succeeds.
 
 use super::{
-    Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
-    ModuleResult, ParamExt,
+    set_local_ownership, validate_local_content, Diff, Module, ModuleClassification, ModuleContext,
+    ModuleError, ModuleOutput, ModuleParams, ModuleResult, ParamExt,
 };
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
@@ -45,6 +49,29 @@ impl BlockinfileModule {
         Ok(content.lines().map(|s| s.to_string()).collect())
     }
 
+    /// Error for a missing file when `create` is not set
+    fn missing_file(path: &str) -> ModuleError {
+        ModuleError::ExecutionFailed(format!(
+            "File '{}' does not exist (set create: true to create it)",
+            path
+        ))
+    }
+
+    /// File content for a list of lines
+    fn render(lines: &[String]) -> String {
+        if lines.is_empty() {
+            String::new()
+        } else {
+            format!("{}\n", lines.join("\n"))
+        }
+    }
+
+    /// Diff of the edit; a file that did not exist shows every line as added
+    fn content_diff(path: &Path, existed: bool, before: &[String], after: &[String]) -> Diff {
+        let before = existed.then(|| Self::render(before));
+        Diff::for_file(path, before.as_deref(), &Self::render(after))
+    }
+
     /// Write lines to file
     fn write_file(
         path: &Path,
@@ -53,10 +80,7 @@ impl BlockinfileModule {
         mode: Option<u32>,
     ) -> ModuleResult<()> {
         if !path.exists() && !create {
-            return Err(ModuleError::ExecutionFailed(format!(
-                "File '{}' does not exist and create=false",
-                path.display()
-            )));
+            return Err(Self::missing_file(&path.display().to_string()));
         }
 
         // Create parent directories if needed
@@ -66,13 +90,7 @@ impl BlockinfileModule {
             }
         }
 
-        let content = if lines.is_empty() {
-            String::new()
-        } else {
-            format!("{}\n", lines.join("\n"))
-        };
-
-        fs::write(path, content)?;
+        fs::write(path, Self::render(lines))?;
 
         if let Some(mode) = mode {
             fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
@@ -258,15 +276,16 @@ impl Module for BlockinfileModule {
             .get_string("backup_suffix")?
             .unwrap_or_else(|| "~".to_string());
         let mode = params.get_u32("mode")?;
+        let owner = params.get_string("owner")?;
+        let group = params.get_string("group")?;
+        let validate = params.get_string("validate")?;
 
         let (begin_marker, end_marker) = Self::create_markers(&marker);
 
         // Check if file exists
-        if !path.exists() && !create {
-            return Err(ModuleError::ExecutionFailed(format!(
-                "File '{}' does not exist",
-                path_str
-            )));
+        let file_exists = path.exists();
+        if !file_exists && !create {
+            return Err(Self::missing_file(&path_str));
         }
 
         // Read current content
@@ -304,12 +323,18 @@ impl Module for BlockinfileModule {
         // In check mode, don't actually write
         if context.check_mode {
             let diff = if context.diff_mode {
-                Some(Diff::new(original_lines.join("\n"), lines.join("\n")))
+                Some(Self::content_diff(
+                    path,
+                    file_exists,
+                    &original_lines,
+                    &lines,
+                ))
             } else {
                 None
             };
 
-            let mut output = ModuleOutput::changed(format!("Would modify '{}'", path_str));
+            let verb = if file_exists { "modify" } else { "create" };
+            let mut output = ModuleOutput::changed(format!("Would {} '{}'", verb, path_str));
 
             if let Some(d) = diff {
                 output = output.with_diff(d);
@@ -318,6 +343,10 @@ impl Module for BlockinfileModule {
             return Ok(output);
         }
 
+        if let Some(ref validate) = validate {
+            validate_local_content(validate, Self::render(&lines).as_bytes())?;
+        }
+
         // Create backup if requested
         let backup_file = if backup {
             Self::create_backup(path, &backup_suffix)?
@@ -327,15 +356,22 @@ impl Module for BlockinfileModule {
 
         // Write the file
         Self::write_file(path, &lines, create, mode)?;
+        set_local_ownership(path, owner.as_deref(), group.as_deref())?;
 
-        let mut output = ModuleOutput::changed(format!("Modified '{}'", path_str));
+        let verb = if file_exists { "Modified" } else { "Created" };
+        let mut output = ModuleOutput::changed(format!("{} '{}'", verb, path_str));
 
         if let Some(backup_path) = backup_file {
             output = output.with_data("backup_file", serde_json::json!(backup_path));
         }
 
         if context.diff_mode {
-            output = output.with_diff(Diff::new(original_lines.join("\n"), lines.join("\n")));
+            output = output.with_diff(Self::content_diff(
+                path,
+                file_exists,
+                &original_lines,
+                &lines,
+            ));
         }
 
         Ok(output)
@@ -581,6 +617,91 @@ mod tests {
         assert!(begin_pos > 0);
     }
 
+    #[test]
+    fn test_blockinfile_create_sets_mode_and_reports_added_content() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("new_file.txt");
+
+        let module = BlockinfileModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "path".to_string(),
+            serde_json::json!(path.to_str().unwrap()),
+        );
+        params.insert("block".to_string(), serde_json::json!("new block"));
+        params.insert("create".to_string(), serde_json::json!(true));
+        params.insert("mode".to_string(), serde_json::json!("0600"));
+        params.insert(
+            "owner".to_string(),
+            serde_json::json!(nix::unistd::getuid().as_raw().to_string()),
+        );
+
+        let context = ModuleContext::default().with_diff_mode(true);
+        let result = module.execute(&params, &context).unwrap();
+
+        assert!(result.changed);
+        assert!(result.msg.starts_with("Created"));
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("new block"));
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+
+        let diff = result.diff.unwrap();
+        assert!(diff.before.is_empty());
+        let details = diff.details.unwrap();
+        assert!(details.contains("--- /dev/null"));
+        assert!(details.contains("+new block"));
+    }
+
+    #[test]
+    fn test_blockinfile_missing_file_without_create_fails() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("missing.txt");
+
+        let module = BlockinfileModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "path".to_string(),
+            serde_json::json!(path.to_str().unwrap()),
+        );
+        params.insert("block".to_string(), serde_json::json!("new block"));
+
+        let context = ModuleContext::default();
+        let err = module.execute(&params, &context).unwrap_err();
+
+        assert!(err.to_string().contains("does not exist"));
+        assert!(err.to_string().contains("create: true"));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_blockinfile_failing_validate_leaves_file_unchanged() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("sshd_config");
+        fs::write(&path, "Port 22\n").unwrap();
+
+        let module = BlockinfileModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "path".to_string(),
+            serde_json::json!(path.to_str().unwrap()),
+        );
+        params.insert(
+            "block".to_string(),
+            serde_json::json!("PermitRootLogin maybe"),
+        );
+        params.insert(
+            "validate".to_string(),
+            serde_json::json!("! grep -q maybe %s || { echo 'bad PermitRootLogin' >&2; false; }"),
+        );
+
+        let context = ModuleContext::default();
+        let err = module.execute(&params, &context).unwrap_err();
+
+        assert!(err.to_string().contains("bad PermitRootLogin"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Port 22\n");
+    }
+
     #[test]
     fn test_blockinfile_check_mode() {
         let temp = TempDir::new().unwrap();
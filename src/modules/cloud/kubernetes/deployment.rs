@@ -657,6 +657,7 @@ impl K8sDeploymentModule {
             }
 
             // Check if only replicas changed (use scale instead of full update)
+            let current_replicas = dep.replicas;
             if dep.replicas != config.replicas
                 && !config.force
                 && config.image.as_deref() == Some(&dep.image)
@@ -686,7 +687,7 @@ impl K8sDeploymentModule {
 
                 return Ok(ModuleOutput::changed(format!(
                     "Scaled deployment '{}' from {} to {} replicas",
-                    config.name, dep.replicas, config.replicas
+                    config.name, current_replicas, config.replicas
                 ))
                 .with_data("deployment", serde_json::to_value(&final_dep).unwrap()));
             }
@@ -43,8 +43,8 @@
 //! ```
 
 use crate::modules::{
-    Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
-    ModuleResult, ParallelizationHint, ParamExt,
+    Diff, Module, ModuleClassification, ModuleContext, ModuleOutput, ModuleParams, ModuleResult,
+    ParallelizationHint, ParamExt,
 };
 use serde_json::json;
 use std::collections::BTreeMap;
@@ -172,10 +172,6 @@ impl Module for K8sSecretModule {
         &["name"]
     }
 
-    fn supported_platforms(&self) -> &[&'static str] {
-        &["any"]
-    }
-
     fn execute(
         &self,
         params: &ModuleParams,
@@ -196,9 +192,8 @@ impl Module for K8sSecretModule {
         let mut output = self.execute(params, context)?;
         if output.changed {
             output.diff = Some(Diff::new(
+                "current state",
                 format!("Secret {} in namespace {}", config.name, config.namespace),
-                "current state".to_string(),
-                "desired state".to_string(),
             ));
         }
         Ok(output)
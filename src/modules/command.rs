@@ -7,8 +7,9 @@
 //! execution via async connections (SSH, Docker, etc.).
 
 use super::{
-    validate_env_var_name, validate_path_param, Diff, Module, ModuleClassification, ModuleContext,
-    ModuleError, ModuleOutput, ModuleParams, ModuleResult, ParamExt,
+    chdir_not_found, command_warnings, ensure_local_chdir, validate_env_var_name,
+    validate_path_param, Diff, Module, ModuleClassification, ModuleContext, ModuleError,
+    ModuleOutput, ModuleParams, ModuleResult, ParamExt,
 };
 use crate::connection::{Connection, ExecuteOptions};
 use std::path::Path;
@@ -91,10 +92,9 @@ impl CommandModule {
         };
 
         // Set working directory
-        if let Some(chdir) = params.get_string("chdir")? {
-            command.current_dir(&chdir);
-        } else if let Some(ref work_dir) = context.work_dir {
-            command.current_dir(work_dir);
+        if let Some(dir) = context.chdir(params)? {
+            ensure_local_chdir(&dir)?;
+            command.current_dir(dir);
         }
 
         // Set environment variables (with validation)
@@ -117,13 +117,11 @@ impl CommandModule {
         params: &ModuleParams,
         context: &ModuleContext,
     ) -> ModuleResult<ExecuteOptions> {
-        let mut options = ExecuteOptions::new();
+        let mut options = context.execute_options();
 
         // Set working directory
-        if let Some(chdir) = params.get_string("chdir")? {
-            options = options.with_cwd(chdir);
-        } else if let Some(ref work_dir) = context.work_dir {
-            options = options.with_cwd(work_dir.clone());
+        if let Some(dir) = context.chdir(params)? {
+            options = options.with_cwd(dir);
         }
 
         // Set environment variables (with validation)
@@ -144,13 +142,6 @@ impl CommandModule {
             }
         }
 
-        // Handle privilege escalation from context
-        if context.r#become {
-            options.escalate = true;
-            options.escalate_user = context.become_user.clone();
-            options.escalate_method = context.become_method.clone();
-        }
-
         Ok(options)
     }
 
@@ -294,6 +285,7 @@ impl CommandModule {
         let params_clone = params.clone();
         let check_mode = context.check_mode;
         let cmd_display = self.get_command_string(params)?;
+        let argv = params.get_vec_string("argv")?;
         let options = self.build_execute_options(params, context)?;
         let warn_on_stderr = params.get_bool_or("warn", true);
 
@@ -314,16 +306,21 @@ impl CommandModule {
                 )));
             }
 
-            // Execute via connection
-            let result = connection
-                .execute(&cmd_display, Some(options))
-                .await
-                .map_err(|e| {
-                    ModuleError::ExecutionFailed(format!(
-                        "Failed to execute '{}': {}",
-                        cmd_display, e
-                    ))
-                })?;
+            // Fail clearly rather than letting the remote `cd` error surface
+            if let Some(ref dir) = options.cwd {
+                if let Ok(false) = connection.is_directory(Path::new(dir)).await {
+                    return Err(chdir_not_found(dir));
+                }
+            }
+
+            // argv is passed through as is, without shell word splitting
+            let result = match argv {
+                Some(ref argv) => connection.execute_argv(argv, Some(options)).await,
+                None => connection.execute(&cmd_display, Some(options)).await,
+            }
+            .map_err(|e| {
+                ModuleError::from_connection(format_args!("Failed to execute '{}'", cmd_display), e)
+            })?;
 
             if result.success {
                 let mut output = ModuleOutput::changed(format!(
@@ -403,11 +400,15 @@ impl Module for CommandModule {
         context: &ModuleContext,
     ) -> ModuleResult<ModuleOutput> {
         // Dispatch to local or remote execution based on connection
-        if let Some(ref connection) = context.connection {
-            self.execute_remote(params, context, connection.clone())
+        let mut output = if let Some(ref connection) = context.connection {
+            self.execute_remote(params, context, connection.clone())?
         } else {
-            self.execute_local(params, context)
-        }
+            self.execute_local(params, context)?
+        };
+        output
+            .warnings
+            .extend(command_warnings(&self.get_command_string(params)?, params));
+        Ok(output)
     }
 
     fn check(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<ModuleOutput> {
@@ -10,18 +10,23 @@
 //! - Automatic backup creation
 //! - Directory mode control for created parent directories
 //! - Symlink following on source files
+//! - `unsafe_writes` fallback to an in-place write when atomic replacement fails
+//! - SELinux context (`seuser`, `serole`, `setype`, `selevel`) restored on
+//!   enforcing remote hosts
+//! - Remote transfers report their size and timing in `data.transfer_stats`
 
+use super::file::{RemoteOwnership, SelinuxContext};
 use super::{
-    Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
-    ModuleResult, ParamExt,
+    resolve_first_found, validate_local_content, Diff, Module, ModuleClassification, ModuleContext,
+    ModuleError, ModuleOutput, ModuleParams, ModuleResult, ParamExt,
 };
-use crate::connection::{Connection, TransferOptions};
+use crate::connection::{atomic, Connection, TransferOptions, TransferStats};
 use std::fs;
-use std::io::{Read, Write};
+use std::io::Read;
 use std::os::unix::fs::{MetadataExt, PermissionsExt};
-use std::path::Path;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
 
 /// Module for copying files
 pub struct CopyModule;
@@ -81,72 +86,70 @@ impl CopyModule {
         Ok(src_checksum != dest_checksum)
     }
 
-    fn copy_content(content: &str, dest: &Path) -> ModuleResult<()> {
-        let mut file = fs::File::create(dest)?;
-        file.write_all(content.as_bytes())?;
-        Ok(())
+    /// Write `content` to `dest`, returning a warning if `unsafe_writes`
+    /// had to rewrite it in place
+    fn copy_content(
+        content: &str,
+        dest: &Path,
+        mode: Option<u32>,
+        unsafe_writes: bool,
+    ) -> ModuleResult<Option<String>> {
+        Ok(atomic::write_file_with_fallback(
+            dest,
+            content.as_bytes(),
+            mode,
+            unsafe_writes,
+        )?)
     }
 
-    fn copy_file(src: &Path, dest: &Path, force: bool) -> ModuleResult<()> {
-        if dest.exists() && !force {
-            let dest_meta = fs::metadata(dest)?;
-            if dest_meta.permissions().readonly() {
-                return Err(ModuleError::PermissionDenied(format!(
-                    "Destination '{}' is read-only and force is not set",
-                    dest.display()
-                )));
-            }
-        }
-
-        fs::copy(src, dest)?;
-        Ok(())
+    /// Copy `src` to `dest`, returning a warning if `unsafe_writes` had to
+    /// rewrite it in place
+    fn copy_file(
+        src: &Path,
+        dest: &Path,
+        mode: Option<u32>,
+        unsafe_writes: bool,
+    ) -> ModuleResult<Option<String>> {
+        // Like fs::copy, carry the source permissions over unless a mode is given
+        let src_mode = fs::metadata(src)?.permissions().mode() & 0o7777;
+        Ok(atomic::write_file_with_fallback(
+            dest,
+            &fs::read(src)?,
+            mode.or(Some(src_mode)),
+            unsafe_writes,
+        )?)
     }
 
-    /// Validate file content using a validation command
-    /// The command should use %s as a placeholder for the file path
-    fn validate_file(path: &Path, validate_cmd: &str) -> ModuleResult<()> {
-        // Replace %s with the actual file path
-        let cmd = validate_cmd.replace("%s", &path.to_string_lossy());
-
-        // Execute via shell to handle complex commands
-        let output = Command::new("sh")
-            .arg("-c")
-            .arg(&cmd)
-            .output()
-            .map_err(|e| {
-                ModuleError::ExecutionFailed(format!("Failed to run validation command: {}", e))
-            })?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(ModuleError::ValidationFailed(format!(
-                "Validation command failed: {}",
-                stderr.trim()
-            )));
-        }
-
-        Ok(())
+    /// Result for `force: false` when the destination already exists
+    fn kept_existing(final_dest: &Path) -> ModuleOutput {
+        ModuleOutput::ok(format!(
+            "File '{}' already exists and force is false",
+            final_dest.display()
+        ))
+        .with_data("dest", serde_json::json!(final_dest.to_string_lossy()))
     }
 
     /// Create parent directories with specified mode
+    ///
+    /// The mode is applied only to directories created here, never to
+    /// ancestors that already existed.
     fn create_parent_dirs(path: &Path, directory_mode: Option<u32>) -> ModuleResult<()> {
-        if let Some(parent) = path.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent)?;
-
-                // Apply directory mode if specified
-                if let Some(mode) = directory_mode {
-                    // Walk up and apply mode to each created directory
-                    let mut current = parent.to_path_buf();
-                    while !current.as_os_str().is_empty() {
-                        if current.exists() {
-                            fs::set_permissions(&current, fs::Permissions::from_mode(mode))?;
-                        }
-                        if !current.pop() {
-                            break;
-                        }
-                    }
-                }
+        let Some(parent) = path.parent() else {
+            return Ok(());
+        };
+
+        let missing: Vec<&Path> = parent
+            .ancestors()
+            .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(parent)?;
+        if let Some(mode) = directory_mode {
+            for dir in missing {
+                fs::set_permissions(dir, fs::Permissions::from_mode(mode))?;
             }
         }
         Ok(())
@@ -178,6 +181,161 @@ impl CopyModule {
         format!("{:x}", hasher.finish())
     }
 
+    /// Resolve the file path a copy writes to
+    ///
+    /// When `dest` is a directory (or ends with `/`) the source's basename is
+    /// appended, so change detection runs against the final file.
+    fn resolve_dest(dest: &str, src: Option<&str>, dest_is_dir: bool) -> ModuleResult<PathBuf> {
+        let dest_path = Path::new(dest);
+        if !dest_is_dir && !dest.ends_with('/') {
+            return Ok(dest_path.to_path_buf());
+        }
+
+        let src_str = src.ok_or_else(|| {
+            ModuleError::InvalidParameter(
+                "Cannot copy content to a directory without specifying filename".to_string(),
+            )
+        })?;
+        let file_name = Path::new(src_str).file_name().ok_or_else(|| {
+            ModuleError::InvalidParameter("Cannot determine filename from source".to_string())
+        })?;
+        Ok(dest_path.join(file_name))
+    }
+
+    /// Describe a copy, distinguishing a newly created file from a changed one
+    fn change_message(
+        check_mode: bool,
+        dest_exists: bool,
+        src_display: &str,
+        dest: &Path,
+    ) -> String {
+        match (check_mode, dest_exists) {
+            (true, true) => format!("Would copy {} to '{}'", src_display, dest.display()),
+            (true, false) => format!("Would create '{}' from {}", dest.display(), src_display),
+            (false, true) => format!("Copied {} to '{}'", src_display, dest.display()),
+            (false, false) => format!("Created '{}' from {}", dest.display(), src_display),
+        }
+    }
+
+    /// Copy a local source directory recursively
+    ///
+    /// A `src` ending with `/` copies the directory's contents into `dest`;
+    /// otherwise the directory itself is created under `dest`. Each file is
+    /// copied with the regular single-file logic so idempotence, check mode
+    /// and `directory_mode` behave per file. In diff mode the per-file diffs
+    /// of changed files are combined into one.
+    fn copy_directory(
+        &self,
+        src: &str,
+        dest: &str,
+        params: &ModuleParams,
+        context: &ModuleContext,
+    ) -> ModuleResult<ModuleOutput> {
+        let src_path = Path::new(src);
+        let base = if src.ends_with('/') {
+            PathBuf::from(dest)
+        } else {
+            let dir_name = src_path.file_name().ok_or_else(|| {
+                ModuleError::InvalidParameter("Cannot determine directory name from source".into())
+            })?;
+            Path::new(dest).join(dir_name)
+        };
+
+        let mut changed_files = Vec::new();
+        let mut files = Vec::new();
+        let mut diffs = Vec::new();
+        for entry in walkdir::WalkDir::new(src_path).sort_by_file_name() {
+            let entry = entry.map_err(|e| {
+                ModuleError::ExecutionFailed(format!("Failed to read source directory: {}", e))
+            })?;
+            // Symlinks to directories are skipped too; copying them as a
+            // source would recurse, forever if the link points at an ancestor
+            if entry.file_type().is_dir() || entry.path().is_dir() {
+                continue;
+            }
+            // Devices, FIFOs and sockets are not copied; reading one can
+            // block indefinitely
+            if !entry.path().is_file() {
+                continue;
+            }
+
+            let relative = entry
+                .path()
+                .strip_prefix(src_path)
+                .unwrap_or_else(|_| entry.path());
+            let file_dest = base.join(relative);
+
+            let mut file_params = params.clone();
+            file_params.insert(
+                "src".to_string(),
+                serde_json::json!(entry.path().to_string_lossy()),
+            );
+            file_params.insert(
+                "dest".to_string(),
+                serde_json::json!(file_dest.to_string_lossy()),
+            );
+
+            // The diff has to be taken before the file is overwritten
+            let diff = if context.diff_mode {
+                self.diff(&file_params, context)?
+            } else {
+                None
+            };
+            let file_output = self.execute(&file_params, context)?;
+            let file_dest = file_dest.to_string_lossy().to_string();
+
+            files.push(serde_json::json!({
+                "dest": file_dest,
+                "changed": file_output.changed,
+                "msg": file_output.msg,
+            }));
+            if file_output.changed {
+                diffs.extend(diff);
+                changed_files.push(file_dest);
+            }
+        }
+
+        let mut output = if changed_files.is_empty() {
+            ModuleOutput::ok(format!(
+                "Directory '{}' is already up to date",
+                base.display()
+            ))
+        } else if context.check_mode {
+            ModuleOutput::changed(format!(
+                "Would copy {} file(s) from '{}' to '{}'",
+                changed_files.len(),
+                src,
+                base.display()
+            ))
+        } else {
+            ModuleOutput::changed(format!(
+                "Copied {} file(s) from '{}' to '{}'",
+                changed_files.len(),
+                src,
+                base.display()
+            ))
+        };
+        if !diffs.is_empty() {
+            output = output.with_diff(Self::combine_diffs(&base, &diffs));
+        }
+        output = output
+            .with_data("dest", serde_json::json!(base.to_string_lossy()))
+            .with_data("changed_files", serde_json::json!(changed_files))
+            .with_data("files", serde_json::json!(files));
+
+        Ok(output)
+    }
+
+    /// Merge per-file diffs of a directory copy into a single diff
+    fn combine_diffs(base: &Path, diffs: &[Diff]) -> Diff {
+        let details: String = diffs.iter().filter_map(|d| d.details.as_deref()).collect();
+        Diff::new(
+            format!("(current state of {})", base.display()),
+            format!("({} changed file(s) under {})", diffs.len(), base.display()),
+        )
+        .with_details(details)
+    }
+
     /// Async implementation for remote copy using connection
     async fn execute_remote_async(
         connection: Arc<dyn Connection + Send + Sync>,
@@ -187,73 +345,68 @@ impl CopyModule {
         mode: Option<u32>,
         owner: Option<&str>,
         group: Option<&str>,
+        force: bool,
         backup: bool,
         backup_suffix: &str,
+        unsafe_writes: bool,
+        selinux: Option<&SelinuxContext>,
         check_mode: bool,
         diff_mode: bool,
     ) -> ModuleResult<ModuleOutput> {
         let dest_path = Path::new(dest);
 
         // Determine the final destination path (handle directory destinations)
-        let final_dest = if connection.is_directory(dest_path).await.unwrap_or(false) {
-            if let Some(src_str) = src {
-                let src_path = Path::new(src_str);
-                dest_path.join(src_path.file_name().ok_or_else(|| {
-                    ModuleError::InvalidParameter(
-                        "Cannot determine filename from source".to_string(),
-                    )
-                })?)
-            } else {
-                return Err(ModuleError::InvalidParameter(
-                    "Cannot copy content to a directory without specifying filename".to_string(),
-                ));
-            }
-        } else {
-            dest_path.to_path_buf()
-        };
+        let dest_is_dir = connection.is_directory(dest_path).await.unwrap_or(false);
+        let final_dest = Self::resolve_dest(dest, src, dest_is_dir)?;
+
+        // Fail on unknown users or groups before touching the destination
+        let ownership = RemoteOwnership::resolve(connection.as_ref(), owner, group).await?;
 
         // Check if file already exists and get checksum
-        let (needs_copy, current_checksum) =
-            if connection.path_exists(&final_dest).await.unwrap_or(false) {
-                // Download current content to check if it differs
-                if let Some(content_str) = content {
-                    // Compare content
-                    match connection.download_content(&final_dest).await {
-                        Ok(existing) => {
-                            let existing_str = String::from_utf8_lossy(&existing);
-                            (
-                                existing_str.as_ref() != content_str,
-                                Some(Self::compute_checksum(&existing)),
-                            )
-                        }
-                        Err(_) => (true, None),
-                    }
-                } else if let Some(src_str) = src {
-                    // Compare file checksums
-                    let src_path = Path::new(src_str);
-                    if !src_path.exists() {
-                        return Err(ModuleError::ExecutionFailed(format!(
-                            "Source file '{}' does not exist",
-                            src_str
-                        )));
+        let dest_exists = connection.path_exists(&final_dest).await.unwrap_or(false);
+        if dest_exists && !force {
+            return Ok(Self::kept_existing(&final_dest));
+        }
+        let (needs_copy, current_checksum) = if dest_exists {
+            // Download current content to check if it differs
+            if let Some(content_str) = content {
+                // Compare content
+                match connection.download_content(&final_dest).await {
+                    Ok(existing) => {
+                        let existing_str = String::from_utf8_lossy(&existing);
+                        (
+                            existing_str.as_ref() != content_str,
+                            Some(Self::compute_checksum(&existing)),
+                        )
                     }
+                    Err(_) => (true, None),
+                }
+            } else if let Some(src_str) = src {
+                // Compare file checksums
+                let src_path = Path::new(src_str);
+                if !src_path.exists() {
+                    return Err(ModuleError::ExecutionFailed(format!(
+                        "Source file '{}' does not exist",
+                        src_str
+                    )));
+                }
 
-                    let src_content = fs::read(src_path).map_err(ModuleError::Io)?;
+                let src_content = fs::read(src_path).map_err(ModuleError::Io)?;
 
-                    match connection.download_content(&final_dest).await {
-                        Ok(existing) => {
-                            let src_checksum = Self::compute_checksum(&src_content);
-                            let dest_checksum = Self::compute_checksum(&existing);
-                            (src_checksum != dest_checksum, Some(dest_checksum))
-                        }
-                        Err(_) => (true, None),
+                match connection.download_content(&final_dest).await {
+                    Ok(existing) => {
+                        let src_checksum = Self::compute_checksum(&src_content);
+                        let dest_checksum = Self::compute_checksum(&existing);
+                        (src_checksum != dest_checksum, Some(dest_checksum))
                     }
-                } else {
-                    (false, None)
+                    Err(_) => (true, None),
                 }
             } else {
-                (true, None)
-            };
+                (false, None)
+            }
+        } else {
+            (true, None)
+        };
 
         // Check if only permissions need updating
         if !needs_copy {
@@ -276,9 +429,10 @@ impl CopyModule {
 
                 // Update permissions via chmod command
                 let chmod_cmd = format!("chmod {:o} {}", mode.unwrap(), final_dest.display());
-                connection.execute(&chmod_cmd, None).await.map_err(|e| {
-                    ModuleError::ExecutionFailed(format!("Failed to set permissions: {}", e))
-                })?;
+                connection
+                    .execute(&chmod_cmd, None)
+                    .await
+                    .map_err(|e| ModuleError::from_connection("Failed to set permissions", e))?;
 
                 return Ok(ModuleOutput::changed(format!(
                     "Changed permissions on '{}'",
@@ -302,8 +456,13 @@ impl CopyModule {
                 ""
             };
 
+            let new_text = content
+                .map(String::from)
+                .or_else(|| src.and_then(|s| fs::read_to_string(s).ok()));
             let diff = if diff_mode {
-                if let Some(content_str) = content {
+                if let (false, Some(after)) = (dest_exists, new_text.as_deref()) {
+                    Some(Diff::for_file(&final_dest, None, after))
+                } else if let Some(content_str) = content {
                     let before = if let Some(cksum) = current_checksum {
                         format!("(existing file with checksum {})", cksum)
                     } else {
@@ -320,10 +479,11 @@ impl CopyModule {
                 None
             };
 
-            let mut output = ModuleOutput::changed(format!(
-                "Would copy {} to '{}'",
+            let mut output = ModuleOutput::changed(Self::change_message(
+                true,
+                dest_exists,
                 src_display,
-                final_dest.display()
+                &final_dest,
             ));
 
             if let Some(d) = diff {
@@ -334,7 +494,7 @@ impl CopyModule {
         }
 
         // Create backup if requested
-        if backup && connection.path_exists(&final_dest).await.unwrap_or(false) {
+        if backup && dest_exists {
             let backup_path = format!("{}{}", final_dest.display(), backup_suffix);
             let backup_dest = Path::new(&backup_path);
 
@@ -344,9 +504,7 @@ impl CopyModule {
                     connection
                         .upload_content(&backup_content, backup_dest, None)
                         .await
-                        .map_err(|e| {
-                            ModuleError::ExecutionFailed(format!("Failed to create backup: {}", e))
-                        })?;
+                        .map_err(|e| ModuleError::from_connection("Failed to create backup", e))?;
                 }
                 Err(e) => {
                     return Err(ModuleError::ExecutionFailed(format!(
@@ -357,51 +515,80 @@ impl CopyModule {
             }
         }
 
+        // Remember the context of the file being replaced
+        let previous_context = match selinux {
+            Some(_) if dest_exists => {
+                SelinuxContext::remote_context(connection.as_ref(), &final_dest).await
+            }
+            _ => None,
+        };
+
         // Build transfer options
         let mut transfer_opts = TransferOptions::new();
         if let Some(m) = mode {
             transfer_opts = transfer_opts.with_mode(m);
         }
-        if let Some(o) = owner {
-            transfer_opts = transfer_opts.with_owner(o);
-        }
-        if let Some(g) = group {
-            transfer_opts = transfer_opts.with_group(g);
-        }
-        transfer_opts = transfer_opts.with_create_dirs();
+        transfer_opts = ownership.apply(transfer_opts).with_create_dirs();
 
         // Perform the copy
-        let src_display = if let Some(content_str) = content {
+        let start = Instant::now();
+        let (src_display, warning, bytes) = if let Some(content_str) = content {
             // Upload content directly
-            connection
-                .upload_content(content_str.as_bytes(), &final_dest, Some(transfer_opts))
-                .await
-                .map_err(|e| {
-                    ModuleError::ExecutionFailed(format!("Failed to upload content: {}", e))
-                })?;
-            "(content)".to_string()
+            let warning = atomic::upload_content_with_fallback(
+                connection.as_ref(),
+                content_str.as_bytes(),
+                &final_dest,
+                Some(transfer_opts),
+                unsafe_writes,
+            )
+            .await
+            .map_err(|e| {
+                ModuleError::ExecutionFailed(format!("Failed to upload content: {}", e))
+            })?;
+            ("(content)".to_string(), warning, content_str.len() as u64)
         } else if let Some(src_str) = src {
             // Upload file
             let src_path = Path::new(src_str);
-            connection
-                .upload(src_path, &final_dest, Some(transfer_opts))
-                .await
-                .map_err(|e| {
-                    ModuleError::ExecutionFailed(format!("Failed to upload file: {}", e))
-                })?;
-            src_str.to_string()
+            let warning = atomic::upload_with_fallback(
+                connection.as_ref(),
+                src_path,
+                &final_dest,
+                Some(transfer_opts),
+                unsafe_writes,
+            )
+            .await
+            .map_err(|e| ModuleError::ExecutionFailed(format!("Failed to upload file: {}", e)))?;
+            let bytes = fs::metadata(src_path).map(|m| m.len()).unwrap_or(0);
+            (src_str.to_string(), warning, bytes)
         } else {
             return Err(ModuleError::MissingParameter(
                 "Either 'src' or 'content' must be provided".to_string(),
             ));
         };
+        let transfer_stats = TransferStats::since(bytes, start);
+        transfer_stats.log("Upload", &final_dest);
+
+        if let Some(selinux) = selinux {
+            selinux
+                .apply_remote(
+                    connection.as_ref(),
+                    &final_dest,
+                    previous_context.as_deref(),
+                )
+                .await?;
+        }
 
         // Get file info from remote
-        let mut output = ModuleOutput::changed(format!(
-            "Copied {} to '{}'",
-            src_display,
-            final_dest.display()
+        let mut output = ModuleOutput::changed(Self::change_message(
+            false,
+            dest_exists,
+            &src_display,
+            &final_dest,
         ));
+        if let Some(warning) = warning {
+            output = output.with_warning(warning);
+        }
+        output = output.with_data("transfer_stats", transfer_stats.to_json());
 
         // Add file metadata if available
         if let Ok(stat) = connection.stat(&final_dest).await {
@@ -429,8 +616,11 @@ impl CopyModule {
         mode: Option<u32>,
         owner: Option<&str>,
         group: Option<&str>,
+        force: bool,
         backup: bool,
         backup_suffix: &str,
+        unsafe_writes: bool,
+        selinux: Option<&SelinuxContext>,
         check_mode: bool,
         diff_mode: bool,
     ) -> ModuleResult<ModuleOutput> {
@@ -445,8 +635,11 @@ impl CopyModule {
             mode,
             owner,
             group,
+            force,
             backup,
             backup_suffix,
+            unsafe_writes,
+            selinux,
             check_mode,
             diff_mode,
         ))
@@ -464,6 +657,7 @@ impl CopyModule {
         backup_suffix: &str,
         validate: Option<&str>,
         local_follow: bool,
+        unsafe_writes: bool,
         check_mode: bool,
         diff_mode: bool,
     ) -> ModuleResult<ModuleOutput> {
@@ -490,22 +684,11 @@ impl CopyModule {
         };
 
         // Check if dest is a directory
-        let final_dest = if dest_path.is_dir() {
-            if let Some(src_str) = src {
-                let src_path = Path::new(src_str);
-                dest_path.join(src_path.file_name().ok_or_else(|| {
-                    ModuleError::InvalidParameter(
-                        "Cannot determine filename from source".to_string(),
-                    )
-                })?)
-            } else {
-                return Err(ModuleError::InvalidParameter(
-                    "Cannot copy content to a directory without specifying filename".to_string(),
-                ));
-            }
-        } else {
-            dest_path.to_path_buf()
-        };
+        let final_dest = Self::resolve_dest(dest, src, dest_path.is_dir())?;
+        let dest_exists = final_dest.exists();
+        if dest_exists && !force {
+            return Ok(Self::kept_existing(&final_dest));
+        }
 
         // Check if copy is needed - use resolved source if available
         let needs_copy = if let Some(ref resolved) = resolved_src {
@@ -556,14 +739,19 @@ impl CopyModule {
 
         // In check mode, return what would happen
         if check_mode {
+            let new_text = source_content.clone().or_else(|| {
+                resolved_src
+                    .as_ref()
+                    .and_then(|path| fs::read_to_string(path).ok())
+            });
             let diff = if diff_mode {
-                if let Some(ref content_str) = source_content {
-                    let before = if final_dest.exists() {
-                        fs::read_to_string(&final_dest).unwrap_or_default()
+                if let Some(ref after) = new_text {
+                    let before = if dest_exists {
+                        Some(fs::read_to_string(&final_dest).unwrap_or_default())
                     } else {
-                        String::new()
+                        None
                     };
-                    Some(Diff::new(before, content_str.clone()))
+                    Some(Diff::for_file(&final_dest, before.as_deref(), after))
                 } else {
                     Some(Diff::new(
                         format!("(current state of {})", final_dest.display()),
@@ -574,10 +762,11 @@ impl CopyModule {
                 None
             };
 
-            let mut output = ModuleOutput::changed(format!(
-                "Would copy {} to '{}'",
-                src_display,
-                final_dest.display()
+            let mut output = ModuleOutput::changed(Self::change_message(
+                true,
+                dest_exists,
+                &src_display,
+                &final_dest,
             ));
 
             if let Some(d) = diff {
@@ -597,57 +786,38 @@ impl CopyModule {
         // Create parent directories with specified mode if needed
         Self::create_parent_dirs(&final_dest, directory_mode)?;
 
-        // For validation, we'll copy to a temp file first, validate, then move
-        let use_validation = validate.is_some();
-        let temp_dest = if use_validation {
-            let temp_name = format!(
-                "{}.rustible.tmp.{}",
-                final_dest.display(),
-                std::process::id()
-            );
-            std::path::PathBuf::from(temp_name)
-        } else {
-            final_dest.clone()
-        };
+        // Validate the proposed content before the destination is touched
+        if let Some(validate) = validate {
+            let proposed = match (&source_content, &resolved_src) {
+                (Some(content_str), _) => content_str.as_bytes().to_vec(),
+                (None, Some(resolved)) => fs::read(resolved)?,
+                (None, None) => Vec::new(),
+            };
+            validate_local_content(validate, &proposed)?;
+        }
 
-        // Perform the copy to temp or final destination
+        let mut warning = None;
         if let Some(ref content_str) = source_content {
-            Self::copy_content(content_str, &temp_dest)?;
+            warning = Self::copy_content(content_str, &final_dest, mode, unsafe_writes)?;
         } else if let Some(ref resolved) = resolved_src {
-            Self::copy_file(resolved, &temp_dest, force)?;
+            warning = Self::copy_file(resolved, &final_dest, mode, unsafe_writes)?;
         }
 
-        // Set permissions on temp file
-        Self::set_permissions(&temp_dest, mode)?;
-
-        // Run validation if specified
-        if let Some(validate_cmd) = validate {
-            match Self::validate_file(&temp_dest, validate_cmd) {
-                Ok(()) => {
-                    // Validation passed, move temp to final destination
-                    if use_validation {
-                        fs::rename(&temp_dest, &final_dest)?;
-                    }
-                }
-                Err(e) => {
-                    // Validation failed, clean up temp file
-                    if use_validation {
-                        let _ = fs::remove_file(&temp_dest);
-                    }
-                    return Err(e);
-                }
-            }
-        }
+        Self::set_permissions(&final_dest, mode)?;
 
-        let mut output = ModuleOutput::changed(format!(
-            "Copied {} to '{}'",
-            src_display,
-            final_dest.display()
+        let mut output = ModuleOutput::changed(Self::change_message(
+            false,
+            dest_exists,
+            &src_display,
+            &final_dest,
         ));
 
         if let Some(backup_path) = backup_file {
             output = output.with_data("backup_file", serde_json::json!(backup_path));
         }
+        if let Some(warning) = warning {
+            output = output.with_warning(warning);
+        }
 
         // Add file info to output
         let meta = fs::metadata(&final_dest)?;
@@ -703,6 +873,9 @@ impl Module for CopyModule {
         params: &ModuleParams,
         context: &ModuleContext,
     ) -> ModuleResult<ModuleOutput> {
+        // A list of sources picks the first candidate that exists
+        let resolved = resolve_first_found(params, context, "files")?;
+        let params = resolved.as_ref();
         let dest = params.get_string_required("dest")?;
         let src = params.get_string("src")?;
         let content = params.get_string("content")?;
@@ -717,6 +890,15 @@ impl Module for CopyModule {
         let group = params.get_string("group")?;
         let validate = params.get_string("validate")?;
         let local_follow = params.get_bool_or("local_follow", true);
+        let unsafe_writes = params.get_bool_or("unsafe_writes", false);
+        let selinux = SelinuxContext::from_params(params)?;
+
+        // Directory sources are copied recursively, one file at a time
+        if let Some(ref src_str) = src {
+            if content.is_none() && Path::new(src_str).is_dir() {
+                return self.copy_directory(src_str, &dest, params, context);
+            }
+        }
 
         // Check if we have a remote connection
         if let Some(ref connection) = context.connection {
@@ -729,8 +911,11 @@ impl Module for CopyModule {
                 mode,
                 owner.as_deref(),
                 group.as_deref(),
+                force,
                 backup,
                 &backup_suffix,
+                unsafe_writes,
+                SelinuxContext::is_enforcing(context).then_some(&selinux),
                 context.check_mode,
                 context.diff_mode,
             )
@@ -747,6 +932,7 @@ impl Module for CopyModule {
                 &backup_suffix,
                 validate.as_deref(),
                 local_follow,
+                unsafe_writes,
                 context.check_mode,
                 context.diff_mode,
             )
@@ -762,38 +948,68 @@ impl Module for CopyModule {
     }
 
     fn diff(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<Option<Diff>> {
+        // A list of sources picks the first candidate that exists
+        let resolved = resolve_first_found(params, context, "files")?;
+        let params = resolved.as_ref();
         let dest = params.get_string_required("dest")?;
-        let dest_path = Path::new(&dest);
         let content = params.get_string("content")?;
+        let src = params.get_string("src")?;
+
+        // Recursive directory copies combine the diffs of each changed file
+        if let Some(src_dir) = src.as_deref().filter(|s| Path::new(s).is_dir()) {
+            if content.is_none() {
+                let diff_context = ModuleContext {
+                    check_mode: true,
+                    diff_mode: true,
+                    ..context.clone()
+                };
+                return Ok(self
+                    .copy_directory(src_dir, &dest, params, &diff_context)?
+                    .diff);
+            }
+        }
 
         // For remote connections, we need to fetch the remote file content
         if let Some(ref connection) = context.connection {
             // Use async bridge to get remote file content
             let handle = tokio::runtime::Handle::current();
 
-            if let Some(content_str) = content {
-                let before = handle.block_on(async {
+            let dest_is_dir = handle
+                .block_on(connection.is_directory(Path::new(&dest)))
+                .unwrap_or(false);
+            let final_dest = Self::resolve_dest(&dest, src.as_deref(), dest_is_dir)?;
+            let dest_path = final_dest.as_path();
+
+            let before = || {
+                handle.block_on(async {
+                    if !connection.path_exists(dest_path).await.unwrap_or(false) {
+                        return None;
+                    }
                     match connection.download_content(dest_path).await {
-                        Ok(data) => String::from_utf8_lossy(&data).to_string(),
-                        Err(_) => String::new(),
+                        Ok(data) => Some(String::from_utf8_lossy(&data).to_string()),
+                        Err(_) => Some(String::new()),
                     }
-                });
-                return Ok(Some(Diff::new(before, content_str)));
+                })
+            };
+
+            if let Some(content_str) = content {
+                return Ok(Some(Diff::for_file(
+                    dest_path,
+                    before().as_deref(),
+                    &content_str,
+                )));
             }
 
-            let src = params.get_string("src")?;
             if let Some(src_str) = src {
                 let src_path = Path::new(&src_str);
                 if src_path.exists() {
                     let src_content = fs::read_to_string(src_path)
                         .unwrap_or_else(|_| "(binary file)".to_string());
-                    let dest_content = handle.block_on(async {
-                        match connection.download_content(dest_path).await {
-                            Ok(data) => String::from_utf8_lossy(&data).to_string(),
-                            Err(_) => String::new(),
-                        }
-                    });
-                    return Ok(Some(Diff::new(dest_content, src_content)));
+                    return Ok(Some(Diff::for_file(
+                        dest_path,
+                        before().as_deref(),
+                        &src_content,
+                    )));
                 }
             }
 
@@ -801,27 +1017,39 @@ impl Module for CopyModule {
         }
 
         // Local diff
+        let final_dest = Self::resolve_dest(&dest, src.as_deref(), Path::new(&dest).is_dir())?;
+        let dest_path = final_dest.as_path();
         if let Some(content_str) = content {
             let before = if dest_path.exists() {
-                fs::read_to_string(dest_path).unwrap_or_default()
+                Some(fs::read_to_string(dest_path).unwrap_or_default())
             } else {
-                String::new()
+                None
             };
-            return Ok(Some(Diff::new(before, content_str)));
+            return Ok(Some(Diff::for_file(
+                dest_path,
+                before.as_deref(),
+                &content_str,
+            )));
         }
 
-        let src = params.get_string("src")?;
         if let Some(src_str) = src {
             let src_path = Path::new(&src_str);
             if src_path.exists() {
                 let src_content =
                     fs::read_to_string(src_path).unwrap_or_else(|_| "(binary file)".to_string());
                 let dest_content = if dest_path.exists() {
-                    fs::read_to_string(dest_path).unwrap_or_else(|_| "(binary file)".to_string())
+                    Some(
+                        fs::read_to_string(dest_path)
+                            .unwrap_or_else(|_| "(binary file)".to_string()),
+                    )
                 } else {
-                    String::new()
+                    None
                 };
-                return Ok(Some(Diff::new(dest_content, src_content)));
+                return Ok(Some(Diff::for_file(
+                    dest_path,
+                    dest_content.as_deref(),
+                    &src_content,
+                )));
             }
         }
 
@@ -880,6 +1108,33 @@ mod tests {
         assert_eq!(fs::read_to_string(&dest).unwrap(), "Source content");
     }
 
+    #[test]
+    fn test_copy_failing_validate_leaves_dest_unchanged() {
+        let temp = TempDir::new().unwrap();
+        let dest = temp.path().join("app.conf");
+        fs::write(&dest, "port = 80\n").unwrap();
+
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("content".to_string(), serde_json::json!("port = eighty\n"));
+        params.insert(
+            "dest".to_string(),
+            serde_json::json!(dest.to_str().unwrap()),
+        );
+        params.insert(
+            "validate".to_string(),
+            serde_json::json!(
+                "grep -qE '^port = [0-9]+$' %s || { echo 'port must be numeric' >&2; false; }"
+            ),
+        );
+
+        let err = CopyModule
+            .execute(&params, &ModuleContext::default())
+            .unwrap_err();
+
+        assert!(err.to_string().contains("port must be numeric"));
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "port = 80\n");
+    }
+
     #[test]
     fn test_copy_idempotent() {
         let temp = TempDir::new().unwrap();
@@ -903,6 +1158,33 @@ mod tests {
         assert!(!result.changed);
     }
 
+    #[test]
+    fn test_copy_force_false_only_creates_missing_dest() {
+        let temp = TempDir::new().unwrap();
+        let existing = temp.path().join("existing.conf");
+        let missing = temp.path().join("missing.conf");
+        fs::write(&existing, "hand edited").unwrap();
+
+        let module = CopyModule;
+        let context = ModuleContext::default();
+        for dest in [&existing, &missing] {
+            let mut params: ModuleParams = HashMap::new();
+            params.insert("content".to_string(), serde_json::json!("managed"));
+            params.insert(
+                "dest".to_string(),
+                serde_json::json!(dest.to_str().unwrap()),
+            );
+            params.insert("force".to_string(), serde_json::json!(false));
+
+            let result = module.execute(&params, &context).unwrap();
+            assert_eq!(result.changed, dest == &missing);
+        }
+
+        // The existing file is left alone even though its content differs
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "hand edited");
+        assert_eq!(fs::read_to_string(&missing).unwrap(), "managed");
+    }
+
     #[test]
     fn test_copy_with_mode() {
         let temp = TempDir::new().unwrap();
@@ -938,12 +1220,19 @@ mod tests {
             serde_json::json!(dest.to_str().unwrap()),
         );
 
-        let context = ModuleContext::default().with_check_mode(true);
+        let context = ModuleContext::default()
+            .with_check_mode(true)
+            .with_diff_mode(true);
         let result = module.check(&params, &context).unwrap();
 
         assert!(result.changed);
-        assert!(result.msg.contains("Would copy"));
+        assert!(result.msg.contains("Would create"));
         assert!(!dest.exists()); // File should not be created in check mode
+
+        // A missing destination diffs as an all-additions new file
+        let details = result.diff.unwrap().details.unwrap();
+        assert!(details.starts_with("--- /dev/null\n"), "{}", details);
+        assert!(details.contains("+Hello"), "{}", details);
     }
 
     #[test]
@@ -973,4 +1262,175 @@ mod tests {
         assert!(backup_path.exists());
         assert_eq!(fs::read_to_string(&backup_path).unwrap(), "Old content");
     }
+
+    #[test]
+    fn test_copy_into_directory_dest() {
+        let temp = TempDir::new().unwrap();
+        let src = temp.path().join("source.txt");
+        let dest_dir = temp.path().join("target");
+        fs::write(&src, "Source content").unwrap();
+        fs::create_dir(&dest_dir).unwrap();
+
+        let module = CopyModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("src".to_string(), serde_json::json!(src.to_str().unwrap()));
+        params.insert(
+            "dest".to_string(),
+            serde_json::json!(dest_dir.to_str().unwrap()),
+        );
+
+        let context = ModuleContext::default();
+        let result = module.execute(&params, &context).unwrap();
+
+        let final_dest = dest_dir.join("source.txt");
+        assert!(result.changed);
+        assert_eq!(
+            result.data.get("dest"),
+            Some(&serde_json::json!(final_dest.to_str().unwrap()))
+        );
+        assert_eq!(fs::read_to_string(&final_dest).unwrap(), "Source content");
+
+        // Re-running compares against dest/source.txt, not the directory
+        let result = module.execute(&params, &context).unwrap();
+        assert!(!result.changed);
+    }
+
+    #[test]
+    fn test_copy_directory_recursive() {
+        let temp = TempDir::new().unwrap();
+        let src_dir = temp.path().join("site");
+        fs::create_dir_all(src_dir.join("css")).unwrap();
+        fs::write(src_dir.join("index.html"), "<html></html>").unwrap();
+        fs::write(src_dir.join("css/main.css"), "body {}").unwrap();
+        let dest_dir = temp.path().join("www");
+
+        let module = CopyModule;
+        let context = ModuleContext::default();
+
+        // Trailing slash copies the contents of the directory
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "src".to_string(),
+            serde_json::json!(format!("{}/", src_dir.display())),
+        );
+        params.insert(
+            "dest".to_string(),
+            serde_json::json!(dest_dir.to_str().unwrap()),
+        );
+
+        let result = module.execute(&params, &context).unwrap();
+        assert!(result.changed);
+        assert_eq!(
+            fs::read_to_string(dest_dir.join("index.html")).unwrap(),
+            "<html></html>"
+        );
+        assert_eq!(
+            fs::read_to_string(dest_dir.join("css/main.css")).unwrap(),
+            "body {}"
+        );
+
+        let result = module.execute(&params, &context).unwrap();
+        assert!(!result.changed);
+
+        // Without the trailing slash the directory itself is copied
+        params.insert(
+            "src".to_string(),
+            serde_json::json!(src_dir.to_str().unwrap()),
+        );
+        let result = module.execute(&params, &context).unwrap();
+        assert!(result.changed);
+        assert!(dest_dir.join("site/css/main.css").exists());
+    }
+
+    #[test]
+    fn test_copy_directory_reports_per_file_changes() {
+        let temp = TempDir::new().unwrap();
+        let src_dir = temp.path().join("site");
+        fs::create_dir_all(src_dir.join("conf")).unwrap();
+        fs::write(src_dir.join("index.html"), "<html></html>\n").unwrap();
+        fs::write(src_dir.join("conf/app.conf"), "port=8080\n").unwrap();
+        let dest_dir = temp.path().join("www");
+        fs::create_dir_all(&dest_dir).unwrap();
+        fs::write(dest_dir.join("index.html"), "<html></html>\n").unwrap();
+
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "src".to_string(),
+            serde_json::json!(format!("{}/", src_dir.display())),
+        );
+        params.insert(
+            "dest".to_string(),
+            serde_json::json!(dest_dir.to_str().unwrap()),
+        );
+        params.insert("directory_mode".to_string(), serde_json::json!("0750"));
+        let new_file = dest_dir.join("conf/app.conf");
+        let new_file_str = new_file.to_string_lossy().to_string();
+
+        // Check mode reports only the new file and writes nothing
+        let context = ModuleContext::default()
+            .with_check_mode(true)
+            .with_diff_mode(true);
+        let result = CopyModule.execute(&params, &context).unwrap();
+        assert!(result.changed);
+        assert_eq!(
+            result.data["changed_files"],
+            serde_json::json!([new_file_str.clone()])
+        );
+        let files = result.data["files"].as_array().unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files
+            .iter()
+            .any(|f| f["dest"] == serde_json::json!(new_file_str) && f["changed"] == true));
+        assert!(files
+            .iter()
+            .any(|f| f["dest"].as_str().unwrap().ends_with("index.html") && f["changed"] == false));
+        let details = result.diff.unwrap().details.unwrap();
+        assert!(details.contains("+port=8080"));
+        assert!(!details.contains("<html>"));
+        assert!(!dest_dir.join("conf").exists());
+
+        // A real run creates the missing directory with directory_mode
+        let dest_mode_before = fs::metadata(&dest_dir).unwrap().permissions().mode();
+        let result = CopyModule
+            .execute(&params, &ModuleContext::default())
+            .unwrap();
+        assert!(result.changed);
+        assert_eq!(fs::read_to_string(&new_file).unwrap(), "port=8080\n");
+        let conf_mode = fs::metadata(dest_dir.join("conf"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(conf_mode & 0o7777, 0o750);
+        assert_eq!(
+            fs::metadata(&dest_dir).unwrap().permissions().mode(),
+            dest_mode_before
+        );
+    }
+
+    #[test]
+    fn test_copy_directory_skips_symlink_to_ancestor() {
+        let temp = TempDir::new().unwrap();
+        let src_dir = temp.path().join("site");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::write(src_dir.join("index.html"), "<html></html>").unwrap();
+        std::os::unix::fs::symlink(&src_dir, src_dir.join("loop")).unwrap();
+        let dest_dir = temp.path().join("www");
+
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "src".to_string(),
+            serde_json::json!(format!("{}/", src_dir.display())),
+        );
+        params.insert(
+            "dest".to_string(),
+            serde_json::json!(dest_dir.to_str().unwrap()),
+        );
+
+        let result = CopyModule
+            .execute(&params, &ModuleContext::default())
+            .unwrap();
+        assert!(result.changed);
+        assert!(dest_dir.join("index.html").exists());
+        assert!(!dest_dir.join("loop").exists());
+    }
 }
@@ -261,18 +261,12 @@ pub struct PostgresqlDbModule;
 impl PostgresqlDbModule {
     /// Build execute options with privilege escalation and environment
     fn build_execute_options(context: &ModuleContext, env: HashMap<String, String>) -> ExecuteOptions {
-        let mut options = ExecuteOptions::new();
+        let mut options = context.execute_options();
 
         for (key, value) in env {
             options = options.with_env(&key, &value);
         }
 
-        if context.r#become {
-            options.escalate = true;
-            options.escalate_user = context.become_user.clone();
-            options.escalate_method = context.become_method.clone();
-        }
-
         options
     }
 
@@ -287,7 +281,7 @@ impl PostgresqlDbModule {
         let result = connection
             .execute(command, Some(options))
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("Connection execute failed: {}", e)))?;
+            .map_err(|e| ModuleError::from_connection("Connection execute failed", e))?;
         Ok((result.success, result.stdout, result.stderr))
     }
 
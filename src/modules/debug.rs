@@ -14,16 +14,19 @@ use std::collections::HashMap;
 /// Module for printing debug messages and variable values
 pub struct DebugModule;
 
-impl DebugModule {
-    /// Format a variable value for display
-    fn format_value(&self, value: &Value) -> String {
-        match value {
-            Value::String(s) => s.clone(),
-            Value::Null => "(undefined)".to_string(),
-            _ => serde_json::to_string_pretty(value).unwrap_or_else(|_| format!("{:?}", value)),
-        }
+/// Format a value for debug output
+///
+/// Strings are shown as-is and structures as indented JSON, so nested data
+/// stays readable instead of falling back to Rust debug formatting.
+pub fn format_debug_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "(undefined)".to_string(),
+        _ => serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string()),
     }
+}
 
+impl DebugModule {
     /// Get the value of a variable from context
     fn get_variable_value(&self, var_name: &str, context: &ModuleContext) -> Option<Value> {
         // Try to parse as a JSON path or simple variable name
@@ -154,7 +157,7 @@ impl Module for DebugModule {
             // Print a message
             message = match msg {
                 Value::String(s) => s.clone(),
-                _ => self.format_value(msg),
+                _ => format_debug_value(msg),
             };
 
             output_data.insert("msg".to_string(), Value::String(message.clone()));
@@ -171,7 +174,7 @@ impl Module for DebugModule {
 
             match self.get_variable_value(var_name, context) {
                 Some(value) => {
-                    let formatted = self.format_value(&value);
+                    let formatted = format_debug_value(&value);
                     message = format!("{}: {}", var_name, formatted);
                     output_data.insert(var_name.clone(), value);
                 }
@@ -12,6 +12,7 @@
 //! - Alternate installation roots
 //! - Release version specification
 
+use super::package::{group_listed, package_group_name, with_package_delta};
 use super::{
     Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
     ModuleResult, ParallelizationHint, ParamExt,
@@ -75,16 +76,7 @@ pub struct DnfModule;
 impl DnfModule {
     /// Build execution options with become/sudo if needed
     fn build_exec_options(context: &ModuleContext) -> ExecuteOptions {
-        let mut options = ExecuteOptions::new();
-
-        if context.r#become {
-            options.escalate = true;
-            options.escalate_user = context
-                .become_user
-                .clone()
-                .or_else(|| Some("root".to_string()));
-            options.escalate_method = context.become_method.clone();
-        }
+        let mut options = context.execute_options();
 
         if let Some(ref work_dir) = context.work_dir {
             options = options.with_cwd(work_dir);
@@ -197,7 +189,7 @@ impl DnfModule {
         let result = conn
             .execute(&cmd, options)
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("Failed to execute dnf: {}", e)))?;
+            .map_err(|e| ModuleError::from_connection("Failed to execute dnf", e))?;
 
         Ok((result.success, result.stdout, result.stderr))
     }
@@ -208,15 +200,11 @@ impl DnfModule {
         group: &str,
         options: Option<ExecuteOptions>,
     ) -> ModuleResult<bool> {
-        // Remove @ prefix for group check
-        let group_name = group.trim_start_matches('@');
-        let cmd = format!(
-            "dnf group list installed 2>/dev/null | grep -qi {}",
-            shell_escape(group_name)
-        );
-        match conn.execute(&cmd, options).await {
-            Ok(result) => Ok(result.success),
-            Err(_) => Ok(false),
+        let group_name = package_group_name(group)?;
+        let cmd = "dnf group list --installed --ids 2>/dev/null || dnf group list --installed 2>/dev/null";
+        match conn.execute(cmd, options).await {
+            Ok(result) if result.success => Ok(group_listed(&result.stdout, group_name)),
+            _ => Ok(false),
         }
     }
 
@@ -252,7 +240,7 @@ impl DnfModule {
         let result = conn
             .execute(cmd, options)
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("Failed to update cache: {}", e)))?;
+            .map_err(|e| ModuleError::from_connection("Failed to update cache", e))?;
 
         if result.success {
             Ok(())
@@ -299,6 +287,11 @@ impl Module for DnfModule {
             vec![params.get_string_required("name")?]
         };
 
+        // Reject malformed `@group` names before touching the host
+        for group in packages.iter().filter(|p| Self::is_package_group(p)) {
+            package_group_name(group)?;
+        }
+
         let state_str = params
             .get_string("state")?
             .unwrap_or_else(|| "present".to_string());
@@ -354,6 +347,7 @@ impl Module for DnfModule {
                 let mut to_install: Vec<String> = Vec::new();
                 let mut to_remove: Vec<String> = Vec::new();
                 let mut already_ok: Vec<String> = Vec::new();
+                let mut installed: Vec<String> = Vec::new();
 
                 // Check current state of packages
                 for package in &packages_to_check {
@@ -363,6 +357,9 @@ impl Module for DnfModule {
                         Some(exec_options.clone()),
                     )
                     .await?;
+                    if is_installed {
+                        installed.push(package.clone());
+                    }
 
                     match state {
                         DnfState::Present => {
@@ -394,6 +391,9 @@ impl Module for DnfModule {
                         Some(exec_options.clone()),
                     )
                     .await?;
+                    if is_installed {
+                        installed.push(group.clone());
+                    }
 
                     match state {
                         DnfState::Present => {
@@ -434,7 +434,13 @@ impl Module for DnfModule {
                         msg.push_str(&format!("Would remove: {}. ", to_remove.join(", ")));
                     }
 
-                    return Ok(ModuleOutput::changed(msg.trim().to_string()));
+                    return Ok(with_package_delta(
+                        ModuleOutput::changed(msg.trim().to_string()),
+                        &installed,
+                        &to_install,
+                        &to_remove,
+                        &[],
+                    ));
                 }
 
                 // Perform the actual operations
@@ -476,10 +482,14 @@ impl Module for DnfModule {
                         let group_args =
                             Self::build_dnf_args(&["group", "install", "-y"], &dnf_options);
                         let groups_owned: Vec<String> = groups.into_iter().cloned().collect();
+                        let group_names = groups_owned
+                            .iter()
+                            .map(|grp| package_group_name(grp).map(str::to_string))
+                            .collect::<ModuleResult<Vec<_>>>()?;
                         let (success, stdout, stderr) = Self::run_dnf_command_remote(
                             conn.as_ref(),
                             &group_args,
-                            &groups_owned,
+                            &group_names,
                             Some(exec_options.clone()),
                         )
                         .await?;
@@ -533,10 +543,14 @@ impl Module for DnfModule {
                         let group_args =
                             Self::build_dnf_args(&["group", "remove", "-y"], &dnf_options);
                         let groups_owned: Vec<String> = groups.into_iter().cloned().collect();
+                        let group_names = groups_owned
+                            .iter()
+                            .map(|grp| package_group_name(grp).map(str::to_string))
+                            .collect::<ModuleResult<Vec<_>>>()?;
                         let (success, stdout, stderr) = Self::run_dnf_command_remote(
                             conn.as_ref(),
                             &group_args,
-                            &groups_owned,
+                            &group_names,
                             Some(exec_options.clone()),
                         )
                         .await?;
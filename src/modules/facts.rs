@@ -3,152 +3,59 @@
 //! This module gathers facts about the target system including OS, hardware,
 //! network, and other system information.
 
-use super::{Module, ModuleContext, ModuleOutput, ModuleParams, ModuleResult, ParamExt};
+use super::{
+    Module, ModuleContext, ModuleError, ModuleOutput, ModuleParams, ModuleResult, ParamExt,
+};
 use std::collections::HashMap;
 use std::fs;
 use std::process::Command;
+use std::sync::OnceLock;
+
+/// Local `python3` version, looked up once per process
+static PYTHON_VERSION: OnceLock<Option<String>> = OnceLock::new();
 
 /// Module for gathering system facts
 pub struct FactsModule;
 
 impl FactsModule {
-    fn gather_os_facts() -> HashMap<String, serde_json::Value> {
-        let mut facts = HashMap::new();
-
-        // Get hostname
-        if let Ok(output) = Command::new("hostname").arg("-f").output() {
-            if output.status.success() {
-                let hostname = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                facts.insert("hostname".to_string(), serde_json::json!(hostname));
-
-                // Also get short hostname
-                if let Some(short) = hostname.split('.').next() {
-                    facts.insert("hostname_short".to_string(), serde_json::json!(short));
-                }
-            }
-        }
-
-        // Get kernel info via uname
-        if let Ok(output) = Command::new("uname").arg("-s").output() {
-            if output.status.success() {
-                facts.insert(
-                    "system".to_string(),
-                    serde_json::json!(String::from_utf8_lossy(&output.stdout).trim()),
-                );
-            }
-        }
-
-        if let Ok(output) = Command::new("uname").arg("-r").output() {
-            if output.status.success() {
-                facts.insert(
-                    "kernel".to_string(),
-                    serde_json::json!(String::from_utf8_lossy(&output.stdout).trim()),
-                );
-            }
-        }
-
-        if let Ok(output) = Command::new("uname").arg("-m").output() {
-            if output.status.success() {
-                let arch = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                facts.insert("architecture".to_string(), serde_json::json!(arch));
-
-                // Map to common architecture names
-                let machine = match arch.as_str() {
-                    "x86_64" | "amd64" => "x86_64",
-                    "aarch64" | "arm64" => "aarch64",
-                    "armv7l" => "armv7l",
-                    "i686" | "i386" => "i386",
-                    _ => &arch,
-                };
-                facts.insert("machine".to_string(), serde_json::json!(machine));
-            }
-        }
-
-        // Get OS release info
-        if let Ok(content) = fs::read_to_string("/etc/os-release") {
-            for line in content.lines() {
-                if let Some((key, value)) = line.split_once('=') {
-                    let value = value.trim_matches('"');
-                    match key {
-                        "ID" => {
-                            facts.insert("distribution".to_string(), serde_json::json!(value));
-                        }
-                        "VERSION_ID" => {
-                            facts.insert(
-                                "distribution_version".to_string(),
-                                serde_json::json!(value),
-                            );
-                        }
-                        "ID_LIKE" => {
-                            facts.insert("os_family".to_string(), serde_json::json!(value));
-                        }
-                        "PRETTY_NAME" => {
-                            facts.insert(
-                                "distribution_pretty_name".to_string(),
-                                serde_json::json!(value),
-                            );
-                        }
-                        "VERSION_CODENAME" => {
-                            facts.insert(
-                                "distribution_codename".to_string(),
-                                serde_json::json!(value),
-                            );
-                        }
-                        _ => {}
-                    }
-                }
-            }
-        }
-
-        // Determine OS family if not set
-        if !facts.contains_key("os_family") {
-            if let Some(serde_json::Value::String(distro)) = facts.get("distribution") {
-                let family = match distro.to_lowercase().as_str() {
-                    "ubuntu" | "debian" | "linuxmint" | "pop" | "elementary" => "debian",
-                    "fedora" | "centos" | "rhel" | "rocky" | "alma" | "oracle" => "redhat",
-                    "arch" | "manjaro" | "endeavouros" => "arch",
-                    "opensuse" | "sles" => "suse",
-                    "alpine" => "alpine",
-                    "gentoo" => "gentoo",
-                    _ => "unknown",
-                };
-                facts.insert("os_family".to_string(), serde_json::json!(family));
-            }
-        }
-
-        // Get current user
-        if let Ok(output) = Command::new("whoami").output() {
-            if output.status.success() {
-                facts.insert(
-                    "user_id".to_string(),
-                    serde_json::json!(String::from_utf8_lossy(&output.stdout).trim()),
-                );
-            }
-        }
-
-        // Get user's UID
-        if let Ok(output) = Command::new("id").arg("-u").output() {
-            if output.status.success() {
-                if let Ok(uid) = String::from_utf8_lossy(&output.stdout)
-                    .trim()
-                    .parse::<u32>()
-                {
-                    facts.insert("user_uid".to_string(), serde_json::json!(uid));
-                }
-            }
-        }
+    /// Trimmed stdout of a program run directly, or empty if it fails
+    fn command_output(program: &str, args: &[&str]) -> String {
+        Command::new(program)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .unwrap_or_default()
+    }
 
-        // Get user's GID
-        if let Ok(output) = Command::new("id").arg("-g").output() {
-            if output.status.success() {
-                if let Ok(gid) = String::from_utf8_lossy(&output.stdout)
-                    .trim()
-                    .parse::<u32>()
-                {
-                    facts.insert("user_gid".to_string(), serde_json::json!(gid));
-                }
-            }
+    fn gather_os_facts() -> HashMap<String, serde_json::Value> {
+        // Hostname, kernel and distribution, parsed like remote facts
+        let mut fqdn = Self::command_output("hostname", &["-f"]);
+        if fqdn.is_empty() {
+            fqdn = Self::command_output("hostname", &[]);
         }
+        let outputs = [
+            fqdn,
+            Self::command_output("uname", &["-s"]),
+            Self::command_output("uname", &["-r"]),
+            Self::command_output("uname", &["-m"]),
+            fs::read_to_string("/etc/os-release")
+                .map(|content| content.trim().to_string())
+                .unwrap_or_default(),
+        ];
+        let mut facts: HashMap<String, serde_json::Value> =
+            crate::facts::os_facts(&outputs).into_iter().collect();
+
+        // SELinux status and mode, read from selinuxfs
+        let selinux = match fs::read_to_string("/sys/fs/selinux/enforce") {
+            Ok(enforce) => serde_json::json!({
+                "status": "enabled",
+                "mode": if enforce.trim() == "1" { "enforcing" } else { "permissive" },
+            }),
+            Err(_) => serde_json::json!({ "status": "disabled" }),
+        };
+        facts.insert("selinux".to_string(), selinux);
 
         facts
     }
@@ -296,31 +203,64 @@ impl FactsModule {
         {
             if output.status.success() {
                 let stdout = String::from_utf8_lossy(&output.stdout);
-                for part in stdout.split_whitespace() {
-                    // Look for src keyword followed by IP
-                    if part == "src" {
-                        if let Some(ip) = stdout.split("src ").nth(1) {
-                            if let Some(ip) = ip.split_whitespace().next() {
-                                facts.insert("default_ipv4".to_string(), serde_json::json!(ip));
-                                break;
-                            }
-                        }
-                    }
+                if let Some(default_ipv4) = Self::parse_default_route(&stdout) {
+                    facts.insert("default_ipv4".to_string(), default_ipv4);
                 }
             }
         }
 
-        // Get FQDN
-        if let Ok(output) = Command::new("hostname").arg("-f").output() {
-            if output.status.success() {
-                facts.insert(
-                    "fqdn".to_string(),
-                    serde_json::json!(String::from_utf8_lossy(&output.stdout).trim()),
-                );
+        facts
+    }
+
+    /// Gather facts about the user running the tasks
+    fn gather_user_facts() -> HashMap<String, serde_json::Value> {
+        use nix::unistd::{getgid, getuid, User};
+
+        let uid = getuid();
+        let name = User::from_uid(uid)
+            .ok()
+            .flatten()
+            .map(|user| user.name)
+            .unwrap_or_default();
+        let outputs = [name, uid.to_string(), getgid().to_string()];
+        crate::facts::user_facts(&outputs).into_iter().collect()
+    }
+
+    /// Whether a fact matches any filter pattern
+    ///
+    /// Facts are stored without the `ansible_` prefix but filters are usually
+    /// written with it (`ansible_distribution*`), so both forms are tried.
+    fn fact_matches(name: &str, patterns: &[glob::Pattern]) -> bool {
+        let prefixed = format!("ansible_{}", name);
+        patterns
+            .iter()
+            .any(|p| p.matches(name) || p.matches(&prefixed))
+    }
+
+    /// Build the `default_ipv4` fact from `ip route get` output
+    fn parse_default_route(output: &str) -> Option<serde_json::Value> {
+        let mut tokens = output.split_whitespace();
+        let mut address = None;
+        let mut interface = None;
+        let mut gateway = None;
+        while let Some(token) = tokens.next() {
+            match token {
+                "src" => address = tokens.next(),
+                "dev" => interface = tokens.next(),
+                "via" => gateway = tokens.next(),
+                _ => {}
             }
         }
 
-        facts
+        let mut default_ipv4 = serde_json::Map::new();
+        default_ipv4.insert("address".to_string(), serde_json::json!(address?));
+        if let Some(interface) = interface {
+            default_ipv4.insert("interface".to_string(), serde_json::json!(interface));
+        }
+        if let Some(gateway) = gateway {
+            default_ipv4.insert("gateway".to_string(), serde_json::json!(gateway));
+        }
+        Some(serde_json::Value::Object(default_ipv4))
     }
 
     fn gather_date_facts() -> HashMap<String, serde_json::Value> {
@@ -337,15 +277,8 @@ impl FactsModule {
         }
 
         // Get epoch
-        if let Ok(output) = Command::new("date").arg("+%s").output() {
-            if output.status.success() {
-                if let Ok(epoch) = String::from_utf8_lossy(&output.stdout)
-                    .trim()
-                    .parse::<u64>()
-                {
-                    facts.insert("epoch".to_string(), serde_json::json!(epoch));
-                }
-            }
+        if let Ok(epoch) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            facts.insert("epoch".to_string(), serde_json::json!(epoch.as_secs()));
         }
 
         // Get timezone
@@ -390,14 +323,14 @@ impl FactsModule {
 
         facts.insert("env".to_string(), serde_json::Value::Object(env_vars));
 
-        // Get Python version if available
-        if let Ok(output) = Command::new("python3").arg("--version").output() {
-            if output.status.success() {
-                let version = String::from_utf8_lossy(&output.stdout);
-                if let Some(ver) = version.strip_prefix("Python ") {
-                    facts.insert("python_version".to_string(), serde_json::json!(ver.trim()));
-                }
-            }
+        // Get Python version if available; it cannot change while we run, so
+        // only the first gather pays for spawning the interpreter
+        if let Some(version) = PYTHON_VERSION.get_or_init(|| {
+            Self::command_output("python3", &["--version"])
+                .strip_prefix("Python ")
+                .map(|ver| ver.trim().to_string())
+        }) {
+            facts.insert("python_version".to_string(), serde_json::json!(version));
         }
 
         facts
@@ -436,6 +369,16 @@ impl Module for FactsModule {
             }
         }
 
+        // Gather user facts
+        if gather_all
+            || gather_subset.contains(&"user".to_string())
+            || gather_subset.contains(&"min".to_string())
+        {
+            for (k, v) in Self::gather_user_facts() {
+                all_facts.insert(k, v);
+            }
+        }
+
         // Gather hardware facts
         if gather_all || gather_subset.contains(&"hardware".to_string()) {
             for (k, v) in Self::gather_hardware_facts() {
@@ -464,6 +407,19 @@ impl Module for FactsModule {
             }
         }
 
+        // Keep only facts matching `filter`, if given
+        if let Some(filter) = params.get_vec_string("filter")? {
+            let patterns = filter
+                .iter()
+                .map(|p| {
+                    glob::Pattern::new(p.trim()).map_err(|e| {
+                        ModuleError::InvalidParameter(format!("Invalid filter '{}': {}", p, e))
+                    })
+                })
+                .collect::<ModuleResult<Vec<_>>>()?;
+            all_facts.retain(|name, _| Self::fact_matches(name, &patterns));
+        }
+
         // Convert to serde_json::Value
         let facts_json: serde_json::Map<String, serde_json::Value> =
             all_facts.into_iter().collect();
@@ -490,6 +446,30 @@ mod tests {
 
         // Should always have some OS facts on Linux
         assert!(facts.contains_key("system") || facts.contains_key("hostname"));
+        assert!(facts["selinux"]["status"].is_string());
+        // User facts belong to the `user` subset
+        assert!(!facts.contains_key("user_id"));
+    }
+
+    #[test]
+    fn test_min_subset_includes_user_and_hostname() {
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("gather_subset".to_string(), serde_json::json!(["min"]));
+
+        let result = FactsModule
+            .execute(&params, &ModuleContext::default())
+            .unwrap();
+        let facts = result.data["ansible_facts"].as_object().unwrap();
+
+        if Command::new("id").arg("-u").output().is_ok() {
+            assert!(facts["user_id"].is_string());
+            assert!(facts["user_uid"].is_u64());
+        }
+        if let Some(hostname) = facts.get("hostname") {
+            assert!(!hostname.as_str().unwrap().contains('.'));
+            assert!(facts.contains_key("fqdn"));
+        }
+        assert!(!facts.contains_key("interfaces"));
     }
 
     #[test]
@@ -512,6 +492,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_default_route() {
+        let output = "1.1.1.1 via 10.0.0.1 dev eth0 src 10.0.0.5 uid 0\n    cache\n";
+        assert_eq!(
+            FactsModule::parse_default_route(output),
+            Some(serde_json::json!({
+                "address": "10.0.0.5",
+                "interface": "eth0",
+                "gateway": "10.0.0.1"
+            }))
+        );
+        assert_eq!(FactsModule::parse_default_route("unreachable"), None);
+    }
+
+    #[test]
+    fn test_facts_module_filter() {
+        let module = FactsModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "filter".to_string(),
+            serde_json::json!("ansible_distribution*"),
+        );
+
+        let result = module.execute(&params, &ModuleContext::default()).unwrap();
+        let facts = result.data["ansible_facts"].as_object().unwrap();
+
+        assert!(facts.keys().all(|k| k.starts_with("distribution")));
+        assert!(!facts.contains_key("interfaces"));
+        assert!(!facts.contains_key("default_ipv4"));
+        if std::path::Path::new("/etc/os-release").exists() {
+            assert!(facts.contains_key("distribution"));
+        }
+
+        params.insert(
+            "filter".to_string(),
+            serde_json::json!(["hostname", "os_*"]),
+        );
+        let result = module.execute(&params, &ModuleContext::default()).unwrap();
+        let facts = result.data["ansible_facts"].as_object().unwrap();
+        assert!(facts
+            .keys()
+            .all(|k| k == "hostname" || k.starts_with("os_")));
+    }
+
     #[test]
     fn test_facts_module_execute() {
         let module = FactsModule;
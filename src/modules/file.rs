@@ -8,10 +8,14 @@ use super::{
     Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
     ModuleResult, ParamExt,
 };
+use crate::connection::{Connection, TransferOptions};
 use std::fs;
 use std::os::unix::fs::{symlink, MetadataExt, PermissionsExt};
 use std::path::Path;
 
+/// Largest file whose content is shown in the diff when it is removed
+const MAX_REMOVED_DIFF_SIZE: u64 = 64 * 1024;
+
 /// Desired state for a file/directory
 #[derive(Debug, Clone, PartialEq)]
 pub enum FileState {
@@ -81,6 +85,193 @@ impl SelinuxContext {
             self.selevel.as_deref().unwrap_or("_")
         ))
     }
+
+    /// Read the `seuser`, `serole`, `setype` and `selevel` parameters
+    pub fn from_params(params: &ModuleParams) -> ModuleResult<Self> {
+        Ok(Self {
+            seuser: params.get_string("seuser")?,
+            serole: params.get_string("serole")?,
+            setype: params.get_string("setype")?,
+            selevel: params.get_string("selevel")?,
+        })
+    }
+
+    /// `chcon` options for the parts of the context that are set
+    fn chcon_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        for (flag, value) in [
+            ("-u", &self.seuser),
+            ("-r", &self.serole),
+            ("-t", &self.setype),
+            ("-l", &self.selevel),
+        ] {
+            if let Some(value) = value {
+                args.push(flag.to_string());
+                args.push(value.clone());
+            }
+        }
+        args
+    }
+
+    /// Whether the host's `selinux` facts report SELinux as enforcing
+    pub fn is_enforcing(context: &ModuleContext) -> bool {
+        ["selinux", "ansible_selinux"]
+            .iter()
+            .filter_map(|key| context.facts.get(*key))
+            .any(|selinux| selinux.get("mode").and_then(|m| m.as_str()) == Some("enforcing"))
+    }
+
+    /// Read the current SELinux context of a remote file, if it has one
+    pub async fn remote_context(connection: &dyn Connection, path: &Path) -> Option<String> {
+        let cmd = format!("stat -c %C {}", shell_escape(&path.to_string_lossy()));
+        match connection.execute(&cmd, None).await {
+            Ok(result) if result.success => {
+                let context = result.stdout.trim();
+                (!context.is_empty() && context != "?").then(|| context.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Fix the SELinux context of a file just written over `connection`.
+    ///
+    /// The context given in the parameters wins; otherwise the file gets back
+    /// `previous`, the context the destination had before it was replaced,
+    /// and a new file gets the policy default through `restorecon`.
+    pub async fn apply_remote(
+        &self,
+        connection: &dyn Connection,
+        path: &Path,
+        previous: Option<&str>,
+    ) -> ModuleResult<()> {
+        let path = shell_escape(&path.to_string_lossy());
+        let cmd = if self.is_set() {
+            let args: Vec<String> = self.chcon_args().iter().map(|a| shell_escape(a)).collect();
+            format!("chcon {} {}", args.join(" "), path)
+        } else if let Some(previous) = previous {
+            format!("chcon {} {}", shell_escape(previous), path)
+        } else {
+            format!("restorecon {}", path)
+        };
+
+        let result = connection
+            .execute(&cmd, None)
+            .await
+            .map_err(|e| ModuleError::from_connection("Failed to set SELinux context", e))?;
+        if !result.success {
+            return Err(ModuleError::ExecutionFailed(format!(
+                "Failed to set SELinux context: {}",
+                result.stderr.trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Escape a string for use in shell commands
+fn shell_escape(s: &str) -> String {
+    if s.chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | ':'))
+    {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Owner and group of a remote file, resolved to numeric ids on the target
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RemoteOwnership {
+    /// Resolved `owner`
+    pub uid: Option<u32>,
+    /// Resolved `group`
+    pub gid: Option<u32>,
+}
+
+impl RemoteOwnership {
+    /// Resolve `owner` and `group` names with `getent` on the target host.
+    ///
+    /// Numeric values are used as they are. Lookups are cached for the
+    /// lifetime of the connection when it keeps a
+    /// [`RemoteIdCache`](crate::connection::RemoteIdCache), and a name the
+    /// host does not know is an error rather than a failed `chown`.
+    pub async fn resolve(
+        connection: &dyn Connection,
+        owner: Option<&str>,
+        group: Option<&str>,
+    ) -> ModuleResult<Self> {
+        let uid = match owner {
+            Some(owner) => Some(Self::resolve_id(connection, "passwd", owner).await?),
+            None => None,
+        };
+        let gid = match group {
+            Some(group) => Some(Self::resolve_id(connection, "group", group).await?),
+            None => None,
+        };
+        Ok(Self { uid, gid })
+    }
+
+    /// Look up one name in a `getent` database (`passwd` or `group`)
+    async fn resolve_id(
+        connection: &dyn Connection,
+        database: &'static str,
+        name: &str,
+    ) -> ModuleResult<u32> {
+        if let Ok(id) = name.parse() {
+            return Ok(id);
+        }
+
+        let cache = connection.remote_ids();
+        if let Some(id) = cache.and_then(|cache| cache.get(database, name)) {
+            return Ok(id);
+        }
+
+        let kind = if database == "passwd" {
+            "User"
+        } else {
+            "Group"
+        };
+        let cmd = format!("getent {} {}", database, shell_escape(name));
+        let result = connection.execute(&cmd, None).await.map_err(|e| {
+            ModuleError::ExecutionFailed(format!(
+                "Failed to look up {} '{}': {}",
+                kind.to_lowercase(),
+                name,
+                e
+            ))
+        })?;
+
+        // name:password:id:...
+        let id = result
+            .success
+            .then(|| result.stdout.lines().next())
+            .flatten()
+            .and_then(|line| line.split(':').nth(2))
+            .and_then(|id| id.trim().parse().ok())
+            .ok_or_else(|| {
+                ModuleError::ExecutionFailed(format!(
+                    "{} '{}' does not exist on {}",
+                    kind,
+                    name,
+                    connection.identifier()
+                ))
+            })?;
+
+        if let Some(cache) = cache {
+            cache.insert(database, name, id);
+        }
+        Ok(id)
+    }
+
+    /// Add the resolved ids to `options`
+    pub fn apply(&self, mut options: TransferOptions) -> TransferOptions {
+        if let Some(uid) = self.uid {
+            options = options.with_owner(uid.to_string());
+        }
+        if let Some(gid) = self.gid {
+            options = options.with_group(gid.to_string());
+        }
+        options
+    }
 }
 
 /// Module for file/directory management
@@ -207,26 +398,7 @@ impl FileModule {
             return Ok(false);
         }
 
-        // Build chcon arguments
-        let mut args: Vec<String> = Vec::new();
-
-        if let Some(ref user) = context.seuser {
-            args.push("-u".to_string());
-            args.push(user.clone());
-        }
-        if let Some(ref role) = context.serole {
-            args.push("-r".to_string());
-            args.push(role.clone());
-        }
-        if let Some(ref setype) = context.setype {
-            args.push("-t".to_string());
-            args.push(setype.clone());
-        }
-        if let Some(ref level) = context.selevel {
-            args.push("-l".to_string());
-            args.push(level.clone());
-        }
-
+        let mut args = context.chcon_args();
         args.push(path.to_string_lossy().to_string());
 
         let output = Command::new("chcon").args(&args).output()?;
@@ -435,6 +607,40 @@ impl FileModule {
         Ok(true)
     }
 
+    /// Describe what removing `path` takes away
+    ///
+    /// Small text files show their content as deleted lines. Directories,
+    /// links and large or binary files only name the path.
+    fn removal_diff(path: &Path, current_state: Option<&FileState>) -> Diff {
+        match current_state {
+            Some(FileState::Directory) => Diff::new(format!("directory {}", path.display()), ""),
+            Some(FileState::Link) => {
+                let target = fs::read_link(path)
+                    .map(|t| t.display().to_string())
+                    .unwrap_or_default();
+                Diff::new(format!("symlink {} -> {}", path.display(), target), "")
+            }
+            _ => {
+                let text = fs::metadata(path)
+                    .ok()
+                    .filter(|meta| meta.len() <= MAX_REMOVED_DIFF_SIZE)
+                    .and_then(|_| fs::read(path).ok())
+                    .filter(|bytes| !bytes.contains(&0))
+                    .and_then(|bytes| String::from_utf8(bytes).ok());
+                match text {
+                    Some(text) => Diff::for_removed_file(path, &text),
+                    None => Diff::new(
+                        format!(
+                            "file {} (binary or large, content not shown)",
+                            path.display()
+                        ),
+                        "",
+                    ),
+                }
+            }
+        }
+    }
+
     fn remove_path(path: &Path, recurse: bool) -> ModuleResult<bool> {
         if !path.exists() && !path.is_symlink() {
             return Ok(false);
@@ -530,12 +736,7 @@ impl Module for FileModule {
         };
 
         // SELinux context parameters
-        let selinux = SelinuxContext {
-            seuser: params.get_string("seuser")?,
-            serole: params.get_string("serole")?,
-            setype: params.get_string("setype")?,
-            selevel: params.get_string("selevel")?,
-        };
+        let selinux = SelinuxContext::from_params(params)?;
 
         let current_state = Self::get_current_state(path);
 
@@ -549,15 +750,23 @@ impl Module for FileModule {
                     )));
                 }
 
+                // Read what is about to go before it is gone
+                let diff = Self::removal_diff(path, current_state.as_ref());
+
                 if context.check_mode {
                     return Ok(
                         ModuleOutput::changed(format!("Would remove '{}'", path_str))
-                            .with_diff(Diff::new(format!("{:?}", current_state), "absent")),
+                            .with_diff(diff),
                     );
                 }
 
                 Self::remove_path(path, recurse)?;
-                Ok(ModuleOutput::changed(format!("Removed '{}'", path_str)))
+                let output = ModuleOutput::changed(format!("Removed '{}'", path_str));
+                if context.diff_mode {
+                    Ok(output.with_diff(diff))
+                } else {
+                    Ok(output)
+                }
             }
 
             FileState::Directory => {
@@ -847,6 +1056,11 @@ impl Module for FileModule {
 
         if before == after {
             Ok(None)
+        } else if state == FileState::Absent {
+            // Keep the summary, but carry the removed content along
+            let removal = Self::removal_diff(path, current_state.as_ref());
+            let details = removal.details.unwrap_or(removal.before);
+            Ok(Some(Diff::new(before, after).with_details(details)))
         } else {
             Ok(Some(Diff::new(before, after)))
         }
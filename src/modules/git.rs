@@ -4,8 +4,8 @@
 //! and checking out specific versions or branches.
 
 use super::{
-    Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
-    ModuleResult, ParamExt,
+    ensure_local_chdir, Diff, Module, ModuleClassification, ModuleContext, ModuleError,
+    ModuleOutput, ModuleParams, ModuleResult, ParamExt,
 };
 use std::path::Path;
 use std::process::Command;
@@ -19,6 +19,15 @@ struct SshConfig {
 }
 
 impl SshConfig {
+    /// Read `key_file`, `ssh_opts` and `accept_hostkey` from module parameters
+    fn from_params(params: &ModuleParams) -> ModuleResult<Self> {
+        Ok(Self {
+            key_file: params.get_string("key_file")?,
+            ssh_opts: params.get_string("ssh_opts")?,
+            accept_hostkey: params.get_bool_or("accept_hostkey", false),
+        })
+    }
+
     /// Build the GIT_SSH_COMMAND environment variable
     fn build_ssh_command(&self) -> Option<String> {
         let mut parts = vec!["ssh".to_string()];
@@ -77,6 +86,18 @@ impl GitModule {
         Ok(output.status.success())
     }
 
+    /// Resolve a relative `dest` against `chdir` (or the context's `work_dir`)
+    fn resolve_dest(params: &ModuleParams, context: &ModuleContext) -> ModuleResult<String> {
+        let dest = params.get_string_required("dest")?;
+        match context.chdir(params)? {
+            Some(dir) if Path::new(&dest).is_relative() => {
+                ensure_local_chdir(&dir)?;
+                Ok(Path::new(&dir).join(&dest).to_string_lossy().into_owned())
+            }
+            _ => Ok(dest),
+        }
+    }
+
     /// Check if a directory is a git repository
     fn is_git_repo(dest: &str) -> bool {
         Path::new(&format!("{}/.git", dest)).exists() || Self::is_bare_repo(dest)
@@ -205,6 +226,107 @@ impl GitModule {
         }
     }
 
+    /// Get the `--stat` summary of files changed between two commits
+    fn get_diff_stat(dest: &str, from: &str, to: &str) -> ModuleResult<Vec<String>> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dest)
+            .arg("diff")
+            .arg("--stat")
+            .arg(from)
+            .arg(to)
+            .output()
+            .map_err(|e| ModuleError::ExecutionFailed(format!("Failed to get diff stat: {}", e)))?;
+
+        if output.status.success() {
+            let stat: Vec<String> = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|s| s.trim().to_string())
+                .collect();
+            Ok(stat)
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    /// Resolve the commit an update would move to without fetching.
+    ///
+    /// Branches and tags are looked up with `git ls-remote`, which only reads
+    /// the remote's refs. Anything else (a commit hash, a local ref) is
+    /// resolved against the local object database.
+    fn resolve_target_version(
+        dest: &str,
+        version: Option<&str>,
+        remote: &str,
+        ssh_config: &SshConfig,
+    ) -> ModuleResult<Option<String>> {
+        let pattern = version.unwrap_or("HEAD");
+
+        let mut ls_remote_cmd = Command::new("git");
+        ls_remote_cmd
+            .arg("-C")
+            .arg(dest)
+            .arg("ls-remote")
+            .arg(remote)
+            .arg(pattern);
+        ssh_config.apply_to_command(&mut ls_remote_cmd);
+
+        let output = ls_remote_cmd.output().map_err(|e| {
+            ModuleError::ExecutionFailed(format!("Failed to list remote refs: {}", e))
+        })?;
+
+        if output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let refs: Vec<(&str, &str)> = stdout
+                .lines()
+                .filter_map(|line| line.split_once('\t'))
+                .collect();
+
+            // Peeled tags point at the commit rather than the tag object
+            let candidates = match version {
+                Some(v) => vec![
+                    format!("refs/heads/{}", v),
+                    format!("refs/tags/{}^{{}}", v),
+                    format!("refs/tags/{}", v),
+                    v.to_string(),
+                ],
+                None => vec!["HEAD".to_string()],
+            };
+            for candidate in &candidates {
+                if let Some((sha, _)) = refs.iter().find(|(_, name)| *name == candidate) {
+                    return Ok(Some(sha.to_string()));
+                }
+            }
+        }
+
+        let Some(v) = version else {
+            return Ok(None);
+        };
+
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dest)
+            .arg("rev-parse")
+            .arg("--verify")
+            .arg("--quiet")
+            .arg(format!("{}^{{commit}}", v))
+            .output()
+            .map_err(|e| {
+                ModuleError::ExecutionFailed(format!("Failed to resolve version: {}", e))
+            })?;
+
+        if output.status.success() {
+            Ok(Some(
+                String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            ))
+        } else if v.len() == 40 && v.chars().all(|c| c.is_ascii_hexdigit()) {
+            // A full hash that has not been fetched yet
+            Ok(Some(v.to_lowercase()))
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Verify GPG signature of a commit
     fn verify_commit(dest: &str, commit: &str, gpg_whitelist: &[String]) -> ModuleResult<bool> {
         let output = Command::new("git")
@@ -286,65 +408,118 @@ impl GitModule {
         }
     }
 
-    /// Clone a git repository
-    fn clone_repo(
-        repo: &str,
-        dest: &str,
+    /// Arguments for `git clone`, up to the repository and destination
+    fn clone_args(
         version: Option<&str>,
         clone_config: &CloneConfig,
-        ssh_config: &SshConfig,
         remote: &str,
-        umask: Option<&str>,
         track_submodules: bool,
-        _context: &ModuleContext,
-    ) -> ModuleResult<ModuleOutput> {
-        let mut command = Command::new("git");
-        command.arg("clone");
-
-        // Apply SSH configuration
-        ssh_config.apply_to_command(&mut command);
-
-        // Apply umask
-        Self::apply_umask(&mut command, umask);
+    ) -> Vec<String> {
+        let mut args = vec!["clone".to_string()];
 
         // Bare repository
         if clone_config.bare {
-            command.arg("--bare");
+            args.push("--bare".to_string());
         }
 
         // Shallow clone
         if let Some(d) = clone_config.depth {
-            command.arg("--depth").arg(d.to_string());
+            args.push("--depth".to_string());
+            args.push(d.to_string());
         }
 
         // Single branch (implied with depth, but can be explicit)
         if clone_config.single_branch {
-            command.arg("--single-branch");
+            args.push("--single-branch".to_string());
         }
 
         // Separate git directory
         if let Some(ref git_dir) = clone_config.separate_git_dir {
-            command.arg("--separate-git-dir").arg(git_dir);
+            args.push("--separate-git-dir".to_string());
+            args.push(git_dir.clone());
         }
 
         // Branch/tag to clone
         if let Some(v) = version {
-            command.arg("--branch").arg(v);
+            args.push("--branch".to_string());
+            args.push(v.to_string());
         }
 
         // Custom remote name
         if remote != "origin" {
-            command.arg("--origin").arg(remote);
+            args.push("--origin".to_string());
+            args.push(remote.to_string());
         }
 
-        // Recursive submodules
+        // Recursive submodules, as shallow as the superproject
         if clone_config.recursive {
-            command.arg("--recurse-submodules");
+            args.push("--recurse-submodules".to_string());
+            if clone_config.depth.is_some() {
+                args.push("--shallow-submodules".to_string());
+            }
             if track_submodules {
-                command.arg("--remote-submodules");
+                args.push("--remote-submodules".to_string());
             }
         }
 
+        args
+    }
+
+    /// Arguments for `git fetch` when updating an existing checkout
+    ///
+    /// A shallow fetch names the version explicitly: a single-branch shallow
+    /// clone has no refs for other branches, tags or commits to check out.
+    fn fetch_args(remote: &str, version: Option<&str>, clone_config: &CloneConfig) -> Vec<String> {
+        let mut args = vec!["fetch".to_string()];
+        if let Some(d) = clone_config.depth {
+            args.push("--depth".to_string());
+            args.push(d.to_string());
+        }
+        args.push(remote.to_string());
+
+        if let Some(ref rs) = clone_config.refspec {
+            args.push(rs.clone());
+        } else if clone_config.depth.is_some() {
+            args.push(version.unwrap_or("HEAD").to_string());
+        }
+        args
+    }
+
+    /// Arguments for `git submodule update` after a clone or update
+    fn submodule_update_args(track_submodules: bool) -> Vec<&'static str> {
+        let mut args = vec!["submodule", "update", "--init", "--recursive"];
+        if track_submodules {
+            args.push("--remote");
+        }
+        args
+    }
+
+    /// Clone a git repository
+    fn clone_repo(
+        repo: &str,
+        dest: &str,
+        version: Option<&str>,
+        clone_config: &CloneConfig,
+        ssh_config: &SshConfig,
+        remote: &str,
+        umask: Option<&str>,
+        track_submodules: bool,
+        _context: &ModuleContext,
+    ) -> ModuleResult<ModuleOutput> {
+        let mut command = Command::new("git");
+        command.args(Self::clone_args(
+            version,
+            clone_config,
+            remote,
+            track_submodules,
+        ));
+
+        // Apply SSH configuration
+        ssh_config.apply_to_command(&mut command);
+
+        // Apply umask
+        Self::apply_umask(&mut command, umask);
+
         command.arg(repo).arg(dest);
 
         let output = command.output().map_err(|e| {
@@ -408,7 +583,7 @@ impl GitModule {
         ssh_config: &SshConfig,
         force: bool,
         track_submodules: bool,
-        refspec: Option<&str>,
+        clone_config: &CloneConfig,
         context: &ModuleContext,
     ) -> ModuleResult<(bool, String, String, Vec<String>)> {
         // Get current version before update
@@ -416,7 +591,16 @@ impl GitModule {
             Self::get_current_version(dest)?.unwrap_or_else(|| "unknown".to_string());
 
         if context.check_mode {
-            return Ok((false, before_version.clone(), before_version, vec![]));
+            // Only read refs here; fetch and checkout would touch the working tree
+            let after_version = Self::resolve_target_version(dest, version, remote, ssh_config)?
+                .unwrap_or_else(|| before_version.clone());
+            let changed = before_version != after_version;
+            let commits = if changed {
+                Self::get_commit_log(dest, &before_version, &after_version).unwrap_or_default()
+            } else {
+                vec![]
+            };
+            return Ok((changed, after_version, before_version, commits));
         }
 
         // If force, reset local changes first
@@ -426,12 +610,10 @@ impl GitModule {
 
         // Fetch updates
         let mut fetch_cmd = Command::new("git");
-        fetch_cmd.arg("-C").arg(dest).arg("fetch").arg(remote);
-
-        if let Some(rs) = refspec {
-            fetch_cmd.arg(rs);
-        }
-
+        fetch_cmd
+            .arg("-C")
+            .arg(dest)
+            .args(Self::fetch_args(remote, version, clone_config));
         ssh_config.apply_to_command(&mut fetch_cmd);
 
         let fetch_output = fetch_cmd
@@ -445,8 +627,12 @@ impl GitModule {
             });
         }
 
-        // Checkout the specified version or default branch
-        let checkout_target = if let Some(v) = version {
+        // Checkout the specified version or default branch. A shallow fetch
+        // leaves the requested commit in FETCH_HEAD.
+        let shallow = clone_config.depth.is_some() && clone_config.refspec.is_none();
+        let checkout_target = if shallow {
+            "FETCH_HEAD".to_string()
+        } else if let Some(v) = version {
             v.to_string()
         } else {
             format!("{}/HEAD", remote)
@@ -486,17 +672,14 @@ impl GitModule {
             let _ = pull_cmd.output();
         }
 
-        // Update submodules if needed
-        if track_submodules {
+        // Bring submodules to the commits the new checkout records, or to
+        // their remote branches when tracking them
+        if clone_config.recursive || track_submodules {
             let mut submodule_cmd = Command::new("git");
             submodule_cmd
                 .arg("-C")
                 .arg(dest)
-                .arg("submodule")
-                .arg("update")
-                .arg("--init")
-                .arg("--recursive")
-                .arg("--remote");
+                .args(Self::submodule_update_args(track_submodules));
 
             ssh_config.apply_to_command(&mut submodule_cmd);
             let _ = submodule_cmd.output();
@@ -584,7 +767,7 @@ impl Module for GitModule {
     ) -> ModuleResult<ModuleOutput> {
         // Extract required parameters
         let repo = params.get_string_required("repo")?;
-        let dest = params.get_string_required("dest")?;
+        let dest = Self::resolve_dest(params, context)?;
 
         // Extract optional parameters
         let version = params.get_string("version")?;
@@ -598,11 +781,6 @@ impl Module for GitModule {
         let track_submodules = params.get_bool_or("track_submodules", false);
         let verify_commit = params.get_bool_or("verify_commit", false);
 
-        // SSH configuration
-        let key_file = params.get_string("key_file")?;
-        let ssh_opts = params.get_string("ssh_opts")?;
-        let accept_hostkey = params.get_bool_or("accept_hostkey", false);
-
         // Other options
         let remote = params
             .get_string("remote")?
@@ -623,11 +801,7 @@ impl Module for GitModule {
             .unwrap_or_default();
 
         // Build SSH configuration
-        let ssh_config = SshConfig {
-            key_file,
-            ssh_opts,
-            accept_hostkey,
-        };
+        let ssh_config = SshConfig::from_params(params)?;
 
         // Build clone configuration
         let clone_config = CloneConfig {
@@ -708,18 +882,20 @@ impl Module for GitModule {
                 &ssh_config,
                 force,
                 track_submodules,
-                refspec.as_deref(),
+                &clone_config,
                 context,
             )?;
 
-            // Verify GPG signature if requested
-            if verify_commit && changed {
-                if !Self::verify_commit(&dest, &new_version, &gpg_whitelist)? {
-                    return Err(ModuleError::ExecutionFailed(format!(
-                        "GPG signature verification failed for commit {}",
-                        new_version
-                    )));
-                }
+            // Verify GPG signature if requested; check mode has not fetched the commit
+            if verify_commit
+                && changed
+                && !context.check_mode
+                && !Self::verify_commit(&dest, &new_version, &gpg_whitelist)?
+            {
+                return Err(ModuleError::ExecutionFailed(format!(
+                    "GPG signature verification failed for commit {}",
+                    new_version
+                )));
             }
 
             if changed {
@@ -746,8 +922,24 @@ impl Module for GitModule {
                     }
                 }
 
+                // Empty when the target commit has not been fetched (check mode)
+                let diff_stat =
+                    Self::get_diff_stat(&dest, &old_version, &new_version).unwrap_or_default();
+                if !diff_stat.is_empty() {
+                    diff_after.push_str("\n\nChanged files:");
+                    for line in &diff_stat {
+                        diff_after.push_str(&format!("\n  {}", line));
+                    }
+                }
+
+                let action = if context.check_mode {
+                    "Would update"
+                } else {
+                    "Updated"
+                };
                 Ok(ModuleOutput::changed(format!(
-                    "Updated repository from '{}' to '{}'",
+                    "{} repository from '{}' to '{}'",
+                    action,
                     &old_version[..8.min(old_version.len())],
                     &new_version[..8.min(new_version.len())]
                 ))
@@ -787,8 +979,8 @@ impl Module for GitModule {
         self.execute(params, &check_context)
     }
 
-    fn diff(&self, params: &ModuleParams, _context: &ModuleContext) -> ModuleResult<Option<Diff>> {
-        let dest = params.get_string_required("dest")?;
+    fn diff(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<Option<Diff>> {
+        let dest = Self::resolve_dest(params, context)?;
         let repo = params.get_string_required("repo")?;
         let version = params.get_string("version")?;
         let remote = params
@@ -830,8 +1022,33 @@ impl Module for GitModule {
                 }
             }
 
-            // Build target state
-            let after = format!("commit: {}\nremote: {}", target_version, remote);
+            // Build target state, naming the resolved commit when the remote knows it
+            let resolved = Self::resolve_target_version(
+                &dest,
+                Some(target_version.as_str()).filter(|v| *v != "HEAD"),
+                &remote,
+                &SshConfig::from_params(params)?,
+            )
+            .unwrap_or_default();
+            let mut after = match resolved.as_deref() {
+                Some(sha) if sha != target_version => format!(
+                    "commit: {} ({})\nremote: {}",
+                    &sha[..8.min(sha.len())],
+                    target_version,
+                    remote
+                ),
+                _ => format!("commit: {}\nremote: {}", target_version, remote),
+            };
+            if let Some(sha) = resolved.as_deref() {
+                let diff_stat =
+                    Self::get_diff_stat(&dest, &current_version, sha).unwrap_or_default();
+                if !diff_stat.is_empty() {
+                    after.push_str("\n\nChanged files:");
+                    for line in &diff_stat {
+                        after.push_str(&format!("\n  {}", line));
+                    }
+                }
+            }
 
             Ok(Some(Diff::new(before, after)))
         }
@@ -1043,6 +1260,42 @@ mod tests {
         assert!(cmd.contains("-v"));
     }
 
+    #[test]
+    fn test_ssh_config_from_params() {
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("key_file".to_string(), serde_json::json!("/path/to/key"));
+        params.insert("accept_hostkey".to_string(), serde_json::json!(true));
+
+        let cmd = SshConfig::from_params(&params)
+            .unwrap()
+            .build_ssh_command()
+            .unwrap();
+        assert!(cmd.contains("-i /path/to/key"));
+        assert!(cmd.contains("-o StrictHostKeyChecking=no"));
+    }
+
+    #[test]
+    fn test_resolve_dest_against_chdir() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().to_str().unwrap();
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("dest".to_string(), serde_json::json!("checkout"));
+
+        let context = ModuleContext::default().with_work_dir(dir);
+        let dest = GitModule::resolve_dest(&params, &context).unwrap();
+        assert_eq!(Path::new(&dest), temp.path().join("checkout"));
+
+        // Absolute destinations are left alone
+        params.insert("dest".to_string(), serde_json::json!("/opt/checkout"));
+        let dest = GitModule::resolve_dest(&params, &context).unwrap();
+        assert_eq!(dest, "/opt/checkout");
+
+        // A missing chdir is reported
+        params.insert("dest".to_string(), serde_json::json!("checkout"));
+        params.insert("chdir".to_string(), serde_json::json!("/nonexistent/dir"));
+        assert!(GitModule::resolve_dest(&params, &context).is_err());
+    }
+
     #[test]
     fn test_is_bare_repo() {
         let temp = TempDir::new().unwrap();
@@ -1083,4 +1336,196 @@ mod tests {
         assert!(diff.before.contains("absent"));
         assert!(diff.after.contains("v1.0.0"));
     }
+
+    #[test]
+    fn test_clone_args_shallow_and_recursive() {
+        let config = CloneConfig {
+            depth: Some(1),
+            recursive: true,
+            ..Default::default()
+        };
+        let args = GitModule::clone_args(Some("main"), &config, "origin", false);
+        assert_eq!(
+            args,
+            vec![
+                "clone",
+                "--depth",
+                "1",
+                "--branch",
+                "main",
+                "--recurse-submodules",
+                "--shallow-submodules"
+            ]
+        );
+
+        let args = GitModule::clone_args(None, &CloneConfig::default(), "origin", false);
+        assert_eq!(args, vec!["clone"]);
+    }
+
+    #[test]
+    fn test_fetch_args_name_version_when_shallow() {
+        let config = CloneConfig {
+            depth: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            GitModule::fetch_args("origin", Some("v2.0"), &config),
+            vec!["fetch", "--depth", "1", "origin", "v2.0"]
+        );
+        assert_eq!(
+            GitModule::fetch_args("origin", Some("v2.0"), &CloneConfig::default()),
+            vec!["fetch", "origin"]
+        );
+    }
+
+    #[test]
+    fn test_submodule_update_args() {
+        assert_eq!(
+            GitModule::submodule_update_args(false),
+            vec!["submodule", "update", "--init", "--recursive"]
+        );
+        assert_eq!(
+            GitModule::submodule_update_args(true).last(),
+            Some(&"--remote")
+        );
+    }
+
+    #[test]
+    fn test_shallow_clone_stays_idempotent() {
+        let temp = TempDir::new().unwrap();
+        let (origin, _, second, dest) = repo_behind_origin(&temp);
+        std::fs::remove_dir_all(&dest).unwrap();
+
+        // Local paths ignore --depth; a file:// URL honors it
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "repo".to_string(),
+            serde_json::json!(format!("file://{}", origin)),
+        );
+        params.insert(
+            "dest".to_string(),
+            serde_json::json!(dest.to_str().unwrap()),
+        );
+        params.insert("depth".to_string(), serde_json::json!(1));
+
+        let result = GitModule
+            .execute(&params, &ModuleContext::default())
+            .unwrap();
+        assert!(result.changed);
+        assert!(dest.join(".git/shallow").exists());
+        assert_eq!(git(&dest, &["rev-list", "--count", "HEAD"]), "1");
+
+        let result = GitModule
+            .execute(&params, &ModuleContext::default())
+            .unwrap();
+        assert!(!result.changed);
+        assert_eq!(result.data["after"], serde_json::json!(second));
+
+        let origin_path = Path::new(&origin);
+        std::fs::write(origin_path.join("README"), "two\n").unwrap();
+        git(origin_path, &["commit", "-q", "-am", "third"]);
+        let third = git(origin_path, &["rev-parse", "HEAD"]);
+
+        let result = GitModule
+            .execute(&params, &ModuleContext::default())
+            .unwrap();
+        assert!(result.changed);
+        assert_eq!(result.data["after"], serde_json::json!(third));
+    }
+
+    /// Run git in `dir` with a fixed identity, panicking on failure
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    /// Clone a one-commit origin, then add a second commit to the origin
+    fn repo_behind_origin(temp: &TempDir) -> (String, String, String, std::path::PathBuf) {
+        let origin = temp.path().join("origin");
+        std::fs::create_dir(&origin).unwrap();
+        git(&origin, &["init", "-q"]);
+        std::fs::write(origin.join("README"), "one\n").unwrap();
+        git(&origin, &["add", "README"]);
+        git(&origin, &["commit", "-q", "-m", "first"]);
+        let first = git(&origin, &["rev-parse", "HEAD"]);
+
+        let dest = temp.path().join("checkout");
+        git(
+            temp.path(),
+            &[
+                "clone",
+                "-q",
+                origin.to_str().unwrap(),
+                dest.to_str().unwrap(),
+            ],
+        );
+
+        std::fs::write(origin.join("app.conf"), "port = 80\n").unwrap();
+        git(&origin, &["add", "app.conf"]);
+        git(&origin, &["commit", "-q", "-m", "second"]);
+        let second = git(&origin, &["rev-parse", "HEAD"]);
+
+        (origin.to_str().unwrap().to_string(), first, second, dest)
+    }
+
+    #[test]
+    fn test_check_mode_reports_update_without_fetching() {
+        let temp = TempDir::new().unwrap();
+        let (origin, first, second, dest) = repo_behind_origin(&temp);
+
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("repo".to_string(), serde_json::json!(origin));
+        params.insert(
+            "dest".to_string(),
+            serde_json::json!(dest.to_str().unwrap()),
+        );
+
+        let context = ModuleContext::default().with_check_mode(true);
+        let result = GitModule.check(&params, &context).unwrap();
+
+        assert!(result.changed);
+        assert!(result.msg.contains("Would update"));
+        assert_eq!(result.data["before"], serde_json::json!(first));
+        assert_eq!(result.data["after"], serde_json::json!(second));
+        let diff = result.diff.unwrap();
+        assert!(diff.before.contains(&first[..8]));
+        assert!(diff.after.contains(&second[..8]));
+
+        // Nothing was fetched or checked out
+        assert_eq!(git(&dest, &["rev-parse", "HEAD"]), first);
+        assert_eq!(git(&dest, &["rev-parse", "origin/HEAD"]), first);
+        assert!(!dest.join(".git/FETCH_HEAD").exists());
+        assert!(!dest.join("app.conf").exists());
+    }
+
+    #[test]
+    fn test_update_diff_lists_changed_files() {
+        let temp = TempDir::new().unwrap();
+        let (origin, first, second, dest) = repo_behind_origin(&temp);
+
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("repo".to_string(), serde_json::json!(origin));
+        params.insert(
+            "dest".to_string(),
+            serde_json::json!(dest.to_str().unwrap()),
+        );
+
+        let result = GitModule
+            .execute(&params, &ModuleContext::default())
+            .unwrap();
+
+        assert!(result.changed);
+        assert_eq!(result.data["before"], serde_json::json!(first));
+        assert_eq!(result.data["after"], serde_json::json!(second));
+        let diff = result.diff.unwrap();
+        assert!(diff.after.contains("Changed files:"));
+        assert!(diff.after.contains("app.conf"));
+    }
 }
@@ -45,14 +45,7 @@ pub struct GroupModule;
 impl GroupModule {
     /// Get execution options with become support if needed
     fn get_exec_options(context: &ModuleContext) -> ExecuteOptions {
-        let mut options = ExecuteOptions::new();
-        if context.r#become {
-            options = options.with_escalation(context.become_user.clone());
-            if let Some(ref method) = context.become_method {
-                options.escalate_method = Some(method.clone());
-            }
-        }
-        options
+        context.execute_options()
     }
 
     /// Execute a command via connection
@@ -66,7 +59,7 @@ impl GroupModule {
         // Use tokio runtime to execute async command
         let result = Handle::current()
             .block_on(async { connection.execute(command, Some(options)).await })
-            .map_err(|e| ModuleError::ExecutionFailed(format!("Connection error: {}", e)))?;
+            .map_err(ModuleError::from)?;
 
         Ok((result.success, result.stdout, result.stderr))
     }
@@ -44,20 +44,83 @@ impl HostnameStrategy {
     }
 }
 
+/// Hostname configuration observed on the target
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HostnameState {
+    /// Hostname the kernel is currently using
+    pub running: String,
+    /// Hostname that survives a reboot
+    pub persistent: String,
+    /// Pretty hostname (systemd only)
+    pub pretty: Option<String>,
+}
+
+impl HostnameState {
+    /// Whether both the running and persistent hostnames equal `name`
+    pub fn matches(&self, name: &str) -> bool {
+        self.running == name && self.persistent == name
+    }
+
+    /// Render the state for a before/after diff
+    fn to_diff_string(&self) -> String {
+        let mut rendered = format!(
+            "hostname: {}\npersistent_hostname: {}\n",
+            self.running, self.persistent
+        );
+        if let Some(ref pretty) = self.pretty {
+            rendered.push_str(&format!("pretty_hostname: {}\n", pretty));
+        }
+        rendered
+    }
+
+    /// Render the state that `name` and a managed `pretty` name would produce
+    fn desired(&self, name: &str, pretty: Option<&str>) -> Self {
+        Self {
+            running: name.to_string(),
+            persistent: name.to_string(),
+            pretty: pretty.map(str::to_string).or_else(|| self.pretty.clone()),
+        }
+    }
+}
+
+/// Parse the output of `hostnamectl status`
+///
+/// The transient hostname is only listed when it differs from the static
+/// one, so the running hostname falls back to the static hostname.
+pub fn parse_hostnamectl_status(output: &str) -> HostnameState {
+    let mut static_name = String::new();
+    let mut transient = None;
+    let mut pretty = None;
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let value = if value == "n/a" { "" } else { value };
+
+        match key.trim() {
+            "Static hostname" => static_name = value.to_string(),
+            "Transient hostname" => transient = Some(value.to_string()),
+            "Pretty hostname" => pretty = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    HostnameState {
+        running: transient.unwrap_or_else(|| static_name.clone()),
+        persistent: static_name,
+        pretty,
+    }
+}
+
 /// Module for hostname management
 pub struct HostnameModule;
 
 impl HostnameModule {
     /// Get execution options with become support if needed
     fn get_exec_options(context: &ModuleContext) -> ExecuteOptions {
-        let mut options = ExecuteOptions::new();
-        if context.r#become {
-            options = options.with_escalation(context.become_user.clone());
-            if let Some(ref method) = context.become_method {
-                options.escalate_method = Some(method.clone());
-            }
-        }
-        options
+        context.execute_options()
     }
 
     /// Execute a command via connection
@@ -67,10 +130,16 @@ impl HostnameModule {
         context: &ModuleContext,
     ) -> ModuleResult<(bool, String, String)> {
         let options = Self::get_exec_options(context);
+        let handle = Handle::try_current()
+            .map_err(|_| ModuleError::ExecutionFailed("No tokio runtime available".to_string()))?;
 
-        let result = Handle::current()
-            .block_on(async { connection.execute(command, Some(options)).await })
-            .map_err(|e| ModuleError::ExecutionFailed(format!("Connection error: {}", e)))?;
+        // Run on a separate thread to avoid blocking inside the caller's runtime
+        let result = std::thread::scope(|s| {
+            s.spawn(|| handle.block_on(connection.execute(command, Some(options))))
+                .join()
+                .unwrap()
+        })
+        .map_err(ModuleError::from)?;
 
         Ok((result.success, result.stdout, result.stderr))
     }
@@ -80,6 +149,19 @@ impl HostnameModule {
         connection: &Arc<dyn Connection + Send + Sync>,
         context: &ModuleContext,
     ) -> ModuleResult<HostnameStrategy> {
+        // Prefer gathered facts when they tell us the service manager
+        if let Some(service_mgr) = context
+            .facts
+            .get("ansible_service_mgr")
+            .and_then(|v| v.as_str())
+        {
+            return Ok(if service_mgr == "systemd" {
+                HostnameStrategy::Systemd
+            } else {
+                HostnameStrategy::File
+            });
+        }
+
         // Check for systemd/hostnamectl
         let cmd = "which hostnamectl >/dev/null 2>&1 && echo yes || echo no";
         let (_, stdout, _) = Self::execute_command(connection, cmd, context)?;
@@ -96,36 +178,46 @@ impl HostnameModule {
         Ok(HostnameStrategy::File)
     }
 
-    /// Get current hostname
-    fn get_current_hostname(
+    /// Read the running and persistent hostname using the given strategy
+    fn read_state(
         connection: &Arc<dyn Connection + Send + Sync>,
+        strategy: &HostnameStrategy,
         context: &ModuleContext,
-    ) -> ModuleResult<String> {
-        let (success, stdout, stderr) = Self::execute_command(connection, "hostname", context)?;
+    ) -> ModuleResult<HostnameState> {
+        if *strategy == HostnameStrategy::Systemd {
+            let (success, stdout, stderr) =
+                Self::execute_command(connection, "hostnamectl status", context)?;
+            if !success {
+                return Err(ModuleError::ExecutionFailed(format!(
+                    "Failed to query hostnamectl: {}",
+                    stderr
+                )));
+            }
+            return Ok(parse_hostnamectl_status(&stdout));
+        }
 
-        if success {
-            Ok(stdout.trim().to_string())
-        } else {
-            Err(ModuleError::ExecutionFailed(format!(
+        let (success, stdout, stderr) = Self::execute_command(connection, "hostname", context)?;
+        if !success {
+            return Err(ModuleError::ExecutionFailed(format!(
                 "Failed to get hostname: {}",
                 stderr
-            )))
+            )));
         }
-    }
 
-    /// Get pretty hostname (systemd only)
-    fn get_pretty_hostname(
-        connection: &Arc<dyn Connection + Send + Sync>,
-        context: &ModuleContext,
-    ) -> ModuleResult<Option<String>> {
-        let cmd = "hostnamectl --static 2>/dev/null | head -1";
-        let (_, stdout, _) = Self::execute_command(connection, cmd, context)?;
-        let hostname = stdout.trim();
-        if hostname.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(hostname.to_string()))
-        }
+        let (_, persistent, _) =
+            Self::execute_command(connection, "cat /etc/hostname 2>/dev/null || true", context)?;
+        let persistent = persistent
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(HostnameState {
+            running: stdout.trim().to_string(),
+            persistent,
+            pretty: None,
+        })
     }
 
     /// Set hostname using hostnamectl (systemd)
@@ -148,46 +240,62 @@ impl HostnameModule {
 
         // Set pretty hostname if provided
         if let Some(pretty) = pretty_hostname {
-            let cmd = format!("hostnamectl set-hostname --pretty {}", shell_escape(pretty));
-            let (success, _, stderr) = Self::execute_command(connection, &cmd, context)?;
-
-            if !success {
-                return Err(ModuleError::ExecutionFailed(format!(
-                    "Failed to set pretty hostname: {}",
-                    stderr
-                )));
-            }
+            Self::set_pretty_hostname(connection, pretty, context)?;
         }
 
         Ok(())
     }
 
-    /// Set hostname using traditional file method
-    fn set_hostname_file(
+    /// Set the pretty hostname using hostnamectl (systemd)
+    fn set_pretty_hostname(
         connection: &Arc<dyn Connection + Send + Sync>,
-        hostname: &str,
+        pretty: &str,
         context: &ModuleContext,
     ) -> ModuleResult<()> {
-        // Write to /etc/hostname
-        let cmd = format!("echo {} > /etc/hostname", shell_escape(hostname));
+        let cmd = format!("hostnamectl set-hostname --pretty {}", shell_escape(pretty));
         let (success, _, stderr) = Self::execute_command(connection, &cmd, context)?;
 
         if !success {
             return Err(ModuleError::ExecutionFailed(format!(
-                "Failed to write /etc/hostname: {}",
+                "Failed to set pretty hostname: {}",
                 stderr
             )));
         }
 
+        Ok(())
+    }
+
+    /// Set hostname using traditional file method
+    fn set_hostname_file(
+        connection: &Arc<dyn Connection + Send + Sync>,
+        hostname: &str,
+        current: &HostnameState,
+        context: &ModuleContext,
+    ) -> ModuleResult<()> {
+        // Write to /etc/hostname
+        if current.persistent != hostname {
+            let cmd = format!("echo {} > /etc/hostname", shell_escape(hostname));
+            let (success, _, stderr) = Self::execute_command(connection, &cmd, context)?;
+
+            if !success {
+                return Err(ModuleError::ExecutionFailed(format!(
+                    "Failed to write /etc/hostname: {}",
+                    stderr
+                )));
+            }
+        }
+
         // Apply the hostname immediately
-        let cmd = format!("hostname {}", shell_escape(hostname));
-        let (success, _, stderr) = Self::execute_command(connection, &cmd, context)?;
+        if current.running != hostname {
+            let cmd = format!("hostname {}", shell_escape(hostname));
+            let (success, _, stderr) = Self::execute_command(connection, &cmd, context)?;
 
-        if !success {
-            return Err(ModuleError::ExecutionFailed(format!(
-                "Failed to set runtime hostname: {}",
-                stderr
-            )));
+            if !success {
+                return Err(ModuleError::ExecutionFailed(format!(
+                    "Failed to set runtime hostname: {}",
+                    stderr
+                )));
+            }
         }
 
         Ok(())
@@ -306,70 +414,91 @@ impl Module for HostnameModule {
             s => s,
         };
 
-        // Get current hostname
-        let current_hostname = Self::get_current_hostname(connection, context)?;
-
-        // Check if change is needed
-        if current_hostname == name {
-            // Check if pretty hostname needs update (systemd only)
-            if effective_strategy == HostnameStrategy::Systemd {
-                if let Some(ref pretty) = pretty_hostname {
-                    let current_pretty =
-                        Self::get_pretty_hostname(connection, context)?.unwrap_or_default();
-                    if current_pretty != *pretty {
-                        if context.check_mode {
-                            return Ok(ModuleOutput::changed(format!(
-                                "Would update pretty hostname to '{}'",
-                                pretty
-                            )));
-                        }
-
-                        Self::set_hostname_systemd(connection, &name, Some(pretty), context)?;
-                        return Ok(ModuleOutput::changed(format!(
-                            "Updated pretty hostname to '{}'",
-                            pretty
-                        ))
-                        .with_data("name", serde_json::json!(name))
-                        .with_data("pretty_hostname", serde_json::json!(pretty)));
-                    }
-                }
-            }
+        let current = Self::read_state(connection, &effective_strategy, context)?;
+        let managed_pretty = pretty_hostname
+            .as_deref()
+            .filter(|_| effective_strategy == HostnameStrategy::Systemd);
+        let name_changed = !current.matches(&name);
+        let pretty_changed =
+            managed_pretty.is_some_and(|pretty| current.pretty.as_deref() != Some(pretty));
 
+        if !name_changed && !pretty_changed {
             return Ok(ModuleOutput::ok(format!("Hostname is already '{}'", name))
                 .with_data("name", serde_json::json!(name)));
         }
 
+        let diff = Diff::new(
+            current.to_diff_string(),
+            current.desired(&name, managed_pretty).to_diff_string(),
+        );
+
         // Check mode
         if context.check_mode {
-            let mut msg = format!(
-                "Would change hostname from '{}' to '{}'",
-                current_hostname, name
-            );
-            if update_hosts {
+            let mut msg = if name_changed {
+                format!(
+                    "Would change hostname from '{}' to '{}'",
+                    current.running, name
+                )
+            } else {
+                format!(
+                    "Would update pretty hostname to '{}'",
+                    pretty_hostname.as_deref().unwrap_or_default()
+                )
+            };
+            if name_changed && update_hosts {
                 msg.push_str(" and update /etc/hosts");
             }
-            return Ok(ModuleOutput::changed(msg));
+            let mut output = ModuleOutput::changed(msg);
+            if context.diff_mode {
+                output = output.with_diff(diff);
+            }
+            return Ok(output);
         }
 
         // Apply the change based on strategy
         match effective_strategy {
             HostnameStrategy::Systemd => {
-                Self::set_hostname_systemd(connection, &name, pretty_hostname.as_deref(), context)?;
+                if name_changed {
+                    Self::set_hostname_systemd(
+                        connection,
+                        &name,
+                        pretty_hostname.as_deref(),
+                        context,
+                    )?;
+                } else if let Some(ref pretty) = pretty_hostname {
+                    Self::set_pretty_hostname(connection, pretty, context)?;
+                }
             }
             HostnameStrategy::File | HostnameStrategy::Auto => {
-                Self::set_hostname_file(connection, &name, context)?;
+                Self::set_hostname_file(connection, &name, &current, context)?;
             }
         }
 
-        let mut messages = vec![format!(
-            "Changed hostname from '{}' to '{}'",
-            current_hostname, name
-        )];
+        // Verify both the running and persistent hostname took effect
+        let applied = Self::read_state(connection, &effective_strategy, context)?;
+        if !applied.matches(&name) {
+            return Err(ModuleError::ExecutionFailed(format!(
+                "Hostname verification failed: running '{}', persistent '{}', expected '{}'",
+                applied.running, applied.persistent, name
+            )));
+        }
+
+        let mut messages = if name_changed {
+            vec![format!(
+                "Changed hostname from '{}' to '{}'",
+                current.running, name
+            )]
+        } else {
+            vec![format!(
+                "Updated pretty hostname to '{}'",
+                pretty_hostname.as_deref().unwrap_or_default()
+            )]
+        };
 
         // Update /etc/hosts if requested
-        if update_hosts {
+        if update_hosts && current.running != name && !current.running.is_empty() {
             let hosts_updated =
-                Self::update_etc_hosts(connection, &current_hostname, &name, context)?;
+                Self::update_etc_hosts(connection, &current.running, &name, context)?;
             if hosts_updated {
                 messages.push("Updated /etc/hosts".to_string());
             }
@@ -377,7 +506,11 @@ impl Module for HostnameModule {
 
         let mut output = ModuleOutput::changed(messages.join(". "))
             .with_data("name", serde_json::json!(name))
-            .with_data("previous_name", serde_json::json!(current_hostname))
+            .with_data("previous_name", serde_json::json!(current.running))
+            .with_data(
+                "previous_persistent_name",
+                serde_json::json!(current.persistent),
+            )
             .with_data(
                 "strategy",
                 serde_json::json!(format!("{:?}", effective_strategy).to_lowercase()),
@@ -386,6 +519,9 @@ impl Module for HostnameModule {
         if let Some(ref pretty) = pretty_hostname {
             output = output.with_data("pretty_hostname", serde_json::json!(pretty));
         }
+        if context.diff_mode {
+            output = output.with_diff(diff);
+        }
 
         Ok(output)
     }
@@ -405,15 +541,27 @@ impl Module for HostnameModule {
         };
 
         let name = params.get_string_required("name")?;
-        let current_hostname = Self::get_current_hostname(connection, context).unwrap_or_default();
+        let strategy_str = params
+            .get_string("use")?
+            .unwrap_or_else(|| "auto".to_string());
+        let strategy = match HostnameStrategy::from_str(&strategy_str)? {
+            HostnameStrategy::Auto => Self::detect_strategy(connection, context)?,
+            s => s,
+        };
+        let current = Self::read_state(connection, &strategy, context)?;
+        let pretty_hostname = params.get_string("pretty_hostname")?;
+        let managed_pretty = pretty_hostname
+            .as_deref()
+            .filter(|_| strategy == HostnameStrategy::Systemd);
 
-        if current_hostname == name {
+        let desired = current.desired(&name, managed_pretty);
+        if current == desired {
             return Ok(None);
         }
 
         Ok(Some(Diff::new(
-            format!("hostname: {}", current_hostname),
-            format!("hostname: {}", name),
+            current.to_diff_string(),
+            desired.to_diff_string(),
         )))
     }
 }
@@ -491,6 +639,23 @@ mod tests {
         assert_eq!(module.required_params(), &["name"]);
     }
 
+    #[test]
+    fn test_parse_hostnamectl_status() {
+        let state = parse_hostnamectl_status(
+            "   Static hostname: web01\n   Pretty hostname: Web Server\n         Icon name: computer-vm\n",
+        );
+        assert_eq!(state.running, "web01");
+        assert_eq!(state.persistent, "web01");
+        assert_eq!(state.pretty.as_deref(), Some("Web Server"));
+        assert!(state.matches("web01"));
+
+        let state =
+            parse_hostnamectl_status("   Static hostname: n/a\nTransient hostname: localhost\n");
+        assert_eq!(state.running, "localhost");
+        assert_eq!(state.persistent, "");
+        assert!(!state.matches("localhost"));
+    }
+
     #[test]
     fn test_shell_escape() {
         assert_eq!(shell_escape("simple"), "simple");
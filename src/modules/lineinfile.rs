@@ -6,10 +6,15 @@
 //! Supports both local and remote execution:
 //! - Local: Uses native Rust std::fs operations
 //! - Remote: Downloads file via connection, edits in memory, uploads back
+//!
+//! With `validate`, the command (`%s` stands for the file) runs against the
+//! edited content in a temp file first; the file is only written if it
+//! succeeds.
 
 use super::{
-    Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
-    ModuleResult, ParamExt,
+    set_local_ownership, validate_local_content, validate_remote_content, Diff, Module,
+    ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams, ModuleResult,
+    ParamExt,
 };
 use crate::connection::TransferOptions;
 use regex::Regex;
@@ -63,6 +68,29 @@ impl LineinfileModule {
         Ok(content.lines().map(|s| s.to_string()).collect())
     }
 
+    /// Error for a missing file when `create` is not set
+    fn missing_file(path: &str) -> ModuleError {
+        ModuleError::ExecutionFailed(format!(
+            "File '{}' does not exist (set create: true to create it)",
+            path
+        ))
+    }
+
+    /// File content for a list of lines
+    fn render(lines: &[String]) -> String {
+        if lines.is_empty() {
+            String::new()
+        } else {
+            format!("{}\n", lines.join("\n"))
+        }
+    }
+
+    /// Diff of the edit; a file that did not exist shows every line as added
+    fn content_diff(path: &str, existed: bool, before: &[String], after: &[String]) -> Diff {
+        let before = existed.then(|| Self::render(before));
+        Diff::for_file(Path::new(path), before.as_deref(), &Self::render(after))
+    }
+
     fn write_file(
         path: &Path,
         lines: &[String],
@@ -70,10 +98,7 @@ impl LineinfileModule {
         mode: Option<u32>,
     ) -> ModuleResult<()> {
         if !path.exists() && !create {
-            return Err(ModuleError::ExecutionFailed(format!(
-                "File '{}' does not exist and create=false",
-                path.display()
-            )));
+            return Err(Self::missing_file(&path.display().to_string()));
         }
 
         // Create parent directories if needed
@@ -83,13 +108,7 @@ impl LineinfileModule {
             }
         }
 
-        let content = if lines.is_empty() {
-            String::new()
-        } else {
-            format!("{}\n", lines.join("\n"))
-        };
-
-        fs::write(path, content)?;
+        fs::write(path, Self::render(lines))?;
 
         if let Some(mode) = mode {
             fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
@@ -273,6 +292,9 @@ impl LineinfileModule {
         firstmatch: bool,
         backrefs: bool,
         mode: Option<u32>,
+        owner: Option<String>,
+        group: Option<String>,
+        validate: Option<String>,
     ) -> ModuleResult<ModuleOutput> {
         let connection = context.connection.as_ref().ok_or_else(|| {
             ModuleError::ExecutionFailed("No connection available for remote execution".to_string())
@@ -294,17 +316,14 @@ impl LineinfileModule {
             let file_exists = conn.path_exists(remote_path).await.unwrap_or(false);
 
             if !file_exists && !create {
-                return Err(ModuleError::ExecutionFailed(format!(
-                    "File '{}' does not exist and create=false",
-                    path
-                )));
+                return Err(Self::missing_file(path));
             }
 
             // Download file content (empty if doesn't exist)
             let content = if file_exists {
-                conn.download_content(remote_path).await.map_err(|e| {
-                    ModuleError::ExecutionFailed(format!("Failed to download file: {}", e))
-                })?
+                conn.download_content(remote_path)
+                    .await
+                    .map_err(|e| ModuleError::from_connection("Failed to download file", e))?
             } else {
                 Vec::new()
             };
@@ -365,15 +384,22 @@ impl LineinfileModule {
                 )));
             }
 
+            let verb = if file_exists { "modify" } else { "create" };
+
             // In check mode, don't actually write
             if check_mode {
                 let diff = if diff_mode {
-                    Some(Diff::new(original_lines.join("\n"), lines.join("\n")))
+                    Some(Self::content_diff(
+                        path,
+                        file_exists,
+                        &original_lines,
+                        &lines,
+                    ))
                 } else {
                     None
                 };
 
-                let mut output = ModuleOutput::changed(format!("Would modify '{}'", path));
+                let mut output = ModuleOutput::changed(format!("Would {} '{}'", verb, path));
 
                 if let Some(d) = diff {
                     output = output.with_diff(d);
@@ -382,6 +408,13 @@ impl LineinfileModule {
                 return Ok(output);
             }
 
+            // Prepare new content
+            let new_content = Self::render(&lines);
+
+            if let Some(ref validate) = validate {
+                validate_remote_content(conn.as_ref(), validate, new_content.as_bytes()).await?;
+            }
+
             // Create backup if requested
             if backup && file_exists {
                 let backup_path_str = format!("{}{}", path, backup_suffix);
@@ -389,36 +422,37 @@ impl LineinfileModule {
 
                 conn.upload_content(&content, backup_dest, None)
                     .await
-                    .map_err(|e| {
-                        ModuleError::ExecutionFailed(format!("Failed to create backup: {}", e))
-                    })?;
+                    .map_err(|e| ModuleError::from_connection("Failed to create backup", e))?;
             }
 
-            // Prepare new content
-            let new_content = if lines.is_empty() {
-                String::new()
-            } else {
-                format!("{}\n", lines.join("\n"))
-            };
-
             // Build transfer options
             let mut transfer_opts = TransferOptions::new();
             if let Some(m) = mode {
                 transfer_opts = transfer_opts.with_mode(m);
             }
+            if let Some(o) = owner {
+                transfer_opts = transfer_opts.with_owner(o);
+            }
+            if let Some(g) = group {
+                transfer_opts = transfer_opts.with_group(g);
+            }
             transfer_opts = transfer_opts.with_create_dirs();
 
             // Upload modified content
             conn.upload_content(new_content.as_bytes(), remote_path, Some(transfer_opts))
                 .await
-                .map_err(|e| {
-                    ModuleError::ExecutionFailed(format!("Failed to upload file: {}", e))
-                })?;
+                .map_err(|e| ModuleError::from_connection("Failed to upload file", e))?;
 
-            let mut output = ModuleOutput::changed(format!("Modified '{}'", path));
+            let verb = if file_exists { "Modified" } else { "Created" };
+            let mut output = ModuleOutput::changed(format!("{} '{}'", verb, path));
 
             if diff_mode {
-                output = output.with_diff(Diff::new(original_lines.join("\n"), lines.join("\n")));
+                output = output.with_diff(Self::content_diff(
+                    path,
+                    file_exists,
+                    &original_lines,
+                    &lines,
+                ));
             }
 
             if backup && file_exists {
@@ -448,15 +482,16 @@ impl LineinfileModule {
         firstmatch: bool,
         backrefs: bool,
         mode: Option<u32>,
+        owner: Option<String>,
+        group: Option<String>,
+        validate: Option<String>,
     ) -> ModuleResult<ModuleOutput> {
         let path = Path::new(path_str);
 
         // Check if file exists
-        if !path.exists() && !create {
-            return Err(ModuleError::ExecutionFailed(format!(
-                "File '{}' does not exist and create=false",
-                path_str
-            )));
+        let file_exists = path.exists();
+        if !file_exists && !create {
+            return Err(Self::missing_file(path_str));
         }
 
         // Read current content
@@ -515,12 +550,18 @@ impl LineinfileModule {
         // In check mode, don't actually write
         if context.check_mode {
             let diff = if context.diff_mode {
-                Some(Diff::new(original_lines.join("\n"), lines.join("\n")))
+                Some(Self::content_diff(
+                    path_str,
+                    file_exists,
+                    &original_lines,
+                    &lines,
+                ))
             } else {
                 None
             };
 
-            let mut output = ModuleOutput::changed(format!("Would modify '{}'", path_str));
+            let verb = if file_exists { "modify" } else { "create" };
+            let mut output = ModuleOutput::changed(format!("Would {} '{}'", verb, path_str));
 
             if let Some(d) = diff {
                 output = output.with_diff(d);
@@ -529,6 +570,10 @@ impl LineinfileModule {
             return Ok(output);
         }
 
+        if let Some(ref validate) = validate {
+            validate_local_content(validate, Self::render(&lines).as_bytes())?;
+        }
+
         // Create backup if requested
         let backup_file = if backup {
             Self::create_backup(path, &backup_suffix)?
@@ -538,15 +583,22 @@ impl LineinfileModule {
 
         // Write the file
         Self::write_file(path, &lines, create, mode)?;
+        set_local_ownership(path, owner.as_deref(), group.as_deref())?;
 
-        let mut output = ModuleOutput::changed(format!("Modified '{}'", path_str));
+        let verb = if file_exists { "Modified" } else { "Created" };
+        let mut output = ModuleOutput::changed(format!("{} '{}'", verb, path_str));
 
         if let Some(backup_path) = backup_file {
             output = output.with_data("backup_file", serde_json::json!(backup_path));
         }
 
         if context.diff_mode {
-            output = output.with_diff(Diff::new(original_lines.join("\n"), lines.join("\n")));
+            output = output.with_diff(Self::content_diff(
+                path_str,
+                file_exists,
+                &original_lines,
+                &lines,
+            ));
         }
 
         Ok(output)
@@ -614,6 +666,9 @@ impl Module for LineinfileModule {
         let firstmatch = params.get_bool_or("firstmatch", false);
         let backrefs = params.get_bool_or("backrefs", false);
         let mode = params.get_u32("mode")?;
+        let owner = params.get_string("owner")?;
+        let group = params.get_string("group")?;
+        let validate = params.get_string("validate")?;
 
         // Compile regexp if provided
         let regexp = if let Some(ref re_str) = regexp_str {
@@ -642,6 +697,9 @@ impl Module for LineinfileModule {
                 firstmatch,
                 backrefs,
                 mode,
+                owner,
+                group,
+                validate,
             )
         } else {
             // Local execution using filesystem operations
@@ -659,6 +717,9 @@ impl Module for LineinfileModule {
                 firstmatch,
                 backrefs,
                 mode,
+                owner,
+                group,
+                validate,
             )
         }
     }
@@ -896,6 +957,116 @@ mod tests {
         assert!(content.contains("new_line"));
     }
 
+    #[test]
+    fn test_lineinfile_create_sets_mode_and_reports_added_content() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("new_file.txt");
+
+        let module = LineinfileModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "path".to_string(),
+            serde_json::json!(path.to_str().unwrap()),
+        );
+        params.insert("line".to_string(), serde_json::json!("new_line"));
+        params.insert("create".to_string(), serde_json::json!(true));
+        params.insert("mode".to_string(), serde_json::json!("0600"));
+        params.insert(
+            "owner".to_string(),
+            serde_json::json!(nix::unistd::getuid().as_raw().to_string()),
+        );
+
+        let context = ModuleContext::default().with_diff_mode(true);
+        let result = module.execute(&params, &context).unwrap();
+
+        assert!(result.changed);
+        assert!(result.msg.starts_with("Created"));
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("new_line"));
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+
+        let diff = result.diff.unwrap();
+        assert!(diff.before.is_empty());
+        let details = diff.details.unwrap();
+        assert!(details.contains("--- /dev/null"));
+        assert!(details.contains("+new_line"));
+    }
+
+    #[test]
+    fn test_lineinfile_missing_file_without_create_fails() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("missing.txt");
+
+        let module = LineinfileModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "path".to_string(),
+            serde_json::json!(path.to_str().unwrap()),
+        );
+        params.insert("line".to_string(), serde_json::json!("new_line"));
+
+        let context = ModuleContext::default();
+        let err = module.execute(&params, &context).unwrap_err();
+
+        assert!(err.to_string().contains("does not exist"));
+        assert!(err.to_string().contains("create: true"));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_lineinfile_failing_validate_leaves_file_unchanged() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("sudoers");
+        fs::write(&path, "root ALL=(ALL) ALL\n").unwrap();
+
+        let module = LineinfileModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "path".to_string(),
+            serde_json::json!(path.to_str().unwrap()),
+        );
+        params.insert("line".to_string(), serde_json::json!("deploy ALL=(ALL"));
+        params.insert(
+            "validate".to_string(),
+            serde_json::json!(
+                r#"awk '/\(ALL$/ { print "syntax error near line " NR > "/dev/stderr"; exit 1 }' %s"#
+            ),
+        );
+
+        let context = ModuleContext::default();
+        let err = module.execute(&params, &context).unwrap_err();
+
+        assert!(matches!(err, ModuleError::ValidationFailed(_)));
+        assert!(err.to_string().contains("syntax error near line 2"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "root ALL=(ALL) ALL\n");
+    }
+
+    #[test]
+    fn test_lineinfile_validate_sees_proposed_content() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("test.txt");
+        fs::write(&path, "line1\n").unwrap();
+
+        let module = LineinfileModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "path".to_string(),
+            serde_json::json!(path.to_str().unwrap()),
+        );
+        params.insert("line".to_string(), serde_json::json!("line2"));
+        params.insert(
+            "validate".to_string(),
+            serde_json::json!("grep -q line2 %s"),
+        );
+
+        let context = ModuleContext::default();
+        let result = module.execute(&params, &context).unwrap();
+
+        assert!(result.changed);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "line1\nline2\n");
+    }
+
     #[test]
     fn test_lineinfile_check_mode() {
         let temp = TempDir::new().unwrap();
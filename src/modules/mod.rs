@@ -4,6 +4,7 @@
 //! Modules are the building blocks that perform actual work on target systems.
 
 pub mod apt;
+pub mod apt_repository;
 pub mod archive;
 pub mod assert;
 pub mod authorized_key;
@@ -34,8 +35,11 @@ pub mod package;
 pub mod pause;
 pub mod pip;
 pub mod python;
+pub mod replace;
+pub mod script;
 pub mod selinux;
 pub mod service;
+pub mod service_facts;
 pub mod set_fact;
 pub mod shell;
 pub mod stat;
@@ -50,15 +54,17 @@ pub mod user;
 pub mod wait_for;
 pub mod windows;
 pub mod yum;
+pub mod yum_repository;
 
 pub use python::PythonModuleExecutor;
 
-use crate::connection::Connection;
+use crate::connection::{Connection, ConnectionError, ExecuteOptions};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::path::Path;
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -259,6 +265,28 @@ pub enum ModuleError {
 
     #[error("Validation failed: {0}")]
     ValidationFailed(String),
+
+    #[error("Connection error: {0}")]
+    Connection(#[from] ConnectionError),
+}
+
+impl ModuleError {
+    /// Whether this error was caused by the host being unreachable
+    pub fn is_unreachable(&self) -> bool {
+        matches!(self, ModuleError::Connection(e) if e.is_unreachable())
+    }
+
+    /// Convert a connection error raised while doing `context`.
+    ///
+    /// Transport failures stay `ModuleError::Connection` so the host is
+    /// reported unreachable; anything else fails the task with `context`.
+    pub fn from_connection(context: impl fmt::Display, err: ConnectionError) -> Self {
+        if err.is_unreachable() {
+            ModuleError::Connection(err)
+        } else {
+            ModuleError::ExecutionFailed(format!("{}: {}", context, err))
+        }
+    }
 }
 
 /// Result type for module operations
@@ -382,6 +410,36 @@ impl Diff {
         self.details = Some(details.into());
         self
     }
+
+    /// Diff a file's content, with a unified diff in `details`.
+    ///
+    /// A missing file (`before` of `None`) is diffed as empty, so every line
+    /// of the new content shows up as an addition.
+    pub fn for_file(path: &Path, before: Option<&str>, after: &str) -> Self {
+        let new_header = path.display().to_string();
+        let old_header = if before.is_some() {
+            new_header.as_str()
+        } else {
+            "/dev/null"
+        };
+        let before = before.unwrap_or_default();
+        let details = similar::TextDiff::from_lines(before, after)
+            .unified_diff()
+            .header(old_header, &new_header)
+            .to_string();
+
+        Self::new(before, after).with_details(details)
+    }
+
+    /// Diff a file that is being removed, showing its content as deleted.
+    pub fn for_removed_file(path: &Path, before: &str) -> Self {
+        let details = similar::TextDiff::from_lines(before, "")
+            .unified_diff()
+            .header(&path.display().to_string(), "/dev/null")
+            .to_string();
+
+        Self::new(before, "").with_details(details)
+    }
 }
 
 /// Result of a module execution
@@ -393,6 +451,12 @@ pub struct ModuleOutput {
     pub msg: String,
     /// Status of the execution
     pub status: ModuleStatus,
+    /// Whether the module failed
+    ///
+    /// Set from the status by the constructors; independent of `changed`, so
+    /// a module can report that it changed something and still failed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub failed: bool,
     /// Optional diff showing what changed
     #[serde(skip_serializing_if = "Option::is_none")]
     pub diff: Option<Diff>,
@@ -408,6 +472,9 @@ pub struct ModuleOutput {
     /// Return code (for command modules)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rc: Option<i32>,
+    /// Non-fatal warnings raised during execution (e.g. deprecations)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 impl ModuleOutput {
@@ -417,11 +484,13 @@ impl ModuleOutput {
             changed: false,
             msg: msg.into(),
             status: ModuleStatus::Ok,
+            failed: false,
             diff: None,
             data: HashMap::new(),
             stdout: None,
             stderr: None,
             rc: None,
+            warnings: Vec::new(),
         }
     }
 
@@ -431,11 +500,13 @@ impl ModuleOutput {
             changed: true,
             msg: msg.into(),
             status: ModuleStatus::Changed,
+            failed: false,
             diff: None,
             data: HashMap::new(),
             stdout: None,
             stderr: None,
             rc: None,
+            warnings: Vec::new(),
         }
     }
 
@@ -445,11 +516,13 @@ impl ModuleOutput {
             changed: false,
             msg: msg.into(),
             status: ModuleStatus::Failed,
+            failed: true,
             diff: None,
             data: HashMap::new(),
             stdout: None,
             stderr: None,
             rc: None,
+            warnings: Vec::new(),
         }
     }
 
@@ -459,14 +532,22 @@ impl ModuleOutput {
             changed: false,
             msg: msg.into(),
             status: ModuleStatus::Skipped,
+            failed: false,
             diff: None,
             data: HashMap::new(),
             stdout: None,
             stderr: None,
             rc: None,
+            warnings: Vec::new(),
         }
     }
 
+    /// Mark the output as failed or not, keeping `changed` as it is
+    pub fn with_failed(mut self, failed: bool) -> Self {
+        self.failed = failed;
+        self
+    }
+
     /// Add a diff to the output
     pub fn with_diff(mut self, diff: Diff) -> Self {
         self.diff = Some(diff);
@@ -479,6 +560,12 @@ impl ModuleOutput {
         self
     }
 
+    /// Add a warning to the output
+    pub fn with_warning(mut self, warning: impl Into<String>) -> Self {
+        self.warnings.push(warning.into());
+        self
+    }
+
     /// Add stdout/stderr/rc for command outputs
     pub fn with_command_output(
         mut self,
@@ -515,13 +602,16 @@ pub struct ModuleContext {
     pub become_method: Option<String>,
     /// User to become
     pub become_user: Option<String>,
+    /// Password for the privilege escalation method
+    pub become_password: Option<String>,
     /// Connection to use for remote operations
     pub connection: Option<Arc<dyn Connection + Send + Sync>>,
 }
 
 impl std::fmt::Debug for ModuleContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ModuleContext")
+        let mut debug = f.debug_struct("ModuleContext");
+        debug
             .field("check_mode", &self.check_mode)
             .field("diff_mode", &self.diff_mode)
             .field("vars", &self.vars)
@@ -529,7 +619,12 @@ impl std::fmt::Debug for ModuleContext {
             .field("work_dir", &self.work_dir)
             .field("become", &self.r#become)
             .field("become_method", &self.become_method)
-            .field("become_user", &self.become_user)
+            .field("become_user", &self.become_user);
+        // Only mention the password when one is set, and never its value
+        if self.become_password.is_some() {
+            debug.field("become_password", &"[REDACTED]");
+        }
+        debug
             .field(
                 "connection",
                 &self.connection.as_ref().map(|c| c.identifier()),
@@ -549,6 +644,7 @@ impl Default for ModuleContext {
             r#become: false,
             become_method: None,
             become_user: None,
+            become_password: None,
             connection: None,
         }
     }
@@ -583,6 +679,317 @@ impl ModuleContext {
         self.connection = Some(connection);
         self
     }
+
+    pub fn with_work_dir(mut self, work_dir: impl Into<String>) -> Self {
+        self.work_dir = Some(work_dir.into());
+        self
+    }
+
+    /// Execute options carrying this context's privilege escalation
+    ///
+    /// Modules that run commands over the connection start from these so
+    /// `become` wraps every command they send.
+    pub fn execute_options(&self) -> ExecuteOptions {
+        let mut options = ExecuteOptions::new();
+        if self.r#become {
+            options = options.with_escalation(self.become_user.clone());
+            options.escalate_method = self.become_method.clone();
+            options.escalate_password = self.become_password.clone();
+        }
+        options
+    }
+
+    /// Resolve the directory a command-style module should run in.
+    ///
+    /// An explicit `chdir` parameter takes precedence; otherwise `work_dir`
+    /// is used as the default.
+    pub fn chdir(&self, params: &ModuleParams) -> ModuleResult<Option<String>> {
+        match params.get_string("chdir")? {
+            Some(dir) => {
+                validate_path_param(&dir, "chdir")?;
+                Ok(Some(dir))
+            }
+            None => Ok(self.work_dir.clone()),
+        }
+    }
+}
+
+/// Error returned when a module's working directory does not exist.
+pub(crate) fn chdir_not_found(dir: &str) -> ModuleError {
+    ModuleError::InvalidParameter(format!(
+        "chdir '{}' does not exist or is not a directory",
+        dir
+    ))
+}
+
+/// Ensure a local working directory exists before spawning a process in it.
+pub(crate) fn ensure_local_chdir(dir: &str) -> ModuleResult<()> {
+    if Path::new(dir).is_dir() {
+        Ok(())
+    } else {
+        Err(chdir_not_found(dir))
+    }
+}
+
+/// Resolve a `src` given as a list of candidates to the first one that exists.
+///
+/// Relative candidates are looked up in `<work_dir>/<subdir>` (`files` or
+/// `templates`), then `work_dir`, then the current directory. A plain string
+/// `src` is returned unchanged.
+pub(crate) fn resolve_first_found<'a>(
+    params: &'a ModuleParams,
+    context: &ModuleContext,
+    subdir: &str,
+) -> ModuleResult<std::borrow::Cow<'a, ModuleParams>> {
+    let Some(serde_json::Value::Array(_)) = params.get("src") else {
+        return Ok(std::borrow::Cow::Borrowed(params));
+    };
+    let candidates = params.get_vec_string("src")?.unwrap_or_default();
+
+    let found = candidates.iter().find_map(|candidate| {
+        let path = Path::new(candidate);
+        let mut search = Vec::new();
+        if let (true, Some(work_dir)) = (path.is_relative(), &context.work_dir) {
+            search.push(Path::new(work_dir).join(subdir).join(path));
+            search.push(Path::new(work_dir).join(path));
+        }
+        search.push(path.to_path_buf());
+        search.into_iter().find(|p| p.exists())
+    });
+
+    let found = found.ok_or_else(|| {
+        ModuleError::ExecutionFailed(format!(
+            "None of the src candidates exist: {}",
+            candidates.join(", ")
+        ))
+    })?;
+
+    let mut resolved = params.clone();
+    resolved.insert(
+        "src".to_string(),
+        serde_json::Value::String(found.to_string_lossy().into_owned()),
+    );
+    Ok(std::borrow::Cow::Owned(resolved))
+}
+
+/// Command line for a `validate` parameter checking the file at `path`.
+fn validation_command(validate: &str, path: &Path) -> ModuleResult<String> {
+    if !validate.contains("%s") {
+        return Err(ModuleError::InvalidParameter(format!(
+            "validate must contain %s: {}",
+            validate
+        )));
+    }
+    Ok(validate.replace("%s", &shell_words::quote(&path.to_string_lossy())))
+}
+
+/// Error for a validator that rejected the proposed content.
+fn validation_failed(validate: &str, stdout: &str, stderr: &str) -> ModuleError {
+    let output = if stderr.trim().is_empty() {
+        stdout.trim()
+    } else {
+        stderr.trim()
+    };
+    ModuleError::ValidationFailed(format!("'{}' failed: {}", validate, output))
+}
+
+/// Run a `validate` command against proposed file content on the control node.
+///
+/// The content is written to a temp file substituted for `%s`, so the real
+/// file is only replaced once the validator accepts it.
+pub(crate) fn validate_local_content(validate: &str, content: &[u8]) -> ModuleResult<()> {
+    let mut staged = tempfile::NamedTempFile::new()?;
+    std::io::Write::write_all(&mut staged, content)?;
+
+    let command = validation_command(validate, staged.path())?;
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .output()
+        .map_err(|e| {
+            ModuleError::ExecutionFailed(format!("Failed to run validation command: {}", e))
+        })?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(validation_failed(
+            validate,
+            &String::from_utf8_lossy(&output.stdout),
+            &String::from_utf8_lossy(&output.stderr),
+        ))
+    }
+}
+
+/// Run a `validate` command against proposed file content on the target.
+///
+/// The content is staged in the connection's managed temp directory, or in
+/// a temp directory of its own that is removed afterwards.
+pub(crate) async fn validate_remote_content(
+    connection: &dyn Connection,
+    validate: &str,
+    content: &[u8],
+) -> ModuleResult<()> {
+    let own_tmp;
+    let (tmp, owns_tmp) = match connection.remote_tmp() {
+        Some(tmp) => (tmp, false),
+        None => {
+            own_tmp = crate::connection::RemoteTmp::new(uuid::Uuid::new_v4().to_string());
+            (&own_tmp, true)
+        }
+    };
+
+    let name = format!("validate-{}", uuid::Uuid::new_v4().simple());
+    let result = match tmp.stage(connection, &name, content).await {
+        Ok(staged) => {
+            let result = match validation_command(validate, &staged) {
+                Ok(command) => connection
+                    .execute(&command, None)
+                    .await
+                    .map_err(ModuleError::from),
+                Err(e) => Err(e),
+            };
+            if !owns_tmp {
+                let rm = format!("rm -f {}", shell_words::quote(&staged.to_string_lossy()));
+                let _ = connection.execute(&rm, None).await;
+            }
+            result
+        }
+        Err(e) => Err(ModuleError::ExecutionFailed(format!(
+            "Failed to stage content for validation: {}",
+            e
+        ))),
+    };
+    if owns_tmp {
+        if let Err(e) = tmp.cleanup(connection).await {
+            tracing::warn!("Failed to remove validation temp directory: {}", e);
+        }
+    }
+
+    let result = result?;
+    if result.success {
+        Ok(())
+    } else {
+        Err(validation_failed(validate, &result.stdout, &result.stderr))
+    }
+}
+
+/// Set the owner and/or group of a local file, given as names or numeric ids.
+pub(crate) fn set_local_ownership(
+    path: &Path,
+    owner: Option<&str>,
+    group: Option<&str>,
+) -> ModuleResult<()> {
+    use nix::unistd::{Gid, Group, Uid, User};
+
+    if owner.is_none() && group.is_none() {
+        return Ok(());
+    }
+
+    let uid = match owner {
+        Some(name) => Some(match name.parse::<u32>() {
+            Ok(id) => Uid::from_raw(id),
+            Err(_) => {
+                User::from_name(name)
+                    .map_err(|e| ModuleError::ExecutionFailed(e.to_string()))?
+                    .ok_or_else(|| {
+                        ModuleError::InvalidParameter(format!("Unknown owner '{}'", name))
+                    })?
+                    .uid
+            }
+        }),
+        None => None,
+    };
+    let gid = match group {
+        Some(name) => Some(match name.parse::<u32>() {
+            Ok(id) => Gid::from_raw(id),
+            Err(_) => {
+                Group::from_name(name)
+                    .map_err(|e| ModuleError::ExecutionFailed(e.to_string()))?
+                    .ok_or_else(|| {
+                        ModuleError::InvalidParameter(format!("Unknown group '{}'", name))
+                    })?
+                    .gid
+            }
+        }),
+        None => None,
+    };
+
+    std::os::unix::fs::chown(path, uid.map(Uid::as_raw), gid.map(Gid::as_raw))?;
+    Ok(())
+}
+
+/// Commands that are better handled by a dedicated module, with the module to use
+const COMMAND_MODULE_HINTS: &[(&str, &str)] = &[
+    ("apt-get", "apt"),
+    ("apt", "apt"),
+    ("aptitude", "apt"),
+    ("yum", "yum"),
+    ("dnf", "dnf"),
+    ("zypper", "zypper"),
+    ("pacman", "pacman"),
+    ("apk", "apk"),
+    ("useradd", "user"),
+    ("usermod", "user"),
+    ("userdel", "user"),
+    ("groupadd", "group"),
+    ("groupmod", "group"),
+    ("groupdel", "group"),
+    ("service", "service"),
+    ("systemctl", "systemd"),
+    ("rm", "file"),
+    ("rmdir", "file"),
+    ("mkdir", "file"),
+    ("touch", "file"),
+    ("chmod", "file"),
+    ("chown", "file"),
+    ("chgrp", "file"),
+    ("ln", "file"),
+    ("curl", "get_url or uri"),
+    ("wget", "get_url or uri"),
+    ("git", "git"),
+    ("tar", "unarchive"),
+    ("unzip", "unarchive"),
+    ("mount", "mount"),
+    ("crontab", "cron"),
+    ("sed", "lineinfile or replace"),
+];
+
+/// Warnings suggesting a module for commands run through `command`/`shell`
+///
+/// Each `;`, `&&`, `||`, `|` or newline separated part of the command is
+/// checked by its program name, skipping a leading `sudo` and environment
+/// assignments. Suppressed when the task sets `warn: false`.
+pub fn command_warnings(cmd: &str, params: &ModuleParams) -> Vec<String> {
+    if !params.get_bool_or("warn", true) {
+        return Vec::new();
+    }
+
+    let mut warnings = Vec::new();
+    for part in cmd.split([';', '&', '|', '\n']) {
+        let program = part
+            .split_whitespace()
+            .find(|word| *word != "sudo" && !word.contains('='))
+            .map(|word| word.rsplit('/').next().unwrap_or(word));
+        let Some(program) = program else {
+            continue;
+        };
+        let Some((_, module)) = COMMAND_MODULE_HINTS
+            .iter()
+            .find(|(name, _)| *name == program)
+        else {
+            continue;
+        };
+        let warning = format!(
+            "Consider using the {} module rather than running '{}'. \
+             Set 'warn: false' on this task to hide this warning.",
+            module, program
+        );
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+    warnings
 }
 
 /// Trait that all modules must implement
@@ -804,9 +1211,47 @@ impl ParamExt for ModuleParams {
     }
 }
 
+/// Collections whose modules are also provided natively under their short
+/// name, so `ansible.builtin.copy` resolves to the built-in `copy` module
+pub const NATIVE_COLLECTIONS: &[&str] = &[
+    "ansible.builtin",
+    "ansible.legacy",
+    "ansible.posix",
+    "community.general",
+];
+
+/// Split a fully-qualified module name into its collection and short name
+///
+/// Returns `None` for short names such as `copy`.
+pub fn split_fqcn(name: &str) -> Option<(&str, &str)> {
+    let (collection, short) = name.rsplit_once('.')?;
+    (collection.contains('.') && !short.is_empty()).then_some((collection, short))
+}
+
+/// Strip the `ansible.builtin` / `ansible.legacy` namespace from a module name
+///
+/// Modules of these collections are always core modules, so the short name
+/// can be used without consulting a registry. Other names are returned as is.
+pub fn builtin_module_name(name: &str) -> &str {
+    match split_fqcn(name) {
+        Some(("ansible.builtin" | "ansible.legacy", short)) => short,
+        _ => name,
+    }
+}
+
 /// Registry for looking up modules by name
 pub struct ModuleRegistry {
     modules: HashMap<String, Arc<dyn Module>>,
+    aliases: HashMap<String, ModuleAlias>,
+}
+
+/// An alternative name that resolves to a registered module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleAlias {
+    /// Name of the module the alias points to
+    pub target: String,
+    /// Whether using the alias should emit a deprecation warning
+    pub deprecated: bool,
 }
 
 impl ModuleRegistry {
@@ -814,6 +1259,7 @@ impl ModuleRegistry {
     pub fn new() -> Self {
         Self {
             modules: HashMap::new(),
+            aliases: HashMap::new(),
         }
     }
 
@@ -826,16 +1272,20 @@ impl ModuleRegistry {
         registry.register(Arc::new(package::PackageModule));
         registry.register(Arc::new(pip::PipModule));
         registry.register(Arc::new(yum::YumModule));
+        registry.register(Arc::new(apt_repository::AptRepositoryModule));
+        registry.register(Arc::new(yum_repository::YumRepositoryModule));
 
         // Core command modules
         registry.register(Arc::new(command::CommandModule));
         registry.register(Arc::new(shell::ShellModule));
+        registry.register(Arc::new(script::ScriptModule));
 
         // File/transport modules
         registry.register(Arc::new(blockinfile::BlockinfileModule));
         registry.register(Arc::new(copy::CopyModule));
         registry.register(Arc::new(file::FileModule));
         registry.register(Arc::new(lineinfile::LineinfileModule));
+        registry.register(Arc::new(replace::ReplaceModule));
         registry.register(Arc::new(template::TemplateModule));
 
         // System management modules
@@ -844,6 +1294,7 @@ impl ModuleRegistry {
         registry.register(Arc::new(hostname::HostnameModule));
         registry.register(Arc::new(mount::MountModule));
         registry.register(Arc::new(service::ServiceModule));
+        registry.register(Arc::new(service_facts::ServiceFactsModule));
         registry.register(Arc::new(sysctl::SysctlModule));
         registry.register(Arc::new(user::UserModule));
 
@@ -858,6 +1309,7 @@ impl ModuleRegistry {
         registry.register(Arc::new(stat::StatModule));
 
         registry.register(Arc::new(facts::FactsModule));
+        registry.register_alias("setup", "gather_facts");
 
         // Network device configuration modules
         network::register_network_modules(&mut registry);
@@ -870,14 +1322,90 @@ impl ModuleRegistry {
         self.modules.insert(module.name().to_string(), module);
     }
 
-    /// Get a module by name
+    /// Register an alternative name for a module
+    ///
+    /// Aliases are followed transparently by [`get`](Self::get) and
+    /// [`execute`](Self::execute). A real module registered under the same
+    /// name always takes precedence over the alias.
+    pub fn register_alias(&mut self, alias: impl Into<String>, target: impl Into<String>) {
+        self.aliases.insert(
+            alias.into(),
+            ModuleAlias {
+                target: target.into(),
+                deprecated: false,
+            },
+        );
+    }
+
+    /// Register a deprecated alias that warns whenever it is used
+    pub fn register_deprecated_alias(
+        &mut self,
+        alias: impl Into<String>,
+        target: impl Into<String>,
+    ) {
+        self.aliases.insert(
+            alias.into(),
+            ModuleAlias {
+                target: target.into(),
+                deprecated: true,
+            },
+        );
+    }
+
+    /// Get the alias entry for a name, if it is an alias
+    pub fn alias(&self, name: &str) -> Option<&ModuleAlias> {
+        self.aliases.get(name)
+    }
+
+    /// Resolve a module name, following aliases to the canonical name
+    ///
+    /// A fully-qualified name from one of the [`NATIVE_COLLECTIONS`] resolves
+    /// like its short name when that module is registered.
+    pub fn resolve_name<'a>(&'a self, name: &'a str) -> &'a str {
+        if self.modules.contains_key(name) {
+            return name;
+        }
+        if let Some(alias) = self.aliases.get(name) {
+            return alias.target.as_str();
+        }
+        match split_fqcn(name) {
+            Some((collection, short))
+                if NATIVE_COLLECTIONS.contains(&collection)
+                    && (self.modules.contains_key(short) || self.aliases.contains_key(short)) =>
+            {
+                self.resolve_name(short)
+            }
+            _ => name,
+        }
+    }
+
+    /// Deprecation warning for a name, if it is a deprecated alias
+    pub fn deprecation_warning(&self, name: &str) -> Option<String> {
+        if self.modules.contains_key(name) {
+            return None;
+        }
+        self.aliases
+            .get(name)
+            .filter(|alias| alias.deprecated)
+            .map(|alias| {
+                format!(
+                    "Module '{}' is deprecated, use '{}' instead",
+                    name, alias.target
+                )
+            })
+    }
+
+    /// Get a module by name, following aliases
     pub fn get(&self, name: &str) -> Option<Arc<dyn Module>> {
-        self.modules.get(name).cloned()
+        if let Some(warning) = self.deprecation_warning(name) {
+            tracing::warn!("{}", warning);
+        }
+        self.modules.get(self.resolve_name(name)).cloned()
     }
 
-    /// Check if a module exists
+    /// Check if a module exists, either directly or through an alias
     pub fn contains(&self, name: &str) -> bool {
-        self.modules.contains_key(name)
+        self.modules.contains_key(self.resolve_name(name))
     }
 
     /// Get all module names
@@ -892,9 +1420,15 @@ impl ModuleRegistry {
         params: &ModuleParams,
         context: &ModuleContext,
     ) -> ModuleResult<ModuleOutput> {
-        let module = self
-            .get(name)
-            .ok_or_else(|| ModuleError::NotFound(name.to_string()))?;
+        let module = self.get(name).ok_or_else(|| match split_fqcn(name) {
+            Some((collection, _)) if !NATIVE_COLLECTIONS.contains(&collection) => {
+                ModuleError::NotFound(format!(
+                    "{} (collection '{}' is not provided natively)",
+                    name, collection
+                ))
+            }
+            _ => ModuleError::NotFound(name.to_string()),
+        })?;
 
         // Validate parameters first
         module.validate_params(params)?;
@@ -907,11 +1441,16 @@ impl ModuleRegistry {
         }
 
         // Execute based on mode
-        if context.check_mode {
+        let output = if context.check_mode {
             module.check(params, context)
         } else {
             module.execute(params, context)
-        }
+        }?;
+
+        Ok(match self.deprecation_warning(name) {
+            Some(warning) => output.with_warning(warning),
+            None => output,
+        })
     }
 }
 
@@ -968,6 +1507,102 @@ mod tests {
         assert_eq!(module.name(), "test");
     }
 
+    #[test]
+    fn test_module_error_unreachable_classification() {
+        let unreachable = || {
+            [
+                ConnectionError::ConnectionFailed("refused".into()),
+                ConnectionError::AuthenticationFailed("bad key".into()),
+                ConnectionError::Timeout(30),
+            ]
+        };
+        for err in unreachable() {
+            assert!(ModuleError::from(err).is_unreachable());
+        }
+        for err in unreachable() {
+            assert!(ModuleError::from_connection("Failed to stat", err).is_unreachable());
+        }
+
+        let failed = ModuleError::from(ConnectionError::ExecutionFailed("exit 1".into()));
+        assert!(!failed.is_unreachable());
+        assert!(!ModuleError::ExecutionFailed("boom".into()).is_unreachable());
+
+        let wrapped = ModuleError::from_connection(
+            "Failed to stat",
+            ConnectionError::ExecutionFailed("exit 1".into()),
+        );
+        assert!(
+            matches!(wrapped, ModuleError::ExecutionFailed(ref m) if m.starts_with("Failed to stat: "))
+        );
+    }
+
+    #[test]
+    fn test_module_registry_aliases() {
+        let mut registry = ModuleRegistry::new();
+        registry.register(Arc::new(TestModule));
+        registry.register_alias("test_alias", "test");
+        registry.register_deprecated_alias("old_test", "test");
+
+        assert!(registry.contains("test_alias"));
+        assert!(registry.contains("old_test"));
+        assert_eq!(registry.resolve_name("old_test"), "test");
+        assert_eq!(registry.get("test_alias").unwrap().name(), "test");
+        assert!(registry.deprecation_warning("test_alias").is_none());
+
+        let params: ModuleParams = HashMap::new();
+        let context = ModuleContext::default();
+
+        let output = registry.execute("test_alias", &params, &context).unwrap();
+        assert!(output.changed);
+        assert!(output.warnings.is_empty());
+
+        let output = registry.execute("old_test", &params, &context).unwrap();
+        assert!(output.changed);
+        assert_eq!(
+            output.warnings,
+            vec!["Module 'old_test' is deprecated, use 'test' instead".to_string()]
+        );
+
+        // Aliases to missing modules do not resolve
+        registry.register_alias("dangling", "nonexistent");
+        assert!(!registry.contains("dangling"));
+        assert!(matches!(
+            registry.execute("dangling", &params, &context),
+            Err(ModuleError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_module_registry_resolves_fqcn() {
+        let mut registry = ModuleRegistry::new();
+        registry.register(Arc::new(TestModule));
+
+        assert_eq!(registry.resolve_name("ansible.builtin.test"), "test");
+        assert_eq!(registry.resolve_name("ansible.posix.test"), "test");
+        assert!(registry.contains("ansible.legacy.test"));
+        assert!(!registry.contains("ansible.builtin.missing"));
+
+        // Collections that are not provided natively are not guessed at
+        assert!(!registry.contains("acme.widgets.test"));
+        let params: ModuleParams = HashMap::new();
+        let err = registry
+            .execute("acme.widgets.test", &params, &ModuleContext::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("collection 'acme.widgets'"));
+
+        assert_eq!(split_fqcn("test"), None);
+        assert_eq!(split_fqcn("a.test"), None);
+        assert_eq!(
+            split_fqcn("community.general.ufw"),
+            Some(("community.general", "ufw"))
+        );
+        assert_eq!(builtin_module_name("ansible.builtin.copy"), "copy");
+        assert_eq!(
+            builtin_module_name("ansible.posix.mount"),
+            "ansible.posix.mount"
+        );
+    }
+
     #[test]
     fn test_module_output() {
         let output = ModuleOutput::changed("Something changed")
@@ -980,6 +1615,17 @@ mod tests {
         assert!(output.data.contains_key("key"));
     }
 
+    #[test]
+    fn test_module_output_failed_defaults_from_status() {
+        assert!(ModuleOutput::failed("boom").failed);
+        assert!(!ModuleOutput::ok("fine").failed);
+        assert!(!ModuleOutput::changed("done").failed);
+
+        let output = ModuleOutput::changed("half done").with_failed(true);
+        assert!(output.changed);
+        assert!(output.failed);
+    }
+
     #[test]
     fn test_param_ext() {
         let mut params: ModuleParams = HashMap::new();
@@ -1062,4 +1708,30 @@ mod tests {
         assert!(validate_package_name("pkg>file").is_err()); // redirect
         assert!(validate_package_name("pkg<file").is_err()); // redirect
     }
+
+    #[test]
+    fn test_command_warnings() {
+        let params: ModuleParams = HashMap::new();
+
+        let warnings = command_warnings("sudo apt-get install -y nginx && rm -rf /tmp/x", &params);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("apt module"));
+        assert!(warnings[0].contains("'apt-get'"));
+        assert!(warnings[1].contains("file module"));
+
+        assert_eq!(
+            command_warnings(
+                "DEBIAN_FRONTEND=noninteractive /usr/bin/apt-get update",
+                &params
+            )
+            .len(),
+            1
+        );
+        assert!(command_warnings("echo apt-get | cat", &params).is_empty());
+        assert!(command_warnings("rm a; rm b", &params).len() == 1);
+
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("warn".to_string(), serde_json::json!(false));
+        assert!(command_warnings("useradd deploy", &params).is_empty());
+    }
 }
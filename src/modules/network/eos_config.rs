@@ -529,16 +529,7 @@ pub struct EosConfigModule;
 impl EosConfigModule {
     /// Build execute options with privilege escalation if needed
     fn build_execute_options(context: &ModuleContext) -> Option<ExecuteOptions> {
-        if context.r#become {
-            Some(ExecuteOptions {
-                escalate: true,
-                escalate_user: context.become_user.clone(),
-                escalate_method: context.become_method.clone(),
-                ..Default::default()
-            })
-        } else {
-            None
-        }
+        context.r#become.then(|| context.execute_options())
     }
 
     /// Build eAPI client with appropriate SSL settings
@@ -1030,7 +1021,7 @@ impl EosConfigModule {
         connection
             .execute(command, options)
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("SSH command failed: {}", e)))
+            .map_err(|e| ModuleError::from_connection("SSH command failed", e))
     }
 
     /// Get running configuration via SSH
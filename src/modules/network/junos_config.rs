@@ -924,16 +924,7 @@ impl JunosConfig {
 impl JunosConfigModule {
     /// Build execute options with privilege escalation if needed
     fn build_execute_options(context: &ModuleContext) -> Option<ExecuteOptions> {
-        if context.r#become {
-            Some(ExecuteOptions {
-                escalate: true,
-                escalate_user: context.become_user.clone(),
-                escalate_method: context.become_method.clone(),
-                ..Default::default()
-            })
-        } else {
-            None
-        }
+        context.r#become.then(|| context.execute_options())
     }
 
     /// Execute a CLI command on the device
@@ -946,7 +937,7 @@ impl JunosConfigModule {
         connection
             .execute(command, options)
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("CLI execution failed: {}", e)))
+            .map_err(|e| ModuleError::from_connection("CLI execution failed", e))
     }
 
     /// Execute JunOS CLI command in configuration mode
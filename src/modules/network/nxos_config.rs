@@ -445,16 +445,7 @@ pub struct NxosConfigModule;
 impl NxosConfigModule {
     /// Build execute options with privilege escalation if needed
     fn build_execute_options(context: &ModuleContext) -> Option<ExecuteOptions> {
-        if context.r#become {
-            Some(ExecuteOptions {
-                escalate: true,
-                escalate_user: context.become_user.clone(),
-                escalate_method: context.become_method.clone(),
-                ..Default::default()
-            })
-        } else {
-            None
-        }
+        context.r#become.then(|| context.execute_options())
     }
 
     /// Execute a command via SSH connection
@@ -467,7 +458,7 @@ impl NxosConfigModule {
         connection
             .execute(command, options)
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("SSH command failed: {}", e)))
+            .map_err(|e| ModuleError::from_connection("SSH command failed", e))
     }
 
     /// Execute commands via NX-API
@@ -113,6 +113,19 @@ impl PackageManager {
         }
     }
 
+    /// Command that upgrades already-installed packages to the newest version
+    pub fn latest_cmd(&self) -> Vec<&'static str> {
+        match self {
+            PackageManager::Apt => vec!["apt-get", "install", "-y", "--only-upgrade"],
+            PackageManager::Dnf => vec!["dnf", "upgrade", "-y"],
+            PackageManager::Yum => vec!["yum", "update", "-y"],
+            PackageManager::Pacman => vec!["pacman", "-S", "--noconfirm"],
+            PackageManager::Zypper => vec!["zypper", "update", "-y"],
+            PackageManager::Apk => vec!["apk", "add", "--upgrade"],
+            PackageManager::Brew => vec!["brew", "upgrade"],
+        }
+    }
+
     pub fn is_installed(&self, package: &str) -> ModuleResult<bool> {
         let result = match self {
             PackageManager::Apt => Command::new("dpkg")
@@ -165,16 +178,75 @@ impl PackageManager {
 
         match output {
             Ok(o) if o.status.success() => {
-                let version = String::from_utf8_lossy(&o.stdout).trim().to_string();
+                let stdout = String::from_utf8_lossy(&o.stdout);
+                // pacman prints "name version"
+                let version = match self {
+                    PackageManager::Pacman => stdout.split_whitespace().last().unwrap_or(""),
+                    _ => stdout.trim(),
+                };
                 if version.is_empty() {
                     Ok(None)
                 } else {
-                    Ok(Some(version))
+                    Ok(Some(version.to_string()))
                 }
             }
             _ => Ok(None),
         }
     }
+
+    /// Get the version the package manager would install, if it can tell
+    pub fn get_candidate_version(&self, package: &str) -> ModuleResult<Option<String>> {
+        let output = match self {
+            PackageManager::Apt => Command::new("apt-cache").args(["policy", package]).output(),
+            PackageManager::Dnf => Command::new("dnf")
+                .args([
+                    "repoquery",
+                    "-q",
+                    "--latest-limit=1",
+                    "--qf",
+                    "%{version}-%{release}",
+                    package,
+                ])
+                .output(),
+            PackageManager::Yum => Command::new("repoquery")
+                .args(["-q", "--qf", "%{version}-%{release}", package])
+                .output(),
+            PackageManager::Pacman => Command::new("pacman").args(["-Si", package]).output(),
+            PackageManager::Zypper => Command::new("zypper")
+                .args(["--non-interactive", "info", package])
+                .output(),
+            // No cheap candidate query; the upgrade itself decides
+            PackageManager::Apk | PackageManager::Brew => return Ok(None),
+        };
+
+        match output {
+            Ok(o) if o.status.success() => {
+                Ok(self.parse_candidate_version(&String::from_utf8_lossy(&o.stdout)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Extract the candidate version from the output of the candidate query
+    pub fn parse_candidate_version(&self, output: &str) -> Option<String> {
+        let field = |name: &str| {
+            output.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                (key.trim() == name).then(|| value.trim().to_string())
+            })
+        };
+        let version = match self {
+            PackageManager::Apt => field("Candidate"),
+            PackageManager::Pacman | PackageManager::Zypper => field("Version"),
+            PackageManager::Dnf | PackageManager::Yum => output
+                .lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .map(String::from),
+            PackageManager::Apk | PackageManager::Brew => None,
+        };
+        version.filter(|v| !v.is_empty() && v != "(none)")
+    }
 }
 
 /// Desired state for a package
@@ -294,10 +366,17 @@ impl Module for PackageModule {
         // Track what we'll do
         let mut to_install: Vec<String> = Vec::new();
         let mut to_remove: Vec<String> = Vec::new();
+        let mut to_upgrade: Vec<String> = Vec::new();
         let mut already_ok: Vec<String> = Vec::new();
+        let mut installed: Vec<String> = Vec::new();
+        // Installed version and candidate (if known) of packages to upgrade
+        let mut upgrade_versions: HashMap<String, (String, Option<String>)> = HashMap::new();
 
         for package in &packages {
             let is_installed = pkg_manager.is_installed(package)?;
+            if is_installed {
+                installed.push(package.clone());
+            }
 
             match state {
                 PackageState::Present => {
@@ -315,15 +394,27 @@ impl Module for PackageModule {
                     }
                 }
                 PackageState::Latest => {
-                    // For 'latest', we always try to install/upgrade
-                    to_install.push(package.clone());
+                    if !is_installed {
+                        to_install.push(package.clone());
+                        continue;
+                    }
+                    let installed = pkg_manager
+                        .get_installed_version(package)?
+                        .unwrap_or_default();
+                    let candidate = pkg_manager.get_candidate_version(package)?;
+                    if candidate.as_deref() == Some(installed.as_str()) {
+                        already_ok.push(package.clone());
+                    } else {
+                        to_upgrade.push(package.clone());
+                        upgrade_versions.insert(package.clone(), (installed, candidate));
+                    }
                 }
             }
         }
 
         // Check mode - return what would happen
         if context.check_mode {
-            if to_install.is_empty() && to_remove.is_empty() {
+            if to_install.is_empty() && to_remove.is_empty() && to_upgrade.is_empty() {
                 return Ok(ModuleOutput::ok(format!(
                     "All packages already in desired state: {}",
                     already_ok.join(", ")
@@ -334,16 +425,48 @@ impl Module for PackageModule {
             if !to_install.is_empty() {
                 msg.push_str(&format!("Would install: {}. ", to_install.join(", ")));
             }
+            if !to_upgrade.is_empty() {
+                msg.push_str(&format!("Would upgrade: {}. ", to_upgrade.join(", ")));
+            }
             if !to_remove.is_empty() {
                 msg.push_str(&format!("Would remove: {}. ", to_remove.join(", ")));
             }
 
-            return Ok(ModuleOutput::changed(msg.trim().to_string()));
+            let mut output = ModuleOutput::changed(msg.trim().to_string());
+            // Only known candidates can be reported ahead of time
+            let transitions: Vec<(String, String, String)> = to_upgrade
+                .iter()
+                .filter_map(|pkg| {
+                    let (before, candidate) = upgrade_versions.get(pkg)?;
+                    Some((pkg.clone(), before.clone(), candidate.clone()?))
+                })
+                .collect();
+            if !transitions.is_empty() {
+                output = with_version_transitions(output, &transitions);
+            }
+            let upgrades: Vec<(String, String, String)> = to_upgrade
+                .iter()
+                .map(|pkg| {
+                    let (before, candidate) =
+                        upgrade_versions.get(pkg).cloned().unwrap_or_default();
+                    let after = candidate.unwrap_or_else(|| "(latest)".to_string());
+                    (pkg.clone(), before, after)
+                })
+                .collect();
+            return Ok(with_package_delta(
+                output,
+                &installed,
+                &to_install,
+                &to_remove,
+                &upgrades,
+            ));
         }
 
         // Perform the actual operations
         let mut changed = false;
         let mut results: HashMap<String, String> = HashMap::new();
+        let mut upgraded: Vec<String> = Vec::new();
+        let mut transitions: Vec<(String, String, String)> = Vec::new();
 
         if !to_install.is_empty() {
             let install_cmd = pkg_manager.install_cmd();
@@ -362,6 +485,35 @@ impl Module for PackageModule {
             }
         }
 
+        if !to_upgrade.is_empty() {
+            let latest_cmd = pkg_manager.latest_cmd();
+            let (success, stdout, stderr) = Self::run_package_command(&latest_cmd, &to_upgrade)?;
+
+            if !success {
+                return Err(ModuleError::ExecutionFailed(format!(
+                    "Failed to upgrade packages: {}",
+                    if stderr.is_empty() { stdout } else { stderr }
+                )));
+            }
+
+            // Only packages whose installed version moved count as changed
+            for pkg in &to_upgrade {
+                let before = upgrade_versions
+                    .get(pkg)
+                    .map(|(before, _)| before.clone())
+                    .unwrap_or_default();
+                let after = pkg_manager.get_installed_version(pkg)?.unwrap_or_default();
+                if after != before {
+                    changed = true;
+                    results.insert(pkg.clone(), "upgraded".to_string());
+                    upgraded.push(pkg.clone());
+                    transitions.push((pkg.clone(), before, after));
+                } else {
+                    already_ok.push(pkg.clone());
+                }
+            }
+        }
+
         if !to_remove.is_empty() {
             let remove_cmd = pkg_manager.remove_cmd();
             let (success, stdout, stderr) = Self::run_package_command(&remove_cmd, &to_remove)?;
@@ -388,12 +540,19 @@ impl Module for PackageModule {
             if !to_install.is_empty() {
                 msg.push_str(&format!("Installed: {}. ", to_install.join(", ")));
             }
+            if !upgraded.is_empty() {
+                msg.push_str(&format!("Upgraded: {}. ", upgraded.join(", ")));
+            }
             if !to_remove.is_empty() {
                 msg.push_str(&format!("Removed: {}. ", to_remove.join(", ")));
             }
 
-            Ok(ModuleOutput::changed(msg.trim().to_string())
-                .with_data("results", serde_json::json!(results)))
+            let mut output = ModuleOutput::changed(msg.trim().to_string())
+                .with_data("results", serde_json::json!(results));
+            if !transitions.is_empty() {
+                output = with_version_transitions(output, &transitions);
+            }
+            Ok(output)
         } else {
             Ok(
                 ModuleOutput::ok("All packages already in desired state".to_string())
@@ -443,8 +602,15 @@ impl Module for PackageModule {
             match state {
                 PackageState::Present | PackageState::Latest => {
                     if is_installed {
+                        let target = if state == PackageState::Latest {
+                            pkg_manager
+                                .get_candidate_version(package)?
+                                .unwrap_or_else(|| version.clone())
+                        } else {
+                            version.clone()
+                        };
                         before_lines.push(format!("{}: {}", package, version));
-                        after_lines.push(format!("{}: {}", package, version));
+                        after_lines.push(format!("{}: {}", package, target));
                     } else {
                         before_lines.push(format!("{}: (not installed)", package));
                         after_lines.push(format!("{}: (will be installed)", package));
@@ -469,6 +635,150 @@ impl Module for PackageModule {
     }
 }
 
+/// Record `(package, before, after)` version transitions as data and a diff
+pub(crate) fn with_version_transitions(
+    output: ModuleOutput,
+    transitions: &[(String, String, String)],
+) -> ModuleOutput {
+    let versions: serde_json::Map<String, serde_json::Value> = transitions
+        .iter()
+        .map(|(pkg, before, after)| {
+            (
+                pkg.clone(),
+                serde_json::json!({ "before": before, "after": after }),
+            )
+        })
+        .collect();
+    let before: Vec<String> = transitions
+        .iter()
+        .map(|(pkg, before, _)| format!("{}: {}", pkg, before))
+        .collect();
+    let after: Vec<String> = transitions
+        .iter()
+        .map(|(pkg, _, after)| format!("{}: {}", pkg, after))
+        .collect();
+
+    output
+        .with_data("versions", serde_json::Value::Object(versions))
+        .with_diff(Diff::new(before.join("\n"), after.join("\n")))
+}
+
+/// Describe what a check-mode run would change.
+///
+/// The diff goes from the requested packages that are `installed` now to the
+/// set that would be installed afterwards, with upgraded packages shown with
+/// their `(package, before, after)` versions. The specific deltas are also
+/// recorded as `install`, `remove` and `upgrade` lists under `package_changes`.
+pub(crate) fn with_package_delta(
+    output: ModuleOutput,
+    installed: &[String],
+    to_install: &[String],
+    to_remove: &[String],
+    upgrades: &[(String, String, String)],
+) -> ModuleOutput {
+    let install: Vec<&String> = to_install
+        .iter()
+        .filter(|pkg| !installed.contains(pkg))
+        .collect();
+    let mut upgrade: Vec<&String> = to_install
+        .iter()
+        .filter(|pkg| installed.contains(pkg))
+        .collect();
+    for (pkg, _, _) in upgrades {
+        if !upgrade.contains(&pkg) {
+            upgrade.push(pkg);
+        }
+    }
+
+    let line = |pkg: &String, version: Option<&String>| match version {
+        Some(version) => format!("{}: {}", pkg, version),
+        None => pkg.clone(),
+    };
+    let upgrade_of = |pkg: &String| upgrades.iter().find(|(name, _, _)| name == pkg);
+
+    let mut before: Vec<String> = installed
+        .iter()
+        .map(|pkg| line(pkg, upgrade_of(pkg).map(|(_, before, _)| before)))
+        .collect();
+    let mut after: Vec<String> = installed
+        .iter()
+        .filter(|pkg| !to_remove.contains(pkg))
+        .chain(install.iter().copied())
+        .map(|pkg| line(pkg, upgrade_of(pkg).map(|(_, _, after)| after)))
+        .collect();
+    before.sort();
+    after.sort();
+
+    output
+        .with_data(
+            "package_changes",
+            serde_json::json!({
+                "install": install,
+                "remove": to_remove,
+                "upgrade": upgrade,
+            }),
+        )
+        .with_diff(Diff::new(before.join("\n"), after.join("\n")))
+}
+
+/// Group name of an `@group` package spec such as `@Development Tools`
+///
+/// Group names may contain spaces, but must not be empty, span lines or
+/// look like a command line option.
+pub(crate) fn package_group_name(spec: &str) -> ModuleResult<&str> {
+    let name = spec.trim_start_matches('@').trim();
+    let problem = if name.is_empty() {
+        Some("group name is empty")
+    } else if name.starts_with('-') {
+        Some("group name must not start with '-'")
+    } else if name.chars().any(char::is_control) {
+        Some("group name must not contain control characters")
+    } else {
+        None
+    };
+    match problem {
+        Some(problem) => Err(ModuleError::InvalidParameter(format!(
+            "Invalid package group '{}': {}",
+            spec, problem
+        ))),
+        None => Ok(name),
+    }
+}
+
+/// Whether `group` is listed in `group list` output of dnf or yum
+///
+/// Matches a group by name or id, case-insensitively, in the
+/// `Name (id)` lines of `--ids`/verbose listings, plain name lines, and the
+/// rows of dnf5's `ID Name Installed` table.
+pub(crate) fn group_listed(output: &str, group: &str) -> bool {
+    let matches = |candidate: &str| candidate.trim().eq_ignore_ascii_case(group);
+    output.lines().map(str::trim).any(|line| {
+        if matches(line) {
+            return true;
+        }
+        if let Some((name, id)) = line
+            .strip_suffix(')')
+            .and_then(|line| line.rsplit_once(" ("))
+        {
+            return matches(name) || matches(id);
+        }
+        // dnf5: `id   Name   yes|no`
+        match (
+            line.split_once(char::is_whitespace),
+            line.rsplit_once(char::is_whitespace),
+        ) {
+            (Some((id, rest)), Some((_, "yes" | "no"))) => {
+                let name = rest
+                    .trim_end()
+                    .rsplit_once(char::is_whitespace)
+                    .map(|(name, _)| name);
+                matches(id) || name.is_some_and(matches)
+            }
+            _ => false,
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -526,6 +836,113 @@ mod tests {
         assert_eq!(pacman.remove_cmd(), vec!["pacman", "-R", "--noconfirm"]);
     }
 
+    #[test]
+    fn test_parse_candidate_version() {
+        let apt = PackageManager::Apt;
+        let policy = "nginx:\n  Installed: 1.18.0-6\n  Candidate: 1.18.0-7\n  Version table:\n";
+        assert_eq!(
+            apt.parse_candidate_version(policy),
+            Some("1.18.0-7".to_string())
+        );
+        assert_eq!(
+            apt.parse_candidate_version("foo:\n  Installed: (none)\n  Candidate: (none)\n"),
+            None
+        );
+
+        let pacman = PackageManager::Pacman;
+        let info = "Repository      : extra\nName            : nginx\nVersion         : 1.26.1-1\n";
+        assert_eq!(
+            pacman.parse_candidate_version(info),
+            Some("1.26.1-1".to_string())
+        );
+
+        let dnf = PackageManager::Dnf;
+        assert_eq!(
+            dnf.parse_candidate_version("\n1.20.1-14.el9\n"),
+            Some("1.20.1-14.el9".to_string())
+        );
+
+        assert_eq!(
+            PackageManager::Apk.parse_candidate_version("anything"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_with_version_transitions() {
+        let output = with_version_transitions(
+            ModuleOutput::changed("Upgraded: nginx"),
+            &[(
+                "nginx".to_string(),
+                "1.18.0-6".to_string(),
+                "1.18.0-7".to_string(),
+            )],
+        );
+        assert_eq!(
+            output.data["versions"]["nginx"],
+            serde_json::json!({ "before": "1.18.0-6", "after": "1.18.0-7" })
+        );
+        let diff = output.diff.unwrap();
+        assert_eq!(diff.before, "nginx: 1.18.0-6");
+        assert_eq!(diff.after, "nginx: 1.18.0-7");
+    }
+
+    #[test]
+    fn test_with_package_delta() {
+        let installed = vec!["nginx".to_string(), "vim".to_string()];
+        let output = with_package_delta(
+            ModuleOutput::changed("Would change"),
+            &installed,
+            &["curl".to_string()],
+            &["vim".to_string()],
+            &[(
+                "nginx".to_string(),
+                "1.18.0-6".to_string(),
+                "1.18.0-7".to_string(),
+            )],
+        );
+        assert_eq!(
+            output.data["package_changes"],
+            serde_json::json!({ "install": ["curl"], "remove": ["vim"], "upgrade": ["nginx"] })
+        );
+        let diff = output.diff.unwrap();
+        assert_eq!(diff.before, "nginx: 1.18.0-6\nvim");
+        assert_eq!(diff.after, "curl\nnginx: 1.18.0-7");
+    }
+
+    #[test]
+    fn test_package_group_name() {
+        assert_eq!(
+            package_group_name("@Development Tools").unwrap(),
+            "Development Tools"
+        );
+        assert_eq!(package_group_name("@core").unwrap(), "core");
+        assert!(package_group_name("@").is_err());
+        assert!(package_group_name("@ ").is_err());
+        assert!(package_group_name("@--nogpgcheck").is_err());
+        assert!(package_group_name("@Web\nServer").is_err());
+    }
+
+    #[test]
+    fn test_group_listed() {
+        let dnf4 = "Installed Groups:\n   Development Tools (development)\n   Headless Management (headless-management)\n";
+        assert!(group_listed(dnf4, "Development Tools"));
+        assert!(group_listed(dnf4, "development tools"));
+        assert!(group_listed(dnf4, "development"));
+        assert!(!group_listed(dnf4, "Headless"));
+        assert!(!group_listed(dnf4, "Tools"));
+
+        let plain = "Installed Groups:\n   Development Tools\n";
+        assert!(group_listed(plain, "Development Tools"));
+        assert!(!group_listed(plain, "Web Server"));
+        assert!(!group_listed(plain, "Development"));
+
+        let dnf5 = "ID                   Name                Installed\ndevelopment          Development Tools         yes\n";
+        assert!(group_listed(dnf5, "development"));
+        assert!(group_listed(dnf5, "Development Tools"));
+        assert!(!group_listed(dnf5, "Tools"));
+    }
+
     // Integration tests would require actual package manager access
     // These are unit tests for the parsing/configuration logic
 }
@@ -4,8 +4,8 @@
 //! requirements files, proxy configuration, and different package states.
 
 use super::{
-    Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
-    ModuleResult, ParallelizationHint, ParamExt,
+    ensure_local_chdir, Diff, Module, ModuleClassification, ModuleContext, ModuleError,
+    ModuleOutput, ModuleParams, ModuleResult, ParallelizationHint, ParamExt,
 };
 use std::collections::HashMap;
 use std::process::Command;
@@ -40,6 +40,7 @@ impl PipState {
 #[derive(Debug, Clone)]
 struct PipConfig {
     pip_cmd: String,
+    in_virtualenv: bool,
     extra_args: Vec<String>,
     chdir: Option<String>,
     editable: bool,
@@ -58,10 +59,10 @@ impl PipConfig {
             .unwrap_or_else(|| "pip3".to_string());
 
         // If virtualenv is specified, use the pip from that virtualenv
-        let pip_cmd = if let Some(venv) = params.get_string("virtualenv")? {
-            format!("{}/bin/pip", venv)
-        } else {
-            executable
+        let virtualenv = params.get_string("virtualenv")?;
+        let pip_cmd = match virtualenv {
+            Some(ref venv) => format!("{}/bin/pip", venv.trim_end_matches('/')),
+            None => executable,
         };
 
         // Parse extra_args - check raw type to handle array vs string correctly
@@ -108,6 +109,7 @@ impl PipConfig {
 
         Ok(Self {
             pip_cmd,
+            in_virtualenv: virtualenv.is_some(),
             extra_args,
             chdir: params.get_string("chdir")?,
             editable: params.get_bool("editable")?.unwrap_or(false),
@@ -120,6 +122,16 @@ impl PipConfig {
         })
     }
 
+    /// Build configuration for execution, defaulting `chdir` to the context's `work_dir`
+    fn from_context(params: &ModuleParams, context: &ModuleContext) -> ModuleResult<Self> {
+        let mut config = Self::from_params(params)?;
+        config.chdir = context.chdir(params)?;
+        if let Some(ref dir) = config.chdir {
+            ensure_local_chdir(dir)?;
+        }
+        Ok(config)
+    }
+
     /// Build the base pip command with common arguments
     fn build_command(&self) -> Command {
         let mut cmd = Command::new(&self.pip_cmd);
@@ -173,20 +185,6 @@ impl PipModule {
         Ok(config.pip_cmd)
     }
 
-    /// Check if a package is installed
-    fn is_package_installed(&self, config: &PipConfig, package: &str) -> ModuleResult<bool> {
-        // Strip version specifier for package check
-        let pkg_name = Self::extract_package_name(package);
-
-        let mut cmd = config.build_command();
-        cmd.arg("show").arg(&pkg_name);
-
-        let output = cmd.output().map_err(|e| {
-            ModuleError::ExecutionFailed(format!("Failed to check package status: {}", e))
-        })?;
-        Ok(output.status.success())
-    }
-
     /// Extract package name from a package specification (removes version specifiers)
     fn extract_package_name(package: &str) -> String {
         // Handle various version specifier formats
@@ -199,36 +197,59 @@ impl PipModule {
         name.to_string()
     }
 
-    /// Get installed version of a package
-    fn get_installed_version(
-        &self,
-        config: &PipConfig,
-        package: &str,
-    ) -> ModuleResult<Option<String>> {
-        let pkg_name = Self::extract_package_name(package);
+    /// Normalize a package name the way pip compares them
+    fn normalize_name(name: &str) -> String {
+        name.trim().to_lowercase().replace('_', "-")
+    }
+
+    /// Parse `pip freeze` output into normalized package names and versions
+    fn parse_freeze(output: &str) -> HashMap<String, String> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                if let Some((name, version)) = line.split_once("==") {
+                    Some((Self::normalize_name(name), version.trim().to_string()))
+                } else {
+                    // Direct references (`name @ url`) have no comparable version
+                    line.split_once(" @ ")
+                        .map(|(name, _)| (Self::normalize_name(name), String::new()))
+                }
+            })
+            .collect()
+    }
+
+    /// Get installed packages and their versions via `pip freeze`
+    fn installed_packages(&self, config: &PipConfig) -> ModuleResult<HashMap<String, String>> {
+        // A virtualenv that does not exist yet (e.g. in check mode) has nothing installed
+        if config.in_virtualenv && !std::path::Path::new(&config.pip_cmd).exists() {
+            return Ok(HashMap::new());
+        }
 
         let mut cmd = config.build_command();
-        cmd.arg("show").arg(&pkg_name);
+        cmd.args(["freeze", "--all"]);
 
         let output = cmd.output().map_err(|e| {
-            ModuleError::ExecutionFailed(format!("Failed to get package version: {}", e))
+            ModuleError::ExecutionFailed(format!("Failed to list installed packages: {}", e))
         })?;
-
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                if let Some(version) = line.strip_prefix("Version:") {
-                    let version = version.trim().to_string();
-                    if version.is_empty() {
-                        return Ok(None);
-                    }
-                    return Ok(Some(version));
-                }
-            }
-            Ok(None)
-        } else {
-            Ok(None)
+        if !output.status.success() {
+            return Err(ModuleError::ExecutionFailed(format!(
+                "pip freeze failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
         }
+
+        Ok(Self::parse_freeze(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Look up the installed version of a package in `pip freeze` output
+    fn installed_version<'a>(
+        installed: &'a HashMap<String, String>,
+        package: &str,
+    ) -> Option<&'a str> {
+        installed
+            .get(&Self::normalize_name(&Self::extract_package_name(package)))
+            .map(String::as_str)
     }
 
     /// Execute a pip command with the given configuration
@@ -257,49 +278,61 @@ impl PipModule {
         Ok((output.status.success(), stdout, stderr))
     }
 
-    /// Create a virtualenv if it doesn't exist
-    fn ensure_virtualenv(
-        &self,
+    /// Build the command line that creates a virtualenv
+    fn virtualenv_args(
         venv_path: &str,
         python: Option<&str>,
         site_packages: bool,
         virtualenv_command: Option<&str>,
-    ) -> ModuleResult<bool> {
-        // Check if virtualenv exists by checking for the activate script
-        let activate_path = std::path::Path::new(venv_path).join("bin").join("activate");
-        if activate_path.exists() {
-            return Ok(false);
-        }
-
-        // Determine the command to use for creating virtualenv
-        let venv_cmd = virtualenv_command.unwrap_or("python3 -m venv");
-        let venv_parts: Vec<&str> = venv_cmd.split_whitespace().collect();
-
-        let mut cmd = if venv_parts.len() > 1 {
-            let mut c = Command::new(venv_parts[0]);
-            c.args(&venv_parts[1..]);
-            c
-        } else {
-            Command::new(venv_parts[0])
+    ) -> Vec<String> {
+        // Without an explicit command, create the venv with the requested interpreter
+        let mut args: Vec<String> = match virtualenv_command {
+            Some(venv_cmd) => venv_cmd.split_whitespace().map(String::from).collect(),
+            None => vec![
+                python.unwrap_or("python3").to_string(),
+                "-m".into(),
+                "venv".into(),
+            ],
         };
 
         // Add system site-packages option if requested
         if site_packages {
-            cmd.arg("--system-site-packages");
+            args.push("--system-site-packages".to_string());
         }
 
         // Add python interpreter if specified (for virtualenv command, not venv)
-        if let Some(py) = python {
+        if let (Some(py), Some(venv_cmd)) = (python, virtualenv_command) {
             if venv_cmd.contains("virtualenv") {
-                cmd.arg("--python").arg(py);
+                args.push("--python".to_string());
+                args.push(py.to_string());
             }
         }
 
-        cmd.arg(venv_path);
+        args.push(venv_path.to_string());
+        args
+    }
 
-        let output = cmd.output().map_err(|e| {
-            ModuleError::ExecutionFailed(format!("Failed to create virtualenv: {}", e))
-        })?;
+    /// Create a virtualenv if it doesn't exist
+    fn ensure_virtualenv(
+        &self,
+        venv_path: &str,
+        python: Option<&str>,
+        site_packages: bool,
+        virtualenv_command: Option<&str>,
+    ) -> ModuleResult<bool> {
+        // Check if virtualenv exists by checking for the activate script
+        let activate_path = std::path::Path::new(venv_path).join("bin").join("activate");
+        if activate_path.exists() {
+            return Ok(false);
+        }
+
+        let args = Self::virtualenv_args(venv_path, python, site_packages, virtualenv_command);
+        let output = Command::new(&args[0])
+            .args(&args[1..])
+            .output()
+            .map_err(|e| {
+                ModuleError::ExecutionFailed(format!("Failed to create virtualenv: {}", e))
+            })?;
 
         if !output.status.success() {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -311,6 +344,36 @@ impl PipModule {
         Ok(true)
     }
 
+    /// Build the pip arguments that install packages in the given state
+    fn install_args(state: &PipState, editable: bool, specs: &[String]) -> Vec<String> {
+        let mut args = vec!["install".to_string()];
+
+        // Add state-specific flags
+        match state {
+            PipState::Latest => args.push("--upgrade".to_string()),
+            PipState::ForceReinstall => args.push("--force-reinstall".to_string()),
+            _ => {}
+        }
+
+        for spec in specs {
+            // Editable installs need the flag in front of every path or URL
+            if editable {
+                args.push("-e".to_string());
+            }
+            args.push(spec.clone());
+        }
+
+        args
+    }
+
+    /// Build the pip arguments that install a requirements file
+    fn requirements_args(state: &PipState, requirements: &str) -> Vec<String> {
+        let mut args = Self::install_args(state, false, &[]);
+        args.push("-r".to_string());
+        args.push(requirements.to_string());
+        args
+    }
+
     /// Handle requirements file installation
     fn handle_requirements(
         &self,
@@ -338,20 +401,11 @@ impl PipModule {
             return Ok(ModuleOutput::changed(msg));
         }
 
-        let mut args = vec!["install"];
+        let before = self.installed_packages(config)?;
 
-        if *state == PipState::Latest {
-            args.push("--upgrade");
-        }
-
-        if *state == PipState::ForceReinstall {
-            args.push("--force-reinstall");
-        }
-
-        args.push("-r");
-        args.push(requirements);
-
-        let (success, stdout, stderr) = self.execute_pip_command(config, &args)?;
+        let args = Self::requirements_args(state, requirements);
+        let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        let (success, stdout, stderr) = self.execute_pip_command(config, &arg_refs)?;
 
         if !success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -360,9 +414,9 @@ impl PipModule {
             )));
         }
 
-        // Check if anything was actually installed by looking for "already satisfied" in output
-        let changed =
-            !stdout.contains("Requirement already satisfied") || *state == PipState::ForceReinstall;
+        // Compare installed versions to see whether anything actually changed
+        let after = self.installed_packages(config)?;
+        let changed = before != after || *state == PipState::ForceReinstall;
 
         if changed || venv_created {
             Ok(ModuleOutput::changed(format!(
@@ -378,6 +432,50 @@ impl PipModule {
         }
     }
 
+    /// Decide which packages need installing or removing given what is installed
+    fn plan_packages(
+        packages: &[String],
+        version: Option<&str>,
+        state: &PipState,
+        installed: &HashMap<String, String>,
+    ) -> PackagePlan {
+        let mut plan = PackagePlan::default();
+
+        for package in packages {
+            let spec = Self::build_package_spec(package, version);
+            let installed_ver = Self::installed_version(installed, package);
+
+            match state {
+                PipState::Present => match (installed_ver, version) {
+                    (Some(_), None) => plan.already_ok.push(package.clone()),
+                    (Some(inst_ver), Some(req_ver)) => {
+                        // Only exact versions are compared; ranges accept any installed version
+                        let exact = req_ver.trim_start_matches("==");
+                        if inst_ver == exact || req_ver.starts_with(&['>', '<', '!', '~'][..]) {
+                            plan.already_ok.push(package.clone());
+                        } else {
+                            plan.to_install.push(spec);
+                        }
+                    }
+                    (None, _) => plan.to_install.push(spec),
+                },
+                PipState::Absent => {
+                    if installed_ver.is_some() {
+                        plan.to_remove.push(package.clone());
+                    } else {
+                        plan.already_ok.push(package.clone());
+                    }
+                }
+                PipState::Latest | PipState::ForceReinstall => {
+                    // For 'latest' or 'forcereinstall', we always try to install/upgrade
+                    plan.to_install.push(spec);
+                }
+            }
+        }
+
+        plan
+    }
+
     /// Build package specification with version if provided
     fn build_package_spec(name: &str, version: Option<&str>) -> String {
         if let Some(ver) = version {
@@ -394,6 +492,14 @@ impl PipModule {
     }
 }
 
+/// Packages to change for a pip task
+#[derive(Debug, Default, PartialEq)]
+struct PackagePlan {
+    to_install: Vec<String>,
+    to_remove: Vec<String>,
+    already_ok: Vec<String>,
+}
+
 impl Module for PipModule {
     fn name(&self) -> &'static str {
         "pip"
@@ -408,8 +514,8 @@ impl Module for PipModule {
     }
 
     fn parallelization_hint(&self) -> ParallelizationHint {
-        // Pip can generally run in parallel, but virtualenv operations might conflict
-        ParallelizationHint::FullyParallel
+        // Concurrent pip runs on one host race on site-packages and virtualenv creation
+        ParallelizationHint::HostExclusive
     }
 
     fn required_params(&self) -> &[&'static str] {
@@ -433,7 +539,7 @@ impl Module for PipModule {
         context: &ModuleContext,
     ) -> ModuleResult<ModuleOutput> {
         // Build configuration from parameters
-        let config = PipConfig::from_params(params)?;
+        let config = PipConfig::from_context(params, context)?;
 
         // Get state
         let state_str = params
@@ -475,55 +581,12 @@ impl Module for PipModule {
             vec![params.get_string_required("name")?]
         };
 
-        // Build package specs with version if provided
-        let package_specs: Vec<String> = packages
-            .iter()
-            .map(|p| Self::build_package_spec(p, version.as_deref()))
-            .collect();
-
-        let mut to_install: Vec<String> = Vec::new();
-        let mut to_remove: Vec<String> = Vec::new();
-        let mut already_ok: Vec<String> = Vec::new();
-
-        for (package, spec) in packages.iter().zip(package_specs.iter()) {
-            let is_installed = self.is_package_installed(&config, package)?;
-
-            match state {
-                PipState::Present => {
-                    if is_installed && version.is_none() {
-                        already_ok.push(package.clone());
-                    } else if is_installed && version.is_some() {
-                        // Check if the installed version matches
-                        let installed_ver = self.get_installed_version(&config, package)?;
-                        if let (Some(inst_ver), Some(req_ver)) = (installed_ver, &version) {
-                            // Simple exact match check - for complex version specs, always upgrade
-                            if inst_ver == *req_ver
-                                || req_ver.starts_with(&['>', '<', '!', '~'][..])
-                            {
-                                already_ok.push(package.clone());
-                            } else if inst_ver != *req_ver {
-                                to_install.push(spec.clone());
-                            }
-                        } else {
-                            to_install.push(spec.clone());
-                        }
-                    } else {
-                        to_install.push(spec.clone());
-                    }
-                }
-                PipState::Absent => {
-                    if is_installed {
-                        to_remove.push(package.clone());
-                    } else {
-                        already_ok.push(package.clone());
-                    }
-                }
-                PipState::Latest | PipState::ForceReinstall => {
-                    // For 'latest' or 'forcereinstall', we always try to install/upgrade
-                    to_install.push(spec.clone());
-                }
-            }
-        }
+        let installed = self.installed_packages(&config)?;
+        let PackagePlan {
+            to_install,
+            to_remove,
+            already_ok,
+        } = Self::plan_packages(&packages, version.as_deref(), &state, &installed);
 
         // Check mode - return what would happen
         if context.check_mode {
@@ -551,29 +614,11 @@ impl Module for PipModule {
         // Perform the actual operations
         let mut changed = venv_created;
         let mut results: HashMap<String, String> = HashMap::new();
+        let mut installed_now: Vec<String> = Vec::new();
 
         if !to_install.is_empty() {
-            let mut args: Vec<&str> = vec!["install"];
-
-            // Add state-specific flags
-            match state {
-                PipState::Latest => {
-                    args.push("--upgrade");
-                }
-                PipState::ForceReinstall => {
-                    args.push("--force-reinstall");
-                }
-                _ => {}
-            }
-
-            // Add editable flag if requested
-            if config.editable {
-                args.push("-e");
-            }
-
-            // Convert to refs for the command
-            let pkg_refs: Vec<&str> = to_install.iter().map(|s| s.as_str()).collect();
-            args.extend(pkg_refs);
+            let args = Self::install_args(&state, config.editable, &to_install);
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
 
             let (success, stdout, stderr) = self.execute_pip_command(&config, &args)?;
 
@@ -584,11 +629,22 @@ impl Module for PipModule {
                 )));
             }
 
-            changed = true;
+            // Compare installed versions so `latest` on an up-to-date package is a no-op
+            let after = self.installed_packages(&config)?;
             for pkg in &to_install {
                 let pkg_name = Self::extract_package_name(pkg);
-                results.insert(pkg_name, "installed".to_string());
+                let updated = config.editable
+                    || state == PipState::ForceReinstall
+                    || Self::installed_version(&installed, pkg)
+                        != Self::installed_version(&after, pkg);
+                if updated {
+                    installed_now.push(pkg.clone());
+                    results.insert(pkg_name, "installed".to_string());
+                } else {
+                    results.insert(pkg_name, "ok".to_string());
+                }
             }
+            changed |= !installed_now.is_empty();
         }
 
         if !to_remove.is_empty() {
@@ -620,8 +676,8 @@ impl Module for PipModule {
             if venv_created {
                 msg.push_str("Virtualenv created. ");
             }
-            if !to_install.is_empty() {
-                msg.push_str(&format!("Installed: {}. ", to_install.join(", ")));
+            if !installed_now.is_empty() {
+                msg.push_str(&format!("Installed: {}. ", installed_now.join(", ")));
             }
             if !to_remove.is_empty() {
                 msg.push_str(&format!("Removed: {}. ", to_remove.join(", ")));
@@ -645,8 +701,8 @@ impl Module for PipModule {
         self.execute(params, &check_context)
     }
 
-    fn diff(&self, params: &ModuleParams, _context: &ModuleContext) -> ModuleResult<Option<Diff>> {
-        let config = PipConfig::from_params(params)?;
+    fn diff(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<Option<Diff>> {
+        let config = PipConfig::from_context(params, context)?;
 
         // Get state
         let state_str = params
@@ -672,17 +728,14 @@ impl Module for PipModule {
             vec![params.get_string_required("name")?]
         };
 
+        let installed = self.installed_packages(&config)?;
         let mut before_lines = Vec::new();
         let mut after_lines = Vec::new();
 
         for package in &packages {
-            let is_installed = self.is_package_installed(&config, package)?;
-            let installed_version = if is_installed {
-                self.get_installed_version(&config, package)?
-                    .unwrap_or_default()
-            } else {
-                String::new()
-            };
+            let installed_version = Self::installed_version(&installed, package);
+            let is_installed = installed_version.is_some();
+            let installed_version = installed_version.unwrap_or_default();
 
             let target_spec = Self::build_package_spec(package, version.as_deref());
 
@@ -957,4 +1010,89 @@ mod tests {
         assert!(config.no_index);
         assert_eq!(config.find_links, Some("/path/to/packages".to_string()));
     }
+
+    #[test]
+    fn test_requirements_install_into_virtualenv() {
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("virtualenv".to_string(), serde_json::json!("/opt/app/venv"));
+        params.insert(
+            "virtualenv_python".to_string(),
+            serde_json::json!("python3.11"),
+        );
+        params.insert(
+            "requirements".to_string(),
+            serde_json::json!("/opt/app/requirements.txt"),
+        );
+
+        let config = PipConfig::from_params(&params).unwrap();
+        assert_eq!(config.pip_cmd, "/opt/app/venv/bin/pip");
+        assert_eq!(
+            PipModule::requirements_args(&PipState::Present, "/opt/app/requirements.txt"),
+            vec!["install", "-r", "/opt/app/requirements.txt"]
+        );
+
+        // The virtualenv is created with the requested interpreter
+        assert_eq!(
+            PipModule::virtualenv_args("/opt/app/venv", Some("python3.11"), false, None),
+            vec!["python3.11", "-m", "venv", "/opt/app/venv"]
+        );
+        assert_eq!(
+            PipModule::virtualenv_args(
+                "/opt/app/venv",
+                Some("python3.11"),
+                true,
+                Some("virtualenv")
+            ),
+            vec![
+                "virtualenv",
+                "--system-site-packages",
+                "--python",
+                "python3.11",
+                "/opt/app/venv"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_installed_version_is_noop() {
+        let installed = PipModule::parse_freeze(
+            "Flask==2.0.1\nrequests==2.31.0\nmy_tool @ file:///src/my_tool\n",
+        );
+        assert_eq!(installed.get("flask"), Some(&"2.0.1".to_string()));
+        assert_eq!(installed.get("my-tool"), Some(&String::new()));
+
+        let packages = vec!["flask".to_string()];
+        let plan =
+            PipModule::plan_packages(&packages, Some("2.0.1"), &PipState::Present, &installed);
+        assert!(plan.to_install.is_empty());
+        assert_eq!(plan.already_ok, vec!["flask"]);
+
+        // A different version is installed with an exact pin
+        let plan =
+            PipModule::plan_packages(&packages, Some("2.1.0"), &PipState::Present, &installed);
+        assert_eq!(plan.to_install, vec!["flask==2.1.0"]);
+
+        // Names are compared the way pip normalizes them
+        let packages = vec!["My_Tool".to_string(), "django".to_string()];
+        let plan = PipModule::plan_packages(&packages, None, &PipState::Absent, &installed);
+        assert_eq!(plan.to_remove, vec!["My_Tool"]);
+        assert_eq!(plan.already_ok, vec!["django"]);
+    }
+
+    #[test]
+    fn test_install_args_editable() {
+        let specs = vec!["./pkg_a".to_string(), "./pkg_b".to_string()];
+        assert_eq!(
+            PipModule::install_args(&PipState::Latest, true, &specs),
+            vec!["install", "--upgrade", "-e", "./pkg_a", "-e", "./pkg_b"]
+        );
+    }
+
+    #[test]
+    fn test_pip_is_host_exclusive() {
+        assert_eq!(
+            PipModule.parallelization_hint(),
+            ParallelizationHint::HostExclusive
+        );
+    }
 }
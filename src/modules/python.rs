@@ -459,9 +459,7 @@ if __name__ == '__main__':
         let result = conn
             .execute(&command, Some(ExecuteOptions::new()))
             .await
-            .map_err(|e| {
-                ModuleError::ExecutionFailed(format!("Failed to execute Python module: {}", e))
-            })?;
+            .map_err(|e| ModuleError::from_connection("Failed to execute Python module", e))?;
 
         // Parse the result
         self.parse_result(&result, module_name)
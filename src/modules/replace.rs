@@ -0,0 +1,458 @@
+//! Replace module - Regex substitution across a file
+//!
+//! This module replaces every match of a regular expression in a file,
+//! optionally only within the section between the `after` and `before`
+//! anchors. Unlike `lineinfile`, patterns may span several lines.
+//!
+//! Supports both local and remote execution:
+//! - Local: Uses native Rust std::fs operations
+//! - Remote: Downloads file via connection, edits in memory, uploads back
+//!
+//! With `validate`, the command (`%s` stands for the file) runs against the
+//! edited content in a temp file first; the file is only written if it
+//! succeeds.
+
+use super::{
+    validate_local_content, validate_remote_content, Diff, Module, ModuleClassification,
+    ModuleContext, ModuleError, ModuleOutput, ModuleParams, ModuleResult, ParamExt,
+};
+use crate::connection::{Connection, TransferOptions};
+use regex::{Regex, RegexBuilder};
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+
+/// Parsed substitution parameters
+#[derive(Debug, Clone)]
+pub struct ReplaceSpec {
+    /// Pattern to replace; `^` and `$` match at line boundaries
+    pub regexp: Regex,
+    /// Replacement in regex crate syntax (`${1}`, `${name}`)
+    pub replace: String,
+    /// Only replace after the first match of this pattern
+    pub after: Option<Regex>,
+    /// Only replace before this pattern
+    pub before: Option<Regex>,
+}
+
+impl ReplaceSpec {
+    /// Build a spec from module parameters
+    pub fn from_params(params: &ModuleParams) -> ModuleResult<Self> {
+        let regexp = params.get_string_required("regexp")?;
+        let replace = params.get_string("replace")?.unwrap_or_default();
+        let anchor = |name: &str| -> ModuleResult<Option<Regex>> {
+            params
+                .get_string(name)?
+                .map(|pattern| Self::compile(name, &pattern, true))
+                .transpose()
+        };
+
+        Ok(Self {
+            regexp: Self::compile("regexp", &regexp, false)?,
+            replace: Self::expand_replacement(&replace),
+            after: anchor("after")?,
+            before: anchor("before")?,
+        })
+    }
+
+    fn compile(name: &str, pattern: &str, dot_matches_new_line: bool) -> ModuleResult<Regex> {
+        RegexBuilder::new(pattern)
+            .multi_line(true)
+            .dot_matches_new_line(dot_matches_new_line)
+            .build()
+            .map_err(|e| ModuleError::InvalidParameter(format!("Invalid {}: {}", name, e)))
+    }
+
+    /// Convert a Python-style replacement (`\1`, `\g<name>`) to regex crate syntax
+    pub fn expand_replacement(replace: &str) -> String {
+        let mut out = String::with_capacity(replace.len());
+        let mut chars = replace.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '$' => out.push_str("$$"),
+                '\\' => match chars.peek().copied() {
+                    Some(d) if d.is_ascii_digit() => {
+                        let mut group = String::new();
+                        while let Some(d) = chars.peek().copied().filter(char::is_ascii_digit) {
+                            group.push(d);
+                            chars.next();
+                        }
+                        out.push_str(&format!("${{{}}}", group));
+                    }
+                    Some('g') => {
+                        chars.next();
+                        let mut group = String::new();
+                        if chars.peek() == Some(&'<') {
+                            chars.next();
+                            for c in chars.by_ref() {
+                                if c == '>' {
+                                    break;
+                                }
+                                group.push(c);
+                            }
+                            out.push_str(&format!("${{{}}}", group));
+                        } else {
+                            out.push_str("\\g");
+                        }
+                    }
+                    Some('n') => {
+                        chars.next();
+                        out.push('\n');
+                    }
+                    Some('t') => {
+                        chars.next();
+                        out.push('\t');
+                    }
+                    Some('\\') => {
+                        chars.next();
+                        out.push('\\');
+                    }
+                    _ => out.push('\\'),
+                },
+                _ => out.push(ch),
+            }
+        }
+
+        out
+    }
+
+    /// Byte range the substitution applies to, or `None` if an anchor does not match.
+    ///
+    /// The section starts after the first `after` match and ends at the first
+    /// following `before` match; with only `before` it ends at the last match.
+    fn section(&self, content: &str) -> Option<Range<usize>> {
+        let start = match &self.after {
+            Some(after) => after.find(content)?.end(),
+            None => 0,
+        };
+        let end = match (&self.before, &self.after) {
+            (Some(before), Some(_)) => start + before.find(&content[start..])?.start(),
+            (Some(before), None) => before.find_iter(content).last()?.start(),
+            (None, _) => content.len(),
+        };
+        Some(start..end)
+    }
+
+    /// Apply the substitution, returning the new content and the number of
+    /// matches, or `None` when the `after`/`before` anchors do not match
+    pub fn apply(&self, content: &str) -> Option<(String, usize)> {
+        let range = self.section(content)?;
+        let section = &content[range.clone()];
+        let count = self.regexp.find_iter(section).count();
+        let replaced = self.regexp.replace_all(section, self.replace.as_str());
+
+        Some((
+            format!(
+                "{}{}{}",
+                &content[..range.start],
+                replaced,
+                &content[range.end..]
+            ),
+            count,
+        ))
+    }
+}
+
+/// Module for regex replacement across a file
+pub struct ReplaceModule;
+
+impl ReplaceModule {
+    fn missing_file(path: &str) -> ModuleError {
+        ModuleError::ExecutionFailed(format!("Path '{}' does not exist", path))
+    }
+
+    /// Apply the substitution to `content`, or return the unchanged output
+    /// when the anchors do not match or nothing would change
+    fn edit(
+        path: &str,
+        spec: &ReplaceSpec,
+        content: &str,
+    ) -> Result<(String, usize), ModuleOutput> {
+        match spec.apply(content) {
+            None => Err(ModuleOutput::ok(format!(
+                "Pattern for before/after params did not match '{}'",
+                path
+            ))),
+            Some((new_content, _)) if new_content == content => Err(ModuleOutput::ok(format!(
+                "No replacements made in '{}'",
+                path
+            ))),
+            Some(edited) => Ok(edited),
+        }
+    }
+
+    /// Output for an edit that changes the file
+    fn changed(
+        path: &str,
+        before: &str,
+        after: &str,
+        count: usize,
+        context: &ModuleContext,
+    ) -> ModuleOutput {
+        let msg = if context.check_mode {
+            format!("Would make {} replacements in '{}'", count, path)
+        } else {
+            format!("{} replacements made in '{}'", count, path)
+        };
+        let mut output =
+            ModuleOutput::changed(msg).with_data("replacements", serde_json::json!(count));
+        if context.diff_mode {
+            output = output.with_diff(Diff::for_file(Path::new(path), Some(before), after));
+        }
+        output
+    }
+
+    /// Execute replace locally using filesystem operations
+    fn execute_local(
+        context: &ModuleContext,
+        path_str: &str,
+        spec: &ReplaceSpec,
+        backup: Option<String>,
+        validate: Option<&str>,
+    ) -> ModuleResult<ModuleOutput> {
+        let path = Path::new(path_str);
+        if !path.exists() {
+            return Err(Self::missing_file(path_str));
+        }
+
+        let content = fs::read_to_string(path)?;
+        let (new_content, count) = match Self::edit(path_str, spec, &content) {
+            Ok(edited) => edited,
+            Err(unchanged) => return Ok(unchanged),
+        };
+        let mut output = Self::changed(path_str, &content, &new_content, count, context);
+        if context.check_mode {
+            return Ok(output);
+        }
+        if let Some(validate) = validate {
+            validate_local_content(validate, new_content.as_bytes())?;
+        }
+
+        if let Some(suffix) = backup {
+            let backup_path = format!("{}{}", path_str, suffix);
+            fs::copy(path, &backup_path)?;
+            output = output.with_data("backup_file", serde_json::json!(backup_path));
+        }
+
+        // fs::write keeps the existing file's permissions
+        fs::write(path, new_content)?;
+        Ok(output)
+    }
+
+    /// Execute replace on a remote host via connection
+    ///
+    /// Downloads the file, substitutes in memory and uploads the result, so
+    /// no tools beyond file transfer are needed on the target.
+    async fn execute_remote(
+        context: &ModuleContext,
+        connection: Arc<dyn Connection + Send + Sync>,
+        path_str: &str,
+        spec: &ReplaceSpec,
+        backup: Option<String>,
+        validate: Option<&str>,
+    ) -> ModuleResult<ModuleOutput> {
+        let path = Path::new(path_str);
+        if !connection.path_exists(path).await.unwrap_or(false) {
+            return Err(Self::missing_file(path_str));
+        }
+
+        let raw = connection
+            .download_content(path)
+            .await
+            .map_err(|e| ModuleError::from_connection("Failed to download file", e))?;
+        let content = String::from_utf8_lossy(&raw);
+        let (new_content, count) = match Self::edit(path_str, spec, &content) {
+            Ok(edited) => edited,
+            Err(unchanged) => return Ok(unchanged),
+        };
+        let mut output = Self::changed(path_str, &content, &new_content, count, context);
+        if context.check_mode {
+            return Ok(output);
+        }
+        if let Some(validate) = validate {
+            validate_remote_content(connection.as_ref(), validate, new_content.as_bytes()).await?;
+        }
+
+        if let Some(suffix) = backup {
+            let backup_path = format!("{}{}", path_str, suffix);
+            connection
+                .upload_content(&raw, Path::new(&backup_path), None)
+                .await
+                .map_err(|e| ModuleError::from_connection("Failed to create backup", e))?;
+            output = output.with_data("backup_file", serde_json::json!(backup_path));
+        }
+
+        connection
+            .upload_content(new_content.as_bytes(), path, Some(TransferOptions::new()))
+            .await
+            .map_err(|e| ModuleError::from_connection("Failed to upload file", e))?;
+        Ok(output)
+    }
+}
+
+impl Module for ReplaceModule {
+    fn name(&self) -> &'static str {
+        "replace"
+    }
+
+    fn description(&self) -> &'static str {
+        "Replace all instances of a pattern within a file"
+    }
+
+    fn classification(&self) -> ModuleClassification {
+        ModuleClassification::NativeTransport
+    }
+
+    fn required_params(&self) -> &[&'static str] {
+        &["path", "regexp"]
+    }
+
+    fn validate_params(&self, params: &ModuleParams) -> ModuleResult<()> {
+        ReplaceSpec::from_params(params).map(|_| ())
+    }
+
+    fn execute(
+        &self,
+        params: &ModuleParams,
+        context: &ModuleContext,
+    ) -> ModuleResult<ModuleOutput> {
+        let path = params.get_string_required("path")?;
+        let spec = ReplaceSpec::from_params(params)?;
+        let backup = params.get_bool_or("backup", false).then(|| {
+            params
+                .get_string("backup_suffix")
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| "~".to_string())
+        });
+        let validate = params.get_string("validate")?;
+
+        let Some(connection) = context.connection.clone() else {
+            return Self::execute_local(context, &path, &spec, backup, validate.as_deref());
+        };
+
+        let handle = Handle::try_current()
+            .map_err(|_| ModuleError::ExecutionFailed("No tokio runtime available".to_string()))?;
+
+        // Spawn blocking task on a separate thread to avoid runtime nesting issues
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                handle.block_on(Self::execute_remote(
+                    context,
+                    connection,
+                    &path,
+                    &spec,
+                    backup,
+                    validate.as_deref(),
+                ))
+            })
+            .join()
+            .unwrap()
+        })
+    }
+
+    fn check(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<ModuleOutput> {
+        let check_context = ModuleContext {
+            check_mode: true,
+            ..context.clone()
+        };
+        self.execute(params, &check_context)
+    }
+
+    fn diff(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<Option<Diff>> {
+        let diff_context = ModuleContext {
+            check_mode: true,
+            diff_mode: true,
+            ..context.clone()
+        };
+        Ok(self.execute(params, &diff_context)?.diff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn spec(params: serde_json::Value) -> ReplaceSpec {
+        let params: ModuleParams = serde_json::from_value(params).unwrap();
+        ReplaceSpec::from_params(&params).unwrap()
+    }
+
+    #[test]
+    fn test_expand_replacement() {
+        assert_eq!(ReplaceSpec::expand_replacement(r"\1-\2"), "${1}-${2}");
+        assert_eq!(
+            ReplaceSpec::expand_replacement(r"\g<host>:80"),
+            "${host}:80"
+        );
+        assert_eq!(ReplaceSpec::expand_replacement(r"cost $5\n"), "cost $$5\n");
+        assert_eq!(ReplaceSpec::expand_replacement(r"a\\b"), r"a\b");
+    }
+
+    #[test]
+    fn test_apply_multiline_with_backrefs() {
+        let spec = spec(serde_json::json!({
+            "regexp": r"^(\w+) = old$",
+            "replace": r"\1 = new",
+        }));
+        let (content, count) = spec.apply("a = old\nb = keep\nc = old\n").unwrap();
+
+        assert_eq!(content, "a = new\nb = keep\nc = new\n");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_apply_within_anchors() {
+        let content = "x=1\n[main]\nx=1\n[other]\nx=1\n";
+        let spec = spec(serde_json::json!({
+            "regexp": "x=1",
+            "replace": "x=2",
+            "after": r"\[main\]",
+            "before": r"\[other\]",
+        }));
+        let (replaced, count) = spec.apply(content).unwrap();
+        assert_eq!(replaced, "x=1\n[main]\nx=2\n[other]\nx=1\n");
+        assert_eq!(count, 1);
+
+        let missing = ReplaceSpec {
+            after: Some(Regex::new("nope").unwrap()),
+            ..spec
+        };
+        assert!(missing.apply(content).is_none());
+    }
+
+    #[test]
+    fn test_failing_validate_leaves_file_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.conf");
+        fs::write(&path, "port = 80\n").unwrap();
+
+        let params: ModuleParams = serde_json::from_value(serde_json::json!({
+            "path": path.to_str().unwrap(),
+            "regexp": "80",
+            "replace": "eighty",
+            "validate": "grep -qE '^port = [0-9]+$' %s || { echo 'port must be numeric' >&2; false; }",
+        }))
+        .unwrap();
+        let err = ReplaceModule
+            .execute(&params, &ModuleContext::default())
+            .unwrap_err();
+
+        assert!(err.to_string().contains("port must be numeric"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "port = 80\n");
+    }
+
+    #[test]
+    fn test_invalid_regexp_is_rejected() {
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("path".to_string(), serde_json::json!("/tmp/x"));
+        params.insert("regexp".to_string(), serde_json::json!("(unclosed"));
+
+        let err = ReplaceModule.validate_params(&params).unwrap_err();
+        assert!(err.to_string().contains("Invalid regexp"));
+    }
+}
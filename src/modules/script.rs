@@ -0,0 +1,381 @@
+//! Script module - Run a local script on the target
+//!
+//! This module transfers a script from the control node to the target,
+//! executes it there and removes it again, so nothing is left behind.
+//!
+//! The script is staged in the connection's managed temp directory (see
+//! [`RemoteTmp`]); connections without one get a temp directory of their own
+//! that is removed together with the script.
+//!
+//! ## Parameters
+//!
+//! - `cmd`: Path to the local script followed by its arguments (required,
+//!   also accepted as the free-form argument)
+//! - `executable`: Interpreter to run the script with (e.g. `python3`);
+//!   without it the script is executed directly and needs a shebang
+//! - `chdir`: Directory to run the script in
+//! - `creates`: Skip if this path exists on the target
+//! - `removes`: Skip unless this path exists on the target
+//!
+//! Relative script paths are looked up in `<work_dir>/files`, then
+//! `work_dir`, then the current directory.
+
+use super::{
+    chdir_not_found, validate_path_param, Diff, Module, ModuleClassification, ModuleContext,
+    ModuleError, ModuleOutput, ModuleParams, ModuleResult, ParamExt,
+};
+use crate::connection::local::LocalConnection;
+use crate::connection::{Connection, RemoteTmp, TransferOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+
+/// Parsed script invocation
+#[derive(Debug, Clone)]
+struct ScriptSpec {
+    /// Script on the control node
+    script: PathBuf,
+    /// Arguments passed to the script
+    args: Vec<String>,
+    /// Interpreter to run the script with
+    executable: Option<String>,
+}
+
+impl ScriptSpec {
+    fn from_params(params: &ModuleParams, context: &ModuleContext) -> ModuleResult<Self> {
+        let raw = match params.get_string("cmd")? {
+            Some(cmd) => cmd,
+            None => params.get_string("_raw_params")?.ok_or_else(|| {
+                ModuleError::MissingParameter("script requires 'cmd'".to_string())
+            })?,
+        };
+        let mut words = shell_words::split(&raw).map_err(|e| {
+            ModuleError::InvalidParameter(format!("Invalid script command '{}': {}", raw, e))
+        })?;
+        if words.is_empty() {
+            return Err(ModuleError::InvalidParameter(
+                "cmd cannot be empty".to_string(),
+            ));
+        }
+        let script = words.remove(0);
+
+        Ok(Self {
+            script: Self::resolve(&script, context)?,
+            args: words,
+            executable: params.get_string("executable")?,
+        })
+    }
+
+    /// Find the script on the control node
+    fn resolve(script: &str, context: &ModuleContext) -> ModuleResult<PathBuf> {
+        let path = Path::new(script);
+        let mut search = Vec::new();
+        if let (true, Some(work_dir)) = (path.is_relative(), &context.work_dir) {
+            search.push(Path::new(work_dir).join("files").join(path));
+            search.push(Path::new(work_dir).join(path));
+        }
+        search.push(path.to_path_buf());
+        search.into_iter().find(|p| p.is_file()).ok_or_else(|| {
+            ModuleError::ExecutionFailed(format!("Could not find script '{}'", script))
+        })
+    }
+
+    /// Command line running the staged copy of the script
+    fn command(&self, staged: &Path) -> String {
+        let mut parts = Vec::with_capacity(self.args.len() + 2);
+        if let Some(ref executable) = self.executable {
+            parts.push(executable.clone());
+        }
+        parts.push(shell_escape(&staged.to_string_lossy()));
+        parts.extend(self.args.iter().map(|arg| shell_escape(arg)));
+        parts.join(" ")
+    }
+
+    /// How the script is shown in messages
+    fn display(&self) -> String {
+        let mut display = self.script.display().to_string();
+        for arg in &self.args {
+            display.push(' ');
+            display.push_str(arg);
+        }
+        display
+    }
+}
+
+/// Module for running local scripts on the target
+pub struct ScriptModule;
+
+impl ScriptModule {
+    /// Check creates/removes conditions on the target
+    async fn check_creates_removes(
+        params: &ModuleParams,
+        connection: &dyn Connection,
+    ) -> ModuleResult<Option<ModuleOutput>> {
+        if let Some(creates) = params.get_string("creates")? {
+            validate_path_param(&creates, "creates")?;
+            if connection
+                .path_exists(Path::new(&creates))
+                .await
+                .unwrap_or(false)
+            {
+                return Ok(Some(ModuleOutput::ok(format!(
+                    "Skipped, '{}' exists",
+                    creates
+                ))));
+            }
+        }
+
+        if let Some(removes) = params.get_string("removes")? {
+            validate_path_param(&removes, "removes")?;
+            if !connection
+                .path_exists(Path::new(&removes))
+                .await
+                .unwrap_or(false)
+            {
+                return Ok(Some(ModuleOutput::ok(format!(
+                    "Skipped, '{}' does not exist",
+                    removes
+                ))));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Transfer the script, run it and remove it again
+    async fn execute_async(
+        params: &ModuleParams,
+        context: &ModuleContext,
+        connection: Arc<dyn Connection + Send + Sync>,
+    ) -> ModuleResult<ModuleOutput> {
+        let spec = ScriptSpec::from_params(params, context)?;
+
+        if let Some(output) = Self::check_creates_removes(params, connection.as_ref()).await? {
+            return Ok(output);
+        }
+
+        if context.check_mode {
+            return Ok(ModuleOutput::changed(format!(
+                "Would run script: {}",
+                spec.display()
+            )));
+        }
+
+        let mut options = context.execute_options();
+        if let Some(dir) = context.chdir(params)? {
+            if let Ok(false) = connection.is_directory(Path::new(&dir)).await {
+                return Err(chdir_not_found(&dir));
+            }
+            options = options.with_cwd(dir);
+        }
+
+        let content = std::fs::read(&spec.script).map_err(|e| {
+            ModuleError::ExecutionFailed(format!(
+                "Failed to read script '{}': {}",
+                spec.script.display(),
+                e
+            ))
+        })?;
+
+        // Without a managed temp directory, use one just for this script
+        let own_tmp;
+        let (tmp, owns_tmp) = match connection.remote_tmp() {
+            Some(tmp) => (tmp, false),
+            None => {
+                own_tmp = RemoteTmp::new(uuid::Uuid::new_v4().to_string());
+                (&own_tmp, true)
+            }
+        };
+        let staged = Self::stage(connection.as_ref(), tmp, &spec.script, &content).await?;
+
+        let result = connection
+            .execute(&spec.command(&staged), Some(options))
+            .await;
+
+        // Remove the script whether or not it ran
+        let cleanup = if owns_tmp {
+            tmp.cleanup(connection.as_ref()).await
+        } else {
+            let rm = format!("rm -f {}", shell_escape(&staged.to_string_lossy()));
+            connection.execute(&rm, None).await.map(|_| ())
+        };
+        if let Err(e) = cleanup {
+            tracing::warn!("Failed to remove script {}: {}", staged.display(), e);
+        }
+
+        let result = result.map_err(|e| {
+            ModuleError::from_connection(
+                format_args!("Failed to run script '{}'", spec.display()),
+                e,
+            )
+        })?;
+
+        if result.success {
+            Ok(
+                ModuleOutput::changed(format!("Script '{}' executed successfully", spec.display()))
+                    .with_command_output(
+                        Some(result.stdout),
+                        Some(result.stderr),
+                        Some(result.exit_code),
+                    ),
+            )
+        } else {
+            Err(ModuleError::CommandFailed {
+                code: result.exit_code,
+                message: if result.stderr.is_empty() {
+                    result.stdout
+                } else {
+                    result.stderr
+                },
+            })
+        }
+    }
+
+    /// Upload the script into the temp directory, executable by its owner
+    async fn stage(
+        connection: &dyn Connection,
+        tmp: &RemoteTmp,
+        script: &Path,
+        content: &[u8],
+    ) -> ModuleResult<PathBuf> {
+        let name = script
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "script".to_string());
+        let dir = tmp.path(connection).await.map_err(|e| {
+            ModuleError::ExecutionFailed(format!("Failed to create temp directory: {}", e))
+        })?;
+        // Scripts from concurrent tasks share the directory
+        let staged = dir.join(format!("{}-{}", uuid::Uuid::new_v4().simple(), name));
+
+        connection
+            .upload_content(
+                content,
+                &staged,
+                Some(TransferOptions::new().with_mode(0o700)),
+            )
+            .await
+            .map_err(|e| ModuleError::from_connection("Failed to upload script", e))?;
+        Ok(staged)
+    }
+}
+
+/// Escape a string for use in shell commands
+fn shell_escape(s: &str) -> String {
+    // If the string contains no special characters, return as-is
+    if s.chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '/')
+    {
+        return s.to_string();
+    }
+    // Otherwise, wrap in single quotes and escape any single quotes within
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+impl Module for ScriptModule {
+    fn name(&self) -> &'static str {
+        "script"
+    }
+
+    fn description(&self) -> &'static str {
+        "Transfer a local script to the target and run it"
+    }
+
+    fn classification(&self) -> ModuleClassification {
+        ModuleClassification::RemoteCommand
+    }
+
+    fn required_params(&self) -> &[&'static str] {
+        // 'cmd' may also come as the free-form argument
+        &[]
+    }
+
+    fn validate_params(&self, params: &ModuleParams) -> ModuleResult<()> {
+        if params.get("cmd").is_none() && params.get("_raw_params").is_none() {
+            return Err(ModuleError::MissingParameter(
+                "script requires 'cmd'".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        params: &ModuleParams,
+        context: &ModuleContext,
+    ) -> ModuleResult<ModuleOutput> {
+        let connection: Arc<dyn Connection + Send + Sync> = match context.connection {
+            Some(ref connection) => connection.clone(),
+            None => Arc::new(LocalConnection::new()),
+        };
+
+        let handle = Handle::try_current()
+            .map_err(|_| ModuleError::ExecutionFailed("No tokio runtime available".to_string()))?;
+
+        // Spawn blocking task on a separate thread to avoid runtime nesting issues
+        std::thread::scope(|s| {
+            s.spawn(|| handle.block_on(Self::execute_async(params, context, connection)))
+                .join()
+                .unwrap()
+        })
+    }
+
+    fn check(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<ModuleOutput> {
+        let check_context = ModuleContext {
+            check_mode: true,
+            ..context.clone()
+        };
+        let mut output = self.execute(params, &check_context)?;
+        if let Some(diff) = self.diff(params, context)? {
+            output.diff = Some(diff);
+        }
+        Ok(output)
+    }
+
+    fn diff(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<Option<Diff>> {
+        let spec = ScriptSpec::from_params(params, context)?;
+        Ok(Some(Diff::new(
+            "(none)",
+            format!("Run script: {}", spec.display()),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_spec_splits_script_and_arguments() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("files")).unwrap();
+        std::fs::write(dir.path().join("files/setup.sh"), "#!/bin/sh\n").unwrap();
+
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "_raw_params".to_string(),
+            serde_json::json!("setup.sh --name 'a b'"),
+        );
+        params.insert("executable".to_string(), serde_json::json!("bash"));
+        let context = ModuleContext::default().with_work_dir(dir.path().to_string_lossy());
+
+        let spec = ScriptSpec::from_params(&params, &context).unwrap();
+        assert_eq!(spec.script, dir.path().join("files/setup.sh"));
+        assert_eq!(spec.args, vec!["--name", "a b"]);
+        assert_eq!(
+            spec.command(Path::new("/tmp/x/setup.sh")),
+            "bash /tmp/x/setup.sh --name 'a b'"
+        );
+    }
+
+    #[test]
+    fn test_missing_script_is_an_error() {
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "cmd".to_string(),
+            serde_json::json!("/nonexistent/script.sh"),
+        );
+        assert!(ScriptSpec::from_params(&params, &ModuleContext::default()).is_err());
+    }
+}
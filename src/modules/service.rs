@@ -22,6 +22,14 @@
 //! - `use_systemctl`: Force use of systemctl even if service command available
 //! - `daemon_reload`: Reload systemd daemon before action
 //! - `daemon_reexec`: Re-execute systemd manager
+//! - `scope`: systemd manager to talk to (system, user, global)
+//!
+//! ## User Scope
+//!
+//! `scope: user` runs `systemctl --user` for the connecting (or become) user.
+//! Over SSH there is no login session to inherit, so `XDG_RUNTIME_DIR` and
+//! `DBUS_SESSION_BUS_ADDRESS` are exported from that user's uid to reach their
+//! service manager.
 
 use super::{
     Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
@@ -51,7 +59,7 @@ impl InitSystem {
     /// 4. Check for launchctl (macOS)
     /// 5. Check for Upstart (/etc/init)
     /// 6. Fall back to SysV if /etc/init.d exists
-    async fn detect_async(
+    pub(crate) async fn detect_async(
         connection: &dyn Connection,
         use_systemctl: Option<bool>,
     ) -> Option<Self> {
@@ -214,6 +222,42 @@ impl ServiceState {
     }
 }
 
+/// systemd manager a service belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SystemdScope {
+    /// The system manager
+    #[default]
+    System,
+    /// The calling user's manager
+    User,
+    /// Unit files for all users; only enablement can be changed
+    Global,
+}
+
+impl SystemdScope {
+    pub fn from_str(s: &str) -> ModuleResult<Self> {
+        match s.to_lowercase().as_str() {
+            "system" => Ok(SystemdScope::System),
+            "user" => Ok(SystemdScope::User),
+            "global" => Ok(SystemdScope::Global),
+            _ => Err(ModuleError::InvalidParameter(format!(
+                "Invalid scope '{}'. Valid scopes: system, user, global",
+                s
+            ))),
+        }
+    }
+}
+
+/// `systemctl` invocation for a user's manager, given the user's uid
+///
+/// `env` carries the variables through sudo/su, which reset the environment.
+fn user_systemctl(uid: u32) -> String {
+    format!(
+        "env XDG_RUNTIME_DIR=/run/user/{uid} DBUS_SESSION_BUS_ADDRESS=unix:path=/run/user/{uid}/bus systemctl --user",
+        uid = uid
+    )
+}
+
 /// Service module configuration parsed from parameters
 #[derive(Debug, Clone)]
 struct ServiceConfig {
@@ -237,6 +281,8 @@ struct ServiceConfig {
     daemon_reexec: bool,
     /// Additional arguments passed to the service command
     arguments: Option<String>,
+    /// systemd manager to use
+    scope: SystemdScope,
 }
 
 impl ServiceConfig {
@@ -271,6 +317,11 @@ impl ServiceConfig {
             daemon_reload: params.get_bool_or("daemon_reload", false),
             daemon_reexec: params.get_bool_or("daemon_reexec", false),
             arguments: params.get_string("arguments")?,
+            scope: params
+                .get_string("scope")?
+                .map(|s| SystemdScope::from_str(&s))
+                .transpose()?
+                .unwrap_or_default(),
         })
     }
 
@@ -286,16 +337,7 @@ pub struct ServiceModule;
 impl ServiceModule {
     /// Build execute options with privilege escalation if needed
     fn build_execute_options(context: &ModuleContext) -> Option<ExecuteOptions> {
-        if context.r#become {
-            Some(ExecuteOptions {
-                escalate: true,
-                escalate_user: context.become_user.clone(),
-                escalate_method: context.become_method.clone(),
-                ..Default::default()
-            })
-        } else {
-            None
-        }
+        context.r#become.then(|| context.execute_options())
     }
 
     /// Execute a command via connection
@@ -308,19 +350,49 @@ impl ServiceModule {
         connection
             .execute(command, options)
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("Connection execute failed: {}", e)))
+            .map_err(|e| ModuleError::from_connection("Connection execute failed", e))
+    }
+
+    /// Resolve the `systemctl` invocation for the configured scope
+    ///
+    /// The user scope looks up the uid of whoever commands run as, so a
+    /// `become_user` gets their own manager.
+    async fn systemctl_command(
+        connection: &dyn Connection,
+        scope: SystemdScope,
+        context: &ModuleContext,
+    ) -> ModuleResult<String> {
+        match scope {
+            SystemdScope::System => Ok("systemctl".to_string()),
+            SystemdScope::Global => Ok("systemctl --global".to_string()),
+            SystemdScope::User => {
+                let result = Self::execute_command(connection, "id -u", context).await?;
+                let uid = result
+                    .success
+                    .then(|| result.stdout.trim().parse::<u32>().ok())
+                    .flatten()
+                    .ok_or_else(|| {
+                        ModuleError::ExecutionFailed(format!(
+                            "Failed to determine uid for user scope: {}",
+                            result.stderr.trim()
+                        ))
+                    })?;
+                Ok(user_systemctl(uid))
+            }
+        }
     }
 
     /// Expand service pattern to list of matching services (systemd only)
     async fn expand_service_pattern(
         connection: &dyn Connection,
+        systemctl: &str,
         pattern: &str,
         context: &ModuleContext,
     ) -> ModuleResult<Vec<String>> {
         // Use systemctl list-units to find matching services
         let cmd = format!(
-            "systemctl list-units --type=service --all --no-legend --no-pager '{}' | awk '{{print $1}}'",
-            pattern
+            "{} list-units --type=service --all --no-legend --no-pager '{}' | awk '{{print $1}}'",
+            systemctl, pattern
         );
         let result = Self::execute_command(connection, &cmd, context).await?;
 
@@ -335,8 +407,8 @@ impl ServiceModule {
         } else {
             // Fallback: try list-unit-files for services that might not be loaded
             let cmd = format!(
-                "systemctl list-unit-files --type=service --no-legend --no-pager '{}' | awk '{{print $1}}'",
-                pattern
+                "{} list-unit-files --type=service --no-legend --no-pager '{}' | awk '{{print $1}}'",
+                systemctl, pattern
             );
             let result = Self::execute_command(connection, &cmd, context).await?;
 
@@ -373,10 +445,11 @@ impl ServiceModule {
     /// Check if service is active (systemd)
     async fn systemd_is_active(
         connection: &dyn Connection,
+        systemctl: &str,
         service: &str,
         context: &ModuleContext,
     ) -> ModuleResult<bool> {
-        let cmd = format!("systemctl is-active {}", service);
+        let cmd = format!("{} is-active {}", systemctl, service);
         let result = Self::execute_command(connection, &cmd, context).await?;
         Ok(result.success)
     }
@@ -384,22 +457,24 @@ impl ServiceModule {
     /// Check if service is enabled (systemd)
     async fn systemd_is_enabled(
         connection: &dyn Connection,
+        systemctl: &str,
         service: &str,
         context: &ModuleContext,
     ) -> ModuleResult<bool> {
-        let cmd = format!("systemctl is-enabled {}", service);
+        let cmd = format!("{} is-enabled {}", systemctl, service);
         let result = Self::execute_command(connection, &cmd, context).await?;
-        Ok(result.success)
+        Ok(systemd_unit_file_enabled(result.stdout.trim()).unwrap_or(result.success))
     }
 
     /// Execute a systemd action
     async fn systemd_action(
         connection: &dyn Connection,
+        systemctl: &str,
         service: &str,
         action: &str,
         context: &ModuleContext,
     ) -> ModuleResult<(bool, String, String)> {
-        let cmd = format!("systemctl {} {}", action, service);
+        let cmd = format!("{} {} {}", systemctl, action, service);
         let result = Self::execute_command(connection, &cmd, context).await?;
         Ok((result.success, result.stdout, result.stderr))
     }
@@ -407,9 +482,11 @@ impl ServiceModule {
     /// Reload systemd daemon
     async fn systemd_daemon_reload(
         connection: &dyn Connection,
+        systemctl: &str,
         context: &ModuleContext,
     ) -> ModuleResult<()> {
-        let result = Self::execute_command(connection, "systemctl daemon-reload", context).await?;
+        let cmd = format!("{} daemon-reload", systemctl);
+        let result = Self::execute_command(connection, &cmd, context).await?;
         if result.success {
             Ok(())
         } else {
@@ -610,9 +687,11 @@ impl ServiceModule {
     /// Re-execute systemd manager
     async fn systemd_daemon_reexec(
         connection: &dyn Connection,
+        systemctl: &str,
         context: &ModuleContext,
     ) -> ModuleResult<()> {
-        let result = Self::execute_command(connection, "systemctl daemon-reexec", context).await?;
+        let cmd = format!("{} daemon-reexec", systemctl);
+        let result = Self::execute_command(connection, &cmd, context).await?;
         if result.success {
             Ok(())
         } else {
@@ -627,13 +706,16 @@ impl ServiceModule {
     async fn is_active(
         connection: &dyn Connection,
         init: &InitSystem,
+        systemctl: &str,
         service: &str,
         pattern: Option<&str>,
         context: &ModuleContext,
     ) -> ModuleResult<bool> {
         // First try init-system-specific status check
         let result = match init {
-            InitSystem::Systemd => Self::systemd_is_active(connection, service, context).await,
+            InitSystem::Systemd => {
+                Self::systemd_is_active(connection, systemctl, service, context).await
+            }
             InitSystem::SysV => Self::sysv_is_active(connection, service, context).await,
             InitSystem::OpenRC => Self::openrc_is_active(connection, service, context).await,
             InitSystem::Upstart => Self::upstart_is_active(connection, service, context).await,
@@ -660,12 +742,15 @@ impl ServiceModule {
     async fn is_enabled(
         connection: &dyn Connection,
         init: &InitSystem,
+        systemctl: &str,
         service: &str,
         runlevel: Option<&str>,
         context: &ModuleContext,
     ) -> ModuleResult<bool> {
         match init {
-            InitSystem::Systemd => Self::systemd_is_enabled(connection, service, context).await,
+            InitSystem::Systemd => {
+                Self::systemd_is_enabled(connection, systemctl, service, context).await
+            }
             InitSystem::SysV => Self::sysv_is_enabled(connection, service, runlevel, context).await,
             InitSystem::OpenRC => {
                 Self::openrc_is_enabled(connection, service, runlevel, context).await
@@ -686,6 +771,7 @@ impl ServiceModule {
     async fn set_enabled(
         connection: &dyn Connection,
         init: &InitSystem,
+        systemctl: &str,
         service: &str,
         enable: bool,
         runlevel: Option<&str>,
@@ -694,7 +780,7 @@ impl ServiceModule {
         match init {
             InitSystem::Systemd => {
                 let action = if enable { "enable" } else { "disable" };
-                Self::systemd_action(connection, service, action, context).await
+                Self::systemd_action(connection, systemctl, service, action, context).await
             }
             InitSystem::SysV => {
                 Self::sysv_enable(connection, service, enable, runlevel, context).await
@@ -724,6 +810,7 @@ impl ServiceModule {
     async fn service_action(
         connection: &dyn Connection,
         init: &InitSystem,
+        systemctl: &str,
         service: &str,
         action: &str,
         arguments: Option<&str>,
@@ -732,9 +819,9 @@ impl ServiceModule {
         match init {
             InitSystem::Systemd => {
                 let cmd = if let Some(args) = arguments {
-                    format!("systemctl {} {} {}", action, service, args)
+                    format!("{} {} {} {}", systemctl, action, service, args)
                 } else {
-                    format!("systemctl {} {}", action, service)
+                    format!("{} {} {}", systemctl, action, service)
                 };
                 let result = Self::execute_command(connection, &cmd, context).await?;
                 Ok((result.success, result.stdout, result.stderr))
@@ -760,6 +847,7 @@ impl ServiceModule {
     async fn restart_with_sleep(
         connection: &dyn Connection,
         init: &InitSystem,
+        systemctl: &str,
         service: &str,
         sleep_secs: Option<u64>,
         arguments: Option<&str>,
@@ -767,8 +855,10 @@ impl ServiceModule {
     ) -> ModuleResult<(bool, String, String)> {
         if let Some(secs) = sleep_secs {
             // Stop, sleep, start
-            let (stop_ok, stop_out, stop_err) =
-                Self::service_action(connection, init, service, "stop", arguments, context).await?;
+            let (stop_ok, stop_out, stop_err) = Self::service_action(
+                connection, init, systemctl, service, "stop", arguments, context,
+            )
+            .await?;
 
             if !stop_ok {
                 return Ok((false, stop_out, stop_err));
@@ -776,9 +866,10 @@ impl ServiceModule {
 
             Self::sleep_seconds(secs).await;
 
-            let (start_ok, start_out, start_err) =
-                Self::service_action(connection, init, service, "start", arguments, context)
-                    .await?;
+            let (start_ok, start_out, start_err) = Self::service_action(
+                connection, init, systemctl, service, "start", arguments, context,
+            )
+            .await?;
 
             Ok((
                 start_ok,
@@ -787,7 +878,10 @@ impl ServiceModule {
             ))
         } else {
             // Use native restart
-            Self::service_action(connection, init, service, "restart", arguments, context).await
+            Self::service_action(
+                connection, init, systemctl, service, "restart", arguments, context,
+            )
+            .await
         }
     }
 
@@ -807,6 +901,18 @@ impl ServiceModule {
                 ModuleError::ExecutionFailed("Could not detect init system".to_string())
             })?;
 
+        if config.scope != SystemdScope::System && init != InitSystem::Systemd {
+            return Err(ModuleError::InvalidParameter(
+                "scope is only supported with systemd".to_string(),
+            ));
+        }
+        if config.scope == SystemdScope::Global && config.state.is_some() {
+            return Err(ModuleError::InvalidParameter(
+                "scope: global can only change 'enabled', not 'state'".to_string(),
+            ));
+        }
+        let systemctl = Self::systemctl_command(connection.as_ref(), config.scope, context).await?;
+
         // Handle wildcard patterns (systemd only)
         if config.has_pattern() {
             if init != InitSystem::Systemd {
@@ -815,11 +921,11 @@ impl ServiceModule {
                 ));
             }
             return self
-                .execute_pattern_async(&config, context, connection, &init)
+                .execute_pattern_async(&config, context, connection, &init, &systemctl)
                 .await;
         }
 
-        self.execute_single_service_async(&config, context, connection, &init)
+        self.execute_single_service_async(&config, context, connection, &init, &systemctl)
             .await
     }
 
@@ -830,6 +936,7 @@ impl ServiceModule {
         context: &ModuleContext,
         connection: Arc<dyn Connection + Send + Sync>,
         init: &InitSystem,
+        systemctl: &str,
     ) -> ModuleResult<ModuleOutput> {
         let service = &config.name;
         let mut changed = false;
@@ -840,7 +947,7 @@ impl ServiceModule {
             if context.check_mode {
                 messages.push("Would re-execute systemd daemon".to_string());
             } else {
-                Self::systemd_daemon_reexec(connection.as_ref(), context).await?;
+                Self::systemd_daemon_reexec(connection.as_ref(), systemctl, context).await?;
                 messages.push("Re-executed systemd daemon".to_string());
                 changed = true;
             }
@@ -851,21 +958,31 @@ impl ServiceModule {
             if context.check_mode {
                 messages.push("Would reload systemd daemon".to_string());
             } else {
-                Self::systemd_daemon_reload(connection.as_ref(), context).await?;
+                Self::systemd_daemon_reload(connection.as_ref(), systemctl, context).await?;
                 messages.push("Reloaded systemd daemon".to_string());
                 changed = true;
             }
         }
 
         // Handle enabled state (now supports all init systems)
+        let mut diff = None;
         if let Some(should_enable) = config.enabled {
             let runlevel = config.runlevel.as_deref();
-            let is_enabled =
-                Self::is_enabled(connection.as_ref(), init, service, runlevel, context)
-                    .await
-                    .unwrap_or(false);
+            let is_enabled = Self::is_enabled(
+                connection.as_ref(),
+                init,
+                systemctl,
+                service,
+                runlevel,
+                context,
+            )
+            .await
+            .unwrap_or(false);
 
             if should_enable != is_enabled {
+                if context.diff_mode {
+                    diff = Some(enabled_diff(is_enabled, should_enable));
+                }
                 if context.check_mode {
                     let action = if should_enable { "enable" } else { "disable" };
                     messages.push(format!("Would {} service '{}'", action, service));
@@ -875,6 +992,7 @@ impl ServiceModule {
                     let (success, _, stderr) = Self::set_enabled(
                         connection.as_ref(),
                         init,
+                        systemctl,
                         service,
                         should_enable,
                         runlevel,
@@ -898,8 +1016,15 @@ impl ServiceModule {
         // Handle state
         if let Some(ref desired_state) = config.state {
             let pattern = config.pattern.as_deref();
-            let is_active =
-                Self::is_active(connection.as_ref(), init, service, pattern, context).await?;
+            let is_active = Self::is_active(
+                connection.as_ref(),
+                init,
+                systemctl,
+                service,
+                pattern,
+                context,
+            )
+            .await?;
 
             match desired_state {
                 ServiceState::Started => {
@@ -911,6 +1036,7 @@ impl ServiceModule {
                             let (success, _, stderr) = Self::service_action(
                                 connection.as_ref(),
                                 init,
+                                systemctl,
                                 service,
                                 "start",
                                 config.arguments.as_deref(),
@@ -942,6 +1068,7 @@ impl ServiceModule {
                             let (success, _, stderr) = Self::service_action(
                                 connection.as_ref(),
                                 init,
+                                systemctl,
                                 service,
                                 "stop",
                                 config.arguments.as_deref(),
@@ -972,6 +1099,7 @@ impl ServiceModule {
                         let (success, _, stderr) = Self::restart_with_sleep(
                             connection.as_ref(),
                             init,
+                            systemctl,
                             service,
                             config.sleep,
                             config.arguments.as_deref(),
@@ -999,6 +1127,7 @@ impl ServiceModule {
                         let (success, _, _stderr) = Self::service_action(
                             connection.as_ref(),
                             init,
+                            systemctl,
                             service,
                             "reload",
                             config.arguments.as_deref(),
@@ -1011,6 +1140,7 @@ impl ServiceModule {
                             if *init == InitSystem::Systemd {
                                 let (success2, _, stderr2) = Self::systemd_action(
                                     connection.as_ref(),
+                                    systemctl,
                                     service,
                                     "reload-or-restart",
                                     context,
@@ -1027,6 +1157,7 @@ impl ServiceModule {
                                 let (success2, _, stderr2) = Self::service_action(
                                     connection.as_ref(),
                                     init,
+                                    systemctl,
                                     service,
                                     "restart",
                                     config.arguments.as_deref(),
@@ -1049,12 +1180,14 @@ impl ServiceModule {
                                     .build_output(
                                         service,
                                         init,
+                                        systemctl,
                                         connection.as_ref(),
                                         context,
                                         changed,
                                         messages,
                                     )
-                                    .await;
+                                    .await
+                                    .map(|output| with_optional_diff(output, diff));
                             }
                         }
 
@@ -1068,12 +1201,14 @@ impl ServiceModule {
         self.build_output(
             service,
             init,
+            systemctl,
             connection.as_ref(),
             context,
             changed,
             messages,
         )
         .await
+        .map(|output| with_optional_diff(output, diff))
     }
 
     /// Execute module for pattern/wildcard service names
@@ -1083,9 +1218,11 @@ impl ServiceModule {
         context: &ModuleContext,
         connection: Arc<dyn Connection + Send + Sync>,
         init: &InitSystem,
+        systemctl: &str,
     ) -> ModuleResult<ModuleOutput> {
         let services =
-            Self::expand_service_pattern(connection.as_ref(), &config.name, context).await?;
+            Self::expand_service_pattern(connection.as_ref(), systemctl, &config.name, context)
+                .await?;
 
         if services.is_empty() {
             return Ok(ModuleOutput::ok(format!(
@@ -1111,10 +1248,17 @@ impl ServiceModule {
                 daemon_reload: false, // Only do once
                 daemon_reexec: false, // Only do once
                 arguments: config.arguments.clone(),
+                scope: config.scope,
             };
 
             match self
-                .execute_single_service_async(&service_config, context, connection.clone(), init)
+                .execute_single_service_async(
+                    &service_config,
+                    context,
+                    connection.clone(),
+                    init,
+                    systemctl,
+                )
                 .await
             {
                 Ok(output) => {
@@ -1163,6 +1307,7 @@ impl ServiceModule {
         &self,
         service: &str,
         init: &InitSystem,
+        systemctl: &str,
         connection: &dyn Connection,
         context: &ModuleContext,
         changed: bool,
@@ -1177,10 +1322,10 @@ impl ServiceModule {
         // Get current status for output
         let status = match init {
             InitSystem::Systemd => {
-                let is_active = Self::systemd_is_active(connection, service, context)
+                let is_active = Self::systemd_is_active(connection, systemctl, service, context)
                     .await
                     .unwrap_or(false);
-                let is_enabled = Self::systemd_is_enabled(connection, service, context)
+                let is_enabled = Self::systemd_is_enabled(connection, systemctl, service, context)
                     .await
                     .unwrap_or(false);
                 serde_json::json!({
@@ -1190,12 +1335,14 @@ impl ServiceModule {
                 })
             }
             _ => {
-                let is_active = Self::is_active(connection, init, service, None, context)
-                    .await
-                    .unwrap_or(false);
-                let is_enabled = Self::is_enabled(connection, init, service, None, context)
-                    .await
-                    .unwrap_or(false);
+                let is_active =
+                    Self::is_active(connection, init, systemctl, service, None, context)
+                        .await
+                        .unwrap_or(false);
+                let is_enabled =
+                    Self::is_enabled(connection, init, systemctl, service, None, context)
+                        .await
+                        .unwrap_or(false);
                 serde_json::json!({
                     "active": is_active,
                     "enabled": is_enabled,
@@ -1229,17 +1376,32 @@ impl ServiceModule {
         if config.has_pattern() {
             return Ok(None);
         }
+        let systemctl = Self::systemctl_command(connection.as_ref(), config.scope, context).await?;
 
         let service = &config.name;
         let pattern = config.pattern.as_deref();
         let runlevel = config.runlevel.as_deref();
 
-        let is_active = Self::is_active(connection.as_ref(), &init, service, pattern, context)
-            .await
-            .unwrap_or(false);
-        let is_enabled = Self::is_enabled(connection.as_ref(), &init, service, runlevel, context)
-            .await
-            .unwrap_or(false);
+        let is_active = Self::is_active(
+            connection.as_ref(),
+            &init,
+            &systemctl,
+            service,
+            pattern,
+            context,
+        )
+        .await
+        .unwrap_or(false);
+        let is_enabled = Self::is_enabled(
+            connection.as_ref(),
+            &init,
+            &systemctl,
+            service,
+            runlevel,
+            context,
+        )
+        .await
+        .unwrap_or(false);
 
         let mut before_lines = Vec::new();
         let mut after_lines = Vec::new();
@@ -1294,6 +1456,36 @@ impl ServiceModule {
     }
 }
 
+/// Interpret the unit file state printed by `systemctl is-enabled`.
+///
+/// Static, indirect and generated units start without being enabled and
+/// can't be toggled, so they count as enabled.
+fn systemd_unit_file_enabled(state: &str) -> Option<bool> {
+    match state {
+        "enabled" | "enabled-runtime" | "static" | "alias" | "indirect" | "generated" => Some(true),
+        "disabled" | "masked" | "masked-runtime" | "linked" | "linked-runtime" | "not-found" => {
+            Some(false)
+        }
+        _ => None,
+    }
+}
+
+/// Diff for a change in whether a service starts at boot
+fn enabled_diff(before: bool, after: bool) -> Diff {
+    Diff::new(
+        format!("enabled: {}", before),
+        format!("enabled: {}", after),
+    )
+    .with_details(format!("enabled: {} -> {}", before, after))
+}
+
+fn with_optional_diff(output: ModuleOutput, diff: Option<Diff>) -> ModuleOutput {
+    match diff {
+        Some(diff) => output.with_diff(diff),
+        None => output,
+    }
+}
+
 impl Module for ServiceModule {
     fn name(&self) -> &'static str {
         "service"
@@ -1375,6 +1567,15 @@ impl Module for ServiceModule {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_systemd_unit_file_enabled() {
+        assert_eq!(systemd_unit_file_enabled("enabled"), Some(true));
+        assert_eq!(systemd_unit_file_enabled("static"), Some(true));
+        assert_eq!(systemd_unit_file_enabled("disabled"), Some(false));
+        assert_eq!(systemd_unit_file_enabled("masked"), Some(false));
+        assert_eq!(systemd_unit_file_enabled(""), None);
+    }
+
     #[test]
     fn test_service_state_from_str() {
         assert_eq!(
@@ -1400,6 +1601,20 @@ mod tests {
         assert!(ServiceState::from_str("invalid").is_err());
     }
 
+    #[test]
+    fn test_systemd_scope_from_str() {
+        assert_eq!(SystemdScope::from_str("user").unwrap(), SystemdScope::User);
+        assert_eq!(
+            SystemdScope::from_str("Global").unwrap(),
+            SystemdScope::Global
+        );
+        assert!(SystemdScope::from_str("session").is_err());
+        assert_eq!(
+            user_systemctl(1000),
+            "env XDG_RUNTIME_DIR=/run/user/1000 DBUS_SESSION_BUS_ADDRESS=unix:path=/run/user/1000/bus systemctl --user"
+        );
+    }
+
     #[test]
     fn test_service_module_metadata() {
         let module = ServiceModule;
@@ -0,0 +1,296 @@
+//! Service facts module - Service inventory
+//!
+//! This module lists the services known to the target's init system and
+//! returns them as the `services` fact, keyed by service name:
+//!
+//! ```yaml
+//! - service_facts:
+//!
+//! - debug:
+//!     msg: nginx is running
+//!   when: "'nginx.service' in services and services['nginx.service'].state == 'running'"
+//! ```
+//!
+//! Each entry has `name`, `state` (running, stopped, failed or unknown),
+//! `status` (enabled, disabled, static, ... or unknown) and `source`
+//! (systemd or sysv).
+//!
+//! ## Supported Init Systems
+//!
+//! - **Systemd**: `systemctl list-units` and `systemctl list-unit-files`
+//! - **SysV**: `service --status-all`, also used for Upstart and OpenRC hosts
+
+use super::service::InitSystem;
+use super::{
+    Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
+    ModuleResult,
+};
+use crate::connection::{Connection, ExecuteOptions};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// Lists every loaded service unit, one per line
+const SYSTEMD_UNITS_CMD: &str =
+    "systemctl list-units --type=service --all --no-pager --no-legend --plain";
+
+/// Lists the enablement state of every service unit file
+const SYSTEMD_UNIT_FILES_CMD: &str =
+    "systemctl list-unit-files --type=service --no-pager --no-legend --plain";
+
+/// Lists init scripts with `[ + ]`, `[ - ]` or `[ ? ]` markers
+const SYSV_STATUS_CMD: &str = "service --status-all 2>&1";
+
+/// Module for gathering service facts
+pub struct ServiceFactsModule;
+
+impl ServiceFactsModule {
+    /// Build a service entry
+    fn service_entry(name: &str, state: &str, status: &str, source: &str) -> Value {
+        serde_json::json!({
+            "name": name,
+            "state": state,
+            "status": status,
+            "source": source,
+        })
+    }
+
+    /// Parse `systemctl list-units` output into service entries.
+    ///
+    /// `statuses` maps unit names to their `list-unit-files` state; units
+    /// without a unit file (e.g. generated ones) get `unknown`.
+    pub fn parse_systemd_units(output: &str, statuses: &Map<String, Value>) -> Map<String, Value> {
+        let mut services = Map::new();
+        for line in output.lines() {
+            // Failed units may be marked with a bullet even with --plain
+            let line = line.trim_start_matches(['●', '*', ' ']);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [unit, load, active, sub, ..] = fields[..] else {
+                continue;
+            };
+            if !unit.ends_with(".service") || load == "not-found" {
+                continue;
+            }
+
+            let state = match (active, sub) {
+                (_, "running") => "running",
+                ("failed", _) | (_, "failed") => "failed",
+                ("inactive", _) | (_, "dead") | (_, "exited") => "stopped",
+                _ => "unknown",
+            };
+            let status = statuses
+                .get(unit)
+                .and_then(Value::as_str)
+                .unwrap_or("unknown");
+            services.insert(
+                unit.to_string(),
+                Self::service_entry(unit, state, status, "systemd"),
+            );
+        }
+        services
+    }
+
+    /// Parse `systemctl list-unit-files` output into unit name -> status
+    pub fn parse_systemd_unit_files(output: &str) -> Map<String, Value> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let unit = fields.next()?;
+                let status = fields.next()?;
+                unit.ends_with(".service")
+                    .then(|| (unit.to_string(), Value::String(status.to_string())))
+            })
+            .collect()
+    }
+
+    /// Parse `service --status-all` output into service entries
+    pub fn parse_sysv_status(output: &str) -> Map<String, Value> {
+        let mut services = Map::new();
+        for line in output.lines() {
+            let Some((marker, name)) = line.trim().split_once(']') else {
+                continue;
+            };
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            let state = match marker.trim_start_matches('[').trim() {
+                "+" => "running",
+                "-" => "stopped",
+                _ => "unknown",
+            };
+            services.insert(
+                name.to_string(),
+                Self::service_entry(name, state, "unknown", "sysv"),
+            );
+        }
+        services
+    }
+
+    /// Run a listing command, returning its stdout
+    async fn list(
+        connection: &dyn Connection,
+        command: &str,
+        options: Option<ExecuteOptions>,
+    ) -> ModuleResult<String> {
+        let result = connection
+            .execute(command, options)
+            .await
+            .map_err(|e| ModuleError::from_connection("Connection execute failed", e))?;
+        if !result.success {
+            return Err(ModuleError::ExecutionFailed(format!(
+                "'{}' failed: {}",
+                command,
+                result.stderr.trim()
+            )));
+        }
+        Ok(result.stdout)
+    }
+
+    async fn execute_async(
+        &self,
+        context: &ModuleContext,
+        connection: Arc<dyn Connection + Send + Sync>,
+    ) -> ModuleResult<ModuleOutput> {
+        let options = context.r#become.then(|| context.execute_options());
+        let init = InitSystem::detect_async(connection.as_ref(), None)
+            .await
+            .ok_or_else(|| {
+                ModuleError::ExecutionFailed("Could not detect init system".to_string())
+            })?;
+
+        let services = match init {
+            InitSystem::Systemd => {
+                let units =
+                    Self::list(connection.as_ref(), SYSTEMD_UNITS_CMD, options.clone()).await?;
+                // Enablement is informational; don't fail the whole listing on it
+                let statuses = Self::list(connection.as_ref(), SYSTEMD_UNIT_FILES_CMD, options)
+                    .await
+                    .map(|out| Self::parse_systemd_unit_files(&out))
+                    .unwrap_or_default();
+                Self::parse_systemd_units(&units, &statuses)
+            }
+            InitSystem::SysV | InitSystem::Upstart | InitSystem::OpenRC => {
+                // Scripts that don't implement `status` make the command exit
+                // non-zero, so take the listing whatever the exit code
+                let result = connection
+                    .execute(SYSV_STATUS_CMD, options)
+                    .await
+                    .map_err(|e| ModuleError::from_connection("Connection execute failed", e))?;
+                Self::parse_sysv_status(&result.stdout)
+            }
+            InitSystem::Launchd => {
+                return Err(ModuleError::Unsupported(
+                    "service_facts does not support launchd".to_string(),
+                ));
+            }
+        };
+
+        let count = services.len();
+        let facts = serde_json::json!({ "services": services });
+        Ok(
+            ModuleOutput::ok(format!("Gathered facts for {} services", count))
+                .with_data("ansible_facts", facts),
+        )
+    }
+}
+
+impl Module for ServiceFactsModule {
+    fn name(&self) -> &'static str {
+        "service_facts"
+    }
+
+    fn description(&self) -> &'static str {
+        "Return service state information as fact data"
+    }
+
+    fn classification(&self) -> ModuleClassification {
+        ModuleClassification::RemoteCommand
+    }
+
+    fn execute(
+        &self,
+        _params: &ModuleParams,
+        context: &ModuleContext,
+    ) -> ModuleResult<ModuleOutput> {
+        let connection = context.connection.clone().ok_or_else(|| {
+            ModuleError::ExecutionFailed(
+                "No connection available for service_facts module execution".to_string(),
+            )
+        })?;
+
+        let handle = tokio::runtime::Handle::try_current()
+            .map_err(|_| ModuleError::ExecutionFailed("No tokio runtime available".to_string()))?;
+
+        // Spawn blocking task on a separate thread to avoid runtime nesting issues
+        let context = context.clone();
+        let module = self;
+        std::thread::scope(|s| {
+            s.spawn(|| handle.block_on(module.execute_async(&context, connection)))
+                .join()
+                .unwrap()
+        })
+    }
+
+    fn check(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<ModuleOutput> {
+        // Read-only: gathering facts is the same in check mode
+        self.execute(params, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST_UNITS: &str = "\
+cron.service                   loaded    active   running Regular background program processing daemon
+nginx.service                  loaded    active   running A high performance web server
+ssh.service                    loaded    inactive dead    OpenBSD Secure Shell server
+systemd-fsck-root.service      loaded    active   exited  File System Check on Root Device
+● postgresql.service           loaded    failed   failed  PostgreSQL RDBMS
+ntp.service                    not-found inactive dead    ntp.service
+";
+
+    const LIST_UNIT_FILES: &str = "\
+cron.service                   enabled         enabled
+nginx.service                  enabled         enabled
+ssh.service                    disabled        enabled
+systemd-fsck-root.service      static          -
+";
+
+    #[test]
+    fn test_parse_systemd_units() {
+        let statuses = ServiceFactsModule::parse_systemd_unit_files(LIST_UNIT_FILES);
+        let services = ServiceFactsModule::parse_systemd_units(LIST_UNITS, &statuses);
+
+        assert_eq!(services.len(), 5);
+        assert_eq!(
+            services["nginx.service"],
+            serde_json::json!({
+                "name": "nginx.service",
+                "state": "running",
+                "status": "enabled",
+                "source": "systemd",
+            })
+        );
+        assert_eq!(services["ssh.service"]["state"], "stopped");
+        assert_eq!(services["ssh.service"]["status"], "disabled");
+        assert_eq!(services["systemd-fsck-root.service"]["state"], "stopped");
+        assert_eq!(services["systemd-fsck-root.service"]["status"], "static");
+        assert_eq!(services["postgresql.service"]["state"], "failed");
+        assert_eq!(services["postgresql.service"]["status"], "unknown");
+        assert!(!services.contains_key("ntp.service"));
+    }
+
+    #[test]
+    fn test_parse_sysv_status() {
+        let output = " [ + ]  cron\n [ - ]  nginx\n [ ? ]  hwclock.sh\n";
+        let services = ServiceFactsModule::parse_sysv_status(output);
+
+        assert_eq!(services.len(), 3);
+        assert_eq!(services["cron"]["state"], "running");
+        assert_eq!(services["nginx"]["state"], "stopped");
+        assert_eq!(services["hwclock.sh"]["state"], "unknown");
+        assert_eq!(services["cron"]["source"], "sysv");
+    }
+}
@@ -7,8 +7,9 @@
 //! execution via async connections (SSH, Docker, etc.).
 
 use super::{
-    validate_env_var_name, validate_path_param, Diff, Module, ModuleClassification, ModuleContext,
-    ModuleError, ModuleOutput, ModuleParams, ModuleResult, ParamExt,
+    chdir_not_found, command_warnings, ensure_local_chdir, validate_env_var_name,
+    validate_path_param, Diff, Module, ModuleClassification, ModuleContext, ModuleError,
+    ModuleOutput, ModuleParams, ModuleResult, ParamExt,
 };
 use crate::connection::{Connection, ExecuteOptions};
 use std::path::Path;
@@ -64,13 +65,11 @@ impl ShellModule {
         params: &ModuleParams,
         context: &ModuleContext,
     ) -> ModuleResult<ExecuteOptions> {
-        let mut options = ExecuteOptions::new();
+        let mut options = context.execute_options();
 
         // Set working directory
-        if let Some(chdir) = params.get_string("chdir")? {
-            options = options.with_cwd(chdir);
-        } else if let Some(ref work_dir) = context.work_dir {
-            options = options.with_cwd(work_dir.clone());
+        if let Some(dir) = context.chdir(params)? {
+            options = options.with_cwd(dir);
         }
 
         // Set environment variables (with validation)
@@ -91,13 +90,6 @@ impl ShellModule {
             }
         }
 
-        // Handle privilege escalation from context
-        if context.r#become {
-            options.escalate = true;
-            options.escalate_user = context.become_user.clone();
-            options.escalate_method = context.become_method.clone();
-        }
-
         Ok(options)
     }
 
@@ -201,10 +193,9 @@ impl ShellModule {
         command.arg(&flag).arg(&cmd);
 
         // Set working directory
-        if let Some(chdir) = params.get_string("chdir")? {
-            command.current_dir(&chdir);
-        } else if let Some(ref work_dir) = context.work_dir {
-            command.current_dir(work_dir);
+        if let Some(dir) = context.chdir(params)? {
+            ensure_local_chdir(&dir)?;
+            command.current_dir(dir);
         }
 
         // Set environment variables (with validation)
@@ -325,6 +316,13 @@ impl ShellModule {
                 )));
             }
 
+            // Fail clearly rather than letting the remote `cd` error surface
+            if let Some(ref dir) = options.cwd {
+                if let Ok(false) = connection.is_directory(Path::new(dir)).await {
+                    return Err(chdir_not_found(dir));
+                }
+            }
+
             // Execute via connection
             // Note: The connection.execute() runs through a shell anyway,
             // but we wrap with explicit shell call for consistency and to
@@ -333,10 +331,10 @@ impl ShellModule {
                 .execute(&shell_cmd, Some(options))
                 .await
                 .map_err(|e| {
-                    ModuleError::ExecutionFailed(format!(
-                        "Failed to execute shell command '{}': {}",
-                        cmd, e
-                    ))
+                    ModuleError::from_connection(
+                        format_args!("Failed to execute shell command '{}'", cmd),
+                        e,
+                    )
                 })?;
 
             if result.success {
@@ -392,11 +390,16 @@ impl Module for ShellModule {
         context: &ModuleContext,
     ) -> ModuleResult<ModuleOutput> {
         // Dispatch to local or remote execution based on connection
-        if let Some(ref connection) = context.connection {
-            self.execute_remote(params, context, connection.clone())
+        let mut output = if let Some(ref connection) = context.connection {
+            self.execute_remote(params, context, connection.clone())?
         } else {
-            self.execute_local(params, context)
-        }
+            self.execute_local(params, context)?
+        };
+        output.warnings.extend(command_warnings(
+            &params.get_string_required("cmd")?,
+            params,
+        ));
+        Ok(output)
     }
 
     fn check(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<ModuleOutput> {
@@ -509,4 +512,50 @@ mod tests {
         assert!(result.changed);
         assert!(result.stdout.as_ref().unwrap().contains("hello from stdin"));
     }
+
+    #[test]
+    fn test_shell_chdir() {
+        let module = ShellModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("cmd".to_string(), serde_json::json!("pwd"));
+        params.insert("chdir".to_string(), serde_json::json!("/tmp"));
+
+        let context = ModuleContext::default();
+        let result = module.execute(&params, &context).unwrap();
+
+        assert_eq!(result.stdout.as_deref().map(str::trim), Some("/tmp"));
+    }
+
+    #[test]
+    fn test_shell_work_dir_default() {
+        let module = ShellModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("cmd".to_string(), serde_json::json!("pwd"));
+
+        let context = ModuleContext::default().with_work_dir("/tmp");
+        let result = module.execute(&params, &context).unwrap();
+        assert_eq!(result.stdout.as_deref().map(str::trim), Some("/tmp"));
+
+        // An explicit chdir overrides work_dir
+        params.insert("chdir".to_string(), serde_json::json!("/"));
+        let result = module.execute(&params, &context).unwrap();
+        assert_eq!(result.stdout.as_deref().map(str::trim), Some("/"));
+    }
+
+    #[test]
+    fn test_shell_chdir_missing() {
+        let module = ShellModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("cmd".to_string(), serde_json::json!("pwd"));
+        params.insert(
+            "chdir".to_string(),
+            serde_json::json!("/nonexistent/rustible-chdir"),
+        );
+
+        let context = ModuleContext::default();
+        let err = module.execute(&params, &context).unwrap_err();
+
+        assert!(matches!(err, ModuleError::InvalidParameter(_)));
+        assert!(err.to_string().contains("/nonexistent/rustible-chdir"));
+    }
 }
@@ -438,16 +438,7 @@ pub struct SystemdUnitModule;
 impl SystemdUnitModule {
     /// Build execute options with privilege escalation if needed
     fn build_execute_options(context: &ModuleContext) -> Option<ExecuteOptions> {
-        if context.r#become {
-            Some(ExecuteOptions {
-                escalate: true,
-                escalate_user: context.become_user.clone(),
-                escalate_method: context.become_method.clone(),
-                ..Default::default()
-            })
-        } else {
-            None
-        }
+        context.r#become.then(|| context.execute_options())
     }
 
     /// Render template content with context variables
@@ -604,10 +595,7 @@ impl SystemdUnitModule {
                     let options = Self::build_execute_options(context);
                     let mkdir_cmd = format!("mkdir -p '{}'", config.unit_path);
                     connection.execute(&mkdir_cmd, options).await.map_err(|e| {
-                        ModuleError::ExecutionFailed(format!(
-                            "Failed to create unit directory: {}",
-                            e
-                        ))
+                        ModuleError::from_connection("Failed to create unit directory", e)
                     })?;
                 }
 
@@ -624,9 +612,7 @@ impl SystemdUnitModule {
                 connection
                     .upload_content(desired_content.as_bytes(), unit_path, Some(transfer_opts))
                     .await
-                    .map_err(|e| {
-                        ModuleError::ExecutionFailed(format!("Failed to write unit file: {}", e))
-                    })?;
+                    .map_err(|e| ModuleError::from_connection("Failed to write unit file", e))?;
 
                 if current_content.is_some() {
                     messages.push(format!("Updated unit file '{}'", unit_file_path));
@@ -879,9 +865,10 @@ impl SystemdUnitModule {
         } else {
             let options = Self::build_execute_options(context);
             let rm_cmd = format!("rm -f '{}'", unit_file_path);
-            connection.execute(&rm_cmd, options).await.map_err(|e| {
-                ModuleError::ExecutionFailed(format!("Failed to remove unit file: {}", e))
-            })?;
+            connection
+                .execute(&rm_cmd, options)
+                .await
+                .map_err(|e| ModuleError::from_connection("Failed to remove unit file", e))?;
             messages.push(format!("Removed unit file '{}'", unit_file_path));
             changed = true;
         }
@@ -967,7 +954,7 @@ impl SystemdUnitModule {
         let result = connection
             .execute("systemctl daemon-reload", options)
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("Failed to reload daemon: {}", e)))?;
+            .map_err(|e| ModuleError::from_connection("Failed to reload daemon", e))?;
 
         if !result.success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -989,9 +976,7 @@ impl SystemdUnitModule {
         let result = connection
             .execute("systemctl daemon-reexec", options)
             .await
-            .map_err(|e| {
-                ModuleError::ExecutionFailed(format!("Failed to re-execute daemon: {}", e))
-            })?;
+            .map_err(|e| ModuleError::from_connection("Failed to re-execute daemon", e))?;
 
         if !result.success {
             return Err(ModuleError::ExecutionFailed(format!(
@@ -2,20 +2,34 @@
 //!
 //! This module renders Jinja2 templates and copies the result
 //! to a destination file. Supports both local and remote execution via async connections.
+//! On remote hosts where SELinux is enforcing, the destination's context is
+//! restored after writing, or set from `seuser`/`serole`/`setype`/`selevel`.
+//! Remote uploads report their size and timing in `data.transfer_stats`.
+//! With `validate`, the command (`%s` stands for the file) runs against the
+//! rendered content before the destination is replaced.
+//!
+//! Templates can call `lookup('<plugin>', ...)`; within one render, repeated
+//! lookups with the same arguments are answered from a cache, so a file is
+//! read (or a command run) only once.
 
+use super::file::{RemoteOwnership, SelinuxContext};
 use super::{
-    Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
-    ModuleResult, ParamExt,
+    resolve_first_found, validate_local_content, validate_remote_content, Diff, Module,
+    ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams, ModuleResult,
+    ParamExt,
 };
-use crate::connection::TransferOptions;
-use minijinja::value::Kwargs;
-use minijinja::{Environment, Error, Value};
+use crate::connection::{atomic, TransferOptions, TransferStats};
+use crate::lookup::{LookupCache, LookupContext, LookupRegistry};
+use minijinja::value::{Kwargs, Rest};
+use minijinja::{Environment, Error, ErrorKind, Value};
 use once_cell::sync::Lazy;
 use std::fs;
 use std::io::Read;
 #[cfg(unix)]
 use std::os::unix::fs::{MetadataExt, PermissionsExt};
 use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
 use tokio::runtime::Handle;
 
 /// Escape a string for use in shell commands
@@ -30,6 +44,42 @@ fn shell_escape(s: &str) -> String {
     format!("'{}'", s.replace('\'', "'\\''"))
 }
 
+/// Describe a render failure with the template name and where it happened
+///
+/// Names the line and column from the error's span and quotes the offending
+/// source, e.g. `undefined variable in 'app.conf.j2' at line 3, column 8
+/// (`{{ db.host }}`): ...`.
+fn describe_render_error(name: &str, source: &str, err: &Error) -> String {
+    let what = match err.kind() {
+        ErrorKind::SyntaxError => "syntax error",
+        ErrorKind::UndefinedError => "undefined variable",
+        _ => "render error",
+    };
+
+    let mut location = format!("'{}'", err.name().unwrap_or(name));
+    let span = err
+        .range()
+        .and_then(|range| source.get(range.clone()).map(|text| (range.start, text)));
+    match (err.line(), span) {
+        (Some(line), Some((start, text))) => {
+            let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+            let column = source[line_start..start].chars().count() + 1;
+            location.push_str(&format!(" at line {}, column {}", line, column));
+            let text = text.trim();
+            if !text.is_empty() && !text.contains('\n') {
+                location.push_str(&format!(" (`{}`)", text));
+            }
+        }
+        (Some(line), None) => location.push_str(&format!(" at line {}", line)),
+        _ => {}
+    }
+
+    let detail = err
+        .detail()
+        .map_or_else(|| err.kind().to_string(), String::from);
+    format!("{} in {}: {}", what, location, detail)
+}
+
 /// Global Minijinja environment with pre-registered filters
 static TEMPLATE_ENV: Lazy<Environment<'static>> = Lazy::new(|| {
     let mut env = Environment::new();
@@ -92,6 +142,33 @@ static TEMPLATE_ENV: Lazy<Environment<'static>> = Lazy::new(|| {
     env
 });
 
+/// Lookup plugins available to templates
+static LOOKUPS: Lazy<Arc<LookupRegistry>> = Lazy::new(|| Arc::new(LookupRegistry::with_builtins()));
+
+/// `lookup()` for templates, answered from `lookups`
+///
+/// Keyword arguments are passed to the plugin as `key=value` terms. Like
+/// Ansible, multiple results are joined with commas.
+fn lookup_function(lookups: Arc<LookupCache>) -> Value {
+    Value::from_function(
+        move |plugin: String, terms: Rest<Value>, kwargs: Kwargs| -> Result<Value, Error> {
+            let mut args: Vec<String> = terms.iter().map(|t| t.to_string()).collect();
+            for key in kwargs.args() {
+                let value: Value = kwargs.get(key)?;
+                args.push(format!("{}={}", key, value));
+            }
+
+            let values = lookups.lookup(&plugin, &args).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidOperation,
+                    format!("lookup('{}') failed: {}", plugin, e),
+                )
+            })?;
+            Ok(Value::from(values.join(",")))
+        },
+    )
+}
+
 /// Module for rendering templates
 pub struct TemplateModule;
 
@@ -126,15 +203,33 @@ impl TemplateModule {
         serde_json::Value::Object(ctx_map)
     }
 
+    /// Fresh lookup cache for one render, resolving relative paths against
+    /// the work dir
+    fn lookup_cache(context: &ModuleContext) -> Arc<LookupCache> {
+        let mut lookup_context = LookupContext::new();
+        if let Some(ref work_dir) = context.work_dir {
+            lookup_context = lookup_context.with_base_dir(work_dir);
+        }
+        Arc::new(LookupCache::new(LOOKUPS.clone(), lookup_context))
+    }
+
     fn render_template(
+        name: &str,
         template_content: &str,
         context: &serde_json::Value,
+        lookups: Arc<LookupCache>,
     ) -> ModuleResult<String> {
+        let context = minijinja::context! {
+            lookup => lookup_function(lookups),
+            ..Value::from_serialize(context)
+        };
         // Use the shared environment without cloning it
         // minijinja::Environment is thread-safe and designed to be shared
         TEMPLATE_ENV
-            .render_str(template_content, context)
-            .map_err(|e| ModuleError::TemplateError(format!("Failed to render template: {}", e)))
+            .render_named_str(name, template_content, context)
+            .map_err(|e| {
+                ModuleError::TemplateError(describe_render_error(name, template_content, &e))
+            })
     }
 
     #[allow(dead_code)]
@@ -179,10 +274,24 @@ impl TemplateModule {
         Ok(false)
     }
 
+    #[cfg(unix)]
+    fn mode_differs(path: &Path, mode: Option<u32>) -> ModuleResult<bool> {
+        match (mode, fs::metadata(path)) {
+            (Some(mode), Ok(meta)) => Ok(meta.permissions().mode() & 0o7777 != mode),
+            (Some(_), Err(_)) => Ok(true),
+            (None, _) => Ok(false),
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn mode_differs(_path: &Path, _mode: Option<u32>) -> ModuleResult<bool> {
+        Ok(false)
+    }
+
     /// Execute template rendering locally (when no connection is present)
     #[allow(clippy::too_many_arguments)]
     fn execute_local(
-        _params: &ModuleParams,
+        params: &ModuleParams,
         context: &ModuleContext,
         rendered: &str,
         src_name: &str,
@@ -195,12 +304,12 @@ impl TemplateModule {
         let dest = dest_path.to_string_lossy();
 
         // Check if dest needs updating
-        let needs_update = if dest_path.exists() {
-            let current_content = fs::read_to_string(dest_path)?;
-            current_content != rendered
+        let current_content = if dest_path.exists() {
+            Some(fs::read_to_string(dest_path)?)
         } else {
-            true
+            None
         };
+        let needs_update = current_content.as_deref() != Some(rendered);
 
         if !needs_update {
             // Check if only permissions need updating
@@ -241,18 +350,20 @@ impl TemplateModule {
         // In check mode, return what would happen
         if context.check_mode {
             let diff = if context.diff_mode {
-                let before = if dest_path.exists() {
-                    fs::read_to_string(dest_path).unwrap_or_default()
-                } else {
-                    String::new()
-                };
-                Some(Diff::new(before, rendered.to_string()))
+                Some(Diff::for_file(
+                    dest_path,
+                    current_content.as_deref(),
+                    rendered,
+                ))
             } else {
                 None
             };
 
-            let mut output =
-                ModuleOutput::changed(format!("Would render template '{}' to '{}'", src, dest));
+            let mut output = ModuleOutput::changed(if current_content.is_some() {
+                format!("Would render template '{}' to '{}'", src, dest)
+            } else {
+                format!("Would create '{}' from template '{}'", dest, src)
+            });
 
             if let Some(d) = diff {
                 output = output.with_diff(d);
@@ -261,6 +372,10 @@ impl TemplateModule {
             return Ok(output);
         }
 
+        if let Some(validate) = params.get_string("validate")? {
+            validate_local_content(&validate, rendered.as_bytes())?;
+        }
+
         // Create backup if requested
         let backup_file = if backup {
             Self::create_backup(dest_path, backup_suffix)?
@@ -275,19 +390,29 @@ impl TemplateModule {
             }
         }
 
-        // Write rendered content
-        fs::write(dest_path, rendered)?;
-
-        // Set permissions
-        let perm_changed = Self::set_permissions(dest_path, mode)?;
-
-        let mut output =
-            ModuleOutput::changed(format!("Rendered template '{}' to '{}'", src, dest));
+        // Write rendered content, applying the mode before it is renamed into place
+        let perm_changed = Self::mode_differs(dest_path, mode)?;
+        let warning = atomic::write_file_with_fallback(
+            dest_path,
+            rendered.as_bytes(),
+            mode,
+            params.get_bool_or("unsafe_writes", false),
+        )?;
+
+        let mut output = ModuleOutput::changed(if current_content.is_some() {
+            format!("Rendered template '{}' to '{}'", src, dest)
+        } else {
+            format!("Created '{}' from template '{}'", dest, src)
+        });
 
         if let Some(backup_path) = backup_file {
             output = output.with_data("backup_file", serde_json::json!(backup_path));
         }
 
+        if let Some(warning) = warning {
+            output = output.with_warning(warning);
+        }
+
         if perm_changed {
             output = output.with_data("mode_changed", serde_json::json!(true));
         }
@@ -337,6 +462,9 @@ impl Module for TemplateModule {
         params: &ModuleParams,
         context: &ModuleContext,
     ) -> ModuleResult<ModuleOutput> {
+        // A list of sources picks the first candidate that exists
+        let resolved = resolve_first_found(params, context, "templates")?;
+        let params = resolved.as_ref();
         let src = params.get_string("src")?;
         let content = params.get_string("content")?;
         let dest = params.get_string_required("dest")?;
@@ -346,6 +474,11 @@ impl Module for TemplateModule {
             .get_string("backup_suffix")?
             .unwrap_or_else(|| "~".to_string());
         let mode = params.get_u32("mode")?;
+        let owner = params.get_string("owner")?;
+        let group = params.get_string("group")?;
+        let force = params.get_bool_or("force", true);
+        let unsafe_writes = params.get_bool_or("unsafe_writes", false);
+        let validate = params.get_string("validate")?;
         let extra_vars = params.get("vars");
 
         // Get template content from either src file or content parameter
@@ -370,9 +503,36 @@ impl Module for TemplateModule {
         };
         let _src_path = Path::new(&src_name);
 
+        // With force: false an existing destination is never rewritten
+        if !force {
+            let dest_exists = match context.connection {
+                Some(ref conn) => {
+                    let handle = Handle::try_current().map_err(|e| {
+                        ModuleError::ExecutionFailed(format!("No tokio runtime available: {}", e))
+                    })?;
+                    handle
+                        .block_on(conn.path_exists(dest_path))
+                        .unwrap_or(false)
+                }
+                None => dest_path.exists(),
+            };
+            if dest_exists {
+                return Ok(ModuleOutput::ok(format!(
+                    "File '{}' already exists and force is false",
+                    dest
+                ))
+                .with_data("dest", serde_json::json!(dest)));
+            }
+        }
+
         // Build context and render
         let ctx = Self::build_context(context, extra_vars);
-        let rendered = Self::render_template(&template_content, &ctx)?;
+        let rendered = Self::render_template(
+            &src_name,
+            &template_content,
+            &ctx,
+            Self::lookup_cache(context),
+        )?;
 
         // Check if we have a connection for remote execution
         if let Some(ref conn) = context.connection {
@@ -381,6 +541,13 @@ impl Module for TemplateModule {
                 ModuleError::ExecutionFailed(format!("No tokio runtime available: {}", e))
             })?;
 
+            // Fail on unknown users or groups before touching the destination
+            let ownership = handle.block_on(RemoteOwnership::resolve(
+                conn.as_ref(),
+                owner.as_deref(),
+                group.as_deref(),
+            ))?;
+
             // Get current content from remote to check if update is needed
             let current_content = handle.block_on(async {
                 if conn.path_exists(dest_path).await.unwrap_or(false) {
@@ -423,10 +590,7 @@ impl Module for TemplateModule {
                     handle
                         .block_on(async { conn.execute(&chmod_cmd, None).await })
                         .map_err(|e| {
-                            ModuleError::ExecutionFailed(format!(
-                                "Failed to set permissions: {}",
-                                e
-                            ))
+                            ModuleError::from_connection("Failed to set permissions", e)
                         })?;
                     return Ok(ModuleOutput::changed(format!(
                         "Changed permissions on '{}'",
@@ -443,16 +607,20 @@ impl Module for TemplateModule {
             // In check mode, return what would happen
             if context.check_mode {
                 let diff = if context.diff_mode {
-                    let before = current_content.unwrap_or_default();
-                    Some(Diff::new(before, rendered.clone()))
+                    Some(Diff::for_file(
+                        dest_path,
+                        current_content.as_deref(),
+                        &rendered,
+                    ))
                 } else {
                     None
                 };
 
-                let mut output = ModuleOutput::changed(format!(
-                    "Would render template '{}' to '{}'",
-                    src_name, dest
-                ));
+                let mut output = ModuleOutput::changed(if current_content.is_some() {
+                    format!("Would render template '{}' to '{}'", src_name, dest)
+                } else {
+                    format!("Would create '{}' from template '{}'", dest, src_name)
+                });
 
                 if let Some(d) = diff {
                     output = output.with_diff(d);
@@ -461,45 +629,86 @@ impl Module for TemplateModule {
                 return Ok(output);
             }
 
+            if let Some(ref validate) = validate {
+                handle.block_on(validate_remote_content(
+                    conn.as_ref(),
+                    validate,
+                    rendered.as_bytes(),
+                ))?;
+            }
+
             // Create backup if requested (via remote command)
             let backup_file = if backup && current_content.is_some() {
                 let backup_path = format!("{}{}", dest, backup_suffix);
                 let cp_cmd = format!("cp {} {}", shell_escape(&dest), shell_escape(&backup_path));
                 handle
                     .block_on(async { conn.execute(&cp_cmd, None).await })
-                    .map_err(|e| {
-                        ModuleError::ExecutionFailed(format!("Failed to create backup: {}", e))
-                    })?;
+                    .map_err(|e| ModuleError::from_connection("Failed to create backup", e))?;
                 Some(backup_path)
             } else {
                 None
             };
 
             // Build transfer options
-            let transfer_opts = TransferOptions {
+            let transfer_opts = ownership.apply(TransferOptions {
                 mode,
                 create_dirs: true,
                 backup: false, // We already handled backup above
                 ..Default::default()
+            });
+
+            // Remember the context of the file being replaced
+            let selinux = SelinuxContext::from_params(params)?;
+            let selinux = SelinuxContext::is_enforcing(context).then_some(&selinux);
+            let previous_context = match selinux {
+                Some(_) if current_content.is_some() => {
+                    handle.block_on(SelinuxContext::remote_context(conn.as_ref(), dest_path))
+                }
+                _ => None,
             };
 
             // Upload rendered content to remote
-            handle
+            let start = Instant::now();
+            let warning = handle
                 .block_on(async {
-                    conn.upload_content(rendered.as_bytes(), dest_path, Some(transfer_opts))
-                        .await
+                    atomic::upload_content_with_fallback(
+                        conn.as_ref(),
+                        rendered.as_bytes(),
+                        dest_path,
+                        Some(transfer_opts),
+                        unsafe_writes,
+                    )
+                    .await
                 })
                 .map_err(|e| {
                     ModuleError::ExecutionFailed(format!("Failed to upload template: {}", e))
                 })?;
+            let transfer_stats = TransferStats::since(rendered.len() as u64, start);
+            transfer_stats.log("Upload", dest_path);
+
+            if let Some(selinux) = selinux {
+                handle.block_on(selinux.apply_remote(
+                    conn.as_ref(),
+                    dest_path,
+                    previous_context.as_deref(),
+                ))?;
+            }
 
-            let mut output =
-                ModuleOutput::changed(format!("Rendered template '{}' to '{}'", src_name, dest));
+            let mut output = ModuleOutput::changed(if current_content.is_some() {
+                format!("Rendered template '{}' to '{}'", src_name, dest)
+            } else {
+                format!("Created '{}' from template '{}'", dest, src_name)
+            });
 
             if let Some(backup_path) = backup_file {
                 output = output.with_data("backup_file", serde_json::json!(backup_path));
             }
 
+            if let Some(warning) = warning {
+                output = output.with_warning(warning);
+            }
+            output = output.with_data("transfer_stats", transfer_stats.to_json());
+
             // Get file info from remote
             if let Ok(stat) = handle.block_on(async { conn.stat(dest_path).await }) {
                 output = output
@@ -539,6 +748,9 @@ impl Module for TemplateModule {
     }
 
     fn diff(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<Option<Diff>> {
+        // A list of sources picks the first candidate that exists
+        let resolved = resolve_first_found(params, context, "templates")?;
+        let params = resolved.as_ref();
         let src = params.get_string("src")?;
         let content = params.get_string("content")?;
         let dest = params.get_string_required("dest")?;
@@ -565,7 +777,9 @@ impl Module for TemplateModule {
             }
         };
         let ctx = Self::build_context(context, extra_vars);
-        let rendered = Self::render_template(&template_content, &ctx)?;
+        let name = src.as_deref().unwrap_or("<inline>");
+        let rendered =
+            Self::render_template(name, &template_content, &ctx, Self::lookup_cache(context))?;
 
         // Check if we have a connection for remote diff
         if let Some(ref conn) = context.connection {
@@ -575,26 +789,36 @@ impl Module for TemplateModule {
 
             let before = handle.block_on(async {
                 if conn.path_exists(dest_path).await.unwrap_or(false) {
-                    conn.download_content(dest_path)
-                        .await
-                        .ok()
-                        .and_then(|bytes| String::from_utf8(bytes).ok())
-                        .unwrap_or_default()
+                    Some(
+                        conn.download_content(dest_path)
+                            .await
+                            .ok()
+                            .and_then(|bytes| String::from_utf8(bytes).ok())
+                            .unwrap_or_default(),
+                    )
                 } else {
-                    String::new()
+                    None
                 }
             });
 
-            Ok(Some(Diff::new(before, rendered)))
+            Ok(Some(Diff::for_file(
+                dest_path,
+                before.as_deref(),
+                &rendered,
+            )))
         } else {
             // Local diff
             let before = if dest_path.exists() {
-                fs::read_to_string(dest_path).unwrap_or_default()
+                Some(fs::read_to_string(dest_path).unwrap_or_default())
             } else {
-                String::new()
+                None
             };
 
-            Ok(Some(Diff::new(before, rendered)))
+            Ok(Some(Diff::for_file(
+                dest_path,
+                before.as_deref(),
+                &rendered,
+            )))
         }
     }
 }
@@ -605,6 +829,50 @@ mod tests {
     use std::collections::HashMap;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_repeated_lookup_reads_file_once() {
+        use crate::lookup::{FileLookup, Lookup, LookupResult};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// `file` lookup that counts how often it runs
+        struct CountingFile(Arc<AtomicUsize>);
+
+        impl Lookup for CountingFile {
+            fn name(&self) -> &'static str {
+                "file"
+            }
+
+            fn description(&self) -> &'static str {
+                "Counting file lookup"
+            }
+
+            fn lookup(&self, args: &[&str], context: &LookupContext) -> LookupResult<Vec<String>> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                FileLookup::new().lookup(args, context)
+            }
+        }
+
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("motd");
+        fs::write(&path, "welcome").unwrap();
+
+        let reads = Arc::new(AtomicUsize::new(0));
+        let mut registry = LookupRegistry::new();
+        registry.register(Arc::new(CountingFile(reads.clone())));
+        let lookups = Arc::new(LookupCache::new(Arc::new(registry), LookupContext::new()));
+
+        let rendered = TemplateModule::render_template(
+            "motd.j2",
+            "{{ lookup('file', path) }} / {{ lookup('file', path) | upper }}",
+            &serde_json::json!({ "path": path.to_str().unwrap() }),
+            lookups,
+        )
+        .unwrap();
+
+        assert_eq!(rendered, "welcome / WELCOME");
+        assert_eq!(reads.load(Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn test_template_basic() {
         let temp = TempDir::new().unwrap();
@@ -632,6 +900,111 @@ mod tests {
         assert_eq!(fs::read_to_string(&dest).unwrap(), "Hello, World!");
     }
 
+    #[test]
+    fn test_template_syntax_error_reports_location() {
+        let temp = TempDir::new().unwrap();
+        let src = temp.path().join("app.conf.j2");
+        let dest = temp.path().join("app.conf");
+        fs::write(&src, "port = 80\nhost = {{ host \n").unwrap();
+
+        let mut params: ModuleParams = HashMap::new();
+        params.insert("src".to_string(), serde_json::json!(src.to_str().unwrap()));
+        params.insert(
+            "dest".to_string(),
+            serde_json::json!(dest.to_str().unwrap()),
+        );
+
+        let err = TemplateModule
+            .execute(&params, &ModuleContext::default())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("syntax error"), "{}", err);
+        assert!(err.contains("app.conf.j2"), "{}", err);
+        assert!(err.contains("line 2"), "{}", err);
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_template_undefined_error_names_expression() {
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "content".to_string(),
+            serde_json::json!("a\nb = {{ db.host.name }}\n"),
+        );
+        params.insert("dest".to_string(), serde_json::json!("/tmp/unused"));
+
+        let err = TemplateModule
+            .execute(&params, &ModuleContext::default())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("undefined variable in '<inline>'"), "{}", err);
+        assert!(err.contains("line 2, column"), "{}", err);
+        assert!(err.contains("db.host"), "{}", err);
+    }
+
+    #[test]
+    fn test_template_failing_validate_leaves_dest_unchanged() {
+        let temp = TempDir::new().unwrap();
+        let dest = temp.path().join("app.conf");
+        fs::write(&dest, "port = 80\n").unwrap();
+
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "content".to_string(),
+            serde_json::json!("port = {{ port }}\n"),
+        );
+        params.insert(
+            "dest".to_string(),
+            serde_json::json!(dest.to_str().unwrap()),
+        );
+        params.insert(
+            "validate".to_string(),
+            serde_json::json!(
+                "grep -qE '^port = [0-9]+$' %s || { echo 'port must be numeric' >&2; false; }"
+            ),
+        );
+        let mut vars = HashMap::new();
+        vars.insert("port".to_string(), serde_json::json!("eighty"));
+        let context = ModuleContext::default().with_vars(vars);
+
+        let err = TemplateModule.execute(&params, &context).unwrap_err();
+
+        assert!(err.to_string().contains("port must be numeric"));
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "port = 80\n");
+    }
+
+    #[test]
+    fn test_template_force_false_only_creates_missing_dest() {
+        let temp = TempDir::new().unwrap();
+        let src = temp.path().join("app.conf.j2");
+        let existing = temp.path().join("existing.conf");
+        let missing = temp.path().join("missing.conf");
+        fs::write(&src, "port={{ port }}").unwrap();
+        fs::write(&existing, "port=1").unwrap();
+
+        let module = TemplateModule;
+        let mut vars = HashMap::new();
+        vars.insert("port".to_string(), serde_json::json!(8080));
+        let context = ModuleContext::default().with_vars(vars);
+
+        for dest in [&existing, &missing] {
+            let mut params: ModuleParams = HashMap::new();
+            params.insert("src".to_string(), serde_json::json!(src.to_str().unwrap()));
+            params.insert(
+                "dest".to_string(),
+                serde_json::json!(dest.to_str().unwrap()),
+            );
+            params.insert("force".to_string(), serde_json::json!(false));
+
+            let result = module.execute(&params, &context).unwrap();
+            assert_eq!(result.changed, dest == &missing);
+        }
+
+        // The existing file is left alone even though the rendered content differs
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "port=1");
+        assert_eq!(fs::read_to_string(&missing).unwrap(), "port=8080");
+    }
+
     #[test]
     fn test_template_with_loops() {
         let temp = TempDir::new().unwrap();
@@ -742,10 +1115,62 @@ mod tests {
         let result = module.check(&params, &context).unwrap();
 
         assert!(result.changed);
-        assert!(result.msg.contains("Would render"));
+        assert!(result.msg.contains("Would create"));
         assert!(!dest.exists()); // File should not be created in check mode
     }
 
+    #[test]
+    fn test_template_new_file_diff_shows_additions() {
+        let temp = TempDir::new().unwrap();
+        let dest = temp.path().join("conf").join("app.conf");
+
+        let module = TemplateModule;
+        let mut params: ModuleParams = HashMap::new();
+        params.insert(
+            "content".to_string(),
+            serde_json::json!("port={{ port }}\nhost=localhost\n"),
+        );
+        params.insert(
+            "dest".to_string(),
+            serde_json::json!(dest.to_str().unwrap()),
+        );
+
+        let mut vars = HashMap::new();
+        vars.insert("port".to_string(), serde_json::json!(8080));
+
+        let context = ModuleContext::default()
+            .with_vars(vars)
+            .with_check_mode(true)
+            .with_diff_mode(true);
+        let result = module.check(&params, &context).unwrap();
+
+        assert!(result.changed);
+        assert!(result.msg.starts_with("Would create"), "{}", result.msg);
+        let diff = result.diff.unwrap();
+        assert_eq!(diff.before, "");
+        let details = diff.details.unwrap();
+        assert!(details.starts_with("--- /dev/null\n"), "{}", details);
+        assert!(details.contains("@@ -0,0 +1,2 @@"), "{}", details);
+        assert!(
+            details.contains("\n+port=8080\n+host=localhost\n"),
+            "{}",
+            details
+        );
+        assert!(!dest.exists());
+
+        // Applying the template reports the file as created, then as rendered
+        let context = ModuleContext::default().with_vars(context.vars.clone());
+        let result = module.execute(&params, &context).unwrap();
+        assert!(result.msg.starts_with("Created"), "{}", result.msg);
+        fs::write(&dest, "port=80\n").unwrap();
+        let result = module.execute(&params, &context).unwrap();
+        assert!(
+            result.msg.starts_with("Rendered template"),
+            "{}",
+            result.msg
+        );
+    }
+
     #[test]
     fn test_template_filters() {
         let temp = TempDir::new().unwrap();
@@ -49,14 +49,7 @@ pub struct UserModule;
 impl UserModule {
     /// Get execution options with become support if needed
     fn get_exec_options(context: &ModuleContext) -> ExecuteOptions {
-        let mut options = ExecuteOptions::new();
-        if context.r#become {
-            options = options.with_escalation(context.become_user.clone());
-            if let Some(ref method) = context.become_method {
-                options.escalate_method = Some(method.clone());
-            }
-        }
-        options
+        context.execute_options()
     }
 
     /// Execute a command via connection or locally
@@ -79,7 +72,7 @@ impl UserModule {
                 .join()
                 .unwrap()
         })
-        .map_err(|e| ModuleError::ExecutionFailed(format!("Connection error: {}", e)))?;
+        .map_err(ModuleError::from)?;
 
         Ok((result.success, result.stdout, result.stderr))
     }
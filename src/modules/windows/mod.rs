@@ -222,7 +222,7 @@ pub async fn execute_powershell(
     let result = connection
         .execute(&command, None)
         .await
-        .map_err(|e| ModuleError::ExecutionFailed(format!("PowerShell execution failed: {}", e)))?;
+        .map_err(|e| ModuleError::from_connection("PowerShell execution failed", e))?;
 
     Ok((result.success, result.stdout, result.stderr))
 }
@@ -12,6 +12,7 @@
 //! - Alternate installation roots
 //! - Release version specification
 
+use super::package::{group_listed, package_group_name, with_package_delta};
 use super::{
     Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
     ModuleResult, ParallelizationHint, ParamExt,
@@ -174,7 +175,7 @@ impl YumModule {
         let result = conn
             .execute(&cmd, options)
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("Failed to execute yum: {}", e)))?;
+            .map_err(|e| ModuleError::from_connection("Failed to execute yum", e))?;
 
         Ok((result.success, result.stdout, result.stderr))
     }
@@ -185,15 +186,11 @@ impl YumModule {
         group: &str,
         options: Option<ExecuteOptions>,
     ) -> ModuleResult<bool> {
-        // Remove @ prefix for group check
-        let group_name = group.trim_start_matches('@');
-        let cmd = format!(
-            "yum group list installed 2>/dev/null | grep -qi {}",
-            shell_escape(group_name)
-        );
-        match conn.execute(&cmd, options).await {
-            Ok(result) => Ok(result.success),
-            Err(_) => Ok(false),
+        let group_name = package_group_name(group)?;
+        let cmd = "yum -v grouplist installed 2>/dev/null";
+        match conn.execute(cmd, options).await {
+            Ok(result) if result.success => Ok(group_listed(&result.stdout, group_name)),
+            _ => Ok(false),
         }
     }
 
@@ -229,7 +226,7 @@ impl YumModule {
         let result = conn
             .execute(cmd, options)
             .await
-            .map_err(|e| ModuleError::ExecutionFailed(format!("Failed to update cache: {}", e)))?;
+            .map_err(|e| ModuleError::from_connection("Failed to update cache", e))?;
 
         if result.success {
             Ok(())
@@ -243,16 +240,7 @@ impl YumModule {
 
     /// Build execution options with become/sudo if needed
     fn build_exec_options(context: &ModuleContext) -> ExecuteOptions {
-        let mut options = ExecuteOptions::new();
-
-        if context.r#become {
-            options.escalate = true;
-            options.escalate_user = context
-                .become_user
-                .clone()
-                .or_else(|| Some("root".to_string()));
-            options.escalate_method = context.become_method.clone();
-        }
+        let mut options = context.execute_options();
 
         if let Some(ref work_dir) = context.work_dir {
             options = options.with_cwd(work_dir);
@@ -296,6 +284,11 @@ impl Module for YumModule {
             vec![params.get_string_required("name")?]
         };
 
+        // Reject malformed `@group` names before touching the host
+        for group in packages.iter().filter(|p| Self::is_package_group(p)) {
+            package_group_name(group)?;
+        }
+
         let state_str = params
             .get_string("state")?
             .unwrap_or_else(|| "present".to_string());
@@ -349,6 +342,7 @@ impl Module for YumModule {
                 let mut to_install: Vec<String> = Vec::new();
                 let mut to_remove: Vec<String> = Vec::new();
                 let mut already_ok: Vec<String> = Vec::new();
+                let mut installed: Vec<String> = Vec::new();
 
                 // Check current state of packages
                 for package in &packages_to_check {
@@ -358,6 +352,9 @@ impl Module for YumModule {
                         Some(exec_options.clone()),
                     )
                     .await?;
+                    if is_installed {
+                        installed.push(package.clone());
+                    }
 
                     match state {
                         YumState::Present => {
@@ -389,6 +386,9 @@ impl Module for YumModule {
                         Some(exec_options.clone()),
                     )
                     .await?;
+                    if is_installed {
+                        installed.push(group.clone());
+                    }
 
                     match state {
                         YumState::Present => {
@@ -429,7 +429,13 @@ impl Module for YumModule {
                         msg.push_str(&format!("Would remove: {}. ", to_remove.join(", ")));
                     }
 
-                    return Ok(ModuleOutput::changed(msg.trim().to_string()));
+                    return Ok(with_package_delta(
+                        ModuleOutput::changed(msg.trim().to_string()),
+                        &installed,
+                        &to_install,
+                        &to_remove,
+                        &[],
+                    ));
                 }
 
                 // Perform the actual operations
@@ -471,10 +477,14 @@ impl Module for YumModule {
                         let group_args =
                             Self::build_yum_args(&["groupinstall", "-y"], &yum_options);
                         let groups_owned: Vec<String> = groups.into_iter().cloned().collect();
+                        let group_names = groups_owned
+                            .iter()
+                            .map(|grp| package_group_name(grp).map(str::to_string))
+                            .collect::<ModuleResult<Vec<_>>>()?;
                         let (success, stdout, stderr) = Self::run_yum_command_remote(
                             conn.as_ref(),
                             &group_args,
-                            &groups_owned,
+                            &group_names,
                             Some(exec_options.clone()),
                         )
                         .await?;
@@ -527,10 +537,14 @@ impl Module for YumModule {
                     if !groups.is_empty() {
                         let group_args = Self::build_yum_args(&["groupremove", "-y"], &yum_options);
                         let groups_owned: Vec<String> = groups.into_iter().cloned().collect();
+                        let group_names = groups_owned
+                            .iter()
+                            .map(|grp| package_group_name(grp).map(str::to_string))
+                            .collect::<ModuleResult<Vec<_>>>()?;
                         let (success, stdout, stderr) = Self::run_yum_command_remote(
                             conn.as_ref(),
                             &group_args,
-                            &groups_owned,
+                            &group_names,
                             Some(exec_options.clone()),
                         )
                         .await?;
@@ -0,0 +1,584 @@
+//! Yum repository module - Manage YUM/DNF repository files
+//!
+//! This module writes repository definitions to `.repo` files under
+//! `/etc/yum.repos.d`. Each repository is an INI section; other sections in
+//! the same file are left untouched.
+//!
+//! ## Parameters
+//!
+//! - `name`: Repository id, used as the section name (required)
+//! - `description`: Human-readable name (default: the repository id)
+//! - `baseurl`: Repository base URL (one of baseurl, mirrorlist, metalink is required when present)
+//! - `mirrorlist`: URL of a mirror list
+//! - `metalink`: URL of a metalink document
+//! - `gpgkey`: URL (or list of URLs) of signing keys
+//! - `gpgcheck`: Verify package signatures
+//! - `enabled`: Whether the repository is enabled (default: true)
+//! - `file`: Name of the `.repo` file without extension (default: the repository id)
+//! - `reposdir`: Directory holding repository files (default: /etc/yum.repos.d)
+//! - `state`: Desired state (present, absent)
+//! - `mode`: File permissions for the `.repo` file (default: 0644)
+//!
+//! Values are written verbatim into the INI file, so they are rejected if
+//! they contain line breaks or other control characters that could smuggle
+//! extra keys or sections into it.
+
+use super::apt_repository::RepositoryState;
+use super::{
+    Diff, Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
+    ModuleResult, ParamExt,
+};
+use crate::connection::{Connection, ExecuteOptions, TransferOptions};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Regex for repository ids and file names
+static REPO_ID_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z0-9_.:-]+$").expect("Invalid repo id regex"));
+
+/// Regex for repository and key URLs
+static URL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(https?|ftp|file)://[A-Za-z0-9_.~/:@%+=,?&$-]+$").expect("Invalid URL regex")
+});
+
+/// Regex for the repository directory
+static DIR_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^/[A-Za-z0-9_./-]*$").expect("Invalid directory regex"));
+
+/// Validated module parameters
+#[derive(Debug, Clone)]
+struct YumRepositoryConfig {
+    id: String,
+    state: RepositoryState,
+    /// Ordered `key=value` settings of the section
+    settings: Vec<(&'static str, String)>,
+    file: String,
+    reposdir: String,
+    mode: u32,
+}
+
+impl YumRepositoryConfig {
+    fn from_params(params: &ModuleParams) -> ModuleResult<Self> {
+        let id = params.get_string_required("name")?;
+        if !REPO_ID_REGEX.is_match(&id) {
+            return Err(ModuleError::InvalidParameter(format!(
+                "Invalid repository name '{}': must contain only alphanumeric characters, underscores, dots, colons, and dashes",
+                id
+            )));
+        }
+
+        let state = RepositoryState::from_str(
+            &params
+                .get_string("state")?
+                .unwrap_or_else(|| "present".to_string()),
+        )?;
+
+        let file = params
+            .get_string("file")?
+            .map(|f| f.trim_end_matches(".repo").to_string())
+            .unwrap_or_else(|| id.clone());
+        if !REPO_ID_REGEX.is_match(&file) {
+            return Err(ModuleError::InvalidParameter(format!(
+                "Invalid file '{}': must contain only alphanumeric characters, underscores, dots, colons, and dashes",
+                file
+            )));
+        }
+
+        let reposdir = params
+            .get_string("reposdir")?
+            .unwrap_or_else(|| "/etc/yum.repos.d".to_string());
+        if !DIR_REGEX.is_match(&reposdir) {
+            return Err(ModuleError::InvalidParameter(format!(
+                "Invalid reposdir '{}': must be an absolute path",
+                reposdir
+            )));
+        }
+
+        let mut settings = Vec::new();
+
+        let description = params
+            .get_string("description")?
+            .unwrap_or_else(|| id.clone());
+        if description.chars().any(|c| c.is_control()) {
+            return Err(ModuleError::InvalidParameter(
+                "description must be a single line".to_string(),
+            ));
+        }
+        settings.push(("name", description));
+
+        for key in ["baseurl", "metalink", "mirrorlist"] {
+            if let Some(url) = params.get_string(key)? {
+                settings.push((key, Self::validate_url(key, &url)?));
+            }
+        }
+        if state == RepositoryState::Present && settings.len() == 1 {
+            return Err(ModuleError::MissingParameter(
+                "one of baseurl, mirrorlist or metalink is required when state is present"
+                    .to_string(),
+            ));
+        }
+
+        settings.push((
+            "enabled",
+            Self::flag(params.get_bool("enabled")?.unwrap_or(true)),
+        ));
+        if let Some(gpgcheck) = params.get_bool("gpgcheck")? {
+            settings.push(("gpgcheck", Self::flag(gpgcheck)));
+        }
+
+        let gpgkeys = match params.get_vec_string("gpgkey")? {
+            Some(keys) => keys,
+            None => params.get_string("gpgkey")?.into_iter().collect(),
+        };
+        if !gpgkeys.is_empty() {
+            let keys = gpgkeys
+                .iter()
+                .map(|key| Self::validate_url("gpgkey", key))
+                .collect::<ModuleResult<Vec<_>>>()?;
+            settings.push(("gpgkey", keys.join(" ")));
+        }
+
+        Ok(Self {
+            id,
+            state,
+            settings,
+            file,
+            reposdir,
+            mode: params.get_u32("mode")?.unwrap_or(0o644),
+        })
+    }
+
+    fn validate_url(key: &str, url: &str) -> ModuleResult<String> {
+        if URL_REGEX.is_match(url) {
+            Ok(url.to_string())
+        } else {
+            Err(ModuleError::InvalidParameter(format!(
+                "Invalid {} '{}': must be an http(s), ftp or file URL",
+                key, url
+            )))
+        }
+    }
+
+    fn flag(value: bool) -> String {
+        if value { "1" } else { "0" }.to_string()
+    }
+
+    fn repo_path(&self) -> String {
+        format!("{}/{}.repo", self.reposdir.trim_end_matches('/'), self.file)
+    }
+
+    /// Render this repository as an INI section
+    fn render_section(&self) -> String {
+        let mut section = format!("[{}]\n", self.id);
+        for (key, value) in &self.settings {
+            section.push_str(&format!("{}={}\n", key, value));
+        }
+        section
+    }
+
+    /// Compute the desired file content, or `None` if nothing changes
+    fn desired_content(&self, current: &str) -> Option<String> {
+        match self.state {
+            RepositoryState::Present => upsert_section(current, &self.id, &self.render_section()),
+            RepositoryState::Absent => remove_section(current, &self.id),
+        }
+    }
+}
+
+/// A `.repo` file split into its leading lines and INI sections
+struct RepoFile {
+    preamble: Vec<String>,
+    sections: Vec<(String, Vec<String>)>,
+}
+
+impl RepoFile {
+    fn parse(content: &str) -> Self {
+        let mut preamble = Vec::new();
+        let mut sections: Vec<(String, Vec<String>)> = Vec::new();
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                let id = trimmed[1..trimmed.len() - 1].trim().to_string();
+                sections.push((id, vec![line.to_string()]));
+            } else if let Some((_, lines)) = sections.last_mut() {
+                lines.push(line.to_string());
+            } else {
+                preamble.push(line.to_string());
+            }
+        }
+
+        Self { preamble, sections }
+    }
+
+    /// Meaningful lines of a section, ignoring blanks and comments
+    fn significant(lines: &[String]) -> Vec<&str> {
+        lines
+            .iter()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect()
+    }
+
+    /// Render the file, keeping untouched lines verbatim
+    fn render(&self) -> String {
+        let mut lines: Vec<&str> = self.preamble.iter().map(String::as_str).collect();
+        for (_, section) in &self.sections {
+            lines.extend(section.iter().map(String::as_str));
+        }
+        while lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+
+        if lines.is_empty() {
+            String::new()
+        } else {
+            format!("{}\n", lines.join("\n"))
+        }
+    }
+
+    /// Lines of the last block, where a new section would be appended
+    fn last_block(&mut self) -> &mut Vec<String> {
+        match self.sections.last_mut() {
+            Some((_, lines)) => lines,
+            None => &mut self.preamble,
+        }
+    }
+}
+
+/// Insert or replace a repository section, returning the new content if it changed
+pub fn upsert_section(content: &str, id: &str, section: &str) -> Option<String> {
+    let mut file = RepoFile::parse(content);
+    let desired: Vec<String> = section.lines().map(str::to_string).collect();
+
+    match file.sections.iter_mut().find(|(name, _)| name == id) {
+        Some((_, lines)) => {
+            if RepoFile::significant(lines) == RepoFile::significant(&desired) {
+                return None;
+            }
+            // Keep the blank lines separating this section from the next one
+            let trailing = lines
+                .iter()
+                .rev()
+                .take_while(|l| l.trim().is_empty())
+                .count();
+            let separator = lines.split_off(lines.len() - trailing);
+            *lines = desired;
+            lines.extend(separator);
+        }
+        None => {
+            let last = file.last_block();
+            if last.last().is_some_and(|l| !l.trim().is_empty()) {
+                last.push(String::new());
+            }
+            file.sections.push((id.to_string(), desired));
+        }
+    }
+
+    Some(file.render())
+}
+
+/// Remove a repository section, returning the new content if it changed
+pub fn remove_section(content: &str, id: &str) -> Option<String> {
+    let mut file = RepoFile::parse(content);
+    let before = file.sections.len();
+    file.sections.retain(|(name, _)| name != id);
+    if file.sections.len() == before {
+        return None;
+    }
+
+    if file.sections.is_empty() {
+        // Nothing but comments left; drop the file entirely
+        Some(String::new())
+    } else {
+        Some(file.render())
+    }
+}
+
+/// Module for YUM/DNF repository management
+pub struct YumRepositoryModule;
+
+impl YumRepositoryModule {
+    /// Build execute options with privilege escalation if needed
+    fn build_execute_options(context: &ModuleContext) -> Option<ExecuteOptions> {
+        context.r#become.then(|| context.execute_options())
+    }
+
+    /// Read a file from the target, treating a missing file as empty
+    async fn read_file(connection: &(dyn Connection + Send + Sync), path: &Path) -> String {
+        if connection.path_exists(path).await.unwrap_or(false) {
+            connection
+                .download_content(path)
+                .await
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .unwrap_or_default()
+        } else {
+            String::new()
+        }
+    }
+
+    async fn execute_async(
+        &self,
+        config: &YumRepositoryConfig,
+        context: &ModuleContext,
+        connection: Arc<dyn Connection + Send + Sync>,
+    ) -> ModuleResult<ModuleOutput> {
+        let repo_path = config.repo_path();
+        let current = Self::read_file(connection.as_ref(), Path::new(&repo_path)).await;
+        let desired = config.desired_content(&current);
+
+        let state = match config.state {
+            RepositoryState::Present => "present",
+            RepositoryState::Absent => "absent",
+        };
+
+        let output = match desired {
+            None => ModuleOutput::ok(format!("Repository '{}' already {}", config.id, state)),
+            Some(ref new_content) => {
+                if !context.check_mode {
+                    if new_content.is_empty() {
+                        let cmd = format!("rm -f '{}'", repo_path);
+                        let result = connection
+                            .execute(&cmd, Self::build_execute_options(context))
+                            .await?;
+                        if !result.success {
+                            return Err(ModuleError::ExecutionFailed(format!(
+                                "Failed to remove {}: {}",
+                                repo_path,
+                                result.stderr.trim()
+                            )));
+                        }
+                    } else {
+                        let transfer_opts = TransferOptions {
+                            mode: Some(config.mode),
+                            create_dirs: true,
+                            ..Default::default()
+                        };
+                        connection
+                            .upload_content(
+                                new_content.as_bytes(),
+                                Path::new(&repo_path),
+                                Some(transfer_opts),
+                            )
+                            .await
+                            .map_err(|e| {
+                                ModuleError::ExecutionFailed(format!(
+                                    "Failed to write {}: {}",
+                                    repo_path, e
+                                ))
+                            })?;
+                    }
+                }
+
+                let verb = match (config.state.clone(), context.check_mode) {
+                    (RepositoryState::Present, false) => "Configured",
+                    (RepositoryState::Present, true) => "Would configure",
+                    (RepositoryState::Absent, false) => "Removed",
+                    (RepositoryState::Absent, true) => "Would remove",
+                };
+                ModuleOutput::changed(format!(
+                    "{} repository '{}' in {}",
+                    verb, config.id, repo_path
+                ))
+            }
+        };
+
+        let output = output
+            .with_data("repo", serde_json::json!(config.id))
+            .with_data("repo_file", serde_json::json!(repo_path))
+            .with_data("state", serde_json::json!(state));
+
+        match desired {
+            Some(after) if context.diff_mode => Ok(output.with_diff(Diff::new(current, after))),
+            _ => Ok(output),
+        }
+    }
+}
+
+impl Module for YumRepositoryModule {
+    fn name(&self) -> &'static str {
+        "yum_repository"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add and remove YUM/DNF repositories"
+    }
+
+    fn classification(&self) -> ModuleClassification {
+        ModuleClassification::RemoteCommand
+    }
+
+    fn required_params(&self) -> &[&'static str] {
+        &["name"]
+    }
+
+    fn validate_params(&self, params: &ModuleParams) -> ModuleResult<()> {
+        YumRepositoryConfig::from_params(params).map(|_| ())
+    }
+
+    fn execute(
+        &self,
+        params: &ModuleParams,
+        context: &ModuleContext,
+    ) -> ModuleResult<ModuleOutput> {
+        let config = YumRepositoryConfig::from_params(params)?;
+
+        let connection = context.connection.clone().ok_or_else(|| {
+            ModuleError::ExecutionFailed(
+                "No connection available for yum_repository module execution".to_string(),
+            )
+        })?;
+
+        let handle = tokio::runtime::Handle::try_current()
+            .map_err(|_| ModuleError::ExecutionFailed("No tokio runtime available".to_string()))?;
+
+        let context = context.clone();
+        let module = self;
+
+        std::thread::scope(|s| {
+            s.spawn(|| handle.block_on(module.execute_async(&config, &context, connection)))
+                .join()
+                .unwrap()
+        })
+    }
+
+    fn check(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<ModuleOutput> {
+        let check_context = ModuleContext {
+            check_mode: true,
+            ..context.clone()
+        };
+        self.execute(params, &check_context)
+    }
+
+    fn diff(&self, params: &ModuleParams, context: &ModuleContext) -> ModuleResult<Option<Diff>> {
+        let config = YumRepositoryConfig::from_params(params)?;
+
+        let connection = match context.connection.clone() {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+
+        let handle = match tokio::runtime::Handle::try_current() {
+            Ok(h) => h,
+            Err(_) => return Ok(None),
+        };
+
+        let repo_path = config.repo_path();
+        let current = std::thread::scope(|s| {
+            s.spawn(|| handle.block_on(Self::read_file(connection.as_ref(), Path::new(&repo_path))))
+                .join()
+                .unwrap()
+        });
+
+        Ok(config
+            .desired_content(&current)
+            .map(|after| Diff::new(current, after)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, serde_json::Value)]) -> ModuleParams {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_render_section() {
+        let config = YumRepositoryConfig::from_params(&params(&[
+            ("name", serde_json::json!("epel")),
+            ("description", serde_json::json!("EPEL YUM repo")),
+            (
+                "baseurl",
+                serde_json::json!(
+                    "https://download.fedoraproject.org/pub/epel/$releasever/$basearch/"
+                ),
+            ),
+            ("gpgcheck", serde_json::json!(true)),
+            (
+                "gpgkey",
+                serde_json::json!("https://download.fedoraproject.org/pub/epel/RPM-GPG-KEY-EPEL-9"),
+            ),
+        ]))
+        .unwrap();
+
+        assert_eq!(config.repo_path(), "/etc/yum.repos.d/epel.repo");
+        assert_eq!(
+            config.render_section(),
+            "[epel]\n\
+             name=EPEL YUM repo\n\
+             baseurl=https://download.fedoraproject.org/pub/epel/$releasever/$basearch/\n\
+             enabled=1\n\
+             gpgcheck=1\n\
+             gpgkey=https://download.fedoraproject.org/pub/epel/RPM-GPG-KEY-EPEL-9\n"
+        );
+    }
+
+    #[test]
+    fn test_rejects_injection() {
+        let newline = params(&[
+            ("name", serde_json::json!("epel")),
+            (
+                "description",
+                serde_json::json!("EPEL\n[evil]\nbaseurl=http://x"),
+            ),
+            ("baseurl", serde_json::json!("https://example.com/")),
+        ]);
+        assert!(YumRepositoryConfig::from_params(&newline).is_err());
+
+        let bad_url = params(&[
+            ("name", serde_json::json!("epel")),
+            (
+                "baseurl",
+                serde_json::json!("https://example.com/\ngpgcheck=0"),
+            ),
+        ]);
+        assert!(YumRepositoryConfig::from_params(&bad_url).is_err());
+
+        let bad_id = params(&[
+            ("name", serde_json::json!("epel]\n[x")),
+            ("baseurl", serde_json::json!("https://example.com/")),
+        ]);
+        assert!(YumRepositoryConfig::from_params(&bad_id).is_err());
+
+        let bad_file = params(&[
+            ("name", serde_json::json!("epel")),
+            ("file", serde_json::json!("../../etc/passwd")),
+            ("baseurl", serde_json::json!("https://example.com/")),
+        ]);
+        assert!(YumRepositoryConfig::from_params(&bad_file).is_err());
+
+        let no_url = params(&[("name", serde_json::json!("epel"))]);
+        assert!(YumRepositoryConfig::from_params(&no_url).is_err());
+    }
+
+    #[test]
+    fn test_upsert_and_remove_section() {
+        let existing = "# shared file\n[base]\nname=Base\nbaseurl=https://example.com/base/\n";
+        let section = "[extras]\nname=Extras\nbaseurl=https://example.com/extras/\n";
+
+        let added = upsert_section(existing, "extras", section).unwrap();
+        assert_eq!(
+            added,
+            "# shared file\n[base]\nname=Base\nbaseurl=https://example.com/base/\n\n\
+             [extras]\nname=Extras\nbaseurl=https://example.com/extras/\n"
+        );
+        assert!(upsert_section(&added, "extras", section).is_none());
+
+        let updated = upsert_section(&added, "base", "[base]\nname=Base\nenabled=0\n").unwrap();
+        assert!(updated.contains("[base]\nname=Base\nenabled=0\n"));
+        assert!(updated.contains("[extras]"));
+
+        let removed = remove_section(&added, "extras").unwrap();
+        assert_eq!(removed, existing);
+        assert_eq!(remove_section(existing, "base").unwrap(), "");
+        assert!(remove_section(existing, "missing").is_none());
+    }
+}
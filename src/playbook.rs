@@ -55,6 +55,54 @@ where
     deserializer.deserialize_any(StringOrVec)
 }
 
+/// Serde helpers for an optional pause duration, given either as a number of
+/// seconds (`30`, `0.5`) or as a humantime string (`"2m"`, `"1m 30s"`).
+pub(crate) mod pause_duration {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match duration {
+            Some(d) => serializer
+                .serialize_str(&humantime_serde::re::humantime::format_duration(*d).to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Seconds(f64),
+            Text(String),
+        }
+
+        match Option::<Raw>::deserialize(deserializer)? {
+            None => Ok(None),
+            Some(Raw::Seconds(secs)) => Duration::try_from_secs_f64(secs)
+                .map(Some)
+                .map_err(serde::de::Error::custom),
+            Some(Raw::Text(text)) => {
+                let text = text.trim();
+                if let Ok(secs) = text.parse::<f64>() {
+                    return Duration::try_from_secs_f64(secs)
+                        .map(Some)
+                        .map_err(serde::de::Error::custom);
+                }
+                humantime_serde::re::humantime::parse_duration(text)
+                    .map(Some)
+                    .map_err(serde::de::Error::custom)
+            }
+        }
+    }
+}
+
 /// Deserialize boolean that accepts various formats (true, True, yes, 1, etc.)
 fn deserialize_bool_flexible<'de, D>(deserializer: D) -> std::result::Result<bool, D::Error>
 where
@@ -280,6 +328,18 @@ pub struct Play {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_fail_percentage: Option<u8>,
 
+    /// Whether `max_fail_percentage` applies to the play or each batch
+    #[serde(default)]
+    pub max_fail_scope: MaxFailScope,
+
+    /// Time to wait between serial batches
+    #[serde(
+        default,
+        with = "pause_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub serial_pause: Option<std::time::Duration>,
+
     /// Whether to run handlers on failure
     #[serde(default)]
     pub force_handlers: bool,
@@ -403,6 +463,8 @@ impl Play {
             strategy: None,
             serial: None,
             max_fail_percentage: None,
+            max_fail_scope: MaxFailScope::default(),
+            serial_pause: None,
             force_handlers: false,
             ignore_unreachable: false,
             module_defaults: HashMap::new(),
@@ -540,6 +602,9 @@ pub struct Task {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub become_user: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub become_method: Option<String>,
+
     /// Delegation
     #[serde(skip_serializing_if = "Option::is_none")]
     pub delegate_to: Option<String>,
@@ -634,6 +699,7 @@ impl<'de> Deserialize<'de> for Task {
             "ignore_unreachable",
             "become",
             "become_user",
+            "become_method",
             "delegate_to",
             "delegate_facts",
             "run_once",
@@ -785,6 +851,10 @@ impl<'de> Deserialize<'de> for Task {
                 .get("become_user")
                 .and_then(|v| v.as_str())
                 .map(String::from),
+            become_method: obj
+                .get("become_method")
+                .and_then(|v| v.as_str())
+                .map(String::from),
             delegate_to: obj
                 .get("delegate_to")
                 .and_then(|v| v.as_str())
@@ -852,6 +922,7 @@ impl Task {
             ignore_unreachable: false,
             r#become: None,
             become_user: None,
+            become_method: None,
             delegate_to: None,
             delegate_facts: None,
             run_once: false,
@@ -953,6 +1024,17 @@ fn default_loop_var() -> String {
     "item".to_string()
 }
 
+/// Which hosts `max_fail_percentage` is measured against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MaxFailScope {
+    /// Failures accumulated across every host in the play
+    #[default]
+    Play,
+    /// Failures within each serial batch, judged independently
+    Batch,
+}
+
 /// Serial execution specification.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -966,6 +1048,16 @@ pub enum SerialSpec {
 }
 
 impl SerialSpec {
+    /// Canary rollout: run `canary_size` hosts first, then all remaining hosts.
+    ///
+    /// Equivalent to `serial: [canary_size, "100%"]`.
+    pub fn canary(canary_size: usize) -> Self {
+        SerialSpec::Progressive(vec![
+            SerialSpec::Fixed(canary_size),
+            SerialSpec::Percentage("100%".to_string()),
+        ])
+    }
+
     /// Calculate batch sizes for a given number of hosts.
     /// Returns a vector of batch sizes that should be used in order.
     pub fn calculate_batches(&self, total_hosts: usize) -> Vec<usize> {
@@ -1006,6 +1098,22 @@ impl SerialSpec {
         }
     }
 
+    /// Whether the first batch is a canary for the rest of the rollout.
+    ///
+    /// That is the case for a progressive spec whose first entry is smaller
+    /// than every later one, such as `serial: [1, "100%"]`. It is decided
+    /// from the spec rather than the resulting batches, whose sizes are
+    /// capped by the hosts that remain.
+    pub fn has_canary(&self, total_hosts: usize) -> bool {
+        let SerialSpec::Progressive(_) = self else {
+            return false;
+        };
+        match self.calculate_batches(total_hosts).split_first() {
+            Some((first, rest)) => !rest.is_empty() && rest.iter().all(|size| first < size),
+            None => false,
+        }
+    }
+
     /// Split hosts into batches according to the serial specification.
     pub fn batch_hosts<'a>(&self, hosts: &'a [String]) -> Vec<&'a [String]> {
         let total_hosts = hosts.len();
@@ -28,6 +28,7 @@ use crate::modules::{
     package::PackageModule,
     pip::PipModule,
     service::ServiceModule,
+    service_facts::ServiceFactsModule,
     set_fact::SetFactModule,
     shell::ShellModule,
     stat::StatModule,
@@ -112,6 +113,9 @@ impl LazyModuleRegistry {
         factories.insert("hostname", || Arc::new(HostnameModule) as Arc<dyn Module>);
         factories.insert("mount", || Arc::new(MountModule) as Arc<dyn Module>);
         factories.insert("service", || Arc::new(ServiceModule) as Arc<dyn Module>);
+        factories.insert("service_facts", || {
+            Arc::new(ServiceFactsModule) as Arc<dyn Module>
+        });
         factories.insert("sysctl", || Arc::new(SysctlModule) as Arc<dyn Module>);
         factories.insert("systemd_unit", || Arc::new(SystemdUnitModule) as Arc<dyn Module>);
         factories.insert("user", || Arc::new(UserModule) as Arc<dyn Module>);
@@ -1,7 +1,7 @@
 //! Template engine for Rustible (Jinja2-compatible)
 
 use crate::error::Result;
-use minijinja::Environment;
+use minijinja::{Environment, Error, ErrorKind, UndefinedBehavior, Value};
 use std::collections::HashMap;
 
 /// Template engine using minijinja
@@ -13,7 +13,11 @@ impl TemplateEngine {
     /// Create a new template engine
     #[must_use]
     pub fn new() -> Self {
-        let env = Environment::new();
+        let mut env = Environment::new();
+        // Like Ansible, `foo.bar | default(...)` works when `foo` is undefined
+        env.set_undefined_behavior(UndefinedBehavior::Chainable);
+        env.add_filter("bool", bool_filter);
+        env.add_filter("regex_search", regex_search_filter);
         Self { env }
     }
 
@@ -32,6 +36,26 @@ impl TemplateEngine {
         Ok(result)
     }
 
+    /// Evaluate a single expression such as `items | length | int`
+    ///
+    /// Unlike [`render`](Self::render) the result keeps its native type, so
+    /// numbers, booleans, lists and mappings come back as JSON values rather
+    /// than strings. Undefined results evaluate to `null`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression does not parse or fails to
+    /// evaluate, including when it uses an unknown filter.
+    pub fn evaluate(
+        &self,
+        expression: &str,
+        vars: &HashMap<String, serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        let expr = self.env.compile_expression(expression)?;
+        let value = expr.eval(vars)?;
+        Ok(serde_json::to_value(&value)?)
+    }
+
     /// Check if a string contains template syntax
     #[must_use]
     pub fn is_template(s: &str) -> bool {
@@ -39,6 +63,30 @@ impl TemplateEngine {
     }
 }
 
+/// Ansible's `bool` filter: `yes`, `on`, `true`, `y` and `1` are true
+fn bool_filter(value: Value) -> bool {
+    match value.as_str() {
+        Some(s) => matches!(
+            s.trim().to_lowercase().as_str(),
+            "yes" | "true" | "on" | "1" | "y"
+        ),
+        None => value.is_true(),
+    }
+}
+
+/// Ansible's `regex_search` filter: the first match, or none
+fn regex_search_filter(value: String, pattern: String) -> std::result::Result<Value, Error> {
+    let re = regex::Regex::new(&pattern).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidOperation,
+            format!("invalid regex_search pattern: {}", e),
+        )
+    })?;
+    Ok(re
+        .find(&value)
+        .map_or(Value::from(()), |m| Value::from(m.as_str())))
+}
+
 impl Default for TemplateEngine {
     fn default() -> Self {
         Self::new()
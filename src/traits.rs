@@ -295,7 +295,7 @@ pub struct ModuleDiff {
 /// - `SshConnection` - SSH-based remote connections
 /// - `LocalConnection` - Local system execution
 /// - `DockerConnection` - Docker container connections
-/// - `KubernetesConnection` - Kubernetes pod connections
+/// - `KubeConnection` - Kubernetes pod connections
 #[async_trait]
 pub trait Connection: Send + Sync + Debug {
     /// Returns the connection type name (e.g., "ssh", "local", "docker").
@@ -585,6 +585,33 @@ pub struct ExecutionResult {
     pub notify: Vec<String>,
 }
 
+/// Progress of a play running in serial batches.
+///
+/// Reported after each batch so long rolling deploys can show how far along
+/// they are.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SerialProgress {
+    /// 1-based index of the batch that just finished
+    pub batch: usize,
+    /// Number of batches in the play
+    pub total_batches: usize,
+    /// Hosts that have finished so far, including failed ones
+    pub hosts_completed: usize,
+    /// Hosts targeted by the play
+    pub total_hosts: usize,
+    /// Hosts that failed or were unreachable so far
+    pub hosts_failed: usize,
+    /// Rough time left, from the average batch duration so far
+    pub eta: Option<std::time::Duration>,
+}
+
+impl SerialProgress {
+    /// Hosts that have not run yet.
+    pub fn remaining_hosts(&self) -> usize {
+        self.total_hosts.saturating_sub(self.hosts_completed)
+    }
+}
+
 // ============================================================================
 // Strategy Traits
 // ============================================================================
@@ -664,6 +691,19 @@ pub trait ExecutionCallback: Send + Sync {
     async fn on_facts_gathered(&self, host: &str, facts: &Facts) {
         let _ = (host, facts);
     }
+
+    /// Called before the executor pauses between serial batches.
+    ///
+    /// `batch` is the 1-based index of the batch that just finished and
+    /// `total` the number of batches in the play.
+    async fn on_batch_pause(&self, batch: usize, total: usize, duration: std::time::Duration) {
+        let _ = (batch, total, duration);
+    }
+
+    /// Called after each serial batch finishes, with progress so far.
+    async fn on_batch_complete(&self, progress: &SerialProgress) {
+        let _ = progress;
+    }
 }
 
 // ============================================================================
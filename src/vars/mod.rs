@@ -517,14 +517,14 @@ impl<'a> VarScope<'a> {
         for precedence in VarPrecedence::all() {
             if let Some(layer) = self.parent.layers.get(&precedence) {
                 for (key, var) in layer {
-                    merged.insert(key.clone(), var.value.clone());
+                    self.parent.merge_value(&mut merged, key, &var.value);
                 }
             }
         }
 
-        // Apply local overrides
+        // Apply local overrides, honoring the parent's hash behaviour
         for (key, value) in &self.local {
-            merged.insert(key.clone(), value.clone());
+            self.parent.merge_value(&mut merged, key, value);
         }
 
         merged
@@ -964,6 +964,61 @@ mod tests {
         );
     }
 
+    fn yaml(s: &str) -> serde_yaml::Value {
+        serde_yaml::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_hash_behaviour_merge_nested_across_layers() {
+        let mut store = VarStore::with_hash_behaviour(HashBehaviour::Merge);
+        store.set(
+            "app",
+            yaml("{port: 80, opts: {a: 1}}"),
+            VarPrecedence::InventoryGroupVars,
+        );
+        store.set(
+            "app",
+            yaml("{name: web, opts: {b: 2}}"),
+            VarPrecedence::InventoryHostVars,
+        );
+        store.set(
+            "app",
+            yaml("{port: 8080, opts: {a: 3}}"),
+            VarPrecedence::PlayVars,
+        );
+
+        assert_eq!(
+            store.get("app"),
+            Some(&yaml("{port: 8080, opts: {a: 3, b: 2}, name: web}"))
+        );
+
+        // Child scopes combine with the merged parent the same way
+        let mut scope = store.scope();
+        scope.set("app", yaml("{opts: {c: 4}}"));
+        assert_eq!(
+            scope.all().get("app"),
+            Some(&yaml("{port: 8080, opts: {a: 3, b: 2, c: 4}, name: web}"))
+        );
+    }
+
+    #[test]
+    fn test_hash_behaviour_replace_overwrites() {
+        let mut store = VarStore::new();
+        store.set(
+            "app",
+            yaml("{a: {x: 1}}"),
+            VarPrecedence::InventoryGroupVars,
+        );
+        store.set("app", yaml("{a: {y: 2}}"), VarPrecedence::InventoryHostVars);
+        store.set("app", yaml("{b: 3}"), VarPrecedence::PlayVars);
+
+        assert_eq!(store.get("app"), Some(&yaml("{b: 3}")));
+
+        let mut scope = store.scope();
+        scope.set("app", yaml("{c: 4}"));
+        assert_eq!(scope.all().get("app"), Some(&yaml("{c: 4}")));
+    }
+
     #[test]
     fn test_deep_merge() {
         let base = serde_yaml::from_str::<serde_yaml::Value>(
@@ -836,7 +836,7 @@ fn test_async_with_become() {
     let playbook = Playbook::parse(yaml, None).unwrap();
     let task = &playbook.plays[0].tasks[0];
 
-    assert!(task.r#become);
+    assert_eq!(task.r#become, Some(true));
     assert_eq!(task.become_user, Some("root".to_string()));
 }
 
@@ -19,7 +19,7 @@ use std::path::PathBuf;
 use rustible::executor::playbook::{Play, Playbook};
 use rustible::executor::runtime::RuntimeContext;
 use rustible::executor::task::{Task, TaskResult, TaskStatus};
-use rustible::executor::{ExecutionStats, Executor, ExecutorConfig};
+use rustible::executor::{ExecutionStats, ExecutionStrategy, Executor, ExecutorConfig};
 
 // Import parser types for block structure
 #[path = "../src/parser/playbook.rs"]
@@ -959,6 +959,172 @@ async fn test_execute_nested_blocks() {
     assert!(!host_result.failed);
 }
 
+#[tokio::test]
+async fn test_rescue_sees_failed_task_and_result() {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("localhost".to_string(), None);
+
+    let config = ExecutorConfig {
+        gather_facts: false,
+        ..Default::default()
+    };
+    let executor = Executor::with_runtime(config, runtime);
+
+    let yaml = r#"
+- name: Rescue error propagation
+  hosts: all
+  gather_facts: false
+  tasks:
+    - name: Guarded block
+      block:
+        - name: Break things
+          fail:
+            msg: "disk full on /var"
+      rescue:
+        - name: Report failure
+          debug:
+            msg: "{{ ansible_failed_task.name }} failed: {{ ansible_failed_result.msg }}"
+          register: rescue_out
+    - name: After block
+      debug:
+        msg: "done"
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(!results.get("localhost").unwrap().failed);
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    let rescue_msg = runtime
+        .get_registered("localhost", "rescue_out")
+        .and_then(|r| r.msg.clone())
+        .unwrap();
+    assert!(rescue_msg.contains("Break things failed"));
+    assert!(rescue_msg.contains("disk full on /var"));
+
+    // The failure details do not leak past the block
+    assert!(runtime
+        .get_host_var("localhost", "ansible_failed_result")
+        .is_none());
+    assert!(runtime
+        .get_host_var("localhost", "ansible_failed_task")
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_rescue_sees_failed_task_with_free_strategy() {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("web1".to_string(), None);
+    runtime.add_host("web2".to_string(), None);
+
+    let config = ExecutorConfig {
+        gather_facts: false,
+        strategy: ExecutionStrategy::Free,
+        ..Default::default()
+    };
+    let executor = Executor::with_runtime(config, runtime);
+
+    let yaml = r#"
+- name: Rescue error propagation
+  hosts: all
+  gather_facts: false
+  strategy: free
+  tasks:
+    - name: Guarded block
+      block:
+        - name: Break things
+          fail:
+            msg: "disk full on /var"
+        - name: Never reached
+          debug:
+            msg: "unreachable"
+          register: skipped_out
+      rescue:
+        - name: Report failure
+          debug:
+            msg: "{{ ansible_failed_task.name }} failed: {{ ansible_failed_result.msg }}"
+          register: rescue_out
+    - name: After block
+      debug:
+        msg: "done"
+      register: after_out
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+    let results = executor.run_playbook(&playbook).await.unwrap();
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    for host in ["web1", "web2"] {
+        assert!(!results[host].failed, "{} was not rescued", host);
+        let rescue_msg = runtime
+            .get_registered(host, "rescue_out")
+            .and_then(|r| r.msg.clone())
+            .unwrap();
+        assert!(rescue_msg.contains("Break things failed"));
+        assert!(rescue_msg.contains("disk full on /var"));
+        assert!(runtime.get_registered(host, "skipped_out").is_none());
+        assert!(runtime.get_registered(host, "after_out").is_some());
+        assert!(runtime.get_host_var(host, "ansible_failed_task").is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_ignored_block_failure_skips_rescue() {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("localhost".to_string(), None);
+
+    let config = ExecutorConfig {
+        gather_facts: false,
+        ..Default::default()
+    };
+    let executor = Executor::with_runtime(config, runtime);
+
+    let yaml = r#"
+- name: Ignored failure inside a block
+  hosts: all
+  gather_facts: false
+  tasks:
+    - name: Guarded block
+      block:
+        - name: Tolerated failure
+          fail:
+            msg: "not fatal"
+          ignore_errors: true
+        - name: Tolerated module error
+          no_such_module:
+            arg: value
+          ignore_errors: true
+        - name: Keeps going
+          set_fact:
+            block_finished: true
+      rescue:
+        - name: Should not rescue
+          set_fact:
+            rescued: true
+      always:
+        - name: Cleanup
+          set_fact:
+            cleaned_up: true
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    let host_result = results.get("localhost").unwrap();
+    assert!(!host_result.failed);
+    assert_eq!(host_result.stats.failed, 0);
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    assert_eq!(
+        runtime.get_host_fact("localhost", "block_finished"),
+        Some(serde_json::json!(true))
+    );
+    assert_eq!(runtime.get_host_fact("localhost", "rescued"), None);
+    assert_eq!(
+        runtime.get_host_fact("localhost", "cleaned_up"),
+        Some(serde_json::json!(true))
+    );
+}
+
 // ============================================================================
 // Edge Cases and Error Handling Tests
 // ============================================================================
@@ -930,3 +930,128 @@ async fn test_callback_manager_rapid_add_remove() {
 
     assert_eq!(manager.callback_count(), 0);
 }
+
+// ============================================================================
+// Library CallbackManager: Executor Fan-Out
+// ============================================================================
+
+/// Boxed callback that records events into a log shared with the test
+struct RecordingCallback {
+    events: Arc<RwLock<Vec<String>>>,
+}
+
+#[async_trait]
+impl ExecutionCallback for RecordingCallback {
+    async fn on_playbook_start(&self, name: &str) {
+        self.events.write().push(format!("playbook_start:{}", name));
+    }
+
+    async fn on_playbook_end(&self, name: &str, success: bool) {
+        self.events
+            .write()
+            .push(format!("playbook_end:{}:{}", name, success));
+    }
+
+    async fn on_play_start(&self, name: &str, _hosts: &[String]) {
+        self.events.write().push(format!("play_start:{}", name));
+    }
+
+    async fn on_play_end(&self, name: &str, success: bool) {
+        self.events
+            .write()
+            .push(format!("play_end:{}:{}", name, success));
+    }
+
+    async fn on_task_start(&self, name: &str, host: &str) {
+        self.events
+            .write()
+            .push(format!("task_start:{}:{}", name, host));
+    }
+
+    async fn on_task_complete(&self, result: &ExecutionResult) {
+        self.events.write().push(format!(
+            "task_complete:{}:{}",
+            result.task_name, result.host
+        ));
+    }
+}
+
+/// Callback that panics on every task event
+struct PanickingCallback;
+
+#[async_trait]
+impl ExecutionCallback for PanickingCallback {
+    async fn on_task_start(&self, _name: &str, _host: &str) {
+        panic!("callback failure");
+    }
+}
+
+#[tokio::test]
+async fn test_executor_fans_events_out_to_every_boxed_callback() {
+    use rustible::callback::manager::CallbackManager;
+    use rustible::callback::BoxedCallback;
+    use rustible::executor::playbook::{Play, Playbook};
+    use rustible::executor::runtime::RuntimeContext;
+    use rustible::executor::task::Task;
+    use rustible::executor::{Executor, ExecutorConfig};
+
+    let first = Arc::new(RwLock::new(Vec::new()));
+    let second = Arc::new(RwLock::new(Vec::new()));
+
+    let callbacks = Arc::new(CallbackManager::new());
+    let boxed: Vec<(&str, BoxedCallback)> = vec![
+        (
+            "first",
+            Box::new(RecordingCallback {
+                events: first.clone(),
+            }),
+        ),
+        (
+            "second",
+            Box::new(RecordingCallback {
+                events: second.clone(),
+            }),
+        ),
+        ("broken", Box::new(PanickingCallback)),
+    ];
+    for (name, callback) in boxed {
+        assert!(callbacks.register_boxed(name, callback).await);
+    }
+
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("server1".to_string(), None);
+    runtime.add_host("server2".to_string(), None);
+    let executor =
+        Executor::with_runtime(ExecutorConfig::default(), runtime).with_callbacks(callbacks);
+
+    let mut playbook = Playbook::new("fan-out");
+    let mut play = Play::new("Greet", "all");
+    play.gather_facts = false;
+    play.add_task(Task::new("Say hello", "debug").arg("msg", "hello"));
+    play.add_task(Task::new("Say bye", "debug").arg("msg", "bye"));
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(results.values().all(|r| !r.failed));
+
+    let mut first = first.read().clone();
+    let mut second = second.read().clone();
+    // Hosts run concurrently, so only the set of events is deterministic
+    first.sort();
+    second.sort();
+    assert_eq!(first, second);
+
+    for event in [
+        "playbook_start:fan-out",
+        "playbook_end:fan-out:true",
+        "play_start:Greet",
+        "play_end:Greet:true",
+        "task_start:Say hello:server1",
+        "task_start:Say hello:server2",
+        "task_complete:Say bye:server1",
+        "task_complete:Say bye:server2",
+    ] {
+        assert!(first.iter().any(|e| e == event), "missing {}", event);
+    }
+    assert_eq!(first.len(), 12);
+}
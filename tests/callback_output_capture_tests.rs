@@ -1217,3 +1217,77 @@ async fn test_compare_play_header_format() {
     let lines = callback.get_lines();
     assert_eq!(lines[0], "PLAY [My Play] 3 host(s)");
 }
+
+// ============================================================================
+// Oneline Callback Capture Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_oneline_callback_two_host_run() {
+    use rustible::callback::manager::CallbackManager;
+    use rustible::callback::{OnelineCallback, OnelineConfig};
+    use rustible::executor::playbook::Playbook;
+    use rustible::executor::runtime::RuntimeContext;
+    use rustible::executor::{Executor, ExecutorConfig};
+
+    let capture = CaptureBuffer::new();
+    let config = OnelineConfig::compact().without_headers().without_recap();
+    let oneline = Arc::new(OnelineCallback::with_config(config).with_writer(capture.clone()));
+
+    let callbacks = Arc::new(CallbackManager::new());
+    callbacks.register_default("oneline", oneline).await;
+
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("web1".to_string(), None);
+    runtime.add_host("web2".to_string(), None);
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            gather_facts: false,
+            ..Default::default()
+        },
+        runtime,
+    )
+    .with_callbacks(callbacks);
+
+    let yaml = r#"
+- name: Oneline
+  hosts: all
+  gather_facts: false
+  tasks:
+    - name: Say hello
+      debug:
+        msg: "hello"
+    - name: Only on web2
+      debug:
+        msg: "web2 only"
+      when: inventory_hostname == 'web2'
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+    executor.run_playbook(&playbook).await.unwrap();
+
+    let output = capture.get_output();
+    let mut lines: Vec<&str> = output.lines().collect();
+    lines.sort();
+    assert_eq!(
+        lines.len(),
+        4,
+        "expected one line per host-task:\n{}",
+        output
+    );
+
+    let fields: Vec<Vec<&str>> = lines.iter().map(|l| l.split(" | ").collect()).collect();
+    for f in &fields {
+        assert_eq!(f.len(), 4, "not a host | STATUS | task | msg line: {:?}", f);
+    }
+    let status_of = |host: &str, task: &str| {
+        fields
+            .iter()
+            .find(|f| f[0] == host && f[2] == task)
+            .map(|f| f[1])
+            .unwrap()
+    };
+    assert_eq!(status_of("web1", "Say hello"), "OK");
+    assert_eq!(status_of("web2", "Say hello"), "OK");
+    assert_eq!(status_of("web1", "Only on web2"), "SKIPPED");
+    assert_eq!(status_of("web2", "Only on web2"), "OK");
+}
@@ -772,6 +772,9 @@ async fn test_rescue_block_reliability() {
         task_timeout: 30,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let result = rustible::executor::execute_playbook(&playbook, &inventory, executor_config).await;
@@ -836,6 +839,9 @@ async fn test_always_block_guaranteed_execution() {
         task_timeout: 30,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let _ = rustible::executor::execute_playbook(&playbook, &inventory, executor_config).await;
@@ -215,9 +215,9 @@ fn test_copy_module_check_mode() {
     let context = check_mode_context();
     let result = module.check(&params, &context).unwrap();
 
-    // Should report would copy
+    // Should report would create
     assert!(result.changed);
-    assert!(result.msg.contains("Would copy"));
+    assert!(result.msg.contains("Would create"));
 
     // File should NOT be created in check mode
     assert!(!dest.exists());
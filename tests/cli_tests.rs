@@ -848,6 +848,66 @@ fn test_list_tasks_with_tags() {
         .stdout(predicate::str::contains("Tagged task"));
 }
 
+#[test]
+fn test_list_tasks_marks_tasks_selected_by_tags() {
+    let mut playbook = NamedTempFile::new().unwrap();
+    writeln!(
+        playbook,
+        r#"---
+- name: Mixed tags
+  hosts: localhost
+  tasks:
+    - name: Create database
+      debug:
+        msg: db
+      tags: [db, setup]
+    - name: Install web server
+      debug:
+        msg: web
+      tags: web
+    - name: Untagged task
+      debug:
+        msg: none
+    - name: Migrate database
+      debug:
+        msg: migrate
+      tags: db
+"#
+    )
+    .unwrap();
+
+    let output = rustible_cmd()
+        .arg("list-tasks")
+        .arg(playbook.path())
+        .arg("--tags")
+        .arg("db")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = |name: &str| {
+        stdout
+            .lines()
+            .find(|l| l.contains(name))
+            .unwrap_or_else(|| panic!("task '{}' missing from output:\n{}", name, stdout))
+            .to_string()
+    };
+
+    let create = line("Create database");
+    assert!(create.contains("TAGS: [db, setup]"));
+    assert!(create.contains("(would run)"));
+    assert!(line("Migrate database").contains("(would run)"));
+
+    let web = line("Install web server");
+    assert!(web.contains("TAGS: [web]"));
+    assert!(web.contains("(skipped)"));
+    assert!(line("Untagged task").contains("(skipped)"));
+
+    assert_eq!(stdout.matches("(would run)").count(), 2);
+    assert!(stdout.contains("Tasks that would run: 2"));
+}
+
 // =============================================================================
 // Config File Tests
 // =============================================================================
@@ -1193,6 +1253,106 @@ fn test_playbook_execution_exit_code_success() {
         .code(0);
 }
 
+#[test]
+fn test_detailed_exitcode_reports_drift() {
+    let dir = tempdir().unwrap();
+    let dest = dir.path().join("motd");
+    let playbook = dir.path().join("drift.yml");
+    std::fs::write(
+        &playbook,
+        format!(
+            r#"---
+- name: Drift check
+  hosts: localhost
+  gather_facts: false
+  tasks:
+    - name: Write motd
+      copy:
+        content: "welcome"
+        dest: {}
+"#,
+            dest.display()
+        ),
+    )
+    .unwrap();
+
+    // Check mode would create the file: changes detected
+    rustible_cmd()
+        .arg("check")
+        .arg("--detailed-exitcode")
+        .arg(&playbook)
+        .assert()
+        .code(2);
+    assert!(!dest.exists());
+
+    // Nothing to change
+    rustible_cmd()
+        .arg("run")
+        .arg("--detailed-exitcode")
+        .arg(create_test_playbook().path())
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn test_connection_override_runs_ssh_hosts_locally() {
+    let dir = tempdir().unwrap();
+    let marker = dir.path().join("ran");
+    let inventory = dir.path().join("hosts.yml");
+    std::fs::write(
+        &inventory,
+        r#"all:
+  hosts:
+    web1:
+      ansible_host: 192.0.2.10
+    web2:
+      ansible_host: 192.0.2.11
+"#,
+    )
+    .unwrap();
+    let playbook = dir.path().join("site.yml");
+    std::fs::write(
+        &playbook,
+        format!(
+            r#"---
+- name: Try it locally
+  hosts: all
+  gather_facts: false
+  tasks:
+    - name: Record run
+      shell: echo ran >> {}
+"#,
+            marker.display()
+        ),
+    )
+    .unwrap();
+
+    rustible_cmd()
+        .arg("run")
+        .arg("-i")
+        .arg(&inventory)
+        .arg("--connection")
+        .arg("local")
+        .arg(&playbook)
+        .assert()
+        .success();
+
+    // Both unreachable SSH hosts ran their task on the control node
+    let runs = std::fs::read_to_string(&marker).unwrap();
+    assert_eq!(runs.lines().count(), 2);
+}
+
+#[test]
+fn test_connection_override_rejects_unknown_type() {
+    rustible_cmd()
+        .arg("run")
+        .arg("--connection")
+        .arg("telnet")
+        .arg("site.yml")
+        .assert()
+        .failure();
+}
+
 // =============================================================================
 // Vault Command Tests
 // =============================================================================
@@ -3822,3 +3982,228 @@ fn test_playbook_with_null_tasks() {
         .assert()
         .success();
 }
+
+// =============================================================================
+// Meta Action Tests
+// =============================================================================
+
+fn create_refresh_inventory_playbook() -> NamedTempFile {
+    let mut file = NamedTempFile::new().unwrap();
+    writeln!(
+        file,
+        r#"---
+- name: Refresh inventory
+  hosts: localhost
+  gather_facts: false
+  tasks:
+    - name: Reload hosts
+      meta: refresh_inventory
+"#
+    )
+    .unwrap();
+    file
+}
+
+#[test]
+fn test_refresh_inventory_reloads_inventory_sources() {
+    let playbook = create_refresh_inventory_playbook();
+    let inventory = create_test_inventory();
+
+    rustible_cmd()
+        .arg("-i")
+        .arg(inventory.path())
+        .arg("run")
+        .arg(playbook.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("inventory refreshed (6 host(s))"));
+}
+
+#[test]
+fn test_refresh_inventory_runs_once_per_play() {
+    let mut playbook = NamedTempFile::new().unwrap();
+    writeln!(
+        playbook,
+        r#"---
+- name: Refresh inventory
+  hosts: all
+  gather_facts: false
+  tasks:
+    - name: Reload hosts
+      meta: refresh_inventory
+"#
+    )
+    .unwrap();
+    let inventory = create_test_inventory();
+
+    let output = rustible_cmd()
+        .arg("-i")
+        .arg(inventory.path())
+        .arg("run")
+        .arg(playbook.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("inventory refreshed").count(), 1);
+}
+
+#[test]
+fn test_refresh_inventory_without_source_is_skipped() {
+    let playbook = create_refresh_inventory_playbook();
+    let workdir = tempdir().unwrap();
+
+    let output = rustible_cmd()
+        .current_dir(workdir.path())
+        .arg("run")
+        .arg(playbook.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(combined.contains("no inventory source configured"));
+    assert!(!combined.contains("inventory refreshed"));
+}
+
+// =============================================================================
+// Fact Cache Tests
+// =============================================================================
+
+#[test]
+fn test_run_uses_facts_cached_by_facts_gather() {
+    use rustible::cache::tiered_facts::{CacheTier, TieredCacheConfig, TieredFactCache};
+
+    let workdir = tempdir().unwrap();
+    let cache_dir = workdir.path().join("facts");
+    let cache = TieredFactCache::new(TieredCacheConfig {
+        l2_cache_path: cache_dir.clone(),
+        ..TieredCacheConfig::default()
+    });
+    let mut facts = indexmap::IndexMap::new();
+    facts.insert(
+        "ansible_distribution".to_string(),
+        serde_json::json!("CachedOS"),
+    );
+    cache.insert_to_tier("web1", facts, vec!["all".to_string()], CacheTier::L2Warm);
+
+    let inventory = workdir.path().join("inventory.yml");
+    std::fs::write(
+        &inventory,
+        r#"all:
+  hosts:
+    web1:
+      ansible_connection: local
+    web2:
+      ansible_connection: local
+"#,
+    )
+    .unwrap();
+    let playbook = workdir.path().join("playbook.yml");
+    std::fs::write(
+        &playbook,
+        r#"- name: Report distribution
+  hosts: all
+  tasks:
+    - name: Show distribution
+      debug:
+        msg: "distro={{ ansible_distribution }}"
+"#,
+    )
+    .unwrap();
+
+    let output = rustible_cmd()
+        .env("RUSTIBLE_FACT_CACHE_DIR", &cache_dir)
+        .arg("-v")
+        .arg("-i")
+        .arg(&inventory)
+        .arg("run")
+        .arg(&playbook)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // web1 reads its cached facts, web2 is gathered as usual
+    assert_eq!(stdout.matches("distro=CachedOS").count(), 1, "{}", stdout);
+}
+
+// =============================================================================
+// Diff Report Tests
+// =============================================================================
+
+#[test]
+fn test_check_diff_groups_identical_changes_across_hosts() {
+    let workdir = tempdir().unwrap();
+    let dest = workdir.path().join("motd");
+    std::fs::write(&dest, "welcome\n").unwrap();
+
+    let inventory = workdir.path().join("inventory.yml");
+    std::fs::write(
+        &inventory,
+        r#"all:
+  hosts:
+    web1:
+      ansible_connection: local
+      motd: hello
+    web2:
+      ansible_connection: local
+      motd: hello
+    db1:
+      ansible_connection: local
+      motd: restricted
+"#,
+    )
+    .unwrap();
+
+    let playbook = workdir.path().join("playbook.yml");
+    std::fs::write(
+        &playbook,
+        format!(
+            r#"---
+- name: Update motd
+  hosts: all
+  gather_facts: false
+  tasks:
+    - name: Write motd
+      copy:
+        content: "{{{{ motd }}}}\n"
+        dest: {}
+"#,
+            dest.display()
+        ),
+    )
+    .unwrap();
+
+    let output = rustible_cmd()
+        .arg("--no-color")
+        .arg("-i")
+        .arg(&inventory)
+        .arg("--check")
+        .arg("--diff")
+        .arg("run")
+        .arg(&playbook)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let grouped = stdout
+        .find("changes identical on 2 hosts: web1, web2")
+        .unwrap_or_else(|| panic!("missing grouped diff:\n{}", stdout));
+    let single = stdout
+        .find("changes on db1")
+        .unwrap_or_else(|| panic!("missing db1 diff:\n{}", stdout));
+    assert!(grouped < single);
+    assert_eq!(stdout.matches("+hello").count(), 1, "{}", stdout);
+    assert_eq!(stdout.matches("+restricted").count(), 1, "{}", stdout);
+    assert!(single < stdout.find("+restricted").unwrap());
+
+    // Check mode leaves the file alone
+    assert_eq!(std::fs::read_to_string(&dest).unwrap(), "welcome\n");
+}
@@ -100,7 +100,9 @@ pub struct MockConnection {
     identifier: String,
     alive: AtomicBool,
     commands_executed: RwLock<Vec<String>>,
+    execute_options: RwLock<Vec<(String, ExecuteOptions)>>,
     files_uploaded: RwLock<Vec<(PathBuf, PathBuf)>>,
+    upload_options: RwLock<Vec<(PathBuf, TransferOptions)>>,
     files_downloaded: RwLock<Vec<PathBuf>>,
     command_results: RwLock<HashMap<String, CommandResult>>,
     default_result: RwLock<CommandResult>,
@@ -108,6 +110,7 @@ pub struct MockConnection {
     fail_after_n: AtomicU32,
     command_count: AtomicU32,
     virtual_filesystem: RwLock<HashMap<PathBuf, Vec<u8>>>,
+    atomic_rename: AtomicBool,
 }
 
 impl MockConnection {
@@ -117,7 +120,9 @@ impl MockConnection {
             identifier: identifier.into(),
             alive: AtomicBool::new(true),
             commands_executed: RwLock::new(Vec::new()),
+            execute_options: RwLock::new(Vec::new()),
             files_uploaded: RwLock::new(Vec::new()),
+            upload_options: RwLock::new(Vec::new()),
             files_downloaded: RwLock::new(Vec::new()),
             command_results: RwLock::new(HashMap::new()),
             default_result: RwLock::new(CommandResult::success(String::new(), String::new())),
@@ -125,9 +130,16 @@ impl MockConnection {
             fail_after_n: AtomicU32::new(u32::MAX),
             command_count: AtomicU32::new(0),
             virtual_filesystem: RwLock::new(HashMap::new()),
+            atomic_rename: AtomicBool::new(false),
         }
     }
 
+    /// Report support for atomic renames, so uploads stage a temp file and
+    /// move it into place with a command.
+    pub fn set_supports_atomic_rename(&self, supported: bool) {
+        self.atomic_rename.store(supported, Ordering::SeqCst);
+    }
+
     /// Set the result for a specific command.
     pub fn set_command_result(&self, command: impl Into<String>, result: CommandResult) {
         self.command_results.write().insert(command.into(), result);
@@ -158,11 +170,21 @@ impl MockConnection {
         self.commands_executed.read().clone()
     }
 
+    /// Get the execute options passed with each command (command, options).
+    pub fn get_execute_options(&self) -> Vec<(String, ExecuteOptions)> {
+        self.execute_options.read().clone()
+    }
+
     /// Get all files that were uploaded (src, dest pairs).
     pub fn get_uploaded_files(&self) -> Vec<(PathBuf, PathBuf)> {
         self.files_uploaded.read().clone()
     }
 
+    /// Get the transfer options passed with each upload (dest, options).
+    pub fn get_upload_options(&self) -> Vec<(PathBuf, TransferOptions)> {
+        self.upload_options.read().clone()
+    }
+
     /// Get all files that were downloaded.
     pub fn get_downloaded_files(&self) -> Vec<PathBuf> {
         self.files_downloaded.read().clone()
@@ -188,7 +210,9 @@ impl MockConnection {
     /// Reset the mock to its initial state.
     pub fn reset(&self) {
         self.commands_executed.write().clear();
+        self.execute_options.write().clear();
         self.files_uploaded.write().clear();
+        self.upload_options.write().clear();
         self.files_downloaded.write().clear();
         self.command_count.store(0, Ordering::SeqCst);
         self.should_fail.store(false, Ordering::SeqCst);
@@ -216,10 +240,14 @@ impl Connection for MockConnection {
         self.alive.load(Ordering::SeqCst)
     }
 
+    fn supports_atomic_rename(&self) -> bool {
+        self.atomic_rename.load(Ordering::SeqCst)
+    }
+
     async fn execute(
         &self,
         command: &str,
-        _options: Option<ExecuteOptions>,
+        options: Option<ExecuteOptions>,
     ) -> ConnectionResult<CommandResult> {
         if self.check_should_fail() {
             return Err(ConnectionError::ConnectionFailed(
@@ -229,6 +257,11 @@ impl Connection for MockConnection {
 
         self.command_count.fetch_add(1, Ordering::SeqCst);
         self.commands_executed.write().push(command.to_string());
+        if let Some(options) = options {
+            self.execute_options
+                .write()
+                .push((command.to_string(), options));
+        }
 
         // Check for specific command result
         if let Some(result) = self.command_results.read().get(command) {
@@ -243,7 +276,7 @@ impl Connection for MockConnection {
         &self,
         src: &Path,
         dest: &Path,
-        _options: Option<TransferOptions>,
+        options: Option<TransferOptions>,
     ) -> ConnectionResult<()> {
         if self.check_should_fail() {
             return Err(ConnectionError::TransferFailed(
@@ -251,6 +284,12 @@ impl Connection for MockConnection {
             ));
         }
 
+        if let Some(options) = options {
+            self.upload_options
+                .write()
+                .push((dest.to_path_buf(), options));
+        }
+
         self.files_uploaded
             .write()
             .push((src.to_path_buf(), dest.to_path_buf()));
@@ -271,7 +310,7 @@ impl Connection for MockConnection {
         &self,
         content: &[u8],
         dest: &Path,
-        _options: Option<TransferOptions>,
+        options: Option<TransferOptions>,
     ) -> ConnectionResult<()> {
         if self.check_should_fail() {
             return Err(ConnectionError::TransferFailed(
@@ -279,6 +318,12 @@ impl Connection for MockConnection {
             ));
         }
 
+        if let Some(options) = options {
+            self.upload_options
+                .write()
+                .push((dest.to_path_buf(), options));
+        }
+
         self.virtual_filesystem
             .write()
             .insert(dest.to_path_buf(), content.to_vec());
@@ -333,8 +378,13 @@ impl Connection for MockConnection {
         Ok(self.virtual_filesystem.read().contains_key(path))
     }
 
-    async fn is_directory(&self, _path: &Path) -> ConnectionResult<bool> {
-        Ok(false) // Simplified for testing
+    async fn is_directory(&self, path: &Path) -> ConnectionResult<bool> {
+        // A virtual directory exists whenever a virtual file lives beneath it
+        Ok(self
+            .virtual_filesystem
+            .read()
+            .keys()
+            .any(|file| file != path && file.starts_with(path)))
     }
 
     async fn stat(&self, path: &Path) -> ConnectionResult<FileStat> {
@@ -691,7 +741,7 @@ pub struct PlayBuilder {
     handlers: Vec<rustible::executor::task::Handler>,
     vars: IndexMap<String, serde_json::Value>,
     gather_facts: bool,
-    r#become: bool,
+    r#become: Option<bool>,
     become_user: Option<String>,
 }
 
@@ -705,7 +755,7 @@ impl PlayBuilder {
             handlers: Vec::new(),
             vars: IndexMap::new(),
             gather_facts: false,
-            r#become: false,
+            r#become: None,
             become_user: None,
         }
     }
@@ -736,7 +786,7 @@ impl PlayBuilder {
 
     /// Enable privilege escalation.
     pub fn r#become(mut self, enabled: bool) -> Self {
-        self.r#become = enabled;
+        self.r#become = Some(enabled);
         self
     }
 
@@ -1137,6 +1187,9 @@ pub fn test_executor_config() -> ExecutorConfig {
         task_timeout: 30,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     }
 }
 
@@ -1151,6 +1204,9 @@ pub fn test_check_mode_config() -> ExecutorConfig {
         task_timeout: 30,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     }
 }
 
@@ -956,6 +956,54 @@ async fn test_loop_ansible_loop_variables() {
     assert!(!host_result.failed);
 }
 
+#[tokio::test]
+async fn test_loop_last_renders_separators_between_items() {
+    let executor = create_test_executor(vec!["localhost"]);
+
+    let joined = Task::new("Join items", "debug")
+        .arg(
+            "msg",
+            "{{ item }}{% if not ansible_loop.last %},{% endif %}",
+        )
+        .loop_over(vec![json!("a"), json!("b"), json!("c")])
+        .register("joined");
+    let jinja_style = Task::new("Join items with loop", "debug")
+        .arg(
+            "msg",
+            "{{ loop.index }}/{{ loop.length }}/{{ loop.revindex }}{% if not loop.last %},{% endif %}",
+        )
+        .loop_over(vec![json!("a"), json!("b"), json!("c")])
+        .register("indexed");
+
+    let playbook = create_playbook("test", "all", vec![joined, jinja_style]);
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(!results.get("localhost").unwrap().failed);
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    let rendered = |name: &str| -> String {
+        let registered = runtime.get_registered("localhost", name).unwrap();
+        registered
+            .results
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|r| r.msg.clone().unwrap_or_default())
+            .collect()
+    };
+
+    let joined = rendered("joined");
+    assert_eq!(joined, "a,b,c");
+    assert_eq!(joined.matches(',').count(), 2);
+    assert_eq!(rendered("indexed"), "1/3/3,2/3/2,3/3/1");
+
+    // Loop variables do not leak past the loop
+    assert!(runtime
+        .get_merged_vars("localhost")
+        .get("ansible_loop")
+        .is_none());
+}
+
 // ============================================================================
 // Section 7: Conditional Loops - Loop with When
 // ============================================================================
@@ -1010,6 +1058,40 @@ async fn test_loop_with_when_skip_all() {
     assert_eq!(host_result.stats.skipped, 1);
 }
 
+#[tokio::test]
+async fn test_loop_when_evaluated_per_item() {
+    let executor = create_test_executor(vec!["localhost"]);
+
+    let task = Task::new("Per-item condition", "debug")
+        .arg("msg", "Processing {{ item }}")
+        .loop_over(vec![json!(1), json!(2), json!(3)])
+        .when("item > 1")
+        .register("loop_result");
+
+    let playbook = create_playbook("test", "all", vec![task]);
+    let results = executor.run_playbook(&playbook).await.unwrap();
+
+    // The task ran for the remaining items, so it is not skipped overall
+    let host_result = results.get("localhost").unwrap();
+    assert!(!host_result.failed);
+    assert_eq!(host_result.stats.skipped, 0);
+    assert_eq!(host_result.stats.ok, 1);
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    let registered = runtime.get_registered("localhost", "loop_result").unwrap();
+    assert!(!registered.skipped);
+
+    let items = registered.results.as_ref().unwrap();
+    assert_eq!(items.len(), 3);
+    assert!(items[0].skipped);
+    assert_eq!(items[0].data.get("item"), Some(&json!(1)));
+    let executed: Vec<_> = items.iter().filter(|r| !r.skipped).collect();
+    assert_eq!(executed.len(), 2);
+    assert_eq!(executed[0].data.get("item"), Some(&json!(2)));
+    assert_eq!(executed[1].data.get("item"), Some(&json!(3)));
+}
+
 // ============================================================================
 // Section 8: Nested Conditionals - Multiple Tasks with Different Conditions
 // ============================================================================
@@ -554,6 +554,59 @@ async fn test_local_connection_close() {
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+async fn test_local_connection_atomic_upload_never_partial() {
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dest = temp_dir.path().join("app.conf");
+    let versions = [vec![b'a'; 1 << 20], vec![b'b'; 1 << 20]];
+    std::fs::write(&dest, &versions[0]).unwrap();
+
+    // Read the destination continuously while it is being replaced
+    let done = Arc::new(AtomicBool::new(false));
+    let reader = {
+        let done = Arc::clone(&done);
+        let dest = dest.clone();
+        let versions = versions.clone();
+        std::thread::spawn(move || {
+            let mut reads = 0;
+            while !done.load(Ordering::SeqCst) {
+                let content = std::fs::read(&dest).unwrap();
+                assert!(
+                    versions.contains(&content),
+                    "observed a partial file of {} bytes",
+                    content.len()
+                );
+                reads += 1;
+            }
+            reads
+        })
+    };
+
+    let conn = LocalConnection::new();
+    assert!(conn.supports_atomic_rename());
+    for i in 0..20 {
+        rustible::connection::atomic::upload_content(
+            &conn,
+            &versions[i % 2],
+            &dest,
+            Some(TransferOptions::new().with_mode(0o640)),
+        )
+        .await
+        .unwrap();
+    }
+    done.store(true, Ordering::SeqCst);
+    assert!(reader.join().unwrap() > 0);
+
+    assert_eq!(std::fs::read(&dest).unwrap(), versions[1]);
+    let mode = std::fs::metadata(&dest).unwrap().permissions().mode() & 0o7777;
+    assert_eq!(mode, 0o640);
+    // Only the destination remains, no temp files
+    assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+}
+
 // ============================================================================
 // ConnectionConfig Tests
 // ============================================================================
@@ -1561,6 +1614,7 @@ async fn test_upload_with_backup_option() {
         group: None,
         create_dirs: false,
         backup: true,
+        preserve_mode: false,
     };
 
     conn.upload(&src_path, &dst_path, Some(options))
@@ -1594,6 +1648,7 @@ async fn test_upload_content_with_backup_option() {
         group: None,
         create_dirs: false,
         backup: true,
+        preserve_mode: false,
     };
 
     conn.upload_content(b"new data", &dst_path, Some(options))
@@ -2210,6 +2265,7 @@ fn test_transfer_options_all_fields() {
         group: Some("www-data".to_string()),
         create_dirs: true,
         backup: true,
+        preserve_mode: false,
     };
 
     assert_eq!(options.mode, Some(0o700));
@@ -46,7 +46,7 @@ fn test_copy_with_src_dest_basic() {
     assert!(result.changed);
     assert!(dest.exists());
     assert_eq!(fs::read_to_string(&dest).unwrap(), "Source file content");
-    assert!(result.msg.contains("Copied"));
+    assert!(result.msg.contains("Created"));
 }
 
 #[test]
@@ -569,7 +569,7 @@ fn test_copy_check_mode() {
     let result = module.check(&params, &context).unwrap();
 
     assert!(result.changed);
-    assert!(result.msg.contains("Would copy"));
+    assert!(result.msg.contains("Would create"));
     assert!(!dest.exists()); // File should not be created in check mode
 }
 
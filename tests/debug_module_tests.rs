@@ -1,4 +1,8 @@
 // Integration tests for the debug module
+use rustible::executor::playbook::{Play, Playbook};
+use rustible::executor::runtime::RuntimeContext;
+use rustible::executor::task::Task;
+use rustible::executor::{Executor, ExecutorConfig};
 use rustible::modules::{debug::DebugModule, Module, ModuleContext, ModuleParams};
 use serde_json::Value;
 use std::collections::HashMap;
@@ -258,3 +262,47 @@ fn test_debug_with_json_message() {
     // Should be formatted as JSON
     assert!(result.msg.contains("status") || result.msg.contains("ok"));
 }
+
+#[tokio::test]
+async fn test_debug_var_prints_nested_dict_as_indented_json() {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("localhost".to_string(), None);
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+    let config = serde_json::json!({
+        "server": {"name": "web", "ports": [80, 443]},
+        "enabled": true
+    });
+    let mut play = Play::new("Debug nested", "all");
+    play.gather_facts = false;
+    play.set_var("config", config.clone());
+    play.add_task(
+        Task::new("Show config", "debug")
+            .arg("var", "config")
+            .register("shown"),
+    );
+    play.add_task(
+        Task::new("Show config as msg", "debug")
+            .arg("msg", "{{ config }}")
+            .register("message"),
+    );
+    let mut playbook = Playbook::new("debug");
+    playbook.add_play(play);
+
+    executor.run_playbook(&playbook).await.unwrap();
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+
+    let shown = runtime.get_registered("localhost", "shown").unwrap();
+    let msg = shown.msg.as_deref().unwrap();
+    let json = msg.strip_prefix("config: ").unwrap();
+    assert!(!msg.contains("Object") && !msg.contains("String("));
+    assert!(json.contains("\n  \"server\": {\n    \"name\": \"web\""));
+    assert_eq!(serde_json::from_str::<Value>(json).unwrap(), config);
+
+    let message = runtime.get_registered("localhost", "message").unwrap();
+    let msg = message.msg.as_deref().unwrap();
+    assert!(msg.contains('\n'));
+    assert_eq!(serde_json::from_str::<Value>(msg).unwrap(), config);
+}
@@ -983,7 +983,7 @@ async fn test_delegate_with_become() {
 
     let mut task = Task::new("Privileged delegate", "command").arg("cmd", "whoami");
     task.delegate_to = Some("db1".to_string());
-    task.r#become = true;
+    task.r#become = Some(true);
     task.become_user = Some("root".to_string());
     play.add_task(task);
 
@@ -190,6 +190,60 @@ async fn test_delegate_with_register() {
     assert!(localhost_registered.is_none());
 }
 
+#[tokio::test]
+async fn test_delegated_loop_and_retry_register_on_original_host() {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("web1".to_string(), None);
+    runtime.add_host("db1".to_string(), None);
+
+    let runtime = Arc::new(RwLock::new(runtime));
+    let handlers = Arc::new(RwLock::new(HashMap::new()));
+    let notified = Arc::new(Mutex::new(HashSet::new()));
+    let parallelization = Arc::new(ParallelizationManager::new());
+    let ctx = ExecutionContext::new("web1");
+
+    // Looped task delegated to db1 while iterating web1
+    let mut looped = Task::new("Check replicas", "command").arg("cmd", "pg_isready");
+    looped.loop_items = Some(vec![serde_json::json!("a"), serde_json::json!("b")]);
+    looped.delegate_to = Some("db1".to_string());
+    looped.register = Some("replicas".to_string());
+
+    // Retried task delegated to db1 while iterating web1
+    let mut retried = Task::new("Wait for db", "command").arg("cmd", "pg_isready");
+    retried.until = Some("db_ready.rc == 0".to_string());
+    retried.retries = Some(1);
+    retried.delay = Some(0);
+    retried.delegate_to = Some("db1".to_string());
+    retried.register = Some("db_ready".to_string());
+
+    for task in [&looped, &retried] {
+        let result = task
+            .execute(&ctx, &runtime, &handlers, &notified, &parallelization)
+            .await
+            .unwrap();
+        assert_ne!(result.status, TaskStatus::Failed, "{:?}", result.msg);
+    }
+
+    {
+        let rt = runtime.read().await;
+        for name in ["replicas", "db_ready"] {
+            assert!(rt.get_registered("web1", name).is_some(), "{}", name);
+            assert!(rt.get_registered("db1", name).is_none(), "{}", name);
+        }
+    }
+
+    // A later task on web1 reads both registered values
+    let check = Task::new("Use results", "assert").arg(
+        "that",
+        serde_json::json!(["replicas.results | length == 2", "db_ready.rc == 0"]),
+    );
+    let result = check
+        .execute(&ctx, &runtime, &handlers, &notified, &parallelization)
+        .await
+        .unwrap();
+    assert_eq!(result.status, TaskStatus::Ok, "{:?}", result.msg);
+}
+
 #[tokio::test]
 async fn test_no_delegation() {
     // Test that tasks without delegate_to work normally
@@ -660,6 +660,68 @@ fn test_file_diff_remove_file() {
     assert_eq!(d.after, "absent");
 }
 
+#[test]
+fn test_file_absent_diff_shows_removed_content() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("app.conf");
+    fs::write(&path, "listen 80\nworkers 4\n").unwrap();
+
+    let module = FileModule;
+    let mut params: ModuleParams = HashMap::new();
+    params.insert(
+        "path".to_string(),
+        serde_json::json!(path.to_str().unwrap()),
+    );
+    params.insert("state".to_string(), serde_json::json!("absent"));
+
+    // Check mode reports the removal without deleting
+    let context = ModuleContext::default()
+        .with_check_mode(true)
+        .with_diff_mode(true);
+    let result = module.execute(&params, &context).unwrap();
+    assert!(result.changed);
+    assert!(path.exists());
+    let d = result.diff.unwrap();
+    assert_eq!(d.before, "listen 80\nworkers 4\n");
+    assert_eq!(d.after, "");
+
+    let context = ModuleContext::default().with_diff_mode(true);
+    let result = module.execute(&params, &context).unwrap();
+    assert!(result.changed);
+    assert!(!path.exists());
+    let d = result.diff.unwrap();
+    assert_eq!(d.before, "listen 80\nworkers 4\n");
+    assert_eq!(d.after, "");
+    let details = d.details.unwrap();
+    assert!(details.contains("+++ /dev/null"));
+    assert!(details.contains("-listen 80"));
+    assert!(details.contains("-workers 4"));
+}
+
+#[test]
+fn test_file_absent_diff_binary_file_names_path_only() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("blob.bin");
+    fs::write(&path, [0u8, 159, 146, 150]).unwrap();
+
+    let module = FileModule;
+    let mut params: ModuleParams = HashMap::new();
+    params.insert(
+        "path".to_string(),
+        serde_json::json!(path.to_str().unwrap()),
+    );
+    params.insert("state".to_string(), serde_json::json!("absent"));
+
+    let context = ModuleContext::default().with_diff_mode(true);
+    let result = module.execute(&params, &context).unwrap();
+
+    assert!(!path.exists());
+    let d = result.diff.unwrap();
+    assert!(d.before.contains(path.to_str().unwrap()));
+    assert!(d.before.contains("content not shown"));
+    assert!(d.details.is_none());
+}
+
 #[test]
 fn test_file_diff_remove_directory() {
     let temp = TempDir::new().unwrap();
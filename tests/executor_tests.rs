@@ -7,14 +7,22 @@
 //! - Handler notification and execution
 //! - Parallel execution strategies
 
+mod common;
+
 use std::collections::HashMap;
+use std::sync::Arc;
+
+use common::MockConnection;
 
+use rustible::callback::manager::CallbackManager;
+use rustible::callback::plugins::ProfileTasksCallback;
 use rustible::executor::playbook::{Play, Playbook};
 use rustible::executor::runtime::{ExecutionContext, RegisteredResult, RuntimeContext};
 use rustible::executor::task::{Handler, Task, TaskResult, TaskStatus};
 use rustible::executor::{
     DependencyGraph, ExecutionStats, ExecutionStrategy, Executor, ExecutorConfig, HostResult,
 };
+use rustible::inventory::{Group, Host, Inventory, InventoryPlugin, InventoryResult};
 
 // ============================================================================
 // Executor Configuration Tests
@@ -48,6 +56,9 @@ fn test_executor_config_custom() {
         task_timeout: 600,
         gather_facts: false,
         extra_vars,
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     assert_eq!(config.forks, 10);
@@ -511,7 +522,7 @@ fn test_parse_playbook_with_become() {
 
     let playbook = Playbook::parse(yaml, None).unwrap();
 
-    assert!(playbook.plays[0].r#become);
+    assert_eq!(playbook.plays[0].r#become, Some(true));
     assert_eq!(playbook.plays[0].become_user, Some("root".to_string()));
 }
 
@@ -638,6 +649,40 @@ async fn test_executor_run_simple_playbook() {
     assert!(!host_result.failed);
 }
 
+#[tokio::test]
+async fn test_executor_closes_connection_pool_after_playbook() {
+    use rustible::connection::{ConnectionConfig, ConnectionFactory};
+
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("localhost".to_string(), None);
+
+    let factory = ConnectionFactory::new(ConnectionConfig::default());
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime)
+        .with_connection_factory(factory.clone());
+
+    let mut playbook = Playbook::new("Connection Teardown");
+    let mut play = Play::new("Test Play", "all");
+    play.gather_facts = false;
+    play.add_task(
+        Task::new("Echo", "command")
+            .arg("cmd", "echo pooled")
+            .register("echo"),
+    );
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(!results["localhost"].failed);
+
+    // The task ran over a pooled connection, which is closed afterwards
+    let rt = executor.runtime();
+    let registered = rt.read().await.get_registered("localhost", "echo").cloned();
+    assert_eq!(
+        registered.unwrap().stdout.as_deref().map(str::trim),
+        Some("pooled")
+    );
+    assert_eq!(factory.pool_stats().active_connections, 0);
+}
+
 #[tokio::test]
 async fn test_executor_summarize_results() {
     let mut results = HashMap::new();
@@ -828,6 +873,55 @@ async fn test_full_playbook_execution() {
     assert!(web1.stats.changed >= db1.stats.changed || web1.stats.ok >= db1.stats.ok);
 }
 
+#[tokio::test]
+async fn test_ansible_version_and_min_rustible_version() {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("server1".to_string(), None);
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+    let yaml = r#"
+- name: Version check
+  hosts: all
+  gather_facts: false
+  min_rustible_version: 0.1
+  tasks:
+    - name: Show version
+      debug:
+        msg: "ansible {{ ansible_version.full }} / rustible {{ rustible_version }}"
+      register: version_out
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(!results.get("server1").unwrap().failed);
+
+    {
+        let runtime = executor.runtime();
+        let rt = runtime.read().await;
+        let registered = rt.get_registered("server1", "version_out").unwrap();
+        assert_eq!(
+            registered.msg.as_deref(),
+            Some(format!("ansible 2.16.0 / rustible {}", env!("CARGO_PKG_VERSION")).as_str())
+        );
+    }
+
+    let yaml = r#"
+- name: Needs a future release
+  hosts: all
+  gather_facts: false
+  min_rustible_version: "999.0"
+  tasks:
+    - debug:
+        msg: never runs
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+    let err = executor.run_playbook(&playbook).await.unwrap_err();
+    assert!(
+        err.to_string().contains("requires rustible 999.0 or newer"),
+        "{}",
+        err
+    );
+}
+
 #[tokio::test]
 async fn test_conditional_task_execution() {
     let mut runtime = RuntimeContext::new();
@@ -869,6 +963,158 @@ async fn test_conditional_task_execution() {
     assert!(host_result.stats.skipped >= 1);
 }
 
+#[tokio::test]
+async fn test_task_vars_scoped_to_task() {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("server1".to_string(), None);
+
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+    let yaml = r#"
+- name: Task vars
+  hosts: all
+  gather_facts: false
+  tasks:
+    - name: Use task vars
+      set_fact:
+        used_port: "{{ port }}"
+      vars:
+        port: 8080
+      when: port == 8080
+    - name: Task vars do not leak
+      set_fact:
+        leaked: true
+      when: port is defined
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    let host_result = results.get("server1").unwrap();
+    assert!(!host_result.failed);
+    assert_eq!(host_result.stats.skipped, 1);
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    assert_eq!(
+        runtime.get_host_fact("server1", "used_port"),
+        Some(serde_json::json!(8080))
+    );
+    assert_eq!(runtime.get_host_fact("server1", "leaked"), None);
+    assert_eq!(runtime.get_var("port", Some("server1")), None);
+}
+
+#[tokio::test]
+async fn test_task_vars_stay_per_host_when_delegated() {
+    let mut runtime = RuntimeContext::new();
+    for host in ["web1", "web2", "web3", "web4"] {
+        runtime.add_host(host.to_string(), None);
+    }
+
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+    let yaml = r#"
+- name: Delegated task vars
+  hosts: all
+  gather_facts: false
+  tasks:
+    - name: Record own name
+      set_fact:
+        seen_name: "{{ my_name }}"
+      vars:
+        my_name: "{{ inventory_hostname }}"
+      delegate_to: localhost
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(results.values().all(|r| !r.failed));
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    for host in ["web1", "web2", "web3", "web4"] {
+        assert_eq!(
+            runtime.get_host_fact(host, "seen_name"),
+            Some(serde_json::json!(host))
+        );
+    }
+    assert_eq!(runtime.get_var("my_name", Some("localhost")), None);
+}
+
+#[tokio::test]
+async fn test_fully_qualified_builtin_modules_run_natively() {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("server1".to_string(), None);
+
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+    let yaml = r#"
+- name: FQCN
+  hosts: all
+  gather_facts: false
+  tasks:
+    - name: Short name
+      debug:
+        msg: "hello"
+      register: short
+    - name: Fully-qualified name
+      ansible.builtin.debug:
+        msg: "hello"
+      register: fqcn
+    - ansible.builtin.set_fact:
+        fact_set: true
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(!results.get("server1").unwrap().failed);
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    let short = runtime.get_var("short", Some("server1")).unwrap();
+    let fqcn = runtime.get_var("fqcn", Some("server1")).unwrap();
+    assert_eq!(fqcn, short);
+    assert_eq!(fqcn["msg"], serde_json::json!("hello"));
+    assert_eq!(
+        runtime.get_host_fact("server1", "fact_set"),
+        Some(serde_json::json!(true))
+    );
+}
+
+#[tokio::test]
+async fn test_profile_report_ranks_slow_task_first() {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("server1".to_string(), None);
+    runtime.add_host("server2".to_string(), None);
+
+    let profiler = Arc::new(ProfileTasksCallback::summary_only());
+    let callbacks = Arc::new(CallbackManager::new());
+    callbacks
+        .register_default("profile_tasks", profiler.clone())
+        .await;
+
+    let executor =
+        Executor::with_runtime(ExecutorConfig::default(), runtime).with_callbacks(callbacks);
+
+    let mut playbook = Playbook::new("Profile");
+    let mut play = Play::new("Timed", "all");
+    play.gather_facts = false;
+    play.add_task(Task::new("Quick task", "debug").arg("msg", "fast"));
+    play.add_task(Task::new("Slow task", "pause").arg("seconds", 1));
+    play.add_task(Task::new("Another quick task", "debug").arg("msg", "fast"));
+    playbook.add_play(play);
+
+    executor.run_playbook(&playbook).await.unwrap();
+
+    let report = profiler.report();
+    assert_eq!(report.tasks.len(), 3);
+    assert_eq!(report.tasks[0].task_name, "Slow task");
+    assert_eq!(report.tasks[0].host_count, 2);
+    assert!(report.tasks[0].max_duration >= std::time::Duration::from_secs(1));
+    assert!(report.tasks[1].max_duration < std::time::Duration::from_secs(1));
+    assert_eq!(report.hosts.len(), 2);
+    assert!(report.wall_time >= report.tasks[0].max_duration);
+}
+
 #[tokio::test]
 async fn test_loop_task_execution() {
     let mut runtime = RuntimeContext::new();
@@ -899,3 +1145,706 @@ async fn test_loop_task_execution() {
     // Loop ran successfully
     assert!(!host_result.failed);
 }
+
+/// Dynamic inventory stub that discovers an extra host on every parse
+#[derive(Debug, Default)]
+struct GrowingInventoryPlugin {
+    invocations: std::sync::atomic::AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl InventoryPlugin for GrowingInventoryPlugin {
+    fn name(&self) -> &str {
+        "growing"
+    }
+
+    async fn parse(&self) -> InventoryResult<Inventory> {
+        let count = self
+            .invocations
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+            + 1;
+
+        let mut inventory = Inventory::new();
+        let mut group = Group::new("webservers");
+        for i in 1..=count {
+            let name = format!("web{}", i);
+            group.add_host(name.clone());
+            let mut host = Host::new(name);
+            host.add_to_group("webservers");
+            inventory.add_host(host).unwrap();
+        }
+        inventory.add_group(group).unwrap();
+        Ok(inventory)
+    }
+}
+
+#[tokio::test]
+async fn test_meta_refresh_inventory_adds_hosts_for_later_plays() {
+    let source = std::sync::Arc::new(GrowingInventoryPlugin::default());
+
+    let mut runtime = RuntimeContext::new();
+    let initial = source.parse().await.unwrap();
+    assert_eq!(runtime.merge_inventory(&initial), 1);
+    runtime.set_host_fact(
+        "web1",
+        "ansible_os_family".to_string(),
+        serde_json::json!("Debian"),
+    );
+    runtime.set_inventory_source(source.clone());
+
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+    let mut playbook = Playbook::new("Refresh Inventory");
+
+    let mut refresh_play = Play::new("Refresh", "webservers");
+    refresh_play.gather_facts = false;
+    refresh_play
+        .add_task(Task::new("Refresh inventory", "meta").arg("_raw_params", "refresh_inventory"));
+    playbook.add_play(refresh_play);
+
+    let mut web_play = Play::new("Configure", "webservers");
+    web_play.gather_facts = false;
+    web_play.add_task(Task::new("Greet", "debug").arg("msg", "hello"));
+    playbook.add_play(web_play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+
+    assert!(results.contains_key("web1"));
+    assert!(results.contains_key("web2"));
+    assert!(!results.values().any(|r| r.failed));
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    assert_eq!(
+        runtime.get_group_hosts("webservers").unwrap(),
+        vec!["web1".to_string(), "web2".to_string()]
+    );
+    assert_eq!(
+        runtime.get_host_fact("web1", "ansible_os_family"),
+        Some(serde_json::json!("Debian"))
+    );
+}
+
+#[tokio::test]
+async fn test_meta_refresh_inventory_runs_once_per_play() {
+    let source = std::sync::Arc::new(GrowingInventoryPlugin::default());
+
+    let mut runtime = RuntimeContext::new();
+    source.parse().await.unwrap();
+    let initial = source.parse().await.unwrap();
+    assert_eq!(runtime.merge_inventory(&initial), 2);
+    runtime.set_inventory_source(source.clone());
+
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+    let mut playbook = Playbook::new("Refresh Inventory");
+    let mut play = Play::new("Refresh", "webservers");
+    play.gather_facts = false;
+    play.add_task(Task::new("Refresh inventory", "meta").arg("_raw_params", "refresh_inventory"));
+    playbook.add_play(play);
+
+    executor.run_playbook(&playbook).await.unwrap();
+
+    // Two hosts ran the play, but the source was parsed only once more
+    assert_eq!(
+        source.invocations.load(std::sync::atomic::Ordering::SeqCst),
+        3
+    );
+}
+
+#[tokio::test]
+async fn test_runtime_from_inventory_refreshes_from_its_sources() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("hosts.ini");
+    std::fs::write(&path, "[webservers]\nweb1 ansible_connection=local\n").unwrap();
+
+    let inventory = Inventory::load(&path).unwrap();
+    let runtime = RuntimeContext::from_inventory(&inventory);
+    assert!(runtime.inventory_source().is_some());
+
+    // A host provisioned after the inventory was loaded
+    std::fs::write(
+        &path,
+        "[webservers]\nweb1 ansible_connection=local\nweb2 ansible_connection=local\n",
+    )
+    .unwrap();
+
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+    let mut playbook = Playbook::new("Refresh Inventory");
+    let mut refresh_play = Play::new("Refresh", "webservers");
+    refresh_play.gather_facts = false;
+    refresh_play
+        .add_task(Task::new("Refresh inventory", "meta").arg("_raw_params", "refresh_inventory"));
+    playbook.add_play(refresh_play);
+
+    let mut web_play = Play::new("Configure", "webservers");
+    web_play.gather_facts = false;
+    web_play.add_task(Task::new("Greet", "debug").arg("msg", "hello"));
+    playbook.add_play(web_play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+
+    assert!(results.contains_key("web2"));
+    assert!(!results.values().any(|r| r.failed));
+}
+
+#[tokio::test]
+async fn test_meta_refresh_inventory_without_source_is_skipped() {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("web1".to_string(), Some("webservers"));
+
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+    let mut playbook = Playbook::new("Refresh Inventory");
+    let mut play = Play::new("Refresh", "webservers");
+    play.gather_facts = false;
+    play.add_task(Task::new("Refresh inventory", "meta").arg("_raw_params", "refresh_inventory"));
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+
+    let web1 = &results["web1"];
+    assert!(!web1.failed);
+    assert_eq!(web1.stats.skipped, 1);
+    assert_eq!(web1.stats.ok, 0);
+}
+
+#[tokio::test]
+async fn test_connection_failure_marks_task_unreachable() {
+    use rustible::executor::parallelization::ParallelizationManager;
+    use tokio::sync::{Mutex, RwLock};
+
+    let connection = Arc::new(MockConnection::new("web1"));
+    connection.set_should_fail(true);
+
+    let runtime = Arc::new(RwLock::new(RuntimeContext::new()));
+    let handlers = Arc::new(RwLock::new(HashMap::new()));
+    let notified = Arc::new(Mutex::new(std::collections::HashSet::new()));
+    let parallelization = Arc::new(ParallelizationManager::new());
+
+    // ignore_errors must not turn an unreachable host into a success
+    let mut task = Task::new("Check uptime", "command").arg("cmd", "uptime");
+    task.ignore_errors = true;
+
+    let ctx = ExecutionContext::new("web1").with_connection(connection.clone());
+    let result = task
+        .execute(&ctx, &runtime, &handlers, &notified, &parallelization)
+        .await
+        .unwrap();
+
+    assert_eq!(result.status, TaskStatus::Unreachable, "{:?}", result.msg);
+    assert!(result.msg.unwrap().contains("Connection failed"));
+    assert!(connection.get_commands().is_empty());
+}
+
+#[tokio::test]
+async fn test_set_fact_preserves_native_types() {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("server1".to_string(), None);
+
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+    let yaml = r#"
+- name: Typed facts
+  hosts: all
+  gather_facts: false
+  vars:
+    packages: [nginx, redis, postgresql]
+  tasks:
+    - name: Count packages
+      set_fact: package_count={{ packages | length }}
+    - name: Copy the package list
+      set_fact:
+        selected: "{{ packages }}"
+        extras: "['curl', 'jq']"
+    - name: Integer comparison
+      set_fact:
+        counted: true
+      when: package_count == 3
+    - name: List membership
+      set_fact:
+        has_redis: true
+      when: "'redis' in selected"
+    - name: Filters on typed facts
+      set_fact:
+        many_extras: true
+      when: extras | length > 1
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    let host_result = results.get("server1").unwrap();
+    assert!(!host_result.failed);
+    assert_eq!(host_result.stats.skipped, 0);
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    assert_eq!(
+        runtime.get_host_fact("server1", "package_count"),
+        Some(serde_json::json!(3))
+    );
+    assert_eq!(
+        runtime.get_host_fact("server1", "selected"),
+        Some(serde_json::json!(["nginx", "redis", "postgresql"]))
+    );
+    assert_eq!(
+        runtime.get_host_fact("server1", "extras"),
+        Some(serde_json::json!(["curl", "jq"]))
+    );
+    assert_eq!(
+        runtime.get_host_fact("server1", "counted"),
+        Some(serde_json::json!(true))
+    );
+    assert_eq!(
+        runtime.get_host_fact("server1", "has_redis"),
+        Some(serde_json::json!(true))
+    );
+    assert_eq!(
+        runtime.get_host_fact("server1", "many_extras"),
+        Some(serde_json::json!(true))
+    );
+}
+
+#[tokio::test]
+async fn test_safe_mode_blocks_destructive_tasks_unless_allowlisted() {
+    use rustible::executor::parallelization::ParallelizationManager;
+    use rustible::executor::SafeMode;
+    use tokio::sync::{Mutex, RwLock};
+
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("release.tar.gz");
+    std::fs::write(&path, "old release").unwrap();
+
+    let runtime = Arc::new(RwLock::new(RuntimeContext::new()));
+    let handlers = Arc::new(RwLock::new(HashMap::new()));
+    let notified = Arc::new(Mutex::new(std::collections::HashSet::new()));
+    let parallelization = Arc::new(ParallelizationManager::new());
+
+    let task = Task::new("Remove old release", "file")
+        .arg("path", path.to_str().unwrap())
+        .arg("state", "absent");
+
+    let ctx = ExecutionContext::new("localhost").with_safe_mode(Some(Arc::new(SafeMode::new())));
+    let result = task
+        .execute(&ctx, &runtime, &handlers, &notified, &parallelization)
+        .await
+        .unwrap();
+
+    assert_eq!(result.status, TaskStatus::Failed);
+    let msg = result.msg.unwrap();
+    assert!(msg.contains("Remove old release"), "{}", msg);
+    assert!(msg.contains("file state=absent"), "{}", msg);
+    assert!(msg.contains("allowlist"), "{}", msg);
+    assert!(path.exists());
+
+    let ctx = ExecutionContext::new("localhost")
+        .with_safe_mode(Some(Arc::new(SafeMode::new().allow("file"))));
+    let result = task
+        .execute(&ctx, &runtime, &handlers, &notified, &parallelization)
+        .await
+        .unwrap();
+
+    assert_eq!(result.status, TaskStatus::Changed);
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn test_registered_result_exposes_module_data_at_top_level() {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("server1".to_string(), None);
+
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+    let yaml = r#"
+- name: Registered results
+  hosts: all
+  gather_facts: false
+  tasks:
+    - name: Run a command
+      command: uptime
+      register: result
+    - name: Stat a file
+      stat:
+        path: /etc/hosts
+      register: hosts_file
+    - name: Use registered values
+      set_fact:
+        command_rc: "{{ result.rc }}"
+        command_stdout: "{{ result.stdout }}"
+        hosts_size: "{{ hosts_file.stat.size }}"
+    - name: Output is defined
+      set_fact:
+        stdout_defined: true
+      when: result.stdout is defined and result.rc == 0
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(!results.get("server1").unwrap().failed);
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    let registered = runtime.get_registered("server1", "result").unwrap();
+    assert_eq!(registered.rc, Some(0));
+    assert!(registered.data.get("data").is_none());
+
+    assert_eq!(
+        runtime.get_host_fact("server1", "command_rc"),
+        Some(serde_json::json!(0))
+    );
+    assert_eq!(
+        runtime.get_host_fact("server1", "command_stdout"),
+        Some(serde_json::json!(""))
+    );
+    assert_eq!(
+        runtime.get_host_fact("server1", "hosts_size"),
+        Some(serde_json::json!(1024))
+    );
+    assert_eq!(
+        runtime.get_host_fact("server1", "stdout_defined"),
+        Some(serde_json::json!(true))
+    );
+}
+
+async fn run_once_counts(strategy: ExecutionStrategy) -> HashMap<String, usize> {
+    let mut runtime = RuntimeContext::new();
+    for host in ["web1", "web2", "web3"] {
+        runtime.add_host(host.to_string(), Some("web"));
+    }
+
+    let profiler = Arc::new(ProfileTasksCallback::summary_only());
+    let callbacks = Arc::new(CallbackManager::new());
+    callbacks
+        .register_default("profile_tasks", profiler.clone())
+        .await;
+
+    let config = ExecutorConfig {
+        strategy,
+        gather_facts: false,
+        ..Default::default()
+    };
+    let executor = Executor::with_runtime(config, runtime).with_callbacks(callbacks);
+
+    let yaml = r#"
+- name: Rolling deploy
+  hosts: web
+  gather_facts: false
+  serial: 2
+  tasks:
+    - name: Configure app
+      debug:
+        msg: configure
+    - name: Run migrations
+      command: migrate
+      run_once: true
+      register: migration
+      notify: restart app
+    - name: Report migration
+      debug:
+        msg: "rc={{ migration.rc }}"
+  handlers:
+    - name: restart app
+      debug:
+        msg: restart
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(results.values().all(|r| !r.failed));
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    for host in ["web1", "web2", "web3"] {
+        assert_eq!(
+            runtime.get_registered(host, "migration").and_then(|r| r.rc),
+            Some(0),
+            "{} should see the run_once result",
+            host
+        );
+    }
+
+    profiler
+        .report()
+        .tasks
+        .into_iter()
+        .map(|task| (task.task_name, task.host_timings.len()))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_run_once_and_handlers_match_across_strategies() {
+    let linear = run_once_counts(ExecutionStrategy::Linear).await;
+    let free = run_once_counts(ExecutionStrategy::Free).await;
+
+    assert_eq!(linear, free);
+    assert_eq!(linear["Configure app"], 3);
+    // Once per serial batch, like Ansible
+    assert_eq!(linear["Run migrations"], 2);
+    assert_eq!(linear["Report migration"], 3);
+    // Handlers are flushed per batch on that batch's hosts
+    assert_eq!(linear["restart app"], 3);
+}
+
+#[tokio::test]
+async fn test_become_shell_task_runs_through_sudo() {
+    use rustible::executor::parallelization::ParallelizationManager;
+    use tokio::sync::{Mutex, RwLock};
+
+    let mock = Arc::new(MockConnection::new("web1"));
+    let runtime = Arc::new(RwLock::new(RuntimeContext::new()));
+    let handlers = Arc::new(RwLock::new(HashMap::new()));
+    let notified = Arc::new(Mutex::new(std::collections::HashSet::new()));
+    let parallelization = Arc::new(ParallelizationManager::new());
+
+    let mut task = Task::new("Restart app", "shell").arg("cmd", "systemctl restart app");
+    task.r#become = Some(true);
+    task.become_method = Some("sudo".to_string());
+    task.become_user = Some("deploy".to_string());
+
+    let ctx = ExecutionContext::new("web1").with_connection(mock.clone());
+    let result = task
+        .execute(&ctx, &runtime, &handlers, &notified, &parallelization)
+        .await
+        .unwrap();
+
+    assert_eq!(result.status, TaskStatus::Changed);
+    let executed = mock.get_execute_options();
+    let (_, options) = executed
+        .iter()
+        .find(|(c, _)| c.contains("systemctl restart app"))
+        .unwrap_or_else(|| panic!("{:?}", mock.get_commands()));
+    assert!(options.escalate);
+    assert_eq!(options.escalate_user.as_deref(), Some("deploy"));
+    assert_eq!(options.escalate_method.as_deref(), Some("sudo"));
+}
+
+#[tokio::test]
+async fn test_command_warnings_reach_task_result() {
+    use rustible::executor::parallelization::ParallelizationManager;
+    use tokio::sync::{Mutex, RwLock};
+
+    let mock = Arc::new(MockConnection::new("web1"));
+    let runtime = Arc::new(RwLock::new(RuntimeContext::new()));
+    let handlers = Arc::new(RwLock::new(HashMap::new()));
+    let notified = Arc::new(Mutex::new(std::collections::HashSet::new()));
+    let parallelization = Arc::new(ParallelizationManager::new());
+
+    let mut task = Task::new("Install nginx", "shell").arg("cmd", "apt-get install -y nginx");
+    task.register = Some("install".to_string());
+
+    let ctx = ExecutionContext::new("web1").with_connection(mock.clone());
+    let result = task
+        .execute(&ctx, &runtime, &handlers, &notified, &parallelization)
+        .await
+        .unwrap();
+
+    // Warnings don't change the status
+    assert_eq!(result.status, TaskStatus::Changed);
+    assert_eq!(result.warnings.len(), 1, "{:?}", result.warnings);
+    assert!(result.warnings[0].contains("apt module"));
+
+    let registered = runtime
+        .read()
+        .await
+        .get_registered("web1", "install")
+        .cloned()
+        .unwrap();
+    assert_eq!(
+        registered.data["warnings"],
+        serde_json::json!(result.warnings)
+    );
+}
+
+#[tokio::test]
+async fn test_become_defaults_escalate_tasks_without_become() {
+    use rustible::executor::parallelization::ParallelizationManager;
+    use rustible::executor::runtime::BecomeDefaults;
+    use tokio::sync::{Mutex, RwLock};
+
+    let yaml = r#"
+- name: Configure web
+  hosts: web1
+  gather_facts: false
+  tasks:
+    - name: Install nginx
+      shell:
+        cmd: apt-get install -y nginx
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+    let task = playbook.plays[0].tasks[0].clone();
+    assert_eq!(task.r#become, None);
+
+    // Equivalent of `--become --become-user root`
+    let config = ExecutorConfig {
+        become_defaults: BecomeDefaults {
+            enabled: true,
+            method: Some("sudo".to_string()),
+            user: Some("root".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mock = Arc::new(MockConnection::new("web1"));
+    let runtime = Arc::new(RwLock::new(RuntimeContext::new()));
+    let handlers = Arc::new(RwLock::new(HashMap::new()));
+    let notified = Arc::new(Mutex::new(std::collections::HashSet::new()));
+    let parallelization = Arc::new(ParallelizationManager::new());
+
+    let ctx = ExecutionContext::new("web1")
+        .with_connection(mock.clone())
+        .with_become_defaults(config.become_defaults.clone());
+    let result = task
+        .execute(&ctx, &runtime, &handlers, &notified, &parallelization)
+        .await
+        .unwrap();
+
+    assert_eq!(result.status, TaskStatus::Changed, "{:?}", result.msg);
+    let executed = mock.get_execute_options();
+    let (_, options) = executed
+        .iter()
+        .find(|(c, _)| c.contains("apt-get install -y nginx"))
+        .unwrap_or_else(|| panic!("{:?}", mock.get_commands()));
+    assert!(options.escalate);
+    assert_eq!(options.escalate_user.as_deref(), Some("root"));
+    assert_eq!(options.escalate_method.as_deref(), Some("sudo"));
+}
+
+#[tokio::test]
+async fn test_play_and_block_become_reach_tasks() {
+    use rustible::executor::parallelization::ParallelizationManager;
+    use rustible::executor::runtime::BecomeDefaults;
+    use tokio::sync::{Mutex, RwLock};
+
+    let yaml = r#"
+- name: Configure web
+  hosts: web1
+  gather_facts: false
+  become: true
+  become_user: deploy
+  become_method: su
+  tasks:
+    - name: Restart app
+      shell:
+        cmd: systemctl restart app
+    - block:
+        - name: Read config
+          shell:
+            cmd: cat /etc/app.conf
+      become: false
+    - block:
+        - name: Rotate logs
+          shell:
+            cmd: logrotate /etc/logrotate.conf
+      become_user: root
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+    let tasks = &playbook.plays[0].tasks;
+
+    // Play-level settings
+    assert_eq!(tasks[0].r#become, Some(true));
+    assert_eq!(tasks[0].become_user.as_deref(), Some("deploy"));
+    assert_eq!(tasks[0].become_method.as_deref(), Some("su"));
+    // A block's `become: false` wins over the play
+    assert_eq!(tasks[1].r#become, Some(false));
+    // A block's user wins over the play, the rest still comes from the play
+    assert_eq!(tasks[2].r#become, Some(true));
+    assert_eq!(tasks[2].become_user.as_deref(), Some("root"));
+    assert_eq!(tasks[2].become_method.as_deref(), Some("su"));
+
+    // `--become` only applies where nothing in the playbook decided
+    let become_defaults = BecomeDefaults {
+        enabled: true,
+        method: Some("sudo".to_string()),
+        user: Some("root".to_string()),
+        ..Default::default()
+    };
+    let mock = Arc::new(MockConnection::new("web1"));
+    let runtime = Arc::new(RwLock::new(RuntimeContext::new()));
+    let handlers = Arc::new(RwLock::new(HashMap::new()));
+    let notified = Arc::new(Mutex::new(std::collections::HashSet::new()));
+    let parallelization = Arc::new(ParallelizationManager::new());
+    let ctx = ExecutionContext::new("web1")
+        .with_connection(mock.clone())
+        .with_become_defaults(become_defaults);
+    for task in tasks {
+        task.execute(&ctx, &runtime, &handlers, &notified, &parallelization)
+            .await
+            .unwrap();
+    }
+
+    let executed = mock.get_execute_options();
+    let options_for = |needle: &str| {
+        executed
+            .iter()
+            .find(|(c, _)| c.contains(needle))
+            .map(|(_, o)| o.clone())
+            .unwrap_or_else(|| panic!("{:?}", mock.get_commands()))
+    };
+    let restart = options_for("systemctl restart app");
+    assert!(restart.escalate);
+    assert_eq!(restart.escalate_user.as_deref(), Some("deploy"));
+    assert_eq!(restart.escalate_method.as_deref(), Some("su"));
+    assert!(!options_for("cat /etc/app.conf").escalate);
+    let rotate = options_for("logrotate");
+    assert!(rotate.escalate);
+    assert_eq!(rotate.escalate_user.as_deref(), Some("root"));
+    assert_eq!(rotate.escalate_method.as_deref(), Some("su"));
+}
+
+#[tokio::test]
+async fn test_pre_task_handlers_flush_before_roles() {
+    use rustible::executor::playbook::Role;
+
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("server1".to_string(), None);
+
+    let config = ExecutorConfig {
+        gather_facts: false,
+        ..Default::default()
+    };
+    let executor = Executor::with_runtime(config, runtime);
+
+    let mut role = Role::new("app");
+    role.tasks.push(
+        Task::new("Check handler ran", "set_fact")
+            .arg("role_saw_handler", true)
+            .when("handler_ran is defined"),
+    );
+
+    let mut play = Play::new("Ordered sections", "all");
+    play.gather_facts = false;
+    play.pre_tasks.push(
+        Task::new("Prepare", "command")
+            .arg("cmd", "uptime")
+            .notify("mark handler"),
+    );
+    play.roles.push(role);
+    play.tasks
+        .push(Task::new("Task", "set_fact").arg("task_ran", true));
+    play.post_tasks
+        .push(Task::new("Post task", "set_fact").arg("post_ran", true));
+    play.handlers.push(Handler {
+        name: "mark handler".to_string(),
+        module: "set_fact".to_string(),
+        args: [("handler_ran".to_string(), serde_json::json!(true))]
+            .into_iter()
+            .collect(),
+        when: None,
+        listen: Vec::new(),
+    });
+
+    let results = executor.run_play(&play).await.unwrap();
+    assert!(!results.get("server1").unwrap().failed);
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    assert_eq!(
+        runtime.get_host_fact("server1", "role_saw_handler"),
+        Some(serde_json::json!(true))
+    );
+    assert_eq!(
+        runtime.get_host_fact("server1", "post_ran"),
+        Some(serde_json::json!(true))
+    );
+}
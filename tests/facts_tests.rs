@@ -45,7 +45,7 @@ use rustible::executor::playbook::{Play, Playbook};
 use rustible::executor::runtime::{RegisteredResult, RuntimeContext};
 use rustible::executor::task::Task;
 use rustible::executor::{Executor, ExecutorConfig};
-use rustible::facts::Facts;
+use rustible::facts::{distribution_facts, Facts};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -194,6 +194,43 @@ mod fact_parsing {
         assert!(parsed.get("VERSION_ID").is_none());
     }
 
+    #[test]
+    fn test_distribution_facts_ubuntu() {
+        let facts = distribution_facts(include_str!("fixtures/facts/os_release_ubuntu"));
+
+        assert_eq!(facts["ansible_distribution"], "Ubuntu");
+        assert_eq!(facts["ansible_distribution_version"], "22.04");
+        assert_eq!(facts["ansible_distribution_major_version"], "22");
+        assert_eq!(facts["ansible_distribution_release"], "jammy");
+        assert_eq!(facts["ansible_os_family"], "Debian");
+    }
+
+    #[test]
+    fn test_distribution_facts_rocky() {
+        let facts = distribution_facts(include_str!("fixtures/facts/os_release_rocky"));
+
+        assert_eq!(facts["ansible_distribution"], "Rocky");
+        assert_eq!(facts["ansible_distribution_version"], "9.3");
+        assert_eq!(facts["ansible_distribution_major_version"], "9");
+        assert_eq!(facts["ansible_os_family"], "RedHat");
+    }
+
+    #[test]
+    fn test_distribution_facts_unknown_and_rolling() {
+        // Rolling releases have no version facts
+        let facts = distribution_facts(include_str!("fixtures/facts/os_release_arch"));
+        assert_eq!(facts["ansible_distribution"], "Archlinux");
+        assert_eq!(facts["ansible_os_family"], "Archlinux");
+        assert!(!facts.contains_key("ansible_distribution_major_version"));
+
+        // Unknown distributions fall back to NAME and the ID_LIKE family
+        let facts = distribution_facts("NAME=\"Acme OS\"\nID=acme\nID_LIKE=\"rhel fedora\"\n");
+        assert_eq!(facts["ansible_distribution"], "Acme OS");
+        assert_eq!(facts["ansible_os_family"], "RedHat");
+
+        assert!(distribution_facts("").is_empty());
+    }
+
     /// Helper function to determine OS family from distribution
     fn get_os_family(distribution: &str) -> &'static str {
         match distribution.to_lowercase().as_str() {
@@ -928,6 +965,38 @@ mod fact_filtering {
         assert!(playbook.plays[0].gather_facts);
     }
 
+    #[tokio::test]
+    async fn test_setup_filter_keeps_only_matching_facts() {
+        let mut runtime = RuntimeContext::new();
+        runtime.add_host("localhost".to_string(), None);
+        let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+        let mut playbook = Playbook::new("Filter Test");
+        let mut play = Play::new("Filtered facts", "all");
+        play.gather_facts = false;
+        play.add_task(
+            Task::new("Gather distribution facts", "setup").arg("filter", "ansible_distribution*"),
+        );
+        playbook.add_play(play);
+
+        let results = executor.run_playbook(&playbook).await.unwrap();
+        assert!(!results.get("localhost").unwrap().failed);
+
+        let runtime = executor.runtime();
+        let runtime = runtime.read().await;
+        let merged = runtime.get_merged_vars("localhost");
+        let facts = merged
+            .get("ansible_facts")
+            .and_then(|f| f.as_object())
+            .cloned()
+            .unwrap_or_default();
+
+        assert!(facts.keys().all(|k| k.starts_with("distribution")));
+        assert!(!merged.contains_key("ansible_interfaces"));
+        assert!(!merged.contains_key("ansible_default_ipv4"));
+        assert!(!merged.contains_key("ansible_hostname"));
+    }
+
     #[test]
     fn test_gather_subset_categories() {
         // Test that all expected gather_subset categories are recognized
@@ -1572,6 +1641,46 @@ mod async_integration {
         assert!(!host_result.failed);
     }
 
+    #[tokio::test]
+    async fn test_play_vars_resolve_gathered_facts() {
+        let mut runtime = RuntimeContext::new();
+        runtime.add_host("localhost".to_string(), None);
+
+        let config = ExecutorConfig::default();
+        let executor = Executor::with_runtime(config, runtime);
+
+        let mut playbook = Playbook::new("Fact Vars Test");
+        let mut play = Play::new("Play vars from facts", "all");
+        play.gather_facts = true;
+        play.set_var(
+            "my_ip",
+            serde_json::json!("{{ ansible_default_ipv4.address }}"),
+        );
+        play.set_var("listen", serde_json::json!("{{ my_ip }}:8080"));
+        play.add_task(
+            Task::new("Show address", "debug")
+                .arg("msg", "{{ listen }}")
+                .register("shown"),
+        );
+        playbook.add_play(play);
+
+        let results = executor.run_playbook(&playbook).await.unwrap();
+        assert!(!results.get("localhost").unwrap().failed);
+
+        let runtime = executor.runtime();
+        let runtime = runtime.read().await;
+        let address = runtime
+            .get_host_fact("localhost", "default_ipv4")
+            .and_then(|ipv4| ipv4.get("address").cloned())
+            .and_then(|address| address.as_str().map(String::from))
+            .unwrap_or_default();
+        let shown = runtime.get_registered("localhost", "shown").unwrap();
+        assert_eq!(
+            shown.msg.as_deref(),
+            Some(format!("{}:8080", address).as_str())
+        );
+    }
+
     #[tokio::test]
     async fn test_playbook_with_gather_facts_false() {
         let mut runtime = RuntimeContext::new();
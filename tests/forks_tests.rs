@@ -427,3 +427,74 @@ fn test_executor_config_stores_forks() {
     // Executor should be created successfully with custom forks
     assert!(!executor.is_check_mode());
 }
+
+// ============================================================================
+// Test 13: Forks bounds the free strategy across serial batches
+// ============================================================================
+
+#[tokio::test]
+async fn test_forks_bounds_free_strategy_with_serial() {
+    let runtime = create_runtime_with_hosts(8);
+
+    let config = ExecutorConfig {
+        strategy: ExecutionStrategy::Free,
+        forks: 2,
+        gather_facts: false,
+        ..Default::default()
+    };
+
+    let executor = Executor::with_runtime(config, runtime);
+
+    let mut playbook = Playbook::new("Forks + Serial + Free");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.serial = Some(rustible::playbook::SerialSpec::Fixed(4));
+
+    // A pause keeps each host busy long enough for batches to overlap if
+    // the semaphore were not shared
+    play.add_task(Task::new("Hold slot", "pause").arg("seconds", 1));
+    play.add_task(Task::new("After pause", "debug").arg("msg", "done"));
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+
+    assert_eq!(results.len(), 8);
+    for result in results.values() {
+        assert!(!result.failed);
+        assert_eq!(result.stats.ok, 2);
+    }
+
+    let peak = executor.peak_host_concurrency();
+    assert!(peak <= 2, "peak concurrency {} exceeded forks", peak);
+    assert_eq!(peak, 2, "free strategy should use both fork slots");
+}
+
+#[tokio::test]
+async fn test_zero_forks_is_clamped() {
+    let config = ExecutorConfig {
+        strategy: ExecutionStrategy::Free,
+        forks: 0,
+        gather_facts: false,
+        ..Default::default()
+    };
+
+    let executor = Executor::with_runtime(config, create_runtime_with_hosts(3));
+
+    let mut playbook = Playbook::new("Zero Forks");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.add_task(Task::new("Task", "debug").arg("msg", "runs"));
+    playbook.add_play(play);
+
+    // forks: 0 must not deadlock waiting for a permit that never exists
+    let results = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        executor.run_playbook(&playbook),
+    )
+    .await
+    .expect("execution should not hang with forks: 0")
+    .unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(executor.peak_host_concurrency(), 1);
+}
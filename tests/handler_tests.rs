@@ -1175,6 +1175,74 @@ async fn test_handlers_scoped_to_play() {
     assert!(results.contains_key("localhost"));
 }
 
+#[tokio::test]
+async fn test_role_and_play_handlers_notify_each_other() {
+    let dir = tempfile::tempdir().unwrap();
+    let role = dir.path().join("roles/web");
+    std::fs::create_dir_all(role.join("tasks")).unwrap();
+    std::fs::create_dir_all(role.join("handlers")).unwrap();
+    std::fs::write(
+        role.join("tasks/main.yml"),
+        r#"
+- name: Deploy site
+  command: uptime
+  notify: refresh cache
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        role.join("handlers/main.yml"),
+        r#"
+- name: restart web
+  listen: web config changed
+  set_fact:
+    web_restarted: true
+"#,
+    )
+    .unwrap();
+    let playbook_path = dir.path().join("site.yml");
+    std::fs::write(
+        &playbook_path,
+        r#"
+- name: Cross-role handlers
+  hosts: all
+  gather_facts: false
+  roles:
+    - web
+  tasks:
+    - name: Update web config
+      command: uptime
+      notify: web config changed
+  handlers:
+    - name: refresh cache
+      set_fact:
+        cache_refreshed: true
+"#,
+    )
+    .unwrap();
+
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("localhost".to_string(), None);
+    let executor = Executor::with_runtime(ExecutorConfig::default(), runtime);
+
+    let playbook = Playbook::load(&playbook_path).unwrap();
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(!results["localhost"].failed);
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    // The play task reached the role's handler through its listen topic
+    assert_eq!(
+        runtime.get_host_fact("localhost", "web_restarted"),
+        Some(serde_json::json!(true))
+    );
+    // The role task reached the play's handler by name
+    assert_eq!(
+        runtime.get_host_fact("localhost", "cache_refreshed"),
+        Some(serde_json::json!(true))
+    );
+}
+
 #[tokio::test]
 async fn test_handler_not_available_across_plays() {
     let mut runtime = RuntimeContext::new();
@@ -79,7 +79,6 @@ fn test_include_tasks_basic_structure() {
 #[test]
 fn test_include_tasks_with_vars() {
     // Test include_tasks with variables
-    // Note: TaskDefinition uses module_args for vars in task context
     let yaml = r#"
 - name: Include with vars
   include_tasks: tasks/configure.yml
@@ -91,8 +90,7 @@ fn test_include_tasks_with_vars() {
     let tasks: Vec<TaskDefinition> = serde_yaml::from_str(yaml).unwrap();
     assert_eq!(tasks.len(), 1);
     assert!(tasks[0].include_tasks.is_some());
-    // vars are captured in the module flattening
-    assert!(tasks[0].module.contains_key("vars"));
+    assert!(!tasks[0].vars.is_empty());
 }
 
 #[test]
@@ -198,8 +196,7 @@ fn test_import_tasks_with_vars() {
     let tasks: Vec<TaskDefinition> = serde_yaml::from_str(yaml).unwrap();
     assert_eq!(tasks.len(), 1);
     assert!(tasks[0].import_tasks.is_some());
-    // vars are captured in the module flattening
-    assert!(tasks[0].module.contains_key("vars"));
+    assert!(!tasks[0].vars.is_empty());
 }
 
 #[test]
@@ -291,8 +288,7 @@ fn test_include_role_with_vars() {
     let tasks: Vec<TaskDefinition> = serde_yaml::from_str(yaml).unwrap();
     assert_eq!(tasks.len(), 1);
     assert!(tasks[0].include_role.is_some());
-    // vars are in the module map
-    assert!(tasks[0].module.contains_key("vars"));
+    assert!(!tasks[0].vars.is_empty());
 }
 
 #[test]
@@ -799,8 +795,7 @@ fn test_vars_parameter_in_include_tasks() {
 "#;
 
     let tasks: Vec<TaskDefinition> = serde_yaml::from_str(yaml).unwrap();
-    // Vars are in the module map due to serde flatten
-    assert!(tasks[0].module.contains_key("vars"));
+    assert!(!tasks[0].vars.is_empty());
 }
 
 #[test]
@@ -816,7 +811,7 @@ fn test_vars_parameter_in_include_role() {
 "#;
 
     let tasks: Vec<TaskDefinition> = serde_yaml::from_str(yaml).unwrap();
-    assert!(tasks[0].module.contains_key("vars"));
+    assert!(!tasks[0].vars.is_empty());
 }
 
 #[test]
@@ -830,9 +825,7 @@ fn test_variable_override_in_include() {
 "#;
 
     let tasks: Vec<TaskDefinition> = serde_yaml::from_str(yaml).unwrap();
-    // Check that vars are present in module map
-    let vars = tasks[0].module.get("vars").unwrap();
-    assert!(vars.get("config_file").is_some());
+    assert!(tasks[0].vars.contains_key("config_file"));
 }
 
 #[tokio::test]
@@ -1201,8 +1194,7 @@ fn test_parse_include_with_complex_vars() {
 "#;
 
     let tasks: Vec<TaskDefinition> = serde_yaml::from_str(yaml).unwrap();
-    // vars should be in module map
-    assert!(tasks[0].module.contains_key("vars"));
+    assert!(!tasks[0].vars.is_empty());
 }
 
 #[test]
@@ -1237,7 +1229,7 @@ fn test_parse_include_role_full_options() {
     assert_eq!(role.handlers_from, Some("handlers.yml".to_string()));
     assert!(role.public);
     assert!(role.allow_duplicates);
-    assert!(task.module.contains_key("vars"));
+    assert!(!task.vars.is_empty());
     assert!(task.when.is_some());
     assert!(!task.tags.is_empty());
 }
@@ -1378,7 +1370,7 @@ fn test_deeply_nested_vars_in_include() {
 "#;
 
     let tasks: Vec<TaskDefinition> = serde_yaml::from_str(yaml).unwrap();
-    assert!(tasks[0].module.contains_key("vars"));
+    assert!(!tasks[0].vars.is_empty());
 }
 
 #[tokio::test]
@@ -13,6 +13,7 @@
 
 use rustible::inventory::{
     ConnectionType, Group, GroupBuilder, GroupHierarchy, Host, Inventory, InventoryError,
+    InventoryIssue,
 };
 use std::fs;
 use tempfile::TempDir;
@@ -469,6 +470,53 @@ web1 ansible_host=10.0.0.1 ansible_port=2222 ansible_user=admin
     assert_eq!(host.connection.ssh.user, Some("admin".to_string()));
 }
 
+#[test]
+fn test_parse_ini_host_ranges() {
+    let ini = r#"[webservers]
+web[01:03] ansible_user=deploy
+
+[dbservers]
+db[a:c]
+"#;
+
+    let inv = load_inventory_from_string(ini, "ini");
+
+    let web = inv.get_group("webservers").unwrap();
+    for name in ["web01", "web02", "web03"] {
+        assert!(web.has_host(name), "missing {}", name);
+        let host = inv.get_host(name).unwrap();
+        assert_eq!(host.connection.ssh.user, Some("deploy".to_string()));
+    }
+    assert!(inv.get_host("web[01:03]").is_none());
+
+    let db = inv.get_group("dbservers").unwrap();
+    for name in ["dba", "dbb", "dbc"] {
+        assert!(db.has_host(name), "missing {}", name);
+    }
+    assert_eq!(inv.host_count(), 6);
+}
+
+#[test]
+fn test_parse_yaml_host_ranges() {
+    let yaml = r#"
+all:
+  children:
+    webservers:
+      hosts:
+        web[1:9:4].example.com:
+          http_port: 8080
+"#;
+
+    let inv = load_inventory_from_string(yaml, "yml");
+
+    let web = inv.get_group("webservers").unwrap();
+    for name in ["web1.example.com", "web5.example.com", "web9.example.com"] {
+        assert!(web.has_host(name), "missing {}", name);
+        assert!(inv.get_host(name).unwrap().has_var("http_port"));
+    }
+    assert_eq!(inv.host_count(), 3);
+}
+
 // ============================================================================
 // JSON Inventory Parsing Tests
 // ============================================================================
@@ -998,6 +1046,46 @@ fn test_load_directory_with_group_vars() {
     assert!(webservers.has_var("http_port"));
 }
 
+#[test]
+fn test_load_directory_with_json_group_vars() {
+    let dir = TempDir::new().unwrap();
+
+    let hosts_file = dir.path().join("hosts");
+    fs::write(&hosts_file, "[web]\nweb1\n").unwrap();
+
+    let group_vars = dir.path().join("group_vars");
+    fs::create_dir(&group_vars).unwrap();
+    fs::write(
+        group_vars.join("web.json"),
+        r#"{"http_port": 8080, "upstreams": ["app1", "app2"]}"#,
+    )
+    .unwrap();
+
+    // A directory may mix YAML and JSON files, loaded in sorted order
+    let all_vars = group_vars.join("all");
+    fs::create_dir(&all_vars).unwrap();
+    fs::write(all_vars.join("01-base.yml"), "region: eu\ntier: base\n").unwrap();
+    fs::write(all_vars.join("02-override.json"), r#"{"tier": "frontend"}"#).unwrap();
+
+    let inv = Inventory::load(dir.path()).unwrap();
+
+    let web = inv.get_group("web").unwrap();
+    assert!(web.has_var("http_port"));
+
+    let web1 = inv.get_host("web1").unwrap();
+    let vars = inv.get_host_vars(web1);
+    assert_eq!(vars.get("http_port"), Some(&serde_yaml::Value::from(8080)));
+    assert_eq!(
+        vars.get("upstreams"),
+        Some(&serde_yaml::Value::Sequence(vec![
+            "app1".into(),
+            "app2".into()
+        ]))
+    );
+    assert_eq!(vars.get("region"), Some(&serde_yaml::Value::from("eu")));
+    assert_eq!(vars.get("tier"), Some(&serde_yaml::Value::from("frontend")));
+}
+
 #[test]
 fn test_load_directory_with_host_vars() {
     let dir = TempDir::new().unwrap();
@@ -1055,6 +1143,94 @@ fn test_parse_invalid_json() {
     assert!(result.is_err());
 }
 
+// ============================================================================
+// Validation Tests
+// ============================================================================
+
+#[test]
+fn test_validate_detects_circular_children() {
+    let ini = r#"
+[web:children]
+app
+
+[app:children]
+web
+"#;
+    let inv = load_inventory_from_string(ini, "ini");
+    assert!(inv
+        .validate()
+        .contains(&InventoryIssue::CircularChildren(vec![
+            "app".to_string(),
+            "web".to_string(),
+            "app".to_string(),
+        ])));
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("inventory.ini");
+    fs::write(&path, ini).unwrap();
+    assert!(matches!(
+        Inventory::load_validated(&path),
+        Err(InventoryError::CircularDependency(cycle)) if cycle == "app -> web -> app"
+    ));
+}
+
+#[test]
+fn test_validate_reports_undefined_group_references() {
+    let json = r#"
+{
+    "webservers": {
+        "hosts": ["web1"],
+        "children": ["canary"]
+    }
+}
+"#;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("inventory.json");
+    fs::write(&path, json).unwrap();
+    let (mut inv, issues) = Inventory::load_validated(&path).unwrap();
+    assert_eq!(
+        issues,
+        vec![InventoryIssue::UndefinedChild {
+            group: "webservers".to_string(),
+            child: "canary".to_string(),
+        }]
+    );
+
+    let mut host = Host::new("db1");
+    host.add_to_group("databases".to_string());
+    inv.add_host(host).unwrap();
+    assert!(inv.validate().contains(&InventoryIssue::UndefinedGroup {
+        host: "db1".to_string(),
+        group: "databases".to_string(),
+    }));
+}
+
+#[test]
+fn test_validate_reports_conflicting_duplicate_hosts() {
+    let inv = load_inventory_from_string(
+        r#"
+[web]
+web1 http_port=80 ansible_host=10.0.0.1
+
+[api]
+web1 http_port=8080 ansible_host=10.0.0.1
+web2 http_port=80
+
+[lb]
+web2 http_port=80
+"#,
+        "ini",
+    );
+
+    assert_eq!(
+        inv.validate(),
+        vec![InventoryIssue::ConflictingHostVar {
+            host: "web1".to_string(),
+            var: "http_port".to_string(),
+        }]
+    );
+}
+
 // ============================================================================
 // Edge Cases Tests
 // ============================================================================
@@ -2971,3 +3147,91 @@ fn test_group_display() {
     assert!(display.contains("2 hosts"));
     assert!(display.contains("1 children"));
 }
+
+// ============================================================================
+// Multiple Inventory Sources
+// ============================================================================
+
+#[test]
+fn test_load_sources_later_source_overrides_host_vars() {
+    let dir = TempDir::new().unwrap();
+    let base = dir.path().join("base.yml");
+    let overrides = dir.path().join("overrides.yml");
+    fs::write(
+        &base,
+        r#"
+all:
+  children:
+    webservers:
+      hosts:
+        web1:
+          http_port: 80
+          region: us-east
+      vars:
+        max_clients: 100
+"#,
+    )
+    .unwrap();
+    fs::write(
+        &overrides,
+        r#"
+all:
+  children:
+    webservers:
+      hosts:
+        web1:
+          http_port: 8080
+        web2:
+          http_port: 8081
+      vars:
+        max_clients: 200
+"#,
+    )
+    .unwrap();
+
+    let inv = Inventory::load_sources(&[
+        base.to_string_lossy().to_string(),
+        overrides.to_string_lossy().to_string(),
+    ])
+    .unwrap();
+
+    let web1 = inv.get_host("web1").unwrap();
+    assert_eq!(
+        web1.get_var("http_port"),
+        Some(&serde_yaml::Value::from(8080))
+    );
+    // Vars only defined in the first source survive the merge
+    assert_eq!(
+        web1.get_var("region"),
+        Some(&serde_yaml::Value::from("us-east"))
+    );
+
+    let webservers = inv.get_group("webservers").unwrap();
+    assert!(webservers.has_host("web1"));
+    assert!(webservers.has_host("web2"));
+    assert_eq!(
+        webservers.get_var("max_clients"),
+        Some(&serde_yaml::Value::from(200))
+    );
+    assert_eq!(inv.host_count(), 2);
+}
+
+#[test]
+fn test_load_sources_comma_separated_and_ad_hoc_hosts() {
+    let dir = TempDir::new().unwrap();
+    let first = dir.path().join("first.ini");
+    let second = dir.path().join("second.ini");
+    fs::write(&first, "[webservers]\nweb1\n").unwrap();
+    fs::write(&second, "[databases]\ndb1\n").unwrap();
+
+    let combined = format!("{},{}", first.display(), second.display());
+    let inv = Inventory::load_sources(&[combined.as_str(), "adhoc1,adhoc2,"]).unwrap();
+
+    assert_eq!(inv.host_count(), 4);
+    assert!(inv.get_group("webservers").unwrap().has_host("web1"));
+    assert!(inv.get_group("databases").unwrap().has_host("db1"));
+    let ungrouped = inv.get_group("ungrouped").unwrap();
+    assert!(ungrouped.has_host("adhoc1"));
+    assert!(ungrouped.has_host("adhoc2"));
+    assert_eq!(inv.get_hosts_for_pattern("all").unwrap().len(), 4);
+}
@@ -0,0 +1,220 @@
+//! Tests for the playbook linter
+//!
+//! These tests lint crafted playbooks and assert which rules fire, where
+//! they are reported, and that `# noqa` comments suppress them.
+
+use rustible::lint::{LintConfig, LintResult, Linter, Severity};
+use std::path::Path;
+
+fn lint(content: &str) -> LintResult {
+    Linter::new(LintConfig::default())
+        .check_content(content, Path::new("site.yml"))
+        .unwrap()
+}
+
+fn rule_ids(result: &LintResult) -> Vec<&str> {
+    result.issues.iter().map(|i| i.rule_id.as_str()).collect()
+}
+
+fn find<'a>(result: &'a LintResult, rule_id: &str) -> &'a rustible::lint::LintIssue {
+    result
+        .issues
+        .iter()
+        .find(|i| i.rule_id == rule_id)
+        .unwrap_or_else(|| panic!("expected {} in {:?}", rule_id, rule_ids(result)))
+}
+
+#[test]
+fn test_clean_playbook_has_no_findings() {
+    let result = lint(
+        r#"---
+- name: Configure web servers
+  hosts: webservers
+  become: true
+  vars:
+    db_password: "{{ vault_db_password }}"
+  tasks:
+    - name: Install nginx
+      package:
+        name: nginx
+        state: present
+
+    - name: Report status
+      debug:
+        msg: "nginx installed"
+"#,
+    );
+
+    assert!(result.issues.is_empty(), "{:?}", rule_ids(&result));
+    assert_eq!(result.exit_code(), 0);
+}
+
+#[test]
+fn test_unnamed_task_reported_with_location() {
+    let result = lint(
+        r#"- name: Play
+  hosts: all
+  tasks:
+    - name: Named
+      debug:
+        msg: hi
+    - debug:
+        msg: unnamed
+"#,
+    );
+
+    let issue = find(&result, "B004");
+    assert_eq!(issue.severity, Severity::Warning);
+    assert_eq!(issue.location.file, Path::new("site.yml"));
+    assert_eq!(issue.location.line, Some(7));
+    assert_eq!(issue.location.to_location_string(), "site.yml:7");
+}
+
+#[test]
+fn test_shell_without_shell_features() {
+    let result = lint(
+        r#"- name: Play
+  hosts: all
+  tasks:
+    - name: List files
+      shell: ls -l /tmp
+      changed_when: false
+    - name: Count files
+      shell: ls /tmp | wc -l
+      changed_when: false
+"#,
+    );
+
+    let shell_findings: Vec<_> = result
+        .issues
+        .iter()
+        .filter(|i| i.rule_id == "B007")
+        .collect();
+    assert_eq!(shell_findings.len(), 1);
+    assert_eq!(shell_findings[0].location.line, Some(4));
+}
+
+#[test]
+fn test_become_without_become_user() {
+    let result = lint(
+        r#"- name: Database
+  hosts: db
+  become: true
+  tasks:
+    - name: Create database
+      postgresql_db:
+        name: app
+    - name: Create user
+      postgresql_user:
+        name: app
+      become_user: postgres
+"#,
+    );
+
+    let issue = find(&result, "B016");
+    assert_eq!(issue.location.line, Some(5));
+    assert_eq!(
+        rule_ids(&result).iter().filter(|r| **r == "B016").count(),
+        1
+    );
+}
+
+#[test]
+fn test_deprecated_module_name() {
+    let result = lint(
+        r#"- name: Play
+  hosts: all
+  tasks:
+    - name: Install legacy tool
+      easy_install:
+        name: pip
+"#,
+    );
+
+    let issue = find(&result, "B017");
+    assert!(issue.message.contains("easy_install"));
+    assert_eq!(issue.suggestion.as_deref(), Some("Use 'pip' instead"));
+}
+
+#[test]
+fn test_literal_secrets() {
+    let result = lint(
+        r#"- name: Play
+  hosts: all
+  vars:
+    api_token: abc123
+    vaulted_password: !vault |
+      $ANSIBLE_VAULT;1.1;AES256
+      6162
+  tasks:
+    - name: Create user
+      user:
+        name: deploy
+        password: hunter2
+    - name: Templated secret
+      user:
+        name: deploy
+        password: "{{ deploy_password }}"
+"#,
+    );
+
+    let secrets: Vec<_> = result
+        .issues
+        .iter()
+        .filter(|i| i.rule_id == "S001")
+        .collect();
+    assert_eq!(secrets.len(), 2, "{:?}", secrets);
+    assert!(secrets.iter().all(|i| i.severity == Severity::Error));
+    assert_eq!(secrets[0].location.line, Some(1));
+    assert!(secrets[0].message.contains("api_token"));
+    assert_eq!(secrets[1].location.line, Some(9));
+    assert!(result.has_errors());
+}
+
+#[test]
+fn test_noqa_suppresses_rule_for_task() {
+    let result = lint(
+        r#"- name: Play
+  hosts: all
+  tasks:
+    - name: List files
+      shell: ls -l /tmp  # noqa: B007
+      changed_when: false
+    - debug:  # noqa: B004
+        msg: unnamed
+    - name: Other listing
+      shell: ls -l /var
+      changed_when: false
+"#,
+    );
+
+    let ids = rule_ids(&result);
+    assert!(!ids.contains(&"B004"), "{:?}", ids);
+    // Only the task without a comment is reported
+    let shell_findings: Vec<_> = result
+        .issues
+        .iter()
+        .filter(|i| i.rule_id == "B007")
+        .collect();
+    assert_eq!(shell_findings.len(), 1);
+    assert_eq!(shell_findings[0].location.line, Some(9));
+}
+
+#[test]
+fn test_findings_in_blocks_are_located() {
+    let result = lint(
+        r#"- name: Play
+  hosts: all
+  tasks:
+    - name: Guarded
+      block:
+        - name: Inner
+          debug:
+            msg: ok
+        - debug:
+            msg: unnamed
+"#,
+    );
+
+    assert_eq!(find(&result, "B004").location.line, Some(9));
+}
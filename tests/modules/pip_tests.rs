@@ -93,7 +93,7 @@ fn test_pip_module_parallelization() {
     let module = PipModule;
     assert_eq!(
         module.parallelization_hint(),
-        ParallelizationHint::FullyParallel
+        ParallelizationHint::HostExclusive
     );
 }
 
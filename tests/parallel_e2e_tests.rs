@@ -205,6 +205,9 @@ all:
         task_timeout: 300,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let executor = Executor::with_runtime(exec_config, runtime);
@@ -266,6 +269,9 @@ async fn test_parallel_execution_multiple_hosts() {
         task_timeout: 300,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let executor = Executor::with_runtime(exec_config, runtime);
@@ -361,6 +367,9 @@ async fn test_linear_vs_free_strategy_performance() {
         task_timeout: 300,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let executor = Executor::with_runtime(linear_config, runtime);
@@ -385,6 +394,9 @@ async fn test_linear_vs_free_strategy_performance() {
         task_timeout: 300,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let executor = Executor::with_runtime(free_config, runtime);
@@ -476,6 +488,9 @@ async fn test_connection_reuse_in_parallel_execution() {
         task_timeout: 300,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let executor = Executor::with_runtime(exec_config, runtime);
@@ -554,6 +569,9 @@ async fn test_fork_limiting_with_many_hosts() {
         task_timeout: 300,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let executor = Executor::with_runtime(exec_config, runtime);
@@ -631,6 +649,9 @@ async fn test_parallel_performance_improvement() {
         task_timeout: 300,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let executor = Executor::with_runtime(serial_config, runtime);
@@ -655,6 +676,9 @@ async fn test_parallel_performance_improvement() {
         task_timeout: 300,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let executor = Executor::with_runtime(parallel_config, runtime);
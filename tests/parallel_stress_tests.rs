@@ -142,6 +142,9 @@ async fn test_linear_strategy_task_ordering() {
         task_timeout: 60,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let start = Instant::now();
@@ -205,6 +208,9 @@ async fn test_free_strategy_independent_execution() {
         task_timeout: 60,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let start = Instant::now();
@@ -275,6 +281,9 @@ async fn test_host_pinned_strategy_affinity() {
         task_timeout: 60,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let result = rustible::executor::execute_playbook(&playbook, &inventory, executor_config).await;
@@ -336,6 +345,9 @@ async fn test_fork_limit_enforcement() {
         task_timeout: 60,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let start = Instant::now();
@@ -409,6 +421,9 @@ async fn test_fork_limit_with_different_values() {
             task_timeout: 60,
             gather_facts: false,
             extra_vars: HashMap::new(),
+            safe_mode: None,
+            become_defaults: Default::default(),
+            batch_pause_skip: None,
         };
 
         let start = Instant::now();
@@ -477,6 +492,9 @@ async fn test_serial_execution_batching() {
         task_timeout: 60,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let result = rustible::executor::execute_playbook(&playbook, &inventory, executor_config).await;
@@ -556,6 +574,9 @@ async fn test_handler_deduplication_parallel() {
         task_timeout: 60,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let result = rustible::executor::execute_playbook(&playbook, &inventory, executor_config).await;
@@ -619,6 +640,9 @@ async fn test_high_host_count_stress() {
         task_timeout: 60,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let start = Instant::now();
@@ -681,6 +705,9 @@ async fn test_many_tasks_stress() {
         task_timeout: 120,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let start = Instant::now();
@@ -815,6 +842,9 @@ async fn test_partial_host_failure() {
         task_timeout: 10, // Short timeout for unreachable host
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     let result = rustible::executor::execute_playbook(&playbook, &inventory, executor_config).await;
@@ -879,6 +909,9 @@ async fn test_max_fail_percentage() {
         task_timeout: 5,
         gather_facts: false,
         extra_vars: HashMap::new(),
+        safe_mode: None,
+        become_defaults: Default::default(),
+        batch_pause_skip: None,
     };
 
     // With 5 hosts and 2 unreachable (40%), we exceed 30% threshold
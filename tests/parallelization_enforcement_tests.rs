@@ -6,8 +6,16 @@
 //! - RateLimited: Rate limiting enforced
 //! - GlobalExclusive: Only one task globally
 
+use rustible::callback::manager::CallbackManager;
 use rustible::executor::parallelization::ParallelizationManager;
-use rustible::modules::ParallelizationHint;
+use rustible::executor::playbook::{Play, Playbook};
+use rustible::executor::runtime::RuntimeContext;
+use rustible::executor::task::Task;
+use rustible::executor::{Executor, ExecutorConfig};
+use rustible::modules::{
+    Module, ModuleContext, ModuleOutput, ModuleParams, ModuleResult, ParallelizationHint,
+};
+use rustible::traits::{ExecutionCallback, ExecutionResult};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
@@ -321,3 +329,187 @@ async fn test_parallelization_stats() {
     assert_eq!(stats2.host_locks.get("host1"), Some(&1)); // Available
     assert_eq!(stats2.global_available, 1); // Available
 }
+
+/// Stand-in for a custom module that calls a rate-limited API
+struct RateLimitedApi;
+
+impl Module for RateLimitedApi {
+    fn name(&self) -> &'static str {
+        // Runs through the executor's debug implementation
+        "debug"
+    }
+
+    fn description(&self) -> &'static str {
+        "Calls a rate-limited API"
+    }
+
+    fn parallelization_hint(&self) -> ParallelizationHint {
+        ParallelizationHint::RateLimited {
+            requests_per_second: 2,
+        }
+    }
+
+    fn execute(
+        &self,
+        _params: &ModuleParams,
+        _context: &ModuleContext,
+    ) -> ModuleResult<ModuleOutput> {
+        Ok(ModuleOutput::ok("called"))
+    }
+}
+
+/// Records when each host finishes a task
+#[derive(Default)]
+struct CompletionRecorder {
+    completions: parking_lot::Mutex<Vec<Instant>>,
+}
+
+#[async_trait::async_trait]
+impl ExecutionCallback for CompletionRecorder {
+    async fn on_task_complete(&self, _result: &ExecutionResult) {
+        self.completions.lock().push(Instant::now());
+    }
+}
+
+/// Test that the executor spaces a rate-limited module across all hosts
+#[tokio::test]
+async fn test_executor_enforces_rate_limit_across_hosts() {
+    let mut runtime = RuntimeContext::new();
+    for i in 0..10 {
+        runtime.add_host(format!("host{}", i), None);
+    }
+
+    let recorder = Arc::new(CompletionRecorder::default());
+    let callbacks = Arc::new(CallbackManager::new());
+    callbacks
+        .register_default("completions", recorder.clone())
+        .await;
+
+    let config = ExecutorConfig {
+        forks: 10,
+        gather_facts: false,
+        ..Default::default()
+    };
+    let executor = Executor::with_runtime(config, runtime).with_callbacks(callbacks);
+    executor
+        .parallelization_manager()
+        .register_module(&RateLimitedApi);
+
+    let mut playbook = Playbook::new("Rate limited");
+    let mut play = Play::new("Call API", "all");
+    play.gather_facts = false;
+    play.add_task(Task::new("Call API", "debug").arg("msg", "calling"));
+    playbook.add_play(play);
+
+    let start = Instant::now();
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    let elapsed = start.elapsed();
+    assert!(results.values().all(|r| !r.failed));
+
+    let mut completions = recorder.completions.lock().clone();
+    completions.sort();
+    assert_eq!(completions.len(), 10);
+
+    // Two requests use the initial burst, the other eight wait 500ms each
+    assert!(
+        elapsed >= Duration::from_millis(3500),
+        "10 calls at 2 req/sec finished in {:?}",
+        elapsed
+    );
+    for pair in completions[2..].windows(2) {
+        let gap = pair[1] - pair[0];
+        assert!(
+            gap >= Duration::from_millis(350),
+            "completions only {:?} apart",
+            gap
+        );
+    }
+}
+
+/// Run a one-second pause on two hosts delegated to the same host
+async fn delegated_pause_completions(hint: ParallelizationHint) -> Vec<Instant> {
+    let mut runtime = RuntimeContext::new();
+    runtime.add_host("web1".to_string(), Some("web"));
+    runtime.add_host("web2".to_string(), Some("web"));
+    runtime.add_host("pkgcache".to_string(), None);
+
+    let recorder = Arc::new(CompletionRecorder::default());
+    let callbacks = Arc::new(CallbackManager::new());
+    callbacks
+        .register_default("completions", recorder.clone())
+        .await;
+
+    let config = ExecutorConfig {
+        gather_facts: false,
+        ..Default::default()
+    };
+    let executor = Executor::with_runtime(config, runtime).with_callbacks(callbacks);
+    executor.parallelization_manager().set_hint("pause", hint);
+
+    let yaml = r#"
+- name: Refresh package cache
+  hosts: web
+  gather_facts: false
+  tasks:
+    - name: Hold the package lock
+      pause:
+        seconds: 1
+      delegate_to: pkgcache
+"#;
+    let playbook = Playbook::parse(yaml, None).unwrap();
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(results.values().all(|r| !r.failed));
+
+    let mut completions = recorder.completions.lock().clone();
+    completions.sort();
+    completions
+}
+
+/// Test that HostExclusive tasks delegated to one host do not overlap
+#[tokio::test]
+async fn test_host_exclusive_serializes_delegated_tasks() {
+    let completions = delegated_pause_completions(ParallelizationHint::HostExclusive).await;
+    assert_eq!(completions.len(), 2);
+    // Each task holds the delegate's lock for a full second
+    assert!(
+        completions[1] - completions[0] >= Duration::from_millis(900),
+        "delegated HostExclusive tasks overlapped"
+    );
+
+    // Without the hint both hosts pause at the same time
+    let completions = delegated_pause_completions(ParallelizationHint::FullyParallel).await;
+    assert!(completions[1] - completions[0] < Duration::from_millis(500));
+}
+
+/// Test that inventory aliases for one machine share its HostExclusive lock
+#[tokio::test]
+async fn test_host_exclusive_serializes_host_aliases() {
+    let mut runtime = RuntimeContext::new();
+    for alias in ["db-primary", "db-maintenance"] {
+        runtime.add_host(alias.to_string(), Some("db"));
+        runtime.set_host_var(
+            alias,
+            "ansible_host".to_string(),
+            serde_json::json!("10.0.0.5"),
+        );
+    }
+
+    let config = ExecutorConfig {
+        gather_facts: false,
+        ..Default::default()
+    };
+    let executor = Executor::with_runtime(config, runtime);
+    executor
+        .parallelization_manager()
+        .set_hint("pause", ParallelizationHint::HostExclusive);
+
+    let mut playbook = Playbook::new("Aliases");
+    let mut play = Play::new("Maintain database", "db");
+    play.gather_facts = false;
+    play.add_task(Task::new("Hold the package lock", "pause").arg("seconds", 1));
+    playbook.add_play(play);
+
+    let start = Instant::now();
+    executor.run_playbook(&playbook).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(1900));
+}
@@ -581,6 +581,9 @@ fn test_executor_creation_performance() {
             task_timeout: 300,
             gather_facts: false,
             extra_vars: HashMap::new(),
+            safe_mode: None,
+            become_defaults: Default::default(),
+            batch_pause_skip: None,
         };
         let _ = Executor::new(config);
     }
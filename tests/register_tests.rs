@@ -837,6 +837,29 @@ async fn test_changed_when_and_failed_when_together() {
     assert_eq!(host_result.stats.changed, 0);
 }
 
+#[tokio::test]
+async fn test_failed_when_keeps_module_changed() {
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("motd");
+    let executor = create_test_executor(vec!["localhost"]);
+
+    let mut task = Task::new("Write motd", "copy")
+        .arg("content", "welcome\n")
+        .arg("dest", dest.to_string_lossy().to_string())
+        .register("motd");
+    task.failed_when = Some("true".to_string());
+
+    let playbook = create_playbook("test", "all", vec![task]);
+    executor.run_playbook(&playbook).await.unwrap();
+
+    let runtime = executor.runtime();
+    let rt = runtime.read().await;
+    let registered = rt.get_registered("localhost", "motd").unwrap();
+    assert!(registered.failed);
+    assert!(registered.changed);
+    assert!(dest.exists());
+}
+
 // ============================================================================
 // Section 7: Result in Conditions
 // ============================================================================
@@ -1082,6 +1105,71 @@ async fn test_register_with_loop_stores_results_list() {
     assert!(!host_result.failed);
 }
 
+#[tokio::test]
+async fn test_loop_template_iterates_rendered_list() {
+    let executor = create_test_executor(vec!["localhost"]);
+
+    let set_pkgs =
+        Task::new("Collect packages", "set_fact").arg("pkgs", json!(["nginx", "redis", "curl"]));
+    let install = Task::new("Install each", "debug")
+        .arg("msg", "{{ item }}")
+        .loop_template("{{ pkgs }}")
+        .register("installed");
+
+    let playbook = create_playbook("test", "all", vec![set_pkgs, install]);
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(!results.get("localhost").unwrap().failed);
+
+    let runtime = executor.runtime();
+    let rt = runtime.read().await;
+    let registered = rt.get_registered("localhost", "installed").unwrap();
+    let items = registered.results.as_ref().unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0].data.get("item"), Some(&json!("nginx")));
+    assert_eq!(items[2].data.get("item"), Some(&json!("curl")));
+}
+
+#[tokio::test]
+async fn test_loop_template_rejects_non_list() {
+    let executor = create_test_executor(vec!["localhost"]);
+
+    let set_name = Task::new("Set name", "set_fact").arg("app_name", "myapp");
+    let task = Task::new("Loop over a string", "debug")
+        .arg("msg", "{{ item }}")
+        .loop_template("{{ app_name }}");
+
+    let playbook = create_playbook("test", "all", vec![set_name, task]);
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(results.get("localhost").unwrap().failed);
+}
+
+#[tokio::test]
+async fn test_loop_until_retries_each_item_independently() {
+    let executor = create_test_executor(vec!["localhost"]);
+
+    let mut task = Task::new("Retry per item", "debug")
+        .arg("msg", "{{ item }}")
+        .loop_over(vec![json!("a"), json!("b")])
+        .register("per_item");
+    task.until = Some("item == 'b' or per_item.attempts >= 3".to_string());
+    task.retries = Some(5);
+    task.delay = Some(0);
+
+    let playbook = create_playbook("test", "all", vec![task]);
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(!results.get("localhost").unwrap().failed);
+
+    let runtime = executor.runtime();
+    let rt = runtime.read().await;
+    let registered = rt.get_registered("localhost", "per_item").unwrap();
+    let items = registered.results.as_ref().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].data.get("item"), Some(&json!("a")));
+    assert_eq!(items[0].data.get("attempts"), Some(&json!(3)));
+    assert_eq!(items[1].data.get("item"), Some(&json!("b")));
+    assert_eq!(items[1].data.get("attempts"), Some(&json!(1)));
+}
+
 // ============================================================================
 // Section 9: Result Access
 // ============================================================================
@@ -1547,6 +1547,71 @@ fn test_role_discovery_multiple_paths() {
     assert_eq!(found_path.unwrap(), role_path);
 }
 
+/// Test that a role present only in a later roles_path entry is loaded
+#[test]
+fn test_role_found_in_secondary_roles_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let primary = temp_dir.path().join("primary");
+    let secondary = temp_dir.path().join("secondary");
+    fs::create_dir_all(&primary).unwrap();
+
+    let role_path = secondary.join("shared_role");
+    fs::create_dir_all(role_path.join("tasks")).unwrap();
+    fs::write(
+        role_path.join("tasks").join("main.yml"),
+        "---\n- name: Shared task\n  debug:\n    msg: Found in secondary path\n",
+    )
+    .unwrap();
+
+    let playbook_path = temp_dir.path().join("site.yml");
+    fs::write(
+        &playbook_path,
+        "---\n- hosts: all\n  roles:\n    - shared_role\n",
+    )
+    .unwrap();
+
+    let playbook = rustible::executor::playbook::Playbook::load_with_roles_path(
+        &playbook_path,
+        &[primary, secondary],
+    )
+    .unwrap();
+
+    let role = &playbook.plays[0].roles[0];
+    assert_eq!(role.path.as_deref(), Some(role_path.as_path()));
+    assert_eq!(role.tasks.len(), 1);
+    assert_eq!(role.tasks[0].name, "Shared task");
+}
+
+/// Test that a missing role reports every directory that was searched
+#[test]
+fn test_role_not_found_lists_searched_directories() {
+    let temp_dir = TempDir::new().unwrap();
+    let shared = temp_dir.path().join("shared");
+    fs::create_dir_all(&shared).unwrap();
+
+    let playbook_path = temp_dir.path().join("site.yml");
+    fs::write(
+        &playbook_path,
+        "---\n- hosts: all\n  roles:\n    - nowhere\n",
+    )
+    .unwrap();
+
+    let err = rustible::executor::playbook::Playbook::load_with_roles_path(
+        &playbook_path,
+        std::slice::from_ref(&shared),
+    )
+    .unwrap_err()
+    .to_string();
+
+    assert!(err.contains("nowhere"), "{}", err);
+    assert!(err.contains(&shared.display().to_string()), "{}", err);
+    assert!(
+        err.contains(&temp_dir.path().join("roles").display().to_string()),
+        "{}",
+        err
+    );
+}
+
 // ============================================================================
 // Include/Import Role Tests
 // ============================================================================
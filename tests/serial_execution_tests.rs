@@ -8,11 +8,16 @@
 //! - max_fail_percentage with serial execution
 //! - Edge cases (zero hosts, single host, batch size larger than hosts)
 
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rustible::callback::manager::CallbackManager;
 use rustible::executor::playbook::{Play, Playbook};
 use rustible::executor::runtime::RuntimeContext;
 use rustible::executor::task::Task;
 use rustible::executor::{ExecutionStrategy, Executor, ExecutorConfig};
-use rustible::playbook::SerialSpec;
+use rustible::playbook::{MaxFailScope, SerialSpec};
+use rustible::traits::{ExecutionCallback, ExecutionResult};
 
 // ============================================================================
 // Helper Utilities
@@ -98,6 +103,57 @@ async fn test_serial_fixed_batch_size_two() {
     }
 }
 
+#[tokio::test]
+async fn test_serial_play_batch_magic_vars() {
+    let runtime = create_runtime_with_hosts(vec!["host1", "host2", "host3", "host4"]);
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            strategy: ExecutionStrategy::Linear,
+            forks: 10,
+            ..Default::default()
+        },
+        runtime,
+    );
+
+    let mut playbook = Playbook::new("Play Batch Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.serial = Some(SerialSpec::Fixed(2));
+
+    play.add_task(
+        Task::new("Record batch", "set_fact")
+            .arg("batch", "{{ ansible_play_batch }}")
+            .arg("hosts_all", "{{ ansible_play_hosts_all }}"),
+    );
+
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert_eq!(results.len(), 4);
+
+    let runtime = executor.runtime();
+    let runtime = runtime.read().await;
+    for (host, expected_batch) in [
+        ("host1", ["host1", "host2"]),
+        ("host2", ["host1", "host2"]),
+        ("host3", ["host3", "host4"]),
+        ("host4", ["host3", "host4"]),
+    ] {
+        assert_eq!(
+            runtime.get_host_fact(host, "batch"),
+            Some(serde_json::json!(expected_batch)),
+            "unexpected ansible_play_batch for {}",
+            host
+        );
+        assert_eq!(
+            runtime.get_host_fact(host, "hosts_all"),
+            Some(serde_json::json!(["host1", "host2", "host3", "host4"])),
+            "unexpected ansible_play_hosts_all for {}",
+            host
+        );
+    }
+}
+
 #[tokio::test]
 async fn test_serial_fixed_batch_size_larger_than_hosts() {
     // If serial batch size is larger than total hosts, all hosts execute together
@@ -576,6 +632,396 @@ async fn test_serial_max_fail_percentage_zero() {
     }
 }
 
+#[tokio::test]
+async fn test_serial_max_fail_scope_batch_aborts_on_failing_batch() {
+    let hosts: Vec<String> = (1..=10).map(|i| format!("host{}", i)).collect();
+    let runtime = create_runtime_with_hosts(hosts.iter().map(String::as_str).collect());
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            strategy: ExecutionStrategy::Linear,
+            forks: 5,
+            ..Default::default()
+        },
+        runtime,
+    );
+
+    let mut playbook = Playbook::new("Batch Scope Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.serial = Some(SerialSpec::Fixed(5));
+    // 3 of 10 hosts is 30% of the play, but 60% of the first batch
+    play.max_fail_percentage = Some(50);
+    play.max_fail_scope = MaxFailScope::Batch;
+
+    play.add_task(
+        Task::new("Fail some hosts", "fail")
+            .arg("msg", "Failed")
+            .when("inventory_hostname == 'host1' or inventory_hostname == 'host2' or inventory_hostname == 'host3'"),
+    );
+    play.add_task(Task::new("Deploy", "debug").arg("msg", "Deploying"));
+
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert_eq!(results.len(), 10);
+
+    for host in ["host1", "host2", "host3"] {
+        assert!(results.get(host).unwrap().failed);
+    }
+    for host in ["host4", "host5"] {
+        assert!(!results.get(host).unwrap().failed);
+        assert!(results.get(host).unwrap().stats.ok > 0);
+    }
+    for host in &hosts[5..] {
+        let result = results.get(host).unwrap();
+        assert_eq!(result.stats.ok, 0, "Host {} should not have run", host);
+        assert_eq!(result.stats.skipped, 2, "Host {} should be skipped", host);
+    }
+}
+
+#[tokio::test]
+async fn test_serial_max_fail_scope_play_is_cumulative() {
+    let hosts: Vec<String> = (1..=10).map(|i| format!("host{}", i)).collect();
+    let runtime = create_runtime_with_hosts(hosts.iter().map(String::as_str).collect());
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            strategy: ExecutionStrategy::Linear,
+            forks: 5,
+            ..Default::default()
+        },
+        runtime,
+    );
+
+    let mut playbook = Playbook::new("Play Scope Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.serial = Some(SerialSpec::Fixed(5));
+    play.max_fail_percentage = Some(50);
+    assert_eq!(play.max_fail_scope, MaxFailScope::Play);
+
+    play.add_task(
+        Task::new("Fail some hosts", "fail")
+            .arg("msg", "Failed")
+            .when("inventory_hostname == 'host1' or inventory_hostname == 'host2' or inventory_hostname == 'host3'"),
+    );
+    play.add_task(Task::new("Deploy", "debug").arg("msg", "Deploying"));
+
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+
+    // 30% cumulative stays under the threshold, so the second batch runs
+    for host in &hosts[5..] {
+        assert!(results.get(host).unwrap().stats.ok > 0);
+    }
+}
+
+// ============================================================================
+// Canary Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_serial_canary_failure_skips_remaining_batch() {
+    let runtime = create_runtime_with_hosts(vec!["host1", "host2", "host3", "host4"]);
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            strategy: ExecutionStrategy::Linear,
+            forks: 5,
+            ..Default::default()
+        },
+        runtime,
+    );
+
+    let mut playbook = Playbook::new("Canary Failure Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    // serial: [1, "100%"]
+    play.serial = Some(SerialSpec::canary(1));
+    // 25% of the whole play, but 100% of the canary batch
+    play.max_fail_percentage = Some(30);
+
+    play.add_task(
+        Task::new("Break the canary", "fail")
+            .arg("msg", "Canary failed")
+            .when("inventory_hostname == 'host1'"),
+    );
+    play.add_task(Task::new("Deploy", "debug").arg("msg", "Deploying"));
+
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert_eq!(results.len(), 4);
+
+    let canary = results.get("host1").unwrap();
+    assert!(canary.failed);
+
+    for host in ["host2", "host3", "host4"] {
+        let result = results.get(host).unwrap();
+        assert!(!result.failed, "Host {} should not have run", host);
+        assert_eq!(result.stats.ok, 0, "Host {} should not have run", host);
+        assert_eq!(result.stats.skipped, 2, "Host {} should be skipped", host);
+    }
+}
+
+#[tokio::test]
+async fn test_serial_canary_failure_without_max_fail_percentage() {
+    let runtime = create_runtime_with_hosts(vec!["host1", "host2", "host3"]);
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            strategy: ExecutionStrategy::Linear,
+            forks: 5,
+            ..Default::default()
+        },
+        runtime,
+    );
+
+    let mut playbook = Playbook::new("Canary Default Abort Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.serial = Some(SerialSpec::canary(1));
+
+    play.add_task(
+        Task::new("Break the canary", "fail")
+            .arg("msg", "Canary failed")
+            .when("inventory_hostname == 'host1'"),
+    );
+
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+
+    // A fully failed canary batch aborts the rollout even without a threshold
+    assert!(results.get("host1").unwrap().failed);
+    assert_eq!(results.get("host2").unwrap().stats.ok, 0);
+    assert_eq!(results.get("host3").unwrap().stats.ok, 0);
+}
+
+#[tokio::test]
+async fn test_serial_canary_failure_with_two_hosts() {
+    // serial: [1, "100%"] over two hosts gives two batches of one host each;
+    // the first is still the canary
+    let runtime = create_runtime_with_hosts(vec!["host1", "host2"]);
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            strategy: ExecutionStrategy::Linear,
+            forks: 5,
+            ..Default::default()
+        },
+        runtime,
+    );
+
+    let mut playbook = Playbook::new("Two Host Canary Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.serial = Some(SerialSpec::canary(1));
+
+    play.add_task(
+        Task::new("Break the canary", "fail")
+            .arg("msg", "Canary failed")
+            .when("inventory_hostname == 'host1'"),
+    );
+    play.add_task(Task::new("Deploy", "debug").arg("msg", "Deploying"));
+
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert!(results.get("host1").unwrap().failed);
+    let host2 = results.get("host2").unwrap();
+    assert_eq!(host2.stats.ok, 0, "host2 should not have run");
+    assert_eq!(host2.stats.skipped, 2);
+}
+
+#[tokio::test]
+async fn test_serial_canary_success_runs_remaining_batch() {
+    let runtime = create_runtime_with_hosts(vec!["host1", "host2", "host3", "host4"]);
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            strategy: ExecutionStrategy::Linear,
+            forks: 5,
+            ..Default::default()
+        },
+        runtime,
+    );
+
+    let mut playbook = Playbook::new("Canary Success Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.serial = Some(SerialSpec::canary(1));
+    play.max_fail_percentage = Some(0);
+
+    play.add_task(Task::new("Deploy", "debug").arg("msg", "Deploying"));
+
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert_eq!(results.len(), 4);
+    for (host, result) in &results {
+        assert!(!result.failed, "Host {} should succeed", host);
+        assert_eq!(result.stats.ok, 1, "Host {} should have run", host);
+    }
+}
+
+// ============================================================================
+// Batch Pause Tests
+// ============================================================================
+
+/// Records task and batch pause events in the order they arrive
+#[derive(Default)]
+struct BatchEventRecorder {
+    events: parking_lot::Mutex<Vec<(String, Instant)>>,
+}
+
+#[async_trait::async_trait]
+impl ExecutionCallback for BatchEventRecorder {
+    async fn on_task_start(&self, _name: &str, host: &str) {
+        self.events
+            .lock()
+            .push((format!("start {}", host), Instant::now()));
+    }
+
+    async fn on_task_complete(&self, result: &ExecutionResult) {
+        self.events
+            .lock()
+            .push((format!("complete {}", result.host), Instant::now()));
+    }
+
+    async fn on_batch_pause(&self, batch: usize, total: usize, _duration: Duration) {
+        self.events
+            .lock()
+            .push((format!("pause {}/{}", batch, total), Instant::now()));
+    }
+}
+
+#[tokio::test]
+async fn test_serial_pause_only_between_batches() {
+    let runtime = create_runtime_with_hosts(vec!["host1", "host2"]);
+    let recorder = Arc::new(BatchEventRecorder::default());
+    let callbacks = Arc::new(CallbackManager::new());
+    callbacks.register_default("events", recorder.clone()).await;
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            strategy: ExecutionStrategy::Linear,
+            forks: 5,
+            ..Default::default()
+        },
+        runtime,
+    )
+    .with_callbacks(callbacks);
+
+    let pause = Duration::from_millis(300);
+    let mut playbook = Playbook::new("Batch Pause Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.serial = Some(SerialSpec::Fixed(1));
+    play.serial_pause = Some(pause);
+    play.add_task(Task::new("Deploy", "debug").arg("msg", "Deploying"));
+    playbook.add_play(play);
+
+    let started = Instant::now();
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    let finished = Instant::now();
+    assert_eq!(results.len(), 2);
+
+    let events = recorder.events.lock().clone();
+    let labels: Vec<&str> = events.iter().map(|(label, _)| label.as_str()).collect();
+    assert_eq!(
+        labels,
+        vec![
+            "start host1",
+            "complete host1",
+            "pause 1/2",
+            "start host2",
+            "complete host2",
+        ]
+    );
+
+    // No wait before the first batch or after the last one
+    assert!(events[0].1 - started < pause);
+    assert!(finished - events[4].1 < pause);
+    // The full pause sits between the two batches
+    assert!(events[3].1 - events[2].1 >= pause);
+}
+
+#[tokio::test]
+async fn test_serial_pause_skipped_when_notified() {
+    let runtime = create_runtime_with_hosts(vec!["host1", "host2"]);
+    let skip = Arc::new(tokio::sync::Notify::new());
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            strategy: ExecutionStrategy::Linear,
+            forks: 5,
+            batch_pause_skip: Some(skip.clone()),
+            ..Default::default()
+        },
+        runtime,
+    );
+
+    let mut playbook = Playbook::new("Batch Pause Skip Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.serial = Some(SerialSpec::Fixed(1));
+    play.serial_pause = Some(Duration::from_secs(60));
+    play.add_task(Task::new("Deploy", "debug").arg("msg", "Deploying"));
+    playbook.add_play(play);
+
+    // Stand-in for the caller's Ctrl-C handler
+    let notifier = tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            skip.notify_waiters();
+        }
+    });
+
+    let started = Instant::now();
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    notifier.abort();
+
+    assert_eq!(results.len(), 2);
+    assert!(started.elapsed() < Duration::from_secs(30));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_serial_pause_skipped_on_ctrl_c() {
+    use nix::sys::signal::{kill, Signal};
+    use nix::unistd::Pid;
+
+    let runtime = create_runtime_with_hosts(vec!["host1", "host2"]);
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            strategy: ExecutionStrategy::Linear,
+            forks: 5,
+            batch_pause_skip: Some(Arc::new(tokio::sync::Notify::new())),
+            ..Default::default()
+        },
+        runtime,
+    );
+
+    let mut playbook = Playbook::new("Batch Pause Ctrl-C Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.serial = Some(SerialSpec::Fixed(1));
+    play.serial_pause = Some(Duration::from_secs(60));
+    play.add_task(Task::new("Deploy", "debug").arg("msg", "Deploying"));
+    playbook.add_play(play);
+
+    // Press Ctrl-C once the first batch is pausing
+    let interrupter = tokio::spawn(async {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        loop {
+            kill(Pid::this(), Signal::SIGINT).unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    });
+
+    let started = Instant::now();
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    interrupter.abort();
+
+    assert_eq!(results.len(), 2);
+    assert!(started.elapsed() < Duration::from_secs(30));
+}
+
 // ============================================================================
 // Edge Cases
 // ============================================================================
@@ -895,3 +1341,20 @@ fn test_serial_spec_batch_hosts_progressive() {
     assert_eq!(batches[1].len(), 5);
     assert_eq!(batches[2].len(), 10);
 }
+
+#[test]
+fn test_serial_spec_canary_batches() {
+    let hosts: Vec<String> = (1..=5).map(|i| format!("host{}", i)).collect();
+    let spec = SerialSpec::canary(1);
+    let batches = spec.batch_hosts(&hosts);
+
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[0].len(), 1);
+    assert_eq!(batches[1].len(), 4);
+    assert!(spec.has_canary(5));
+    assert!(spec.has_canary(2));
+    assert!(!SerialSpec::Fixed(1).has_canary(5));
+    assert!(
+        !SerialSpec::Progressive(vec![SerialSpec::Fixed(2), SerialSpec::Fixed(1)]).has_canary(5)
+    );
+}
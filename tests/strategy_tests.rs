@@ -13,10 +13,12 @@
 
 use std::time::{Duration, Instant};
 
+use rustible::callback::manager::CallbackManager;
 use rustible::executor::playbook::{Play, Playbook};
 use rustible::executor::runtime::RuntimeContext;
 use rustible::executor::task::{Handler, Task};
 use rustible::executor::{ExecutionStrategy, Executor, ExecutorConfig};
+use rustible::traits::{ExecutionCallback, ExecutionResult, SerialProgress};
 
 // ============================================================================
 // Helper Utilities
@@ -1496,6 +1498,134 @@ async fn test_serial_with_max_fail_percentage() {
     assert!(results.len() >= 1);
 }
 
+/// Records task start/complete events in the order they happen
+#[derive(Default)]
+struct EventLog {
+    events: std::sync::Mutex<Vec<(&'static str, String, String)>>,
+}
+
+#[async_trait::async_trait]
+impl ExecutionCallback for EventLog {
+    async fn on_task_start(&self, name: &str, host: &str) {
+        let event = ("start", host.to_string(), name.to_string());
+        self.events.lock().unwrap().push(event);
+    }
+
+    async fn on_task_complete(&self, result: &ExecutionResult) {
+        let event = ("done", result.host.clone(), result.task_name.clone());
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+#[tokio::test]
+async fn test_serial_free_runs_batches_sequentially() {
+    let mut runtime = create_runtime_with_hosts(vec!["host1", "host2", "host3", "host4"]);
+    // The first host of each batch is slow on the first task
+    for (host, secs) in [("host1", 1), ("host2", 0), ("host3", 1), ("host4", 0)] {
+        runtime.set_host_var(host, "slow_secs".to_string(), serde_json::json!(secs));
+    }
+
+    let log = std::sync::Arc::new(EventLog::default());
+    let callbacks = std::sync::Arc::new(CallbackManager::new());
+    callbacks.register_default("events", log.clone()).await;
+
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            strategy: ExecutionStrategy::Free,
+            ..Default::default()
+        },
+        runtime,
+    )
+    .with_callbacks(callbacks);
+
+    let mut playbook = Playbook::new("Serial Free Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.serial = Some(rustible::playbook::SerialSpec::Fixed(2));
+    play.add_task(Task::new("Slow", "pause").arg("seconds", "{{ slow_secs }}"));
+    play.add_task(Task::new("Fast", "debug").arg("msg", "done"));
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert_eq!(results.len(), 4);
+    assert!(results.values().all(|r| !r.failed));
+
+    let events = log.events.lock().unwrap().clone();
+    let position = |kind: &str, host: &str, task: &str| {
+        events
+            .iter()
+            .position(|(k, h, t)| *k == kind && h == host && t == task)
+            .unwrap_or_else(|| panic!("no {} event for {} on {}", kind, task, host))
+    };
+
+    // Exactly two batches: the second starts only after the first finished
+    let batch = |host: &str| if host <= "host2" { 1 } else { 2 };
+    let first_end = position("done", "host1", "Fast").max(position("done", "host2", "Fast"));
+    let second_start = position("start", "host3", "Slow").min(position("start", "host4", "Slow"));
+    assert!(first_end < second_start, "{:?}", events);
+    assert_eq!(
+        events.iter().filter(|(_, h, _)| batch(h) == 1).count(),
+        events.len() / 2
+    );
+
+    // Free inside each batch: the fast host finishes without waiting for
+    // the slow one
+    for (slow, fast) in [("host1", "host2"), ("host3", "host4")] {
+        assert!(
+            position("done", fast, "Fast") < position("done", slow, "Slow"),
+            "{:?}",
+            events
+        );
+    }
+}
+
+#[derive(Default)]
+struct SerialProgressLog {
+    reports: std::sync::Mutex<Vec<SerialProgress>>,
+}
+
+#[async_trait::async_trait]
+impl ExecutionCallback for SerialProgressLog {
+    async fn on_batch_complete(&self, progress: &SerialProgress) {
+        self.reports.lock().unwrap().push(progress.clone());
+    }
+}
+
+#[tokio::test]
+async fn test_serial_reports_progress_per_batch() {
+    let runtime =
+        create_runtime_with_hosts(vec!["host1", "host2", "host3", "host4", "host5", "host6"]);
+
+    let log = std::sync::Arc::new(SerialProgressLog::default());
+    let callbacks = std::sync::Arc::new(CallbackManager::new());
+    callbacks.register_default("progress", log.clone()).await;
+
+    let executor =
+        Executor::with_runtime(ExecutorConfig::default(), runtime).with_callbacks(callbacks);
+
+    let mut playbook = Playbook::new("Serial Progress Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+    play.serial = Some(rustible::playbook::SerialSpec::Fixed(2));
+    play.add_task(Task::new("Fast", "debug").arg("msg", "done"));
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+    assert_eq!(results.len(), 6);
+
+    let reports = log.reports.lock().unwrap().clone();
+    let completed: Vec<usize> = reports.iter().map(|p| p.hosts_completed).collect();
+    assert_eq!(completed, vec![2, 4, 6]);
+    for (index, progress) in reports.iter().enumerate() {
+        assert_eq!(progress.batch, index + 1);
+        assert_eq!(progress.total_batches, 3);
+        assert_eq!(progress.total_hosts, 6);
+        assert_eq!(progress.hosts_failed, 0);
+        assert_eq!(progress.eta.is_some(), progress.batch < 3);
+    }
+    assert_eq!(reports[2].remaining_hosts(), 0);
+}
+
 // ============================================================================
 // Throttle Tests
 // ============================================================================
@@ -1720,6 +1850,78 @@ async fn test_run_once_in_free_strategy() {
     assert_eq!(results.len(), 3);
 }
 
+#[tokio::test]
+async fn test_run_once_in_free_strategy_waits_for_result() {
+    // Hosts that skip a run_once task must not move past it before the
+    // registered result has been shared with them
+    let runtime = create_runtime_with_hosts(vec!["host1", "host2", "host3"]);
+    let executor = Executor::with_runtime(
+        ExecutorConfig {
+            strategy: ExecutionStrategy::Free,
+            forks: 5,
+            ..Default::default()
+        },
+        runtime,
+    );
+
+    let mut playbook = Playbook::new("Run Once Free Wait Test");
+    let mut play = Play::new("Test", "all");
+    play.gather_facts = false;
+
+    let mut run_once_task = Task::new("Slow run_once", "pause").arg("seconds", 1);
+    run_once_task.run_once = true;
+    run_once_task.register = Some("once_result".to_string());
+    play.add_task(run_once_task);
+    play.add_task(Task::new("Use result", "assert").arg("that", "once_result is defined"));
+
+    playbook.add_play(play);
+
+    let results = executor.run_playbook(&playbook).await.unwrap();
+
+    assert_eq!(results.len(), 3);
+    for (host, result) in &results {
+        assert!(!result.failed, "{} did not see the run_once result", host);
+    }
+}
+
+#[tokio::test]
+async fn test_failed_run_once_fails_every_host() {
+    for strategy in [ExecutionStrategy::Linear, ExecutionStrategy::Free] {
+        let runtime = create_runtime_with_hosts(vec!["host1", "host2", "host3"]);
+        let executor = Executor::with_runtime(
+            ExecutorConfig {
+                strategy,
+                forks: 5,
+                ..Default::default()
+            },
+            runtime,
+        );
+
+        let mut playbook = Playbook::new("Run Once Failure Test");
+        let mut play = Play::new("Test", "all");
+        play.gather_facts = false;
+
+        let mut run_once_task = Task::new("Failing run_once", "fail").arg("msg", "Broken");
+        run_once_task.run_once = true;
+        play.add_task(run_once_task);
+        play.add_task(Task::new("After", "debug").arg("msg", "Should not run"));
+
+        playbook.add_play(play);
+
+        let results = executor.run_playbook(&playbook).await.unwrap();
+
+        assert_eq!(results.len(), 3);
+        for (host, result) in &results {
+            assert!(result.failed, "{:?}: {} kept going", strategy, host);
+            assert_eq!(
+                result.stats.ok, 0,
+                "{:?}: {} ran the next task",
+                strategy, host
+            );
+        }
+    }
+}
+
 // ============================================================================
 // Host Ordering Tests
 // ============================================================================